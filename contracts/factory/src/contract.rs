@@ -1,31 +1,43 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply,
-    ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse,
+    SubMsgResult, WasmMsg,
 };
-use cw2::{get_contract_version, set_contract_version};
+use cw2::{get_contract_version, set_contract_version, CONTRACT};
 use cw_utils::parse_instantiate_response_data;
 
 use gridiron::asset::{addr_opt_validate, AssetInfo, PairInfo};
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use gridiron::factory::{
-    Config, ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, MigrateMsg, PairConfig,
-    PairType, PairsResponse, QueryMsg,
+    Config, ConfigResponse, ExecuteMsg, FeeBreakdownResponse, FeeDestination, FeeDestinationShare,
+    FeeDiscountTier, FeeDiscounts, FeeInfoResponse, GeneratorProposal, InstantiateMsg, MigrateMsg,
+    PairConfig, PairStatusResponse, PairType, PairTypeProposal, PairTypeStats, PairsResponse,
+    QueryMsg, StatsResponse, GENERATOR_ROTATION_DELAY, MAX_REFERRAL_BPS,
 };
 use gridiron::generator::ExecuteMsg::DeactivatePool;
-use gridiron::pair::InstantiateMsg as PairInstantiateMsg;
+use gridiron::generator::{Config as GeneratorConfig, QueryMsg as GeneratorQueryMsg};
+use gridiron::pair::{
+    ConfigResponse as PairConfigResponse, ExecuteMsg as PairExecuteMsg,
+    InstantiateMsg as PairInstantiateMsg, PairFeeShareParams, QueryMsg as PairQueryMsg,
+    XYKPoolUpdateParams,
+};
+use gridiron::pair_xyk_sale_tax::MAX_SALE_TAX_BPS;
 use itertools::Itertools;
 
 use crate::error::ContractError;
 use crate::migration;
-use crate::migration::{migrate_configs, migrate_pair_configs};
+use crate::migration::{migrate_configs, migrate_pair_configs, migrate_pairs};
 use crate::querier::query_pair_info;
 use crate::state::{
-    check_asset_infos, pair_key, read_pairs, TmpPairInfo, CONFIG, OWNERSHIP_PROPOSAL, PAIRS,
-    PAIR_CONFIGS, TMP_PAIR_INFO,
+    check_asset_infos, check_pair_asset_infos, extra_pair_key, has_pair_of_type, pair_key, pairs,
+    read_pair_code_id_history, read_pair_type_proposals, read_pairs, read_pairs_by_asset,
+    record_pair_code_id, PairEntry, PairFees, TmpPairInfo, CONFIG, FEE_DISCOUNTS,
+    GENERATOR_PROPOSAL, OWNERSHIP_PROPOSAL, PAIR_CONFIGS, PAIR_FEES, PAIR_TYPE_PROPOSALS,
+    TMP_PAIR_INFO,
 };
 
 /// Contract name that is used for migration.
@@ -34,6 +46,10 @@ const CONTRACT_NAME: &str = "gridiron-factory";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID used in a sub-message.
 const INSTANTIATE_PAIR_REPLY_ID: u64 = 1;
+/// The maximum protocol-wide cap on the combined fees of a pair, 100%
+const MAX_COMBINED_FEE_BPS: u16 = 10_000;
+/// The maximum length of the name of a proposed custom pair type
+const MAX_PAIR_TYPE_NAME_LEN: usize = 30;
 
 /// Creates a new contract with the specified parameters packed in the `msg` variable.
 ///
@@ -41,7 +57,7 @@ const INSTANTIATE_PAIR_REPLY_ID: u64 = 1;
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -54,6 +70,10 @@ pub fn instantiate(
         generator_address: None,
         whitelist_code_id: msg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(&msg.coin_registry_address)?,
+        max_combined_fee_bps: None,
+        points_contract: None,
+        max_referral_bps: None,
+        max_sale_tax_bps: None,
     };
 
     config.generator_address = addr_opt_validate(deps.api, &msg.generator_address)?;
@@ -76,6 +96,7 @@ pub fn instantiate(
             return Err(ContractError::PairConfigInvalidFeeBps {});
         }
         PAIR_CONFIGS.save(deps.storage, pc.pair_type.to_string(), pc)?;
+        record_pair_code_id(deps.storage, pc, env.block.height)?;
     }
     CONFIG.save(deps.storage, &config)?;
 
@@ -93,6 +114,8 @@ pub struct UpdateConfig {
     /// CW1 whitelist contract code id used to store 3rd party staking rewards
     whitelist_code_id: Option<u64>,
     coin_registry_address: Option<String>,
+    /// The protocol-wide cap (in bps) on the fees a pair combines on a swap
+    max_combined_fee_bps: Option<u16>,
 }
 
 /// Exposes all the execute functions available in the contract.
@@ -103,7 +126,10 @@ pub struct UpdateConfig {
 ///             token_code_id,
 ///             fee_address,
 ///             generator_address,
+///             max_combined_fee_bps,
 ///         }** Updates general contract parameters.
+/// `max_combined_fee_bps` caps the fees a pair combines on a swap. Pairs check it on config updates.
+/// Once set, `generator_address` can only be changed through a generator proposal.
 ///
 /// * **ExecuteMsg::UpdatePairConfig { config }** Updates a pair type
 /// * configuration or creates a new pair type if a [`Custom`] name is used (which hasn't been used before).
@@ -114,9 +140,43 @@ pub struct UpdateConfig {
 ///             init_params,
 ///         }** Creates a new pair with the specified input parameters.
 ///
+/// * **ExecuteMsg::CreateDuplicatePair {
+///             pair_type,
+///             asset_infos,
+///             init_params,
+///         }** Creates a new pair even if the assets already have a pair of the same type.
+///
 /// * **ExecuteMsg::Deregister { asset_infos }** Removes an existing pair from the factory.
 /// * The asset information is for the assets that are traded in the pair.
 ///
+/// * **ExecuteMsg::DisablePairFeeShare { asset_infos }** Disables swap fee sharing in an existing pair.
+///
+/// * **ExecuteMsg::UpdatePairAllowlist { asset_infos, allowlist }** Sets or removes the allowlist
+/// of an existing pair.
+///
+/// * **ExecuteMsg::ProposeNewGenerator { generator }** Creates a request to change the generator address.
+///
+/// * **ExecuteMsg::DropGeneratorProposal {}** Removes a request to change the generator address.
+///
+/// * **ExecuteMsg::ApplyNewGenerator {}** Applies the requested generator address once the delay has passed.
+///
+/// * **ExecuteMsg::SetPointsContract { points_contract }** Sets or removes the points contract.
+///
+/// * **ExecuteMsg::SetMaxReferralBps { max_referral_bps }** Sets or removes the cap on referral fees.
+///
+/// * **ExecuteMsg::SetMaxSaleTaxBps { max_sale_tax_bps }** Sets or removes the cap on the taxes of
+/// sale tax pairs.
+///
+/// * **ExecuteMsg::SetFeeDiscounts { pair_type, staking_contract, tiers }** Sets or removes the swap
+/// fee discounts of a pair type.
+///
+/// * **ExecuteMsg::ProposePairType { name, code_id, params_schema_hash, total_fee_bps, maker_fee_bps }**
+/// Proposes a custom pair type for the owner's approval.
+///
+/// * **ExecuteMsg::ApprovePairType { pair_type }** Adds the config of a proposed pair type.
+///
+/// * **ExecuteMsg::RejectPairType { pair_type }** Removes a pending pair type proposal.
+///
 /// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a request to change contract ownership.
 ///
 /// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
@@ -136,6 +196,7 @@ pub fn execute(
             generator_address,
             whitelist_code_id,
             coin_registry_address,
+            max_combined_fee_bps,
         } => execute_update_config(
             deps,
             info,
@@ -145,15 +206,71 @@ pub fn execute(
                 generator_address,
                 whitelist_code_id,
                 coin_registry_address,
+                max_combined_fee_bps,
             },
         ),
-        ExecuteMsg::UpdatePairConfig { config } => execute_update_pair_config(deps, info, config),
+        ExecuteMsg::UpdatePairConfig { config } => {
+            execute_update_pair_config(deps, env, info, config)
+        }
         ExecuteMsg::CreatePair {
             pair_type,
             asset_infos,
             init_params,
-        } => execute_create_pair(deps, env, pair_type, asset_infos, init_params),
+        } => execute_create_pair(deps, env, info, pair_type, asset_infos, init_params, false),
+        ExecuteMsg::CreateDuplicatePair {
+            pair_type,
+            asset_infos,
+            init_params,
+        } => execute_create_pair(deps, env, info, pair_type, asset_infos, init_params, true),
         ExecuteMsg::Deregister { asset_infos } => deregister(deps, info, asset_infos),
+        ExecuteMsg::DisablePairFeeShare { asset_infos } => {
+            disable_pair_fee_share(deps, info, asset_infos)
+        }
+        ExecuteMsg::UpdatePairAllowlist {
+            asset_infos,
+            allowlist,
+        } => update_pair_allowlist(deps, info, asset_infos, allowlist),
+        ExecuteMsg::ProposeNewGenerator { generator } => {
+            propose_new_generator(deps, env, info, generator)
+        }
+        ExecuteMsg::DropGeneratorProposal {} => drop_generator_proposal(deps, info),
+        ExecuteMsg::ApplyNewGenerator {} => apply_new_generator(deps, env, info),
+        ExecuteMsg::SetPointsContract { points_contract } => {
+            set_points_contract(deps, info, points_contract)
+        }
+        ExecuteMsg::SetMaxReferralBps { max_referral_bps } => {
+            set_max_referral_bps(deps, info, max_referral_bps)
+        }
+        ExecuteMsg::SetMaxSaleTaxBps { max_sale_tax_bps } => {
+            set_max_sale_tax_bps(deps, info, max_sale_tax_bps)
+        }
+        ExecuteMsg::SetFeeDiscounts {
+            pair_type,
+            staking_contract,
+            tiers,
+        } => set_fee_discounts(deps, info, pair_type, staking_contract, tiers),
+        ExecuteMsg::UpdatePairFees {
+            pair_addr,
+            total_fee_bps,
+            maker_fee_bps,
+        } => update_pair_fees(deps, info, pair_addr, total_fee_bps, maker_fee_bps),
+        ExecuteMsg::ProposePairType {
+            name,
+            code_id,
+            params_schema_hash,
+            total_fee_bps,
+            maker_fee_bps,
+        } => propose_pair_type(
+            deps,
+            info,
+            name,
+            code_id,
+            params_schema_hash,
+            total_fee_bps,
+            maker_fee_bps,
+        ),
+        ExecuteMsg::ApprovePairType { pair_type } => approve_pair_type(deps, env, info, pair_type),
+        ExecuteMsg::RejectPairType { pair_type } => reject_pair_type(deps, info, pair_type),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
 
@@ -212,6 +329,10 @@ pub fn execute_update_config(
     }
 
     if let Some(generator_address) = param.generator_address {
+        // Replacing a generator must go through the checks of a generator proposal
+        if config.generator_address.is_some() {
+            return Err(ContractError::GeneratorRotationRequired {});
+        }
         // Validate the address format
         config.generator_address = Some(deps.api.addr_validate(&generator_address)?);
     }
@@ -228,12 +349,19 @@ pub fn execute_update_config(
         config.coin_registry_address = deps.api.addr_validate(&coin_registry_address)?;
     }
 
+    if let Some(max_combined_fee_bps) = param.max_combined_fee_bps {
+        if max_combined_fee_bps == 0 || max_combined_fee_bps > MAX_COMBINED_FEE_BPS {
+            return Err(ContractError::InvalidMaxCombinedFeeBps {});
+        }
+        config.max_combined_fee_bps = Some(max_combined_fee_bps);
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
-/// Updates a pair type's configuration.
+/// Updates a pair type's configuration. A changed code id is recorded in the pair type's code id history.
 ///
 /// * **pair_config** is an object of type [`PairConfig`] that contains the pair type information to update.
 ///
@@ -241,6 +369,7 @@ pub fn execute_update_config(
 /// Only the owner can execute this.
 pub fn execute_update_pair_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     pair_config: PairConfig,
 ) -> Result<Response, ContractError> {
@@ -256,34 +385,57 @@ pub fn execute_update_pair_config(
         return Err(ContractError::PairConfigInvalidFeeBps {});
     }
 
+    if let Some(max_combined_fee_bps) = config.max_combined_fee_bps {
+        if pair_config.total_fee_bps > max_combined_fee_bps {
+            return Err(ContractError::PairConfigFeeExceedsCap {
+                max_combined_fee_bps,
+            });
+        }
+    }
+
     PAIR_CONFIGS.save(
         deps.storage,
         pair_config.pair_type.to_string(),
         &pair_config,
     )?;
+    record_pair_code_id(deps.storage, &pair_config, env.block.height)?;
 
     Ok(Response::new().add_attribute("action", "update_pair_config"))
 }
 
 /// Creates a new pair of `pair_type` with the assets specified in `asset_infos`.
+/// The message sender is recorded in the pair as its creator.
+/// Assets can have only one pair of each pair type unless the owner allows a duplicate.
 ///
 /// * **pair_type** is the pair type of the newly created pair.
 ///
 /// * **asset_infos** is a vector with assets for which we create a pair.
 ///
 /// * **init_params** These are packed params used for custom pair types that need extra data to be instantiated.
+///
+/// * **allow_duplicate** whether the pair is created even if the assets already have a pair of `pair_type`.
+///
+/// ## Executor
+/// Only the owner can allow a duplicate.
 pub fn execute_create_pair(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     pair_type: PairType,
     asset_infos: Vec<AssetInfo>,
     init_params: Option<Binary>,
+    allow_duplicate: bool,
 ) -> Result<Response, ContractError> {
     check_asset_infos(deps.api, &asset_infos)?;
 
     let config = CONFIG.load(deps.storage)?;
 
-    if PAIRS.has(deps.storage, &pair_key(&asset_infos)) {
+    if allow_duplicate && info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_key = pair_key(&asset_infos);
+    if !allow_duplicate && has_pair_of_type(deps.as_ref(), &pair_key, &pair_type)? {
         return Err(ContractError::PairWasCreated {});
     }
 
@@ -297,8 +449,15 @@ pub fn execute_create_pair(
         return Err(ContractError::PairConfigDisabled {});
     }
 
-    let pair_key = pair_key(&asset_infos);
-    TMP_PAIR_INFO.save(deps.storage, &TmpPairInfo { pair_key })?;
+    TMP_PAIR_INFO.save(
+        deps.storage,
+        &TmpPairInfo {
+            pair_key,
+            asset_infos: asset_infos.clone(),
+            pair_type,
+            allow_duplicate,
+        },
+    )?;
 
     let sub_msg: Vec<SubMsg> = vec![SubMsg {
         id: INSTANTIATE_PAIR_REPLY_ID,
@@ -310,6 +469,7 @@ pub fn execute_create_pair(
                 token_code_id: config.token_code_id,
                 factory_addr: env.contract.address.to_string(),
                 init_params,
+                creator: Some(info.sender.to_string()),
             })?,
             funds: vec![],
             label: "Gridiron pair".to_string(),
@@ -339,7 +499,9 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 }),
         } => {
             let tmp = TMP_PAIR_INFO.load(deps.storage)?;
-            if PAIRS.has(deps.storage, &tmp.pair_key) {
+            if !tmp.allow_duplicate
+                && has_pair_of_type(deps.as_ref(), &tmp.pair_key, &tmp.pair_type)?
+            {
                 return Err(ContractError::PairWasRegistered {});
             }
 
@@ -348,7 +510,21 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             let pair_contract = deps.api.addr_validate(&init_response.contract_address)?;
 
-            PAIRS.save(deps.storage, &tmp.pair_key, &pair_contract)?;
+            // The first pair of the assets is the one returned by the Pair query
+            let key = if pairs().has(deps.storage, &tmp.pair_key) {
+                extra_pair_key(&tmp.pair_key, &tmp.pair_type, &pair_contract)
+            } else {
+                tmp.pair_key
+            };
+
+            pairs().save(
+                deps.storage,
+                &key,
+                &PairEntry {
+                    contract_addr: pair_contract.clone(),
+                    asset_infos: tmp.asset_infos,
+                },
+            )?;
 
             Ok(Response::new().add_attributes(vec![
                 attr("action", "register"),
@@ -370,7 +546,7 @@ pub fn deregister(
     info: MessageInfo,
     asset_infos: Vec<AssetInfo>,
 ) -> Result<Response, ContractError> {
-    check_asset_infos(deps.api, &asset_infos)?;
+    check_pair_asset_infos(deps.api, &asset_infos)?;
 
     let config = CONFIG.load(deps.storage)?;
 
@@ -378,8 +554,11 @@ pub fn deregister(
         return Err(ContractError::Unauthorized {});
     }
 
-    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    PAIRS.remove(deps.storage, &pair_key(&asset_infos));
+    let pair_addr = pairs()
+        .load(deps.storage, &pair_key(&asset_infos))?
+        .contract_addr;
+    pairs().remove(deps.storage, &pair_key(&asset_infos))?;
+    PAIR_FEES.remove(deps.storage, &pair_addr);
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(generator) = config.generator_address {
@@ -401,6 +580,605 @@ pub fn deregister(
     ]))
 }
 
+/// Disables swap fee sharing in a pair registered in the factory.
+/// Every pair type accepts the same `disable_fee_share` update params.
+///
+/// * **asset_infos** array with two items the type of [`AssetInfo`].
+pub fn disable_pair_fee_share(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    check_pair_asset_infos(deps.api, &asset_infos)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner && Some(&info.sender) != config.fee_address.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_addr = pairs()
+        .load(deps.storage, &pair_key(&asset_infos))?
+        .contract_addr;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: pair_addr.to_string(),
+        msg: to_binary(&PairExecuteMsg::UpdateConfig {
+            params: to_binary(&XYKPoolUpdateParams::DisableFeeShare)?,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new().add_message(msg).add_attributes(vec![
+        attr("action", "disable_pair_fee_share"),
+        attr("pair_contract_addr", pair_addr),
+    ]))
+}
+
+/// Sets or removes the allowlist of a pair registered in the factory. Once set, only the addresses
+/// approved by the allowlist can provide liquidity and swap in the pair.
+/// Every pair type accepts the same `update_allowlist` update params.
+///
+/// * **asset_infos** array with two items the type of [`AssetInfo`].
+///
+/// * **allowlist** the allowlist contract. `None` makes the pair permissionless.
+pub fn update_pair_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    allowlist: Option<String>,
+) -> Result<Response, ContractError> {
+    check_pair_asset_infos(deps.api, &asset_infos)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_addr = pairs()
+        .load(deps.storage, &pair_key(&asset_infos))?
+        .contract_addr;
+    let allowlist = addr_opt_validate(deps.api, &allowlist)?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: pair_addr.to_string(),
+        msg: to_binary(&PairExecuteMsg::UpdateConfig {
+            params: to_binary(&XYKPoolUpdateParams::UpdateAllowlist {
+                allowlist: allowlist.as_ref().map(ToString::to_string),
+            })?,
+        })?,
+        funds: vec![],
+    };
+
+    let mut attrs = vec![
+        attr("action", "update_pair_allowlist"),
+        attr("pair_contract_addr", pair_addr),
+    ];
+    if let Some(allowlist) = allowlist {
+        attrs.push(attr("allowlist", allowlist));
+    }
+
+    Ok(Response::new().add_message(msg).add_attributes(attrs))
+}
+
+/// Creates a proposal to change the generator address. The proposal can be applied after
+/// [`GENERATOR_ROTATION_DELAY`] seconds.
+///
+/// * **generator** is the proposed generator address.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn propose_new_generator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    generator: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let generator = deps.api.addr_validate(&generator)?;
+    let apply_after = env.block.time.seconds() + GENERATOR_ROTATION_DELAY;
+
+    GENERATOR_PROPOSAL.save(
+        deps.storage,
+        &GeneratorProposal {
+            generator: generator.clone(),
+            apply_after,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_generator"),
+        attr("generator", generator),
+        attr("apply_after", apply_after.to_string()),
+    ]))
+}
+
+/// Removes the existing proposal to change the generator address.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn drop_generator_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    GENERATOR_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_generator_proposal"))
+}
+
+/// Sets the proposed generator address once the delay has passed. The new generator must respond
+/// to a version query and have every pool which is active in the current generator active as well,
+/// so LP tokens auto staked by pairs keep receiving rewards.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn apply_new_generator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proposal = GENERATOR_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::GeneratorProposalNotFound {})?;
+
+    if env.block.time.seconds() < proposal.apply_after {
+        return Err(ContractError::GeneratorProposalNotReady {
+            apply_after: proposal.apply_after,
+        });
+    }
+
+    CONTRACT
+        .query(&deps.querier, proposal.generator.clone())
+        .map_err(|_| ContractError::GeneratorVersionUnavailable {
+            generator: proposal.generator.to_string(),
+        })?;
+
+    if let Some(generator) = &config.generator_address {
+        // The pools of a current generator which can't be queried anymore are not checked
+        let current_config: Option<GeneratorConfig> = deps
+            .querier
+            .query_wasm_smart(generator, &GeneratorQueryMsg::Config {})
+            .ok();
+
+        if let Some(current_config) = current_config {
+            let new_config: GeneratorConfig = deps
+                .querier
+                .query_wasm_smart(&proposal.generator, &GeneratorQueryMsg::Config {})?;
+
+            for (lp_token, _) in current_config.active_pools {
+                if !new_config
+                    .active_pools
+                    .iter()
+                    .any(|(new_lp_token, _)| *new_lp_token == lp_token)
+                {
+                    return Err(ContractError::GeneratorPoolNotRegistered {
+                        lp_token: lp_token.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    config.generator_address = Some(proposal.generator.clone());
+    CONFIG.save(deps.storage, &config)?;
+    GENERATOR_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "apply_new_generator"),
+        attr("generator", proposal.generator),
+    ]))
+}
+
+/// Sets the points contract which pairs and the generator report swaps and LP positions to.
+///
+/// * **points_contract** the points contract. `None` stops the reports.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_points_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    points_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.points_contract = addr_opt_validate(deps.api, &points_contract)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut attrs = vec![attr("action", "set_points_contract")];
+    if let Some(points_contract) = config.points_contract {
+        attrs.push(attr("points_contract", points_contract));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sets the cap on the referral fee frontends can take from swap returns. Pairs read it on every
+/// swap with a referral.
+///
+/// * **max_referral_bps** the cap (in bps). `None` disables referral fees.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_max_referral_bps(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_referral_bps: Option<u16>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_referral_bps) = max_referral_bps {
+        if max_referral_bps == 0 || max_referral_bps > MAX_REFERRAL_BPS {
+            return Err(ContractError::InvalidMaxReferralBps {});
+        }
+    }
+
+    config.max_referral_bps = max_referral_bps;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut attrs = vec![attr("action", "set_max_referral_bps")];
+    if let Some(max_referral_bps) = max_referral_bps {
+        attrs.push(attr("max_referral_bps", max_referral_bps.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sets the cap on the buy and sell taxes sale tax pairs can charge. Pairs check it whenever
+/// their taxes are set, so taxes set before the cap was lowered are kept until the next update.
+///
+/// * **max_sale_tax_bps** the cap (in bps). `None` applies [`MAX_SALE_TAX_BPS`].
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_max_sale_tax_bps(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_sale_tax_bps: Option<u16>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_sale_tax_bps) = max_sale_tax_bps {
+        if max_sale_tax_bps == 0 || max_sale_tax_bps > MAX_SALE_TAX_BPS {
+            return Err(ContractError::InvalidMaxSaleTaxBps {});
+        }
+    }
+
+    config.max_sale_tax_bps = max_sale_tax_bps;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut attrs = vec![attr("action", "set_max_sale_tax_bps")];
+    if let Some(max_sale_tax_bps) = max_sale_tax_bps {
+        attrs.push(attr("max_sale_tax_bps", max_sale_tax_bps.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sets the swap fee discounts pairs of a specific type give to traders holding xGRID.
+///
+/// * **pair_type** the pair type whose fee discounts are set.
+///
+/// * **staking_contract** the staking contract which issues xGRID.
+///
+/// * **tiers** discount tiers ordered by the xGRID threshold. An empty list removes the discounts.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_fee_discounts(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_type: PairType,
+    staking_contract: String,
+    tiers: Vec<FeeDiscountTier>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !PAIR_CONFIGS.has(deps.storage, pair_type.to_string()) {
+        return Err(ContractError::PairConfigNotFound {});
+    }
+
+    if tiers.is_empty() {
+        FEE_DISCOUNTS.remove(deps.storage, pair_type.to_string());
+        return Ok(Response::new().add_attributes([
+            attr("action", "set_fee_discounts"),
+            attr("pair_type", pair_type.to_string()),
+        ]));
+    }
+
+    // Higher thresholds must give strictly higher discounts, otherwise a tier is unreachable
+    let ordered = tiers.windows(2).all(|pair| {
+        pair[0].min_xgrid < pair[1].min_xgrid && pair[0].discount_bps < pair[1].discount_bps
+    });
+    if !ordered || tiers.iter().any(|tier| tier.discount_bps > 10_000) {
+        return Err(ContractError::InvalidFeeDiscountTiers {});
+    }
+
+    let fee_discounts = FeeDiscounts {
+        staking_contract: deps.api.addr_validate(&staking_contract)?,
+        tiers,
+    };
+    FEE_DISCOUNTS.save(deps.storage, pair_type.to_string(), &fee_discounts)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_fee_discounts"),
+        attr("pair_type", pair_type.to_string()),
+        attr("staking_contract", fee_discounts.staking_contract),
+        attr("tiers", fee_discounts.tiers.len().to_string()),
+    ]))
+}
+
+/// Overrides the fees of a specific pair registered in the factory.
+///
+/// * **pair_addr** the pair whose fees are overridden.
+///
+/// * **total_fee_bps** the total fee charged on a swap. `None` falls back to the pair type config.
+///
+/// * **maker_fee_bps** the fee sent to the Maker. `None` falls back to the pair type config.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn update_pair_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_addr: String,
+    total_fee_bps: Option<u16>,
+    maker_fee_bps: Option<u16>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let (pair_addr, pair_info) = query_registered_pair(deps.as_ref(), &pair_addr)?;
+
+    if total_fee_bps.is_none() && maker_fee_bps.is_none() {
+        PAIR_FEES.remove(deps.storage, &pair_addr);
+        return Ok(Response::new()
+            .add_attributes([attr("action", "update_pair_fees"), attr("pair", pair_addr)]));
+    }
+
+    let pair_fees = PairFees {
+        total_fee_bps,
+        maker_fee_bps,
+    };
+    let pair_config = PAIR_CONFIGS
+        .may_load(deps.storage, pair_info.pair_type.to_string())?
+        .ok_or(ContractError::PairConfigNotFound {})?;
+    let pair_config = apply_pair_fees(pair_config, &pair_fees);
+
+    if !pair_config.valid_fee_bps() {
+        return Err(ContractError::PairConfigInvalidFeeBps {});
+    }
+
+    if let Some(max_combined_fee_bps) = config.max_combined_fee_bps {
+        if pair_config.total_fee_bps > max_combined_fee_bps {
+            return Err(ContractError::PairConfigFeeExceedsCap {
+                max_combined_fee_bps,
+            });
+        }
+    }
+
+    PAIR_FEES.save(deps.storage, &pair_addr, &pair_fees)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_pair_fees"),
+        attr("pair", pair_addr),
+        attr("total_fee_bps", pair_config.total_fee_bps.to_string()),
+        attr("maker_fee_bps", pair_config.maker_fee_bps.to_string()),
+    ]))
+}
+
+/// Proposes a custom pair type which the owner can approve with [`approve_pair_type`].
+///
+/// * **name** the name of the custom pair type.
+///
+/// * **code_id** the code id of the pair contract.
+///
+/// * **params_schema_hash** the hex encoded SHA-256 hash of the JSON schema of the pair's
+/// `init_params`.
+///
+/// * **total_fee_bps** the total fee charged on a swap.
+///
+/// * **maker_fee_bps** the fee sent to the Maker.
+///
+/// ## Executor
+/// Anyone can execute this.
+pub fn propose_pair_type(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    code_id: u64,
+    params_schema_hash: String,
+    total_fee_bps: u16,
+    maker_fee_bps: u16,
+) -> Result<Response, ContractError> {
+    if name.is_empty()
+        || name.len() > MAX_PAIR_TYPE_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(ContractError::InvalidPairTypeName {
+            max_len: MAX_PAIR_TYPE_NAME_LEN,
+        });
+    }
+
+    if params_schema_hash.len() != 64 || !params_schema_hash.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(ContractError::InvalidParamsSchemaHash {});
+    }
+
+    let pair_type = PairType::Custom(name);
+    let key = pair_type.to_string();
+    if PAIR_CONFIGS.has(deps.storage, key.clone()) {
+        return Err(ContractError::PairConfigExists {});
+    }
+    if PAIR_TYPE_PROPOSALS.has(deps.storage, key.clone()) {
+        return Err(ContractError::PairTypeProposalExists {});
+    }
+
+    let proposal = PairTypeProposal {
+        pair_type,
+        code_id,
+        params_schema_hash: params_schema_hash.to_lowercase(),
+        total_fee_bps,
+        maker_fee_bps,
+        proposer: info.sender,
+        approved_at: None,
+    };
+    if !pair_type_config(&proposal).valid_fee_bps() {
+        return Err(ContractError::PairConfigInvalidFeeBps {});
+    }
+
+    PAIR_TYPE_PROPOSALS.save(deps.storage, key.clone(), &proposal)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_pair_type"),
+        attr("pair_type", key),
+        attr("code_id", code_id.to_string()),
+        attr("params_schema_hash", proposal.params_schema_hash),
+        attr("proposer", proposal.proposer),
+    ]))
+}
+
+/// Adds the config of a proposed pair type, after which anyone can create pairs of this type.
+/// Generator rewards stay disabled for the pair type until the owner enables them.
+///
+/// * **pair_type** the proposed pair type.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn approve_pair_type(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair_type: PairType,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let key = pair_type.to_string();
+    let mut proposal = PAIR_TYPE_PROPOSALS
+        .may_load(deps.storage, key.clone())?
+        .filter(|proposal| proposal.approved_at.is_none())
+        .ok_or(ContractError::PairTypeProposalNotFound {})?;
+
+    if PAIR_CONFIGS.has(deps.storage, key.clone()) {
+        return Err(ContractError::PairConfigExists {});
+    }
+
+    let pair_config = pair_type_config(&proposal);
+    if let Some(max_combined_fee_bps) = config.max_combined_fee_bps {
+        if pair_config.total_fee_bps > max_combined_fee_bps {
+            return Err(ContractError::PairConfigFeeExceedsCap {
+                max_combined_fee_bps,
+            });
+        }
+    }
+
+    PAIR_CONFIGS.save(deps.storage, key.clone(), &pair_config)?;
+    record_pair_code_id(deps.storage, &pair_config, env.block.height)?;
+
+    proposal.approved_at = Some(env.block.height);
+    PAIR_TYPE_PROPOSALS.save(deps.storage, key.clone(), &proposal)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "approve_pair_type"),
+        attr("pair_type", key),
+        attr("code_id", pair_config.code_id.to_string()),
+    ]))
+}
+
+/// Removes a pending pair type proposal.
+///
+/// * **pair_type** the proposed pair type.
+///
+/// ## Executor
+/// Only the owner or the proposer can execute this.
+pub fn reject_pair_type(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_type: PairType,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let key = pair_type.to_string();
+    let proposal = PAIR_TYPE_PROPOSALS
+        .may_load(deps.storage, key.clone())?
+        .filter(|proposal| proposal.approved_at.is_none())
+        .ok_or(ContractError::PairTypeProposalNotFound {})?;
+
+    if info.sender != config.owner && info.sender != proposal.proposer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PAIR_TYPE_PROPOSALS.remove(deps.storage, key.clone());
+
+    Ok(Response::new()
+        .add_attribute("action", "reject_pair_type")
+        .add_attribute("pair_type", key))
+}
+
+/// Returns the config a proposed pair type gets once approved.
+fn pair_type_config(proposal: &PairTypeProposal) -> PairConfig {
+    PairConfig {
+        code_id: proposal.code_id,
+        pair_type: proposal.pair_type.clone(),
+        total_fee_bps: proposal.total_fee_bps,
+        maker_fee_bps: proposal.maker_fee_bps,
+        is_disabled: false,
+        is_generator_disabled: true,
+    }
+}
+
+/// Returns the pair type config with the fees of a specific pair applied.
+fn apply_pair_fees(pair_config: PairConfig, pair_fees: &PairFees) -> PairConfig {
+    PairConfig {
+        total_fee_bps: pair_fees.total_fee_bps.unwrap_or(pair_config.total_fee_bps),
+        maker_fee_bps: pair_fees.maker_fee_bps.unwrap_or(pair_config.maker_fee_bps),
+        ..pair_config
+    }
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -411,9 +1189,29 @@ pub fn deregister(
 /// * **QueryMsg::Pairs { start_after, limit }** Returns an array that contains items of type [`PairInfo`].
 /// This returns information about multiple Gridiron pairs
 ///
+/// * **QueryMsg::PairsByAsset { asset_info, start_after, limit }** Returns an array that contains items of type [`PairInfo`]
+/// for pairs which contain a specific asset.
+///
 /// * **QueryMsg::FeeInfo { pair_type }** Returns the fee structure (total and maker fees) for a specific pair type.
 ///
+/// * **QueryMsg::PairFeeInfo { pair_addr }** Returns the fee structure for a specific pair including its fee override.
+///
 /// * **QueryMsg::BlacklistedPairTypes {}** Returns a vector that contains blacklisted pair types (pair types that cannot get GRID emissions).
+///
+/// * **QueryMsg::PairStatus { pair }** Returns the status of a specific pair using a custom [`PairStatusResponse`] structure.
+///
+/// * **QueryMsg::PairCodeIdHistory { pair_type, start_after, limit }** Returns the code ids used to create
+/// pairs of a specific pair type along with their activation heights.
+///
+/// * **QueryMsg::GeneratorProposal {}** Returns the pending proposal to change the generator address.
+///
+/// * **QueryMsg::PairTypeProposal { pair_type }** Returns a pending or approved proposal of a custom
+/// pair type.
+///
+/// * **QueryMsg::PairTypeProposals { start_after, limit }** Returns pending and approved proposals of
+/// custom pair types.
+///
+/// * **QueryMsg::Stats {}** Returns aggregate protocol statistics using a custom [`StatsResponse`] structure.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -422,8 +1220,34 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Pairs { start_after, limit } => {
             to_binary(&query_pairs(deps, start_after, limit)?)
         }
+        QueryMsg::PairsByAsset {
+            asset_info,
+            start_after,
+            limit,
+        } => to_binary(&query_pairs_by_asset(deps, asset_info, start_after, limit)?),
         QueryMsg::FeeInfo { pair_type } => to_binary(&query_fee_info(deps, pair_type)?),
+        QueryMsg::PairFeeInfo { pair_addr } => to_binary(&query_pair_fee_info(deps, pair_addr)?),
         QueryMsg::BlacklistedPairTypes {} => to_binary(&query_blacklisted_pair_types(deps)?),
+        QueryMsg::PairStatus { pair } => to_binary(&query_pair_status(deps, pair)?),
+        QueryMsg::PairCodeIdHistory {
+            pair_type,
+            start_after,
+            limit,
+        } => to_binary(&read_pair_code_id_history(
+            deps,
+            pair_type,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::FeeBreakdown { pair } => to_binary(&query_fee_breakdown(deps, pair)?),
+        QueryMsg::GeneratorProposal {} => to_binary(&GENERATOR_PROPOSAL.may_load(deps.storage)?),
+        QueryMsg::PairTypeProposal { pair_type } => {
+            to_binary(&PAIR_TYPE_PROPOSALS.may_load(deps.storage, pair_type.to_string())?)
+        }
+        QueryMsg::PairTypeProposals { start_after, limit } => {
+            to_binary(&read_pair_type_proposals(deps, start_after, limit)?)
+        }
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
     }
 }
 
@@ -458,16 +1282,75 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         generator_address: config.generator_address,
         whitelist_code_id: config.whitelist_code_id,
         coin_registry_address: config.coin_registry_address,
+        max_combined_fee_bps: config.max_combined_fee_bps,
+        points_contract: config.points_contract,
+        max_referral_bps: config.max_referral_bps,
+        max_sale_tax_bps: config.max_sale_tax_bps,
     };
 
     Ok(resp)
 }
 
+/// Returns aggregate protocol statistics using a custom [`StatsResponse`] structure.
+/// Pair types are read from the pairs themselves, so every registered pair is queried.
+pub fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut pair_types: BTreeMap<String, PairTypeStats> = PAIR_CONFIGS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (key, pair_config) = item?;
+            Ok((
+                key,
+                PairTypeStats {
+                    pair_type: pair_config.pair_type,
+                    code_id: Some(pair_config.code_id),
+                    is_disabled: pair_config.is_disabled,
+                    pairs: 0,
+                },
+            ))
+        })
+        .collect::<StdResult<_>>()?;
+
+    let mut total_pairs = 0u64;
+    for item in pairs().range(deps.storage, None, None, Order::Ascending) {
+        let (_, pair) = item?;
+        let pair_info = query_pair_info(&deps.querier, pair.contract_addr)?;
+        total_pairs += 1;
+        pair_types
+            .entry(pair_info.pair_type.to_string())
+            .or_insert(PairTypeStats {
+                pair_type: pair_info.pair_type,
+                code_id: None,
+                is_disabled: true,
+                pairs: 0,
+            })
+            .pairs += 1;
+    }
+
+    let disabled_pairs = pair_types
+        .values()
+        .filter(|stats| stats.is_disabled)
+        .map(|stats| stats.pairs)
+        .sum();
+
+    Ok(StatsResponse {
+        total_pairs,
+        pair_types: pair_types.into_values().collect(),
+        disabled_pairs,
+        fee_address: config.fee_address,
+        generator_address: config.generator_address,
+        points_contract: config.points_contract,
+        token_code_id: config.token_code_id,
+        whitelist_code_id: config.whitelist_code_id,
+    })
+}
+
 /// Returns a pair's data using the assets in `asset_infos` as input (those being the assets that are traded in the pair).
 /// * **asset_infos** is a vector with assets traded in the pair.
 pub fn query_pair(deps: Deps, asset_infos: Vec<AssetInfo>) -> StdResult<PairInfo> {
-    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    query_pair_info(&deps.querier, pair_addr)
+    let pair = pairs().load(deps.storage, &pair_key(&asset_infos))?;
+    query_pair_info(&deps.querier, pair.contract_addr)
 }
 
 /// Returns a vector with pair data that contains items of type [`PairInfo`]. Querying starts at `start_after` and returns `limit` pairs.
@@ -488,6 +1371,27 @@ pub fn query_pairs(
     Ok(PairsResponse { pairs })
 }
 
+/// Returns a vector with data of the pairs which contain `asset_info`. Querying starts at `start_after` and returns `limit` pairs.
+/// * **asset_info** is the asset which the returned pairs trade.
+///
+/// * **start_after** is a field which accepts a vector with items of type [`AssetInfo`].
+/// This is the pair from which we start a query.
+///
+/// * **limit** sets the number of pairs to be retrieved.
+pub fn query_pairs_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let pairs = read_pairs_by_asset(deps, &asset_info, start_after, limit)?
+        .iter()
+        .map(|pair_addr| query_pair_info(&deps.querier, pair_addr))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
 /// Returns the fee setup for a specific pair type using a [`FeeInfoResponse`] struct.
 /// * **pair_type** is a struct that represents the fee information (total and maker fees) for a specific pair type.
 pub fn query_fee_info(deps: Deps, pair_type: PairType) -> StdResult<FeeInfoResponse> {
@@ -498,12 +1402,150 @@ pub fn query_fee_info(deps: Deps, pair_type: PairType) -> StdResult<FeeInfoRespo
         fee_address: config.fee_address,
         total_fee_bps: pair_config.total_fee_bps,
         maker_fee_bps: pair_config.maker_fee_bps,
+        points_contract: config.points_contract,
+        fee_discounts: FEE_DISCOUNTS.may_load(deps.storage, pair_type.to_string())?,
+        max_referral_bps: config.max_referral_bps,
+    })
+}
+
+/// Returns the fee structure of a specific pair using a [`FeeInfoResponse`] struct. Fees overridden
+/// for the pair take precedence over the config of its pair type.
+/// * **pair_addr** is the address of the pair contract.
+pub fn query_pair_fee_info(deps: Deps, pair_addr: String) -> StdResult<FeeInfoResponse> {
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+    let pair_info = query_pair_info(&deps.querier, &pair_addr)?;
+
+    pair_fee_info(deps, &pair_addr, pair_info.pair_type)
+}
+
+/// Returns the fee structure of a pair of the given type with the pair's fee override applied.
+fn pair_fee_info(deps: Deps, pair_addr: &Addr, pair_type: PairType) -> StdResult<FeeInfoResponse> {
+    let fee_info = query_fee_info(deps, pair_type)?;
+
+    Ok(match PAIR_FEES.may_load(deps.storage, pair_addr)? {
+        Some(pair_fees) => FeeInfoResponse {
+            total_fee_bps: pair_fees.total_fee_bps.unwrap_or(fee_info.total_fee_bps),
+            maker_fee_bps: pair_fees.maker_fee_bps.unwrap_or(fee_info.maker_fee_bps),
+            ..fee_info
+        },
+        None => fee_info,
     })
 }
 
+/// Returns the status of a pair registered in the factory using a [`PairStatusResponse`] struct.
+/// * **pair** is the address of the pair contract.
+pub fn query_pair_status(deps: Deps, pair: String) -> StdResult<PairStatusResponse> {
+    let (pair_addr, pair_info) = query_registered_pair(deps, &pair)?;
+
+    let (is_disabled, is_generator_disabled) = PAIR_CONFIGS
+        .may_load(deps.storage, pair_info.pair_type.to_string())?
+        .map_or((true, true), |config| {
+            (config.is_disabled, config.is_generator_disabled)
+        });
+
+    let contract_info = CONTRACT.query(&deps.querier, pair_addr.clone())?;
+
+    Ok(PairStatusResponse {
+        contract_addr: pair_addr,
+        pair_type: pair_info.pair_type,
+        liquidity_token: pair_info.liquidity_token,
+        is_disabled,
+        is_generator_disabled,
+        contract_name: contract_info.contract,
+        contract_version: contract_info.version,
+    })
+}
+
+/// Returns how swap fees of a pair registered in the factory are split between their destinations
+/// using a [`FeeBreakdownResponse`] struct.
+/// * **pair** is the address of the pair contract.
+pub fn query_fee_breakdown(deps: Deps, pair: String) -> StdResult<FeeBreakdownResponse> {
+    let (pair_addr, pair_info) = query_registered_pair(deps, &pair)?;
+    let fee_info = pair_fee_info(deps, &pair_addr, pair_info.pair_type.clone())?;
+
+    // Pair types without fee sharing are treated as if fee sharing is disabled
+    let pair_config: PairConfigResponse = deps
+        .querier
+        .query_wasm_smart(&pair_addr, &PairQueryMsg::Config {})?;
+    let params = pair_config
+        .params
+        .and_then(|params| from_binary::<PairFeeShareParams>(&params).ok())
+        .unwrap_or_default();
+
+    // The fee share is taken from the total fee first, the Maker gets its part of the remainder
+    // and xGRID stakers get their part of what is left for liquidity providers
+    let bps_ratio = |bps: u16| Decimal::from_ratio(bps, 10000u16);
+    let mut destinations = vec![];
+    let mut remainder = Decimal::one();
+
+    if let Some(fee_share) = &params.fee_share {
+        let share = bps_ratio(fee_share.bps);
+        remainder -= share;
+        destinations.push((
+            FeeDestination::FeeShare,
+            Some(fee_share.recipient.clone()),
+            share,
+        ));
+    }
+
+    if let Some(fee_address) = &fee_info.fee_address {
+        let share = remainder * bps_ratio(fee_info.maker_fee_bps);
+        remainder -= share;
+        destinations.push((FeeDestination::Maker, Some(fee_address.clone()), share));
+    }
+
+    if let Some(staker_fee_share) = &params.staker_fee_share {
+        let share = remainder * bps_ratio(staker_fee_share.bps);
+        remainder -= share;
+        destinations.push((
+            FeeDestination::Stakers,
+            Some(staker_fee_share.staking_contract.clone()),
+            share,
+        ));
+    }
+
+    destinations.push((FeeDestination::LiquidityProviders, None, remainder));
+
+    let total_fee_bps = Decimal::from_ratio(fee_info.total_fee_bps, 1u8);
+
+    Ok(FeeBreakdownResponse {
+        pair: pair_addr,
+        pair_type: pair_info.pair_type,
+        total_fee_bps: fee_info.total_fee_bps,
+        maker_fee_bps: fee_info.maker_fee_bps,
+        staker_fee_share_bps: params.staker_fee_share.map(|config| config.bps),
+        fee_share: params.fee_share,
+        destinations: destinations
+            .into_iter()
+            .map(|(destination, recipient, share)| FeeDestinationShare {
+                destination,
+                recipient,
+                share,
+                fee_bps: share * total_fee_bps,
+            })
+            .collect(),
+    })
+}
+
+/// Returns the address and the [`PairInfo`] of a pair after checking that it's registered in the factory.
+/// * **pair** is the address of the pair contract.
+fn query_registered_pair(deps: Deps, pair: &str) -> StdResult<(Addr, PairInfo)> {
+    let pair_addr = deps.api.addr_validate(pair)?;
+    let pair_info = query_pair_info(&deps.querier, &pair_addr)?;
+
+    let registered = pairs().may_load(deps.storage, &pair_key(&pair_info.asset_infos))?;
+    if registered.map(|pair| pair.contract_addr).as_ref() != Some(&pair_addr) {
+        return Err(StdError::generic_err(format!(
+            "Pair {pair_addr} is not registered in the factory"
+        )));
+    }
+
+    Ok((pair_addr, pair_info))
+}
+
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
@@ -512,7 +1554,8 @@ pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response
                 let msg: migration::MigrationMsg = from_binary(&msg.params)?;
                 migrate_configs(&mut deps, &msg)?;
             }
-            "1.3.0" | "1.5.1" => {}
+            "1.3.0" | "1.5.1" | "1.6.0" | "1.7.0" | "1.8.0" | "1.9.0" | "1.10.0" | "1.11.0"
+            | "1.12.0" | "1.13.0" | "1.14.0" | "1.15.0" | "1.16.0" | "1.17.0" | "1.18.0" => {}
             "1.3.1" | "1.5.0" => {
                 migrate_pair_configs(deps.storage)?;
             }
@@ -521,6 +1564,18 @@ pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    // Index registered pairs by their assets
+    migrate_pairs(deps.branch())?;
+
+    // Start the code id history from the current pair configs
+    for pair_config in PAIR_CONFIGS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, pair_config)| pair_config))
+        .collect::<StdResult<Vec<_>>>()?
+    {
+        record_pair_code_id(deps.storage, &pair_config, env.block.height)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
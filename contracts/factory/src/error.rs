@@ -1,4 +1,7 @@
 use cosmwasm_std::StdError;
+use gridiron::asset::AssetInfoError;
+use gridiron::factory::MAX_REFERRAL_BPS;
+use gridiron::pair_xyk_sale_tax::MAX_SALE_TAX_BPS;
 use thiserror::Error;
 
 /// This enum describes factory contract errors
@@ -7,6 +10,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    AssetInfo(#[from] AssetInfoError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -22,15 +28,60 @@ pub enum ContractError {
     #[error("Fee bps in pair config must be smaller than or equal to 10,000")]
     PairConfigInvalidFeeBps {},
 
+    #[error("Max combined fee bps must be between 1 and 10,000")]
+    InvalidMaxCombinedFeeBps {},
+
+    #[error("Total fee exceeds the max combined fee of {max_combined_fee_bps} bps")]
+    PairConfigFeeExceedsCap { max_combined_fee_bps: u16 },
+
     #[error("Pair config not found")]
     PairConfigNotFound {},
 
     #[error("Pair config disabled")]
     PairConfigDisabled {},
 
+    #[error("Pair config already exists")]
+    PairConfigExists {},
+
+    #[error("Pair type name must have 1 to {max_len} chars of a-z, 0-9, '-' or '_'")]
+    InvalidPairTypeName { max_len: usize },
+
+    #[error("Params schema hash must be a hex encoded SHA-256 hash")]
+    InvalidParamsSchemaHash {},
+
+    #[error("Pair type was already proposed")]
+    PairTypeProposalExists {},
+
+    #[error("Pending pair type proposal not found")]
+    PairTypeProposalNotFound {},
+
     #[error("Doubling assets in asset infos")]
     DoublingAssets {},
 
+    #[error("The generator address can only be changed through a generator proposal")]
+    GeneratorRotationRequired {},
+
+    #[error("Generator proposal not found")]
+    GeneratorProposalNotFound {},
+
+    #[error("Generator proposal can't be applied before {apply_after}")]
+    GeneratorProposalNotReady { apply_after: u64 },
+
+    #[error("Generator {generator} doesn't respond to a version query")]
+    GeneratorVersionUnavailable { generator: String },
+
+    #[error("Pool {lp_token} is active in the current generator but not in the new one")]
+    GeneratorPoolNotRegistered { lp_token: String },
+
+    #[error("Fee discount tiers must strictly increase and discounts can't exceed 10,000 bps")]
+    InvalidFeeDiscountTiers {},
+
+    #[error("Max referral fee bps must be between 1 and {}", MAX_REFERRAL_BPS)]
+    InvalidMaxReferralBps {},
+
+    #[error("Max sale tax bps must be between 1 and {}", MAX_SALE_TAX_BPS)]
+    InvalidMaxSaleTaxBps {},
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
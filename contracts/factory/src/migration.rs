@@ -1,7 +1,8 @@
-use crate::state::{CONFIG, PAIR_CONFIGS};
+use crate::querier::query_pair_info;
+use crate::state::{pairs, PairEntry, CONFIG, PAIR_CONFIGS};
 use gridiron::factory::{Config, PairConfig, PairType};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, DepsMut, StdError, StdResult, Storage};
+use cosmwasm_std::{Addr, DepsMut, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
 
 /// This structure describes a contract migration message.
@@ -41,6 +42,10 @@ pub fn migrate_configs(deps: &mut DepsMut, msg: &MigrationMsg) -> StdResult<()>
         fee_address: old_cfg.fee_address,
         whitelist_code_id: old_cfg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(msg.coin_registry_address.as_str())?,
+        max_combined_fee_bps: None,
+        points_contract: None,
+        max_referral_bps: None,
+        max_sale_tax_bps: None,
     };
 
     CONFIG.save(deps.storage, &new_config)
@@ -104,3 +109,30 @@ pub fn migrate_pair_configs(storage: &mut dyn Storage) -> Result<(), StdError> {
 
     Ok(())
 }
+
+/// Pairs registry before it was indexed by asset
+pub const OLD_PAIRS: Map<&[u8], Addr> = Map::new("pair_info");
+
+/// Moves registered pairs to the indexed [`pairs`] map. The assets of each pair are queried from
+/// the pair contract as they can't be recovered from the pair key.
+pub fn migrate_pairs(deps: DepsMut) -> StdResult<()> {
+    let old_pairs = OLD_PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (pair_key, contract_addr) in old_pairs {
+        let pair_info = query_pair_info(&deps.querier, &contract_addr)?;
+        // The old value has to be removed first as the indexed map can't read it
+        OLD_PAIRS.remove(deps.storage, &pair_key);
+        pairs().save(
+            deps.storage,
+            &pair_key,
+            &PairEntry {
+                contract_addr,
+                asset_infos: pair_info.asset_infos,
+            },
+        )?;
+    }
+
+    Ok(())
+}
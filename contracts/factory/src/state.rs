@@ -1,16 +1,24 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Deps, Order, StdResult};
-use cw_storage_plus::{Bound, Item, Map};
+use cosmwasm_std::{Addr, Api, Deps, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map};
 use itertools::Itertools;
 
 use crate::error::ContractError;
+use crate::querier::query_pair_info;
 use gridiron::asset::AssetInfo;
 use gridiron::common::OwnershipProposal;
-use gridiron::factory::{Config, PairConfig};
+use gridiron::factory::{
+    Config, FeeDiscounts, GeneratorProposal, PairCodeIdEntry, PairConfig, PairType,
+    PairTypeProposal,
+};
 /// This is an intermediate structure for storing a pair's key. It is used in a submessage response.
 #[cw_serde]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
+    pub asset_infos: Vec<AssetInfo>,
+    pub pair_type: PairType,
+    /// Whether the pair is registered even if its assets already have a pair of the same type
+    pub allow_duplicate: bool,
 }
 
 /// Saves a pair's key
@@ -19,8 +27,101 @@ pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
 /// Saves factory settings
 pub const CONFIG: Item<Config> = Item::new("config");
 
-/// Saves created pairs (from olders to latest)
-pub const PAIRS: Map<&[u8], Addr> = Map::new("pair_info");
+/// This structure describes a pair registered in the factory.
+#[cw_serde]
+pub struct PairEntry {
+    /// The pair contract address
+    pub contract_addr: Addr,
+    /// The assets traded in the pair
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+/// A secondary index that references a pair under each of its assets. Unlike [`cw_storage_plus::MultiIndex`]
+/// it produces one index entry per asset, so pairs with any number of assets can be looked up by each of them.
+pub struct AssetIndex<'a> {
+    /// Maps (asset key, pair key) to the pair contract address
+    idx: Map<'a, (Vec<u8>, Vec<u8>), Addr>,
+}
+
+impl<'a> AssetIndex<'a> {
+    pub const fn new(idx_namespace: &'a str) -> Self {
+        Self {
+            idx: Map::new(idx_namespace),
+        }
+    }
+
+    /// Reads the addresses of the pairs that contain `asset_info`, ordered by pair key.
+    ///
+    /// `start_after` is the key of the pair from which the function starts to fetch results.
+    ///
+    /// `limit` is the number of items to retrieve.
+    pub fn read(
+        &self,
+        storage: &dyn Storage,
+        asset_info: &AssetInfo,
+        start_after: Option<Vec<u8>>,
+        limit: usize,
+    ) -> StdResult<Vec<Addr>> {
+        self.idx
+            .prefix(asset_info.as_bytes().to_vec())
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (_, pair_addr) = item?;
+                Ok(pair_addr)
+            })
+            .collect()
+    }
+}
+
+impl<'a> Index<PairEntry> for AssetIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &PairEntry) -> StdResult<()> {
+        data.asset_infos.iter().try_for_each(|asset_info| {
+            self.idx.save(
+                store,
+                (asset_info.as_bytes().to_vec(), pk.to_vec()),
+                &data.contract_addr,
+            )
+        })
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &PairEntry) -> StdResult<()> {
+        old_data.asset_infos.iter().for_each(|asset_info| {
+            self.idx
+                .remove(store, (asset_info.as_bytes().to_vec(), pk.to_vec()))
+        });
+
+        Ok(())
+    }
+}
+
+/// Secondary indexes of [`pairs`]
+pub struct PairIndexes<'a> {
+    /// Pairs by each of their assets
+    pub asset: AssetIndex<'a>,
+}
+
+impl<'a> IndexList<PairEntry> for PairIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PairEntry>> + '_> {
+        let v: Vec<&dyn Index<PairEntry>> = vec![&self.asset];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Saves created pairs (from olders to latest) indexed by each of their assets
+pub fn pairs<'a>() -> IndexedMap<'a, &'a [u8], PairEntry, PairIndexes<'a>> {
+    IndexedMap::new(
+        "pair_info",
+        PairIndexes {
+            asset: AssetIndex::new("pair_info__asset"),
+        },
+    )
+}
 
 /// Calculates a pair key from the specified parameters in the `asset_infos` variable.
 ///
@@ -35,16 +136,81 @@ pub fn pair_key(asset_infos: &[AssetInfo]) -> Vec<u8> {
         .collect()
 }
 
+/// Calculates the key prefix of the pools of `pair_type` saved by [`extra_pair_key`].
+fn extra_pair_key_prefix(pair_key: &[u8], pair_type: &PairType) -> Vec<u8> {
+    [pair_key, &[0], pair_type.to_string().as_bytes(), &[0]].concat()
+}
+
+/// Calculates the key of a pool registered for an asset set which already has a pair in [`pairs`].
+/// The key starts with the `pair_key` of the asset set, so the pool is read right after the pair
+/// under `pair_key` and pagination skips both once `start_after` is their asset set.
+///
+/// `pair_addr` is the address of the pool, which tells apart pools of the same pair type.
+pub fn extra_pair_key(pair_key: &[u8], pair_type: &PairType, pair_addr: &Addr) -> Vec<u8> {
+    [
+        extra_pair_key_prefix(pair_key, pair_type),
+        pair_addr.as_bytes().to_vec(),
+    ]
+    .concat()
+}
+
+/// Checks whether the asset set with `pair_key` already has a pair of `pair_type` in [`pairs`].
+/// The pair type of the pair under `pair_key` is queried from the pair contract as it isn't stored
+/// in the factory.
+pub fn has_pair_of_type(deps: Deps, pair_key: &[u8], pair_type: &PairType) -> StdResult<bool> {
+    if let Some(pair) = pairs().may_load(deps.storage, pair_key)? {
+        if query_pair_info(&deps.querier, pair.contract_addr)?.pair_type == *pair_type {
+            return Ok(true);
+        }
+    }
+
+    let start = extra_pair_key_prefix(pair_key, pair_type);
+    let mut end = start.clone();
+    // The prefix ends with a zero separator, so this is the first key after the prefix
+    if let Some(last) = end.last_mut() {
+        *last = 1;
+    }
+
+    Ok(pairs()
+        .keys(
+            deps.storage,
+            Some(Bound::inclusive(start.as_slice())),
+            Some(Bound::exclusive(end.as_slice())),
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?
+        .is_some())
+}
+
 /// Saves pair type configurations
 pub const PAIR_CONFIGS: Map<String, PairConfig> = Map::new("pair_configs");
 
+/// Saves the code ids used for each pair type by the block height they were activated at
+pub const PAIR_CODE_ID_HISTORY: Map<(String, u64), u64> = Map::new("pair_code_id_history");
+
+/// Saves the swap fee discounts of each pair type
+pub const FEE_DISCOUNTS: Map<String, FeeDiscounts> = Map::new("fee_discounts");
+
+/// This structure describes the fees of a specific pair which override the config of its pair type.
+#[cw_serde]
+pub struct PairFees {
+    /// Total amount of fees (in bps) charged on a swap
+    pub total_fee_bps: Option<u16>,
+    /// Amount of fees (in bps) sent to the Maker contract
+    pub maker_fee_bps: Option<u16>,
+}
+
+/// Saves the fee overrides of specific pairs
+pub const PAIR_FEES: Map<&Addr, PairFees> = Map::new("pair_fees");
+
 /// ## Pagination settings
-/// The maximum limit for reading pairs from [`PAIRS`]
+/// The maximum limit for reading pairs from [`pairs`]
 const MAX_LIMIT: u32 = 30;
-/// The default limit for reading pairs from [`PAIRS`]
+/// The default limit for reading pairs from [`pairs`]
 const DEFAULT_LIMIT: u32 = 10;
 
-/// Reads pairs from the [`PAIRS`] vector according to the `start_after` and `limit` variables.
+/// Reads pairs from the [`pairs`] map according to the `start_after` and `limit` variables.
 /// Otherwise, it returns the default number of pairs, starting from the oldest one.
 ///
 /// `start_after` is the pair from which the function starts to fetch results.
@@ -58,7 +224,7 @@ pub fn read_pairs(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
     if let Some(start) = calc_range_start(start_after) {
-        PAIRS
+        pairs()
             .range(
                 deps.storage,
                 Some(Bound::exclusive(start.as_slice())),
@@ -67,22 +233,125 @@ pub fn read_pairs(
             )
             .take(limit)
             .map(|item| {
-                let (_, pair_addr) = item?;
-                Ok(pair_addr)
+                let (_, pair) = item?;
+                Ok(pair.contract_addr)
             })
             .collect()
     } else {
-        PAIRS
+        pairs()
             .range(deps.storage, None, None, Order::Ascending)
             .take(limit)
             .map(|item| {
-                let (_, pair_addr) = item?;
-                Ok(pair_addr)
+                let (_, pair) = item?;
+                Ok(pair.contract_addr)
             })
             .collect()
     }
 }
 
+/// Reads the pairs containing `asset_info` using the asset index of [`pairs`].
+/// Otherwise, it returns the default number of pairs, ordered by their pair keys.
+///
+/// `start_after` is the pair from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pairs_by_asset(
+    deps: Deps,
+    asset_info: &AssetInfo,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    pairs().idx.asset.read(
+        deps.storage,
+        asset_info,
+        calc_range_start(start_after),
+        limit,
+    )
+}
+
+/// Records the code id of `pair_config` in [`PAIR_CODE_ID_HISTORY`] if it differs from the latest
+/// recorded code id for the pair type.
+///
+/// `height` is the block height from which the code id is used.
+pub fn record_pair_code_id(
+    storage: &mut dyn Storage,
+    pair_config: &PairConfig,
+    height: u64,
+) -> StdResult<()> {
+    let pair_type = pair_config.pair_type.to_string();
+    let last_code_id = PAIR_CODE_ID_HISTORY
+        .prefix(pair_type.clone())
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, code_id)| code_id);
+
+    if last_code_id != Some(pair_config.code_id) {
+        PAIR_CODE_ID_HISTORY.save(storage, (pair_type, height), &pair_config.code_id)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the code id history of `pair_type` from [`PAIR_CODE_ID_HISTORY`] starting from the
+/// oldest entry.
+///
+/// `start_after` is the activation height from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pair_code_id_history(
+    deps: Deps,
+    pair_type: PairType,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairCodeIdEntry>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PAIR_CODE_ID_HISTORY
+        .prefix(pair_type.to_string())
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (activated_at, code_id) = item?;
+            Ok(PairCodeIdEntry {
+                code_id,
+                activated_at,
+            })
+        })
+        .collect()
+}
+
+/// Reads pair type proposals from [`PAIR_TYPE_PROPOSALS`] ordered by pair type.
+///
+/// `start_after` is the pair type from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pair_type_proposals(
+    deps: Deps,
+    start_after: Option<PairType>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairTypeProposal>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PAIR_TYPE_PROPOSALS
+        .range(
+            deps.storage,
+            start_after.map(|pair_type| Bound::exclusive(pair_type.to_string())),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
 /// Calculates the key of a pair from which to start reading data.
 ///
 /// `start_after` is an [`Option`] type that accepts [`AssetInfo`] elements.
@@ -95,6 +364,7 @@ fn calc_range_start(start_after: Option<Vec<AssetInfo>>) -> Option<Vec<u8>> {
     })
 }
 
+/// Checks that the asset infos of a new pair are unique and pass [`AssetInfo::check_strict`].
 pub(crate) fn check_asset_infos(
     api: &dyn Api,
     asset_infos: &[AssetInfo],
@@ -103,6 +373,22 @@ pub(crate) fn check_asset_infos(
         return Err(ContractError::DoublingAssets {});
     }
 
+    asset_infos
+        .iter()
+        .try_for_each(|asset_info| asset_info.check_strict(api))
+        .map_err(Into::into)
+}
+
+/// Checks the asset infos of an existing pair.
+/// Pairs created before strict validation must stay manageable so only [`AssetInfo::check`] is applied.
+pub(crate) fn check_pair_asset_infos(
+    api: &dyn Api,
+    asset_infos: &[AssetInfo],
+) -> Result<(), ContractError> {
+    if !asset_infos.iter().all_unique() {
+        return Err(ContractError::DoublingAssets {});
+    }
+
     asset_infos
         .iter()
         .try_for_each(|asset_info| asset_info.check(api))
@@ -112,6 +398,12 @@ pub(crate) fn check_asset_infos(
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Stores the pending proposal to change the generator address
+pub const GENERATOR_PROPOSAL: Item<GeneratorProposal> = Item::new("generator_proposal");
+
+/// Stores pending and approved proposals of custom pair types by pair type
+pub const PAIR_TYPE_PROPOSALS: Map<String, PairTypeProposal> = Map::new("pair_type_proposals");
+
 /// This state key isn't used anymore but left for backward compatability with old pairs
 pub const PAIRS_TO_MIGRATE: Item<Vec<Addr>> = Item::new("pairs_to_migrate");
 
@@ -175,4 +467,17 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_extra_pair_key_range() {
+        for asset_infos in get_test_case() {
+            let key = pair_key(&asset_infos);
+            let range_start = calc_range_start(Some(asset_infos.to_vec())).unwrap();
+
+            for pair_type in [PairType::Xyk {}, PairType::Custom("xyk-v2".to_string())] {
+                let extra_key = extra_pair_key(&key, &pair_type, &Addr::unchecked("pair"));
+                assert!(key < extra_key && extra_key < range_start);
+            }
+        }
+    }
 }
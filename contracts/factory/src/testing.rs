@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult,
-    WasmMsg,
+    attr, from_binary, to_binary, Addr, Deps, DepsMut, Reply, ReplyOn, SubMsg, SubMsgResponse,
+    SubMsgResult, Uint128, WasmMsg,
 };
 
 use crate::mock_querier::mock_dependencies;
@@ -10,13 +10,15 @@ use crate::{
     error::ContractError,
 };
 
-use gridiron::asset::{AssetInfo, PairInfo};
+use gridiron::asset::{AssetInfo, AssetInfoError, PairInfo};
 use gridiron::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, PairConfig, PairType, PairsResponse, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeeDiscountTier, FeeDiscounts, FeeInfoResponse, InstantiateMsg,
+    PairCodeIdEntry, PairConfig, PairType, PairsResponse, QueryMsg, MAX_REFERRAL_BPS,
 };
 
 use crate::contract::reply;
 use gridiron::pair::InstantiateMsg as PairInstantiateMsg;
+use gridiron::pair_xyk_sale_tax::MAX_SALE_TAX_BPS;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 
 use prost::Message;
@@ -157,7 +159,7 @@ fn update_config() {
         token_code_id: 123u64,
         fee_address: None,
         owner: owner.to_string(),
-        generator_address: Some(String::from("generator")),
+        generator_address: None,
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
     };
@@ -177,6 +179,7 @@ fn update_config() {
         generator_address: Some(String::from("new_generator_addr")),
         whitelist_code_id: None,
         coin_registry_address: None,
+        max_combined_fee_bps: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -196,6 +199,20 @@ fn update_config() {
         config_res.generator_address.unwrap()
     );
 
+    // Once set, the generator can only be replaced through a generator proposal
+    let info = mock_info(owner, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: Some(String::from("another_generator_addr")),
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        max_combined_fee_bps: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::GeneratorRotationRequired {});
+
     // Unauthorized err
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
@@ -205,10 +222,110 @@ fn update_config() {
         generator_address: None,
         whitelist_code_id: None,
         coin_registry_address: None,
+        max_combined_fee_bps: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
     assert_eq!(res, ContractError::Unauthorized {});
+
+    // Only the owner can set the points contract
+    let msg = ExecuteMsg::SetPointsContract {
+        points_contract: Some(String::from("points_addr")),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let query_res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        config_res.points_contract,
+        Some(Addr::unchecked("points_addr"))
+    );
+
+    let msg = ExecuteMsg::SetPointsContract {
+        points_contract: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let query_res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.points_contract, None);
+
+    // Only the owner can set the referral fee cap, which can't exceed MAX_REFERRAL_BPS
+    let msg = ExecuteMsg::SetMaxReferralBps {
+        max_referral_bps: Some(50),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    for max_referral_bps in [0, MAX_REFERRAL_BPS + 1] {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(owner, &[]),
+            ExecuteMsg::SetMaxReferralBps {
+                max_referral_bps: Some(max_referral_bps),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidMaxReferralBps {});
+    }
+
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let query_res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.max_referral_bps, Some(50));
+
+    let msg = ExecuteMsg::SetMaxReferralBps {
+        max_referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let query_res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.max_referral_bps, None);
+
+    // Only the owner can set the sale tax cap, which can't exceed MAX_SALE_TAX_BPS
+    let msg = ExecuteMsg::SetMaxSaleTaxBps {
+        max_sale_tax_bps: Some(300),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    for max_sale_tax_bps in [0, MAX_SALE_TAX_BPS + 1] {
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(owner, &[]),
+            ExecuteMsg::SetMaxSaleTaxBps {
+                max_sale_tax_bps: Some(max_sale_tax_bps),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidMaxSaleTaxBps {});
+    }
+
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let query_res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(config_res.max_sale_tax_bps, Some(300));
 }
 
 #[test]
@@ -400,6 +517,269 @@ fn update_pair_config() {
     );
 }
 
+#[test]
+fn set_fee_discounts() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![PairConfig {
+            code_id: 123u64,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 30,
+            maker_fee_bps: 3333,
+            is_disabled: false,
+            is_generator_disabled: false,
+        }],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let tiers = vec![
+        FeeDiscountTier {
+            min_xgrid: Uint128::new(1_000),
+            discount_bps: 1000,
+        },
+        FeeDiscountTier {
+            min_xgrid: Uint128::new(10_000),
+            discount_bps: 2500,
+        },
+    ];
+    let set_fee_discounts =
+        |pair_type: PairType, tiers: Vec<FeeDiscountTier>| ExecuteMsg::SetFeeDiscounts {
+            pair_type,
+            staking_contract: String::from("staking"),
+            tiers,
+        };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        set_fee_discounts(PairType::Xyk {}, tiers.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_fee_discounts(PairType::Stable {}, tiers.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PairConfigNotFound {});
+
+    // A tier which doesn't increase the discount is unreachable
+    let mut unordered = tiers.clone();
+    unordered[1].discount_bps = 1000;
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_fee_discounts(PairType::Xyk {}, unordered),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeeDiscountTiers {});
+
+    let mut too_high = tiers.clone();
+    too_high[1].discount_bps = 10_001;
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_fee_discounts(PairType::Xyk {}, too_high),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeeDiscountTiers {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_fee_discounts(PairType::Xyk {}, tiers.clone()),
+    )
+    .unwrap();
+
+    let query_fee_info = |deps: Deps| -> FeeInfoResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::FeeInfo {
+                    pair_type: PairType::Xyk {},
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let fee_discounts = query_fee_info(deps.as_ref()).fee_discounts.unwrap();
+    assert_eq!(
+        fee_discounts,
+        FeeDiscounts {
+            staking_contract: Addr::unchecked("staking"),
+            tiers: tiers.clone(),
+        }
+    );
+    assert_eq!(fee_discounts.discount_bps(Uint128::new(999)), 0);
+    assert_eq!(fee_discounts.discount_bps(Uint128::new(1_000)), 1000);
+    assert_eq!(fee_discounts.discount_bps(Uint128::new(50_000)), 2500);
+
+    // An empty list of tiers removes the discounts
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(owner, &[]),
+        set_fee_discounts(PairType::Xyk {}, vec![]),
+    )
+    .unwrap();
+    assert_eq!(query_fee_info(deps.as_ref()).fee_discounts, None);
+}
+
+#[test]
+fn pair_code_id_history() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let pair_config = PairConfig {
+        code_id: 123u64,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 3,
+        maker_fee_bps: 166,
+        is_disabled: false,
+        is_generator_disabled: false,
+    };
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![pair_config.clone()],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: Some(String::from("generator")),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let mut env = mock_env();
+    let instantiated_at = env.block.height;
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let update_pair_config = |deps: DepsMut, env, pair_config| {
+        execute(
+            deps,
+            env,
+            mock_info(owner, &[]),
+            ExecuteMsg::UpdatePairConfig {
+                config: pair_config,
+            },
+        )
+        .unwrap();
+    };
+
+    // Changes other than the code id are not recorded
+    env.block.height += 10;
+    update_pair_config(
+        deps.as_mut(),
+        env.clone(),
+        PairConfig {
+            total_fee_bps: 30,
+            ..pair_config.clone()
+        },
+    );
+
+    env.block.height += 10;
+    update_pair_config(
+        deps.as_mut(),
+        env.clone(),
+        PairConfig {
+            code_id: 124u64,
+            ..pair_config.clone()
+        },
+    );
+
+    // New pair types start their own history
+    update_pair_config(
+        deps.as_mut(),
+        env.clone(),
+        PairConfig {
+            code_id: 200u64,
+            pair_type: PairType::Stable {},
+            ..pair_config
+        },
+    );
+
+    let history: Vec<PairCodeIdEntry> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PairCodeIdHistory {
+                pair_type: PairType::Xyk {},
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            PairCodeIdEntry {
+                code_id: 123,
+                activated_at: instantiated_at,
+            },
+            PairCodeIdEntry {
+                code_id: 124,
+                activated_at: env.block.height,
+            },
+        ]
+    );
+
+    let history: Vec<PairCodeIdEntry> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PairCodeIdHistory {
+                pair_type: PairType::Xyk {},
+                start_after: Some(instantiated_at),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].code_id, 124);
+
+    let history: Vec<PairCodeIdEntry> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PairCodeIdHistory {
+                pair_type: PairType::Stable {},
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![PairCodeIdEntry {
+            code_id: 200,
+            activated_at: env.block.height,
+        }]
+    );
+}
+
 #[test]
 fn create_pair() {
     let mut deps = mock_dependencies(&[]);
@@ -456,6 +836,47 @@ fn create_pair() {
     .unwrap_err();
     assert_eq!(res, ContractError::PairConfigNotFound {});
 
+    // Check pair creation with a malformed IBC denom
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: vec![
+                asset_infos[0].clone(),
+                AssetInfo::native("ibc/EBD5A24C554198EBA"),
+            ],
+            init_params: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::AssetInfo(AssetInfoError::InvalidIbcDenom {
+            denom: "ibc/EBD5A24C554198EBA".to_string()
+        })
+    );
+
+    // Check pair creation with an uppercase denom
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: vec![asset_infos[0].clone(), AssetInfo::native("uLUNA")],
+            init_params: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::AssetInfo(AssetInfoError::DenomNotLowercase {
+            denom: "uLUNA".to_string()
+        })
+    );
+
     let res = execute(
         deps.as_mut(),
         env,
@@ -481,6 +902,7 @@ fn create_pair() {
             msg: WasmMsg::Instantiate {
                 msg: to_binary(&PairInstantiateMsg {
                     factory_addr: String::from(MOCK_CONTRACT_ADDR),
+                    creator: Some(String::from("addr0000")),
                     asset_infos: asset_infos.clone(),
                     token_code_id: msg.token_code_id,
                     init_params: None
@@ -550,6 +972,9 @@ fn register() {
         contract_addr: Addr::unchecked("pair0000"),
         liquidity_token: Addr::unchecked("liquidity0000"),
         pair_type: PairType::Xyk {},
+        creator: None,
+        created_at: None,
+        allowlist: None,
     };
 
     let mut deployed_pairs = vec![(&pair0_addr, &pair0_info)];
@@ -594,6 +1019,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         }
     );
 
@@ -627,6 +1055,9 @@ fn register() {
         contract_addr: Addr::unchecked("pair0001"),
         liquidity_token: Addr::unchecked("liquidity0001"),
         pair_type: PairType::Xyk {},
+        creator: None,
+        created_at: None,
+        allowlist: None,
     };
 
     deployed_pairs.push((&pair1_addr, &pair1_info));
@@ -669,12 +1100,18 @@ fn register() {
                 contract_addr: Addr::unchecked("pair0000"),
                 asset_infos: asset_infos.clone(),
                 pair_type: PairType::Xyk {},
+                creator: None,
+                created_at: None,
+                allowlist: None,
             },
             PairInfo {
                 liquidity_token: Addr::unchecked("liquidity0001"),
                 contract_addr: Addr::unchecked("pair0001"),
                 asset_infos: asset_infos_2.clone(),
                 pair_type: PairType::Xyk {},
+                creator: None,
+                created_at: None,
+                allowlist: None,
             }
         ]
     );
@@ -693,6 +1130,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         }]
     );
 
@@ -710,6 +1150,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0001"),
             asset_infos: asset_infos_2.clone(),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         }]
     );
 
@@ -757,6 +1200,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         },]
     );
 }
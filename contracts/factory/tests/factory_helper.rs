@@ -114,6 +114,7 @@ impl FactoryHelper {
         generator_address: Option<String>,
         whitelist_code_id: Option<u64>,
         coin_registry_address: Option<String>,
+        max_combined_fee_bps: Option<u16>,
     ) -> AnyResult<AppResponse> {
         let msg = gridiron::factory::ExecuteMsg::UpdateConfig {
             token_code_id,
@@ -121,6 +122,7 @@ impl FactoryHelper {
             generator_address,
             whitelist_code_id,
             coin_registry_address,
+            max_combined_fee_bps,
         };
 
         router.execute_contract(sender.clone(), self.factory.clone(), &msg, &[])
@@ -2,11 +2,18 @@
 
 mod factory_helper;
 
-use cosmwasm_std::{attr, Addr};
+use cosmwasm_std::{attr, to_binary, Addr, Decimal, StdError, Uint128};
+use cw20::Cw20ExecuteMsg;
+use itertools::Itertools;
 
-use gridiron::asset::{AssetInfo, PairInfo};
+use gridiron::asset::{AssetInfo, AssetInfoExt, PairCreatedAt, PairInfo};
 use gridiron::factory::{
-    ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, PairConfig, PairType, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeeBreakdownResponse, FeeDestination, FeeDestinationShare,
+    FeeInfoResponse, InstantiateMsg, PairConfig, PairStatusResponse, PairType, PairTypeProposal,
+    PairTypeStats, PairsResponse, QueryMsg, StatsResponse,
+};
+use gridiron::pair::{
+    ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, SimulationResponse, XYKPoolUpdateParams,
 };
 
 use crate::factory_helper::{instantiate_token, FactoryHelper};
@@ -94,6 +101,7 @@ fn update_config() {
             Some("generator".to_string()),
             None,
             None,
+            Some(300),
         )
         .unwrap();
 
@@ -108,6 +116,16 @@ fn update_config() {
         "generator",
         config_res.generator_address.unwrap().to_string()
     );
+    assert_eq!(Some(300), config_res.max_combined_fee_bps);
+
+    // The cap must be in the (0, 10000] range
+    let err = helper
+        .update_config(&mut app, &owner, None, None, None, None, None, Some(0))
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Max combined fee bps must be between 1 and 10,000"
+    );
 
     // Unauthorized err
     let res = helper
@@ -119,6 +137,7 @@ fn update_config() {
             None,
             None,
             None,
+            None,
         )
         .unwrap_err();
     assert_eq!(res.root_cause().to_string(), "Unauthorized");
@@ -189,6 +208,9 @@ fn test_create_pair() {
     assert_eq!("contract1", helper.factory.to_string());
     assert_eq!("contract4", res.contract_addr.to_string());
     assert_eq!("contract5", res.liquidity_token.to_string());
+    // The pair records who requested its creation and when
+    assert_eq!(Some(owner.clone()), res.creator);
+    assert_eq!(Some(PairCreatedAt::from(&app.block_info())), res.created_at);
 
     // Create disabled pair type
     app.execute_contract(
@@ -248,6 +270,771 @@ fn test_create_pair() {
     assert_eq!(pair_types, vec![PairType::Custom("Custom".to_string())]);
 }
 
+#[test]
+fn pair_status() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+
+    helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: token1.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token2.clone(),
+        },
+    ];
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+
+    let query_status = |app: &App| {
+        app.wrap().query_wasm_smart::<PairStatusResponse>(
+            &helper.factory,
+            &QueryMsg::PairStatus {
+                pair: pair_info.contract_addr.to_string(),
+            },
+        )
+    };
+
+    let status = query_status(&app).unwrap();
+    assert_eq!(status.contract_addr, pair_info.contract_addr);
+    assert_eq!(status.pair_type, PairType::Xyk {});
+    assert_eq!(status.liquidity_token, pair_info.liquidity_token);
+    assert!(!status.is_disabled);
+    assert!(!status.is_generator_disabled);
+    assert_eq!(status.contract_name, "gridiron-pair");
+    assert!(!status.contract_version.is_empty());
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                code_id: 0,
+                pair_type: PairType::Xyk {},
+                total_fee_bps: 100,
+                maker_fee_bps: 10,
+                is_disabled: false,
+                is_generator_disabled: true,
+            },
+        },
+        &[],
+    )
+    .unwrap();
+
+    let status = query_status(&app).unwrap();
+    assert!(!status.is_disabled);
+    assert!(status.is_generator_disabled);
+
+    // A deregistered pair has no status in the factory
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister { asset_infos },
+        &[],
+    )
+    .unwrap();
+
+    let err = query_status(&app).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "Querier contract error: Generic error: Pair {} is not registered in the factory",
+            pair_info.contract_addr
+        ))
+    );
+}
+
+#[test]
+fn stats() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let tokens = ["tokenX", "tokenY", "tokenZ"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, Some(18)));
+
+    let query_stats = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<StatsResponse>(&helper.factory, &QueryMsg::Stats {})
+            .unwrap()
+    };
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    let pair_code_id = config.pair_configs[0].code_id;
+
+    // Pair types without pairs are reported too
+    let stats = query_stats(&app);
+    assert_eq!(stats.total_pairs, 0);
+    assert_eq!(stats.disabled_pairs, 0);
+    assert_eq!(
+        stats.pair_types,
+        vec![PairTypeStats {
+            pair_type: PairType::Xyk {},
+            code_id: Some(pair_code_id),
+            is_disabled: false,
+            pairs: 0,
+        }]
+    );
+    assert_eq!(stats.fee_address, config.fee_address);
+    assert_eq!(stats.generator_address, config.generator_address);
+    assert_eq!(stats.points_contract, config.points_contract);
+    assert_eq!(stats.token_code_id, config.token_code_id);
+    assert_eq!(stats.whitelist_code_id, config.whitelist_code_id);
+
+    for pair_tokens in [[&tokens[0], &tokens[1]], [&tokens[0], &tokens[2]]] {
+        helper
+            .create_pair(&mut app, &owner, PairType::Xyk {}, pair_tokens, None)
+            .unwrap();
+    }
+
+    let stats = query_stats(&app);
+    assert_eq!(stats.total_pairs, 2);
+    assert_eq!(stats.pair_types[0].pairs, 2);
+    assert_eq!(stats.disabled_pairs, 0);
+
+    // Pairs of a disabled pair type are counted as disabled
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                code_id: pair_code_id,
+                pair_type: PairType::Xyk {},
+                total_fee_bps: 100,
+                maker_fee_bps: 10,
+                is_disabled: true,
+                is_generator_disabled: false,
+            },
+        },
+        &[],
+    )
+    .unwrap();
+
+    let stats = query_stats(&app);
+    assert!(stats.pair_types[0].is_disabled);
+    assert_eq!(stats.disabled_pairs, 2);
+
+    // Deregistered pairs are not counted
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister {
+            asset_infos: vec![
+                AssetInfo::Token {
+                    contract_addr: tokens[0].clone(),
+                },
+                AssetInfo::Token {
+                    contract_addr: tokens[1].clone(),
+                },
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let stats = query_stats(&app);
+    assert_eq!(stats.total_pairs, 1);
+    assert_eq!(stats.pair_types[0].pairs, 1);
+    assert_eq!(stats.disabled_pairs, 1);
+}
+
+#[test]
+fn pairs_by_asset() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let tokens = ["tokenX", "tokenY", "tokenZ"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, Some(18)));
+
+    for pair_tokens in [
+        [&tokens[0], &tokens[1]],
+        [&tokens[0], &tokens[2]],
+        [&tokens[1], &tokens[2]],
+    ] {
+        helper
+            .create_pair(&mut app, &owner, PairType::Xyk {}, pair_tokens, None)
+            .unwrap();
+    }
+
+    let token_info = |token: &Addr| AssetInfo::Token {
+        contract_addr: token.clone(),
+    };
+    let query_pairs_by_asset =
+        |app: &App, token: &Addr, start_after: Option<Vec<AssetInfo>>, limit: Option<u32>| {
+            app.wrap()
+                .query_wasm_smart::<PairsResponse>(
+                    &helper.factory,
+                    &QueryMsg::PairsByAsset {
+                        asset_info: token_info(token),
+                        start_after,
+                        limit,
+                    },
+                )
+                .unwrap()
+                .pairs
+        };
+    let query_pair = |app: &App, token1: &Addr, token2: &Addr| {
+        app.wrap()
+            .query_wasm_smart::<PairInfo>(
+                &helper.factory,
+                &QueryMsg::Pair {
+                    asset_infos: vec![token_info(token1), token_info(token2)],
+                },
+            )
+            .unwrap()
+    };
+
+    // Pairs are returned in the same order as in the Pairs query
+    let all_pairs: PairsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let pairs = query_pairs_by_asset(&app, &tokens[0], None, None);
+    assert_eq!(
+        pairs,
+        all_pairs
+            .pairs
+            .iter()
+            .filter(|pair| pair.asset_infos.contains(&token_info(&tokens[0])))
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(pairs.len(), 2);
+
+    // Pagination
+    let first_page = query_pairs_by_asset(&app, &tokens[0], None, Some(1));
+    assert_eq!(first_page, pairs[..1]);
+    let second_page = query_pairs_by_asset(
+        &app,
+        &tokens[0],
+        Some(first_page[0].asset_infos.clone()),
+        None,
+    );
+    assert_eq!(second_page, pairs[1..]);
+
+    // Deregistered pairs are removed from the index
+    let pair = query_pair(&app, &tokens[0], &tokens[1]);
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister {
+            asset_infos: pair.asset_infos,
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_pairs_by_asset(&app, &tokens[0], None, None),
+        vec![query_pair(&app, &tokens[0], &tokens[2])]
+    );
+    assert_eq!(
+        query_pairs_by_asset(&app, &tokens[1], None, None),
+        vec![query_pair(&app, &tokens[1], &tokens[2])]
+    );
+    assert_eq!(
+        query_pairs_by_asset(&app, &helper.grid_token, None, None),
+        vec![]
+    );
+}
+
+#[test]
+fn one_pair_per_type() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let tokens = ["tokenX", "tokenY"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, Some(18)));
+    let asset_infos = tokens
+        .iter()
+        .map(|token| AssetInfo::Token {
+            contract_addr: token.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    let custom_type = PairType::Custom("xyk-v2".to_string());
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                code_id: config.pair_configs[0].code_id,
+                pair_type: custom_type.clone(),
+                total_fee_bps: 100,
+                maker_fee_bps: 10,
+                is_disabled: false,
+                is_generator_disabled: false,
+            },
+        },
+        &[],
+    )
+    .unwrap();
+
+    let factory = helper.factory.clone();
+    let query_pair = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<PairInfo>(
+                &factory,
+                &QueryMsg::Pair {
+                    asset_infos: asset_infos.clone(),
+                },
+            )
+            .unwrap()
+    };
+    let query_pairs = |app: &App, start_after: Option<Vec<AssetInfo>>| {
+        app.wrap()
+            .query_wasm_smart::<PairsResponse>(
+                &factory,
+                &QueryMsg::Pairs {
+                    start_after,
+                    limit: None,
+                },
+            )
+            .unwrap()
+            .pairs
+    };
+
+    let pair_tokens = [&tokens[0], &tokens[1]];
+    helper
+        .create_pair(&mut app, &user, PairType::Xyk {}, pair_tokens, None)
+        .unwrap();
+    let first_pair = query_pair(&app);
+
+    // The assets can have one pair of each pair type
+    let err = helper
+        .create_pair(&mut app, &user, PairType::Xyk {}, pair_tokens, None)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair was already created");
+
+    helper
+        .create_pair(&mut app, &user, custom_type.clone(), pair_tokens, None)
+        .unwrap();
+    let err = helper
+        .create_pair(&mut app, &user, custom_type, pair_tokens, None)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair was already created");
+
+    // Only the owner can create a duplicate pair
+    let duplicate_msg = ExecuteMsg::CreateDuplicatePair {
+        pair_type: PairType::Xyk {},
+        asset_infos: asset_infos.clone(),
+        init_params: None,
+    };
+    let err = app
+        .execute_contract(user, helper.factory.clone(), &duplicate_msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    app.execute_contract(owner, helper.factory.clone(), &duplicate_msg, &[])
+        .unwrap();
+
+    // The Pair query keeps returning the first pair while all of them are listed
+    assert_eq!(query_pair(&app), first_pair);
+    let pairs = query_pairs(&app, None);
+    assert_eq!(pairs.len(), 3);
+    assert_eq!(pairs[0], first_pair);
+    assert_eq!(
+        pairs
+            .iter()
+            .map(|pair| &pair.contract_addr)
+            .unique()
+            .count(),
+        3
+    );
+
+    // Pagination skips all pairs of the assets
+    assert_eq!(query_pairs(&app, Some(asset_infos)), vec![]);
+}
+
+#[test]
+fn fee_breakdown() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+
+    helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap();
+
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: vec![
+                    AssetInfo::Token {
+                        contract_addr: token1.clone(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: token2.clone(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    let query_breakdown = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<FeeBreakdownResponse>(
+                &helper.factory,
+                &QueryMsg::FeeBreakdown {
+                    pair: pair_info.contract_addr.to_string(),
+                },
+            )
+            .unwrap()
+    };
+
+    // Without a fee address all fees stay with liquidity providers
+    let breakdown = query_breakdown(&app);
+    assert_eq!(breakdown.pair, pair_info.contract_addr);
+    assert_eq!(breakdown.total_fee_bps, 100);
+    assert_eq!(breakdown.maker_fee_bps, 10);
+    assert_eq!(breakdown.fee_share, None);
+    assert_eq!(breakdown.staker_fee_share_bps, None);
+    assert_eq!(
+        breakdown.destinations,
+        vec![FeeDestinationShare {
+            destination: FeeDestination::LiquidityProviders,
+            recipient: None,
+            share: Decimal::one(),
+            fee_bps: Decimal::from_ratio(100u8, 1u8),
+        }]
+    );
+
+    helper
+        .update_config(
+            &mut app,
+            &owner,
+            None,
+            Some("maker".to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        pair_info.contract_addr.clone(),
+        &PairExecuteMsg::UpdateConfig {
+            params: to_binary(&XYKPoolUpdateParams::EnableFeeShare {
+                fee_share_bps: 1000,
+                fee_share_address: "share".to_string(),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // 10% of fees are shared, the Maker gets 0.1% of the rest
+    let breakdown = query_breakdown(&app);
+    assert_eq!(breakdown.fee_share.unwrap().bps, 1000);
+    assert_eq!(
+        breakdown.destinations,
+        vec![
+            FeeDestinationShare {
+                destination: FeeDestination::FeeShare,
+                recipient: Some(Addr::unchecked("share")),
+                share: Decimal::percent(10),
+                fee_bps: Decimal::from_ratio(10u8, 1u8),
+            },
+            FeeDestinationShare {
+                destination: FeeDestination::Maker,
+                recipient: Some(Addr::unchecked("maker")),
+                share: Decimal::from_ratio(9u8, 10000u16),
+                fee_bps: Decimal::from_ratio(9u8, 100u8),
+            },
+            FeeDestinationShare {
+                destination: FeeDestination::LiquidityProviders,
+                recipient: None,
+                share: Decimal::from_ratio(8991u16, 10000u16),
+                fee_bps: Decimal::from_ratio(8991u16, 100u8),
+            },
+        ]
+    );
+    let total_bps = breakdown
+        .destinations
+        .iter()
+        .fold(Decimal::zero(), |acc, dest| acc + dest.fee_bps);
+    assert_eq!(total_bps, Decimal::from_ratio(100u8, 1u8));
+}
+
+#[test]
+fn update_pair_fees() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+
+    helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: token1.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token2.clone(),
+        },
+    ];
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    let pair = pair_info.contract_addr.clone();
+
+    for token in [&token1, &token2] {
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::Mint {
+                recipient: owner.to_string(),
+                amount: Uint128::new(1_000_000_000_000),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.to_string(),
+                amount: Uint128::new(1_000_000_000_000),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: asset_infos
+                .iter()
+                .map(|info| info.with_balance(1_000_000_000_000u128))
+                .collect(),
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let query_fee_info = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<FeeInfoResponse>(
+                &helper.factory,
+                &QueryMsg::PairFeeInfo {
+                    pair_addr: pair.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    let simulate = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<SimulationResponse>(
+                &pair,
+                &PairQueryMsg::Simulation {
+                    offer_asset: asset_infos[0].with_balance(1_000_000u128),
+                    ask_asset_info: None,
+                },
+            )
+            .unwrap()
+    };
+    let update_pair_fees =
+        |app: &mut App, sender: &Addr, total_fee_bps: Option<u16>, maker_fee_bps: Option<u16>| {
+            app.execute_contract(
+                sender.clone(),
+                helper.factory.clone(),
+                &ExecuteMsg::UpdatePairFees {
+                    pair_addr: pair.to_string(),
+                    total_fee_bps,
+                    maker_fee_bps,
+                },
+                &[],
+            )
+        };
+
+    // Without an override the pair uses the fees of its pair type
+    let fee_info = query_fee_info(&app);
+    assert_eq!(fee_info.total_fee_bps, 100);
+    assert_eq!(fee_info.maker_fee_bps, 10);
+    let default_swap = simulate(&app);
+
+    let err = update_pair_fees(&mut app, &Addr::unchecked("random"), Some(30), None).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    let err = update_pair_fees(&mut app, &owner, Some(10_001), None).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Fee bps in pair config must be smaller than or equal to 10,000"
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePairFees {
+                pair_addr: token1.to_string(),
+                total_fee_bps: Some(30),
+                maker_fee_bps: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Querier contract error"));
+
+    // Fees which are not overridden fall back to the pair type config
+    update_pair_fees(&mut app, &owner, Some(30), None).unwrap();
+    let fee_info = query_fee_info(&app);
+    assert_eq!(fee_info.total_fee_bps, 30);
+    assert_eq!(fee_info.maker_fee_bps, 10);
+
+    let overridden_swap = simulate(&app);
+    assert!(overridden_swap.commission_amount < default_swap.commission_amount);
+    assert!(overridden_swap.return_amount > default_swap.return_amount);
+
+    let breakdown: FeeBreakdownResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::FeeBreakdown {
+                pair: pair.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(breakdown.total_fee_bps, 30);
+
+    // The pair type config is not affected
+    let fee_info: FeeInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::FeeInfo {
+                pair_type: PairType::Xyk {},
+            },
+        )
+        .unwrap();
+    assert_eq!(fee_info.total_fee_bps, 100);
+
+    update_pair_fees(&mut app, &owner, None, Some(5000)).unwrap();
+    let fee_info = query_fee_info(&app);
+    assert_eq!(fee_info.total_fee_bps, 100);
+    assert_eq!(fee_info.maker_fee_bps, 5000);
+
+    // The override respects the protocol-wide fee cap
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            max_combined_fee_bps: Some(200),
+        },
+        &[],
+    )
+    .unwrap();
+    let err = update_pair_fees(&mut app, &owner, Some(300), None).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Total fee exceeds the max combined fee of 200 bps"
+    );
+
+    // Unsetting both fees removes the override
+    update_pair_fees(&mut app, &owner, None, None).unwrap();
+    let fee_info = query_fee_info(&app);
+    assert_eq!(fee_info.total_fee_bps, 100);
+    assert_eq!(fee_info.maker_fee_bps, 10);
+    assert_eq!(simulate(&app), default_swap);
+}
+
 #[test]
 fn check_update_owner() {
     let mut app = mock_app();
@@ -354,3 +1141,219 @@ fn check_update_owner() {
 
     assert_eq!(res.owner, new_owner)
 }
+
+#[test]
+fn pair_type_proposals() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let team = Addr::unchecked("team");
+    let trader = Addr::unchecked("trader");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    let pair_code_id = config.pair_configs[0].code_id;
+    let params_schema_hash = "ab".repeat(32);
+    let pair_type = PairType::Custom("concentrated-v2".to_string());
+
+    let propose_msg =
+        |name: &str, params_schema_hash: &str, total_fee_bps: u16| ExecuteMsg::ProposePairType {
+            name: name.to_string(),
+            code_id: pair_code_id,
+            params_schema_hash: params_schema_hash.to_string(),
+            total_fee_bps,
+            maker_fee_bps: 5000,
+        };
+
+    for (msg, expected) in [
+        (
+            propose_msg("", &params_schema_hash, 30),
+            "Pair type name must have 1 to 30 chars of a-z, 0-9, '-' or '_'",
+        ),
+        (
+            propose_msg("Concentrated", &params_schema_hash, 30),
+            "Pair type name must have 1 to 30 chars of a-z, 0-9, '-' or '_'",
+        ),
+        (
+            propose_msg("concentrated-v2", "abcd", 30),
+            "Params schema hash must be a hex encoded SHA-256 hash",
+        ),
+        (
+            propose_msg("concentrated-v2", &params_schema_hash, 10_001),
+            "Fee bps in pair config must be smaller than or equal to 10,000",
+        ),
+    ] {
+        let err = app
+            .execute_contract(team.clone(), helper.factory.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(err.root_cause().to_string(), expected);
+    }
+
+    // Anyone can propose a custom pair type
+    app.execute_contract(
+        team.clone(),
+        helper.factory.clone(),
+        &propose_msg("concentrated-v2", &params_schema_hash, 30),
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(
+            trader.clone(),
+            helper.factory.clone(),
+            &propose_msg("concentrated-v2", &params_schema_hash, 30),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Pair type was already proposed"
+    );
+
+    let factory = helper.factory.clone();
+    let query_proposal = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<Option<PairTypeProposal>>(
+                &factory,
+                &QueryMsg::PairTypeProposal {
+                    pair_type: pair_type.clone(),
+                },
+            )
+            .unwrap()
+    };
+    assert_eq!(
+        query_proposal(&app),
+        Some(PairTypeProposal {
+            pair_type: pair_type.clone(),
+            code_id: pair_code_id,
+            params_schema_hash: params_schema_hash.clone(),
+            total_fee_bps: 30,
+            maker_fee_bps: 5000,
+            proposer: team.clone(),
+            approved_at: None,
+        })
+    );
+
+    // Pairs can't be created before the approval
+    let token1 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenX",
+        Some(18),
+    );
+    let token2 = instantiate_token(
+        &mut app,
+        helper.cw20_token_code_id,
+        &owner,
+        "tokenY",
+        Some(18),
+    );
+    let err = helper
+        .create_pair(
+            &mut app,
+            &trader,
+            pair_type.clone(),
+            [&token1, &token2],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair config not found");
+
+    // Only the owner can approve the pair type
+    let approve_msg = ExecuteMsg::ApprovePairType {
+        pair_type: pair_type.clone(),
+    };
+    let err = app
+        .execute_contract(team.clone(), helper.factory.clone(), &approve_msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(owner.clone(), helper.factory.clone(), &approve_msg, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(owner.clone(), helper.factory.clone(), &approve_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Pending pair type proposal not found"
+    );
+    assert_eq!(
+        query_proposal(&app).unwrap().approved_at,
+        Some(app.block_info().height)
+    );
+
+    let fee_info: FeeInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::FeeInfo {
+                pair_type: pair_type.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(fee_info.total_fee_bps, 30);
+    assert_eq!(fee_info.maker_fee_bps, 5000);
+
+    // Generator rewards stay disabled until the owner enables them
+    let blacklisted: Vec<PairType> = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::BlacklistedPairTypes {})
+        .unwrap();
+    assert_eq!(blacklisted, vec![pair_type.clone()]);
+
+    // Anyone can create pairs of the approved type
+    helper
+        .create_pair(
+            &mut app,
+            &trader,
+            pair_type.clone(),
+            [&token1, &token2],
+            None,
+        )
+        .unwrap();
+
+    // The same name can't be proposed again
+    let err = app
+        .execute_contract(
+            team.clone(),
+            helper.factory.clone(),
+            &propose_msg("concentrated-v2", &params_schema_hash, 30),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Pair config already exists");
+
+    // Pending proposals can be rejected by the owner or withdrawn by the proposer
+    app.execute_contract(
+        team.clone(),
+        helper.factory.clone(),
+        &propose_msg("stableswap-ng", &params_schema_hash, 30),
+        &[],
+    )
+    .unwrap();
+    let reject_msg = ExecuteMsg::RejectPairType {
+        pair_type: PairType::Custom("stableswap-ng".to_string()),
+    };
+    let err = app
+        .execute_contract(trader, helper.factory.clone(), &reject_msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    app.execute_contract(team, helper.factory.clone(), &reject_msg, &[])
+        .unwrap();
+
+    let proposals: Vec<PairTypeProposal> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairTypeProposals {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].pair_type, pair_type);
+}
@@ -5,33 +5,54 @@ use std::vec;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
-    Fraction, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
+    attr, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps,
+    DepsMut, Env, Fraction, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint512, Uint64,
+    WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 
 use gridiron::asset::{
-    addr_opt_validate, check_swap_parameters, format_lp_token_name, Asset, AssetInfo, CoinsExt,
-    PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    addr_opt_validate, check_swap_parameters, format_lp_token_name, native_asset, Asset, AssetInfo,
+    CoinsExt, PairCreatedAt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+};
+use gridiron::events::{ProvideEvent, SwapEvent};
+use gridiron::factory::{FeeDiscounts, FeeInfoResponse, PairType, QueryMsg as FactoryQueryMsg};
+use gridiron::observation::{
+    query_observation, safe_sma_buffer_not_full, safe_sma_calculation, Observation,
+    PrecommitObservation, OBSERVATIONS_SIZE,
 };
-use gridiron::factory::PairType;
-use gridiron::generator::Cw20HookMsg as GeneratorHookMsg;
 use gridiron::pair::{
-    ConfigResponse, FeeShareConfig, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
+    check_min_lp_to_receive, mint_liquidity_token_message, CombinedFees, ConfigResponse,
+    FeeShareConfig, MinTradeSize, Referral, ReferralFee, XYKPoolConfig, XYKPoolParams,
+    XYKPoolUpdateParams, DEFAULT_SLIPPAGE, INTERFACE_BATCH_SWAP, INTERFACE_DEADLINE,
+    INTERFACE_FEE_DISCOUNTS, INTERFACE_MIN_LP_TO_RECEIVE, INTERFACE_OBSERVATIONS,
+    INTERFACE_REFERRAL, INTERFACE_VERSIONED_QUERIES, MAX_ALLOWED_SLIPPAGE,
+    MAX_BATCH_SWAP_OPERATIONS, MAX_FEE_SHARE_BPS, MAX_MIN_TRADE_FLOOR, MAX_PRICE_MOVE_PER_BLOCK,
 };
 use gridiron::pair::{
     CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse,
-    QueryMsg, ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
+    QueryMsg, ReverseSimulationResponse, RoundingDust, SimulationResponse, TWAP_PRECISION,
+};
+use gridiron::points::swap_hook_msg;
+use gridiron::querier::{
+    query_factory_config, query_pair_fee_info, query_pair_info, query_supply, query_token_balance,
+    query_token_precision,
 };
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
-use gridiron::{token::InstantiateMsg as TokenInstantiateMsg, U256};
+use gridiron::router::SwapOperation;
+use gridiron::staking::{ConfigResponse as StakingConfigResponse, QueryMsg as StakingQueryMsg};
+use gridiron::{allowlist::query_is_allowed, token::InstantiateMsg as TokenInstantiateMsg, U256};
+use gridiron_circular_buffer::error::BufferResult;
+use gridiron_circular_buffer::BufferManager;
 use cw_utils::parse_instantiate_response_data;
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::state::{
+    BatchSwapContext, BlockPrice, Config, BALANCES, BATCH_SWAP, BLOCK_PRICE, CONFIG,
+    DONATIONS_ENABLED, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR, OBSERVATIONS, PRICE_MOVE_LIMIT,
+    ROUNDING_DUST, TOTAL_SHARE, XGRID_TOKEN,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "gridiron-pair";
@@ -39,21 +60,33 @@ const CONTRACT_NAME: &str = "gridiron-pair";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID used for sub-messages.
 const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+/// A `reply` call code ID used for the hops of a batch swap.
+const BATCH_SWAP_REPLY_ID: u64 = 2;
+
+gridiron::supported_interfaces!(
+    INTERFACE_DEADLINE,
+    INTERFACE_MIN_LP_TO_RECEIVE,
+    INTERFACE_VERSIONED_QUERIES,
+    INTERFACE_FEE_DISCOUNTS,
+    INTERFACE_BATCH_SWAP,
+    INTERFACE_OBSERVATIONS,
+    INTERFACE_REFERRAL,
+);
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     if msg.asset_infos.len() != 2 {
         return Err(StdError::generic_err("asset_infos must contain exactly two elements").into());
     }
 
-    msg.asset_infos[0].check(deps.api)?;
-    msg.asset_infos[1].check(deps.api)?;
+    msg.asset_infos[0].check_strict(deps.api)?;
+    msg.asset_infos[1].check_strict(deps.api)?;
 
     if msg.asset_infos[0] == msg.asset_infos[1] {
         return Err(ContractError::DoublingAssets {});
@@ -68,12 +101,17 @@ pub fn instantiate(
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let creator = addr_opt_validate(deps.api, &msg.creator)?.unwrap_or(info.sender);
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: Some(creator),
+            created_at: Some(PairCreatedAt::from(&env.block)),
+            allowlist: None,
         },
         factory_addr: deps.api.addr_validate(msg.factory_addr.as_str())?,
         block_time_last: 0,
@@ -87,10 +125,13 @@ pub fn instantiate(
         for asset in &config.pair_info.asset_infos {
             BALANCES.save(deps.storage, asset, &Uint128::zero(), env.block.height)?;
         }
+        TOTAL_SHARE.save(deps.storage, &Uint128::zero(), env.block.height)?;
     }
 
     CONFIG.save(deps.storage, &config)?;
 
+    BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
     // Create the LP token contract
@@ -131,7 +172,7 @@ pub fn instantiate(
 
 /// The entry point to the contract for processing replies from submessages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: INSTANTIATE_TOKEN_REPLY_ID,
@@ -157,6 +198,43 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             Ok(Response::new()
                 .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
         }
+        Reply {
+            id: BATCH_SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse { events, .. }),
+        } => {
+            let context = BATCH_SWAP.load(deps.storage)?;
+
+            // Take the return amount from the swap event of the pair which executed the hop
+            let return_amount = events
+                .iter()
+                .filter(|event| event.ty == "wasm")
+                .find_map(|event| {
+                    let value = |key: &str| {
+                        event
+                            .attributes
+                            .iter()
+                            .find(|attribute| attribute.key == key)
+                            .map(|attribute| attribute.value.as_str())
+                    };
+                    (value("_contract_address") == Some(context.pair.as_str())
+                        && value("action") == Some("swap"))
+                    .then(|| value("return_amount"))
+                    .flatten()
+                })
+                .ok_or(ContractError::FailedToParseReply {})?;
+
+            batch_swap_hop(
+                deps,
+                &env,
+                Asset {
+                    info: context.ask_asset_info,
+                    amount: Uint128::from_str(return_amount)?,
+                },
+                context.operations,
+                context.minimum_receive,
+                context.receiver,
+            )
+        }
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
@@ -174,14 +252,28 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             deadline,
+///             min_lp_to_receive,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
 ///             offer_asset,
 ///             belief_price,
 ///             max_spread,
+///             min_receive,
 ///             to,
+///             deadline,
+///             referral,
 ///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::BatchSwap {
+///             operations,
+///             minimum_receive,
+///             to,
+///             deadline,
+///         }** Chains several swaps starting with the sent native asset.
+///
+/// * **ExecuteMsg::Donate { assets }** Adds assets to the pool reserves without minting LP tokens.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -196,22 +288,34 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-        ),
+            deadline,
+            min_lp_to_receive,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
+            min_receive,
             to,
+            deadline,
+            referral,
             ..
         } => {
+            assert_deadline(&env, deadline)?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -227,9 +331,35 @@ pub fn execute(
                 offer_asset,
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
+            )
+        }
+        ExecuteMsg::BatchSwap {
+            operations,
+            minimum_receive,
+            to,
+            deadline,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            let offer_asset = match info.funds.as_slice() {
+                [coin] => native_asset(coin.denom.clone(), coin.amount),
+                _ => return Err(ContractError::AssetMismatch {}),
+            };
+
+            batch_swap(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                operations,
+                minimum_receive,
+                to,
             )
         }
+        ExecuteMsg::Donate { assets } => donate(deps, env, info, assets),
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
         _ => Err(ContractError::NonSupported {}),
     }
@@ -248,9 +378,14 @@ pub fn receive_cw20(
         Cw20HookMsg::Swap {
             belief_price,
             max_spread,
+            min_receive,
             to,
+            deadline,
+            referral,
             ..
         } => {
+            assert_deadline(&env, deadline)?;
+
             // Only asset contract can execute this message
             let mut authorized = false;
             let config = CONFIG.load(deps.storage)?;
@@ -281,17 +416,47 @@ pub fn receive_cw20(
                 },
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
+            )
+        }
+        Cw20HookMsg::BatchSwap {
+            operations,
+            minimum_receive,
+            to,
+            deadline,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            // The sent token doesn't have to be a pool asset as every hop is checked by its pair
+            batch_swap(
+                deps,
+                env,
+                Addr::unchecked(cw20_msg.sender),
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender,
+                    },
+                    amount: cw20_msg.amount,
+                },
+                operations,
+                minimum_receive,
+                to,
+            )
+        }
+        Cw20HookMsg::WithdrawLiquidity { assets, deadline } => {
+            assert_deadline(&env, deadline)?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                cw20_msg.amount,
+                assets,
             )
         }
-        Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
-            deps,
-            env,
-            info,
-            Addr::unchecked(cw20_msg.sender),
-            cw20_msg.amount,
-            assets,
-        ),
     }
 }
 
@@ -308,7 +473,10 @@ pub fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **min_lp_to_receive** is an optional minimum amount of LP tokens minted for the receiver.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
@@ -317,6 +485,7 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     if assets.len() != 2 {
         return Err(StdError::generic_err("asset_infos must contain exactly two elements").into());
@@ -327,6 +496,8 @@ pub fn provide_liquidity(
     let auto_stake = auto_stake.unwrap_or(false);
 
     let mut config = CONFIG.load(deps.storage)?;
+    assert_allowed(&deps.querier, &config, &info.sender)?;
+    assert_liquidity_enabled(deps.storage, &env)?;
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
     let mut pools = config
@@ -382,7 +553,8 @@ pub fn provide_liquidity(
 
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.pair_info.liquidity_token,
+            &config.factory_addr,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
@@ -410,11 +582,14 @@ pub fn provide_liquidity(
         )
     };
 
+    check_min_lp_to_receive(share, min_lp_to_receive)?;
+
     // Mint LP tokens for the sender or for the receiver (if set)
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.pair_info.liquidity_token,
+        &config.factory_addr,
         &env.contract.address,
         &receiver,
         share,
@@ -430,6 +605,18 @@ pub fn provide_liquidity(
                 env.block.height,
             )?;
         }
+
+        // The first provide also mints the minimum liquidity to the pair itself
+        let minted = if total_share.is_zero() {
+            share + MINIMUM_LIQUIDITY_AMOUNT
+        } else {
+            share
+        };
+        TOTAL_SHARE.save(
+            deps.storage,
+            &total_share.checked_add(minted)?,
+            env.block.height,
+        )?;
     }
 
     // Accumulate prices for the assets in the pool
@@ -442,77 +629,123 @@ pub fn provide_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", format!("{}, {}", assets[0], assets[1])),
-        attr("share", share),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        ProvideEvent {
+            sender: info.sender,
+            receiver,
+            assets,
+            share,
+        }
+        .into_attributes(),
+    ))
 }
 
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Generator contract (if auto staking is specified).
-///
-/// * **recipient** is the LP token recipient.
+/// Adds assets to the pool reserves without minting LP tokens, so their value accrues to the
+/// existing LPs.
 ///
-/// * **amount** is the amount of LP tokens that will be minted for the recipient.
+/// * **assets** are the assets to donate. One of the pool assets may be omitted.
 ///
-/// * **auto_stake** determines whether the newly minted LP tokens will
-/// be automatically staked in the Generator on behalf of the recipient.
-fn mint_liquidity_token_message(
-    querier: QuerierWrapper,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg>, ContractError> {
-    let lp_token = &config.pair_info.liquidity_token;
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: lp_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Mint {
-                recipient: recipient.to_string(),
-                amount,
-            })?,
-            funds: vec![],
-        })]);
+/// NOTE - the donor should approve the pair contract to pull CW20 tokens.
+pub fn donate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    if !DONATIONS_ENABLED
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+    {
+        return Err(ContractError::DonationsDisabled {});
+    }
+    for asset in &assets {
+        asset.info.check(deps.api)?;
     }
 
-    // Mint for the pair contract and stake into the Generator contract
-    let generator = query_factory_config(&querier, &config.factory_addr)?.generator_address;
+    let mut config = CONFIG.load(deps.storage)?;
+    assert_liquidity_enabled(deps.storage, &env)?;
+    info.funds
+        .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
 
-    if let Some(generator) = generator {
-        Ok(vec![
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: lp_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Mint {
-                    recipient: contract_address.to_string(),
-                    amount,
-                })?,
-                funds: vec![],
-            }),
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: lp_token.to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Send {
-                    contract: generator.to_string(),
-                    amount,
-                    msg: to_binary(&GeneratorHookMsg::DepositFor(recipient.to_string()))?,
-                })?,
-                funds: vec![],
-            }),
-        ])
-    } else {
-        Err(ContractError::AutoStakeError {})
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    if total_share.is_zero() {
+        return Err(ContractError::NoLiquidityToDonateTo {});
+    }
+
+    let mut pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let mut donations = vec![];
+    let mut messages = vec![];
+    for pool in pools.iter_mut() {
+        let amount = assets
+            .iter()
+            .find(|asset| asset.info.equal(&pool.info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+
+        match &pool.info {
+            AssetInfo::Token { contract_addr } if !amount.is_zero() => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+            AssetInfo::Token { .. } => {}
+            // The pool balance already includes the donated native tokens
+            AssetInfo::NativeToken { .. } => pool.amount = pool.amount.checked_sub(amount)?,
+        }
+
+        donations.push(Asset {
+            info: pool.info.clone(),
+            amount,
+        });
+    }
+
+    if donations.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if config.track_asset_balances {
+        for (pool, donation) in pools.iter().zip(&donations) {
+            BALANCES.save(
+                deps.storage,
+                &pool.info,
+                &pool.amount.checked_add(donation.amount)?,
+                env.block.height,
+            )?;
+        }
     }
+
+    // Accumulate prices with the reserves before the donation
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "donate"),
+        attr("sender", info.sender),
+        attr("assets", format!("{}, {}", donations[0], donations[1])),
+    ]))
 }
 
 /// Withdraw liquidity from the pool.
 /// * **sender** is the address that will receive assets back from the pair contract.
 ///
-/// * **amount** is the amount of LP tokens to burn.
+/// * **amount** is the amount of LP tokens to burn. For an imbalanced withdraw it is the maximum
+/// amount of LP tokens to burn, the rest is sent back to the sender.
+///
+/// * **assets** are the exact amounts of assets to withdraw. Empty for a balanced withdraw.
 pub fn withdraw_liquidity(
     deps: DepsMut,
     env: Env,
@@ -526,14 +759,40 @@ pub fn withdraw_liquidity(
     if info.sender != config.pair_info.liquidity_token {
         return Err(ContractError::Unauthorized {});
     }
+    assert_liquidity_enabled(deps.storage, &env)?;
 
     let (pools, total_share) = pool_info(deps.querier, &config)?;
 
-    let refund_assets = if assets.is_empty() {
+    let (refund_assets, burn_amount) = if assets.is_empty() {
         // Usual withdraw (balanced)
-        get_share_in_assets(&pools, amount, total_share)
+        let refund_assets = get_share_in_assets(&pools, amount, total_share);
+
+        // Refunds are rounded down, the remainders stay in the pool
+        for pool in &pools {
+            let remainder = pool.amount.full_mul(amount) % Uint256::from(total_share);
+            accumulate_rounding_dust(
+                deps.storage,
+                &pool.info,
+                Decimal256::from_ratio(remainder, total_share),
+            )?;
+        }
+
+        (refund_assets, amount)
     } else {
-        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+        let fee_info = query_pair_fee_info(
+            &deps.querier,
+            &config.factory_addr,
+            &config.pair_info.contract_addr,
+        )?;
+        let (refund_assets, burn_amount) =
+            get_imbalanced_withdraw_share(&pools, &assets, total_share, fee_info.total_fee_rate)?;
+        if burn_amount > amount {
+            return Err(ContractError::NotEnoughLpTokens {
+                required: burn_amount,
+            });
+        }
+
+        (refund_assets, burn_amount)
     };
 
     if config.track_asset_balances {
@@ -545,6 +804,11 @@ pub fn withdraw_liquidity(
                 env.block.height,
             )?;
         }
+        TOTAL_SHARE.save(
+            deps.storage,
+            &total_share.checked_sub(burn_amount)?,
+            env.block.height,
+        )?;
     }
 
     // Accumulate prices for the pair assets
@@ -558,20 +822,36 @@ pub fn withdraw_liquidity(
     }
 
     // Update the pool info
-    let messages: Vec<CosmosMsg> = vec![
-        refund_assets[0].clone().into_msg(sender.clone())?,
-        refund_assets[1].clone().into_msg(sender.clone())?,
-        CosmosMsg::Wasm(WasmMsg::Execute {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for refund_asset in &refund_assets {
+        // An imbalanced withdraw may skip one of the assets
+        if assets.is_empty() || !refund_asset.amount.is_zero() {
+            messages.push(refund_asset.clone().into_msg(sender.clone())?);
+        }
+    }
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.pair_info.liquidity_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn {
+            amount: burn_amount,
+        })?,
+        funds: vec![],
+    }));
+    // LP tokens which were not needed for an imbalanced withdraw go back to the sender
+    if burn_amount < amount {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: config.pair_info.liquidity_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: sender.to_string(),
+                amount: amount - burn_amount,
+            })?,
             funds: vec![],
-        }),
-    ];
+        }));
+    }
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "withdraw_liquidity"),
         attr("sender", sender),
-        attr("withdrawn_share", amount),
+        attr("withdrawn_share", burn_amount),
         attr(
             "refund_assets",
             format!("{}, {}", refund_assets[0], refund_assets[1]),
@@ -587,20 +867,115 @@ pub fn withdraw_liquidity(
 ///
 /// * **total_share** is the total amount of LP tokens currently minted.
 pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint128) -> Vec<Asset> {
-    let mut share_ratio = Decimal::zero();
-    if !total_share.is_zero() {
-        share_ratio = Decimal::from_ratio(amount, total_share);
-    }
-
     pools
         .iter()
         .map(|a| Asset {
             info: a.info.clone(),
-            amount: a.amount * share_ratio,
+            // multiply_ratio keeps full precision for large 18 decimal reserves
+            amount: if total_share.is_zero() {
+                Uint128::zero()
+            } else {
+                a.amount.multiply_ratio(amount, total_share)
+            },
         })
         .collect()
 }
 
+/// Returns the assets to send and the amount of LP tokens to burn for an imbalanced withdraw.
+/// The part of the withdrawal which diverges from the pool ratio is charged half of the swap
+/// commission, the fee stays in the pool. Rounding is always in favour of the pool.
+///
+/// * **pools** is the array with assets in the pool.
+///
+/// * **assets** are the exact amounts of assets to withdraw.
+///
+/// * **total_share** is the total amount of LP tokens currently minted.
+///
+/// * **commission_rate** is the total swap commission of the pair.
+pub fn get_imbalanced_withdraw_share(
+    pools: &[Asset],
+    assets: &[Asset],
+    total_share: Uint128,
+    commission_rate: Decimal,
+) -> Result<(Vec<Asset>, Uint128), ContractError> {
+    if assets
+        .iter()
+        .enumerate()
+        .any(|(i, asset)| assets[..i].iter().any(|a| a.info.equal(&asset.info)))
+    {
+        return Err(ContractError::DoublingAssets {});
+    }
+
+    let mut refund_assets: Vec<Asset> = pools
+        .iter()
+        .map(|pool| Asset {
+            info: pool.info.clone(),
+            amount: Uint128::zero(),
+        })
+        .collect();
+    for asset in assets {
+        let ind = pools
+            .iter()
+            .position(|pool| pool.info.equal(&asset.info))
+            .ok_or(ContractError::AssetMismatch {})?;
+        refund_assets[ind].amount = asset.amount;
+    }
+    if refund_assets.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if pools
+        .iter()
+        .zip(&refund_assets)
+        .any(|(pool, refund)| refund.amount >= pool.amount)
+    {
+        return Err(ContractError::WithdrawExceedsReserves {});
+    }
+
+    let old_xp: Vec<U256> = pools
+        .iter()
+        .map(|pool| U256::from(pool.amount.u128()))
+        .collect();
+    let new_xp: Vec<U256> = pools
+        .iter()
+        .zip(&refund_assets)
+        .map(|(pool, refund)| U256::from((pool.amount - refund.amount).u128()))
+        .collect();
+
+    // The invariant sqrt(x * y) is rounded up before and down after the withdrawal
+    let old_product = old_xp[0] * old_xp[1];
+    let mut d0 = old_product.integer_sqrt();
+    if d0 * d0 < old_product {
+        d0 += U256::one();
+    }
+    let d1 = (new_xp[0] * new_xp[1]).integer_sqrt();
+
+    // Charge the fee on the difference between the new reserves and a balanced withdrawal
+    let fee_numerator = U256::from(commission_rate.numerator().u128());
+    let fee_denominator = U256::from(commission_rate.denominator().u128()) * U256::from(2u8);
+    let mut fee_xp = vec![];
+    for (old, new) in old_xp.into_iter().zip(new_xp) {
+        let ideal = old * d1 / d0;
+        let diff = if ideal > new {
+            ideal - new
+        } else {
+            new - ideal
+        };
+        let fee = (diff * fee_numerator + fee_denominator - U256::one()) / fee_denominator;
+        fee_xp.push(
+            new.checked_sub(fee)
+                .ok_or(ContractError::WithdrawExceedsReserves {})?,
+        );
+    }
+    let d2 = (fee_xp[0] * fee_xp[1]).integer_sqrt();
+
+    let burn_amount = (U256::from(total_share.u128()) * (d0 - d2) + d0 - U256::one()) / d0;
+
+    Ok((
+        refund_assets,
+        Uint128::new(burn_amount.as_u128()).max(Uint128::one()),
+    ))
+}
+
 /// Performs an swap operation with the specified parameters. The trader must approve the
 /// pool contract to transfer offer assets from their wallet.
 ///
@@ -612,8 +987,14 @@ pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint12
 ///
 /// * **max_spread** sets the maximum spread of the swap operation.
 ///
+/// * **min_receive** sets the minimum amount of ask tokens to receive. When set, it replaces
+/// the `belief_price` and `max_spread` check.
+///
 /// * **to** sets the recipient of the swap operation.
 ///
+/// * **referral** the frontend which takes a fee from the return amount. The slippage checks
+/// apply to the amount left after the fee.
+///
 /// NOTE - the address that wants to swap should approve the pair contract to pull the offer token.
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
@@ -624,12 +1005,21 @@ pub fn swap(
     offer_asset: Asset,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
     to: Option<Addr>,
+    referral: Option<Referral>,
 ) -> Result<Response, ContractError> {
     offer_asset.assert_sent_native_token_balance(&info)?;
 
     let mut config = CONFIG.load(deps.storage)?;
 
+    // The hops of a batch swap are executed by the pair itself after the trader was checked
+    if sender != env.contract.address {
+        assert_allowed(&deps.querier, &config, &sender)?;
+    }
+    assert_swaps_enabled(deps.storage, &env)?;
+    assert_min_trade_size(deps.as_ref(), &config, &offer_asset)?;
+
     // If the asset balance is already increased, we should subtract the user deposit from the pool amount
     let pools = config
         .pair_info
@@ -657,29 +1047,48 @@ pub fn swap(
     }
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
 
+    // Traders holding xGRID pay a discounted fee
+    let fee_discount_bps = match &fee_info.fee_discounts {
+        Some(fee_discounts) => {
+            query_fee_discount_bps(deps.storage, &deps.querier, fee_discounts, &sender)?
+        }
+        None => 0,
+    };
+    let total_fee_rate = fee_info.total_fee_rate
+        * Decimal::from_ratio(10_000u16.saturating_sub(fee_discount_bps), 10_000u16);
+
     let offer_amount = offer_asset.amount;
 
-    let (return_amount, spread_amount, commission_amount) = compute_swap(
-        offer_pool.amount,
-        ask_pool.amount,
-        offer_amount,
-        fee_info.total_fee_rate,
-    )?;
+    let (return_amount, spread_amount, commission_amount, mut rounding_dust) =
+        compute_swap_with_dust(
+            offer_pool.amount,
+            ask_pool.amount,
+            offer_amount,
+            total_fee_rate,
+        )?;
 
-    // Check the max spread limit (if it was specified)
-    assert_max_spread(
-        belief_price,
-        max_spread,
-        offer_amount,
-        return_amount + commission_amount,
-        spread_amount,
-    )?;
+    let referral_fee =
+        ReferralFee::new(deps.api, referral, fee_info.max_referral_bps, return_amount)?;
+    let return_amount = return_amount - referral_fee.amount;
+
+    if let Some(min_receive) = min_receive {
+        assert_min_receive(min_receive, return_amount)?;
+    } else {
+        // Check the max spread limit (if it was specified)
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_amount,
+            return_amount + commission_amount,
+            spread_amount,
+        )?;
+    }
 
     let return_asset = Asset {
         info: ask_pool.info.clone(),
@@ -691,6 +1100,7 @@ pub fn swap(
     if !return_amount.is_zero() {
         messages.push(return_asset.into_msg(receiver.clone())?)
     }
+    messages.extend(referral_fee.into_msg(&ask_pool.info)?);
 
     // If this pool is configured to share fees, calculate the amount to send
     // to the receiver and add the transfer message
@@ -702,6 +1112,8 @@ pub fn swap(
         // Calculate the fee share amount from the full commission amount
         let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
         fee_share_amount = fees_commission_amount * share_fee_rate;
+        rounding_dust +=
+            rounding_remainder(fees_commission_amount, share_fee_rate, fee_share_amount)?;
 
         if !fee_share_amount.is_zero() {
             // Subtract the fee share amount from the commission
@@ -728,8 +1140,20 @@ pub fn swap(
             maker_fee_amount = f.amount;
             messages.push(f.into_msg(fee_address)?);
         }
+        rounding_dust += rounding_remainder(
+            fees_commission_amount,
+            fee_info.maker_fee_rate,
+            maker_fee_amount,
+        )?;
+    }
+
+    // Report the swap to the points contract (if any)
+    if let Some(points_contract) = &fee_info.points_contract {
+        messages.push(swap_hook_msg(points_contract, &receiver, &offer_asset)?);
     }
 
+    accumulate_rounding_dust(deps.storage, &ask_pool.info, rounding_dust)?;
+
     if config.track_asset_balances {
         BALANCES.save(
             deps.storage,
@@ -740,11 +1164,39 @@ pub fn swap(
         BALANCES.save(
             deps.storage,
             &ask_pool.info,
-            &(ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount),
+            &(ask_pool.amount
+                - return_amount
+                - maker_fee_amount
+                - fee_share_amount
+                - referral_fee.amount),
             env.block.height,
         )?;
     }
 
+    let offer_pool_new = offer_pool.amount + offer_amount;
+    let ask_pool_new =
+        ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount - referral_fee.amount;
+    let (base_amount, quote_amount) = if offer_asset.info.equal(&pools[0].info) {
+        (offer_pool_new, ask_pool_new)
+    } else {
+        (ask_pool_new, offer_pool_new)
+    };
+    assert_price_move(
+        deps.storage,
+        &env,
+        (pools[0].amount, pools[1].amount),
+        (base_amount, quote_amount),
+    )?;
+
+    // Store observation from precommit data
+    accumulate_observations(deps.storage, &env)?;
+
+    // Store the pool price after the swap in precommit observation.
+    // This data will be reflected in observations on the next action.
+    if !base_amount.is_zero() && !quote_amount.is_zero() {
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
     // Accumulate prices for the assets in the pool
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
         accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
@@ -761,19 +1213,224 @@ pub fn swap(
             // 2. send inactive commission fees to the Maker contract
             messages,
         )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
+        .add_attributes(
+            SwapEvent {
+                sender,
+                receiver,
+                offer_asset: offer_asset.info,
+                ask_asset: ask_pool.info,
+                offer_amount,
+                return_amount,
+                spread_amount,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount,
+            }
+            .into_attributes(),
+        )
+        .add_attributes(
+            (fee_discount_bps > 0).then(|| attr("fee_discount_bps", fee_discount_bps.to_string())),
+        )
+        .add_attributes(referral_fee.attributes()))
+}
+
+/// Returns the swap fee discount (in bps) a trader gets for the xGRID they hold. The xGRID token
+/// of the staking contract is cached, so a discounted swap only costs one extra balance query.
+/// Failing queries give no discount rather than blocking the swap.
+///
+/// * **fee_discounts** the fee discounts of the pair type.
+///
+/// * **trader** the address whose xGRID balance is checked.
+pub fn query_fee_discount_bps(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    fee_discounts: &FeeDiscounts,
+    trader: &Addr,
+) -> StdResult<u16> {
+    let xgrid_token = match XGRID_TOKEN.may_load(storage)? {
+        Some((staking, xgrid_token)) if staking == fee_discounts.staking_contract => xgrid_token,
+        _ => {
+            let staking_config: StakingConfigResponse = match querier
+                .query_wasm_smart(&fee_discounts.staking_contract, &StakingQueryMsg::Config {})
+            {
+                Ok(staking_config) => staking_config,
+                Err(_) => return Ok(0),
+            };
+            XGRID_TOKEN.save(
+                storage,
+                &(
+                    fee_discounts.staking_contract.clone(),
+                    staking_config.share_token_addr.clone(),
+                ),
+            )?;
+            staking_config.share_token_addr
+        }
+    };
+
+    Ok(query_token_balance(querier, xgrid_token, trader)
+        .map(|balance| fee_discounts.discount_bps(balance))
+        .unwrap_or_default())
+}
+
+/// Starts a batch swap which chains the given swap operations. Every hop is executed in a
+/// sub-message whose return amount is offered in the next hop, so the whole batch reverts when a
+/// hop fails or the last one returns less than `minimum_receive`.
+///
+/// * **sender** the trader who started the batch swap.
+///
+/// * **offer_asset** the asset offered in the first operation.
+///
+/// * **operations** the swaps to perform in order.
+///
+/// * **minimum_receive** the minimum amount of the last ask asset to receive.
+///
+/// * **to** the receiver of the last ask asset. Defaults to the sender.
+pub fn batch_swap(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    if BATCH_SWAP.exists(deps.storage) {
+        return Err(ContractError::BatchSwapInProgress {});
+    }
+
+    if operations.is_empty() || operations.len() > MAX_BATCH_SWAP_OPERATIONS {
+        return Err(ContractError::InvalidBatchSwapLength {});
+    }
+
+    // Every hop must offer the asset returned by the previous one
+    let mut next_offer_info = offer_asset.info.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        match operation {
+            SwapOperation::GridSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } if offer_asset_info.equal(&next_offer_info)
+                && !ask_asset_info.equal(offer_asset_info) =>
+            {
+                ask_asset_info.check(deps.api)?;
+                next_offer_info = ask_asset_info.clone();
+            }
+            _ => return Err(ContractError::InvalidBatchSwapOperation { index }),
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    assert_allowed(&deps.querier, &config, &sender)?;
+
+    let receiver = addr_opt_validate(deps.api, &to)?.unwrap_or(sender);
+
+    batch_swap_hop(
+        deps,
+        &env,
+        offer_asset,
+        operations,
+        minimum_receive,
+        receiver,
+    )
+}
+
+/// Dispatches the next hop of a batch swap or, once all operations are done, checks the
+/// minimum receive amount and sends the last ask asset to the receiver.
+///
+/// Hops between the assets of this pair are executed by the pair itself, other hops go through
+/// the factory pair of the two assets. The return of every hop is sent back to this contract.
+fn batch_swap_hop(
+    deps: DepsMut,
+    env: &Env,
+    offer_asset: Asset,
+    mut operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    receiver: Addr,
+) -> Result<Response, ContractError> {
+    if operations.is_empty() {
+        if let Some(minimum_receive) = minimum_receive {
+            assert_min_receive(minimum_receive, offer_asset.amount)?;
+        }
+        BATCH_SWAP.remove(deps.storage);
+
+        let mut response = Response::new();
+        if !offer_asset.amount.is_zero() {
+            response = response.add_message(offer_asset.clone().into_msg(&receiver)?);
+        }
+
+        return Ok(response.add_attributes([
+            attr("action", "batch_swap"),
             attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("fee_share_amount", fee_share_amount),
-        ]))
+            attr("ask_asset", offer_asset.info.to_string()),
+            attr("return_amount", offer_asset.amount),
+        ]));
+    }
+
+    let ask_asset_info = operations.remove(0).get_target_asset_info();
+
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    let pair = if asset_infos.iter().any(|info| info.equal(&offer_asset.info))
+        && asset_infos.iter().any(|info| info.equal(&ask_asset_info))
+    {
+        env.contract.address.clone()
+    } else {
+        query_pair_info(
+            &deps.querier,
+            &config.factory_addr,
+            &[offer_asset.info.clone(), ask_asset_info.clone()],
+        )?
+        .contract_addr
+    };
+
+    // The batch is protected by the minimum receive amount rather than by every hop
+    let max_spread = Some(Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?);
+    let hop_msg = match &offer_asset.info {
+        AssetInfo::NativeToken { .. } => wasm_execute(
+            &pair,
+            &ExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: Some(ask_asset_info.clone()),
+                belief_price: None,
+                max_spread,
+                min_receive: None,
+                to: None,
+                deadline: None,
+                referral: None,
+            },
+            vec![offer_asset.as_coin()?],
+        )?,
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&Cw20HookMsg::Swap {
+                    ask_asset_info: Some(ask_asset_info.clone()),
+                    belief_price: None,
+                    max_spread,
+                    min_receive: None,
+                    to: None,
+                    deadline: None,
+                    referral: None,
+                })?,
+            },
+            vec![],
+        )?,
+    };
+
+    BATCH_SWAP.save(
+        deps.storage,
+        &BatchSwapContext {
+            operations,
+            pair,
+            ask_asset_info,
+            minimum_receive,
+            receiver,
+        },
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(hop_msg, BATCH_SWAP_REPLY_ID)))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -788,10 +1445,12 @@ pub fn update_config(
     let mut config = CONFIG.load(deps.storage)?;
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
-    if info.sender != factory_config.owner {
+    // The factory is allowed to disable fee sharing on behalf of the protocol
+    if info.sender != factory_config.owner && info.sender != config.factory_addr {
         return Err(ContractError::Unauthorized {});
     }
 
+    let previous_config = config.clone();
     let mut response = Response::default();
 
     match from_binary::<XYKPoolUpdateParams>(&params)? {
@@ -808,6 +1467,8 @@ pub fn update_config(
             for pool in pools.iter() {
                 BALANCES.save(deps.storage, &pool.info, &pool.amount, env.block.height)?;
             }
+            let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+            TOTAL_SHARE.save(deps.storage, &total_share, env.block.height)?;
 
             CONFIG.save(deps.storage, &config)?;
 
@@ -854,11 +1515,328 @@ pub fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        XYKPoolUpdateParams::UpdateMinTradeFloor { min_trade_floor } => {
+            response
+                .attributes
+                .push(attr("action", "update_min_trade_floor"));
+
+            if let Some(floor) = min_trade_floor {
+                if floor.is_zero() || floor > MAX_MIN_TRADE_FLOOR {
+                    return Err(ContractError::MinTradeFloorOutOfBounds {});
+                }
+
+                // Precisions of both assets must be known to convert the floor
+                for asset_info in &config.pair_info.asset_infos {
+                    query_token_precision(&deps.querier, asset_info, &config.factory_addr)?;
+                }
+
+                MIN_TRADE_FLOOR.save(deps.storage, &floor)?;
+                response
+                    .attributes
+                    .push(attr("min_trade_floor", floor.to_string()));
+            } else {
+                MIN_TRADE_FLOOR.remove(deps.storage);
+            }
+        }
+        XYKPoolUpdateParams::UpdateAllowlist { allowlist } => {
+            response.attributes.push(attr("action", "update_allowlist"));
+
+            config.pair_info.allowlist = addr_opt_validate(deps.api, &allowlist)?;
+            if let Some(allowlist) = &config.pair_info.allowlist {
+                // Make sure the allowlist answers the standard query so the pair isn't bricked
+                query_is_allowed(&deps.querier, allowlist, &env.contract.address)?;
+                response.attributes.push(attr("allowlist", allowlist));
+            }
+            CONFIG.save(deps.storage, &config)?;
+        }
+        XYKPoolUpdateParams::ScheduleMaintenance { window } => {
+            if let Some(window) = window {
+                if !window.is_valid(env.block.time.seconds()) {
+                    return Err(ContractError::InvalidMaintenanceWindow {});
+                }
+
+                response.attributes.extend([
+                    attr("action", "schedule_maintenance"),
+                    attr("maintenance_mode", window.mode.to_string()),
+                    attr("maintenance_start", window.start.to_string()),
+                    attr("maintenance_end", window.end.to_string()),
+                ]);
+                MAINTENANCE_WINDOW.save(deps.storage, &window)?;
+            } else {
+                response
+                    .attributes
+                    .push(attr("action", "cancel_maintenance"));
+                MAINTENANCE_WINDOW.remove(deps.storage);
+            }
+        }
+        XYKPoolUpdateParams::UpdateDonations { enabled } => {
+            DONATIONS_ENABLED.save(deps.storage, &enabled)?;
+
+            response.attributes.push(attr("action", "update_donations"));
+            response
+                .attributes
+                .push(attr("donations_enabled", enabled.to_string()));
+        }
+        XYKPoolUpdateParams::UpdatePriceMoveLimit {
+            max_price_move_per_block,
+        } => {
+            response
+                .attributes
+                .push(attr("action", "update_price_move_limit"));
+
+            if let Some(limit) = max_price_move_per_block {
+                if limit.is_zero() || limit > MAX_PRICE_MOVE_PER_BLOCK {
+                    return Err(ContractError::PriceMoveLimitOutOfBounds {});
+                }
+
+                PRICE_MOVE_LIMIT.save(deps.storage, &limit)?;
+                response
+                    .attributes
+                    .push(attr("max_price_move_per_block", limit.to_string()));
+            } else {
+                PRICE_MOVE_LIMIT.remove(deps.storage);
+            }
+        }
     }
 
+    check_combined_fee(
+        &deps.querier,
+        &previous_config,
+        &config,
+        factory_config.max_combined_fee_bps,
+    )?;
+
     Ok(response)
 }
 
+/// Checks that a config update doesn't raise the fees the pair combines on a swap above the
+/// protocol-wide cap set in the factory.
+fn check_combined_fee(
+    querier: &QuerierWrapper,
+    previous_config: &Config,
+    config: &Config,
+    max_combined_fee_bps: Option<u16>,
+) -> Result<(), ContractError> {
+    let max_combined_fee_bps = match max_combined_fee_bps {
+        Some(max_combined_fee_bps) => max_combined_fee_bps,
+        None => return Ok(()),
+    };
+
+    let fee_info: FeeInfoResponse = querier.query_wasm_smart(
+        &config.factory_addr,
+        &FactoryQueryMsg::PairFeeInfo {
+            pair_addr: config.pair_info.contract_addr.to_string(),
+        },
+    )?;
+    let combined_fees = |config: &Config| CombinedFees {
+        total_fee_bps: fee_info.total_fee_bps,
+        fee_share_bps: config.fee_share.as_ref().map_or(0, |share| share.bps),
+        ..Default::default()
+    };
+
+    let fees = combined_fees(config);
+    if fees.exceed_cap(&combined_fees(previous_config), max_combined_fee_bps) {
+        return Err(ContractError::CombinedFeeExceedsCap {
+            combined_fee_bps: fees.combined_fee_bps(),
+            max_combined_fee_bps,
+        });
+    }
+
+    Ok(())
+}
+
+/// Rejects swaps during a post-only maintenance window.
+pub fn assert_swaps_enabled(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_swaps(env.block.time.seconds()) => {
+            Err(ContractError::SwapsHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects swaps which move the pool price further than the owner set limit away from the price
+/// before the first swap of the block. The move is cumulative across all swaps of the block.
+///
+/// * **pools** the pool reserves before the swap.
+///
+/// * **new_pools** the pool reserves after the swap.
+pub fn assert_price_move(
+    storage: &mut dyn Storage,
+    env: &Env,
+    pools: (Uint128, Uint128),
+    new_pools: (Uint128, Uint128),
+) -> Result<(), ContractError> {
+    let limit = match PRICE_MOVE_LIMIT.may_load(storage)? {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    if pools.0.is_zero() || new_pools.0.is_zero() {
+        return Ok(());
+    }
+
+    let block_price = match BLOCK_PRICE.may_load(storage)? {
+        Some(block_price) if block_price.height == env.block.height => block_price.price,
+        _ => {
+            let price = Decimal256::from_ratio(pools.1, pools.0);
+            BLOCK_PRICE.save(
+                storage,
+                &BlockPrice {
+                    height: env.block.height,
+                    price,
+                },
+            )?;
+            price
+        }
+    };
+    if block_price.is_zero() {
+        return Ok(());
+    }
+
+    let new_price = Decimal256::from_ratio(new_pools.1, new_pools.0);
+    let price_move = if new_price > block_price {
+        new_price - block_price
+    } else {
+        block_price - new_price
+    };
+    if price_move / block_price > Decimal256::from(limit) {
+        return Err(ContractError::PriceMoveLimitExceeded { limit });
+    }
+
+    Ok(())
+}
+
+/// Rejects liquidity provision and withdrawals during a swap-only maintenance window.
+pub fn assert_liquidity_enabled(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_liquidity(env.block.time.seconds()) => {
+            Err(ContractError::LiquidityHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects liquidity provision and swaps by addresses which the pair allowlist doesn't approve.
+/// Every address is allowed in permissionless pairs.
+///
+/// * **address** the address which provides liquidity or swaps.
+pub fn assert_allowed(
+    querier: &QuerierWrapper,
+    config: &Config,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !config.pair_info.is_allowed(querier, address)? {
+        return Err(ContractError::AddressNotAllowed {
+            address: address.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Rejects swaps which offer less than the floor set for the pair.
+///
+/// * **offer_asset** the asset offered in the swap.
+pub fn assert_min_trade_size(
+    deps: Deps,
+    config: &Config,
+    offer_asset: &Asset,
+) -> Result<(), ContractError> {
+    if let Some(floor) = MIN_TRADE_FLOOR.may_load(deps.storage)? {
+        let precision =
+            query_token_precision(&deps.querier, &offer_asset.info, &config.factory_addr)?;
+        let min = MinTradeSize::new(offer_asset.info.clone(), precision, Some(floor))?.swap;
+
+        if offer_asset.amount < min {
+            return Err(ContractError::BelowMinTradeSize {
+                amount: offer_asset.amount,
+                min,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the min trade sizes of the pool assets. Price observations are taken from the pool
+/// reserves rather than from trade sizes, so the list is empty unless a swap floor is set.
+pub fn query_min_trade_sizes(deps: Deps, config: &Config) -> StdResult<Vec<MinTradeSize>> {
+    match MIN_TRADE_FLOOR.may_load(deps.storage)? {
+        Some(floor) => config
+            .pair_info
+            .asset_infos
+            .iter()
+            .map(|asset_info| {
+                let precision =
+                    query_token_precision(&deps.querier, asset_info, &config.factory_addr)?;
+                MinTradeSize::new(asset_info.clone(), precision, Some(floor))
+            })
+            .collect(),
+        None => Ok(vec![]),
+    }
+}
+
+/// Calculates and saves the price moving average. Observed prices are the pool prices
+/// (amount of asset\[\0] per unit of asset\[\1]) after the last swap of a block.
+pub fn accumulate_observations(storage: &mut dyn Storage, env: &Env) -> BufferResult<()> {
+    if let Some(PrecommitObservation {
+        base_amount,
+        quote_amount,
+        precommit_ts,
+    }) = PrecommitObservation::may_load(storage)?
+    {
+        let mut buffer = BufferManager::new(storage, OBSERVATIONS)?;
+        let observed_price = Decimal::from_ratio(base_amount, quote_amount);
+
+        let new_observation;
+        if let Some(last_obs) = buffer.read_last(storage)? {
+            // Skip saving observation if it has been already saved
+            if last_obs.ts < precommit_ts {
+                // Since this is circular buffer the next index contains the oldest value
+                let count = buffer.capacity();
+                if let Some(oldest_obs) = buffer.read_single(storage, buffer.head() + 1)? {
+                    let price_sma = safe_sma_calculation(
+                        last_obs.price_sma,
+                        oldest_obs.price,
+                        count,
+                        observed_price,
+                    )?;
+                    new_observation = Observation {
+                        ts: precommit_ts,
+                        price: observed_price,
+                        price_sma,
+                    };
+                } else {
+                    // Buffer is not full yet
+                    let count = buffer.head();
+                    let price_sma =
+                        safe_sma_buffer_not_full(last_obs.price_sma, count, observed_price)?;
+                    new_observation = Observation {
+                        ts: precommit_ts,
+                        price: observed_price,
+                        price_sma,
+                    };
+                }
+
+                buffer.instant_push(storage, &new_observation)?
+            }
+        } else {
+            // Buffer is empty
+            if env.block.time.seconds() > precommit_ts {
+                new_observation = Observation {
+                    ts: precommit_ts,
+                    price: observed_price,
+                    price_sma: observed_price,
+                };
+
+                buffer.instant_push(storage, &new_observation)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Accumulate token prices for the assets in the pool.
 /// Note that this function shifts **block_time** when any of the token prices is zero in order to not
 /// fill an accumulator with a null price for that period.
@@ -924,6 +1902,32 @@ pub fn calculate_maker_fee(
     })
 }
 
+/// Returns the fractional part of `amount * rate` which is lost by rounding it down to `rounded`.
+pub fn rounding_remainder(
+    amount: Uint128,
+    rate: Decimal,
+    rounded: Uint128,
+) -> StdResult<Decimal256> {
+    let exact = Decimal256::from_ratio(amount, 1u8) * Decimal256::from(rate);
+    Ok(exact.checked_sub(Decimal256::from_ratio(rounded, 1u8))?)
+}
+
+/// Adds rounding dust kept by the pool to the cumulative dust of the specified asset.
+pub fn accumulate_rounding_dust(
+    storage: &mut dyn Storage,
+    asset_info: &AssetInfo,
+    dust: Decimal256,
+) -> StdResult<()> {
+    if dust.is_zero() {
+        return Ok(());
+    }
+
+    let total_dust = ROUNDING_DUST
+        .may_load(storage, asset_info)?
+        .unwrap_or_default();
+    ROUNDING_DUST.save(storage, asset_info, &total_dust.checked_add(dust)?)
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -935,6 +1939,9 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 ///
+/// * **QueryMsg::ShareValueIn { amount, asset_info }** Returns the value of a specific amount of LP tokens
+/// denominated in one of the pool assets using an [`Asset`] object.
+///
 /// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
 ///
 /// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation  using
@@ -943,16 +1950,34 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::CumulativePrices {}** Returns information about cumulative prices for the assets in the
 /// pool using a [`CumulativePricesResponse`] object.
 ///
+/// * **QueryMsg::Observe { seconds_ago }** Returns the moving average of the pool price observed
+/// `seconds_ago` seconds ago using an [`gridiron::observation::OracleObservation`] object.
+///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified asset that was in the pool
 /// just preceeding the moment of the specified block height creation.
+///
+/// * **QueryMsg::ShareAt { amount, block_height }** Returns the pool assets that an amount of LP tokens
+/// would have redeemed just preceeding the moment of the specified block height creation.
+///
+/// * **QueryMsg::RoundingDust {}** Returns the rounding dust accumulated by each pool asset
+/// in a vector that contains objects of type [`RoundingDust`].
+///
+/// * **QueryMsg::VersionedPair {}**, **QueryMsg::VersionedPool {}**, **QueryMsg::VersionedConfig {}**
+/// and **QueryMsg::VersionedSimulation { .. }** Answer the `pair`, `pool`, `config` and `simulation`
+/// queries with the response wrapped into a [`gridiron::versioned::Versioned`] envelope.
+///
+/// * **QueryMsg::SupportsInterface { interface }** Returns whether the pair implements the interface.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Pair {} => to_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Pool {} => to_binary(&query_pool(deps)?),
         QueryMsg::Share { amount } => to_binary(&query_share(deps, amount)?),
+        QueryMsg::ShareValueIn { amount, asset_info } => {
+            to_binary(&query_share_value_in(deps, amount, asset_info)?)
+        }
         QueryMsg::Simulation { offer_asset, .. } => {
             to_binary(&query_simulation(deps, offer_asset)?)
         }
@@ -960,11 +1985,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_reverse_simulation(deps, ask_asset)?)
         }
         QueryMsg::CumulativePrices {} => to_binary(&query_cumulative_prices(deps, env)?),
+        QueryMsg::Observe { seconds_ago } => {
+            to_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
+        }
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::AssetBalanceAt {
             asset_info,
             block_height,
         } => to_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::ShareAt {
+            amount,
+            block_height,
+        } => to_binary(&query_share_at(deps, amount, block_height)?),
+        QueryMsg::RoundingDust {} => to_binary(&query_rounding_dust(deps)?),
+        msg @ (QueryMsg::VersionedPair {}
+        | QueryMsg::VersionedPool {}
+        | QueryMsg::VersionedConfig {}
+        | QueryMsg::VersionedSimulation { .. }) => msg.query_versioned(|msg| query(deps, env, msg)),
+        QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
@@ -983,6 +2021,23 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     Ok(resp)
 }
 
+/// Returns the rounding dust accumulated by each pool asset.
+pub fn query_rounding_dust(deps: Deps) -> StdResult<Vec<RoundingDust>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    config
+        .pair_info
+        .asset_infos
+        .into_iter()
+        .map(|info| {
+            let amount = ROUNDING_DUST
+                .may_load(deps.storage, &info)?
+                .unwrap_or_default();
+            Ok(RoundingDust { info, amount })
+        })
+        .collect()
+}
+
 /// Returns the amount of assets that could be withdrawn from the pool using a specific amount of LP tokens.
 /// The result is returned in a vector that contains objects of type [`Asset`].
 ///
@@ -995,6 +2050,45 @@ pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     Ok(refund_assets)
 }
 
+/// Returns the value of a specific amount of LP tokens denominated in one of the pool assets.
+/// The other asset is priced using the current pool reserves.
+///
+/// * **amount** is the amount of LP tokens to value.
+///
+/// * **asset_info** is the pool asset in which the value is denominated.
+pub fn query_share_value_in(
+    deps: Deps,
+    amount: Uint128,
+    asset_info: AssetInfo,
+) -> StdResult<Asset> {
+    let config = CONFIG.load(deps.storage)?;
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+
+    let (target_ind, other_ind) = if asset_info.equal(&pools[0].info) {
+        (0, 1)
+    } else if asset_info.equal(&pools[1].info) {
+        (1, 0)
+    } else {
+        return Err(StdError::generic_err(
+            "Given asset does not belong in the pair",
+        ));
+    };
+
+    let share = get_share_in_assets(&pools, amount, total_share);
+    let other_value = if pools[other_ind].amount.is_zero() {
+        Uint128::zero()
+    } else {
+        share[other_ind]
+            .amount
+            .multiply_ratio(pools[target_ind].amount, pools[other_ind].amount)
+    };
+
+    Ok(Asset {
+        info: asset_info,
+        amount: share[target_ind].amount.checked_add(other_value)?,
+    })
+}
+
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
 ///
 /// * **offer_asset** is the asset to swap as well as an amount of the said asset.
@@ -1020,10 +2114,10 @@ pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationR
     }
 
     // Get fee info from the factory contract
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         config.factory_addr,
-        config.pair_info.pair_type,
+        &config.pair_info.contract_addr,
     )?;
 
     let (return_amount, spread_amount, commission_amount) = compute_swap(
@@ -1069,10 +2163,10 @@ pub fn query_reverse_simulation(
     }
 
     // Get fee info from factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         config.factory_addr,
-        config.pair_info.pair_type,
+        &config.pair_info.contract_addr,
     )?;
 
     let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
@@ -1131,15 +2225,24 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+    let min_trade_sizes = query_min_trade_sizes(deps, &config)?;
 
     Ok(ConfigResponse {
         block_time_last: config.block_time_last,
         params: Some(to_binary(&XYKPoolConfig {
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            max_price_move_per_block: PRICE_MOVE_LIMIT.may_load(deps.storage)?,
+            donations_enabled: DONATIONS_ENABLED
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
         })?),
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
+        creator: config.pair_info.creator,
+        created_at: config.pair_info.created_at,
+        min_trade_sizes,
+        maintenance_window: MAINTENANCE_WINDOW.may_load(deps.storage)?,
     })
 }
 
@@ -1154,7 +2257,37 @@ pub fn query_asset_balances_at(
     BALANCES.may_load_at_height(deps.storage, &asset_info, block_height.u64())
 }
 
-/// Returns the result of a swap.
+/// Returns the amount of assets that could be withdrawn using a specific amount of LP tokens
+/// just preceeding the moment of the specified block height creation.
+/// It will return None (null) if balances were not tracked up to the specified block height
+pub fn query_share_at(
+    deps: Deps,
+    amount: Uint128,
+    block_height: Uint64,
+) -> StdResult<Option<Vec<Asset>>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let total_share = match TOTAL_SHARE.may_load_at_height(deps.storage, block_height.u64())? {
+        Some(total_share) => total_share,
+        None => return Ok(None),
+    };
+
+    let mut pools = vec![];
+    for asset_info in &config.pair_info.asset_infos {
+        match BALANCES.may_load_at_height(deps.storage, asset_info, block_height.u64())? {
+            Some(amount) => pools.push(Asset {
+                info: asset_info.clone(),
+                amount,
+            }),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(get_share_in_assets(&pools, amount, total_share)))
+}
+
+/// Returns the result of a swap. All amounts are rounded in favor of the pool: the return amount
+/// is rounded down and the commission covers the remainder.
 ///
 /// * **offer_pool** total amount of offer assets in the pool.
 ///
@@ -1169,35 +2302,78 @@ pub fn compute_swap(
     offer_amount: Uint128,
     commission_rate: Decimal,
 ) -> StdResult<(Uint128, Uint128, Uint128)> {
+    let (return_amount, spread_amount, commission_amount, _) =
+        compute_swap_with_dust(offer_pool, ask_pool, offer_amount, commission_rate)?;
+
+    Ok((return_amount, spread_amount, commission_amount))
+}
+
+/// Same as [`compute_swap`] but additionally returns the fractional part of the return amount
+/// the pool keeps by rounding it down.
+pub fn compute_swap_with_dust(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    commission_rate: Decimal,
+) -> StdResult<(Uint128, Uint128, Uint128, Decimal256)> {
     // offer => ask
     check_swap_parameters(vec![offer_pool, ask_pool], offer_amount)?;
 
     let offer_pool: Uint256 = offer_pool.into();
     let ask_pool: Uint256 = ask_pool.into();
     let offer_amount: Uint256 = offer_amount.into();
-    let commission_rate = Decimal256::from(commission_rate);
 
-    // ask_amount = (ask_pool - cp / (offer_pool + offer_amount))
-    let cp: Uint256 = offer_pool * ask_pool;
-    let return_amount: Uint256 = (Decimal256::from_ratio(ask_pool, 1u8)
-        - Decimal256::from_ratio(cp, offer_pool + offer_amount))
-        * Uint256::from(1u8);
+    // ask_amount = ask_pool - cp / (offer_pool + offer_amount)
+    //            = ask_pool * offer_amount / (offer_pool + offer_amount)
+    // Uint256::multiply_ratio uses a 512 bit intermediate value so the product can't overflow
+    let return_amount = ask_pool.multiply_ratio(offer_amount, offer_pool + offer_amount);
 
-    // Calculate spread & commission
-    let spread_amount: Uint256 =
+    // Calculate spread
+    let spread_amount =
         (offer_amount * Decimal256::from_ratio(ask_pool, offer_pool)).saturating_sub(return_amount);
-    let commission_amount: Uint256 = return_amount * commission_rate;
 
-    // The commision (minus the part that goes to the Maker contract) will be absorbed by the pool
-    let return_amount: Uint256 = return_amount - commission_amount;
+    // The return amount after fees is calculated exactly and rounded down:
+    // net_return = ask_pool * offer_amount * (1 - commission_rate) / (offer_pool + offer_amount)
+    let one = Decimal::one().atomics();
+    let numerator = Uint512::from(ask_pool)
+        * Uint512::from(offer_amount)
+        * Uint512::from(one.checked_sub(commission_rate.atomics())?);
+    let denominator = Uint512::from(offer_pool + offer_amount) * Uint512::from(one);
+    let net_return_amount = Uint256::try_from(numerator / denominator)?;
+    let rounding_dust = Decimal256::from_ratio(
+        Uint256::try_from(numerator % denominator)?,
+        Uint256::try_from(denominator)?,
+    );
+
+    // The commission is what remains of the return amount and is therefore rounded up.
+    // It (minus the part that goes to the Maker contract) will be absorbed by the pool
+    let commission_amount = return_amount - net_return_amount;
     Ok((
-        return_amount.try_into()?,
+        net_return_amount.try_into()?,
         spread_amount.try_into()?,
         commission_amount.try_into()?,
+        rounding_dust,
     ))
 }
 
-/// Returns an amount of offer assets for a specified amount of ask assets.
+/// Returns `value * numerator / denominator` rounded up.
+fn multiply_ratio_ceil(
+    value: Uint256,
+    numerator: Uint256,
+    denominator: Uint256,
+) -> StdResult<Uint256> {
+    let product = Uint512::from(value) * Uint512::from(numerator);
+    let denominator = Uint512::from(denominator);
+    let mut result = product / denominator;
+    if !(product % denominator).is_zero() {
+        result += Uint512::one();
+    }
+
+    Ok(result.try_into()?)
+}
+
+/// Returns an amount of offer assets for a specified amount of ask assets. The offer amount
+/// and the commission are rounded up in favor of the pool.
 ///
 /// * **offer_pool** total amount of offer assets in the pool.
 ///
@@ -1215,28 +2391,37 @@ pub fn compute_offer_amount(
     // ask => offer
     check_swap_parameters(vec![offer_pool, ask_pool], ask_amount)?;
 
+    let offer_pool: Uint256 = offer_pool.into();
+    let ask_pool: Uint256 = ask_pool.into();
+    let ask_amount: Uint256 = ask_amount.into();
+
+    // before_commission_deduction = ask_amount / (1 - commission_rate)
+    let one = Decimal::one().atomics();
+    let before_commission_deduction = multiply_ratio_ceil(
+        ask_amount,
+        one.into(),
+        one.checked_sub(commission_rate.atomics())?.into(),
+    )?;
+
+    let ask_pool_after = ask_pool.checked_sub(before_commission_deduction)?;
+    if ask_pool_after.is_zero() {
+        return Err(StdError::generic_err(
+            "Ask amount exceeds the available pool liquidity",
+        ));
+    }
+
     // offer_amount = cp / (ask_pool - ask_amount / (1 - commission_rate)) - offer_pool
-    let cp = Uint256::from(offer_pool) * Uint256::from(ask_pool);
-    let one_minus_commission = Decimal256::one() - Decimal256::from(commission_rate);
-    let inv_one_minus_commission = Decimal256::one() / one_minus_commission;
-
-    let offer_amount: Uint128 = cp
-        .multiply_ratio(
-            Uint256::from(1u8),
-            Uint256::from(
-                ask_pool.checked_sub(
-                    (Uint256::from(ask_amount) * inv_one_minus_commission).try_into()?,
-                )?,
-            ),
-        )
-        .checked_sub(offer_pool.into())?
-        .try_into()?;
-
-    let before_commission_deduction = Uint256::from(ask_amount) * inv_one_minus_commission;
-    let spread_amount = (offer_amount * Decimal::from_ratio(ask_pool, offer_pool))
-        .saturating_sub(before_commission_deduction.try_into()?);
-    let commission_amount = before_commission_deduction * Decimal256::from(commission_rate);
-    Ok((offer_amount, spread_amount, commission_amount.try_into()?))
+    let offer_amount =
+        multiply_ratio_ceil(offer_pool, ask_pool, ask_pool_after)?.checked_sub(offer_pool)?;
+
+    let spread_amount = (offer_amount * Decimal256::from_ratio(ask_pool, offer_pool))
+        .saturating_sub(before_commission_deduction);
+    let commission_amount = before_commission_deduction - ask_amount;
+    Ok((
+        offer_amount.try_into()?,
+        spread_amount.try_into()?,
+        commission_amount.try_into()?,
+    ))
 }
 
 /// If `belief_price` and `max_spread` are both specified, we compute a new spread,
@@ -1285,6 +2470,37 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// Ensures the amount received from a swap is not less than `min_receive`.
+///
+/// * **min_receive** minimum amount of ask tokens the trader accepts.
+///
+/// * **return_amount** amount of ask tokens the trader receives from the swap.
+pub fn assert_min_receive(
+    min_receive: Uint128,
+    return_amount: Uint128,
+) -> Result<(), ContractError> {
+    if return_amount < min_receive {
+        return Err(ContractError::MinReceiveAssertion {
+            min_receive,
+            return_amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// Ensures the transaction is not included after `deadline`.
+///
+/// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+pub fn assert_deadline(env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {
+            Err(ContractError::TransactionExpired {})
+        }
+        _ => Ok(()),
+    }
+}
+
 /// This is an internal function that enforces slippage tolerance for swaps.
 ///
 /// * **slippage_tolerance** slippage tolerance to enforce.
@@ -1324,7 +2540,7 @@ pub fn assert_slippage_tolerance(
 
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     use crate::migration;
     let contract_version = get_contract_version(deps.storage)?;
 
@@ -1333,12 +2549,15 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             "1.0.0" | "1.0.1" | "1.1.0" | "1.2.0" => {
                 migration::add_asset_balances_tracking_flag(deps.storage)?;
             }
-            "1.3.0" | "1.3.1" => {}
+            "1.3.0" | "1.3.1" | "1.4.0" | "1.5.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    migration::init_observations(deps.storage)?;
+    migration::seed_total_share(deps.storage, &deps.querier, env.block.height)?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default().add_attributes([
@@ -1377,7 +2596,8 @@ mod tests {
 
         let (return_amount, spread_amount, commission_amount) =
             compute_swap(offer_pool, ask_pool, offer_amount, commission_rate).unwrap();
-        assert_eq!(return_amount, Uint128::from(2u128));
+        // The exact return is slightly below 2 and is rounded down
+        assert_eq!(return_amount, Uint128::from(1u128));
         assert_eq!(spread_amount, Uint128::zero());
         assert_eq!(commission_amount, Uint128::zero());
     }
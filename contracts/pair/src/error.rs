@@ -1,5 +1,12 @@
-use gridiron::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
-use cosmwasm_std::{OverflowError, StdError};
+use gridiron::{
+    asset::{AssetInfoError, MINIMUM_LIQUIDITY_AMOUNT},
+    pair::{
+        ProvideLiquidityError, ReferralError, MAX_BATCH_SWAP_OPERATIONS, MAX_FEE_SHARE_BPS,
+        MAX_MIN_TRADE_FLOOR, MAX_PRICE_MOVE_PER_BLOCK,
+    },
+};
+use cosmwasm_std::{Addr, Decimal, OverflowError, StdError, Uint128};
+use gridiron_circular_buffer::error::BufferError;
 use thiserror::Error;
 
 /// This enum describes pair contract errors
@@ -8,6 +15,18 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    AssetInfo(#[from] AssetInfoError),
+
+    #[error("{0}")]
+    ProvideLiquidity(#[from] ProvideLiquidityError),
+
+    #[error("{0}")]
+    Referral(#[from] ReferralError),
+
+    #[error("{0}")]
+    CircularBuffer(#[from] BufferError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -23,6 +42,36 @@ pub enum ContractError {
     #[error("Operation exceeds max spread limit")]
     MaxSpreadAssertion {},
 
+    #[error("Swap return {return_amount} is less than min_receive {min_receive}")]
+    MinReceiveAssertion {
+        min_receive: Uint128,
+        return_amount: Uint128,
+    },
+
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
+    #[error("Swap amount {amount} is below the min trade size {min}")]
+    BelowMinTradeSize { amount: Uint128, min: Uint128 },
+
+    #[error(
+        "Min trade floor must be greater than zero and at most {}",
+        MAX_MIN_TRADE_FLOOR
+    )]
+    MinTradeFloorOutOfBounds {},
+
+    #[error("Address {address} is not approved by the pair allowlist")]
+    AddressNotAllowed { address: Addr },
+
+    #[error("Maintenance window must end after it starts and after the current block time")]
+    InvalidMaintenanceWindow {},
+
+    #[error("Swaps are halted for maintenance until {end}")]
+    SwapsHaltedForMaintenance { end: u64 },
+
+    #[error("Liquidity provision and withdrawals are halted for maintenance until {end}")]
+    LiquidityHaltedForMaintenance { end: u64 },
+
     #[error("Provided spread amount exceeds allowed limit")]
     AllowedSpreadAssertion {},
 
@@ -38,9 +87,6 @@ pub enum ContractError {
     #[error("Pair type mismatch. Check factory pair configs")]
     PairTypeMismatch {},
 
-    #[error("Generator address is not set in factory. Cannot auto-stake")]
-    AutoStakeError {},
-
     #[error("Initial liquidity must be more than {}", MINIMUM_LIQUIDITY_AMOUNT)]
     MinimumLiquidityAmountError {},
 
@@ -58,6 +104,45 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error(
+        "Batch swap must contain from 1 to {} operations",
+        MAX_BATCH_SWAP_OPERATIONS
+    )]
+    InvalidBatchSwapLength {},
+
+    #[error("Batch swap operation {index} must be a GRID swap of the previous ask asset")]
+    InvalidBatchSwapOperation { index: usize },
+
+    #[error("Another batch swap is in progress")]
+    BatchSwapInProgress {},
+
+    #[error("Combined fee {combined_fee_bps} bps exceeds the cap of {max_combined_fee_bps} bps")]
+    CombinedFeeExceedsCap {
+        combined_fee_bps: u32,
+        max_combined_fee_bps: u16,
+    },
+
+    #[error(
+        "Max price move per block must be greater than zero and at most {}",
+        MAX_PRICE_MOVE_PER_BLOCK
+    )]
+    PriceMoveLimitOutOfBounds {},
+
+    #[error("Swap moves the pool price by more than {limit} within the block")]
+    PriceMoveLimitExceeded { limit: Decimal },
+
+    #[error("Not enough LP tokens. You need {required} LP tokens.")]
+    NotEnoughLpTokens { required: Uint128 },
+
+    #[error("Withdraw amounts exceed the pool reserves")]
+    WithdrawExceedsReserves {},
+
+    #[error("Donations are disabled in this pair")]
+    DonationsDisabled {},
+
+    #[error("The pool has no liquidity providers to donate to")]
+    NoLiquidityToDonateTo {},
 }
 
 impl From<OverflowError> for ContractError {
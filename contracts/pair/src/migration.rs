@@ -1,11 +1,15 @@
 use gridiron::asset::PairInfo;
+use gridiron::observation::OBSERVATIONS_SIZE;
+use gridiron_circular_buffer::BufferManager;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Storage, Uint128};
+use cosmwasm_std::{Addr, QuerierWrapper, Storage, Uint128};
 use cw_storage_plus::Item;
 
+use gridiron::querier::query_supply;
+
 use crate::{
     error::ContractError,
-    state::{Config, CONFIG},
+    state::{Config, CONFIG, OBSERVATIONS, TOTAL_SHARE},
 };
 
 pub(crate) fn add_asset_balances_tracking_flag(
@@ -45,3 +49,27 @@ pub(crate) fn add_asset_balances_tracking_flag(
 
     Ok(())
 }
+
+/// Initializes the price observations buffer of pairs created before it was introduced.
+pub(crate) fn init_observations(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if OBSERVATIONS.state().may_load(storage)?.is_none() {
+        BufferManager::init(storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+    }
+
+    Ok(())
+}
+
+/// Seeds the LP supply snapshot of pairs that tracked asset balances before it was introduced.
+pub(crate) fn seed_total_share(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    block_height: u64,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    if config.track_asset_balances && TOTAL_SHARE.may_load(storage)?.is_none() {
+        let total_share = query_supply(querier, &config.pair_info.liquidity_token)?;
+        TOTAL_SHARE.save(storage, &total_share, block_height)?;
+    }
+
+    Ok(())
+}
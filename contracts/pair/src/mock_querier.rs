@@ -6,7 +6,7 @@ use cosmwasm_std::{
 use std::collections::HashMap;
 
 use gridiron::factory::FeeInfoResponse;
-use gridiron::factory::QueryMsg::FeeInfo;
+use gridiron::factory::QueryMsg::{FeeInfo, PairFeeInfo};
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies.
@@ -81,11 +81,14 @@ impl WasmMockQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 if contract_addr == "factory" {
                     match from_binary(&msg).unwrap() {
-                        FeeInfo { .. } => SystemResult::Ok(
+                        FeeInfo { .. } | PairFeeInfo { .. } => SystemResult::Ok(
                             to_binary(&FeeInfoResponse {
                                 fee_address: Some(Addr::unchecked("fee_address")),
                                 total_fee_bps: 30,
                                 maker_fee_bps: 1660,
+                                points_contract: None,
+                                fee_discounts: None,
+                                max_referral_bps: None,
                             })
                             .into(),
                         ),
@@ -1,10 +1,13 @@
 use gridiron::{
     asset::{AssetInfo, PairInfo},
-    pair::FeeShareConfig,
+    observation::Observation,
+    pair::{FeeShareConfig, MaintenanceWindow, PAIR_CONFIG_KEY},
+    router::SwapOperation,
 };
+use gridiron_circular_buffer::CircularBuffer;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, SnapshotMap};
+use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap};
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -26,7 +29,35 @@ pub struct Config {
 }
 
 /// Stores the config struct at the given key
-pub const CONFIG: Item<Config> = Item::new("config");
+pub const CONFIG: Item<Config> = Item::new(PAIR_CONFIG_KEY);
+
+/// Circular buffer to store pool price observations
+pub const OBSERVATIONS: CircularBuffer<Observation> =
+    CircularBuffer::new("observations_state", "observations_buffer");
+
+/// Stores the floor (in decimal form) below which swaps are rejected
+pub const MIN_TRADE_FLOOR: Item<Decimal256> = Item::new("min_trade_floor");
+
+/// Stores the scheduled maintenance window
+pub const MAINTENANCE_WINDOW: Item<MaintenanceWindow> = Item::new("maintenance_window");
+
+/// Stores the max relative move of the pool price within a single block
+pub const PRICE_MOVE_LIMIT: Item<Decimal> = Item::new("price_move_limit");
+
+/// Stores whether donations to the pool reserves are enabled
+pub const DONATIONS_ENABLED: Item<bool> = Item::new("donations_enabled");
+
+/// This structure stores the pool price before the first swap of a block.
+#[cw_serde]
+pub struct BlockPrice {
+    /// The block height
+    pub height: u64,
+    /// The price of asset 0 in asset 1 before the first swap of the block
+    pub price: Decimal256,
+}
+
+/// Stores the price the price move limit is measured from
+pub const BLOCK_PRICE: Item<BlockPrice> = Item::new("block_price");
 
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
@@ -35,3 +66,36 @@ pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances_change",
     cw_storage_plus::Strategy::EveryBlock,
 );
+
+/// This structure stores the state of a batch swap while one of its hops is executed.
+#[cw_serde]
+pub struct BatchSwapContext {
+    /// The operations which follow the executed hop
+    pub operations: Vec<SwapOperation>,
+    /// The pair which executes the hop
+    pub pair: Addr,
+    /// The asset returned by the hop
+    pub ask_asset_info: AssetInfo,
+    /// The minimum amount of the last ask asset to receive
+    pub minimum_receive: Option<Uint128>,
+    /// The receiver of the last ask asset
+    pub receiver: Addr,
+}
+
+/// Stores the batch swap in progress
+pub const BATCH_SWAP: Item<BatchSwapContext> = Item::new("batch_swap");
+
+/// Caches the xGRID token of the staking contract which fee discounts are based on as
+/// (staking contract, xGRID token)
+pub const XGRID_TOKEN: Item<(Addr, Addr)> = Item::new("xgrid_token");
+
+/// Stores the cumulative fractional amounts each pool asset kept by rounding in favor of the pool
+pub const ROUNDING_DUST: Map<&AssetInfo, Decimal256> = Map::new("rounding_dust");
+
+/// Stores the total amount of LP tokens alongside [`BALANCES`] to value shares at any block height
+pub const TOTAL_SHARE: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_share",
+    "total_share_check",
+    "total_share_change",
+    cw_storage_plus::Strategy::EveryBlock,
+);
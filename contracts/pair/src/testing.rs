@@ -1,7 +1,8 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     attr, to_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, DepsMut, Env, Reply,
-    ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
+    ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, Uint512,
+    WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use proptest::prelude::*;
@@ -17,8 +18,8 @@ use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
 use crate::contract::compute_offer_amount;
 use crate::contract::reply;
 use crate::contract::{
-    accumulate_prices, assert_max_spread, compute_swap, execute, instantiate, query_pool,
-    query_reverse_simulation, query_share, query_simulation,
+    accumulate_prices, assert_max_spread, compute_swap, execute, get_share_in_assets, instantiate,
+    query_pool, query_reverse_simulation, query_share, query_simulation,
 };
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
@@ -67,6 +68,7 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         factory_addr: String::from("factory"),
+        creator: None,
         asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
@@ -160,6 +162,7 @@ fn provide_liquidity() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -190,6 +193,8 @@ fn provide_liquidity() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env();
@@ -305,6 +310,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(50)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -377,6 +384,8 @@ fn provide_liquidity() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env();
@@ -440,6 +449,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -481,6 +492,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -522,6 +535,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -562,6 +577,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -592,6 +609,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
     let info = mock_info(
         "addr0001",
@@ -621,6 +640,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(51)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
     let info = mock_info(
         "addr0001",
@@ -663,6 +684,8 @@ fn withdraw_liquidity() {
         token_code_id: 10u64,
 
         factory_addr: String::from("factory"),
+
+        creator: None,
         init_params: None,
     };
 
@@ -677,7 +700,11 @@ fn withdraw_liquidity() {
     // Withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: String::from("addr0000"),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::new(100u128),
     });
 
@@ -784,6 +811,7 @@ fn try_native_to_token() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -806,7 +834,10 @@ fn try_native_to_token() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: Some(Decimal::percent(50)),
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info(
@@ -827,7 +858,9 @@ fn try_native_to_token() {
     // 47619047 = 1500000000 * (20000000000 / 30000000000) - 952380952
     let expected_spread_amount = Uint128::new(47619047u128);
 
-    let expected_commission_amount = expected_ret_amount.multiply_ratio(3u128, 1000u128); // 0.3%
+    // 2857143 = 952380952 - 949523809, where the return after the 0.3% fee is rounded down
+    // in favor of the pool: 949523809 = 952380952,3809524 * 0.997
+    let expected_commission_amount = Uint128::new(2_857_143u128);
     let expected_maker_fee_amount = expected_commission_amount.multiply_ratio(166u128, 1000u128); // 0.166
 
     let expected_return_amount = expected_ret_amount
@@ -991,6 +1024,7 @@ fn try_token_to_native() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -1013,7 +1047,10 @@ fn try_token_to_native() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: None,
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info("addr0000", &[]);
@@ -1028,7 +1065,10 @@ fn try_token_to_native() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: Some(Decimal::percent(50)),
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -1045,7 +1085,9 @@ fn try_token_to_native() {
     // 47619047 = 1500000000 * (20000000000 / 30000000000) - 952380952,3809524
     let expected_spread_amount = Uint128::new(47619047u128);
 
-    let expected_commission_amount = expected_ret_amount.multiply_ratio(3u128, 1000u128); // 0.3%
+    // 2857143 = 952380952 - 949523809, where the return after the 0.3% fee is rounded down
+    // in favor of the pool: 949523809 = 952380952,3809524 * 0.997
+    let expected_commission_amount = Uint128::new(2_857_143u128);
     let expected_maker_fee_amount = expected_commission_amount.multiply_ratio(166u128, 1000u128);
     let expected_return_amount = expected_ret_amount
         .checked_sub(expected_commission_amount)
@@ -1151,7 +1193,10 @@ fn try_token_to_native() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -1241,6 +1286,7 @@ fn test_query_pool() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -1306,6 +1352,7 @@ fn test_query_share() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -1414,6 +1461,9 @@ fn test_accumulate_prices() {
                     contract_addr: Addr::unchecked("pair"),
                     liquidity_token: Addr::unchecked("lp_token"),
                     pair_type: PairType::Xyk {}, // Implemented in mock querier
+                    creator: None,
+                    created_at: None,
+                    allowlist: None,
                 },
                 factory_addr: Addr::unchecked("factory"),
                 block_time_last: case.block_time_last,
@@ -1491,6 +1541,134 @@ proptest! {
     }
 }
 
+/// Converts a reference value to [`Uint128`] or returns None if it doesn't fit.
+fn to_uint128(value: Uint512) -> Option<Uint128> {
+    Uint128::try_from(value).ok()
+}
+
+/// Reference spread calculation: the price is truncated to 18 decimals as in the contract.
+fn reference_spread(
+    amount: Uint512,
+    ask_pool: Uint512,
+    offer_pool: Uint512,
+    received: Uint512,
+) -> Uint512 {
+    let precision = Uint512::from(1_000_000_000_000_000_000u128);
+    let price = ask_pool * precision / offer_pool;
+    (amount * price / precision).saturating_sub(received)
+}
+
+proptest! {
+    #[test]
+    fn compute_swap_matches_reference(
+        offer_pool in 1_000_000_000_000_000_000..u128::MAX,
+        ask_pool in 1_000_000_000_000_000_000..u128::MAX,
+        offer_amount in 1..u128::MAX,
+        commission_bps in 0..10_000u128,
+    ) {
+        let result = compute_swap(
+            Uint128::new(offer_pool),
+            Uint128::new(ask_pool),
+            Uint128::new(offer_amount),
+            Decimal::from_ratio(commission_bps, 10_000u128),
+        );
+
+        // Reference values calculated with exact 512 bit integer math
+        let (offer_pool, ask_pool, offer_amount) = (
+            Uint512::from(offer_pool),
+            Uint512::from(ask_pool),
+            Uint512::from(offer_amount),
+        );
+        let return_amount = ask_pool * offer_amount / (offer_pool + offer_amount);
+        let spread_amount = reference_spread(offer_amount, ask_pool, offer_pool, return_amount);
+        // The return after fees is rounded down, the commission covers the remainder
+        let net_return_amount = ask_pool
+            * offer_amount
+            * Uint512::from(10_000u128 - commission_bps)
+            / ((offer_pool + offer_amount) * Uint512::from(10_000u128));
+        let commission_amount = return_amount - net_return_amount;
+
+        match to_uint128(spread_amount) {
+            Some(spread_amount) => prop_assert_eq!(
+                result.unwrap(),
+                (
+                    to_uint128(net_return_amount).unwrap(),
+                    spread_amount,
+                    to_uint128(commission_amount).unwrap(),
+                )
+            ),
+            // The spread doesn't fit into Uint128 for extremely unbalanced pools
+            None => prop_assert!(result.is_err()),
+        }
+    }
+
+    #[test]
+    fn compute_offer_amount_matches_reference(
+        offer_pool in 1_000_000_000_000_000_000..u128::MAX,
+        ask_pool in 1_000_000_000_000_000_000..u128::MAX,
+        ask_share_bps in 1..9_999u128,
+    ) {
+        let ask_amount = Uint128::new(ask_pool).multiply_ratio(ask_share_bps, 10_000u128);
+        let result = compute_offer_amount(
+            Uint128::new(offer_pool),
+            Uint128::new(ask_pool),
+            ask_amount,
+            Decimal::zero(),
+        );
+
+        let (offer_pool, ask_pool, ask_amount) = (
+            Uint512::from(offer_pool),
+            Uint512::from(ask_pool),
+            Uint512::from(ask_amount.u128()),
+        );
+        // The offer amount is rounded up
+        let ask_pool_after = ask_pool - ask_amount;
+        let offer_amount =
+            (offer_pool * ask_pool + ask_pool_after - Uint512::one()) / ask_pool_after - offer_pool;
+        let spread_amount = reference_spread(offer_amount, ask_pool, offer_pool, ask_amount);
+
+        match (to_uint128(offer_amount), to_uint128(spread_amount)) {
+            (Some(offer_amount), Some(spread_amount)) => prop_assert_eq!(
+                result.unwrap(),
+                (offer_amount, spread_amount, Uint128::zero())
+            ),
+            _ => prop_assert!(result.is_err()),
+        }
+    }
+
+    #[test]
+    fn get_share_in_assets_matches_reference(
+        pool0 in 1_000_000_000_000_000_000..u128::MAX,
+        pool1 in 1_000_000_000_000_000_000..u128::MAX,
+        total_share in 1_000..u128::MAX,
+        share_bps in 0..=10_000u128,
+    ) {
+        let amount = Uint128::new(total_share).multiply_ratio(share_bps, 10_000u128);
+        let pools = [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::new(pool0),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::new(pool1),
+            },
+        ];
+
+        let assets = get_share_in_assets(&pools, amount, Uint128::new(total_share));
+
+        for (asset, pool) in assets.iter().zip([pool0, pool1]) {
+            let expected = Uint512::from(pool) * Uint512::from(amount.u128())
+                / Uint512::from(total_share);
+            prop_assert_eq!(asset.amount, to_uint128(expected).unwrap());
+        }
+    }
+}
+
 #[test]
 fn ensure_useful_error_messages_are_given_on_swaps() {
     const OFFER: Uint128 = Uint128::new(1_000_000_000000);
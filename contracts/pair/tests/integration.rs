@@ -3,21 +3,39 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use gridiron::asset::{native_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo};
+use gridiron::asset::{
+    native_asset_info, token_asset, token_asset_info, Asset, AssetInfo, AssetInfoExt,
+    PairCreatedAt, PairInfo,
+};
 use gridiron::factory::{
-    ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType,
-    QueryMsg as FactoryQueryMsg,
+    ExecuteMsg as FactoryExecuteMsg, FeeDiscountTier, InstantiateMsg as FactoryInstantiateMsg,
+    PairConfig, PairType, QueryMsg as FactoryQueryMsg,
 };
+use gridiron::observation::OracleObservation;
 use gridiron::pair::{
     ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, FeeShareConfig,
-    InstantiateMsg, PoolResponse, QueryMsg, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
+    InstantiateMsg, MaintenanceMode, MaintenanceWindow, MigrateMsg, MinTradeSize, PoolResponse,
+    ProvideLiquidityError, QueryMsg, Referral, ReferralError, RoundingDust, SimulationResponse,
+    XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams, INTERFACE_BATCH_SWAP, INTERFACE_DEADLINE,
+    INTERFACE_FEE_DISCOUNTS, INTERFACE_MIN_LP_TO_RECEIVE, INTERFACE_OBSERVATIONS,
+    INTERFACE_REANCHOR, INTERFACE_REFERRAL, INTERFACE_VERSIONED_QUERIES, MAX_BATCH_SWAP_OPERATIONS,
     MAX_FEE_SHARE_BPS, TWAP_PRECISION,
 };
+use gridiron::querier::{query_pair_config_raw, query_pair_info_raw, query_pair_reserves_raw};
+use gridiron::router::SwapOperation;
 use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
+use gridiron::versioned::{Versioned, QUERY_RESPONSE_VERSION};
 use gridiron_mocks::cw_multi_test::{App, BasicApp, ContractWrapper, Executor};
-use gridiron_mocks::{gridiron_address, MockGeneratorBuilder, MockXykPairBuilder};
+use gridiron_mocks::{
+    gridiron_address, MockGeneratorBuilder, MockStakingBuilder, MockXykPairBuilder,
+};
 use gridiron_pair::error::ContractError;
-use cosmwasm_std::{attr, to_binary, Addr, Coin, Decimal, Uint128, Uint64};
+use gridiron_pair::state::{Config, OBSERVATIONS, TOTAL_SHARE};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Coin, Decimal, Decimal256, DepsMut, Empty, Env, Response,
+    StdResult, Uint128, Uint64,
+};
+use cw2::set_contract_version;
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 
 const OWNER: &str = "owner";
@@ -65,10 +83,19 @@ fn store_factory_code(app: &mut App) -> u64 {
     app.store_code(factory_contract)
 }
 
-fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
-    let token_contract_code_id = store_token_code(&mut router);
+fn instantiate_pair(router: &mut App, owner: &Addr) -> Addr {
+    let pair_contract_code_id = store_pair_code(router);
 
-    let pair_contract_code_id = store_pair_code(&mut router);
+    instantiate_pair_from_code(router, owner, pair_contract_code_id, None)
+}
+
+fn instantiate_pair_from_code(
+    mut router: &mut App,
+    owner: &Addr,
+    pair_contract_code_id: u64,
+    admin: Option<String>,
+) -> Addr {
+    let token_contract_code_id = store_token_code(&mut router);
     let factory_code_id = store_factory_code(&mut router);
 
     let init_msg = FactoryInstantiateMsg {
@@ -110,6 +137,7 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: None,
     };
 
@@ -120,7 +148,7 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
             &msg,
             &[],
             String::from("PAIR"),
-            None,
+            admin,
         )
         .unwrap();
 
@@ -290,7 +318,11 @@ fn test_provide_and_withdraw_liquidity() {
     let msg = Cw20ExecuteMsg::Send {
         contract: pair_instance.to_string(),
         amount: Uint128::from(50u8),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
     };
     // Try to send withdraw liquidity with FOO token
     let err = router
@@ -316,7 +348,10 @@ fn test_provide_and_withdraw_liquidity() {
                 ask_asset_info: None,
                 belief_price: None,
                 max_spread: None,
+                min_receive: None,
                 to: None,
+                deadline: None,
+                referral: None,
             },
             &[Coin {
                 denom: "cny".to_string(),
@@ -342,11 +377,17 @@ fn test_provide_and_withdraw_liquidity() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
                 })
                 .unwrap()
             ),
             owner,
-            factory_addr: config.factory_addr
+            factory_addr: config.factory_addr,
+            creator: config.creator,
+            created_at: config.created_at,
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         }
     )
 }
@@ -375,6 +416,8 @@ fn provide_liquidity_msg(
         slippage_tolerance: Option::from(slippage_tolerance),
         auto_stake: None,
         receiver,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let coins = [
@@ -392,198 +435,1076 @@ fn provide_liquidity_msg(
 }
 
 #[test]
-fn test_compatibility_of_tokens_with_different_precision() {
-    let owner = Addr::unchecked(OWNER);
-
-    let mut app = mock_app(
+fn swap_with_min_receive() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
         owner.clone(),
         vec![
             Coin {
                 denom: "uusd".to_string(),
-                amount: Uint128::new(100_000_000_000000u128),
+                amount: Uint128::new(100_000_000_000u128),
             },
             Coin {
                 denom: "uluna".to_string(),
-                amount: Uint128::new(100_000_000_000000u128),
+                amount: Uint128::new(100_000_000_000u128),
             },
         ],
     );
 
-    let token_code_id = store_token_code(&mut app);
-
-    let x_amount = Uint128::new(1000000_00000);
-    let y_amount = Uint128::new(1000000_0000000);
-    let x_offer = Uint128::new(1_00000);
-    let y_expected_return = Uint128::new(1_0000000);
-
-    let token_name = "Xtoken";
+    let pair_instance = instantiate_pair(&mut router, &owner);
 
-    let init_msg = TokenInstantiateMsg {
-        name: token_name.to_string(),
-        symbol: token_name.to_string(),
-        decimals: 5,
-        initial_balances: vec![Cw20Coin {
-            address: OWNER.to_string(),
-            amount: x_amount + x_offer,
-        }],
-        mint: Some(MinterResponse {
-            minter: String::from(OWNER),
-            cap: None,
-        }),
-        marketing: None,
-    };
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
 
-    let token_x_instance = app
-        .instantiate_contract(
-            token_code_id,
-            owner.clone(),
-            &init_msg,
-            &[],
-            token_name,
-            None,
+    let offer_asset = native_asset_info("uusd".to_string()).with_balance(10_000_000u128);
+    let sim_res: SimulationResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
         )
         .unwrap();
 
-    let token_name = "Ytoken";
-
-    let init_msg = TokenInstantiateMsg {
-        name: token_name.to_string(),
-        symbol: token_name.to_string(),
-        decimals: 7,
-        initial_balances: vec![Cw20Coin {
-            address: OWNER.to_string(),
-            amount: y_amount,
-        }],
-        mint: Some(MinterResponse {
-            minter: String::from(OWNER),
-            cap: None,
-        }),
-        marketing: None,
+    let swap_msg = |min_receive: Uint128| ExecuteMsg::Swap {
+        offer_asset: offer_asset.clone(),
+        ask_asset_info: None,
+        belief_price: None,
+        // This spread limit would reject the swap, but min_receive takes precedence
+        max_spread: Some(Decimal::permille(1)),
+        min_receive: Some(min_receive),
+        to: None,
+        deadline: None,
+        referral: None,
     };
+    let funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: offer_asset.amount,
+    }];
 
-    let token_y_instance = app
-        .instantiate_contract(
-            token_code_id,
+    let err = router
+        .execute_contract(
             owner.clone(),
-            &init_msg,
-            &[],
-            token_name,
-            None,
+            pair_instance.clone(),
+            &swap_msg(sim_res.return_amount + Uint128::one()),
+            &funds,
         )
-        .unwrap();
-
-    let pair_code_id = store_pair_code(&mut app);
-    let factory_code_id = store_factory_code(&mut app);
-
-    let init_msg = FactoryInstantiateMsg {
-        fee_address: None,
-        pair_configs: vec![PairConfig {
-            code_id: pair_code_id,
-            maker_fee_bps: 0,
-            pair_type: PairType::Xyk {},
-            total_fee_bps: 0,
-            is_disabled: false,
-            is_generator_disabled: false,
-        }],
-        token_code_id,
-        generator_address: Some(String::from("generator")),
-        owner: owner.to_string(),
-        whitelist_code_id: 234u64,
-        coin_registry_address: "coin_registry".to_string(),
-    };
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MinReceiveAssertion {
+            min_receive: sim_res.return_amount + Uint128::one(),
+            return_amount: sim_res.return_amount,
+        }
+    );
 
-    let factory_instance = app
-        .instantiate_contract(
-            factory_code_id,
+    let uluna_before = router.wrap().query_balance(&owner, "uluna").unwrap().amount;
+    router
+        .execute_contract(
             owner.clone(),
-            &init_msg,
-            &[],
-            "FACTORY",
-            None,
+            pair_instance.clone(),
+            &swap_msg(sim_res.return_amount),
+            &funds,
         )
         .unwrap();
+    let uluna_after = router.wrap().query_balance(&owner, "uluna").unwrap().amount;
+    assert_eq!(uluna_after - uluna_before, sim_res.return_amount);
+}
 
-    let msg = FactoryExecuteMsg::CreatePair {
-        asset_infos: vec![
-            AssetInfo::Token {
-                contract_addr: token_x_instance.clone(),
+#[test]
+fn swap_with_referral() {
+    let owner = Addr::unchecked("owner");
+    let frontend = Addr::unchecked("frontend");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
             },
-            AssetInfo::Token {
-                contract_addr: token_y_instance.clone(),
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
             },
         ],
-        pair_type: PairType::Xyk {},
-        init_params: None,
-    };
+    );
 
-    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
-        .unwrap();
+    let pair_instance = instantiate_pair(&mut router, &owner);
+    let factory = Addr::unchecked("contract0");
 
-    let msg = FactoryQueryMsg::Pair {
-        asset_infos: vec![
-            AssetInfo::Token {
-                contract_addr: token_x_instance.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: token_y_instance.clone(),
-            },
-        ],
-    };
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
 
-    let res: PairInfo = app
+    let offer_asset = native_asset_info("uusd".to_string()).with_balance(10_000_000u128);
+    let sim_res: SimulationResponse = router
         .wrap()
-        .query_wasm_smart(&factory_instance, &msg)
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
+        )
         .unwrap();
 
-    let pair_instance = res.contract_addr;
-
-    let msg = Cw20ExecuteMsg::IncreaseAllowance {
-        spender: pair_instance.to_string(),
-        expires: None,
-        amount: x_amount + x_offer,
+    let swap_msg = |bps: u16| ExecuteMsg::Swap {
+        offer_asset: offer_asset.clone(),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: Some(Referral {
+            address: frontend.to_string(),
+            bps,
+        }),
     };
+    let funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: offer_asset.amount,
+    }];
 
-    app.execute_contract(owner.clone(), token_x_instance.clone(), &msg, &[])
-        .unwrap();
+    // Referral fees are disabled until the factory sets a cap
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg(50), &funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Referral(ReferralError::ReferralDisabled {})
+    );
 
-    let msg = Cw20ExecuteMsg::IncreaseAllowance {
-        spender: pair_instance.to_string(),
-        expires: None,
-        amount: y_amount,
-    };
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            factory.clone(),
+            &FactoryExecuteMsg::SetMaxReferralBps {
+                max_referral_bps: Some(100),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
 
-    app.execute_contract(owner.clone(), token_y_instance.clone(), &msg, &[])
+    router
+        .execute_contract(
+            owner.clone(),
+            factory.clone(),
+            &FactoryExecuteMsg::SetMaxReferralBps {
+                max_referral_bps: Some(100),
+            },
+            &[],
+        )
         .unwrap();
 
-    let user = Addr::unchecked("user");
-
-    let swap_msg = Cw20ExecuteMsg::Send {
-        contract: pair_instance.to_string(),
-        msg: to_binary(&Cw20HookMsg::Swap {
-            ask_asset_info: None,
-            belief_price: None,
-            max_spread: None,
-            to: Some(user.to_string()),
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg(150), &funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Referral(ReferralError::ReferralFeeTooHigh {
+            bps: 150,
+            max_referral_bps: 100,
         })
-        .unwrap(),
-        amount: x_offer,
-    };
+    );
 
-    let err = app
-        .execute_contract(owner.clone(), token_x_instance.clone(), &swap_msg, &[])
-        .unwrap_err();
+    let uluna_before = router.wrap().query_balance(&owner, "uluna").unwrap().amount;
+    let res = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg(50), &funds)
+        .unwrap();
+
+    let referral_amount = sim_res.return_amount.multiply_ratio(50u16, 10000u16);
+    let referral_balance = router.wrap().query_balance(&frontend, "uluna").unwrap();
+    assert_eq!(referral_balance.amount, referral_amount);
+    let uluna_after = router.wrap().query_balance(&owner, "uluna").unwrap().amount;
     assert_eq!(
-        "Generic error: One of the pools is empty",
-        err.root_cause().to_string()
+        uluna_after - uluna_before,
+        sim_res.return_amount - referral_amount
     );
+    let referral_attr = attr("referral_amount", referral_amount);
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.attributes.contains(&referral_attr)));
+}
 
-    let msg = ExecuteMsg::ProvideLiquidity {
-        assets: vec![
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: token_x_instance.clone(),
-                },
-                amount: x_amount,
+#[test]
+fn maintenance_window() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+    let lp_token = router
+        .wrap()
+        .query_wasm_smart::<PairInfo>(&pair_instance, &QueryMsg::Pair {})
+        .unwrap()
+        .liquidity_token;
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let schedule = |window: Option<MaintenanceWindow>| ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::ScheduleMaintenance { window }).unwrap(),
+    };
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(1_000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    let swap_funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000),
+    }];
+    let withdraw_msg = Cw20ExecuteMsg::Send {
+        contract: pair_instance.to_string(),
+        amount: Uint128::new(1_000),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
+    };
+    let now = router.block_info().time.seconds();
+
+    // Windows that already ended or have an empty range are rejected
+    for (start, end) in [(now - 20, now - 10), (now + 10, now + 10)] {
+        let err = router
+            .execute_contract(
+                owner.clone(),
+                pair_instance.clone(),
+                &schedule(Some(MaintenanceWindow {
+                    mode: MaintenanceMode::PostOnly,
+                    start,
+                    end,
+                })),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidMaintenanceWindow {}
+        );
+    }
+
+    let post_only = MaintenanceWindow {
+        mode: MaintenanceMode::PostOnly,
+        start: now + 100,
+        end: now + 200,
+    };
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &schedule(Some(post_only.clone())),
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.maintenance_window, Some(post_only));
+
+    // The window has not started yet
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap();
+
+    router.update_block(|b| b.time = b.time.plus_seconds(100));
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::SwapsHaltedForMaintenance { end: now + 200 }
+    );
+
+    // Liquidity can still be provided and withdrawn
+    let (msg, coins) =
+        provide_liquidity_msg(Uint128::new(1_000_000), Uint128::new(1_000_000), None, None);
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+    router
+        .execute_contract(owner.clone(), lp_token.clone(), &withdraw_msg, &[])
+        .unwrap();
+
+    router.update_block(|b| b.time = b.time.plus_seconds(100));
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap();
+
+    let now = router.block_info().time.seconds();
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &schedule(Some(MaintenanceWindow {
+                mode: MaintenanceMode::SwapOnly,
+                start: now,
+                end: now + 100,
+            })),
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::LiquidityHaltedForMaintenance { end: now + 100 }
+    );
+    let err = router
+        .execute_contract(owner.clone(), lp_token.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::LiquidityHaltedForMaintenance { end: now + 100 }.to_string()
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap();
+
+    // Cancelling the window resumes liquidity operations right away
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &schedule(None), &[])
+        .unwrap();
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.maintenance_window, None);
+}
+
+#[test]
+fn price_move_limit() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let update_limit = |max_price_move_per_block: Option<Decimal>| ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::UpdatePriceMoveLimit {
+            max_price_move_per_block,
+        })
+        .unwrap(),
+    };
+    // Every swap moves the price by about 2%
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(1_000_000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    let swap_funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000_000),
+    }];
+
+    for limit in [Decimal::zero(), Decimal::percent(101)] {
+        let err = router
+            .execute_contract(
+                owner.clone(),
+                pair_instance.clone(),
+                &update_limit(Some(limit)),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::PriceMoveLimitOutOfBounds {}
+        );
+    }
+
+    let limit = Decimal::percent(5);
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &update_limit(Some(limit)),
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_binary(&config.params.unwrap()).unwrap();
+    assert_eq!(params.max_price_move_per_block, Some(limit));
+
+    // The price moves are cumulative within a block
+    for _ in 0..2 {
+        router
+            .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PriceMoveLimitExceeded { limit }
+    );
+
+    // The next block measures the move from the current price
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(5);
+    });
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+        .unwrap();
+
+    // Swaps are not limited once the limit is removed
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &update_limit(None),
+            &[],
+        )
+        .unwrap();
+    for _ in 0..3 {
+        router
+            .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg, &swap_funds)
+            .unwrap();
+    }
+}
+
+#[test]
+fn donate() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let donate_msg = ExecuteMsg::Donate {
+        assets: vec![native_asset_info("uusd".to_string()).with_balance(1_000_000u128)],
+    };
+    let donate_funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000_000),
+    }];
+
+    // Donations are disabled by default
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &donate_msg,
+            &donate_funds,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DonationsDisabled {}
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_binary(&XYKPoolUpdateParams::UpdateDonations { enabled: true }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_binary(&config.params.unwrap()).unwrap();
+    assert!(params.donations_enabled);
+
+    // There is nobody to donate to in an empty pool
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &donate_msg,
+            &donate_funds,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoLiquidityToDonateTo {}
+    );
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &donate_msg,
+            &donate_funds,
+        )
+        .unwrap();
+    let donate_event = res
+        .events
+        .iter()
+        .find(|event| event.attributes.contains(&attr("action", "donate")))
+        .unwrap();
+    assert!(donate_event
+        .attributes
+        .contains(&attr("assets", "1000000uusd, 0uluna")));
+
+    // The reserves grow while no LP tokens are minted
+    let pool: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.assets[0].amount, Uint128::new(101_000_000));
+    assert_eq!(pool.assets[1].amount, Uint128::new(100_000_000));
+    assert_eq!(pool.total_share, Uint128::new(100_000_000));
+}
+
+#[test]
+fn expired_deadline() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+    let now = router.block_info().time.seconds();
+
+    let (mut msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    if let ExecuteMsg::ProvideLiquidity { deadline, .. } = &mut msg {
+        *deadline = Some(now - 1);
+    }
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TransactionExpired {}
+    );
+
+    if let ExecuteMsg::ProvideLiquidity { deadline, .. } = &mut msg {
+        *deadline = Some(now);
+    }
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    // The same provision now mints 100_000_000 LP tokens
+    if let ExecuteMsg::ProvideLiquidity {
+        min_lp_to_receive, ..
+    } = &mut msg
+    {
+        *min_lp_to_receive = Some(Uint128::new(100_000_001));
+    }
+    let err = router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ProvideLiquidity(ProvideLiquidityError::MinLpToReceiveAssertion {
+            share: Uint128::new(100_000_000),
+            min_lp_to_receive: Uint128::new(100_000_001),
+        })
+    );
+
+    let swap_msg = |deadline: u64| ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(1_000_000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        min_receive: None,
+        to: None,
+        deadline: Some(deadline),
+        referral: None,
+    };
+    let funds = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000_000),
+    }];
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &swap_msg(now - 1),
+            &funds,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TransactionExpired {}
+    );
+
+    // The deadline itself is still valid
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &swap_msg(now), &funds)
+        .unwrap();
+}
+
+#[test]
+fn share_value_in() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(200_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let pool: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+
+    // 1 uluna is worth 0.5 uusd at the current reserves
+    let value: Asset = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareValueIn {
+                amount: pool.total_share,
+                asset_info: native_asset_info("uusd".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        value,
+        native_asset_info("uusd".to_string()).with_balance(200_000_000u128)
+    );
+
+    let value: Asset = router
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareValueIn {
+                amount: pool.total_share.multiply_ratio(1u8, 2u8),
+                asset_info: native_asset_info("uluna".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        value,
+        native_asset_info("uluna".to_string()).with_balance(200_000_000u128)
+    );
+
+    let err = router
+        .wrap()
+        .query_wasm_smart::<Asset>(
+            &pair_instance,
+            &QueryMsg::ShareValueIn {
+                amount: pool.total_share,
+                asset_info: native_asset_info("ibc/usdc".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Given asset does not belong in the pair"));
+}
+
+#[test]
+fn rounding_dust() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let dust: Vec<RoundingDust> = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::RoundingDust {})
+        .unwrap();
+    assert!(dust.iter().all(|dust| dust.amount.is_zero()));
+
+    // 2,99999991 = 100000000 * 3 / (100000000 + 3) is rounded down to 2
+    router
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(3u128),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: None,
+                min_receive: None,
+                to: None,
+                deadline: None,
+                referral: None,
+            },
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(3),
+            }],
+        )
+        .unwrap();
+
+    let pool: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.assets[1].amount, Uint128::new(100_000_000 - 2));
+
+    // The fraction kept by the pool is accounted as dust of the ask asset
+    let dust: Vec<RoundingDust> = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::RoundingDust {})
+        .unwrap();
+    assert_eq!(
+        dust,
+        vec![
+            RoundingDust {
+                info: native_asset_info("uusd".to_string()),
+                amount: Decimal256::zero(),
+            },
+            RoundingDust {
+                info: native_asset_info("uluna".to_string()),
+                amount: Decimal256::from_atomics(999999910000002699u128, 18).unwrap(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_compatibility_of_tokens_with_different_precision() {
+    let owner = Addr::unchecked(OWNER);
+
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+        ],
+    );
+
+    let token_code_id = store_token_code(&mut app);
+
+    let x_amount = Uint128::new(1000000_00000);
+    let y_amount = Uint128::new(1000000_0000000);
+    let x_offer = Uint128::new(1_00000);
+    let y_expected_return = Uint128::new(1_0000000);
+
+    let token_name = "Xtoken";
+
+    let init_msg = TokenInstantiateMsg {
+        name: token_name.to_string(),
+        symbol: token_name.to_string(),
+        decimals: 5,
+        initial_balances: vec![Cw20Coin {
+            address: OWNER.to_string(),
+            amount: x_amount + x_offer,
+        }],
+        mint: Some(MinterResponse {
+            minter: String::from(OWNER),
+            cap: None,
+        }),
+        marketing: None,
+    };
+
+    let token_x_instance = app
+        .instantiate_contract(
+            token_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            token_name,
+            None,
+        )
+        .unwrap();
+
+    let token_name = "Ytoken";
+
+    let init_msg = TokenInstantiateMsg {
+        name: token_name.to_string(),
+        symbol: token_name.to_string(),
+        decimals: 7,
+        initial_balances: vec![Cw20Coin {
+            address: OWNER.to_string(),
+            amount: y_amount,
+        }],
+        mint: Some(MinterResponse {
+            minter: String::from(OWNER),
+            cap: None,
+        }),
+        marketing: None,
+    };
+
+    let token_y_instance = app
+        .instantiate_contract(
+            token_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            token_name,
+            None,
+        )
+        .unwrap();
+
+    let pair_code_id = store_pair_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+
+    let init_msg = FactoryInstantiateMsg {
+        fee_address: None,
+        pair_configs: vec![PairConfig {
+            code_id: pair_code_id,
+            maker_fee_bps: 0,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 0,
+            is_disabled: false,
+            is_generator_disabled: false,
+        }],
+        token_code_id,
+        generator_address: Some(String::from("generator")),
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let factory_instance = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let msg = FactoryExecuteMsg::CreatePair {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: token_x_instance.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: token_y_instance.clone(),
+            },
+        ],
+        pair_type: PairType::Xyk {},
+        init_params: None,
+    };
+
+    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg = FactoryQueryMsg::Pair {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: token_x_instance.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: token_y_instance.clone(),
+            },
+        ],
+    };
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&factory_instance, &msg)
+        .unwrap();
+
+    let pair_instance = res.contract_addr;
+
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: pair_instance.to_string(),
+        expires: None,
+        amount: x_amount + x_offer,
+    };
+
+    app.execute_contract(owner.clone(), token_x_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: pair_instance.to_string(),
+        expires: None,
+        amount: y_amount,
+    };
+
+    app.execute_contract(owner.clone(), token_y_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+
+    let swap_msg = Cw20ExecuteMsg::Send {
+        contract: pair_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            min_receive: None,
+            to: Some(user.to_string()),
+            deadline: None,
+            referral: None,
+        })
+        .unwrap(),
+        amount: x_offer,
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), token_x_instance.clone(), &swap_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: One of the pools is empty",
+        err.root_cause().to_string()
+    );
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: token_x_instance.clone(),
+                },
+                amount: x_amount,
             },
             Asset {
                 info: AssetInfo::Token {
@@ -595,6 +1516,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -608,7 +1531,10 @@ fn test_compatibility_of_tokens_with_different_precision() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: Some(user.to_string()),
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -627,9 +1553,225 @@ fn test_compatibility_of_tokens_with_different_precision() {
         .query_wasm_smart(&token_y_instance, &msg)
         .unwrap();
 
-    let acceptable_spread_amount = Uint128::new(10);
+    // The return is rounded down, the spread is the only difference to the expected return
+    let spread_amount = Uint128::new(10);
+
+    assert_eq!(res.balance, y_expected_return - spread_amount);
+}
+
+#[test]
+fn min_trade_floor() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(owner.clone(), vec![]);
+
+    let token_code_id = store_token_code(&mut app);
+    let mut create_token = |name: &str, decimals: u8| {
+        app.instantiate_contract(
+            token_code_id,
+            owner.clone(),
+            &TokenInstantiateMsg {
+                name: name.to_string(),
+                symbol: name.to_string(),
+                decimals,
+                initial_balances: vec![Cw20Coin {
+                    address: OWNER.to_string(),
+                    amount: Uint128::new(1_000_000_0000000),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            name,
+            None,
+        )
+        .unwrap()
+    };
+    let token_x = create_token("Xtoken", 5);
+    let token_y = create_token("Ytoken", 7);
+
+    let pair_code_id = store_pair_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &FactoryInstantiateMsg {
+                fee_address: None,
+                pair_configs: vec![PairConfig {
+                    code_id: pair_code_id,
+                    maker_fee_bps: 0,
+                    pair_type: PairType::Xyk {},
+                    total_fee_bps: 0,
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                }],
+                token_code_id,
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 234u64,
+                coin_registry_address: "coin_registry".to_string(),
+            },
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: token_x.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token_y.clone(),
+        },
+    ];
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &FactoryExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            pair_type: PairType::Xyk {},
+            init_params: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let pair = app
+        .wrap()
+        .query_wasm_smart::<PairInfo>(&factory, &FactoryQueryMsg::Pair { asset_infos })
+        .unwrap()
+        .contract_addr;
+
+    for (token, amount) in [
+        (&token_x, 1_000_000_00000u128),
+        (&token_y, 1_000_000_0000000),
+    ] {
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.to_string(),
+                expires: None,
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                token_asset(token_x.clone(), Uint128::new(1_000_000_00000)),
+                token_asset(token_y.clone(), Uint128::new(1_000_000_0000000)),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let update_floor = |min_trade_floor: Option<Decimal256>| ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::UpdateMinTradeFloor { min_trade_floor }).unwrap(),
+    };
+    let swap_x = |amount: u128| Cw20ExecuteMsg::Send {
+        contract: pair.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            min_receive: None,
+            to: None,
+            deadline: None,
+            referral: None,
+        })
+        .unwrap(),
+    };
+
+    // No floor is set by default
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.min_trade_sizes, vec![]);
+    app.execute_contract(owner.clone(), token_x.clone(), &swap_x(1), &[])
+        .unwrap();
+
+    let floor = Decimal256::from_ratio(1u8, 1000u16);
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair.clone(),
+            &update_floor(Some(floor)),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &update_floor(Some(Decimal256::from_ratio(2u8, 1u8))),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MinTradeFloorOutOfBounds {}
+    );
+
+    app.execute_contract(owner.clone(), pair.clone(), &update_floor(Some(floor)), &[])
+        .unwrap();
+
+    // The floor is converted with the precision of each asset
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        config.min_trade_sizes,
+        vec![
+            MinTradeSize {
+                asset_info: token_asset_info(token_x.clone()),
+                observation: Uint128::new(1),
+                swap: Uint128::new(100),
+            },
+            MinTradeSize {
+                asset_info: token_asset_info(token_y.clone()),
+                observation: Uint128::new(100),
+                swap: Uint128::new(10_000),
+            },
+        ]
+    );
+
+    let err = app
+        .execute_contract(owner.clone(), token_x.clone(), &swap_x(99), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BelowMinTradeSize {
+            amount: Uint128::new(99),
+            min: Uint128::new(100),
+        }
+    );
+    app.execute_contract(owner.clone(), token_x.clone(), &swap_x(100), &[])
+        .unwrap();
 
-    assert_eq!(res.balance, y_expected_return - acceptable_spread_amount);
+    // Removing the floor allows dust swaps again
+    app.execute_contract(owner.clone(), pair.clone(), &update_floor(None), &[])
+        .unwrap();
+    app.execute_contract(owner.clone(), token_x.clone(), &swap_x(1), &[])
+        .unwrap();
 }
 
 #[test]
@@ -690,40 +1832,173 @@ fn test_if_twap_is_calculated_correctly_when_pool_idles() {
         b.time = b.time.plus_seconds(ELAPSED_SECONDS);
     });
 
-    // Provide liquidity, accumulators firstly filled with the same prices
+    // Provide liquidity, accumulators firstly filled with the same prices
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(2000000_000000),
+        Uint128::new(1000000_000000),
+        None,
+        Some(Decimal::percent(50)),
+    );
+    app.execute_contract(user1.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    // Get current twap accumulator values
+    let msg = QueryMsg::CumulativePrices {};
+    let cpr_old: CumulativePricesResponse =
+        app.wrap().query_wasm_smart(&pair_instance, &msg).unwrap();
+
+    // A day later
+    app.update_block(|b| {
+        b.height += BLOCKS_PER_DAY;
+        b.time = b.time.plus_seconds(ELAPSED_SECONDS);
+    });
+
+    // Get current cumulative price values; they should have been updated by the query method with new 2/1 ratio
+    let msg = QueryMsg::CumulativePrices {};
+    let cpr_new: CumulativePricesResponse =
+        app.wrap().query_wasm_smart(&pair_instance, &msg).unwrap();
+
+    let twap0 = cpr_new.cumulative_prices[0].2 - cpr_old.cumulative_prices[0].2;
+    let twap1 = cpr_new.cumulative_prices[1].2 - cpr_old.cumulative_prices[1].2;
+
+    // Prices weren't changed for the last day, uusd amount in pool = 3000000_000000, uluna = 2000000_000000
+    // In accumulators we don't have any precision so we rely on elapsed time so we don't need to consider it
+    let price_precision = Uint128::from(10u128.pow(TWAP_PRECISION.into()));
+    assert_eq!(twap0 / price_precision, Uint128::new(57600)); // 0.666666 * ELAPSED_SECONDS (86400)
+    assert_eq!(twap1 / price_precision, Uint128::new(129600)); //   1.5 * ELAPSED_SECONDS
+}
+
+#[test]
+fn observations() {
+    let owner = Addr::unchecked("owner");
+    let user1 = Addr::unchecked("user1");
+
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+        ],
+    );
+
+    app.send_tokens(
+        owner.clone(),
+        user1.clone(),
+        &[
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(4000000_000000),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(2000000_000000),
+            },
+        ],
+    )
+    .unwrap();
+
+    let pair_instance = instantiate_pair(&mut app, &user1);
+
     let (msg, coins) = provide_liquidity_msg(
-        Uint128::new(2000000_000000),
+        Uint128::new(1000000_000000),
         Uint128::new(1000000_000000),
         None,
-        Some(Decimal::percent(50)),
+        None,
     );
     app.execute_contract(user1.clone(), pair_instance.clone(), &msg, &coins)
         .unwrap();
 
-    // Get current twap accumulator values
-    let msg = QueryMsg::CumulativePrices {};
-    let cpr_old: CumulativePricesResponse =
-        app.wrap().query_wasm_smart(&pair_instance, &msg).unwrap();
+    let swap = |app: &mut App, denom: &str, amount: u128| {
+        let offer_asset = Asset {
+            info: AssetInfo::NativeToken {
+                denom: denom.to_string(),
+            },
+            amount: Uint128::new(amount),
+        };
+        app.execute_contract(
+            user1.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset,
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: None,
+                min_receive: Some(Uint128::zero()),
+                to: None,
+                deadline: None,
+                referral: None,
+            },
+            &[Coin::new(amount, denom)],
+        )
+        .unwrap();
+    };
+    let observe = |app: &App, seconds_ago: u64| {
+        app.wrap().query_wasm_smart::<OracleObservation>(
+            &pair_instance,
+            &QueryMsg::Observe { seconds_ago },
+        )
+    };
+    let skip_seconds = |app: &mut App, seconds: u64| {
+        app.update_block(|b| {
+            b.height += 1;
+            b.time = b.time.plus_seconds(seconds);
+        })
+    };
 
-    // A day later
-    app.update_block(|b| {
-        b.height += BLOCKS_PER_DAY;
-        b.time = b.time.plus_seconds(ELAPSED_SECONDS);
-    });
+    let err = observe(&app, 0).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: Querier contract error: Generic error: Buffer is empty"
+    );
 
-    // Get current cumulative price values; they should have been updated by the query method with new 2/1 ratio
-    let msg = QueryMsg::CumulativePrices {};
-    let cpr_new: CumulativePricesResponse =
-        app.wrap().query_wasm_smart(&pair_instance, &msg).unwrap();
+    // The pool holds 2000000 uusd and 500000 uluna after the swap
+    let t0 = app.block_info().time.seconds();
+    swap(&mut app, "uusd", 1000000_000000);
+    skip_seconds(&mut app, 1000);
 
-    let twap0 = cpr_new.cumulative_prices[0].2 - cpr_old.cumulative_prices[0].2;
-    let twap1 = cpr_new.cumulative_prices[1].2 - cpr_old.cumulative_prices[1].2;
+    // The first observation is read from the precommitted data
+    assert_eq!(
+        observe(&app, 0).unwrap(),
+        OracleObservation {
+            timestamp: t0 + 1000,
+            price: Decimal::from_ratio(4u8, 1u8),
+        }
+    );
 
-    // Prices weren't changed for the last day, uusd amount in pool = 3000000_000000, uluna = 2000000_000000
-    // In accumulators we don't have any precision so we rely on elapsed time so we don't need to consider it
-    let price_precision = Uint128::from(10u128.pow(TWAP_PRECISION.into()));
-    assert_eq!(twap0 / price_precision, Uint128::new(57600)); // 0.666666 * ELAPSED_SECONDS (86400)
-    assert_eq!(twap1 / price_precision, Uint128::new(129600)); //   1.5 * ELAPSED_SECONDS
+    // The pool returns to 1000000 uusd and 1000000 uluna
+    swap(&mut app, "uluna", 500000_000000);
+    skip_seconds(&mut app, 1000);
+    swap(&mut app, "uusd", 1000);
+
+    // The observed prices are averaged
+    assert_eq!(
+        observe(&app, 0).unwrap().price,
+        Decimal::from_ratio(5u8, 2u8)
+    );
+    assert_eq!(
+        observe(&app, 1500).unwrap(),
+        OracleObservation {
+            timestamp: t0 + 500,
+            price: Decimal::from_ratio(13u8, 4u8),
+        }
+    );
+    assert_eq!(
+        observe(&app, 2000).unwrap().price,
+        Decimal::from_ratio(4u8, 1u8)
+    );
+    let err = observe(&app, 2001).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Generic error: Querier contract error: Generic error: Requested observation is too old. Last known observation is at {t0}"
+        )
+    );
 }
 
 #[test]
@@ -757,6 +2032,7 @@ fn create_pair_with_same_assets() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -790,6 +2066,7 @@ fn wrong_number_of_assets() {
         }],
         token_code_id: 123,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -817,6 +2094,7 @@ fn wrong_number_of_assets() {
         ],
         token_code_id: 123,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -1045,6 +2323,8 @@ fn asset_balances_tracking_works_correctly() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let send_funds = [
@@ -1296,7 +2576,10 @@ fn asset_balances_tracking_works_correctly() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: None,
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let send_funds = vec![Coin {
         denom: "uusd".to_owned(),
@@ -1339,41 +2622,329 @@ fn asset_balances_tracking_works_correctly() {
     let msg = Cw20ExecuteMsg::Send {
         contract: pair_instance.to_string(),
         amount: Uint128::new(500_000000),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
+    };
+
+    app.execute_contract(owner.clone(), lp_token_address, &msg, &[])
+        .unwrap();
+
+    // Check that asset balances changed after withdrawing
+    app.update_block(|b| b.height += 1);
+    let res: Option<Uint128> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::AssetBalanceAt {
+                asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_owned(),
+                },
+                block_height: app.block_info().height.into(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.unwrap(), Uint128::new(499_250063));
+
+    let res: Option<Uint128> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::AssetBalanceAt {
+                asset_info: AssetInfo::NativeToken {
+                    denom: "uusd".to_owned(),
+                },
+                block_height: app.block_info().height.into(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.unwrap(), Uint128::new(499_749812));
+}
+
+#[test]
+fn share_at_uses_tracked_balances() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000_000000),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1000_000000),
+            },
+        ],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+    let lp_token_address = Addr::unchecked("contract2");
+
+    // Balances are not tracked yet
+    let res: Option<Vec<Asset>> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareAt {
+                amount: Uint128::new(1_000000),
+                block_height: app.block_info().height.into(),
+            },
+        )
+        .unwrap();
+    assert!(res.is_none());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::EnableAssetBalancesTracking).unwrap(),
     };
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+    app.update_block(|b| b.height += 1);
+    let provided_at = app.block_info().height;
+
+    let res: Option<Vec<Asset>> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareAt {
+                amount: Uint128::new(10_000000),
+                block_height: provided_at.into(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.unwrap(),
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(10_000000u128),
+            native_asset_info("uluna".to_string()).with_balance(10_000000u128),
+        ]
+    );
 
+    // Withdraw half of the liquidity and move the price with a swap
+    let msg = Cw20ExecuteMsg::Send {
+        contract: pair_instance.to_string(),
+        amount: Uint128::new(50_000000),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
+    };
     app.execute_contract(owner.clone(), lp_token_address, &msg, &[])
         .unwrap();
 
-    // Check that asset balances changed after withdrawing
-    app.update_block(|b| b.height += 1);
-    let res: Option<Uint128> = app
-        .wrap()
-        .query_wasm_smart(
+    let msg = ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(10_000000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &msg,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(10_000000),
+        }],
+    )
+    .unwrap();
+    app.update_block(|b| b.height += 1);
+
+    // The past share value is unaffected by later operations
+    let res: Option<Vec<Asset>> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareAt {
+                amount: Uint128::new(10_000000),
+                block_height: provided_at.into(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.unwrap(),
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(10_000000u128),
+            native_asset_info("uluna".to_string()).with_balance(10_000000u128),
+        ]
+    );
+
+    // The latest snapshot matches the current share
+    let res: Option<Vec<Asset>> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareAt {
+                amount: Uint128::new(10_000000),
+                block_height: app.block_info().height.into(),
+            },
+        )
+        .unwrap();
+    let share: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::Share {
+                amount: Uint128::new(10_000000),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.unwrap(), share);
+}
+
+fn downgrade_to_v150(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
+    // Pairs released before 1.6.0 neither recorded observations nor snapshotted the LP supply
+    OBSERVATIONS.state().remove(deps.storage);
+    TOTAL_SHARE.remove(deps.storage, env.block.height)?;
+    set_contract_version(deps.storage, "gridiron-pair", "1.5.0")?;
+
+    Ok(Response::new())
+}
+
+#[test]
+fn migrate_from_v150() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000_000000),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1000_000000),
+            },
+        ],
+    );
+
+    let pair_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_pair::contract::execute,
+            gridiron_pair::contract::instantiate,
+            gridiron_pair::contract::query,
+        )
+        .with_reply_empty(gridiron_pair::contract::reply)
+        .with_migrate(gridiron_pair::contract::migrate),
+    ));
+    let downgrade_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_pair::contract::execute,
+            gridiron_pair::contract::instantiate,
+            gridiron_pair::contract::query,
+        )
+        .with_migrate_empty(downgrade_to_v150),
+    ));
+    let pair_instance =
+        instantiate_pair_from_code(&mut app, &owner, pair_code_id, Some(owner.to_string()));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::EnableAssetBalancesTracking).unwrap(),
+    };
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+    app.update_block(|b| b.height += 1);
+
+    app.migrate_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &Empty {},
+        downgrade_code_id,
+    )
+    .unwrap();
+    app.update_block(|b| b.height += 1);
+
+    let share_at = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<Option<Vec<Asset>>>(
+                &pair_instance,
+                &QueryMsg::ShareAt {
+                    amount: Uint128::new(10_000000),
+                    block_height: app.block_info().height.into(),
+                },
+            )
+            .unwrap()
+    };
+    assert_eq!(share_at(&app), None);
+
+    app.migrate_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &MigrateMsg {},
+        pair_code_id,
+    )
+    .unwrap();
+    app.update_block(|b| b.height += 1);
+
+    // The LP supply snapshot is seeded from the live pool
+    assert_eq!(
+        share_at(&app).unwrap(),
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(10_000000u128),
+            native_asset_info("uluna".to_string()).with_balance(10_000000u128),
+        ]
+    );
+
+    // The observations buffer is initialized so swaps can record prices again
+    let msg = ExecuteMsg::Swap {
+        offer_asset: native_asset_info("uusd".to_string()).with_balance(1_000000u128),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    };
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &msg,
+        &[Coin::new(1_000000, "uusd")],
+    )
+    .unwrap();
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(100);
+    });
+    app.wrap()
+        .query_wasm_smart::<OracleObservation>(
             &pair_instance,
-            &QueryMsg::AssetBalanceAt {
-                asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_owned(),
-                },
-                block_height: app.block_info().height.into(),
-            },
+            &QueryMsg::Observe { seconds_ago: 0 },
         )
         .unwrap();
-    assert_eq!(res.unwrap(), Uint128::new(499_250063));
 
-    let res: Option<Uint128> = app
-        .wrap()
-        .query_wasm_smart(
-            &pair_instance,
-            &QueryMsg::AssetBalanceAt {
-                asset_info: AssetInfo::NativeToken {
-                    denom: "uusd".to_owned(),
-                },
-                block_height: app.block_info().height.into(),
-            },
-        )
-        .unwrap();
-    assert_eq!(res.unwrap(), Uint128::new(499_749812));
+    // The current version can't be migrated again
+    let err = app
+        .migrate_contract(owner, pair_instance, &MigrateMsg {}, pair_code_id)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::MigrationError {}.to_string()
+    );
 }
 
 #[test]
@@ -1430,6 +3001,7 @@ fn update_pair_config() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: None,
     };
 
@@ -1457,11 +3029,17 @@ fn update_pair_config() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
-            factory_addr: Addr::unchecked("contract0")
+            factory_addr: Addr::unchecked("contract0"),
+            creator: Some(owner.clone()),
+            created_at: Some(PairCreatedAt::from(&router.block_info())),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         }
     );
 
@@ -1498,11 +3076,17 @@ fn update_pair_config() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: true,
                     fee_share: None,
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
-            factory_addr: Addr::unchecked("contract0")
+            factory_addr: Addr::unchecked("contract0"),
+            creator: Some(owner.clone()),
+            created_at: Some(PairCreatedAt::from(&router.block_info())),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         }
     );
 }
@@ -1561,6 +3145,7 @@ fn enable_disable_fee_sharing() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: None,
     };
 
@@ -1588,11 +3173,17 @@ fn enable_disable_fee_sharing() {
                 to_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
-            factory_addr: Addr::unchecked("contract0")
+            factory_addr: Addr::unchecked("contract0"),
+            creator: Some(owner.clone()),
+            created_at: Some(PairCreatedAt::from(&router.block_info())),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         }
     );
 
@@ -1660,46 +3251,321 @@ fn enable_disable_fee_sharing() {
                         bps: fee_share_bps,
                         recipient: Addr::unchecked(fee_share_contract),
                     }),
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
+                })
+                .unwrap()
+            ),
+            owner: Addr::unchecked("owner"),
+            factory_addr: Addr::unchecked("contract0"),
+            creator: Some(owner.clone()),
+            created_at: Some(PairCreatedAt::from(&router.block_info())),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
+        }
+    );
+
+    // Disable fee sharing
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::DisableFeeShare).unwrap(),
+    };
+
+    router
+        .execute_contract(owner.clone(), pair.clone(), &msg, &[])
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(pair.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        res,
+        ConfigResponse {
+            block_time_last: 0,
+            params: Some(
+                to_binary(&XYKPoolConfig {
+                    track_asset_balances: false,
+                    fee_share: None,
+                    max_price_move_per_block: None,
+                    donations_enabled: false,
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
-            factory_addr: Addr::unchecked("contract0")
+            factory_addr: Addr::unchecked("contract0"),
+            creator: Some(owner.clone()),
+            created_at: Some(PairCreatedAt::from(&router.block_info())),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
+        }
+    );
+}
+
+#[test]
+fn combined_fee_cap() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(owner.clone(), vec![]);
+
+    let token_contract_code_id = store_token_code(&mut router);
+    let pair_contract_code_id = store_pair_code(&mut router);
+    let factory_code_id = store_factory_code(&mut router);
+
+    let pair_config = PairConfig {
+        code_id: pair_contract_code_id,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 30,
+        maker_fee_bps: 3333,
+        is_disabled: false,
+        is_generator_disabled: false,
+    };
+
+    let init_msg = FactoryInstantiateMsg {
+        fee_address: None,
+        pair_configs: vec![pair_config.clone()],
+        token_code_id: token_contract_code_id,
+        generator_address: Some(String::from("generator")),
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let factory_instance = router
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        ],
+        token_code_id: token_contract_code_id,
+        factory_addr: factory_instance.to_string(),
+        creator: None,
+        init_params: None,
+    };
+
+    let pair = router
+        .instantiate_contract(
+            pair_contract_code_id,
+            owner.clone(),
+            &msg,
+            &[],
+            String::from("PAIR"),
+            None,
+        )
+        .unwrap();
+
+    let update_cap = |router: &mut App, max_combined_fee_bps: u16| {
+        router
+            .execute_contract(
+                owner.clone(),
+                factory_instance.clone(),
+                &FactoryExecuteMsg::UpdateConfig {
+                    token_code_id: None,
+                    fee_address: None,
+                    generator_address: None,
+                    whitelist_code_id: None,
+                    coin_registry_address: None,
+                    max_combined_fee_bps: Some(max_combined_fee_bps),
+                },
+                &[],
+            )
+            .unwrap();
+    };
+    let enable_fee_share = |fee_share_bps: u16| ExecuteMsg::UpdateConfig {
+        params: to_binary(&XYKPoolUpdateParams::EnableFeeShare {
+            fee_share_bps,
+            fee_share_address: "contract".to_string(),
+        })
+        .unwrap(),
+    };
+
+    update_cap(&mut router, 300);
+
+    // 30 bps of total fee plus 300 bps of fee share exceed the cap
+    let err = router
+        .execute_contract(owner.clone(), pair.clone(), &enable_fee_share(300), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::CombinedFeeExceedsCap {
+            combined_fee_bps: 330,
+            max_combined_fee_bps: 300
+        }
+    );
+
+    router
+        .execute_contract(owner.clone(), pair.clone(), &enable_fee_share(200), &[])
+        .unwrap();
+
+    // Lowering the cap below the current fees doesn't block updates which decrease them
+    update_cap(&mut router, 100);
+
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_binary(&XYKPoolUpdateParams::DisableFeeShare).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(owner.clone(), pair.clone(), &enable_fee_share(100), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::CombinedFeeExceedsCap {
+            combined_fee_bps: 130,
+            max_combined_fee_bps: 100
         }
     );
 
-    // Disable fee sharing
-    let msg = ExecuteMsg::UpdateConfig {
-        params: to_binary(&XYKPoolUpdateParams::DisableFeeShare).unwrap(),
-    };
+    // The factory doesn't accept pair configs with a total fee above the cap either
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::UpdatePairConfig {
+                config: PairConfig {
+                    total_fee_bps: 150,
+                    ..pair_config
+                },
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Total fee exceeds the max combined fee of 100 bps"
+    );
+}
+
+#[test]
+fn provide_liquidity_with_autostaking_to_generator() {
+    let gridiron = gridiron_address();
+
+    let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &gridiron,
+                vec![Coin {
+                    denom: "ustake".to_owned(),
+                    amount: Uint128::new(1_000_000_000000),
+                }],
+            )
+            .unwrap();
+    })));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+
+    let factory = generator.factory();
+
+    let grid_token_info = generator.grid_token_info();
+    let ustake = native_asset_info("ustake".to_owned());
+
+    let pair = MockXykPairBuilder::new(&app)
+        .with_factory(&factory)
+        .with_asset(&grid_token_info)
+        .with_asset(&ustake)
+        .instantiate();
+
+    pair.mint_allow_provide_and_stake(
+        &gridiron,
+        &[
+            grid_token_info.with_balance(1_000_000000u128),
+            ustake.with_balance(1_000_000000u128),
+        ],
+    );
+
+    assert_eq!(pair.lp_token().balance(&pair.address), Uint128::new(1000));
+    assert_eq!(
+        generator.query_deposit(&pair.lp_token(), &gridiron),
+        Uint128::new(999_999000),
+    );
+}
+
+#[test]
+fn raw_query_helpers() {
+    let gridiron = gridiron_address();
+
+    let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &gridiron,
+                vec![Coin {
+                    denom: "ustake".to_owned(),
+                    amount: Uint128::new(1_000_000_000000),
+                }],
+            )
+            .unwrap();
+    })));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+    let grid_token_info = generator.grid_token_info();
+    let ustake = native_asset_info("ustake".to_owned());
+
+    let pair = MockXykPairBuilder::new(&app)
+        .with_factory(&factory)
+        .with_asset(&grid_token_info)
+        .with_asset(&ustake)
+        .instantiate();
+
+    pair.mint_allow_provide_and_stake(
+        &gridiron,
+        &[
+            grid_token_info.with_balance(1_000_000000u128),
+            ustake.with_balance(2_000_000000u128),
+        ],
+    );
+
+    let app_ref = app.borrow();
+    let querier = app_ref.wrap();
 
-    router
-        .execute_contract(owner.clone(), pair.clone(), &msg, &[])
-        .unwrap();
+    let pair_info = query_pair_info_raw(&querier, &pair.address).unwrap();
+    assert_eq!(pair_info, pair.pair_info().unwrap());
 
-    let res: ConfigResponse = router
-        .wrap()
-        .query_wasm_smart(pair.clone(), &QueryMsg::Config {})
+    let config: Config = query_pair_config_raw(&querier, &pair.address).unwrap();
+    assert_eq!(config.factory_addr, factory.address);
+    assert_eq!(config.pair_info, pair_info);
+
+    let pool: PoolResponse = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::Pool {})
         .unwrap();
+    let reserves = query_pair_reserves_raw(&querier, &pair.address).unwrap();
+    assert_eq!(reserves, pool.assets);
     assert_eq!(
-        res,
-        ConfigResponse {
-            block_time_last: 0,
-            params: Some(
-                to_binary(&XYKPoolConfig {
-                    track_asset_balances: false,
-                    fee_share: None,
-                })
-                .unwrap()
-            ),
-            owner: Addr::unchecked("owner"),
-            factory_addr: Addr::unchecked("contract0")
-        }
+        reserves,
+        vec![
+            grid_token_info.with_balance(1_000_000000u128),
+            ustake.with_balance(2_000_000000u128),
+        ]
     );
+
+    let err = query_pair_info_raw(&querier, &factory.address).unwrap_err();
+    assert!(err.to_string().contains("pair_info"));
 }
 
 #[test]
-fn provide_liquidity_with_autostaking_to_generator() {
+fn versioned_queries() {
     let gridiron = gridiron_address();
 
     let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
@@ -1717,9 +3583,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
     })));
 
     let generator = MockGeneratorBuilder::new(&app).instantiate();
-
     let factory = generator.factory();
-
     let grid_token_info = generator.grid_token_info();
     let ustake = native_asset_info("ustake".to_owned());
 
@@ -1733,19 +3597,469 @@ fn provide_liquidity_with_autostaking_to_generator() {
         &gridiron,
         &[
             grid_token_info.with_balance(1_000_000000u128),
+            ustake.with_balance(2_000_000000u128),
+        ],
+    );
+
+    let app_ref = app.borrow();
+    let querier = app_ref.wrap();
+
+    // Every versioned query returns the legacy response wrapped into the envelope
+    let pair_info: Versioned<PairInfo> = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::VersionedPair {})
+        .unwrap();
+    assert_eq!(pair_info.version, QUERY_RESPONSE_VERSION);
+    assert_eq!(pair_info.into_data().unwrap(), pair.pair_info().unwrap());
+
+    let pool: Versioned<PoolResponse> = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::VersionedPool {})
+        .unwrap();
+    let legacy_pool: PoolResponse = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.into_data().unwrap(), legacy_pool);
+
+    let config: Versioned<ConfigResponse> = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::VersionedConfig {})
+        .unwrap();
+    let legacy_config: ConfigResponse = querier
+        .query_wasm_smart(&pair.address, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.into_data().unwrap(), legacy_config);
+
+    let offer_asset = ustake.with_balance(1_000000u128);
+    let simulation: Versioned<SimulationResponse> = querier
+        .query_wasm_smart(
+            &pair.address,
+            &QueryMsg::VersionedSimulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
+        )
+        .unwrap();
+    let legacy_simulation: SimulationResponse = querier
+        .query_wasm_smart(
+            &pair.address,
+            &QueryMsg::Simulation {
+                offer_asset,
+                ask_asset_info: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(simulation.into_data().unwrap(), legacy_simulation);
+
+    // Responses with an unknown layout version are rejected
+    let err = Versioned {
+        version: QUERY_RESPONSE_VERSION + 1,
+        data: legacy_pool,
+    }
+    .into_data()
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Generic error: Unsupported query response version {}, expected {}",
+            QUERY_RESPONSE_VERSION + 1,
+            QUERY_RESPONSE_VERSION
+        )
+    );
+}
+
+#[test]
+fn supports_interface() {
+    let app = Rc::new(RefCell::new(BasicApp::default()));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+
+    let pair = MockXykPairBuilder::new(&app)
+        .with_factory(&factory)
+        .with_asset(&native_asset_info("uusd".to_owned()))
+        .with_asset(&native_asset_info("ustake".to_owned()))
+        .instantiate();
+
+    let supports_interface = |interface: &str| -> bool {
+        app.borrow()
+            .wrap()
+            .query_wasm_smart(
+                &pair.address,
+                &QueryMsg::SupportsInterface {
+                    interface: interface.to_owned(),
+                },
+            )
+            .unwrap()
+    };
+
+    assert!(supports_interface(INTERFACE_DEADLINE));
+    assert!(supports_interface(INTERFACE_MIN_LP_TO_RECEIVE));
+    assert!(supports_interface(INTERFACE_VERSIONED_QUERIES));
+    assert!(supports_interface(INTERFACE_FEE_DISCOUNTS));
+    assert!(supports_interface(INTERFACE_BATCH_SWAP));
+    assert!(supports_interface(INTERFACE_OBSERVATIONS));
+    assert!(supports_interface(INTERFACE_REFERRAL));
+    assert!(!supports_interface(INTERFACE_REANCHOR));
+    // Unknown interfaces aren't supported
+    assert!(!supports_interface("flash_loan"));
+}
+
+#[test]
+fn fee_discounts_for_xgrid_holders() {
+    let gridiron = gridiron_address();
+    let trader = Addr::unchecked("trader");
+    let whale = Addr::unchecked("whale");
+
+    let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
+        for (account, denoms) in [
+            (&gridiron, vec!["uusd", "ustake"]),
+            (&trader, vec!["uusd"]),
+            (&whale, vec!["uusd"]),
+        ] {
+            let coins = denoms
+                .into_iter()
+                .map(|denom| Coin {
+                    denom: denom.to_owned(),
+                    amount: Uint128::new(1_000_000_000000),
+                })
+                .collect();
+            router.bank.init_balance(storage, account, coins).unwrap();
+        }
+    })));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+    let uusd = native_asset_info("uusd".to_owned());
+    let ustake = native_asset_info("ustake".to_owned());
+
+    let pair = MockXykPairBuilder::new(&app)
+        .with_factory(&factory)
+        .with_asset(&uusd)
+        .with_asset(&ustake)
+        .instantiate();
+    pair.provide(
+        &gridiron,
+        &[
+            uusd.with_balance(1_000_000000u128),
             ustake.with_balance(1_000_000000u128),
         ],
+        None,
+        false,
+        None,
     );
 
-    assert_eq!(pair.lp_token().balance(&pair.address), Uint128::new(1000));
+    // The whale holds xGRID, the trader doesn't
+    let staking = MockStakingBuilder::new(&app).instantiate();
+    staking.grid_token().mint(&whale, Uint128::new(1_000_000));
+    staking.enter(&whale, Uint128::new(1_000_000));
+    assert!(staking.xgrid_token().balance(&whale) >= Uint128::new(10_000));
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            factory.address.clone(),
+            &FactoryExecuteMsg::SetFeeDiscounts {
+                pair_type: PairType::Xyk {},
+                staking_contract: staking.address.to_string(),
+                tiers: vec![FeeDiscountTier {
+                    min_xgrid: Uint128::new(10_000),
+                    discount_bps: 5000,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let offer_asset = uusd.with_balance(1_000000u128);
+    let swap = |sender: &Addr| -> (SimulationResponse, Uint128, Option<String>) {
+        let sim_res: SimulationResponse = app
+            .borrow()
+            .wrap()
+            .query_wasm_smart(
+                &pair.address,
+                &QueryMsg::Simulation {
+                    offer_asset: offer_asset.clone(),
+                    ask_asset_info: None,
+                },
+            )
+            .unwrap();
+
+        let res = app
+            .borrow_mut()
+            .execute_contract(
+                sender.clone(),
+                pair.address.clone(),
+                &ExecuteMsg::Swap {
+                    offer_asset: offer_asset.clone(),
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                    min_receive: None,
+                    deadline: None,
+                    referral: None,
+                },
+                &[Coin {
+                    denom: "uusd".to_owned(),
+                    amount: offer_asset.amount,
+                }],
+            )
+            .unwrap();
+        let attribute = |key: &str| {
+            res.events
+                .iter()
+                .flat_map(|event| event.attributes.iter())
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.clone())
+        };
+
+        (
+            sim_res,
+            attribute("commission_amount").unwrap().parse().unwrap(),
+            attribute("fee_discount_bps"),
+        )
+    };
+
+    // Simulations don't include the discount, so a trader without xGRID pays the simulated fee
+    let (sim_res, commission_amount, fee_discount_bps) = swap(&trader);
+    assert_eq!(commission_amount, sim_res.commission_amount);
+    assert_eq!(fee_discount_bps, None);
+
+    // The commission is rounded up, so the halved fee may differ from half the simulated one by 1
+    let (sim_res, commission_amount, fee_discount_bps) = swap(&whale);
+    let half_commission = sim_res.commission_amount.u128() / 2;
+    assert!(commission_amount.u128().abs_diff(half_commission) <= 1);
+    assert_eq!(fee_discount_bps, Some("5000".to_owned()));
+
+    // The discounts can be removed
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            factory.address.clone(),
+            &FactoryExecuteMsg::SetFeeDiscounts {
+                pair_type: PairType::Xyk {},
+                staking_contract: staking.address.to_string(),
+                tiers: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let (sim_res, commission_amount, fee_discount_bps) = swap(&whale);
+    assert_eq!(commission_amount, sim_res.commission_amount);
+    assert_eq!(fee_discount_bps, None);
+}
+
+#[test]
+fn batch_swap() {
+    let gridiron = gridiron_address();
+    let trader = Addr::unchecked("trader");
+
+    let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
+        for (account, denoms) in [
+            (&gridiron, vec!["uusd", "ustake", "uluna"]),
+            (&trader, vec!["uusd", "ustake"]),
+        ] {
+            let coins = denoms
+                .into_iter()
+                .map(|denom| Coin {
+                    denom: denom.to_owned(),
+                    amount: Uint128::new(1_000_000_000000),
+                })
+                .collect();
+            router.bank.init_balance(storage, account, coins).unwrap();
+        }
+    })));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+    let uusd = native_asset_info("uusd".to_owned());
+    let ustake = native_asset_info("ustake".to_owned());
+    let uluna = native_asset_info("uluna".to_owned());
+
+    let mut pairs = vec![];
+    for (offer, ask) in [(&uusd, &ustake), (&ustake, &uluna)] {
+        let pair = MockXykPairBuilder::new(&app)
+            .with_factory(&factory)
+            .with_asset(offer)
+            .with_asset(ask)
+            .instantiate();
+        pair.provide(
+            &gridiron,
+            &[
+                offer.with_balance(1_000_000000u128),
+                ask.with_balance(2_000_000000u128),
+            ],
+            None,
+            false,
+            None,
+        );
+        pairs.push(pair);
+    }
+
+    let simulate = |pair: &Addr, offer_asset: Asset| -> Uint128 {
+        let res: SimulationResponse = app
+            .borrow()
+            .wrap()
+            .query_wasm_smart(
+                pair,
+                &QueryMsg::Simulation {
+                    offer_asset,
+                    ask_asset_info: None,
+                },
+            )
+            .unwrap();
+        res.return_amount
+    };
+    let operation =
+        |offer_asset_info: &AssetInfo, ask_asset_info: &AssetInfo| SwapOperation::GridSwap {
+            offer_asset_info: offer_asset_info.clone(),
+            ask_asset_info: ask_asset_info.clone(),
+        };
+    let batch_swap =
+        |operations: Vec<SwapOperation>, minimum_receive: Option<Uint128>, funds: &[Coin]| {
+            app.borrow_mut().execute_contract(
+                trader.clone(),
+                pairs[0].address.clone(),
+                &ExecuteMsg::BatchSwap {
+                    operations,
+                    minimum_receive,
+                    to: None,
+                    deadline: None,
+                },
+                funds,
+            )
+        };
+    let offer_funds = [Coin {
+        denom: "uusd".to_owned(),
+        amount: Uint128::new(10_000000),
+    }];
+
+    // The first hop is executed by the pair itself, the second one by the factory pair
+    let ustake_amount = simulate(&pairs[0].address, uusd.with_balance(10_000000u128));
+    let uluna_amount = simulate(&pairs[1].address, ustake.with_balance(ustake_amount));
+
+    let err = batch_swap(
+        vec![operation(&uusd, &ustake), operation(&ustake, &uluna)],
+        Some(uluna_amount + Uint128::one()),
+        &offer_funds,
+    )
+    .unwrap_err();
     assert_eq!(
-        generator.query_deposit(&pair.lp_token(), &gridiron),
-        Uint128::new(999_999000),
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MinReceiveAssertion {
+            min_receive: uluna_amount + Uint128::one(),
+            return_amount: uluna_amount,
+        }
+    );
+
+    let res = batch_swap(
+        vec![operation(&uusd, &ustake), operation(&ustake, &uluna)],
+        Some(uluna_amount),
+        &offer_funds,
+    )
+    .unwrap();
+    let batch_event = res
+        .events
+        .iter()
+        .find(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "action" && attr.value == "batch_swap")
+        })
+        .unwrap();
+    assert!(batch_event
+        .attributes
+        .contains(&attr("return_amount", uluna_amount)));
+    assert_eq!(
+        app.borrow()
+            .wrap()
+            .query_balance(&trader, "uluna")
+            .unwrap()
+            .amount,
+        uluna_amount
+    );
+    // Nothing is left in the pair and the next batch swap can start
+    assert_eq!(
+        app.borrow()
+            .wrap()
+            .query_balance(&pairs[0].address, "uluna")
+            .unwrap()
+            .amount,
+        Uint128::zero()
+    );
+
+    // The same pair can be used by several hops, the round trip only costs fees
+    let uusd_balance = || {
+        app.borrow()
+            .wrap()
+            .query_balance(&trader, "uusd")
+            .unwrap()
+            .amount
+    };
+    let uusd_before = uusd_balance();
+    batch_swap(
+        vec![operation(&uusd, &ustake), operation(&ustake, &uusd)],
+        None,
+        &offer_funds,
+    )
+    .unwrap();
+    let uusd_returned = uusd_balance() + offer_funds[0].amount - uusd_before;
+    assert!(!uusd_returned.is_zero() && uusd_returned < offer_funds[0].amount);
+
+    let err = batch_swap(vec![], None, &offer_funds).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBatchSwapLength {}
+    );
+
+    let err = batch_swap(
+        vec![operation(&uusd, &ustake); MAX_BATCH_SWAP_OPERATIONS + 1],
+        None,
+        &offer_funds,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBatchSwapLength {}
+    );
+
+    // Every hop must offer the asset returned by the previous one
+    let err = batch_swap(
+        vec![operation(&uusd, &ustake), operation(&uusd, &uluna)],
+        None,
+        &offer_funds,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBatchSwapOperation { index: 1 }
+    );
+
+    let err = batch_swap(vec![operation(&ustake, &uluna)], None, &offer_funds).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBatchSwapOperation { index: 0 }
+    );
+
+    let err = batch_swap(
+        vec![operation(&uusd, &ustake)],
+        None,
+        &[
+            offer_funds[0].clone(),
+            Coin {
+                denom: "ustake".to_owned(),
+                amount: Uint128::new(10_000000),
+            },
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssetMismatch {}
     );
 }
 
 #[test]
-fn test_imbalanced_withdraw_is_disabled() {
+fn test_imbalanced_withdraw() {
     let owner = Addr::unchecked("owner");
     let alice_address = Addr::unchecked("alice");
     let mut router = mock_app(
@@ -1823,36 +4137,128 @@ fn test_imbalanced_withdraw_is_disabled() {
         .execute_contract(alice_address.clone(), pair_instance.clone(), &msg, &coins)
         .unwrap();
 
-    // Check that imbalanced withdraw is currently disabled
-    let msg_imbalance = Cw20ExecuteMsg::Send {
+    let withdraw_msg = |amount: u128, assets: Vec<Asset>| Cw20ExecuteMsg::Send {
         contract: pair_instance.to_string(),
-        amount: Uint128::from(50u8),
+        amount: Uint128::new(amount),
         msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
-            assets: vec![Asset {
-                info: AssetInfo::NativeToken {
-                    denom: "uusd".to_string(),
-                },
-                amount: Uint128::from(100u8),
-            }],
+            assets,
+            deadline: None,
         })
         .unwrap(),
     };
+    let uusd = native_asset_info("uusd".to_string());
+    let uluna = native_asset_info("uluna".to_string());
 
+    // Withdrawing a whole reserve is not possible
     let err = router
-        .execute_contract(alice_address.clone(), lp_token.clone(), &msg_imbalance, &[])
+        .execute_contract(
+            alice_address.clone(),
+            lp_token.clone(),
+            &withdraw_msg(50_000_000, vec![uusd.with_balance(100_000_100u128)]),
+            &[],
+        )
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "Generic error: Imbalanced withdraw is currently disabled"
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::WithdrawExceedsReserves {}
+    );
+
+    // The assets must belong to the pair
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            lp_token.clone(),
+            &withdraw_msg(
+                50_000_000,
+                vec![native_asset_info("uatom".to_string()).with_balance(100u128)],
+            ),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssetMismatch {}
+    );
+
+    // The sent LP tokens are the maximum to burn
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            lp_token.clone(),
+            &withdraw_msg(5_000_000, vec![uusd.with_balance(10_000_000u128)]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotEnoughLpTokens {
+            required: Uint128::new(5_131671)
+        }
     );
+
+    // Withdraw only uusd, the unused LP tokens are sent back
+    router
+        .execute_contract(
+            alice_address.clone(),
+            lp_token.clone(),
+            &withdraw_msg(50_000_000, vec![uusd.with_balance(10_000_000u128)]),
+            &[],
+        )
+        .unwrap();
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &lp_token,
+            &Cw20QueryMsg::Balance {
+                address: alice_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::new(99_999_000 - 5_131671));
+    let balances = router.wrap().query_all_balances(&alice_address).unwrap();
+    assert_eq!(
+        balances,
+        vec![
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(99_999_900),
+            },
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(142_999_900),
+            },
+        ]
+    );
+
+    // Withdraw in a custom ratio
+    router
+        .execute_contract(
+            alice_address.clone(),
+            lp_token.clone(),
+            &withdraw_msg(
+                50_000_000,
+                vec![
+                    uluna.with_balance(5_000_000u128),
+                    uusd.with_balance(1_000_000u128),
+                ],
+            ),
+            &[],
+        )
+        .unwrap();
+    let res: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(res.assets[0].amount, Uint128::new(89_000_100));
+    assert_eq!(res.assets[1].amount, Uint128::new(95_000_100));
 }
 
 #[test]
 fn check_correct_fee_share() {
     // Validate the resulting values
     // We swapped 1_000000 of token X
-    // Fee is set to 0.3% of the swap amount resulting in 1000000 * 0.003 = 3000
-    // User receives with 1000000 - 3000 = 997000
+    // The return before fees is 999999,000001, the user receives 0.997 of it rounded down = 996999
+    // Fee is the remainder of the return resulting in 999999 - 996999 = 3000
     // Of the 3000 fee, 10% is sent to the fee sharing contract resulting in 300
     // Of the 2700 fee left, 33.33% is sent to the maker resulting in 899
     // Of the 1801 fee left, all of it is left in the pool
@@ -2079,6 +4485,8 @@ fn test_fee_share(
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -2103,7 +4511,10 @@ fn test_fee_share(
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: Some(user.to_string()),
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -2113,8 +4524,11 @@ fn test_fee_share(
     app.execute_contract(owner.clone(), token_x_instance.clone(), &swap_msg, &[])
         .unwrap();
 
-    let y_expected_return =
-        x_offer - Uint128::from((x_offer * Decimal::from_ratio(total_fee_bps, 10000u64)).u128());
+    // The return after fees is rounded down in favor of the pool
+    let y_expected_return = y_amount.multiply_ratio(
+        x_offer * Uint128::from(10000 - total_fee_bps),
+        (x_amount + x_offer) * Uint128::from(10000u16),
+    );
 
     let msg = Cw20QueryMsg::Balance {
         address: user.to_string(),
@@ -2136,8 +4550,7 @@ fn test_fee_share(
         .query_wasm_smart(&token_y_instance, &msg)
         .unwrap();
 
-    let acceptable_spread_amount = Uint128::new(1);
-    assert_eq!(res.balance, expected_fee_share - acceptable_spread_amount);
+    assert_eq!(res.balance, expected_fee_share);
 
     let msg = Cw20QueryMsg::Balance {
         address: maker_address.to_string(),
@@ -2156,12 +4569,10 @@ fn test_fee_share(
     let msg = QueryMsg::Pool {};
     let res: PoolResponse = app.wrap().query_wasm_smart(&pair_instance, &msg).unwrap();
 
-    let acceptable_spread_amount = Uint128::new(1);
     assert_eq!(res.assets[0].amount, x_amount + x_offer);
     assert_eq!(
         res.assets[1].amount,
         y_amount - y_expected_return - expected_maker_fee - expected_fee_share
-            + acceptable_spread_amount
     );
 
     // Assert LP balances tracked are correct
@@ -2176,7 +4587,6 @@ fn test_fee_share(
     assert_eq!(
         res.unwrap(),
         y_amount - y_expected_return - expected_maker_fee - expected_fee_share
-            + acceptable_spread_amount
     );
 }
 
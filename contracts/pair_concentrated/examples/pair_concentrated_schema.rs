@@ -1,5 +1,5 @@
-use gridiron::pair::{ExecuteMsg, InstantiateMsg, MigrateMsg};
-use gridiron::pair_concentrated::QueryMsg;
+use gridiron::pair::InstantiateMsg;
+use gridiron::pair_concentrated::{ExecuteMsg, MigrateMsg, QueryMsg};
 use cosmwasm_schema::write_api;
 
 fn main() {
@@ -5,45 +5,60 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, from_binary, wasm_execute, wasm_instantiate, Addr, Attribute, Binary, CosmosMsg, Decimal,
     Decimal256, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128,
+    SubMsgResponse, SubMsgResult, Uint128, Uint256,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use cw_utils::parse_instantiate_response_data;
 use itertools::Itertools;
 
+use gridiron::allowlist::query_is_allowed;
 use gridiron::asset::AssetInfoExt;
 use gridiron::asset::{
     addr_opt_validate, format_lp_token_name, token_asset, Asset, AssetInfo, CoinsExt,
-    Decimal256Ext, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    Decimal256Ext, PairCreatedAt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
 };
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use gridiron::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
 use gridiron::factory::PairType;
-use gridiron::observation::{PrecommitObservation, OBSERVATIONS_SIZE};
+use gridiron::maker::{ConfigResponse as MakerConfigResponse, QueryMsg as MakerQueryMsg};
+use gridiron::observation::{
+    PrecommitObservation, FEE_GROWTH_OBSERVATIONS_SIZE, OBSERVATIONS_SIZE,
+};
+use gridiron::oracle::QueryMsg as OracleQueryMsg;
 use gridiron::pair::{
-    Cw20HookMsg, ExecuteMsg, FeeShareConfig, InstantiateMsg, MAX_FEE_SHARE_BPS, MIN_TRADE_SIZE,
+    check_min_lp_to_receive, min_trade_size, Cw20HookMsg, FeeShareConfig, InstantiateMsg,
+    MinTradeSize, Referral, ReferralFee, MAX_FEE_SHARE_BPS, MAX_MIN_TRADE_FLOOR,
 };
 use gridiron::pair_concentrated::{
-    ConcentratedPoolParams, ConcentratedPoolUpdateParams, MigrateMsg, UpdatePoolParams,
+    ConcentratedPoolParams, ConcentratedPoolUpdateParams, ExecuteMsg, MigrateMsg, ReanchorConfig,
+    StakerFeeShareConfig, UpdatePoolParams, MAX_OBSERVATION_GRANULARITY, MAX_STAKER_FEE_SHARE_BPS,
+    MIN_REANCHOR_DORMANCY_PERIOD,
 };
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
+use gridiron::points::swap_hook_msg;
+use gridiron::querier::{query_factory_config, query_pair_fee_info, query_supply};
 use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
 use gridiron_circular_buffer::BufferManager;
 use gridiron_pcl_common::state::{
     AmpGamma, Config, PoolParams, PoolState, Precisions, PriceState,
 };
 use gridiron_pcl_common::utils::{
-    assert_max_spread, assert_slippage_tolerance, before_swap_check, calc_provide_fee,
-    check_asset_infos, check_assets, check_cw20_in_pool, check_pair_registered, compute_swap,
-    get_share_in_assets, mint_liquidity_token_message,
+    assert_max_spread, assert_min_receive, assert_slippage_tolerance, before_swap_check,
+    calc_provide_fee, check_asset_infos, check_assets, check_cw20_in_pool, check_pair_registered,
+    compute_swap, get_share_in_assets, mint_liquidity_token_message,
 };
 use gridiron_pcl_common::{calc_d, get_xcp};
 
 use crate::error::ContractError;
 use crate::migration::migrate_config;
-use crate::state::{BALANCES, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL};
-use crate::utils::{accumulate_swap_sizes, query_pools};
+use crate::state::{
+    BALANCES, CONFIG, FEE_GROWTH_OBSERVATIONS, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR, OBSERVATIONS,
+    OBSERVATION_GRANULARITY, OWNERSHIP_PROPOSAL, REANCHOR_CONFIG,
+};
+use crate::utils::{
+    accumulate_swap_sizes, assert_deadline, check_allowed, check_liquidity_enabled,
+    check_swaps_enabled, query_pools, save_fee_growth_snapshot,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -59,7 +74,7 @@ pub(crate) const LP_TOKEN_PRECISION: u8 = 6;
 pub fn instantiate(
     mut deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     if msg.asset_infos.len() != 2 {
@@ -108,12 +123,17 @@ pub fn instantiate(
         },
     };
 
+    let creator = addr_opt_validate(deps.api, &msg.creator)?.unwrap_or(info.sender);
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("concentrated".to_string()),
+            creator: Some(creator),
+            created_at: Some(PairCreatedAt::from(&env.block)),
+            allowlist: None,
         },
         factory_addr,
         pool_params,
@@ -121,6 +141,7 @@ pub fn instantiate(
         owner: None,
         track_asset_balances: params.track_asset_balances.unwrap_or_default(),
         fee_share: None,
+        staker_fee_share: None,
     };
 
     if config.track_asset_balances {
@@ -132,6 +153,11 @@ pub fn instantiate(
     CONFIG.save(deps.storage, &config)?;
 
     BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+    BufferManager::init(
+        deps.storage,
+        FEE_GROWTH_OBSERVATIONS,
+        FEE_GROWTH_OBSERVATIONS_SIZE,
+    )?;
 
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
@@ -209,14 +235,22 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             deadline,
+///             min_lp_to_receive,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
 ///             offer_asset,
 ///             belief_price,
 ///             max_spread,
+///             min_receive,
 ///             to,
+///             deadline,
+///             referral,
 ///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::Reanchor {}** Moves price_scale towards the price of the re-anchor oracle
+/// after a long period without trades.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -233,22 +267,33 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-        ),
+            deadline,
+            min_lp_to_receive,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
+            min_receive,
             to,
-            ..
+            deadline,
+            referral,
         } => {
+            assert_deadline(&env, deadline)?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -268,7 +313,9 @@ pub fn execute(
                 offer_asset,
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
             )
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
@@ -308,6 +355,7 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::Reanchor {} => reanchor(deps, env),
     }
 }
 
@@ -324,9 +372,13 @@ fn receive_cw20(
         Cw20HookMsg::Swap {
             belief_price,
             max_spread,
+            min_receive,
             to,
-            ..
+            deadline,
+            referral,
         } => {
+            assert_deadline(&env, deadline)?;
+
             let config = CONFIG.load(deps.storage)?;
 
             // Only asset contract can execute this message
@@ -340,17 +392,24 @@ fn receive_cw20(
                 token_asset(info.sender, cw20_msg.amount),
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
+            )
+        }
+        Cw20HookMsg::BatchSwap { .. } => Err(ContractError::NotSupported {}),
+        Cw20HookMsg::WithdrawLiquidity { assets, deadline } => {
+            assert_deadline(&env, deadline)?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                cw20_msg.amount,
+                assets,
             )
         }
-        Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
-            deps,
-            env,
-            info,
-            Addr::unchecked(cw20_msg.sender),
-            cw20_msg.amount,
-            assets,
-        ),
     }
 }
 
@@ -367,7 +426,10 @@ fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **min_lp_to_receive** is an optional minimum amount of LP tokens minted for the receiver.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
@@ -376,8 +438,11 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &info.sender)?;
+    check_liquidity_enabled(deps.storage, &env)?;
 
     if !check_pair_registered(
         deps.querier,
@@ -524,7 +589,9 @@ pub fn provide_liquidity(
     let mut slippage = Decimal256::zero();
 
     // If deposit doesn't diverge too much from the balanced share, we don't update the price
-    if assets_diff[0] >= MIN_TRADE_SIZE && assets_diff[1] >= MIN_TRADE_SIZE {
+    if assets_diff[0] >= min_trade_size(precisions.get_precision(&pools[0].info)?)
+        && assets_diff[1] >= min_trade_size(precisions.get_precision(&pools[1].info)?)
+    {
         slippage = assert_slippage_tolerance(
             &deposits,
             share,
@@ -543,6 +610,7 @@ pub fn provide_liquidity(
     }
 
     let share_uint128 = share.to_uint(LP_TOKEN_PRECISION)?;
+    check_min_lp_to_receive(share_uint128, min_lp_to_receive)?;
 
     // Mint LP tokens for the sender or for the receiver (if set)
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
@@ -570,6 +638,8 @@ pub fn provide_liquidity(
         }
     }
 
+    save_fee_growth_snapshot(deps.storage, &env, config.pool_state.price_state.xcp_profit)?;
+
     CONFIG.save(deps.storage, &config)?;
 
     let attrs = vec![
@@ -604,6 +674,7 @@ fn withdraw_liquidity(
     if info.sender != config.pair_info.liquidity_token {
         return Err(ContractError::Unauthorized {});
     }
+    check_liquidity_enabled(deps.storage, &env)?;
 
     let precisions = Precisions::new(deps.storage)?;
     let pools = query_pools(
@@ -677,6 +748,8 @@ fn withdraw_liquidity(
         }
     }
 
+    save_fee_growth_snapshot(deps.storage, &env, config.pool_state.price_state.xcp_profit)?;
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
@@ -698,7 +771,13 @@ fn withdraw_liquidity(
 ///
 /// * **max_spread** sets the maximum spread of the swap operation.
 ///
+/// * **min_receive** sets the minimum amount of ask tokens to receive. When set, it replaces
+/// the `belief_price` and `max_spread` check.
+///
 /// * **to** sets the recipient of the swap operation.
+///
+/// * **referral** sets the referral which receives a share of the return amount.
+#[allow(clippy::too_many_arguments)]
 fn swap(
     deps: DepsMut,
     env: Env,
@@ -706,12 +785,29 @@ fn swap(
     offer_asset: Asset,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
     to: Option<Addr>,
+    referral: Option<Referral>,
 ) -> Result<Response, ContractError> {
     let precisions = Precisions::new(deps.storage)?;
     let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
     let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
     let mut config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &sender)?;
+    check_swaps_enabled(deps.storage, &env)?;
+
+    let min_swap = MinTradeSize::new(
+        offer_asset.info.clone(),
+        offer_asset_prec,
+        MIN_TRADE_FLOOR.may_load(deps.storage)?,
+    )?
+    .swap;
+    if offer_asset.amount < min_swap {
+        return Err(ContractError::BelowMinTradeSize {
+            amount: offer_asset.amount,
+            min: min_swap,
+        });
+    }
 
     let mut pools = query_pools(deps.querier, &env.contract.address, &config, &precisions)?;
 
@@ -729,10 +825,10 @@ fn swap(
     let mut xs = pools.iter().map(|asset| asset.amount).collect_vec();
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let mut maker_fee_share = Decimal256::zero();
     if fee_info.fee_address.is_some() {
@@ -753,26 +849,46 @@ fn swap(
         maker_fee_share,
         share_fee_share,
     )?;
+
+    // The xGRID stakers' share is taken from the part of the fee which would stay in the pool
+    let staker_fee_dec = match &config.staker_fee_share {
+        Some(staker_fee_share) => {
+            (swap_result.total_fee - swap_result.share_fee - swap_result.maker_fee)
+                * Decimal256::from_ratio(staker_fee_share.bps, 10000u16)
+        }
+        None => Decimal256::zero(),
+    };
+
     xs[offer_ind] += offer_asset_dec.amount;
-    xs[ask_ind] -= swap_result.dy + swap_result.maker_fee + swap_result.share_fee;
+    xs[ask_ind] -= swap_result.dy + swap_result.maker_fee + swap_result.share_fee + staker_fee_dec;
 
     let return_amount = swap_result.dy.to_uint(ask_asset_prec)?;
     let spread_amount = swap_result.spread_fee.to_uint(ask_asset_prec)?;
-    assert_max_spread(
-        belief_price,
-        max_spread,
-        offer_asset.amount,
-        return_amount,
-        spread_amount,
-    )?;
+    // The referral fee is paid out of the return amount, so the pool balances are not affected
+    let referral_fee =
+        ReferralFee::new(deps.api, referral, fee_info.max_referral_bps, return_amount)?;
+    let receive_amount = return_amount - referral_fee.amount;
+    if let Some(min_receive) = min_receive {
+        assert_min_receive(min_receive, receive_amount)?;
+    } else {
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_asset.amount,
+            swap_result.dy - referral_fee.amount.to_decimal256(ask_asset_prec)?,
+            swap_result.spread_fee,
+            ask_asset_prec,
+        )?;
+    }
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
         .to_decimal256(LP_TOKEN_PRECISION)?;
 
     // Skip very small trade sizes which could significantly mess up the price due to rounding errors,
     // especially if token precisions are 18.
-    if (swap_result.dy + swap_result.maker_fee + swap_result.share_fee) >= MIN_TRADE_SIZE
-        && offer_asset_dec.amount >= MIN_TRADE_SIZE
+    if (swap_result.dy + swap_result.maker_fee + swap_result.share_fee)
+        >= min_trade_size(ask_asset_prec)
+        && offer_asset_dec.amount >= min_trade_size(offer_asset_prec)
     {
         let last_price = swap_result.calc_last_price(offer_asset_dec.amount, offer_ind);
 
@@ -787,9 +903,10 @@ fn swap(
 
     let mut messages = vec![Asset {
         info: pools[ask_ind].info.clone(),
-        amount: return_amount,
+        amount: receive_amount,
     }
     .into_msg(&receiver)?];
+    messages.extend(referral_fee.into_msg(&pools[ask_ind].info)?);
 
     // Send the shared fee
     let mut fee_share_amount = Uint128::zero();
@@ -811,13 +928,36 @@ fn swap(
         }
     }
 
+    // Send the xGRID stakers' fee. GRID goes to the staking contract directly while other
+    // assets are sent to the Maker to be converted to GRID.
+    let mut staker_fee = Uint128::zero();
+    if let Some(staker_fee_share) = config.staker_fee_share.clone() {
+        staker_fee = staker_fee_dec.to_uint(ask_asset_prec)?;
+        if !staker_fee.is_zero() {
+            let recipient = if pools[ask_ind].info == staker_fee_share.grid_token {
+                staker_fee_share.staking_contract
+            } else {
+                staker_fee_share.maker
+            };
+            let fee = pools[ask_ind].info.with_balance(staker_fee);
+            messages.push(fee.into_msg(recipient)?);
+        }
+    }
+
+    // Report the swap to the points contract (if any)
+    if let Some(points_contract) = &fee_info.points_contract {
+        messages.push(swap_hook_msg(points_contract, &receiver, &offer_asset)?);
+    }
+
     // Store observation from precommit data
     accumulate_swap_sizes(deps.storage, &env)?;
 
     // Store time series data in precommit observation.
     // Skipping small unsafe values which can seriously mess oracle price due to rounding errors.
     // This data will be reflected in observations in the next action.
-    if offer_asset_dec.amount >= MIN_TRADE_SIZE && swap_result.dy >= MIN_TRADE_SIZE {
+    if offer_asset_dec.amount >= min_trade_size(offer_asset_prec)
+        && swap_result.dy >= min_trade_size(ask_asset_prec)
+    {
         let (base_amount, quote_amount) = if offer_ind == 0 {
             (offer_asset.amount, return_amount)
         } else {
@@ -826,6 +966,8 @@ fn swap(
         PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
     }
 
+    save_fee_growth_snapshot(deps.storage, &env, config.pool_state.price_state.xcp_profit)?;
+
     CONFIG.save(deps.storage, &config)?;
 
     if config.track_asset_balances {
@@ -841,27 +983,32 @@ fn swap(
             &(pools[ask_ind].amount.to_uint(ask_asset_prec)?
                 - return_amount
                 - maker_fee
-                - fee_share_amount),
+                - fee_share_amount
+                - staker_fee),
             env.block.height,
         )?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "swap"),
-        attr("sender", sender),
-        attr("receiver", receiver),
-        attr("offer_asset", offer_asset_dec.info.to_string()),
-        attr("ask_asset", pools[ask_ind].info.to_string()),
-        attr("offer_amount", offer_asset.amount),
-        attr("return_amount", return_amount),
-        attr("spread_amount", spread_amount),
-        attr(
-            "commission_amount",
-            swap_result.total_fee.to_uint(ask_asset_prec)?,
-        ),
-        attr("maker_fee_amount", maker_fee),
-        attr("fee_share_amount", fee_share_amount),
-    ]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "swap"),
+            attr("sender", sender),
+            attr("receiver", receiver),
+            attr("offer_asset", offer_asset_dec.info.to_string()),
+            attr("ask_asset", pools[ask_ind].info.to_string()),
+            attr("offer_amount", offer_asset.amount),
+            attr("return_amount", receive_amount),
+            attr("spread_amount", spread_amount),
+            attr(
+                "commission_amount",
+                swap_result.total_fee.to_uint(ask_asset_prec)?,
+            ),
+            attr("maker_fee_amount", maker_fee),
+            attr("fee_share_amount", fee_share_amount),
+            attr("staker_fee_amount", staker_fee),
+        ])
+        .add_attributes(referral_fee.attributes()))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -877,10 +1024,12 @@ fn update_config(
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
     let owner = config.owner.as_ref().unwrap_or(&factory_config.owner);
-    if info.sender != *owner {
+    // The factory is allowed to disable fee sharing on behalf of the protocol
+    if info.sender != *owner && info.sender != config.factory_addr {
         return Err(ContractError::Unauthorized {});
     }
 
+    let previous_fees = config.combined_fees();
     let mut attrs: Vec<Attribute> = vec![];
 
     let action = match from_binary::<ConcentratedPoolUpdateParams>(&params)? {
@@ -944,7 +1093,135 @@ fn update_config(
             CONFIG.save(deps.storage, &config)?;
             "disable_fee_share"
         }
+        ConcentratedPoolUpdateParams::EnableStakerFeeShare {
+            staker_fee_share_bps,
+        } => {
+            if staker_fee_share_bps == 0 || staker_fee_share_bps > MAX_STAKER_FEE_SHARE_BPS {
+                return Err(ContractError::StakerFeeShareOutOfBounds {});
+            }
+
+            // xGRID stakers are reached via the Maker configured in the factory
+            let maker = factory_config
+                .fee_address
+                .ok_or(ContractError::StakingContractNotFound {})?;
+            let maker_config: MakerConfigResponse = deps
+                .querier
+                .query_wasm_smart(&maker, &MakerQueryMsg::Config {})?;
+            let staking_contract = maker_config
+                .staking_contract
+                .ok_or(ContractError::StakingContractNotFound {})?;
+
+            attrs.push(attr(
+                "staker_fee_share_bps",
+                staker_fee_share_bps.to_string(),
+            ));
+            attrs.push(attr("staking_contract", &staking_contract));
+
+            config.staker_fee_share = Some(StakerFeeShareConfig {
+                bps: staker_fee_share_bps,
+                staking_contract,
+                grid_token: maker_config.grid_token,
+                maker,
+            });
+            "enable_staker_fee_share"
+        }
+        ConcentratedPoolUpdateParams::DisableStakerFeeShare {} => {
+            config.staker_fee_share = None;
+            "disable_staker_fee_share"
+        }
+        ConcentratedPoolUpdateParams::UpdateMinTradeFloor { min_trade_floor } => {
+            if let Some(floor) = min_trade_floor {
+                if floor.is_zero() || floor > MAX_MIN_TRADE_FLOOR {
+                    return Err(ContractError::MinTradeFloorOutOfBounds {});
+                }
+
+                MIN_TRADE_FLOOR.save(deps.storage, &floor)?;
+                attrs.push(attr("min_trade_floor", floor.to_string()));
+            } else {
+                MIN_TRADE_FLOOR.remove(deps.storage);
+            }
+            "update_min_trade_floor"
+        }
+        ConcentratedPoolUpdateParams::UpdateAllowlist { allowlist } => {
+            config.pair_info.allowlist = addr_opt_validate(deps.api, &allowlist)?;
+            if let Some(allowlist) = &config.pair_info.allowlist {
+                // Make sure the allowlist answers the standard query so the pair isn't bricked
+                query_is_allowed(&deps.querier, allowlist, &env.contract.address)?;
+                attrs.push(attr("allowlist", allowlist));
+            }
+            "update_allowlist"
+        }
+        ConcentratedPoolUpdateParams::ScheduleMaintenance { window } => {
+            if let Some(window) = window {
+                if !window.is_valid(env.block.time.seconds()) {
+                    return Err(ContractError::InvalidMaintenanceWindow {});
+                }
+
+                attrs.extend([
+                    attr("maintenance_mode", window.mode.to_string()),
+                    attr("maintenance_start", window.start.to_string()),
+                    attr("maintenance_end", window.end.to_string()),
+                ]);
+                MAINTENANCE_WINDOW.save(deps.storage, &window)?;
+                "schedule_maintenance"
+            } else {
+                MAINTENANCE_WINDOW.remove(deps.storage);
+                "cancel_maintenance"
+            }
+        }
+        ConcentratedPoolUpdateParams::UpdateObservationGranularity { granularity } => {
+            if granularity > MAX_OBSERVATION_GRANULARITY {
+                return Err(ContractError::ObservationGranularityOutOfBounds {});
+            }
+
+            OBSERVATION_GRANULARITY.save(deps.storage, &granularity)?;
+            attrs.push(attr("observation_granularity", granularity.to_string()));
+            "update_observation_granularity"
+        }
+        ConcentratedPoolUpdateParams::UpdateReanchorConfig { reanchor_config } => {
+            if let Some(params) = reanchor_config {
+                if params.dormancy_period < MIN_REANCHOR_DORMANCY_PERIOD
+                    || params.deviation_threshold.is_zero()
+                    || params.deviation_threshold >= Decimal::one()
+                    || params.max_step.is_zero()
+                    || params.max_step >= Decimal::one()
+                {
+                    return Err(ContractError::ReanchorConfigOutOfBounds {});
+                }
+
+                let reanchor_config = ReanchorConfig {
+                    oracle: deps.api.addr_validate(&params.oracle)?,
+                    dormancy_period: params.dormancy_period,
+                    deviation_threshold: params.deviation_threshold,
+                    max_step: params.max_step,
+                };
+                attrs.extend([
+                    attr("oracle", &reanchor_config.oracle),
+                    attr("dormancy_period", params.dormancy_period.to_string()),
+                    attr(
+                        "deviation_threshold",
+                        params.deviation_threshold.to_string(),
+                    ),
+                    attr("max_step", params.max_step.to_string()),
+                ]);
+                REANCHOR_CONFIG.save(deps.storage, &reanchor_config)?;
+            } else {
+                REANCHOR_CONFIG.remove(deps.storage);
+            }
+            "update_reanchor_config"
+        }
     };
+
+    // Config updates can't raise the combined fees above the cap set in the factory
+    if let Some(max_combined_fee_bps) = factory_config.max_combined_fee_bps {
+        let fees = config.combined_fees();
+        if fees.exceed_cap(&previous_fees, max_combined_fee_bps) {
+            return Err(ContractError::CombinedFeeExceedsCap {
+                combined_fee_bps: fees.combined_fee_bps(),
+                max_combined_fee_bps,
+            });
+        }
+    }
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
@@ -952,6 +1229,88 @@ fn update_config(
         .add_attributes(attrs))
 }
 
+/// Moves price_scale towards the price of the re-anchor oracle by at most the configured step.
+/// Anyone can call it once the pool had no swaps or provisions for the configured dormancy period
+/// and price_scale deviates from the oracle price by more than the configured threshold.
+fn reanchor(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let reanchor_config = REANCHOR_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ReanchorDisabled {})?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if env.block.time.seconds()
+        < config.pool_state.price_state.last_price_update + reanchor_config.dormancy_period
+    {
+        return Err(ContractError::PoolNotDormant {
+            dormancy_period: reanchor_config.dormancy_period,
+        });
+    }
+
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(deps.querier, &env.contract.address, &config, &precisions)?;
+    let base_precision = precisions.get_precision(&pools[1].info)?;
+    let quote_precision = precisions.get_precision(&pools[0].info)?;
+
+    // The oracle price of one 2nd asset in the 1st one has the same meaning as price_scale
+    let prices: Vec<(AssetInfo, Uint256)> = deps.querier.query_wasm_smart(
+        &reanchor_config.oracle,
+        &OracleQueryMsg::Consult {
+            token: pools[1].info.clone(),
+            amount: Uint128::new(10u128.pow(base_precision.into())),
+        },
+    )?;
+    let oracle_price = prices
+        .into_iter()
+        .find(|(info, _)| *info == pools[0].info)
+        .map(|(_, amount)| Decimal256::with_precision(amount, quote_precision))
+        .transpose()?
+        .filter(|price| !price.is_zero())
+        .ok_or(ContractError::InvalidOraclePrice {})?;
+
+    let old_price_scale = config.pool_state.price_state.price_scale;
+    if (oracle_price / old_price_scale).diff(Decimal256::one())
+        < Decimal256::from(reanchor_config.deviation_threshold)
+    {
+        return Err(ContractError::DeviationBelowThreshold {});
+    }
+
+    // Limit the price scale change per call
+    let max_step = Decimal256::from(reanchor_config.max_step);
+    let price_scale = oracle_price
+        .max(old_price_scale * (Decimal256::one() - max_step))
+        .min(old_price_scale * (Decimal256::one() + max_step));
+
+    // Real profit is recalculated with the new price scale, otherwise the next swap
+    // would see it as a loss or an unearned profit
+    if !config.pool_state.price_state.xcp_profit_real.is_zero() {
+        let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
+            .to_decimal256(LP_TOKEN_PRECISION)?;
+        let xs = [pools[0].amount, pools[1].amount * price_scale];
+        let d = calc_d(&xs, &config.pool_state.get_amp_gamma(&env))?;
+        let xcp_profit_real = get_xcp(d, price_scale) / total_share;
+        if xcp_profit_real < Decimal256::one() {
+            return Err(ContractError::ReanchorMakesLoss {});
+        }
+
+        config.pool_state.price_state.xcp_profit_real = xcp_profit_real;
+    }
+
+    // The dormancy timer isn't reset, so a large deviation can be closed in several calls
+    let price_state = &mut config.pool_state.price_state;
+    price_state.price_scale = price_scale;
+    price_state.oracle_price = price_scale;
+    price_state.last_price = price_scale;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "reanchor"),
+        attr("oracle_price", oracle_price.to_string()),
+        attr("old_price_scale", old_price_scale.to_string()),
+        attr("new_price_scale", price_scale.to_string()),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
@@ -961,7 +1320,20 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             "1.2.13" | "1.2.14" => {
                 migrate_config(deps.storage)?;
                 BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+                BufferManager::init(
+                    deps.storage,
+                    FEE_GROWTH_OBSERVATIONS,
+                    FEE_GROWTH_OBSERVATIONS_SIZE,
+                )?;
+            }
+            "2.3.0" => {
+                BufferManager::init(
+                    deps.storage,
+                    FEE_GROWTH_OBSERVATIONS,
+                    FEE_GROWTH_OBSERVATIONS_SIZE,
+                )?;
             }
+            "2.4.0" | "2.5.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -1,7 +1,13 @@
-use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{Addr, ConversionOverflowError, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
-use gridiron::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
+use gridiron::{
+    asset::MINIMUM_LIQUIDITY_AMOUNT,
+    pair::{ProvideLiquidityError, ReferralError, MAX_FEE_SHARE_BPS, MAX_MIN_TRADE_FLOOR},
+    pair_concentrated::{
+        MAX_OBSERVATION_GRANULARITY, MAX_STAKER_FEE_SHARE_BPS, MIN_REANCHOR_DORMANCY_PERIOD,
+    },
+};
 use gridiron_circular_buffer::error::BufferError;
 use gridiron_pcl_common::error::PclError;
 
@@ -23,9 +29,69 @@ pub enum ContractError {
     #[error("{0}")]
     PclError(#[from] PclError),
 
+    #[error("{0}")]
+    ProvideLiquidity(#[from] ProvideLiquidityError),
+
+    #[error("{0}")]
+    Referral(#[from] ReferralError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Operation is not supported")]
+    NotSupported {},
+
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
+    #[error("Swap amount {amount} is below the min trade size {min}")]
+    BelowMinTradeSize { amount: Uint128, min: Uint128 },
+
+    #[error(
+        "Min trade floor must be greater than zero and at most {}",
+        MAX_MIN_TRADE_FLOOR
+    )]
+    MinTradeFloorOutOfBounds {},
+
+    #[error(
+        "Observation granularity must be at most {} seconds",
+        MAX_OBSERVATION_GRANULARITY
+    )]
+    ObservationGranularityOutOfBounds {},
+
+    #[error(
+        "Re-anchor dormancy period must be at least {} seconds, threshold and step below one",
+        MIN_REANCHOR_DORMANCY_PERIOD
+    )]
+    ReanchorConfigOutOfBounds {},
+
+    #[error("Re-anchoring is not enabled for this pool")]
+    ReanchorDisabled {},
+
+    #[error("The pool can be re-anchored only after {dormancy_period} seconds without trades")]
+    PoolNotDormant { dormancy_period: u64 },
+
+    #[error("Price scale deviates from the oracle price less than the re-anchor threshold")]
+    DeviationBelowThreshold {},
+
+    #[error("The re-anchor oracle returned no price for the pool assets")]
+    InvalidOraclePrice {},
+
+    #[error("Re-anchoring would drop the pool value below the initial one")]
+    ReanchorMakesLoss {},
+
+    #[error("Address {address} is not approved by the pair allowlist")]
+    AddressNotAllowed { address: Addr },
+
+    #[error("Maintenance window must end after it starts and after the current block time")]
+    InvalidMaintenanceWindow {},
+
+    #[error("Swaps are halted for maintenance until {end}")]
+    SwapsHaltedForMaintenance { end: u64 },
+
+    #[error("Liquidity provision and withdrawals are halted for maintenance until {end}")]
+    LiquidityHaltedForMaintenance { end: u64 },
+
     #[error("CW20 tokens can be swapped via Cw20::Send message only")]
     Cw20DirectSwap {},
 
@@ -61,4 +127,19 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error(
+        "Staker fee share is 0 or exceeds maximum allowed value of {} bps",
+        MAX_STAKER_FEE_SHARE_BPS
+    )]
+    StakerFeeShareOutOfBounds {},
+
+    #[error("Staker fee share requires the Maker with an xGRID staking contract")]
+    StakingContractNotFound {},
+
+    #[error("Combined fee {combined_fee_bps} bps exceeds the cap of {max_combined_fee_bps} bps")]
+    CombinedFeeExceedsCap {
+        combined_fee_bps: u32,
+        max_combined_fee_bps: u16,
+    },
 }
@@ -14,6 +14,7 @@ pub(crate) fn migrate_config(storage: &mut dyn Storage) -> Result<(), StdError>
         owner: old_config.owner,
         track_asset_balances: old_config.track_asset_balances,
         fee_share: None,
+        staker_fee_share: None,
     };
 
     CONFIG.save(storage, &new_config)?;
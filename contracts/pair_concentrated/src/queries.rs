@@ -7,13 +7,18 @@ use itertools::Itertools;
 
 use gridiron::asset::{Asset, AssetInfo};
 use gridiron::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use gridiron::observation::query_observation;
+use gridiron::observation::{query_observation, query_volatility, SECONDS_PER_YEAR};
 use gridiron::pair::{
-    ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, MinTradeSize, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    INTERFACE_DEADLINE, INTERFACE_MIN_LP_TO_RECEIVE, INTERFACE_OBSERVATIONS, INTERFACE_REANCHOR,
+    INTERFACE_REFERRAL, INTERFACE_VERSIONED_QUERIES,
 };
 
-use gridiron::pair_concentrated::{ConcentratedPoolConfig, QueryMsg};
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
+use gridiron::pair_concentrated::{
+    ConcentratedPoolConfig, FeeGrowthResponse, ObservationsInfoResponse, QueryMsg,
+};
+use gridiron::querier::{query_factory_config, query_pair_fee_info, query_supply};
+use gridiron_circular_buffer::BufferManager;
 
 use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
@@ -23,9 +28,21 @@ use gridiron_pcl_common::utils::{
 };
 use gridiron_pcl_common::{calc_d, get_xcp};
 
-use crate::state::{BALANCES, CONFIG, OBSERVATIONS};
+use crate::state::{
+    BALANCES, CONFIG, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR, OBSERVATIONS, OBSERVATION_GRANULARITY,
+    REANCHOR_CONFIG,
+};
+
+use crate::utils::{find_fee_growth_snapshot, pool_info, query_pools};
 
-use crate::utils::{pool_info, query_pools};
+gridiron::supported_interfaces!(
+    INTERFACE_DEADLINE,
+    INTERFACE_MIN_LP_TO_RECEIVE,
+    INTERFACE_OBSERVATIONS,
+    INTERFACE_REANCHOR,
+    INTERFACE_REFERRAL,
+    INTERFACE_VERSIONED_QUERIES,
+);
 
 /// Exposes all the queries available in the contract.
 ///
@@ -38,6 +55,9 @@ use crate::utils::{pool_info, query_pools};
 /// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 ///
+/// * **QueryMsg::ShareValueIn { amount, asset_info }** Returns the value of a specific amount of LP tokens
+/// denominated in one of the pool assets using an [`Asset`] object.
+///
 /// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
 ///
 /// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation  using
@@ -50,6 +70,24 @@ use crate::utils::{pool_info, query_pools};
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified
 /// asset that was in the pool just preceding the moment of the specified block height creation.
+///
+/// * **QueryMsg::FeeGrowth { seconds_ago }** Returns realized LP fee growth between now and
+/// the moment `seconds_ago` seconds ago using a [`FeeGrowthResponse`] object.
+///
+/// * **QueryMsg::ObservationsInfo {}** Returns the observation granularity and the time range
+/// covered by stored price observations using an [`ObservationsInfoResponse`] object.
+///
+/// * **QueryMsg::Volatility { window }** Returns the annualized realized volatility of the pool
+/// price over the last `window` seconds using a [`VolatilityResponse`] object.
+///
+/// * **QueryMsg::ReanchorConfig {}** Returns the parameters of price scale re-anchoring if it is
+/// enabled.
+///
+/// * **QueryMsg::VersionedPair {}**, **QueryMsg::VersionedPool {}**, **QueryMsg::VersionedConfig {}**
+/// and **QueryMsg::VersionedSimulation { .. }** Answer the `pair`, `pool`, `config` and `simulation`
+/// queries with the response wrapped into a [`gridiron::versioned::Versioned`] envelope.
+///
+/// * **QueryMsg::SupportsInterface { interface }** Returns whether the pair implements the interface.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -58,6 +96,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Share { amount } => to_binary(
             &query_share(deps, amount).map_err(|err| StdError::generic_err(err.to_string()))?,
         ),
+        QueryMsg::ShareValueIn { amount, asset_info } => to_binary(
+            &query_share_value_in(deps, amount, asset_info)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
         QueryMsg::Simulation { offer_asset, .. } => to_binary(
             &query_simulation(deps, env, offer_asset)
                 .map_err(|err| StdError::generic_err(format!("{err}")))?,
@@ -72,6 +114,19 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Observe { seconds_ago } => {
             to_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
         }
+        QueryMsg::FeeGrowth { seconds_ago } => {
+            to_binary(&query_fee_growth(deps, env, seconds_ago)?)
+        }
+        QueryMsg::ObservationsInfo {} => to_binary(&query_observations_info(deps)?),
+        QueryMsg::Volatility { window } => {
+            to_binary(&query_volatility(deps, env, OBSERVATIONS, window)?)
+        }
+        QueryMsg::ReanchorConfig {} => to_binary(&REANCHOR_CONFIG.may_load(deps.storage)?),
+        msg @ (QueryMsg::VersionedPair {}
+        | QueryMsg::VersionedPool {}
+        | QueryMsg::VersionedConfig {}
+        | QueryMsg::VersionedSimulation { .. }) => msg.query_versioned(|msg| query(deps, env, msg)),
+        QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
         QueryMsg::Config {} => to_binary(&query_config(deps, env)?),
         QueryMsg::LpPrice {} => to_binary(&query_lp_price(deps, env)?),
         QueryMsg::ComputeD {} => to_binary(&query_compute_d(deps, env)?),
@@ -128,6 +183,44 @@ fn query_share(deps: Deps, amount: Uint128) -> Result<Vec<Asset>, ContractError>
     Ok(refund_assets)
 }
 
+/// Returns the value of a specific amount of LP tokens denominated in one of the pool assets.
+/// The other asset is priced using the current price scale.
+///
+/// * **amount** is the amount of LP tokens to value.
+///
+/// * **asset_info** is the pool asset in which the value is denominated.
+fn query_share_value_in(
+    deps: Deps,
+    amount: Uint128,
+    asset_info: AssetInfo,
+) -> Result<Asset, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(
+        deps.querier,
+        &config.pair_info.contract_addr,
+        &config,
+        &precisions,
+    )?;
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let share = get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share);
+
+    // The price scale is the price of the second asset denominated in the first one
+    let price_scale = config.pool_state.price_state.price_scale;
+    let value = if asset_info.equal(&share[0].info) {
+        share[0].amount + share[1].amount * price_scale
+    } else if asset_info.equal(&share[1].info) {
+        share[1].amount + share[0].amount / price_scale
+    } else {
+        return Err(StdError::generic_err("Given asset does not belong in the pair").into());
+    };
+
+    Ok(Asset {
+        amount: value.to_uint(precisions.get_precision(&asset_info)?)?,
+        info: asset_info,
+    })
+}
+
 /// Returns information about a swap simulation.
 pub fn query_simulation(
     deps: Deps,
@@ -153,10 +246,10 @@ pub fn query_simulation(
     let xs = pools.iter().map(|asset| asset.amount).collect_vec();
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let mut maker_fee_share = Decimal256::zero();
     if fee_info.fee_address.is_some() {
@@ -252,6 +345,20 @@ pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
 
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
+    let precisions = Precisions::new(deps.storage)?;
+    let min_trade_floor = MIN_TRADE_FLOOR.may_load(deps.storage)?;
+    let min_trade_sizes = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| {
+            let precision = precisions
+                .get_precision(asset_info)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            MinTradeSize::new(asset_info.clone(), precision, min_trade_floor)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
     Ok(ConfigResponse {
         block_time_last: 0, // keeping this field for backwards compatibility
         params: Some(to_binary(&ConcentratedPoolConfig {
@@ -266,9 +373,14 @@ pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
             ma_half_time: config.pool_params.ma_half_time,
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            staker_fee_share: config.staker_fee_share,
         })?),
         owner: config.owner.unwrap_or(factory_config.owner),
         factory_addr: config.factory_addr,
+        creator: config.pair_info.creator,
+        created_at: config.pair_info.created_at,
+        min_trade_sizes,
+        maintenance_window: MAINTENANCE_WINDOW.may_load(deps.storage)?,
     })
 }
 
@@ -304,12 +416,74 @@ pub fn query_asset_balances_at(
     BALANCES.may_load_at_height(deps.storage, &asset_info, block_height.u64())
 }
 
+/// Returns realized LP fee growth between the latest snapshot taken not later than
+/// `seconds_ago` seconds ago and the current pool state.
+/// The growth is annualized linearly to get APR.
+fn query_fee_growth(deps: Deps, env: Env, seconds_ago: u64) -> StdResult<FeeGrowthResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let block_time = env.block.time.seconds();
+    let target = block_time.saturating_sub(seconds_ago);
+
+    let snapshot = find_fee_growth_snapshot(deps.storage, target)?;
+    let xcp_profit = config.pool_state.price_state.xcp_profit;
+
+    let growth = (xcp_profit / snapshot.xcp_profit).saturating_sub(Decimal256::one());
+    let elapsed = block_time - snapshot.ts;
+    let apr = if elapsed == 0 {
+        Decimal256::zero()
+    } else {
+        growth * Decimal256::from_ratio(SECONDS_PER_YEAR, elapsed)
+    };
+
+    Ok(FeeGrowthResponse {
+        from_ts: snapshot.ts,
+        to_ts: block_time,
+        growth,
+        apr,
+    })
+}
+
+/// Returns the observation granularity along with the time range covered by stored observations.
+fn query_observations_info(deps: Deps) -> StdResult<ObservationsInfoResponse> {
+    let granularity = OBSERVATION_GRANULARITY
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let buffer = BufferManager::new(deps.storage, OBSERVATIONS)?;
+
+    // Once the buffer is full the head points to the oldest observation
+    let oldest_index = if buffer.exists(deps.storage, buffer.head()) {
+        buffer.head()
+    } else {
+        0
+    };
+    let oldest_ts = buffer
+        .read_single(deps.storage, oldest_index)?
+        .map(|obs| obs.ts);
+    let newest_ts = buffer.read_last(deps.storage)?.map(|obs| obs.ts);
+
+    let covered_duration = match (oldest_ts, newest_ts) {
+        (Some(oldest), Some(newest)) => newest - oldest,
+        _ => 0,
+    };
+
+    Ok(ObservationsInfoResponse {
+        granularity,
+        capacity: buffer.capacity(),
+        oldest_ts,
+        newest_ts,
+        covered_duration,
+        full_buffer_duration: granularity * buffer.capacity() as u64,
+    })
+}
+
 #[cfg(test)]
 mod testing {
     use std::error::Error;
     use std::str::FromStr;
 
-    use gridiron::observation::{query_observation, Observation, OracleObservation};
+    use gridiron::observation::{
+        query_observation, query_volatility, Observation, OracleObservation,
+    };
     use gridiron_circular_buffer::BufferManager;
     use cosmwasm_std::testing::{mock_dependencies, mock_env};
     use cosmwasm_std::Timestamp;
@@ -372,6 +546,55 @@ mod testing {
         );
     }
 
+    #[test]
+    fn volatility_from_observations() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100_000);
+        BufferManager::init(&mut deps.storage, OBSERVATIONS, 10).unwrap();
+
+        let err = query_volatility(deps.as_ref(), env.clone(), OBSERVATIONS, 86400).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Not enough observations to calculate volatility"
+        );
+
+        // A year divided into 100 periods, the price doubles every period
+        let period = SECONDS_PER_YEAR / 100;
+        let array = (1..=15u32)
+            .map(|i| Observation {
+                ts: env.block.time.seconds() + i as u64 * period,
+                price: Decimal::from_ratio(2u128.pow(i), 1u8),
+                price_sma: Default::default(),
+            })
+            .collect_vec();
+        let mut buffer = BufferManager::new(&deps.storage, OBSERVATIONS).unwrap();
+        buffer.push_many(&array);
+        buffer.commit(&mut deps.storage).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(15 * period);
+
+        // Only the last 10 observations are kept in the buffer
+        let resp = query_volatility(deps.as_ref(), env.clone(), OBSERVATIONS, 20 * period).unwrap();
+        assert_eq!(resp.from_ts, 100_000 + 6 * period);
+        assert_eq!(resp.to_ts, 100_000 + 15 * period);
+        assert_eq!(resp.samples, 9);
+        // Each price change is +100%, i.e. variance is 100 changes per year * 1^2
+        assert_eq!(resp.variance, Decimal256::from_ratio(100u8, 1u8));
+        assert_eq!(resp.volatility, Decimal256::from_ratio(10u8, 1u8));
+
+        let resp = query_volatility(deps.as_ref(), env.clone(), OBSERVATIONS, 2 * period).unwrap();
+        assert_eq!(resp.from_ts, 100_000 + 13 * period);
+        assert_eq!(resp.samples, 2);
+        assert_eq!(resp.variance, Decimal256::from_ratio(100u8, 1u8));
+
+        let err = query_volatility(deps.as_ref(), env, OBSERVATIONS, 0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Not enough observations to calculate volatility"
+        );
+    }
+
     #[test]
     fn observations_incomplete_buffer() {
         let mut deps = mock_dependencies();
@@ -1,14 +1,29 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal256, Uint128};
 use cw_storage_plus::{Item, SnapshotMap};
 
 use gridiron::asset::AssetInfo;
 use gridiron::common::OwnershipProposal;
-use gridiron::observation::Observation;
+use gridiron::observation::{FeeGrowthObservation, Observation};
+use gridiron::pair::{MaintenanceWindow, PAIR_CONFIG_KEY};
+use gridiron::pair_concentrated::ReanchorConfig;
 use gridiron_circular_buffer::CircularBuffer;
 use gridiron_pcl_common::state::Config;
 
 /// Stores pool parameters and state.
-pub const CONFIG: Item<Config> = Item::new("config");
+pub const CONFIG: Item<Config> = Item::new(PAIR_CONFIG_KEY);
+
+/// Stores the floor (in decimal form) below which swaps are rejected
+pub const MIN_TRADE_FLOOR: Item<Decimal256> = Item::new("min_trade_floor");
+
+/// Stores the scheduled maintenance window
+pub const MAINTENANCE_WINDOW: Item<MaintenanceWindow> = Item::new("maintenance_window");
+
+/// Stores the minimum number of seconds between two stored price observations.
+/// Missing value means an observation is stored for every block with trades.
+pub const OBSERVATION_GRANULARITY: Item<u64> = Item::new("observation_granularity");
+
+/// Stores the parameters of price scale re-anchoring. Missing value means re-anchoring is disabled.
+pub const REANCHOR_CONFIG: Item<ReanchorConfig> = Item::new("reanchor_config");
 
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -17,6 +32,10 @@ pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_pro
 pub const OBSERVATIONS: CircularBuffer<Observation> =
     CircularBuffer::new("observations_state", "observations_buffer");
 
+/// Circular buffer to store periodic snapshots of xcp_profit
+pub const FEE_GROWTH_OBSERVATIONS: CircularBuffer<FeeGrowthObservation> =
+    CircularBuffer::new("fee_growth_state", "fee_growth_buffer");
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
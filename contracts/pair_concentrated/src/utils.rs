@@ -1,15 +1,21 @@
-use cosmwasm_std::{Addr, Decimal, Env, QuerierWrapper, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Env, QuerierWrapper, StdError, StdResult, Storage, Uint128,
+};
 
 use gridiron::asset::{Asset, DecimalAsset};
 use gridiron::observation::{safe_sma_buffer_not_full, safe_sma_calculation};
-use gridiron::observation::{Observation, PrecommitObservation};
+use gridiron::observation::{
+    FeeGrowthObservation, Observation, PrecommitObservation, FEE_GROWTH_SNAPSHOT_PERIOD,
+};
 use gridiron::querier::query_supply;
 use gridiron_circular_buffer::error::BufferResult;
 use gridiron_circular_buffer::BufferManager;
 use gridiron_pcl_common::state::{Config, Precisions};
 
 use crate::error::ContractError;
-use crate::state::OBSERVATIONS;
+use crate::state::{
+    FEE_GROWTH_OBSERVATIONS, MAINTENANCE_WINDOW, OBSERVATIONS, OBSERVATION_GRANULARITY,
+};
 
 /// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
 pub(crate) fn pool_info(
@@ -24,6 +30,48 @@ pub(crate) fn pool_info(
     Ok((pools, total_share))
 }
 
+/// Checks that the pair allowlist approves the address. Every address is allowed in
+/// permissionless pairs.
+///
+/// * **address** is the address which provides liquidity or swaps.
+pub(crate) fn check_allowed(
+    querier: &QuerierWrapper,
+    config: &Config,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !config.pair_info.is_allowed(querier, address)? {
+        return Err(ContractError::AddressNotAllowed {
+            address: address.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that swaps are not halted by a post-only maintenance window.
+pub(crate) fn check_swaps_enabled(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_swaps(env.block.time.seconds()) => {
+            Err(ContractError::SwapsHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that liquidity provision and withdrawals are not halted by a swap-only maintenance
+/// window.
+pub(crate) fn check_liquidity_enabled(
+    storage: &dyn Storage,
+    env: &Env,
+) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_liquidity(env.block.time.seconds()) => {
+            Err(ContractError::LiquidityHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Returns current pool's volumes where amount is in [`Decimal256`] form.
 pub(crate) fn query_pools(
     querier: QuerierWrapper,
@@ -53,11 +101,14 @@ pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResu
     {
         let mut buffer = BufferManager::new(storage, OBSERVATIONS)?;
         let observed_price = Decimal::from_ratio(base_amount, quote_amount);
+        let granularity = OBSERVATION_GRANULARITY
+            .may_load(storage)?
+            .unwrap_or_default();
 
         let new_observation;
         if let Some(last_obs) = buffer.read_last(storage)? {
-            // Skip saving observation if it has been already saved
-            if last_obs.ts < precommit_ts {
+            // Skip saving observation if it has been already saved or the last one is too recent
+            if last_obs.ts < precommit_ts && precommit_ts - last_obs.ts >= granularity {
                 // Since this is circular buffer the next index contains the oldest value
                 let count = buffer.capacity();
                 if let Some(oldest_obs) = buffer.read_single(storage, buffer.head() + 1)? {
@@ -103,6 +154,73 @@ pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResu
     Ok(())
 }
 
+/// Stores a snapshot of the current xcp_profit if the last one is older than [`FEE_GROWTH_SNAPSHOT_PERIOD`].
+/// Zero xcp_profit (pool without liquidity) is never recorded.
+pub fn save_fee_growth_snapshot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    xcp_profit: Decimal256,
+) -> BufferResult<()> {
+    if xcp_profit.is_zero() {
+        return Ok(());
+    }
+
+    let mut buffer = BufferManager::new(storage, FEE_GROWTH_OBSERVATIONS)?;
+    let block_time = env.block.time.seconds();
+
+    match buffer.read_last(storage)? {
+        Some(last_obs) if last_obs.ts + FEE_GROWTH_SNAPSHOT_PERIOD > block_time => Ok(()),
+        _ => buffer.instant_push(
+            storage,
+            &FeeGrowthObservation {
+                ts: block_time,
+                xcp_profit,
+            },
+        ),
+    }
+}
+
+/// Returns the latest fee growth snapshot which was taken not later than `target` timestamp.
+pub(crate) fn find_fee_growth_snapshot(
+    storage: &dyn Storage,
+    target: u64,
+) -> StdResult<FeeGrowthObservation> {
+    let buffer = BufferManager::new(storage, FEE_GROWTH_OBSERVATIONS)?;
+
+    let (mut start, mut end) = if buffer.exists(storage, buffer.head()) {
+        (buffer.head(), buffer.head() + buffer.capacity() - 1)
+    } else if buffer.head() > 0 {
+        (0, buffer.head() - 1)
+    } else {
+        return Err(StdError::generic_err("Buffer is empty"));
+    };
+
+    let oldest_obs = buffer.read_single(storage, start)?.unwrap();
+    if target < oldest_obs.ts {
+        return Err(StdError::generic_err(format!(
+            "Requested fee growth snapshot is too old. Last known snapshot is at {}",
+            oldest_obs.ts
+        )));
+    }
+
+    // Find the rightmost snapshot with ts <= target
+    while start < end {
+        let mid = (start + end + 1) / 2;
+        let obs = buffer.read_single(storage, mid)?.ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Unexpected error in find_fee_growth_snapshot: no snapshot at index {mid}"
+            ))
+        })?;
+        if obs.ts <= target {
+            start = mid;
+        } else {
+            end = mid - 1;
+        }
+    }
+
+    Ok(buffer.read_single(storage, start)?.unwrap())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Display;
@@ -144,4 +262,58 @@ mod tests {
         assert_eq!(dec_to_f64(obs.price_sma), 2.0);
         assert_eq!(dec_to_f64(obs.price), 2.0);
     }
+
+    #[test]
+    fn test_fee_growth_snapshots() {
+        let mut store = MockStorage::new();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1_000_000);
+
+        BufferManager::init(&mut store, FEE_GROWTH_OBSERVATIONS, 10).unwrap();
+
+        let err = find_fee_growth_snapshot(&store, 1_000_000).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Buffer is empty");
+
+        // Zero xcp_profit is never recorded
+        save_fee_growth_snapshot(&mut store, &env, Decimal256::zero()).unwrap();
+        let buffer = BufferManager::new(&store, FEE_GROWTH_OBSERVATIONS).unwrap();
+        assert_eq!(buffer.read_last(&store).unwrap(), None);
+
+        for i in 1..=15u64 {
+            save_fee_growth_snapshot(&mut store, &env, Decimal256::from_ratio(i, 1u8)).unwrap();
+            // Snapshots within the same period are skipped
+            env.block.time = env.block.time.plus_seconds(FEE_GROWTH_SNAPSHOT_PERIOD / 2);
+            save_fee_growth_snapshot(&mut store, &env, Decimal256::MAX).unwrap();
+            env.block.time = env.block.time.plus_seconds(FEE_GROWTH_SNAPSHOT_PERIOD / 2);
+        }
+
+        let period = FEE_GROWTH_SNAPSHOT_PERIOD;
+        let obs = find_fee_growth_snapshot(&store, 1_000_000 + 14 * period).unwrap();
+        assert_eq!(obs.xcp_profit, Decimal256::from_ratio(15u8, 1u8));
+        let obs = find_fee_growth_snapshot(&store, 1_000_000 + 10 * period + 1).unwrap();
+        assert_eq!(obs.xcp_profit, Decimal256::from_ratio(11u8, 1u8));
+        let obs = find_fee_growth_snapshot(&store, 1_000_000 + 5 * period).unwrap();
+        assert_eq!(obs.xcp_profit, Decimal256::from_ratio(6u8, 1u8));
+
+        let err = find_fee_growth_snapshot(&store, 1_000_000 + 5 * period - 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Generic error: Requested fee growth snapshot is too old. Last known snapshot is at {}",
+                1_000_000 + 5 * period
+            )
+        );
+    }
+}
+
+/// Ensures the transaction is not included after `deadline`.
+///
+/// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+pub(crate) fn assert_deadline(env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {
+            Err(ContractError::TransactionExpired {})
+        }
+        _ => Ok(()),
+    }
 }
@@ -9,8 +9,8 @@ use std::str::FromStr;
 use anyhow::Result as AnyResult;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, from_slice, to_binary, Addr, Coin, Decimal, Decimal256, Empty, StdError, StdResult,
-    Uint128,
+    coin, from_slice, to_binary, to_vec, Addr, Binary, Coin, Decimal, Decimal256, Deps, DepsMut,
+    Empty, Env, MessageInfo, Response, StdError, StdResult, Uint128, Uint256, Uint64,
 };
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
 use derivative::Derivative;
@@ -18,13 +18,16 @@ use itertools::Itertools;
 
 use gridiron::asset::{native_asset_info, token_asset_info, Asset, AssetInfo, PairInfo};
 use gridiron::factory::{PairConfig, PairType};
+use gridiron::maker::{ConfigResponse as MakerConfigResponse, QueryMsg as MakerQueryMsg};
 use gridiron::observation::OracleObservation;
+use gridiron::oracle::QueryMsg as OracleQueryMsg;
 use gridiron::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, PoolResponse,
+    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, MinTradeSize, PoolResponse,
     ReverseSimulationResponse, SimulationResponse,
 };
 use gridiron::pair_concentrated::{
-    ConcentratedPoolConfig, ConcentratedPoolParams, ConcentratedPoolUpdateParams, QueryMsg,
+    ConcentratedPoolConfig, ConcentratedPoolParams, ConcentratedPoolUpdateParams, ExecuteMsg,
+    FeeGrowthResponse, ObservationsInfoResponse, QueryMsg, ReanchorConfig,
 };
 use gridiron_mocks::cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
 use gridiron_pair_concentrated::contract::{execute, instantiate, reply};
@@ -127,6 +130,60 @@ fn coin_registry_contract() -> Box<dyn Contract<Empty>> {
         gridiron_native_coin_registry::contract::query,
     ))
 }
+fn mock_maker_execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn mock_maker_instantiate(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    msg: MakerConfigResponse,
+) -> StdResult<Response> {
+    deps.storage.set(b"config", &to_vec(&msg)?);
+    Ok(Response::new())
+}
+
+fn mock_maker_query(deps: Deps, _: Env, _: MakerQueryMsg) -> StdResult<Binary> {
+    Ok(Binary::from(
+        deps.storage.get(b"config").unwrap_or_default(),
+    ))
+}
+
+/// A stand-in for the Maker which only answers the config query with the data it was instantiated with.
+fn mock_maker_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_maker_execute,
+        mock_maker_instantiate,
+        mock_maker_query,
+    ))
+}
+
+fn mock_oracle_execute(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    prices: Vec<(AssetInfo, Uint256)>,
+) -> StdResult<Response> {
+    deps.storage.set(b"prices", &to_vec(&prices)?);
+    Ok(Response::new())
+}
+
+fn mock_oracle_query(deps: Deps, _: Env, _: OracleQueryMsg) -> StdResult<Binary> {
+    Ok(Binary::from(
+        deps.storage.get(b"prices").unwrap_or_default(),
+    ))
+}
+
+/// A stand-in for the oracle which answers the consult query with the prices it was last given.
+fn mock_oracle_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_oracle_execute,
+        mock_oracle_execute,
+        mock_oracle_query,
+    ))
+}
+
 fn factory_contract() -> Box<dyn Contract<Empty>> {
     Box::new(
         ContractWrapper::new_with_empty(
@@ -299,6 +356,8 @@ impl Helper {
             slippage_tolerance,
             auto_stake: None,
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         };
 
         self.app
@@ -314,7 +373,11 @@ impl Helper {
         let msg = Cw20ExecuteMsg::Send {
             contract: self.pair_addr.to_string(),
             amount: Uint128::from(amount),
-            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets }).unwrap(),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+                assets,
+                deadline: None,
+            })
+            .unwrap(),
         };
 
         self.app
@@ -346,7 +409,10 @@ impl Helper {
                         ask_asset_info: None,
                         belief_price,
                         max_spread,
+                        min_receive: None,
                         to: None,
+                        deadline: None,
+                        referral: None,
                     })
                     .unwrap(),
                 };
@@ -367,7 +433,10 @@ impl Helper {
                     ask_asset_info: None,
                     belief_price,
                     max_spread,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 };
 
                 self.app
@@ -528,6 +597,57 @@ impl Helper {
         )
     }
 
+    /// Replaces the factory fee address with a mock Maker which reports the given xGRID staking
+    /// contract and GRID token.
+    pub fn setup_mock_maker(
+        &mut self,
+        staking_contract: Option<&Addr>,
+        grid_token: &AssetInfo,
+    ) -> Addr {
+        let maker_code_id = self.app.store_code(mock_maker_contract());
+        let maker = self
+            .app
+            .instantiate_contract(
+                maker_code_id,
+                self.owner.clone(),
+                &MakerConfigResponse {
+                    owner: self.owner.clone(),
+                    default_bridge: None,
+                    grid_token: grid_token.clone(),
+                    factory_contract: self.factory.clone(),
+                    staking_contract: staking_contract.cloned(),
+                    governance_contract: None,
+                    governance_percent: Uint64::zero(),
+                    max_spread: Decimal::zero(),
+                    remainder_reward: Uint128::zero(),
+                    pre_upgrade_grid_amount: Uint128::zero(),
+                    second_receiver_cfg: None,
+                },
+                &[],
+                "Mock maker",
+                None,
+            )
+            .unwrap();
+
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.factory.clone(),
+                &gridiron::factory::ExecuteMsg::UpdateConfig {
+                    token_code_id: None,
+                    fee_address: Some(maker.to_string()),
+                    generator_address: None,
+                    whitelist_code_id: None,
+                    coin_registry_address: None,
+                    max_combined_fee_bps: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        maker
+    }
+
     pub fn query_amp_gamma(&self) -> StdResult<AmpGammaResponse> {
         let config_resp: ConfigResponse = self
             .app
@@ -545,6 +665,14 @@ impl Helper {
         })
     }
 
+    pub fn query_min_trade_sizes(&self) -> StdResult<Vec<MinTradeSize>> {
+        let config_resp: ConfigResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::Config {})?;
+        Ok(config_resp.min_trade_sizes)
+    }
+
     pub fn query_d(&self) -> StdResult<Decimal256> {
         self.app
             .wrap()
@@ -560,6 +688,20 @@ impl Helper {
         )
     }
 
+    pub fn query_share_value_in(
+        &self,
+        amount: impl Into<Uint128>,
+        asset_info: &AssetInfo,
+    ) -> StdResult<Asset> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::ShareValueIn {
+                amount: amount.into(),
+                asset_info: asset_info.clone(),
+            },
+        )
+    }
+
     pub fn observe_price(&self, seconds_ago: u64) -> StdResult<Decimal> {
         self.app
             .wrap()
@@ -569,6 +711,48 @@ impl Helper {
             )
             .map(|val| val.price)
     }
+
+    pub fn query_fee_growth(&self, seconds_ago: u64) -> StdResult<FeeGrowthResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::FeeGrowth { seconds_ago })
+    }
+
+    pub fn query_observations_info(&self) -> StdResult<ObservationsInfoResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::ObservationsInfo {})
+    }
+
+    pub fn query_reanchor_config(&self) -> StdResult<Option<ReanchorConfig>> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::ReanchorConfig {})
+    }
+
+    /// Instantiates a mock oracle which reports the given prices for any consult query.
+    pub fn setup_mock_oracle(&mut self, prices: &[(AssetInfo, Uint256)]) -> Addr {
+        let oracle_code_id = self.app.store_code(mock_oracle_contract());
+        self.app
+            .instantiate_contract(
+                oracle_code_id,
+                self.owner.clone(),
+                &prices,
+                &[],
+                "Mock oracle",
+                None,
+            )
+            .unwrap()
+    }
+
+    pub fn reanchor(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::Reanchor {},
+            &[],
+        )
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -650,19 +834,6 @@ impl AssetsExt for &[Asset] {
     }
 }
 
-pub trait AppExtension {
-    fn next_block(&mut self, time: u64);
-}
-
-impl AppExtension for App {
-    fn next_block(&mut self, time: u64) {
-        self.update_block(|block| {
-            block.time = block.time.plus_seconds(time);
-            block.height += 1
-        });
-    }
-}
-
 pub fn f64_to_dec<T>(val: f64) -> T
 where
     T: FromStr,
@@ -4,25 +4,29 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::str::FromStr;
 
-use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, StdError, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, StdError, Uint128, Uint256};
 use itertools::{max, Itertools};
 
 use gridiron::asset::{
     native_asset_info, Asset, AssetInfo, AssetInfoExt, MINIMUM_LIQUIDITY_AMOUNT,
 };
 use gridiron::cosmwasm_ext::{AbsDiff, IntegerToDecimal};
-use gridiron::observation::OracleObservation;
-use gridiron::pair::{ExecuteMsg, PoolResponse, MAX_FEE_SHARE_BPS};
+use gridiron::observation::{OracleObservation, OBSERVATIONS_SIZE};
+use gridiron::pair::{ExecuteMsg, MinTradeSize, PoolResponse, MAX_FEE_SHARE_BPS};
 use gridiron::pair_concentrated::{
-    ConcentratedPoolParams, ConcentratedPoolUpdateParams, PromoteParams, QueryMsg, UpdatePoolParams,
+    ConcentratedPoolParams, ConcentratedPoolUpdateParams, PromoteParams, QueryMsg, ReanchorParams,
+    UpdatePoolParams, MAX_OBSERVATION_GRANULARITY, MAX_STAKER_FEE_SHARE_BPS,
+    MIN_REANCHOR_DORMANCY_PERIOD,
+};
+use gridiron_mocks::cw_multi_test::{AppResponse, BasicApp, Executor};
+use gridiron_mocks::{
+    gridiron_address, MockAppExt, MockConcentratedPairBuilder, MockGeneratorBuilder,
 };
-use gridiron_mocks::cw_multi_test::{BasicApp, Executor};
-use gridiron_mocks::{gridiron_address, MockConcentratedPairBuilder, MockGeneratorBuilder};
 use gridiron_pair_concentrated::error::ContractError;
 use gridiron_pcl_common::consts::{AMP_MAX, AMP_MIN, MA_HALF_TIME_LIMITS};
 use gridiron_pcl_common::error::PclError;
 
-use crate::helper::{common_pcl_params, dec_to_f64, f64_to_dec, AppExtension, Helper, TestCoin};
+use crate::helper::{common_pcl_params, dec_to_f64, f64_to_dec, Helper, TestCoin};
 
 mod helper;
 
@@ -52,7 +56,7 @@ fn check_observe_queries() {
     assert_eq!(0, helper.coin_balance(&test_coins[0], &user));
     assert_eq!(99_737929, helper.coin_balance(&test_coins[1], &user));
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let user2 = Addr::unchecked("user2");
     let offer_asset = helper.assets[&test_coins[1]].with_balance(100_000000u128);
@@ -82,6 +86,102 @@ fn check_observe_queries() {
     );
 }
 
+#[test]
+fn check_fee_growth_query() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let err = helper.query_fee_growth(0).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Querier contract error: Generic error: Buffer is empty")
+    );
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    let start_ts = helper.app.block_info().time.seconds();
+
+    // No fees accrued yet
+    let resp = helper.query_fee_growth(0).unwrap();
+    assert_eq!(resp.from_ts, start_ts);
+    assert_eq!(resp.growth, Decimal256::zero());
+    assert_eq!(resp.apr, Decimal256::zero());
+
+    let user = Addr::unchecked("user");
+    for i in 0..10 {
+        helper.app.next_block_after(86400);
+        let offer_asset = helper.assets[&test_coins[i % 2]].with_balance(1_000_000000u128);
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper.swap(&user, &offer_asset, None).unwrap();
+    }
+
+    let resp = helper.query_fee_growth(10 * 86400).unwrap();
+    assert_eq!(resp.from_ts, start_ts);
+    assert_eq!(resp.to_ts, helper.app.block_info().time.seconds());
+    assert!(resp.growth > Decimal256::zero());
+    assert!(resp.apr > resp.growth);
+
+    // The snapshot taken after the 5th swap is used for the last 5 days
+    let last_days = helper.query_fee_growth(5 * 86400).unwrap();
+    assert_eq!(last_days.from_ts, start_ts + 5 * 86400);
+    assert!(last_days.growth < resp.growth);
+
+    let err = helper.query_fee_growth(10 * 86400 + 1).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Querier contract error: Generic error: Requested fee growth snapshot is too old. Last known snapshot is at {start_ts}"))
+    );
+}
+
+#[test]
+fn check_share_value_in() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+
+    // 1 USDC = 0.5 LUNA
+    let params = ConcentratedPoolParams {
+        price_scale: Decimal::from_ratio(1u8, 2u8),
+        ..common_pcl_params()
+    };
+    let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(200_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let total_share = helper.query_pool().unwrap().total_share;
+
+    let value = helper
+        .query_share_value_in(total_share, &helper.assets[&test_coins[0]])
+        .unwrap();
+    assert_eq!(value.info, helper.assets[&test_coins[0]]);
+    assert!(value.amount <= Uint128::new(200_000_000000));
+    assert!(value.amount > Uint128::new(199_999_000000));
+
+    let value = helper
+        .query_share_value_in(total_share, &helper.assets[&test_coins[1]])
+        .unwrap();
+    assert_eq!(value.info, helper.assets[&test_coins[1]]);
+    assert!(value.amount <= Uint128::new(400_000_000000));
+    assert!(value.amount > Uint128::new(399_998_000000));
+
+    let err = helper
+        .query_share_value_in(total_share, &native_asset_info("uusd".to_string()))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Given asset does not belong in the pair"));
+}
+
 #[test]
 fn check_wrong_initialization() {
     let owner = Addr::unchecked("owner");
@@ -174,7 +274,7 @@ fn check_create_pair_with_unsupported_denom() {
 
     let err = Helper::new(&owner, wrong_coins.clone(), params.clone()).unwrap_err();
     assert_eq!(
-        "Generic error: Invalid denom length [3,128]: rc",
+        "Invalid denom length [3,128]: rc",
         err.root_cause().to_string()
     );
 
@@ -618,7 +718,10 @@ fn check_swaps_simple() {
                 ask_asset_info: None,
                 belief_price: None,
                 max_spread: None,
+                min_receive: None,
                 to: None,
+                deadline: None,
+                referral: None,
             },
             &[],
         )
@@ -652,7 +755,7 @@ fn check_swaps_simple() {
     assert_eq!(dec_to_f64(d), 200000.260415);
 
     let price1 = helper.observe_price(0).unwrap();
-    helper.app.next_block(10);
+    helper.app.next_block_after(10);
     // Swapping the lowest amount possible which results in positive return amount
     helper
         .swap(
@@ -665,7 +768,7 @@ fn check_swaps_simple() {
     // With such a small swap size contract doesn't store observation
     assert_eq!(price1, price2);
 
-    helper.app.next_block(10);
+    helper.app.next_block_after(10);
     // Swap the smallest possible amount which gets observation saved
     helper
         .swap(
@@ -692,7 +795,7 @@ fn check_swaps_with_price_update() {
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
@@ -700,7 +803,7 @@ fn check_swaps_with_price_update() {
     ];
     helper.provide_liquidity(&owner, &assets).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let user1 = Addr::unchecked("user1");
     let offer_asset = helper.assets[&test_coins[1]].with_balance(10_000_000000u128);
@@ -715,14 +818,14 @@ fn check_swaps_with_price_update() {
             "{i}: new_vlp_price <= prev_vlp_price ({new_vlp_price} <= {prev_vlp_price})",
         );
         prev_vlp_price = new_vlp_price;
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 
     let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000_000000u128);
     for _i in 0..4 {
         helper.give_me_money(&[offer_asset.clone()], &user1);
         helper.swap(&user1, &offer_asset, Some(half)).unwrap();
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 }
 
@@ -734,7 +837,7 @@ fn provides_and_swaps() {
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
@@ -742,7 +845,7 @@ fn provides_and_swaps() {
     ];
     helper.provide_liquidity(&owner, &assets).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let user = Addr::unchecked("user");
     let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
@@ -798,7 +901,7 @@ fn check_amp_gamma_change() {
 
     helper.update_config(&owner, &action).unwrap();
 
-    helper.app.next_block(86400);
+    helper.app.next_block_after(86400);
 
     let future_time = helper.app.block_info().time.seconds() + 100_000;
     let target_amp = 44f64;
@@ -815,14 +918,14 @@ fn check_amp_gamma_change() {
     assert_eq!(dec_to_f64(amp_gamma.gamma), 0.0001);
     assert_eq!(amp_gamma.future_time, future_time);
 
-    helper.app.next_block(50_000);
+    helper.app.next_block_after(50_000);
 
     let amp_gamma = helper.query_amp_gamma().unwrap();
     assert_eq!(dec_to_f64(amp_gamma.amp), 42f64);
     assert_eq!(dec_to_f64(amp_gamma.gamma), 0.000095);
     assert_eq!(amp_gamma.future_time, future_time);
 
-    helper.app.next_block(50_000);
+    helper.app.next_block_after(50_000);
 
     let amp_gamma = helper.query_amp_gamma().unwrap();
     assert_eq!(dec_to_f64(amp_gamma.amp), target_amp);
@@ -838,7 +941,7 @@ fn check_amp_gamma_change() {
     });
     helper.update_config(&owner, &action).unwrap();
 
-    helper.app.next_block(50_000);
+    helper.app.next_block_after(50_000);
 
     let amp_gamma = helper.query_amp_gamma().unwrap();
     assert_eq!(dec_to_f64(amp_gamma.amp), 42f64);
@@ -852,7 +955,7 @@ fn check_amp_gamma_change() {
     let last_change_time = helper.app.block_info().time.seconds();
     assert_eq!(amp_gamma.future_time, last_change_time);
 
-    helper.app.next_block(50_000);
+    helper.app.next_block_after(50_000);
 
     let amp_gamma = helper.query_amp_gamma().unwrap();
     assert_eq!(dec_to_f64(amp_gamma.amp), 42f64);
@@ -1227,7 +1330,7 @@ fn provides_and_swaps_and_withdraw() {
     };
     let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
@@ -1241,7 +1344,7 @@ fn provides_and_swaps_and_withdraw() {
     helper.give_me_money(&[offer_asset.clone()], &user);
     helper.swap(&user, &offer_asset, Some(half)).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     // swap usdc
     let offer_asset = helper.assets[&test_coins[1]].with_balance(1000_000000u128);
@@ -1342,14 +1445,14 @@ fn provide_withdraw_provide() {
         helper.assets[&test_coins[1]].with_balance(1_093804u128),
     ];
     helper.provide_liquidity(&owner, &assets).unwrap();
-    helper.app.next_block(90);
+    helper.app.next_block_after(90);
     helper.provide_liquidity(&owner, &assets).unwrap();
 
-    helper.app.next_block(90);
+    helper.app.next_block_after(90);
     let uusd = helper.assets[&test_coins[0]].with_balance(5_000000u128);
     helper.swap(&owner, &uusd, Some(f64_to_dec(0.5))).unwrap();
 
-    helper.app.next_block(600);
+    helper.app.next_block_after(600);
     // Withdraw all
     let lp_amount = helper.token_balance(&helper.lp_token, &owner);
     helper
@@ -1459,7 +1562,7 @@ fn test_frontrun_before_initial_provide() {
     let offer_asset_luna = helper.assets[&test_coins[1]].with_balance(1_000000u128);
     // Arber spinning pool back to balanced state
     loop {
-        helper.app.next_block(10);
+        helper.app.next_block_after(10);
         helper.give_me_money(&[offer_asset_luna.clone()], &arber);
         // swapping until price satisfies an arber
         if helper
@@ -1529,7 +1632,7 @@ fn check_correct_fee_share() {
         ContractError::FeeShareOutOfBounds {}
     );
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     // Set to 5% fee share
     let action = ConcentratedPoolUpdateParams::EnableFeeShare {
@@ -1543,7 +1646,7 @@ fn check_correct_fee_share() {
     assert_eq!(fee_share.bps, 1000u16);
     assert_eq!(fee_share.recipient, share_recipient.to_string());
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
@@ -1551,7 +1654,7 @@ fn check_correct_fee_share() {
     ];
     helper.provide_liquidity(&owner, &assets).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let user = Addr::unchecked("user");
     let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
@@ -1591,6 +1694,116 @@ fn check_correct_fee_share() {
     assert!(config.fee_share.is_none());
 }
 
+#[test]
+fn check_staker_fee_share() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    // uluna plays the role of GRID
+    let grid_token = helper.assets[&test_coins[0]].clone();
+    let staking = Addr::unchecked("staking");
+
+    let action = ConcentratedPoolUpdateParams::EnableStakerFeeShare {
+        staker_fee_share_bps: 1000,
+    };
+
+    // The Maker must know the xGRID staking contract
+    helper.setup_mock_maker(None, &grid_token);
+    let err = helper.update_config(&owner, &action).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::StakingContractNotFound {}
+    );
+
+    let maker = helper.setup_mock_maker(Some(&staking), &grid_token);
+
+    for staker_fee_share_bps in [0, MAX_STAKER_FEE_SHARE_BPS + 1] {
+        let err = helper
+            .update_config(
+                &owner,
+                &ConcentratedPoolUpdateParams::EnableStakerFeeShare {
+                    staker_fee_share_bps,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::StakerFeeShareOutOfBounds {}
+        );
+    }
+
+    helper.update_config(&owner, &action).unwrap();
+
+    let staker_fee_share = helper.query_config().unwrap().staker_fee_share.unwrap();
+    assert_eq!(staker_fee_share.bps, 1000);
+    assert_eq!(staker_fee_share.staking_contract, staking);
+    assert_eq!(staker_fee_share.grid_token, grid_token);
+    assert_eq!(staker_fee_share.maker, maker);
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    helper.app.next_block_after(1000);
+
+    // Fees in GRID go to the staking contract and don't change the simulated return amount
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(100_000000u128);
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let res = helper.swap(&user, &offer_asset, None).unwrap();
+
+    let staker_fee = swap_attribute(&res, "staker_fee_amount");
+    assert!(staker_fee > 0);
+    assert_eq!(
+        helper.coin_balance(&test_coins[0], &user),
+        sim.return_amount.u128()
+    );
+    assert_eq!(helper.coin_balance(&test_coins[0], &staking), staker_fee);
+
+    helper.app.next_block_after(1000);
+
+    // Fees in other assets go to the Maker along with the maker fee
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let res = helper.swap(&user, &offer_asset, None).unwrap();
+
+    let staker_fee = swap_attribute(&res, "staker_fee_amount");
+    let maker_fee = swap_attribute(&res, "maker_fee_amount");
+    assert!(staker_fee > 0);
+    assert_eq!(
+        helper.coin_balance(&test_coins[1], &maker),
+        maker_fee + staker_fee
+    );
+    assert_eq!(helper.coin_balance(&test_coins[1], &staking), 0);
+
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::DisableStakerFeeShare {},
+        )
+        .unwrap();
+    assert!(helper.query_config().unwrap().staker_fee_share.is_none());
+
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let res = helper.swap(&user, &offer_asset, None).unwrap();
+    assert_eq!(swap_attribute(&res, "staker_fee_amount"), 0);
+}
+
+fn swap_attribute(res: &AppResponse, key: &str) -> u128 {
+    res.events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.parse().unwrap())
+        .unwrap()
+}
+
 #[test]
 fn check_small_trades() {
     let owner = Addr::unchecked("owner");
@@ -1613,7 +1826,7 @@ fn check_small_trades() {
 
     // Trying to mess the last price with lowest possible swap
     for _ in 0..1000 {
-        helper.app.next_block(30);
+        helper.app.next_block_after(30);
         let offer_asset = helper.assets[&test_coins[1]].with_balance(1u8);
         helper
             .swap_full_params(&owner, &offer_asset, None, Some(Decimal::MAX))
@@ -1641,7 +1854,7 @@ fn check_small_trades() {
 
     // Trying to mess the last price with lowest possible provide
     for _ in 0..1000 {
-        helper.app.next_block(30);
+        helper.app.next_block_after(30);
         let assets = vec![helper.assets[&test_coins[1]].with_balance(1u8)];
         helper
             .provide_liquidity_with_slip_tolerance(&owner, &assets, Some(f64_to_dec(0.5)))
@@ -1693,7 +1906,7 @@ fn check_small_trades_18decimals() {
 
     // Trying to mess the last price with lowest possible swap
     for _ in 0..1000 {
-        helper.app.next_block(30);
+        helper.app.next_block_after(30);
         let offer_asset = helper.assets[&test_coins[1]].with_balance(1u8);
         helper
             .swap_full_params(&owner, &offer_asset, None, Some(Decimal::MAX))
@@ -1721,7 +1934,7 @@ fn check_small_trades_18decimals() {
 
     // Trying to mess the last price with lowest possible provide
     for _ in 0..1000 {
-        helper.app.next_block(30);
+        helper.app.next_block_after(30);
         // 0.000001 USD. minimum provide is limited to LP token precision which is 6 decimals.
         let assets = vec![helper.assets[&test_coins[1]].with_balance(1000000000000u128)];
         helper
@@ -1749,6 +1962,62 @@ fn check_small_trades_18decimals() {
     );
 }
 
+#[test]
+fn check_spread_assertion_18decimals_extreme_magnitudes() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![
+        TestCoin::cw20precise("INJ", 18),
+        TestCoin::cw20precise("WETH", 18),
+    ];
+
+    let params = ConcentratedPoolParams {
+        price_scale: Decimal::from_ratio(200u8, 1u8),
+        ..common_pcl_params()
+    };
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
+
+    // 1B INJ and 5M wETH
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(1_000_000_000e18 as u128),
+        helper.assets[&test_coins[1]].with_balance(5_000_000e18 as u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    // Dust trades without belief price must not fail with a division by zero
+    for amount in [1u128, 2, 10, 100] {
+        for coin in &test_coins {
+            let offer_asset = helper.assets[coin].with_balance(amount);
+            helper.give_me_money(&[offer_asset.clone()], &owner);
+            if let Err(err) = helper.swap(&owner, &offer_asset, Some(Decimal::percent(50))) {
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap(),
+                    ContractError::PclError(PclError::MaxSpreadAssertion {}),
+                );
+            }
+        }
+    }
+
+    // Huge trade is checked against the belief price precisely
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000_000e18 as u128);
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    let belief_price = Decimal::from_ratio(offer_asset.amount, sim.return_amount);
+    helper.give_me_money(&[offer_asset.clone()], &owner);
+    let res = helper
+        .swap_full_params(
+            &owner,
+            &offer_asset,
+            Some(Decimal::from_ratio(1u8, 1_000_000u32)),
+            Some(belief_price),
+        )
+        .unwrap();
+    assert_eq!(
+        swap_attribute(&res, "return_amount"),
+        sim.return_amount.u128()
+    );
+}
+
 #[test]
 fn check_lsd_swaps_with_price_update() {
     let owner = Addr::unchecked("owner");
@@ -1773,14 +2042,14 @@ fn check_lsd_swaps_with_price_update() {
     };
     let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
 
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance((1e18 * price_scale) as u128),
         helper.assets[&test_coins[1]].with_balance(1e18 as u128),
     ];
     helper.provide_liquidity(&owner, &assets).unwrap();
-    helper.app.next_block(1000);
+    helper.app.next_block_after(1000);
 
     for _ in 0..10 {
         let assets = vec![
@@ -1788,7 +2057,7 @@ fn check_lsd_swaps_with_price_update() {
             helper.assets[&test_coins[1]].with_balance(1e15 as u128),
         ];
         helper.provide_liquidity(&owner, &assets).unwrap();
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 
     for _ in 0..10 {
@@ -1797,7 +2066,7 @@ fn check_lsd_swaps_with_price_update() {
             helper.assets[&test_coins[1]].with_balance(1e13 as u128),
         ];
         helper.provide_liquidity(&owner, &assets).unwrap();
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 
     let user1 = Addr::unchecked("user1");
@@ -1806,14 +2075,14 @@ fn check_lsd_swaps_with_price_update() {
     for _ in 0..10 {
         helper.give_me_money(&[offer_asset.clone()], &user1);
         helper.swap(&user1, &offer_asset, Some(half)).unwrap();
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 
     let offer_asset = helper.assets[&test_coins[1]].with_balance(1e16 as u128);
     for _ in 0..10 {
         helper.give_me_money(&[offer_asset.clone()], &user1);
         helper.swap(&user1, &offer_asset, Some(half)).unwrap();
-        helper.app.next_block(1000);
+        helper.app.next_block_after(1000);
     }
 }
 
@@ -1848,6 +2117,8 @@ fn test_provide_liquidity_without_funds() {
         slippage_tolerance: Some(f64_to_dec(0.5)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let err = helper
@@ -1860,3 +2131,282 @@ fn test_provide_liquidity_without_funds() {
         "Generic error: Native token balance mismatch between the argument (100000000000uluna) and the transferred (0uluna)"
     )
 }
+
+#[test]
+fn check_min_trade_floor() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20precise("FOO", 18)];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    // Observation thresholds are always reported while the swap floor is disabled
+    assert_eq!(
+        helper.query_min_trade_sizes().unwrap(),
+        vec![
+            MinTradeSize {
+                asset_info: helper.assets[&test_coins[0]].clone(),
+                observation: Uint128::new(10),
+                swap: Uint128::zero(),
+            },
+            MinTradeSize {
+                asset_info: helper.assets[&test_coins[1]].clone(),
+                observation: Uint128::new(10_000_000_000_000),
+                swap: Uint128::zero(),
+            },
+        ]
+    );
+
+    let err = helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateMinTradeFloor {
+                min_trade_floor: Some(Decimal256::zero()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MinTradeFloorOutOfBounds {}
+    );
+
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateMinTradeFloor {
+                min_trade_floor: Some(Decimal256::permille(1)),
+            },
+        )
+        .unwrap();
+
+    let min_trade_sizes = helper.query_min_trade_sizes().unwrap();
+    assert_eq!(min_trade_sizes[0].swap, Uint128::new(1000));
+    assert_eq!(min_trade_sizes[1].swap, Uint128::new(1_000_000_000_000_000));
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000_000000000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(999_999_999_999_999u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let err = helper.swap(&user, &offer_asset, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BelowMinTradeSize {
+            amount: Uint128::new(999_999_999_999_999),
+            min: Uint128::new(1_000_000_000_000_000),
+        }
+    );
+
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(1_000_000_000_000_000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+
+    // Removing the floor enables dust swaps again
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateMinTradeFloor {
+                min_trade_floor: None,
+            },
+        )
+        .unwrap();
+    let min_trade_sizes = helper.query_min_trade_sizes().unwrap();
+    assert!(min_trade_sizes.iter().all(|size| size.swap.is_zero()));
+}
+
+#[test]
+fn check_observation_granularity() {
+    let owner = Addr::unchecked("owner");
+    let half = Decimal::from_ratio(1u8, 2u8);
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let err = helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateObservationGranularity {
+                granularity: MAX_OBSERVATION_GRANULARITY + 1,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ObservationGranularityOutOfBounds {}
+    );
+
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateObservationGranularity { granularity: 300 },
+        )
+        .unwrap();
+
+    let info = helper.query_observations_info().unwrap();
+    assert_eq!(info.granularity, 300);
+    assert_eq!(info.capacity, OBSERVATIONS_SIZE);
+    assert_eq!(info.oldest_ts, None);
+    assert_eq!(info.covered_duration, 0);
+    assert_eq!(info.full_buffer_duration, 300 * OBSERVATIONS_SIZE as u64);
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block_after(1000);
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(100_000000u128);
+    let start_ts = helper.app.block_info().time.seconds();
+
+    // Every swap commits the previous block observation, but only one per 300 seconds is stored
+    for _ in 0..10 {
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper.swap(&user, &offer_asset, Some(half)).unwrap();
+        helper.app.next_block_after(100);
+    }
+
+    let info = helper.query_observations_info().unwrap();
+    assert_eq!(info.oldest_ts, Some(start_ts));
+    assert_eq!(info.newest_ts, Some(start_ts + 600));
+    assert_eq!(info.covered_duration, 600);
+}
+
+#[test]
+fn check_reanchor() {
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let err = helper.reanchor(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReanchorDisabled {}
+    );
+
+    // The oracle reports 2 uluna per uusd while the pool quotes 1
+    let oracle = helper.setup_mock_oracle(&[(
+        helper.assets[&test_coins[0]].clone(),
+        Uint256::from(2_000000u128),
+    )]);
+    let reanchor_params = ReanchorParams {
+        oracle: oracle.to_string(),
+        dormancy_period: MIN_REANCHOR_DORMANCY_PERIOD,
+        deviation_threshold: Decimal::percent(10),
+        max_step: Decimal::percent(25),
+    };
+
+    for invalid_params in [
+        ReanchorParams {
+            dormancy_period: MIN_REANCHOR_DORMANCY_PERIOD - 1,
+            ..reanchor_params.clone()
+        },
+        ReanchorParams {
+            max_step: Decimal::one(),
+            ..reanchor_params.clone()
+        },
+    ] {
+        let err = helper
+            .update_config(
+                &owner,
+                &ConcentratedPoolUpdateParams::UpdateReanchorConfig {
+                    reanchor_config: Some(invalid_params),
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ReanchorConfigOutOfBounds {}
+        );
+    }
+
+    let err = helper
+        .update_config(
+            &user,
+            &ConcentratedPoolUpdateParams::UpdateReanchorConfig {
+                reanchor_config: Some(reanchor_params.clone()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateReanchorConfig {
+                reanchor_config: Some(reanchor_params),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        helper.query_reanchor_config().unwrap().unwrap().oracle,
+        oracle
+    );
+
+    let err = helper.reanchor(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolNotDormant {
+            dormancy_period: MIN_REANCHOR_DORMANCY_PERIOD
+        }
+    );
+
+    helper.app.next_block_after(MIN_REANCHOR_DORMANCY_PERIOD);
+
+    // Every call moves the price scale by at most 25% until it is within 10% of the oracle price
+    for expected_price_scale in ["1.25", "1.5625", "1.953125"] {
+        helper.reanchor(&user).unwrap();
+        let price_state = helper.query_config().unwrap().pool_state.price_state;
+        assert_eq!(
+            price_state.price_scale,
+            Decimal256::from_str(expected_price_scale).unwrap()
+        );
+        assert_eq!(price_state.oracle_price, price_state.price_scale);
+    }
+    let err = helper.reanchor(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DeviationBelowThreshold {}
+    );
+
+    // Trading resumes around the new price and resets the dormancy timer
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+
+    let err = helper.reanchor(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolNotDormant {
+            dormancy_period: MIN_REANCHOR_DORMANCY_PERIOD
+        }
+    );
+
+    helper
+        .update_config(
+            &owner,
+            &ConcentratedPoolUpdateParams::UpdateReanchorConfig {
+                reanchor_config: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(helper.query_reanchor_config().unwrap(), None);
+}
@@ -4,10 +4,11 @@ extern crate core;
 
 mod helper;
 
-use crate::helper::{common_pcl_params, dec_to_f64, f64_to_dec, AppExtension, Helper, TestCoin};
+use crate::helper::{common_pcl_params, dec_to_f64, f64_to_dec, Helper, TestCoin};
 use gridiron::asset::AssetInfoExt;
 use gridiron::cosmwasm_ext::AbsDiff;
 use gridiron::pair_concentrated::{ConcentratedPoolParams, ConcentratedPoolUpdateParams};
+use gridiron_mocks::MockAppExt;
 use gridiron_pair_concentrated::error::ContractError;
 use gridiron_pcl_common::error::PclError;
 use cosmwasm_std::{Addr, Decimal, Decimal256};
@@ -59,7 +60,7 @@ fn simulate_case(case: Vec<(usize, u128, u64)>) {
         i += 1;
 
         // Shift time so EMA will update oracle prices
-        helper.app.next_block(shift_time);
+        helper.app.next_block_after(shift_time);
     }
 }
 
@@ -111,7 +112,7 @@ fn simulate_fee_share_case(case: Vec<(usize, u128, u64)>) {
         i += 1;
 
         // Shift time so EMA will update oracle prices
-        helper.app.next_block(shift_time);
+        helper.app.next_block_after(shift_time);
     }
 }
 
@@ -171,7 +172,7 @@ fn simulate_provide_case(case: Vec<(impl Into<String>, u128, u128, u64)>) {
         }
 
         // Shift time so EMA will update oracle prices
-        helper.app.next_block(shift_time);
+        helper.app.next_block_after(shift_time);
     }
 
     let config = helper.query_config().unwrap();
@@ -463,7 +464,7 @@ fn simulate_mixed_case(cases: Vec<(PclEvent, u64)>) {
         }
 
         // Shift time so EMA will update oracle prices
-        helper.app.next_block(shift_time);
+        helper.app.next_block_after(shift_time);
     }
     let config = helper.query_config().unwrap();
     println!("price scale {}", config.pool_state.price_state.price_scale)
@@ -11,40 +11,47 @@ use cw_utils::parse_instantiate_response_data;
 use injective_cosmwasm::{InjectiveMsgWrapper, InjectiveQuerier, InjectiveQueryWrapper};
 use itertools::Itertools;
 
+use gridiron::allowlist::query_is_allowed;
 use gridiron::asset::{
     addr_opt_validate, format_lp_token_name, Asset, AssetInfo, AssetInfoExt, CoinsExt,
-    Decimal256Ext, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    Decimal256Ext, DecimalAsset, PairCreatedAt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
 };
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use gridiron::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
 use gridiron::factory::PairType;
 use gridiron::observation::{PrecommitObservation, OBSERVATIONS_SIZE};
-use gridiron::pair::{Cw20HookMsg, InstantiateMsg, MIN_TRADE_SIZE};
+use gridiron::pair::{
+    min_trade_size, Cw20HookMsg, InstantiateMsg, MinTradeSize, Referral, ReferralFee,
+    MAX_MIN_TRADE_FLOOR,
+};
 use gridiron::pair_concentrated::UpdatePoolParams;
 use gridiron::pair_concentrated_inj::{
     ConcentratedInjObParams, ConcentratedObPoolUpdateParams, ExecuteMsg,
 };
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
+use gridiron::querier::{query_factory_config, query_pair_fee_info, query_supply};
 use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
 use gridiron_circular_buffer::BufferManager;
 use gridiron_pcl_common::state::{
     AmpGamma, Config, PoolParams, PoolState, Precisions, PriceState,
 };
 use gridiron_pcl_common::utils::{
-    assert_max_spread, assert_slippage_tolerance, before_swap_check, calc_provide_fee,
-    check_asset_infos, check_assets, check_pair_registered, compute_swap, get_share_in_assets,
-    mint_liquidity_token_message,
+    assert_max_spread, assert_min_receive, assert_slippage_tolerance, before_swap_check,
+    calc_provide_fee, check_asset_infos, check_assets, check_pair_registered, compute_swap,
+    get_share_in_assets, mint_liquidity_token_message,
 };
 use gridiron_pcl_common::{calc_d, get_xcp};
 
 use crate::error::ContractError;
 use crate::orderbook::state::OrderbookState;
 use crate::orderbook::utils::{
-    get_subaccount_balances, is_allowed_for_begin_blocker, is_contract_active, leave_orderbook,
-    process_cumulative_trade,
+    get_subaccount_balances, get_subaccount_balances_or_degrade, is_allowed_for_begin_blocker,
+    is_contract_active, leave_orderbook, process_cumulative_trade,
+};
+use crate::state::{CONFIG, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR, OBSERVATIONS, OWNERSHIP_PROPOSAL};
+use crate::utils::{
+    accumulate_swap_sizes, assert_deadline, check_allowed, check_liquidity_enabled,
+    check_swaps_enabled, query_contract_balances, query_pools,
 };
-use crate::state::{CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL};
-use crate::utils::{accumulate_swap_sizes, query_contract_balances, query_pools};
 
 /// Contract name that is used for migration.
 pub(crate) const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -60,7 +67,7 @@ pub(crate) const LP_TOKEN_PRECISION: u8 = 6;
 pub fn instantiate(
     mut deps: DepsMut<InjectiveQueryWrapper>,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
     check_asset_infos(deps.api, &msg.asset_infos)?;
@@ -93,6 +100,7 @@ pub fn instantiate(
         &orderbook_params.orderbook_config.market_id,
         orderbook_params.orderbook_config.orders_number,
         orderbook_params.orderbook_config.min_trades_to_avg,
+        orderbook_params.orderbook_config.sides.unwrap_or_default(),
         &msg.asset_infos,
         base_precision,
     )?;
@@ -125,12 +133,17 @@ pub fn instantiate(
         },
     };
 
+    let creator = addr_opt_validate(deps.api, &msg.creator)?.unwrap_or(info.sender);
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("concentrated_inj_orderbook".to_string()),
+            creator: Some(creator),
+            created_at: Some(PairCreatedAt::from(&env.block)),
+            allowlist: None,
         },
         factory_addr,
         pool_params,
@@ -138,6 +151,7 @@ pub fn instantiate(
         owner: None,
         track_asset_balances: false, // TODO: decide whether to track asset balances in PCL inj pool
         fee_share: None,             // TODO: decide whether to enable fee sharing or not
+        staker_fee_share: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -216,13 +230,17 @@ pub fn reply(
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             deadline,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
 ///             offer_asset,
 ///             belief_price,
 ///             max_spread,
+///             min_receive,
 ///             to,
+///             deadline,
+///             referral,
 ///         }** Performs a swap operation with the specified parameters.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -240,22 +258,31 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-        ),
+            deadline,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
+            min_receive,
             to,
-            ..
+            deadline,
+            referral,
         } => {
+            assert_deadline(&env, deadline)?;
+
             offer_asset.info.check(deps.api)?;
             if !config.pair_info.asset_infos.contains(&offer_asset.info) {
                 return Err(ContractError::InvalidAsset(offer_asset.info.to_string()));
@@ -271,7 +298,9 @@ pub fn execute(
                 offer_asset,
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
             )
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
@@ -326,14 +355,18 @@ fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
     match from_binary(&cw20_msg.msg)? {
-        Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
-            deps,
-            env,
-            info,
-            Addr::unchecked(cw20_msg.sender),
-            cw20_msg.amount,
-            assets,
-        ),
+        Cw20HookMsg::WithdrawLiquidity { assets, deadline } => {
+            assert_deadline(&env, deadline)?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                cw20_msg.amount,
+                assets,
+            )
+        }
         _ => Err(ContractError::NotSupported {}),
     }
 }
@@ -363,6 +396,8 @@ where
     T: CustomMsg,
 {
     let mut config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &info.sender)?;
+    check_liquidity_enabled(deps.storage, &env)?;
 
     if !check_pair_registered(
         deps.querier,
@@ -404,13 +439,20 @@ where
 
     let mut ob_state = OrderbookState::load(deps.storage)?;
     let precisions = Precisions::new(deps.storage)?;
+    let inj_querier = InjectiveQuerier::new(&deps.querier);
+    let subacc_balances =
+        get_subaccount_balances_or_degrade(deps.storage, &inj_querier, &env, &ob_state)?;
+    // A degraded pair relies on the balances of the last reconciliation
+    let subacc_deposits = subacc_balances
+        .as_deref()
+        .unwrap_or(&ob_state.last_balances);
     let mut pools = query_pools(
         deps.querier,
         &env.contract.address,
         &config,
         &ob_state,
         &precisions,
-        None,
+        Some(subacc_deposits),
     )?;
 
     if pools[0].info.equal(&assets[1].info) {
@@ -445,17 +487,15 @@ where
     let mut xs = pools.iter().map(|asset| asset.amount).collect_vec();
 
     let mut messages = vec![];
-    let inj_querier = InjectiveQuerier::new(&deps.querier);
-    let subacc_balances = get_subaccount_balances(
-        &config.pair_info.asset_infos,
-        &inj_querier,
-        &ob_state.subaccount,
-    )?;
+    let degraded = subacc_balances.is_none();
     // In case begin blocker logic wasn't executed, we need to update price and send maker fees
-    if ob_state.last_balances != subacc_balances {
+    if let Some(subacc_balances) =
+        subacc_balances.filter(|balances| ob_state.last_balances != *balances)
+    {
         let base_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[0])?;
         let quote_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[1])?;
         let maker_fee_message = process_cumulative_trade(
+            deps.storage,
             deps.querier,
             &env,
             &ob_state,
@@ -532,7 +572,9 @@ where
     let mut slippage = Decimal256::zero();
 
     // If deposit doesn't diverge too much from the balanced share, we don't update the price
-    if assets_diff[0] >= MIN_TRADE_SIZE && assets_diff[1] >= MIN_TRADE_SIZE {
+    if assets_diff[0] >= min_trade_size(precisions.get_precision(&pools[0].info)?)
+        && assets_diff[1] >= min_trade_size(precisions.get_precision(&pools[1].info)?)
+    {
         slippage = assert_slippage_tolerance(
             &deposits,
             share,
@@ -565,7 +607,7 @@ where
     )?);
 
     ob_state.enabled = is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
-    ob_state.reconcile(deps.storage)?;
+    ob_state.reconcile_unless_degraded(deps.storage, degraded)?;
     CONFIG.save(deps.storage, &config)?;
 
     let attrs = vec![
@@ -584,7 +626,8 @@ where
 ///
 /// * **sender** address that will receive assets back from the pair contract
 ///
-/// * **amount** amount of provided LP tokens
+/// * **amount** amount of provided LP tokens. For an imbalanced withdraw it is the maximum amount
+/// of LP tokens to burn, the rest is sent back to the sender.
 ///
 /// * **assets** defines number of coins a user wants to withdraw per each asset.
 fn withdraw_liquidity(
@@ -600,42 +643,64 @@ fn withdraw_liquidity(
     if info.sender != config.pair_info.liquidity_token {
         return Err(ContractError::Unauthorized {});
     }
+    check_liquidity_enabled(deps.storage, &env)?;
 
     let precisions = Precisions::new(deps.storage)?;
     let ob_state = OrderbookState::load(deps.storage)?;
+    let inj_querier = InjectiveQuerier::new(&deps.querier);
+    let subacc_balances =
+        get_subaccount_balances_or_degrade(deps.storage, &inj_querier, &env, &ob_state)?;
+    // A degraded pair relies on the balances of the last reconciliation
+    let subacc_deposits = subacc_balances
+        .as_deref()
+        .unwrap_or(&ob_state.last_balances);
     let pools = query_pools(
         deps.querier,
         &config.pair_info.contract_addr,
         &config,
         &ob_state,
         &precisions,
-        None,
+        Some(subacc_deposits),
     )?;
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
     let mut response = Response::new();
     let mut messages = vec![];
 
-    let refund_assets = if assets.is_empty() {
+    let (refund_assets, burn_amount, last_price) = if assets.is_empty() {
         // Usual withdraw (balanced)
-        get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share)
+        let refund_assets =
+            get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share);
+
+        (refund_assets, amount, None)
     } else {
-        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+        let (refund_assets, burn_amount, last_price) = get_imbalanced_withdraw_share(
+            &env,
+            &config,
+            &precisions,
+            &pools,
+            &assets,
+            total_share,
+        )?;
+        if burn_amount > amount {
+            return Err(ContractError::NotEnoughLpTokens {
+                required: burn_amount,
+            });
+        }
+
+        (refund_assets, burn_amount, last_price)
     };
 
     let contract_balances =
         query_contract_balances(deps.querier, &env.contract.address, &config, &precisions)?;
 
+    let degraded = subacc_balances.is_none();
     // If contract does not have enough liquidity - withdraw all from orderbook
     if refund_assets[0].amount > contract_balances[0].amount
         || refund_assets[1].amount > contract_balances[1].amount
     {
-        let querier = InjectiveQuerier::new(&deps.querier);
-        let orderbook_balances = get_subaccount_balances(
-            &config.pair_info.asset_infos,
-            &querier,
-            &ob_state.subaccount,
-        )?;
+        // The orderbook balances are unknown while the pair is degraded
+        let orderbook_balances = subacc_balances.ok_or(ContractError::OrderbookDegraded {})?;
         response = leave_orderbook(&ob_state, orderbook_balances, &env).map_err(StdError::from)?;
     }
 
@@ -645,11 +710,22 @@ fn withdraw_liquidity(
     xs[0] -= refund_assets[0].amount;
     xs[1] -= refund_assets[1].amount;
     xs[1] *= config.pool_state.price_state.price_scale;
-    let amp_gamma = config.pool_state.get_amp_gamma(&env);
-    let d = calc_d(&xs, &amp_gamma)?;
-    config.pool_state.price_state.xcp_profit_real =
-        get_xcp(d, config.pool_state.price_state.price_scale)
-            / (total_share - amount).to_decimal256(LP_TOKEN_PRECISION)?;
+    let new_total_share = (total_share - burn_amount).to_decimal256(LP_TOKEN_PRECISION)?;
+    if let Some(last_price) = last_price {
+        // An imbalanced withdraw trades against the pool, so it updates the price as well
+        config.pool_state.update_price(
+            &config.pool_params,
+            &env,
+            new_total_share,
+            &xs,
+            last_price,
+        )?;
+    } else {
+        let amp_gamma = config.pool_state.get_amp_gamma(&env);
+        let d = calc_d(&xs, &amp_gamma)?;
+        config.pool_state.price_state.xcp_profit_real =
+            get_xcp(d, config.pool_state.price_state.price_scale) / new_total_share;
+    }
 
     let refund_assets = refund_assets
         .into_iter()
@@ -666,6 +742,8 @@ fn withdraw_liquidity(
     messages.extend(
         refund_assets
             .iter()
+            // An imbalanced withdraw may skip one of the assets
+            .filter(|asset| assets.is_empty() || !asset.amount.is_zero())
             .cloned()
             .map(|asset| asset.into_msg(&sender))
             .collect::<StdResult<Vec<_>>>()?,
@@ -673,23 +751,130 @@ fn withdraw_liquidity(
     messages.push(
         wasm_execute(
             &config.pair_info.liquidity_token,
-            &Cw20ExecuteMsg::Burn { amount },
+            &Cw20ExecuteMsg::Burn {
+                amount: burn_amount,
+            },
             vec![],
         )?
         .into(),
     );
+    // LP tokens which were not needed for an imbalanced withdraw go back to the sender
+    if burn_amount < amount {
+        messages.push(
+            wasm_execute(
+                &config.pair_info.liquidity_token,
+                &Cw20ExecuteMsg::Transfer {
+                    recipient: sender.to_string(),
+                    amount: amount - burn_amount,
+                },
+                vec![],
+            )?
+            .into(),
+        );
+    }
 
     CONFIG.save(deps.storage, &config)?;
-    ob_state.reconcile(deps.storage)?;
+    ob_state.reconcile_unless_degraded(deps.storage, degraded)?;
 
     Ok(response.add_messages(messages).add_attributes(vec![
         attr("action", "withdraw_liquidity"),
         attr("sender", sender),
-        attr("withdrawn_share", amount),
+        attr("withdrawn_share", burn_amount),
         attr("refund_assets", refund_assets.iter().join(", ")),
     ]))
 }
 
+/// Returns the assets to send and the amount of LP tokens to burn for an imbalanced withdraw.
+/// The imbalanced part is charged the same fee as an imbalanced provide. The last element is
+/// the price of the withdrawal if it diverges enough from the pool ratio to update the price.
+///
+/// * **pools** the pool reserves in decimal form.
+///
+/// * **assets** the exact amounts of assets to withdraw.
+///
+/// * **total_share** the total amount of LP tokens currently minted.
+fn get_imbalanced_withdraw_share(
+    env: &Env,
+    config: &Config,
+    precisions: &Precisions,
+    pools: &[DecimalAsset],
+    assets: &[Asset],
+    total_share: Uint128,
+) -> Result<(Vec<DecimalAsset>, Uint128, Option<Decimal256>), ContractError> {
+    if assets.len() > 2 || (assets.len() == 2 && assets[0].info.equal(&assets[1].info)) {
+        return Err(ContractError::InvalidNumberOfAssets(pools.len()));
+    }
+
+    let mut withdrawals = [Decimal256::zero(); 2];
+    for asset in assets {
+        let ind = pools
+            .iter()
+            .position(|pool| pool.info.equal(&asset.info))
+            .ok_or_else(|| ContractError::InvalidAsset(asset.info.to_string()))?;
+        withdrawals[ind] =
+            Decimal256::with_precision(asset.amount, precisions.get_precision(&asset.info)?)?;
+    }
+    if withdrawals.iter().all(|amount| amount.is_zero()) {
+        return Err(StdError::generic_err("Nothing to withdraw").into());
+    }
+    if pools
+        .iter()
+        .zip(withdrawals)
+        .any(|(pool, amount)| amount >= pool.amount)
+    {
+        return Err(ContractError::WithdrawExceedsReserves {});
+    }
+
+    let price_scale = config.pool_state.price_state.price_scale;
+    let amp_gamma = config.pool_state.get_amp_gamma(env);
+    let mut old_xp = pools.iter().map(|pool| pool.amount).collect_vec();
+    old_xp[1] *= price_scale;
+    let mut new_xp = pools
+        .iter()
+        .zip(withdrawals)
+        .map(|(pool, amount)| pool.amount - amount)
+        .collect_vec();
+    new_xp[1] *= price_scale;
+    let old_d = calc_d(&old_xp, &amp_gamma)?;
+    let new_d = calc_d(&new_xp, &amp_gamma)?;
+
+    let mut iwithdrawals = withdrawals;
+    iwithdrawals[1] *= price_scale;
+    let total_share = total_share.to_decimal256(LP_TOKEN_PRECISION)?;
+    let share = total_share * old_d.saturating_sub(new_d) / old_d
+        * (Decimal256::one() + calc_provide_fee(&iwithdrawals, &new_xp, &config.pool_params));
+
+    // Same as for balanced provides, the price is updated only if the withdrawal is imbalanced
+    let share_ratio = share / total_share;
+    let assets_diff = [
+        withdrawals[0].diff(pools[0].amount * share_ratio),
+        withdrawals[1].diff(pools[1].amount * share_ratio),
+    ];
+    let last_price = if assets_diff[0] >= min_trade_size(precisions.get_precision(&pools[0].info)?)
+        && assets_diff[1] >= min_trade_size(precisions.get_precision(&pools[1].info)?)
+    {
+        Some(assets_diff[0] / assets_diff[1])
+    } else {
+        None
+    };
+
+    let refund_assets = pools
+        .iter()
+        .zip(withdrawals)
+        .map(|(pool, amount)| DecimalAsset {
+            info: pool.info.clone(),
+            amount,
+        })
+        .collect();
+
+    // LP tokens to burn are rounded up
+    Ok((
+        refund_assets,
+        share.to_uint(LP_TOKEN_PRECISION)? + Uint128::one(),
+        last_price,
+    ))
+}
+
 /// Performs swap operation with the specified parameters.
 ///
 /// * **sender** is the sender of the swap operation.
@@ -700,7 +885,13 @@ fn withdraw_liquidity(
 ///
 /// * **max_spread** sets the maximum spread of the swap operation.
 ///
+/// * **min_receive** sets the minimum amount of ask tokens to receive. When set, it replaces
+/// the `belief_price` and `max_spread` check.
+///
 /// * **to** sets the recipient of the swap operation.
+///
+/// * **referral** sets the referral which receives a share of the return amount.
+#[allow(clippy::too_many_arguments)]
 fn swap<T>(
     deps: DepsMut<InjectiveQueryWrapper>,
     env: Env,
@@ -708,7 +899,9 @@ fn swap<T>(
     offer_asset: Asset,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
     to: Option<Addr>,
+    referral: Option<Referral>,
 ) -> Result<Response<T>, ContractError>
 where
     T: CustomMsg,
@@ -717,15 +910,37 @@ where
     let offer_asset_prec = precisions.get_precision(&offer_asset.info)?;
     let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
     let mut config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &sender)?;
+    check_swaps_enabled(deps.storage, &env)?;
     let mut ob_state = OrderbookState::load(deps.storage)?;
 
+    let min_swap = MinTradeSize::new(
+        offer_asset.info.clone(),
+        offer_asset_prec,
+        MIN_TRADE_FLOOR.may_load(deps.storage)?,
+    )?
+    .swap;
+    if offer_asset.amount < min_swap {
+        return Err(ContractError::BelowMinTradeSize {
+            amount: offer_asset.amount,
+            min: min_swap,
+        });
+    }
+
+    let inj_querier = InjectiveQuerier::new(&deps.querier);
+    let subacc_balances =
+        get_subaccount_balances_or_degrade(deps.storage, &inj_querier, &env, &ob_state)?;
+    // A degraded pair relies on the balances of the last reconciliation
+    let subacc_deposits = subacc_balances
+        .as_deref()
+        .unwrap_or(&ob_state.last_balances);
     let mut pools = query_pools(
         deps.querier,
         &env.contract.address,
         &config,
         &ob_state,
         &precisions,
-        None,
+        Some(subacc_deposits),
     )?;
 
     let (offer_ind, _) = pools
@@ -742,10 +957,10 @@ where
     let mut xs = pools.iter().map(|asset| asset.amount).collect_vec();
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let mut maker_fee_share = Decimal256::zero();
     if fee_info.fee_address.is_some() {
@@ -754,17 +969,15 @@ where
 
     let mut messages = vec![];
 
-    let inj_querier = InjectiveQuerier::new(&deps.querier);
-    let subacc_balances = get_subaccount_balances(
-        &config.pair_info.asset_infos,
-        &inj_querier,
-        &ob_state.subaccount,
-    )?;
+    let degraded = subacc_balances.is_none();
     // In case begin blocker logic wasn't executed, we need to update price and send maker fees
-    if ob_state.last_balances != subacc_balances {
+    if let Some(subacc_balances) =
+        subacc_balances.filter(|balances| ob_state.last_balances != *balances)
+    {
         let base_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[0])?;
         let quote_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[1])?;
         let maker_fee_message = process_cumulative_trade(
+            deps.storage,
             deps.querier,
             &env,
             &ob_state,
@@ -795,21 +1008,30 @@ where
 
     let return_amount = swap_result.dy.to_uint(ask_asset_prec)?;
     let spread_amount = swap_result.spread_fee.to_uint(ask_asset_prec)?;
-    assert_max_spread(
-        belief_price,
-        max_spread,
-        offer_asset.amount,
-        return_amount,
-        spread_amount,
-    )?;
+    // The referral fee is paid out of the return amount, so the pool balances are not affected
+    let referral_fee =
+        ReferralFee::new(deps.api, referral, fee_info.max_referral_bps, return_amount)?;
+    let receive_amount = return_amount - referral_fee.amount;
+    if let Some(min_receive) = min_receive {
+        assert_min_receive(min_receive, receive_amount)?;
+    } else {
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_asset.amount,
+            swap_result.dy - referral_fee.amount.to_decimal256(ask_asset_prec)?,
+            swap_result.spread_fee,
+            ask_asset_prec,
+        )?;
+    }
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?
         .to_decimal256(LP_TOKEN_PRECISION)?;
 
     // Skip very small trade sizes which could significantly mess up the price due to rounding errors,
     // especially if token precisions are 18.
-    if (swap_result.dy + swap_result.maker_fee) >= MIN_TRADE_SIZE
-        && offer_asset_dec.amount >= MIN_TRADE_SIZE
+    if (swap_result.dy + swap_result.maker_fee) >= min_trade_size(ask_asset_prec)
+        && offer_asset_dec.amount >= min_trade_size(offer_asset_prec)
     {
         let last_price = swap_result.calc_last_price(offer_asset_dec.amount, offer_ind);
 
@@ -825,9 +1047,10 @@ where
     messages.push(
         pools[ask_ind]
             .info
-            .with_balance(return_amount)
+            .with_balance(receive_amount)
             .into_msg(&receiver)?,
     );
+    messages.extend(referral_fee.into_msg(&pools[ask_ind].info)?);
 
     let mut maker_fee = Uint128::zero();
     if let Some(fee_address) = fee_info.fee_address {
@@ -844,7 +1067,9 @@ where
     // Store time series data in precommit observation.
     // Skipping small unsafe values which can seriously mess oracle price due to rounding errors.
     // This data will be reflected in observations in the next action.
-    if offer_asset_dec.amount >= MIN_TRADE_SIZE && swap_result.dy >= MIN_TRADE_SIZE {
+    if offer_asset_dec.amount >= min_trade_size(offer_asset_prec)
+        && swap_result.dy >= min_trade_size(ask_asset_prec)
+    {
         let (base_amount, quote_amount) = if offer_ind == 0 {
             (offer_asset.amount, return_amount)
         } else {
@@ -855,23 +1080,26 @@ where
 
     CONFIG.save(deps.storage, &config)?;
     ob_state.enabled = is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
-    ob_state.reconcile(deps.storage)?;
+    ob_state.reconcile_unless_degraded(deps.storage, degraded)?;
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "swap"),
-        attr("sender", sender),
-        attr("receiver", receiver),
-        attr("offer_asset", offer_asset_dec.info.to_string()),
-        attr("ask_asset", pools[ask_ind].info.to_string()),
-        attr("offer_amount", offer_asset.amount),
-        attr("return_amount", return_amount),
-        attr("spread_amount", spread_amount),
-        attr(
-            "commission_amount",
-            swap_result.total_fee.to_uint(ask_asset_prec)?,
-        ),
-        attr("maker_fee_amount", maker_fee),
-    ]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "swap"),
+            attr("sender", sender),
+            attr("receiver", receiver),
+            attr("offer_asset", offer_asset_dec.info.to_string()),
+            attr("ask_asset", pools[ask_ind].info.to_string()),
+            attr("offer_amount", offer_asset.amount),
+            attr("return_amount", receive_amount),
+            attr("spread_amount", spread_amount),
+            attr(
+                "commission_amount",
+                swap_result.total_fee.to_uint(ask_asset_prec)?,
+            ),
+            attr("maker_fee_amount", maker_fee),
+        ])
+        .add_attributes(referral_fee.attributes()))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -887,10 +1115,12 @@ fn update_config<T>(
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
     let owner = config.owner.as_ref().unwrap_or(&factory_config.owner);
-    if info.sender != *owner {
+    // The factory is allowed to update the allowlist on behalf of the protocol
+    if info.sender != *owner && info.sender != config.factory_addr {
         return Err(ContractError::Unauthorized {});
     }
 
+    let previous_fees = config.combined_fees();
     let attributes = match from_binary::<ConcentratedObPoolUpdateParams>(&params)? {
         ConcentratedObPoolUpdateParams::Update(update_params) => {
             let mut attrs = config.pool_params.update_params(update_params)?;
@@ -911,14 +1141,74 @@ fn update_config<T>(
             config.pool_state.stop_promotion(&env);
             vec![attr("action", "stop_changing_amp_gamma")]
         }
-        ConcentratedObPoolUpdateParams::UpdateOrderbookParams { orders_number } => {
-            OrderbookState::update_orders_number(deps.storage, orders_number)?;
-            vec![
-                attr("action", "update_orderbook_params"),
-                attr("orders_number", orders_number.to_string()),
-            ]
+        ConcentratedObPoolUpdateParams::UpdateOrderbookParams {
+            orders_number,
+            sides,
+        } => {
+            OrderbookState::update_params(deps.storage, orders_number, sides)?;
+            let mut attrs = vec![attr("action", "update_orderbook_params")];
+            if let Some(orders_number) = orders_number {
+                attrs.push(attr("orders_number", orders_number.to_string()));
+            }
+            if let Some(sides) = sides {
+                attrs.push(attr("sides", format!("{sides:?}")));
+            }
+            attrs
+        }
+        ConcentratedObPoolUpdateParams::UpdateMinTradeFloor { min_trade_floor } => {
+            let mut attrs = vec![attr("action", "update_min_trade_floor")];
+            if let Some(floor) = min_trade_floor {
+                if floor.is_zero() || floor > MAX_MIN_TRADE_FLOOR {
+                    return Err(ContractError::MinTradeFloorOutOfBounds {});
+                }
+
+                MIN_TRADE_FLOOR.save(deps.storage, &floor)?;
+                attrs.push(attr("min_trade_floor", floor.to_string()));
+            } else {
+                MIN_TRADE_FLOOR.remove(deps.storage);
+            }
+            attrs
+        }
+        ConcentratedObPoolUpdateParams::UpdateAllowlist { allowlist } => {
+            let mut attrs = vec![attr("action", "update_allowlist")];
+            config.pair_info.allowlist = addr_opt_validate(deps.api, &allowlist)?;
+            if let Some(allowlist) = &config.pair_info.allowlist {
+                // Make sure the allowlist answers the standard query so the pair isn't bricked
+                query_is_allowed(&deps.querier, allowlist, &env.contract.address)?;
+                attrs.push(attr("allowlist", allowlist));
+            }
+            attrs
+        }
+        ConcentratedObPoolUpdateParams::ScheduleMaintenance { window } => {
+            if let Some(window) = window {
+                if !window.is_valid(env.block.time.seconds()) {
+                    return Err(ContractError::InvalidMaintenanceWindow {});
+                }
+
+                MAINTENANCE_WINDOW.save(deps.storage, &window)?;
+                vec![
+                    attr("action", "schedule_maintenance"),
+                    attr("maintenance_mode", window.mode.to_string()),
+                    attr("maintenance_start", window.start.to_string()),
+                    attr("maintenance_end", window.end.to_string()),
+                ]
+            } else {
+                MAINTENANCE_WINDOW.remove(deps.storage);
+                vec![attr("action", "cancel_maintenance")]
+            }
         }
     };
+
+    // Config updates can't raise the combined fees above the cap set in the factory
+    if let Some(max_combined_fee_bps) = factory_config.max_combined_fee_bps {
+        let fees = config.combined_fees();
+        if fees.exceed_cap(&previous_fees, max_combined_fee_bps) {
+            return Err(ContractError::CombinedFeeExceedsCap {
+                combined_fee_bps: fees.combined_fee_bps(),
+                max_combined_fee_bps,
+            });
+        }
+    }
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default().add_attributes(attributes))
@@ -966,6 +1256,7 @@ pub fn orderbook_emergency_withdraw(
         let base_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[0])?;
         let quote_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[1])?;
         let maker_fee_message = process_cumulative_trade(
+            deps.storage,
             deps.querier,
             &env,
             &ob_state,
@@ -1,7 +1,8 @@
-use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{Addr, ConversionOverflowError, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 use gridiron::asset::MINIMUM_LIQUIDITY_AMOUNT;
+use gridiron::pair::{ReferralError, MAX_MIN_TRADE_FLOOR};
 use gridiron_circular_buffer::error::BufferError;
 use gridiron_pcl_common::error::PclError;
 
@@ -23,9 +24,36 @@ pub enum ContractError {
     #[error("{0}")]
     PclError(#[from] PclError),
 
+    #[error("{0}")]
+    Referral(#[from] ReferralError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
+    #[error("Swap amount {amount} is below the min trade size {min}")]
+    BelowMinTradeSize { amount: Uint128, min: Uint128 },
+
+    #[error(
+        "Min trade floor must be greater than zero and at most {}",
+        MAX_MIN_TRADE_FLOOR
+    )]
+    MinTradeFloorOutOfBounds {},
+
+    #[error("Address {address} is not approved by the pair allowlist")]
+    AddressNotAllowed { address: Addr },
+
+    #[error("Maintenance window must end after it starts and after the current block time")]
+    InvalidMaintenanceWindow {},
+
+    #[error("Swaps are halted for maintenance until {end}")]
+    SwapsHaltedForMaintenance { end: u64 },
+
+    #[error("Liquidity provision and withdrawals are halted for maintenance until {end}")]
+    LiquidityHaltedForMaintenance { end: u64 },
+
     #[error("You need to provide init params")]
     InitParamsNotFound {},
 
@@ -49,4 +77,19 @@ pub enum ContractError {
 
     #[error("Operation is not supported")]
     NotSupported {},
+
+    #[error("Liquidity can't be withdrawn from the orderbook while exchange queries fail")]
+    OrderbookDegraded {},
+
+    #[error("Combined fee {combined_fee_bps} bps exceeds the cap of {max_combined_fee_bps} bps")]
+    CombinedFeeExceedsCap {
+        combined_fee_bps: u32,
+        max_combined_fee_bps: u16,
+    },
+
+    #[error("Not enough LP tokens. You need {required} LP tokens.")]
+    NotEnoughLpTokens { required: Uint128 },
+
+    #[error("Withdraw amounts exceed the pool reserves")]
+    WithdrawExceedsReserves {},
 }
@@ -43,6 +43,7 @@ pub fn migrate(
                 &params.market_id,
                 params.orders_number,
                 params.min_trades_to_avg,
+                params.sides.unwrap_or_default(),
                 &config.pair_info.asset_infos,
                 base_precision,
             )?;
@@ -57,7 +58,7 @@ pub fn migrate(
             let contract_info = cw2::get_contract_version(deps.storage)?;
             match contract_info.contract.as_str() {
                 CONTRACT_NAME => match contract_info.version.as_str() {
-                    "2.0.3" | "2.0.4" => {}
+                    "2.0.3" | "2.0.4" | "2.2.2" => {}
                     _ => {
                         return Err(StdError::generic_err(format!(
                             "Can't migrate from {} {}",
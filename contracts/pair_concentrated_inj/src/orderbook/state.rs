@@ -1,13 +1,17 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal256, Env, QuerierWrapper, StdError, StdResult, Storage, Uint256};
+use cosmwasm_std::{
+    Decimal256, Env, QuerierWrapper, StdError, StdResult, Storage, Uint128, Uint256,
+};
 use cw_storage_plus::Item;
 use injective_cosmwasm::{
     InjectiveQuerier, InjectiveQueryWrapper, MarketId, MarketType, SubaccountId,
 };
 
 use gridiron::asset::{Asset, AssetInfo, AssetInfoExt};
-use gridiron::cosmwasm_ext::ConvertInto;
-use gridiron::pair_concentrated_inj::OrderbookStateResponse;
+use gridiron::cosmwasm_ext::{ConvertInto, IntegerToDecimal};
+use gridiron::pair_concentrated_inj::{
+    OrderbookPnlResponse, OrderbookSides, OrderbookStateResponse, OrderbookStatusResponse,
+};
 
 use crate::orderbook::consts::{MIN_TRADES_TO_AVG_LIMITS, ORDER_SIZE_LIMITS};
 use crate::orderbook::error::OrderbookError;
@@ -47,6 +51,10 @@ pub struct OrderbookState {
     /// The higher this number is, the more gas the contract consumes on begin blocker and
     /// the more liquidity the contract places in the order book.
     pub orders_number: u8,
+    /// Sides of the order book the contract deploys liquidity to.
+    /// Liquidity of a disabled side stays in the AMM.
+    #[serde(default)]
+    pub sides: OrderbookSides,
     /// Minimum number of trades to accumulate average trade size.
     /// Orderbook integration will not be enabled until this number is reached.
     pub min_trades_to_avg: u32,
@@ -65,6 +73,7 @@ impl OrderbookState {
         market_id: &str,
         orders_number: u8,
         min_trades_to_avg: u32,
+        sides: OrderbookSides,
         asset_infos: &[AssetInfo],
         base_precision: u8,
     ) -> StdResult<Self> {
@@ -90,6 +99,7 @@ impl OrderbookState {
                 asset_infos[1].with_balance(0u8),
             ],
             orders_number,
+            sides,
             min_trades_to_avg,
             ready: false,
             enabled: true,
@@ -201,6 +211,20 @@ impl OrderbookState {
         )
     }
 
+    /// Set flag that reconciliation is needed unless the pair runs in the degraded AMM-only mode.
+    /// A degraded pair leaves the orderbook untouched until exchange queries work again.
+    pub fn reconcile_unless_degraded(
+        self,
+        storage: &mut dyn Storage,
+        degraded: bool,
+    ) -> StdResult<()> {
+        if degraded {
+            self.save(storage)
+        } else {
+            self.reconcile(storage)
+        }
+    }
+
     /// Set flag that reconciliation is done. Save current subaccount balances.
     pub fn reconciliation_done(
         self,
@@ -222,21 +246,184 @@ impl OrderbookState {
         self.ready = ready;
     }
 
-    /// Validates new orders number parameter and saves it in storage.
-    pub fn update_orders_number(storage: &mut dyn Storage, orders_number: u8) -> StdResult<()> {
-        validate_param!(
-            orders_number,
-            orders_number,
-            *ORDER_SIZE_LIMITS.start(),
-            *ORDER_SIZE_LIMITS.end()
-        );
+    /// Validates new orderbook parameters and saves them in storage.
+    /// Changing the orderbook sides triggers reconciliation on the next begin blocker.
+    pub fn update_params(
+        storage: &mut dyn Storage,
+        orders_number: Option<u8>,
+        sides: Option<OrderbookSides>,
+    ) -> StdResult<()> {
+        let mut ob_state = Self::load(storage)?;
+
+        if let Some(orders_number) = orders_number {
+            validate_param!(
+                orders_number,
+                orders_number,
+                *ORDER_SIZE_LIMITS.start(),
+                *ORDER_SIZE_LIMITS.end()
+            );
+            ob_state.orders_number = orders_number;
+        }
+
+        if let Some(sides) = sides {
+            if sides != ob_state.sides {
+                ob_state.sides = sides;
+                ob_state.need_reconcile = true;
+            }
+        }
+
+        ob_state.save(storage)
+    }
+}
+
+/// The last orderbook fill which is marked to market on the next one
+#[cw_serde]
+pub struct LastFill {
+    /// Quote asset amount traded in the fill
+    pub quote_amount: Decimal256,
+    /// Whether the orderbook bought the quote asset (i.e. sold the base asset)
+    pub bought_quote: bool,
+    /// Pool price right before the fill
+    pub price: Decimal256,
+}
+
+/// Cumulative realized PnL of the liquidity deployed to the orderbook.
+/// Pool prices are denominated in the base asset thus all values are in the base asset as well.
+#[cw_serde]
+#[derive(Default)]
+pub struct OrderbookPnl {
+    /// Number of processed orderbook fills
+    pub fills: u64,
+    /// Total base asset amount traded through the orderbook
+    pub base_volume: Uint128,
+    /// Total quote asset amount traded through the orderbook
+    pub quote_volume: Uint128,
+    /// Spread captured by fills against the pool price right before them, net of maker fees
+    pub fees_earned: Decimal256,
+    /// Spread lost by fills which happened at a worse price than the pool price
+    pub fees_lost: Decimal256,
+    /// Value gained because the pool price moved in favor of a fill until the next one
+    pub adverse_selection_gain: Decimal256,
+    /// Value lost because the pool price moved against a fill until the next one
+    pub adverse_selection_loss: Decimal256,
+    /// The last fill which is marked to market on the next one
+    pub last_fill: Option<LastFill>,
+}
+
+const OB_PNL: Item<OrderbookPnl> = Item::new("orderbook_pnl");
+
+impl OrderbookPnl {
+    pub fn load(storage: &dyn Storage) -> StdResult<Self> {
+        Ok(OB_PNL.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        OB_PNL.save(storage, self)
+    }
+
+    /// Accounts an orderbook fill.
+    /// * **base_amount** and **quote_amount** are the changes of the subaccount balances.
+    /// * **sold_base** is true if the orderbook sold the base asset.
+    /// * **maker_fee** is the maker fee paid out of the pool, denominated in the base asset.
+    /// * **price** is the pool price right before the fill.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_fill(
+        &mut self,
+        base_amount: Uint128,
+        quote_amount: Uint128,
+        base_precision: u8,
+        quote_precision: u8,
+        sold_base: bool,
+        maker_fee: Decimal256,
+        price: Decimal256,
+    ) -> StdResult<()> {
+        self.fills += 1;
+        self.base_volume += base_amount;
+        self.quote_volume += quote_amount;
+
+        let base_amount = base_amount.to_decimal256(base_precision)?;
+        let quote_amount = quote_amount.to_decimal256(quote_precision)?;
 
-        OB_CONFIG
-            .update(storage, |mut ob_state| {
-                ob_state.orders_number = orders_number;
-                Ok(ob_state)
-            })
-            .map(|_| ())
+        // Mark the previous fill to market at the current pool price
+        if let Some(last_fill) = self.last_fill.take() {
+            let value = last_fill.quote_amount * price.abs_diff(last_fill.price);
+            if last_fill.bought_quote == (price >= last_fill.price) {
+                self.adverse_selection_gain += value;
+            } else {
+                self.adverse_selection_loss += value;
+            }
+        }
+
+        let (received, paid) = if sold_base {
+            (quote_amount * price, base_amount + maker_fee)
+        } else {
+            (base_amount, quote_amount * price + maker_fee)
+        };
+        if received >= paid {
+            self.fees_earned += received - paid;
+        } else {
+            self.fees_lost += paid - received;
+        }
+
+        self.last_fill = Some(LastFill {
+            quote_amount,
+            bought_quote: sold_base,
+            price,
+        });
+
+        Ok(())
+    }
+}
+
+/// Tracks whether exchange queries fail and the pair runs in the degraded AMM-only mode.
+#[cw_serde]
+#[derive(Default)]
+pub struct OrderbookStatus {
+    /// The timestamp (in seconds) when the pair entered the degraded mode
+    pub degraded_since: Option<u64>,
+    /// The error of the last failed exchange query
+    pub last_error: Option<String>,
+}
+
+const OB_STATUS: Item<OrderbookStatus> = Item::new("orderbook_status");
+
+impl OrderbookStatus {
+    pub fn load(storage: &dyn Storage) -> StdResult<Self> {
+        Ok(OB_STATUS.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        OB_STATUS.save(storage, self)
+    }
+
+    /// Enters the degraded mode (unless the pair is degraded already) and records the error.
+    pub fn degrade(&mut self, now: u64, error: String) {
+        self.degraded_since.get_or_insert(now);
+        self.last_error = Some(error);
+    }
+}
+
+impl From<OrderbookStatus> for OrderbookStatusResponse {
+    fn from(value: OrderbookStatus) -> Self {
+        Self {
+            degraded: value.degraded_since.is_some(),
+            degraded_since: value.degraded_since,
+            last_error: value.last_error,
+        }
+    }
+}
+
+impl From<OrderbookPnl> for OrderbookPnlResponse {
+    fn from(value: OrderbookPnl) -> Self {
+        Self {
+            fills: value.fills,
+            base_volume: value.base_volume,
+            quote_volume: value.quote_volume,
+            fees_earned: value.fees_earned,
+            fees_lost: value.fees_lost,
+            adverse_selection_gain: value.adverse_selection_gain,
+            adverse_selection_loss: value.adverse_selection_loss,
+        }
     }
 }
 
@@ -250,9 +437,66 @@ impl From<OrderbookState> for OrderbookStateResponse {
             need_reconcile: value.need_reconcile,
             last_balances: value.last_balances,
             orders_number: value.orders_number,
+            sides: value.sides,
             min_trades_to_avg: value.min_trades_to_avg,
             ready: value.ready,
             enabled: value.enabled,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_record_fill() {
+        let mut pnl = OrderbookPnl::default();
+
+        // Orderbook sells 100 base for 51 quote while the pool price is 2 base per quote
+        pnl.record_fill(
+            Uint128::new(100_000000),
+            Uint128::new(51_000000),
+            6,
+            6,
+            true,
+            Decimal256::from_str("0.5").unwrap(),
+            Decimal256::from_str("2").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pnl.fees_earned, Decimal256::from_str("1.5").unwrap());
+        assert_eq!(pnl.fees_lost, Decimal256::zero());
+
+        // The quote asset depreciated before the next fill in which the orderbook buys 100 base for 52 quote
+        pnl.record_fill(
+            Uint128::new(100_000000),
+            Uint128::new(52_000000),
+            6,
+            6,
+            false,
+            Decimal256::zero(),
+            Decimal256::from_str("1.9").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            pnl,
+            OrderbookPnl {
+                fills: 2,
+                base_volume: Uint128::new(200_000000),
+                quote_volume: Uint128::new(103_000000),
+                fees_earned: Decimal256::from_str("2.7").unwrap(),
+                fees_lost: Decimal256::zero(),
+                adverse_selection_gain: Decimal256::zero(),
+                adverse_selection_loss: Decimal256::from_str("5.1").unwrap(),
+                last_fill: Some(LastFill {
+                    quote_amount: Decimal256::from_str("52").unwrap(),
+                    bought_quote: false,
+                    price: Decimal256::from_str("1.9").unwrap(),
+                }),
+            }
+        );
+    }
+}
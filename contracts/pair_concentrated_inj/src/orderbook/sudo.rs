@@ -14,8 +14,8 @@ use crate::orderbook::error::OrderbookError;
 use crate::orderbook::msg::SudoMsg;
 use crate::orderbook::state::OrderbookState;
 use crate::orderbook::utils::{
-    cancel_all_orders, compute_swap, get_subaccount_balances, leave_orderbook,
-    process_cumulative_trade, update_spot_orders, SpotOrdersFactory,
+    cancel_all_orders, compute_swap, get_subaccount_balances, get_subaccount_balances_or_degrade,
+    leave_orderbook, process_cumulative_trade, update_spot_orders, SpotOrdersFactory,
 };
 use crate::state::{CONFIG, OBSERVATIONS};
 use crate::utils::query_pools;
@@ -44,7 +44,12 @@ fn begin_blocker(
     }
     let querier = InjectiveQuerier::new(&deps.querier);
 
-    let balances = get_subaccount_balances(&ob_state.asset_infos, &querier, &ob_state.subaccount)?;
+    let balances =
+        match get_subaccount_balances_or_degrade(deps.storage, &querier, &env, &ob_state)? {
+            Some(balances) => balances,
+            // The orderbook is reconciled once exchange queries work again
+            None => return Ok(Response::new()),
+        };
 
     if ob_state.need_reconcile || ob_state.last_balances != balances {
         let mut messages = vec![];
@@ -70,6 +75,7 @@ fn begin_blocker(
         // and we need to repeg and reconcile orderbook
         if ob_state.last_balances != balances {
             let maker_fee_message = process_cumulative_trade(
+                deps.storage,
                 deps.querier,
                 &env,
                 &ob_state,
@@ -153,8 +159,13 @@ fn begin_blocker(
                 return leave_orderbook(&ob_state, balances, &env);
             }
 
-            orders_factory.sell(sell_price, sell_amount);
-            orders_factory.buy(buy_price, buy_amount);
+            // Liquidity of a disabled side is not deposited and stays in the AMM
+            if ob_state.sides.asks_enabled() {
+                orders_factory.sell(sell_price, sell_amount);
+            }
+            if ob_state.sides.bids_enabled() {
+                orders_factory.buy(buy_price, buy_amount);
+            }
         }
 
         let total_deposits =
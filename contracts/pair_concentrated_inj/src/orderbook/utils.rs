@@ -4,7 +4,7 @@ use std::fmt::Debug;
 
 use cosmwasm_std::{
     Addr, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256, Env, QuerierWrapper, Response,
-    StdError, StdResult,
+    StdError, StdResult, Storage,
 };
 use injective_cosmwasm::{
     checked_address_to_subaccount_id, create_batch_update_orders_msg, create_withdraw_msg,
@@ -15,7 +15,7 @@ use tiny_keccak::Hasher;
 
 use gridiron::asset::{Asset, AssetInfo, AssetInfoExt, DecimalAsset, PairInfo};
 use gridiron::cosmwasm_ext::{AbsDiff, ConvertInto, IntegerToDecimal};
-use gridiron::querier::{query_fee_info, query_supply};
+use gridiron::querier::{query_pair_fee_info, query_supply};
 use gridiron_pcl_common::calc_y;
 use gridiron_pcl_common::state::{AmpGamma, Config, Precisions};
 
@@ -23,7 +23,7 @@ use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
 use crate::orderbook::consts::{GAS_FEE_DENOM, SUBACC_NONCE};
 use crate::orderbook::error::OrderbookError;
-use crate::orderbook::state::OrderbookState;
+use crate::orderbook::state::{OrderbookPnl, OrderbookState, OrderbookStatus};
 
 /// Calculate hash from two binary slices.
 pub fn calc_hash(a1: &[u8], a2: &[u8]) -> String {
@@ -144,6 +144,35 @@ pub fn get_subaccount_balances(
         .collect()
 }
 
+/// Fetches subaccount balances without failing the transaction when the exchange module can't be
+/// queried. A failed query switches the pair to the degraded AMM-only mode and returns `None`,
+/// so the caller falls back to the balances of the last reconciliation and skips the orderbook.
+/// The first successful query leaves the degraded mode.
+pub fn get_subaccount_balances_or_degrade(
+    storage: &mut dyn Storage,
+    querier: &InjectiveQuerier,
+    env: &Env,
+    ob_state: &OrderbookState,
+) -> StdResult<Option<Vec<Asset>>> {
+    let mut status = OrderbookStatus::load(storage)?;
+
+    match get_subaccount_balances(&ob_state.asset_infos, querier, &ob_state.subaccount) {
+        Ok(balances) => {
+            if status.degraded_since.take().is_some() {
+                status.save(storage)?;
+            }
+
+            Ok(Some(balances))
+        }
+        Err(err) => {
+            status.degrade(env.block.time.seconds(), err.to_string());
+            status.save(storage)?;
+
+            Ok(None)
+        }
+    }
+}
+
 /// Cancels all orders and withdraws all balances from the orderbook.
 pub fn leave_orderbook(
     ob_state: &OrderbookState,
@@ -397,6 +426,7 @@ impl<'a> SpotOrdersFactory<'a> {
 /// Process filled orders as one cumulative trade. Send maker fees and run repegging algorithm.
 #[allow(clippy::too_many_arguments)]
 pub fn process_cumulative_trade<C, T>(
+    storage: &mut dyn Storage,
     querier: QuerierWrapper<C>,
     env: &Env,
     ob_state: &OrderbookState,
@@ -421,12 +451,17 @@ where
     // converting into internal representation
     ixs[1] *= config.pool_state.price_state.price_scale;
 
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
 
+    // Pool price before the fill is used as the reference for orderbook PnL
+    let price_before = config.pool_state.price_state.last_price;
+    let sold_base = ob_state.last_balances[0].amount > subacc_balances[0].amount;
+    let mut maker_fee_value = Decimal256::zero();
+
     let mut messages = vec![];
     if let Some(fee_addr) = fee_info.fee_address {
         // This is safe conversion because fee_rate is always <= 1
@@ -444,6 +479,7 @@ where
                 let maker_fee_dec = maker_fee.to_decimal256(base_precision)?;
                 ixs[0] -= maker_fee_dec;
                 pools[0] -= maker_fee_dec;
+                maker_fee_value = maker_fee_dec;
                 messages.push(
                     config.pair_info.asset_infos[0]
                         .with_balance(maker_fee)
@@ -456,6 +492,7 @@ where
                 let maker_fee_dec = maker_fee.to_decimal256(quote_precision)?;
                 ixs[1] -= maker_fee_dec * config.pool_state.price_state.price_scale;
                 pools[1] -= maker_fee_dec;
+                maker_fee_value = maker_fee_dec * price_before;
                 messages.push(
                     config.pair_info.asset_infos[1]
                         .with_balance(maker_fee)
@@ -483,6 +520,18 @@ where
         .pool_state
         .update_price(&config.pool_params, env, total_lp, &ixs, fba_price)?;
 
+    let mut pnl = OrderbookPnl::load(storage)?;
+    pnl.record_fill(
+        bal_diffs[0],
+        bal_diffs[1],
+        base_precision,
+        quote_precision,
+        sold_base,
+        maker_fee_value,
+        price_before,
+    )?;
+    pnl.save(storage)?;
+
     Ok(messages)
 }
 
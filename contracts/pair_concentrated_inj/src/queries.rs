@@ -5,15 +5,18 @@ use cosmwasm_std::{
 use injective_cosmwasm::InjectiveQueryWrapper;
 use itertools::Itertools;
 
-use gridiron::asset::Asset;
+use gridiron::asset::{Asset, AssetInfo};
 use gridiron::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use gridiron::observation::query_observation;
+use gridiron::observation::{query_observation, query_volatility};
 use gridiron::pair::{
-    ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, MinTradeSize, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    INTERFACE_DEADLINE, INTERFACE_OBSERVATIONS, INTERFACE_REFERRAL, INTERFACE_VERSIONED_QUERIES,
 };
 use gridiron::pair_concentrated::ConcentratedPoolParams;
-use gridiron::pair_concentrated_inj::{OrderbookStateResponse, QueryMsg};
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
+use gridiron::pair_concentrated_inj::{
+    OrderbookPnlResponse, OrderbookStateResponse, OrderbookStatusResponse, QueryMsg,
+};
+use gridiron::querier::{query_factory_config, query_pair_fee_info, query_supply};
 use gridiron_pcl_common::state::Precisions;
 use gridiron_pcl_common::utils::{
     before_swap_check, compute_offer_amount, compute_swap, get_share_in_assets,
@@ -22,10 +25,17 @@ use gridiron_pcl_common::{calc_d, get_xcp};
 
 use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
-use crate::orderbook::state::OrderbookState;
-use crate::state::{CONFIG, OBSERVATIONS};
+use crate::orderbook::state::{OrderbookPnl, OrderbookState, OrderbookStatus};
+use crate::state::{CONFIG, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR, OBSERVATIONS};
 use crate::utils::query_pools;
 
+gridiron::supported_interfaces!(
+    INTERFACE_DEADLINE,
+    INTERFACE_OBSERVATIONS,
+    INTERFACE_REFERRAL,
+    INTERFACE_VERSIONED_QUERIES,
+);
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -37,6 +47,9 @@ use crate::utils::query_pools;
 /// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 ///
+/// * **QueryMsg::ShareValueIn { amount, asset_info }** Returns the value of a specific amount of LP tokens
+/// denominated in one of the pool assets using an [`Asset`] object.
+///
 /// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
 ///
 /// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation  using
@@ -46,6 +59,18 @@ use crate::utils::query_pools;
 /// pool using a [`CumulativePricesResponse`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::Volatility { window }** Returns the annualized realized volatility of the pool
+/// price over the last `window` seconds using a [`VolatilityResponse`] object.
+///
+/// * **QueryMsg::OrderbookStatus {}** Returns whether the pair runs in the degraded AMM-only mode
+/// and the last exchange query error using an [`OrderbookStatusResponse`] object.
+///
+/// * **QueryMsg::VersionedPair {}**, **QueryMsg::VersionedPool {}**, **QueryMsg::VersionedConfig {}**
+/// and **QueryMsg::VersionedSimulation { .. }** Answer the `pair`, `pool`, `config` and `simulation`
+/// queries with the response wrapped into a [`gridiron::versioned::Versioned`] envelope.
+///
+/// * **QueryMsg::SupportsInterface { interface }** Returns whether the pair implements the interface.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<InjectiveQueryWrapper>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -56,6 +81,10 @@ pub fn query(deps: Deps<InjectiveQueryWrapper>, env: Env, msg: QueryMsg) -> StdR
         QueryMsg::Share { amount } => to_binary(
             &query_share(deps, amount).map_err(|err| StdError::generic_err(err.to_string()))?,
         ),
+        QueryMsg::ShareValueIn { amount, asset_info } => to_binary(
+            &query_share_value_in(deps, amount, asset_info)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
         QueryMsg::Simulation { offer_asset, .. } => to_binary(
             &query_simulation(deps, env, offer_asset)
                 .map_err(|err| StdError::generic_err(format!("{err}")))?,
@@ -73,10 +102,26 @@ pub fn query(deps: Deps<InjectiveQueryWrapper>, env: Env, msg: QueryMsg) -> StdR
         QueryMsg::Observe { seconds_ago } => {
             to_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
         }
+        QueryMsg::Volatility { window } => {
+            to_binary(&query_volatility(deps, env, OBSERVATIONS, window)?)
+        }
         QueryMsg::OrderbookState {} => {
             let resp: OrderbookStateResponse = OrderbookState::load(deps.storage)?.into();
             to_binary(&resp)
         }
+        QueryMsg::OrderbookPnl {} => {
+            let resp: OrderbookPnlResponse = OrderbookPnl::load(deps.storage)?.into();
+            to_binary(&resp)
+        }
+        QueryMsg::OrderbookStatus {} => {
+            let resp: OrderbookStatusResponse = OrderbookStatus::load(deps.storage)?.into();
+            to_binary(&resp)
+        }
+        msg @ (QueryMsg::VersionedPair {}
+        | QueryMsg::VersionedPool {}
+        | QueryMsg::VersionedConfig {}
+        | QueryMsg::VersionedSimulation { .. }) => msg.query_versioned(|msg| query(deps, env, msg)),
+        QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
     }
 }
 
@@ -149,6 +194,47 @@ fn query_share(
     Ok(refund_assets)
 }
 
+/// Returns the value of a specific amount of LP tokens denominated in one of the pool assets.
+/// The other asset is priced using the current price scale.
+///
+/// * **amount** is the amount of LP tokens to value.
+///
+/// * **asset_info** is the pool asset in which the value is denominated.
+fn query_share_value_in(
+    deps: Deps<InjectiveQueryWrapper>,
+    amount: Uint128,
+    asset_info: AssetInfo,
+) -> Result<Asset, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let ob_config = OrderbookState::load(deps.storage)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools(
+        deps.querier,
+        &config.pair_info.contract_addr,
+        &config,
+        &ob_config,
+        &precisions,
+        None,
+    )?;
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let share = get_share_in_assets(&pools, amount.saturating_sub(Uint128::one()), total_share);
+
+    // The price scale is the price of the second asset denominated in the first one
+    let price_scale = config.pool_state.price_state.price_scale;
+    let value = if asset_info.equal(&share[0].info) {
+        share[0].amount + share[1].amount * price_scale
+    } else if asset_info.equal(&share[1].info) {
+        share[1].amount + share[0].amount / price_scale
+    } else {
+        return Err(StdError::generic_err("Given asset does not belong in the pair").into());
+    };
+
+    Ok(Asset {
+        amount: value.to_uint(precisions.get_precision(&asset_info)?)?,
+        info: asset_info,
+    })
+}
+
 /// Returns information about a swap simulation.
 pub fn query_simulation(
     deps: Deps<InjectiveQueryWrapper>,
@@ -182,10 +268,10 @@ pub fn query_simulation(
     let xs = pools.iter().map(|asset| asset.amount).collect_vec();
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let mut maker_fee_share = Decimal256::zero();
     if fee_info.fee_address.is_some() {
@@ -294,6 +380,21 @@ where
     .map_err(|e| StdError::generic_err(format!("{e}")))?;
 
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+
+    let precisions = Precisions::new(deps.storage)?;
+    let min_trade_floor = MIN_TRADE_FLOOR.may_load(deps.storage)?;
+    let min_trade_sizes = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| {
+            let precision = precisions
+                .get_precision(asset_info)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            MinTradeSize::new(asset_info.clone(), precision, min_trade_floor)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
     Ok(ConfigResponse {
         block_time_last: 0, // keeping this field for backwards compatibility
         params: Some(to_binary(&ConcentratedPoolParams {
@@ -311,6 +412,10 @@ where
         })?),
         owner: config.owner.unwrap_or(factory_config.owner),
         factory_addr: config.factory_addr,
+        creator: config.pair_info.creator,
+        created_at: config.pair_info.created_at,
+        min_trade_sizes,
+        maintenance_window: MAINTENANCE_WINDOW.may_load(deps.storage)?,
     })
 }
 
@@ -1,12 +1,20 @@
+use cosmwasm_std::Decimal256;
 use cw_storage_plus::Item;
 
 use gridiron::common::OwnershipProposal;
 use gridiron::observation::Observation;
+use gridiron::pair::{MaintenanceWindow, PAIR_CONFIG_KEY};
 use gridiron_circular_buffer::CircularBuffer;
 use gridiron_pcl_common::state::Config;
 
 /// Stores pool parameters and state.
-pub const CONFIG: Item<Config> = Item::new("config");
+pub const CONFIG: Item<Config> = Item::new(PAIR_CONFIG_KEY);
+
+/// Stores the floor (in decimal form) below which swaps are rejected
+pub const MIN_TRADE_FLOOR: Item<Decimal256> = Item::new("min_trade_floor");
+
+/// Stores the scheduled maintenance window
+pub const MAINTENANCE_WINDOW: Item<MaintenanceWindow> = Item::new("maintenance_window");
 
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
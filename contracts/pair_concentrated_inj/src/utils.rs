@@ -13,7 +13,7 @@ use gridiron_pcl_common::utils::{safe_sma_buffer_not_full, safe_sma_calculation}
 use crate::error::ContractError;
 use crate::orderbook::state::OrderbookState;
 use crate::orderbook::utils::get_subaccount_balances_dec;
-use crate::state::OBSERVATIONS;
+use crate::state::{MAINTENANCE_WINDOW, OBSERVATIONS};
 
 pub(crate) fn query_contract_balances(
     querier: QuerierWrapper<InjectiveQueryWrapper>,
@@ -33,6 +33,48 @@ pub(crate) fn query_contract_balances(
         .collect()
 }
 
+/// Checks that the pair allowlist approves the address. Every address is allowed in
+/// permissionless pairs.
+///
+/// * **address** is the address which provides liquidity or swaps.
+pub(crate) fn check_allowed(
+    querier: &QuerierWrapper<InjectiveQueryWrapper>,
+    config: &Config,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !config.pair_info.is_allowed(querier, address)? {
+        return Err(ContractError::AddressNotAllowed {
+            address: address.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that swaps are not halted by a post-only maintenance window.
+pub(crate) fn check_swaps_enabled(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_swaps(env.block.time.seconds()) => {
+            Err(ContractError::SwapsHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that liquidity provision and withdrawals are not halted by a swap-only maintenance
+/// window.
+pub(crate) fn check_liquidity_enabled(
+    storage: &dyn Storage,
+    env: &Env,
+) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_liquidity(env.block.time.seconds()) => {
+            Err(ContractError::LiquidityHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Returns current pool's volumes where amount is in [`Decimal256`] form.
 pub(crate) fn query_pools(
     querier: QuerierWrapper<InjectiveQueryWrapper>,
@@ -242,3 +284,15 @@ mod tests {
         assert!(ob_state.ready, "Contract should be ready");
     }
 }
+
+/// Ensures the transaction is not included after `deadline`.
+///
+/// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+pub(crate) fn assert_deadline(env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {
+            Err(ContractError::TransactionExpired {})
+        }
+        _ => Ok(()),
+    }
+}
@@ -290,6 +290,8 @@ pub struct InjMockModule {
     pub orderbook: RefCell<HashMap<MarketId, Vec<(Addr, ShortSpotOrder)>>>,
     pub markets: RefCell<HashMap<MarketId, (String, String)>>,
     pub enabled_contracts: RefCell<HashMap<Addr, (MockFundingMode, bool)>>,
+    /// Makes subaccount deposit queries fail as if the exchange module was unavailable
+    pub deposits_unavailable: RefCell<bool>,
 }
 
 impl InjMockModule {
@@ -301,6 +303,7 @@ impl InjMockModule {
             orderbook: Default::default(),
             markets: Default::default(),
             enabled_contracts: Default::default(),
+            deposits_unavailable: Default::default(),
         }
     }
 }
@@ -645,6 +648,10 @@ impl Module for InjMockModule {
                 subaccount_id,
                 denom,
             } => {
+                if *self.deposits_unavailable.borrow() {
+                    return Err(StdError::generic_err("exchange module is unavailable").into());
+                }
+
                 let balance = self
                     .deposit
                     .borrow()
@@ -28,7 +28,8 @@ use gridiron::pair::{
 };
 use gridiron::pair_concentrated::{ConcentratedPoolParams, ConcentratedPoolUpdateParams};
 use gridiron::pair_concentrated_inj::{
-    ConcentratedInjObParams, ExecuteMsg, OrderbookConfig, OrderbookStateResponse, QueryMsg,
+    ConcentratedInjObParams, ExecuteMsg, OrderbookConfig, OrderbookPnlResponse,
+    OrderbookStateResponse, OrderbookStatusResponse, QueryMsg,
 };
 use gridiron_mocks::cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
 use gridiron_pair_concentrated_injective::contract::{execute, instantiate, reply};
@@ -267,10 +268,9 @@ impl Helper {
                 native_coins: vec![
                     ("uluna".to_owned(), 6),
                     ("uusd".to_owned(), 6),
-                    ("GRID".to_owned(), 6),
-                    ("USDC".to_owned(), 6),
-                    ("FOO".to_owned(), 5),
-                    ("BAR".to_owned(), 6),
+                    ("usdc".to_owned(), 6),
+                    ("foo".to_owned(), 5),
+                    ("bar".to_owned(), 6),
                     ("inj".to_owned(), 18),
                     ("grid".to_owned(), 6),
                 ],
@@ -329,6 +329,7 @@ impl Helper {
                     market_id,
                     orders_number: 5,
                     min_trades_to_avg: 1,
+                    sides: None,
                 },
             })
             .unwrap()
@@ -405,6 +406,7 @@ impl Helper {
             slippage_tolerance,
             auto_stake: None,
             receiver: None,
+            deadline: None,
         };
 
         self.app
@@ -420,7 +422,11 @@ impl Helper {
         let msg = Cw20ExecuteMsg::Send {
             contract: self.pair_addr.to_string(),
             amount: Uint128::from(amount),
-            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets }).unwrap(),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+                assets,
+                deadline: None,
+            })
+            .unwrap(),
         };
 
         self.app
@@ -452,7 +458,10 @@ impl Helper {
                         ask_asset_info: None,
                         belief_price,
                         max_spread,
+                        min_receive: None,
                         to: None,
+                        deadline: None,
+                        referral: None,
                     })
                     .unwrap(),
                 };
@@ -473,7 +482,10 @@ impl Helper {
                     ask_asset_info: None,
                     belief_price,
                     max_spread,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 };
 
                 self.app
@@ -532,6 +544,18 @@ impl Helper {
             .query_wasm_smart(&self.pair_addr, &QueryMsg::OrderbookState {})
     }
 
+    pub fn query_ob_pnl(&self) -> StdResult<OrderbookPnlResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::OrderbookPnl {})
+    }
+
+    pub fn query_ob_status(&self) -> StdResult<OrderbookStatusResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::OrderbookStatus {})
+    }
+
     pub fn try_update_ticks(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             sender.clone(),
@@ -671,6 +695,20 @@ impl Helper {
         )
     }
 
+    pub fn query_share_value_in(
+        &self,
+        amount: impl Into<Uint128>,
+        asset_info: &AssetInfo,
+    ) -> StdResult<Asset> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::ShareValueIn {
+                amount: amount.into(),
+                asset_info: asset_info.clone(),
+            },
+        )
+    }
+
     pub fn query_pool(&self) -> StdResult<PoolResponse> {
         self.app
             .wrap()
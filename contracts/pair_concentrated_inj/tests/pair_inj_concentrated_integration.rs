@@ -40,7 +40,7 @@ fn check_wrong_initialization() {
 
     let err = Helper::new(
         &owner,
-        vec![TestCoin::native("uluna"), TestCoin::native("GRID")],
+        vec![TestCoin::native("uluna"), TestCoin::native("grid")],
         wrong_params,
         true,
     )
@@ -60,7 +60,7 @@ fn check_wrong_initialization() {
 
     let err = Helper::new(
         &owner,
-        vec![TestCoin::native("uluna"), TestCoin::native("GRID")],
+        vec![TestCoin::native("uluna"), TestCoin::native("grid")],
         wrong_params,
         true,
     )
@@ -80,7 +80,7 @@ fn check_wrong_initialization() {
 
     let err = Helper::new(
         &owner,
-        vec![TestCoin::native("uluna"), TestCoin::native("GRID")],
+        vec![TestCoin::native("uluna"), TestCoin::native("grid")],
         wrong_params,
         true,
     )
@@ -94,7 +94,7 @@ fn check_wrong_initialization() {
     // check instantiation with valid params
     Helper::new(
         &owner,
-        vec![TestCoin::native("uluna"), TestCoin::native("GRID")],
+        vec![TestCoin::native("uluna"), TestCoin::native("grid")],
         params,
         true,
     )
@@ -105,7 +105,7 @@ fn check_wrong_initialization() {
 fn provide_and_withdraw() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let params = ConcentratedPoolParams {
         price_scale: Decimal::from_ratio(2u8, 1u8),
@@ -244,19 +244,6 @@ fn provide_and_withdraw() {
     helper.provide_liquidity(&user5, &assets).unwrap();
     assert_eq!(57271_023590, helper.token_balance(&helper.lp_token, &user5));
 
-    // check that imbalanced withdraw is currently disabled
-    let withdraw_assets = vec![
-        helper.assets[&test_coins[0]].with_balance(10_000_000000u128),
-        helper.assets[&test_coins[1]].with_balance(5_000_000000u128),
-    ];
-    let err = helper
-        .withdraw_liquidity(&user1, 7071_067711, withdraw_assets)
-        .unwrap_err();
-    assert_eq!(
-        err.root_cause().to_string(),
-        "Generic error: Imbalanced withdraw is currently disabled"
-    );
-
     // user1 withdraws 1/10 of his LP tokens
     helper
         .withdraw_liquidity(&user1, 7071_067711, vec![])
@@ -280,13 +267,45 @@ fn provide_and_withdraw() {
     );
     assert_eq!(46910_055478, helper.coin_balance(&test_coins[0], &user2));
     assert_eq!(26653_440612, helper.coin_balance(&test_coins[1], &user2));
+
+    // user5 exits into a single asset
+    let user5_lp = helper.token_balance(&helper.lp_token, &user5);
+    let withdraw_assets = vec![helper.assets[&test_coins[0]].with_balance(10_000_000000u128)];
+    let err = helper
+        .withdraw_liquidity(&user5, 1_000000, withdraw_assets.clone())
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::NotEnoughLpTokens { .. }
+    ));
+
+    let err = helper
+        .withdraw_liquidity(
+            &user5,
+            user5_lp,
+            vec![helper.assets[&test_coins[1]].with_balance(1_000_000_000000u128)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WithdrawExceedsReserves {},
+        err.downcast().unwrap()
+    );
+
+    helper
+        .withdraw_liquidity(&user5, user5_lp, withdraw_assets)
+        .unwrap();
+    assert_eq!(10_000_000000, helper.coin_balance(&test_coins[0], &user5));
+    assert_eq!(0, helper.coin_balance(&test_coins[1], &user5));
+    // The unused LP tokens are sent back
+    let burnt = user5_lp - helper.token_balance(&helper.lp_token, &user5);
+    assert!(burnt > 0 && burnt < user5_lp);
 }
 
 #[test]
 fn check_imbalanced_provide() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let mut params = ConcentratedPoolParams {
         price_scale: Decimal::from_ratio(2u8, 1u8),
@@ -334,7 +353,7 @@ fn check_imbalanced_provide() {
 fn provide_with_different_precision() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("FOO"), TestCoin::native("BAR")];
+    let test_coins = vec![TestCoin::native("foo"), TestCoin::native("bar")];
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
 
@@ -380,7 +399,7 @@ fn provide_with_different_precision() {
 fn swap_different_precisions() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("FOO"), TestCoin::native("BAR")];
+    let test_coins = vec![TestCoin::native("foo"), TestCoin::native("bar")];
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
 
@@ -451,7 +470,7 @@ fn check_reverse_swap() {
 fn check_swaps_simple() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
 
@@ -544,7 +563,7 @@ fn check_swaps_with_price_update() {
     let owner = Addr::unchecked("owner");
     let half = Decimal::from_ratio(1u8, 2u8);
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
 
@@ -586,7 +605,7 @@ fn check_swaps_with_price_update() {
 fn provides_and_swaps() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
 
@@ -630,7 +649,7 @@ fn provides_and_swaps() {
 fn check_amp_gamma_change() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let params = ConcentratedPoolParams {
         amp: f64_to_dec(40f64),
@@ -720,7 +739,7 @@ fn check_amp_gamma_change() {
 fn check_prices() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uusd"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::native("usdc")];
 
     let helper = Helper::new(&owner, test_coins.clone(), common_pcl_params(), true).unwrap();
     let err = helper.query_prices().unwrap_err();
@@ -732,7 +751,7 @@ fn check_prices() {
 fn update_owner() {
     let owner = Addr::unchecked("owner");
 
-    let test_coins = vec![TestCoin::native("uusd"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::native("usdc")];
 
     let mut helper = Helper::new(&owner, test_coins, common_pcl_params(), true).unwrap();
 
@@ -892,6 +911,12 @@ fn check_orderbook_integration() {
     assert_eq!(ob_state.need_reconcile, false); // sudo endpoint was already executed and liq. deployed in OB
     assert_eq!(ob_state.ready, true);
 
+    // Orders were not filled thus no PnL was realized
+    let pnl = helper.query_ob_pnl().unwrap();
+    assert_eq!(pnl.fills, 0);
+    assert_eq!(pnl.fees_earned, Decimal256::zero());
+    assert_eq!(pnl.adverse_selection_loss, Decimal256::zero());
+
     let ob_config = helper.query_ob_config().unwrap();
     let querier_wrapper = helper.app.wrap();
     let inj_querier = InjectiveQuerier::new(&querier_wrapper);
@@ -970,6 +995,107 @@ fn check_orderbook_integration() {
     assert_eq!(grid_pool, total_grid);
 }
 
+#[test]
+fn degraded_mode_when_exchange_queries_fail() {
+    let owner = generate_inj_address();
+    let test_coins = vec![TestCoin::native("inj"), TestCoin::native("grid")];
+
+    let params = ConcentratedPoolParams {
+        price_scale: f64_to_dec(0.5),
+        ..common_pcl_params()
+    };
+
+    let mut app = mock_inj_app(|_, _, _| {});
+    app.create_market("inj", "grid").unwrap();
+    let mut helper =
+        Helper::new_with_app(app, &owner, test_coins.clone(), params, true, None).unwrap();
+    helper
+        .app
+        .enable_contract(
+            helper.pair_addr.clone(),
+            MockFundingMode::GrantOnly(helper.owner.clone()),
+        )
+        .unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance((500_000f64 * 1e18) as u128),
+        helper.assets[&test_coins[1]].with_balance((1_000_000f64 * 1e6) as u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    for _ in 0..3 {
+        helper
+            .swap(
+                &owner,
+                &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+                None,
+            )
+            .unwrap();
+        helper.next_block(false).unwrap();
+    }
+    let ob_state = helper.query_ob_config_smart().unwrap();
+    assert!(ob_state.ready && !ob_state.need_reconcile);
+
+    let status = helper.query_ob_status().unwrap();
+    assert!(!status.degraded);
+    assert_eq!(status.last_error, None);
+
+    let set_deposits_unavailable = |helper: &mut Helper, unavailable: bool| {
+        helper.app.init_modules(|router, _, _| {
+            *router.custom.deposits_unavailable.borrow_mut() = unavailable;
+        });
+    };
+
+    // Failing exchange queries don't fail user transactions
+    set_deposits_unavailable(&mut helper, true);
+    let degraded_since = helper.app.block_info().time.seconds();
+    helper
+        .swap(
+            &owner,
+            &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+            None,
+        )
+        .unwrap();
+
+    let status = helper.query_ob_status().unwrap();
+    assert!(status.degraded);
+    assert_eq!(status.degraded_since, Some(degraded_since));
+    assert!(status.last_error.is_some());
+
+    // The orderbook isn't reconciled while the pair is degraded
+    assert!(!helper.query_ob_config_smart().unwrap().need_reconcile);
+    helper.next_block(false).unwrap();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper
+        .swap(
+            &owner,
+            &helper.assets[&test_coins[0]].with_balance((500.0 * 1e18) as u128),
+            None,
+        )
+        .unwrap();
+    assert!(!helper.query_ob_config_smart().unwrap().need_reconcile);
+
+    // The pair stays degraded since the first failure
+    let status = helper.query_ob_status().unwrap();
+    assert_eq!(status.degraded_since, Some(degraded_since));
+
+    // The first successful query leaves the degraded mode, the last error is kept
+    set_deposits_unavailable(&mut helper, false);
+    helper.next_block(false).unwrap();
+    helper
+        .swap(
+            &owner,
+            &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+            None,
+        )
+        .unwrap();
+    assert!(helper.query_ob_config_smart().unwrap().need_reconcile);
+
+    let status = helper.query_ob_status().unwrap();
+    assert!(!status.degraded);
+    assert_eq!(status.degraded_since, None);
+    assert!(status.last_error.is_some());
+}
+
 #[test]
 fn check_last_withdraw() {
     let owner = generate_inj_address();
@@ -1470,6 +1596,7 @@ fn test_migrate_cl_to_orderbook_cl() {
                 .to_string(),
             orders_number: 5,
             min_trades_to_avg: 1,
+            sides: None,
         },
     };
 
@@ -1496,6 +1623,7 @@ fn test_migrate_cl_to_orderbook_cl() {
             market_id,
             orders_number: 5,
             min_trades_to_avg: 1,
+            sides: None,
         },
     };
     helper
@@ -1598,6 +1726,7 @@ fn test_wrong_assets_order() {
             market_id,
             orders_number: 5,
             min_trades_to_avg: *MIN_TRADES_TO_AVG_LIMITS.start(),
+            sides: None,
         },
     };
     let new_code_id = helper.app.store_code(orderbook_pair_contract());
@@ -22,7 +22,7 @@ fn simulate_case(case: Vec<(usize, u128, u64)>) {
     let owner = Addr::unchecked("owner");
     let user = Addr::unchecked("user");
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let balances = vec![100_000_000_000000u128, 100_000_000_000000u128];
 
@@ -69,7 +69,7 @@ fn simulate_provide_case(case: Vec<(impl Into<String>, u128, u128, u64)>) {
     let loss_tolerance = 0.05; // allowed loss per provide due to integer math withing contract
     let xcp_profit_real_tolerance = Decimal256::raw(100000000); // 1e-10
 
-    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("USDC")];
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("usdc")];
 
     let initial_price_scale = Decimal::one();
 
@@ -42,6 +42,7 @@ impl<'a> PairBonded<'a> for Contract<'a> {
         offer_asset: Asset,
         _belief_price: Option<Decimal>,
         _max_spread: Option<Decimal>,
+        min_receive: Option<Uint128>,
         to: Option<Addr>,
     ) -> Result<Response, ContractError> {
         let config = CONFIG.load(deps.storage)?;
@@ -114,6 +115,7 @@ impl<'a> PairBonded<'a> for Contract<'a> {
                 ask_asset_info: ask_pool.info,
                 sender,
                 receiver,
+                min_receive,
             })?,
         }));
 
@@ -221,6 +223,7 @@ impl<'a> PairBonded<'a> for Contract<'a> {
         _offer_asset: Asset,
         _belief_price: Option<Decimal>,
         _max_spread: Option<Decimal>,
+        _min_receive: Option<Uint128>,
         _to: Option<String>,
     ) -> Result<Response, ContractError> {
         Err(ContractError::NotSupported {})
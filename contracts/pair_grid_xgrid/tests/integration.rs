@@ -1,6 +1,6 @@
 #![cfg(not(tarpaulin_include))]
 
-use gridiron::asset::{Asset, AssetInfo, PairInfo};
+use gridiron::asset::{Asset, AssetInfo, PairCreatedAt, PairInfo};
 use gridiron::factory::{InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType};
 use gridiron::pair::{
     ConfigResponse, Cw20HookMsg, InstantiateMsg as PairInstantiateMsg, ReverseSimulationResponse,
@@ -148,6 +148,8 @@ fn instantiate_staking(app: &mut App, owner: Addr, token_instance: &Addr) -> (Ad
         token_code_id: xgrid_code_id,
         deposit_token_addr: token_instance.to_string(),
         marketing: None,
+        revenue_asset: None,
+        redemption: None,
     };
 
     let staking_instance = app
@@ -189,6 +191,7 @@ fn instantiate_gridiron(mut router: &mut App, owner: &Addr) -> GridironContracts
         ],
         token_code_id: 123,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: Some(
             to_binary(&Params {
                 grid_addr: token_instance.clone(),
@@ -270,6 +273,7 @@ fn test_pair_instantiation() {
         ],
         token_code_id: 123,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: None,
     };
 
@@ -300,6 +304,7 @@ fn test_pair_instantiation() {
         ],
         token_code_id: 123,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: Some(
             to_binary(&Params {
                 grid_addr: token_instance.clone(),
@@ -413,7 +418,10 @@ fn test_pair_swap() {
                     ask_asset_info: None,
                     belief_price: None,
                     max_spread: None,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 })
                 .unwrap(),
             },
@@ -433,7 +441,10 @@ fn test_pair_swap() {
                     ask_asset_info: None,
                     belief_price: None,
                     max_spread: None,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 })
                 .unwrap(),
             },
@@ -546,7 +557,10 @@ fn test_pair_swap() {
                     ask_asset_info: None,
                     belief_price: None,
                     max_spread: None,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 })
                 .unwrap(),
             },
@@ -566,7 +580,10 @@ fn test_pair_swap() {
                     ask_asset_info: None,
                     belief_price: None,
                     max_spread: None,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 })
                 .unwrap(),
             },
@@ -607,6 +624,7 @@ fn test_unsupported_methods() {
                 slippage_tolerance: None,
                 auto_stake: None,
                 receiver: None,
+                deadline: None,
             },
             &[],
         )
@@ -662,7 +680,9 @@ fn test_unsupported_methods() {
                 },
                 belief_price: None,
                 max_spread: None,
+                min_receive: None,
                 to: None,
+                deadline: None,
             },
             &[],
         )
@@ -680,6 +700,7 @@ fn test_queries() {
     let mut router = mock_app(owner.clone(), vec![]);
 
     let contracts = instantiate_gridiron(&mut router, &owner);
+    let created_at = Some(PairCreatedAt::from(&router.block_info()));
 
     let res: ConfigResponse = router
         .wrap()
@@ -690,8 +711,12 @@ fn test_queries() {
         ConfigResponse {
             block_time_last: 0u64,
             params: None,
-            owner,
-            factory_addr: contracts.factory_instance
+            owner: owner.clone(),
+            factory_addr: contracts.factory_instance,
+            creator: Some(owner.clone()),
+            created_at: created_at.clone(),
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         }
     );
 
@@ -712,7 +737,10 @@ fn test_queries() {
             ],
             contract_addr: contracts.pair_instance.clone(),
             liquidity_token: Addr::unchecked(""),
-            pair_type: PairType::Custom("Bonded".to_string())
+            pair_type: PairType::Custom("Bonded".to_string()),
+            creator: Some(owner),
+            created_at,
+            allowlist: None,
         }
     );
 }
@@ -14,41 +14,51 @@ use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use cw_utils::parse_instantiate_response_data;
 use itertools::Itertools;
 
+use gridiron::allowlist::query_is_allowed;
 use gridiron::asset::{
     addr_opt_validate, check_swap_parameters, format_lp_token_name, Asset, AssetInfo, CoinsExt,
-    Decimal256Ext, DecimalAsset, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    Decimal256Ext, DecimalAsset, PairCreatedAt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
 };
 
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use gridiron::cosmwasm_ext::IntegerToDecimal;
-use gridiron::factory::PairType;
+use gridiron::events::ProvideEvent;
+use gridiron::factory::{FeeInfoResponse, PairType, QueryMsg as FactoryQueryMsg};
 use gridiron::pair::{
-    ConfigResponse, FeeShareConfig, InstantiateMsg, StablePoolParams, StablePoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS, MIN_TRADE_SIZE,
+    check_min_lp_to_receive, min_trade_size, mint_liquidity_token_message, CombinedFees,
+    ConfigResponse, FeeShareConfig, InstantiateMsg, MinTradeSize, Referral, ReferralFee,
+    StablePoolParams, StablePoolUpdateParams, DEFAULT_SLIPPAGE, INTERFACE_DEADLINE,
+    INTERFACE_MIN_LP_TO_RECEIVE, INTERFACE_OBSERVATIONS, INTERFACE_REFERRAL,
+    INTERFACE_VERSIONED_QUERIES, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS, MAX_MIN_TRADE_FLOOR,
 };
 
 use crate::migration::{migrate_config_from_v21, migrate_config_to_v210};
 use gridiron::observation::{query_observation, PrecommitObservation, OBSERVATIONS_SIZE};
 use gridiron::pair::{
     Cw20HookMsg, ExecuteMsg, MigrateMsg, PoolResponse, QueryMsg, ReverseSimulationResponse,
-    SimulationResponse, StablePoolConfig,
+    SimulationResponse, StablePoolConfig, VirtualPriceResponse,
 };
-use gridiron::querier::{query_factory_config, query_fee_info, query_supply};
+use gridiron::points::swap_hook_msg;
+use gridiron::querier::{query_factory_config, query_pair_fee_info, query_supply};
 use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
 use gridiron::DecimalCheckedOps;
 use gridiron_circular_buffer::BufferManager;
+use gridiron_pcl_common::consts::MA_HALF_TIME_LIMITS;
 
 use crate::error::ContractError;
 use crate::math::{
-    calc_y, compute_d, AMP_PRECISION, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME,
+    calc_y, compute_d, AMP_PRECISION, MAX_AMP, MAX_AMP_CHANGE, MAX_N_COINS, MIN_AMP_CHANGING_TIME,
+    MIN_N_COINS,
 };
 use crate::state::{
-    get_precision, store_precisions, Config, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL,
+    store_precisions, Config, Precisions, CONFIG, MAINTENANCE_WINDOW, MIN_TRADE_FLOOR,
+    OBSERVATIONS, OWNERSHIP_PROPOSAL, PRECISIONS, VIRTUAL_PRICE_MA,
 };
 use crate::utils::{
-    accumulate_swap_sizes, adjust_precision, check_asset_infos, check_assets, check_cw20_in_pool,
+    accumulate_swap_sizes, adjust_precision, calc_virtual_price, check_allowed, check_asset_infos,
+    check_assets, check_cw20_in_pool, check_liquidity_enabled, check_swaps_enabled,
     compute_current_amp, compute_swap, determine_base_quote_amount, get_share_in_assets,
-    mint_liquidity_token_message, select_pools, SwapResult,
+    query_pools_decimal, record_virtual_price, select_pools, SwapResult,
 };
 
 /// Contract name that is used for migration.
@@ -57,21 +67,30 @@ const CONTRACT_NAME: &str = "gridiron-pair-stable";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID of sub-message.
 const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
-/// Number of assets in the pool.
-const N_COINS: usize = 2;
+
+gridiron::supported_interfaces!(
+    INTERFACE_DEADLINE,
+    INTERFACE_MIN_LP_TO_RECEIVE,
+    INTERFACE_OBSERVATIONS,
+    INTERFACE_VERSIONED_QUERIES,
+    INTERFACE_REFERRAL,
+);
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     check_asset_infos(deps.api, &msg.asset_infos)?;
 
-    if msg.asset_infos.len() != N_COINS {
-        return Err(ContractError::InvalidNumberOfAssets(N_COINS));
+    if !(MIN_N_COINS..=MAX_N_COINS).contains(&msg.asset_infos.len()) {
+        return Err(ContractError::UnsupportedNumberOfAssets {
+            min: MIN_N_COINS,
+            max: MAX_N_COINS,
+        });
     }
 
     if msg.init_params.is_none() {
@@ -89,6 +108,8 @@ pub fn instantiate(
     let factory_addr = deps.api.addr_validate(&msg.factory_addr)?;
     let greatest_precision = store_precisions(deps.branch(), &msg.asset_infos, &factory_addr)?;
 
+    let creator = addr_opt_validate(deps.api, &msg.creator)?.unwrap_or(info.sender);
+
     let config = Config {
         owner: addr_opt_validate(deps.api, &params.owner)?,
         pair_info: PairInfo {
@@ -96,6 +117,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Stable {},
+            creator: Some(creator),
+            created_at: Some(PairCreatedAt::from(&env.block)),
+            allowlist: None,
         },
         factory_addr,
         block_time_last: 0,
@@ -179,13 +203,18 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             deadline,
+///             min_lp_to_receive,
 ///         }** Provides liquidity in the pair using the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
 ///             offer_asset,
 ///             belief_price,
 ///             max_spread,
+///             min_receive,
 ///             to,
+///             deadline,
+///             referral,
 ///         }** Performs an swap using the specified parameters.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -201,16 +230,34 @@ pub fn execute(
             assets,
             auto_stake,
             receiver,
+            deadline,
+            min_lp_to_receive,
             ..
-        } => provide_liquidity(deps, env, info, assets, auto_stake, receiver),
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             ask_asset_info,
             belief_price,
             max_spread,
+            min_receive,
             to,
-            ..
+            deadline,
+            referral,
         } => {
+            assert_deadline(&env, deadline)?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -227,7 +274,9 @@ pub fn execute(
                 ask_asset_info,
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
             )
         }
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
@@ -268,6 +317,9 @@ pub fn execute(
             })
             .map_err(|e| e.into())
         }
+        ExecuteMsg::BatchSwap { .. } | ExecuteMsg::Donate { .. } => {
+            Err(ContractError::NotSupported {})
+        }
     }
 }
 
@@ -285,8 +337,13 @@ pub fn receive_cw20(
             ask_asset_info,
             belief_price,
             max_spread,
+            min_receive,
             to,
+            deadline,
+            referral,
         } => {
+            assert_deadline(&env, deadline)?;
+
             let config = CONFIG.load(deps.storage)?;
 
             // Only asset contract can execute this message
@@ -306,16 +363,24 @@ pub fn receive_cw20(
                 ask_asset_info,
                 belief_price,
                 max_spread,
+                min_receive,
                 to_addr,
+                referral,
+            )
+        }
+        Cw20HookMsg::BatchSwap { .. } => Err(ContractError::NotSupported {}),
+        Cw20HookMsg::WithdrawLiquidity { assets, deadline } => {
+            assert_deadline(&env, deadline)?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                cw20_msg.amount,
+                assets,
             )
         }
-        Cw20HookMsg::WithdrawLiquidity { assets } => withdraw_liquidity(
-            deps,
-            info,
-            Addr::unchecked(cw20_msg.sender),
-            cw20_msg.amount,
-            assets,
-        ),
     }
 }
 
@@ -328,6 +393,8 @@ pub fn receive_cw20(
 ///
 /// * **receiver** address that receives LP tokens. If this address isn't specified, the function will default to the caller.
 ///
+/// * **min_lp_to_receive** minimum amount of LP tokens minted for the receiver.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
 pub fn provide_liquidity(
     deps: DepsMut,
@@ -336,11 +403,14 @@ pub fn provide_liquidity(
     assets: Vec<Asset>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     check_assets(deps.api, &assets)?;
 
     let auto_stake = auto_stake.unwrap_or(false);
     let config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &info.sender)?;
+    check_liquidity_enabled(deps.storage, &env)?;
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
 
@@ -423,11 +493,12 @@ pub fn provide_liquidity(
         }
     }
 
+    let precisions = Precisions::new(deps.storage)?;
     let assets_collection = assets_collection
         .iter()
         .cloned()
         .map(|(asset, pool)| {
-            let coin_precision = get_precision(deps.storage, &asset.info)?;
+            let coin_precision = precisions.get_precision(&asset.info)?;
             Ok((
                 asset.to_decimal_asset(coin_precision)?,
                 Decimal256::with_precision(pool, coin_precision)?,
@@ -445,6 +516,13 @@ pub fn provide_liquidity(
     let deposit_d = compute_d(amp, &new_balances)?;
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+
+    let old_balances = assets_collection
+        .iter()
+        .map(|(_, pool)| *pool)
+        .collect_vec();
+    record_virtual_price(deps.storage, &env, &config, &old_balances, total_share)?;
+
     let share = if total_share.is_zero() {
         let share = deposit_d
             .to_uint128_with_precision(config.greatest_precision)?
@@ -458,7 +536,8 @@ pub fn provide_liquidity(
 
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.pair_info.liquidity_token,
+            &config.factory_addr,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
@@ -468,10 +547,6 @@ pub fn provide_liquidity(
         share
     } else {
         // Initial invariant (D)
-        let old_balances = assets_collection
-            .iter()
-            .map(|(_, pool)| *pool)
-            .collect_vec();
         let init_d = compute_d(amp, &old_balances)?;
 
         let share = Decimal256::with_precision(total_share, config.greatest_precision)?
@@ -485,24 +560,29 @@ pub fn provide_liquidity(
         share
     };
 
+    check_min_lp_to_receive(share, min_lp_to_receive)?;
+
     // Mint LP token for the caller (or for the receiver if it was set)
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.pair_info.liquidity_token,
+        &config.factory_addr,
         &env.contract.address,
         &receiver,
         share,
         auto_stake,
     )?);
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", assets.iter().join(", ")),
-        attr("share", share),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        ProvideEvent {
+            sender: info.sender,
+            receiver,
+            assets,
+            share,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Withdraw liquidity from the pool.
@@ -511,6 +591,7 @@ pub fn provide_liquidity(
 /// * **amount** is the amount of LP tokens to burn.
 pub fn withdraw_liquidity(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     amount: Uint128,
@@ -521,9 +602,17 @@ pub fn withdraw_liquidity(
     if info.sender != config.pair_info.liquidity_token {
         return Err(ContractError::Unauthorized {});
     }
+    check_liquidity_enabled(deps.storage, &env)?;
 
     let (pools, total_share) = pool_info(deps.querier, &config)?;
 
+    let precisions = Precisions::new(deps.storage)?;
+    let decimal_pools = pools
+        .iter()
+        .map(|pool| Decimal256::with_precision(pool.amount, precisions.get_precision(&pool.info)?))
+        .collect::<StdResult<Vec<_>>>()?;
+    record_virtual_price(deps.storage, &env, &config, &decimal_pools, total_share)?;
+
     let refund_assets = if assets.is_empty() {
         // Usual withdraw (balanced)
         get_share_in_assets(&pools, amount, total_share)
@@ -563,8 +652,14 @@ pub fn withdraw_liquidity(
 ///
 /// * **max_spread** sets the maximum spread of the swap operation.
 ///
+/// * **min_receive** sets the minimum amount of ask tokens to receive. When set, it replaces
+/// the `belief_price` and `max_spread` check.
+///
 /// * **to** sets the recipient of the swap operation.
 ///
+/// * **referral** the frontend which takes a fee from the return amount. The slippage checks
+/// apply to the amount left after the fee.
+///
 /// NOTE - the address that wants to swap should approve the pair contract to pull the offer token.
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
@@ -575,9 +670,14 @@ pub fn swap(
     ask_asset_info: Option<AssetInfo>,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
     to: Option<Addr>,
+    referral: Option<Referral>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    check_allowed(&deps.querier, &config, &sender)?;
+    check_swaps_enabled(deps.storage, &env)?;
+    let precisions = Precisions::new(deps.storage)?;
 
     // If the asset balance already increased
     // We should subtract the user deposit from the pool offer asset amount
@@ -589,7 +689,7 @@ pub fn swap(
             if pool.info.equal(&offer_asset.info) {
                 pool.amount = pool.amount.checked_sub(offer_asset.amount)?;
             }
-            let token_precision = get_precision(deps.storage, &pool.info)?;
+            let token_precision = precisions.get_precision(&pool.info)?;
             Ok(DecimalAsset {
                 info: pool.info,
                 amount: Decimal256::with_precision(pool.amount, token_precision)?,
@@ -597,10 +697,27 @@ pub fn swap(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let decimal_pools = pools.iter().map(|pool| pool.amount).collect_vec();
+    record_virtual_price(deps.storage, &env, &config, &decimal_pools, total_share)?;
+
     let (offer_pool, ask_pool) =
         select_pools(Some(&offer_asset.info), ask_asset_info.as_ref(), &pools)?;
 
-    let offer_precision = get_precision(deps.storage, &offer_pool.info)?;
+    let offer_precision = precisions.get_precision(&offer_pool.info)?;
+
+    let min_swap = MinTradeSize::new(
+        offer_asset.info.clone(),
+        offer_precision,
+        MIN_TRADE_FLOOR.may_load(deps.storage)?,
+    )?
+    .swap;
+    if offer_asset.amount < min_swap {
+        return Err(ContractError::BelowMinTradeSize {
+            amount: offer_asset.amount,
+            min: min_swap,
+        });
+    }
 
     // Check if the liquidity is non-zero
     check_swap_parameters(
@@ -608,7 +725,7 @@ pub fn swap(
             .iter()
             .map(|pool| {
                 pool.amount
-                    .to_uint128_with_precision(get_precision(deps.storage, &pool.info)?)
+                    .to_uint128_with_precision(precisions.get_precision(&pool.info)?)
             })
             .collect::<StdResult<Vec<Uint128>>>()?,
         offer_asset.amount,
@@ -620,7 +737,7 @@ pub fn swap(
         return_amount,
         spread_amount,
     } = compute_swap(
-        deps.storage,
+        &precisions,
         &env,
         &config,
         &offer_asset_dec,
@@ -630,22 +747,30 @@ pub fn swap(
     )?;
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let commission_amount = fee_info.total_fee_rate.checked_mul_uint128(return_amount)?;
     let return_amount = return_amount.saturating_sub(commission_amount);
 
-    // Check the max spread limit (if it was specified)
-    assert_max_spread(
-        belief_price,
-        max_spread,
-        offer_asset.amount,
-        return_amount + commission_amount,
-        spread_amount,
-    )?;
+    let referral_fee =
+        ReferralFee::new(deps.api, referral, fee_info.max_referral_bps, return_amount)?;
+    let return_amount = return_amount - referral_fee.amount;
+
+    if let Some(min_receive) = min_receive {
+        assert_min_receive(min_receive, return_amount)?;
+    } else {
+        // Check the max spread limit (if it was specified)
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_asset.amount,
+            return_amount + commission_amount,
+            spread_amount,
+        )?;
+    }
 
     let receiver = to.unwrap_or_else(|| sender.clone());
 
@@ -658,6 +783,7 @@ pub fn swap(
     if !return_amount.is_zero() {
         messages.push(return_asset.into_msg(receiver.clone())?)
     }
+    messages.extend(referral_fee.into_msg(&ask_pool.info)?);
 
     // If this pool is configured to share fees, calculate the amount to send
     // to the receiver and add the transfer message
@@ -697,20 +823,29 @@ pub fn swap(
         }
     }
 
+    // Report the swap to the points contract (if any)
+    if let Some(points_contract) = &fee_info.points_contract {
+        messages.push(swap_hook_msg(points_contract, &receiver, &offer_asset)?);
+    }
+
     // Store observation from precommit data
     accumulate_swap_sizes(deps.storage, &env)?;
 
     // Store time series data in precommit observation.
     // Skipping small unsafe values which can seriously mess oracle price due to rounding errors.
     // This data will be reflected in observations on the next action.
-    let ask_precision = get_precision(deps.storage, &ask_pool.info)?;
-    if offer_asset_dec.amount >= MIN_TRADE_SIZE
-        && return_amount.to_decimal256(ask_precision)? >= MIN_TRADE_SIZE
+    // The referral fee is not a part of the pool price
+    let pool_return_amount = return_amount + referral_fee.amount;
+    let ask_precision = precisions.get_precision(&ask_pool.info)?;
+    if offer_asset_dec.amount >= min_trade_size(offer_precision)
+        && pool_return_amount.to_decimal256(ask_precision)? >= min_trade_size(ask_precision)
     {
         // Store time series data
-        let (base_amount, quote_amount) =
-            determine_base_quote_amount(&pools, &offer_asset, return_amount)?;
-        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+        if let Some((base_amount, quote_amount)) =
+            determine_base_quote_amount(&pools, &offer_asset, &ask_pool.info, pool_return_amount)?
+        {
+            PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+        }
     }
 
     Ok(Response::new()
@@ -731,7 +866,8 @@ pub fn swap(
             attr("commission_amount", commission_amount),
             attr("maker_fee_amount", maker_fee_amount),
             attr("fee_share_amount", fee_share_amount),
-        ]))
+        ])
+        .add_attributes(referral_fee.attributes()))
 }
 
 /// Calculates the amount of fees the Maker contract gets according to specified pair parameters.
@@ -768,6 +904,9 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 ///
+/// * **QueryMsg::ShareValueIn { amount, asset_info }** Returns the value of a specific amount of LP tokens
+/// denominated in one of the pool assets using an [`Asset`] object.
+///
 /// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
 ///
 /// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation using
@@ -777,12 +916,24 @@ pub fn calculate_maker_fee(
 /// pool using a [`CumulativePricesResponse`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::VirtualPrice {}** Returns the LP token virtual price and its moving average using
+/// a [`VirtualPriceResponse`] object.
+///
+/// * **QueryMsg::VersionedPair {}**, **QueryMsg::VersionedPool {}**, **QueryMsg::VersionedConfig {}**
+/// and **QueryMsg::VersionedSimulation { .. }** Answer the `pair`, `pool`, `config` and `simulation`
+/// queries with the response wrapped into a [`gridiron::versioned::Versioned`] envelope.
+///
+/// * **QueryMsg::SupportsInterface { interface }** Returns whether the pair implements the interface.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Pair {} => to_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Pool {} => to_binary(&query_pool(deps)?),
         QueryMsg::Share { amount } => to_binary(&query_share(deps, amount)?),
+        QueryMsg::ShareValueIn { amount, asset_info } => {
+            to_binary(&query_share_value_in(deps, env, amount, asset_info)?)
+        }
         QueryMsg::Simulation {
             offer_asset,
             ask_asset_info,
@@ -804,6 +955,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::Config {} => to_binary(&query_config(deps, env)?),
         QueryMsg::QueryComputeD {} => to_binary(&query_compute_d(deps, env)?),
+        QueryMsg::VirtualPrice {} => to_binary(&query_virtual_price(deps, env)?),
+        msg @ (QueryMsg::VersionedPair {}
+        | QueryMsg::VersionedPool {}
+        | QueryMsg::VersionedConfig {}
+        | QueryMsg::VersionedSimulation { .. }) => msg.query_versioned(|msg| query(deps, env, msg)),
+        QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
@@ -834,6 +991,58 @@ pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     Ok(refund_assets)
 }
 
+/// Returns the value of a specific amount of LP tokens denominated in one of the pool assets.
+/// Every other asset is valued at the amount that swapping it to the chosen asset against
+/// the current reserves would return, without fees.
+///
+/// * **amount** is the amount of LP tokens to value.
+///
+/// * **asset_info** is the pool asset in which the value is denominated.
+pub fn query_share_value_in(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+    asset_info: AssetInfo,
+) -> StdResult<Asset> {
+    let config = CONFIG.load(deps.storage)?;
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let share = get_share_in_assets(&pools, amount, total_share);
+
+    let target = share
+        .iter()
+        .find(|asset| asset.info.equal(&asset_info))
+        .ok_or_else(|| StdError::generic_err("Given asset does not belong in the pair"))?;
+
+    let mut value = target.amount;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools_decimal(&deps.querier, &config, &precisions)?;
+    for other in share
+        .iter()
+        .filter(|asset| !asset.info.equal(&asset_info) && !asset.amount.is_zero())
+    {
+        let (offer_pool, ask_pool) = select_pools(Some(&other.info), Some(&asset_info), &pools)
+            .map_err(|err| StdError::generic_err(format!("{err}")))?;
+
+        let SwapResult { return_amount, .. } = compute_swap(
+            &precisions,
+            &env,
+            &config,
+            &other.to_decimal_asset(precisions.get_precision(&other.info)?)?,
+            &offer_pool,
+            &ask_pool,
+            &pools,
+        )
+        .map_err(|err| StdError::generic_err(format!("{err}")))?;
+
+        value = value.checked_add(return_amount)?;
+    }
+
+    Ok(Asset {
+        info: asset_info,
+        amount: value,
+    })
+}
+
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
 ///
 /// * **offer_asset** is the asset to swap as well as an amount of the said asset.
@@ -844,24 +1053,21 @@ pub fn query_simulation(
     ask_asset_info: Option<AssetInfo>,
 ) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let pools = config.pair_info.query_pools_decimal(
-        &deps.querier,
-        &config.pair_info.contract_addr,
-        &config.factory_addr,
-    )?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools_decimal(&deps.querier, &config, &precisions)?;
 
     let (offer_pool, ask_pool) =
         select_pools(Some(&offer_asset.info), ask_asset_info.as_ref(), &pools)
             .map_err(|err| StdError::generic_err(format!("{err}")))?;
 
-    let offer_precision = get_precision(deps.storage, &offer_pool.info)?;
+    let offer_precision = precisions.get_precision(&offer_pool.info)?;
 
     if check_swap_parameters(
         pools
             .iter()
             .map(|pool| {
                 pool.amount
-                    .to_uint128_with_precision(get_precision(deps.storage, &pool.info)?)
+                    .to_uint128_with_precision(precisions.get_precision(&pool.info)?)
             })
             .collect::<StdResult<Vec<Uint128>>>()?,
         offer_asset.amount,
@@ -879,7 +1085,7 @@ pub fn query_simulation(
         return_amount,
         spread_amount,
     } = compute_swap(
-        deps.storage,
+        &precisions,
         &env,
         &config,
         &offer_asset.to_decimal_asset(offer_precision)?,
@@ -890,10 +1096,10 @@ pub fn query_simulation(
     .map_err(|err| StdError::generic_err(format!("{err}")))?;
 
     // Get fee info from factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
 
     let commission_amount = fee_info.total_fee_rate.checked_mul_uint128(return_amount)?;
@@ -920,17 +1126,14 @@ pub fn query_reverse_simulation(
     offer_asset_info: Option<AssetInfo>,
 ) -> StdResult<ReverseSimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let pools = config.pair_info.query_pools_decimal(
-        &deps.querier,
-        &config.pair_info.contract_addr,
-        &config.factory_addr,
-    )?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools_decimal(&deps.querier, &config, &precisions)?;
     let (offer_pool, ask_pool) =
         select_pools(offer_asset_info.as_ref(), Some(&ask_asset.info), &pools)
             .map_err(|err| StdError::generic_err(format!("{err}")))?;
 
-    let offer_precision = get_precision(deps.storage, &offer_pool.info)?;
-    let ask_precision = get_precision(deps.storage, &ask_asset.info)?;
+    let offer_precision = precisions.get_precision(&offer_pool.info)?;
+    let ask_precision = precisions.get_precision(&ask_asset.info)?;
 
     // Check the swap parameters are valid
     if check_swap_parameters(
@@ -938,7 +1141,7 @@ pub fn query_reverse_simulation(
             .iter()
             .map(|pool| {
                 pool.amount
-                    .to_uint128_with_precision(get_precision(deps.storage, &pool.info)?)
+                    .to_uint128_with_precision(precisions.get_precision(&pool.info)?)
             })
             .collect::<StdResult<Vec<Uint128>>>()?,
         ask_asset.amount,
@@ -953,10 +1156,10 @@ pub fn query_reverse_simulation(
     }
 
     // Get fee info from the factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         &config.factory_addr,
-        config.pair_info.pair_type.clone(),
+        &config.pair_info.contract_addr,
     )?;
     let before_commission = (Decimal256::one()
         - Decimal256::new(fee_info.total_fee_rate.atomics().into()))
@@ -964,11 +1167,12 @@ pub fn query_reverse_simulation(
     .ok_or_else(|| StdError::generic_err("The pool must have less than 100% fee!"))?
     .checked_mul(Decimal256::with_precision(ask_asset.amount, ask_precision)?)?;
 
-    let xp = pools.into_iter().map(|pool| pool.amount).collect_vec();
     let new_offer_pool_amount = calc_y(
-        compute_current_amp(&config, &env)?,
+        &ask_pool,
+        &offer_pool.info,
         ask_pool.amount - before_commission,
-        &xp,
+        &pools,
+        compute_current_amp(&config, &env)?,
         config.greatest_precision,
     )?;
 
@@ -993,6 +1197,21 @@ pub fn query_reverse_simulation(
 pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let min_trade_floor = MIN_TRADE_FLOOR.may_load(deps.storage)?;
+    let min_trade_sizes = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| {
+            MinTradeSize::new(
+                asset_info.clone(),
+                precisions.get_precision(asset_info)?,
+                min_trade_floor,
+            )
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
     Ok(ConfigResponse {
         block_time_last: config.block_time_last,
         params: Some(to_binary(&StablePoolConfig {
@@ -1001,6 +1220,10 @@ pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
         })?),
         owner: config.owner.unwrap_or(factory_config.owner),
         factory_addr: config.factory_addr,
+        creator: config.pair_info.creator,
+        created_at: config.pair_info.created_at,
+        min_trade_sizes,
+        maintenance_window: MAINTENANCE_WINDOW.may_load(deps.storage)?,
     })
 }
 
@@ -1053,6 +1276,37 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// Ensures the amount received from a swap is not less than `min_receive`.
+///
+/// * **min_receive** minimum amount of ask tokens the trader accepts.
+///
+/// * **return_amount** amount of ask tokens the trader receives from the swap.
+pub fn assert_min_receive(
+    min_receive: Uint128,
+    return_amount: Uint128,
+) -> Result<(), ContractError> {
+    if return_amount < min_receive {
+        return Err(ContractError::MinReceiveAssertion {
+            min_receive,
+            return_amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// Ensures the transaction is not included after `deadline`.
+///
+/// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+pub fn assert_deadline(env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {
+            Err(ContractError::TransactionExpired {})
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
@@ -1066,7 +1320,7 @@ pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Respons
             "2.1.1" | "2.1.2" => {
                 migrate_config_from_v21(deps.branch())?;
             }
-            "3.0.0" | "3.1.0" => {}
+            "3.0.0" | "3.1.0" | "3.4.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -1102,13 +1356,9 @@ pub fn update_config(
     let mut config = CONFIG.load(deps.storage)?;
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
-    if info.sender
-        != if let Some(ref owner) = config.owner {
-            owner.to_owned()
-        } else {
-            factory_config.owner
-        }
-    {
+    let owner = config.owner.as_ref().unwrap_or(&factory_config.owner);
+    // The factory is allowed to disable fee sharing on behalf of the protocol
+    if info.sender != *owner && info.sender != config.factory_addr {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -1134,10 +1384,17 @@ pub fn update_config(
             }
 
             // Set sharing config
+            let previous_config = config.clone();
             config.fee_share = Some(FeeShareConfig {
                 bps: fee_share_bps,
                 recipient: deps.api.addr_validate(&fee_share_address)?,
             });
+            check_combined_fee(
+                &deps.querier,
+                &previous_config,
+                &config,
+                factory_config.max_combined_fee_bps,
+            )?;
 
             CONFIG.save(deps.storage, &config)?;
 
@@ -1158,11 +1415,125 @@ pub fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        StablePoolUpdateParams::RefreshPrecisions {} => {
+            refresh_precisions(&config, deps)?;
+            response
+                .attributes
+                .push(attr("action", "refresh_precisions"));
+        }
+        StablePoolUpdateParams::UpdateVirtualPriceMaHalfTime { ma_half_time } => {
+            if !MA_HALF_TIME_LIMITS.contains(&ma_half_time) {
+                return Err(ContractError::InvalidMaHalfTime {});
+            }
+
+            // Move the moving average to the current block with the old half-time
+            let precisions = Precisions::new(deps.storage)?;
+            let pools = query_pools_decimal(&deps.querier, &config, &precisions)?
+                .into_iter()
+                .map(|pool| pool.amount)
+                .collect_vec();
+            let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+            record_virtual_price(deps.storage, &env, &config, &pools, total_share)?;
+
+            let mut virtual_price_ma = VIRTUAL_PRICE_MA.may_load(deps.storage)?.unwrap_or_default();
+            virtual_price_ma.ma_half_time = ma_half_time;
+            VIRTUAL_PRICE_MA.save(deps.storage, &virtual_price_ma)?;
+
+            response.attributes.extend([
+                attr("action", "update_virtual_price_ma_half_time"),
+                attr("ma_half_time", ma_half_time.to_string()),
+            ]);
+        }
+        StablePoolUpdateParams::UpdateMinTradeFloor { min_trade_floor } => {
+            response
+                .attributes
+                .push(attr("action", "update_min_trade_floor"));
+
+            if let Some(floor) = min_trade_floor {
+                if floor.is_zero() || floor > MAX_MIN_TRADE_FLOOR {
+                    return Err(ContractError::MinTradeFloorOutOfBounds {});
+                }
+
+                MIN_TRADE_FLOOR.save(deps.storage, &floor)?;
+                response
+                    .attributes
+                    .push(attr("min_trade_floor", floor.to_string()));
+            } else {
+                MIN_TRADE_FLOOR.remove(deps.storage);
+            }
+        }
+        StablePoolUpdateParams::UpdateAllowlist { allowlist } => {
+            response.attributes.push(attr("action", "update_allowlist"));
+
+            config.pair_info.allowlist = addr_opt_validate(deps.api, &allowlist)?;
+            if let Some(allowlist) = &config.pair_info.allowlist {
+                // Make sure the allowlist answers the standard query so the pair isn't bricked
+                query_is_allowed(&deps.querier, allowlist, &env.contract.address)?;
+                response.attributes.push(attr("allowlist", allowlist));
+            }
+            CONFIG.save(deps.storage, &config)?;
+        }
+        StablePoolUpdateParams::ScheduleMaintenance { window } => {
+            if let Some(window) = window {
+                if !window.is_valid(env.block.time.seconds()) {
+                    return Err(ContractError::InvalidMaintenanceWindow {});
+                }
+
+                response.attributes.extend([
+                    attr("action", "schedule_maintenance"),
+                    attr("maintenance_mode", window.mode.to_string()),
+                    attr("maintenance_start", window.start.to_string()),
+                    attr("maintenance_end", window.end.to_string()),
+                ]);
+                MAINTENANCE_WINDOW.save(deps.storage, &window)?;
+            } else {
+                response
+                    .attributes
+                    .push(attr("action", "cancel_maintenance"));
+                MAINTENANCE_WINDOW.remove(deps.storage);
+            }
+        }
     }
 
     Ok(response)
 }
 
+/// Checks that a config update doesn't raise the fees the pair combines on a swap above the
+/// protocol-wide cap set in the factory.
+fn check_combined_fee(
+    querier: &QuerierWrapper,
+    previous_config: &Config,
+    config: &Config,
+    max_combined_fee_bps: Option<u16>,
+) -> Result<(), ContractError> {
+    let max_combined_fee_bps = match max_combined_fee_bps {
+        Some(max_combined_fee_bps) => max_combined_fee_bps,
+        None => return Ok(()),
+    };
+
+    let fee_info: FeeInfoResponse = querier.query_wasm_smart(
+        &config.factory_addr,
+        &FactoryQueryMsg::PairFeeInfo {
+            pair_addr: config.pair_info.contract_addr.to_string(),
+        },
+    )?;
+    let combined_fees = |config: &Config| CombinedFees {
+        total_fee_bps: fee_info.total_fee_bps,
+        fee_share_bps: config.fee_share.as_ref().map_or(0, |share| share.bps),
+        ..Default::default()
+    };
+
+    let fees = combined_fees(config);
+    if fees.exceed_cap(&combined_fees(previous_config), max_combined_fee_bps) {
+        return Err(ContractError::CombinedFeeExceedsCap {
+            combined_fee_bps: fees.combined_fee_bps(),
+            max_combined_fee_bps,
+        });
+    }
+
+    Ok(())
+}
+
 /// Start changing the AMP value.
 ///
 /// * **next_amp** new value for AMP.
@@ -1222,14 +1593,45 @@ fn stop_changing_amp(mut config: Config, deps: DepsMut, env: Env) -> StdResult<(
 
     Ok(())
 }
+/// Re-reads asset precisions from the coin registry and overwrites the cached values.
+/// The greatest precision defines the LP token decimals, so it must stay the same.
+fn refresh_precisions(config: &Config, deps: DepsMut) -> Result<(), ContractError> {
+    let precisions = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| {
+            let precision = asset_info.decimals(&deps.querier, &config.factory_addr)?;
+            Ok((asset_info.to_string(), precision))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let greatest_precision = precisions
+        .iter()
+        .map(|(_, precision)| *precision)
+        .max()
+        .unwrap_or_default();
+    if greatest_precision != config.greatest_precision {
+        return Err(ContractError::GreatestPrecisionChanged(
+            config.greatest_precision,
+            greatest_precision,
+        ));
+    }
+
+    for (asset_info, precision) in precisions {
+        PRECISIONS.save(deps.storage, asset_info, &precision)?;
+    }
+
+    Ok(())
+}
+
 /// Compute the current pool D value.
 fn query_compute_d(deps: Deps, env: Env) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
 
     let amp = compute_current_amp(&config, &env)?;
-    let pools = config
-        .pair_info
-        .query_pools_decimal(&deps.querier, env.contract.address, &config.factory_addr)?
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools_decimal(&deps.querier, &config, &precisions)?
         .into_iter()
         .map(|pool| pool.amount)
         .collect::<Vec<_>>();
@@ -1238,3 +1640,26 @@ fn query_compute_d(deps: Deps, env: Env) -> StdResult<Uint128> {
         .map_err(|_| StdError::generic_err("Failed to calculate the D"))?
         .to_uint128_with_precision(config.greatest_precision)
 }
+
+/// Returns the current LP token virtual price and its moving average.
+fn query_virtual_price(deps: Deps, env: Env) -> StdResult<VirtualPriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let amp = compute_current_amp(&config, &env)?;
+    let precisions = Precisions::new(deps.storage)?;
+    let pools = query_pools_decimal(&deps.querier, &config, &precisions)?
+        .into_iter()
+        .map(|pool| pool.amount)
+        .collect::<Vec<_>>();
+    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+
+    let virtual_price = calc_virtual_price(amp, &pools, total_share, config.greatest_precision)?
+        .unwrap_or_default();
+    let virtual_price_ma = VIRTUAL_PRICE_MA.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(VirtualPriceResponse {
+        virtual_price,
+        virtual_price_ma: virtual_price_ma.ma_at(env.block.time.seconds(), virtual_price)?,
+        ma_half_time: virtual_price_ma.ma_half_time,
+    })
+}
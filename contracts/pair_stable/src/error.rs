@@ -1,8 +1,14 @@
-use cosmwasm_std::{CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{
+    Addr, CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError, Uint128,
+};
 use thiserror::Error;
 
-use gridiron::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
+use gridiron::asset::{AssetInfoError, MINIMUM_LIQUIDITY_AMOUNT};
+use gridiron::pair::{
+    ProvideLiquidityError, ReferralError, MAX_FEE_SHARE_BPS, MAX_MIN_TRADE_FLOOR,
+};
 use gridiron_circular_buffer::error::BufferError;
+use gridiron_pcl_common::consts::MA_HALF_TIME_LIMITS;
 
 use crate::math::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME};
 
@@ -12,6 +18,15 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    AssetInfo(#[from] AssetInfoError),
+
+    #[error("{0}")]
+    ProvideLiquidity(#[from] ProvideLiquidityError),
+
+    #[error("{0}")]
+    Referral(#[from] ReferralError),
+
     #[error("{0}")]
     CheckedMultiplyRatioError(#[from] CheckedMultiplyRatioError),
 
@@ -21,6 +36,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Operation is not supported")]
+    NotSupported {},
+
     #[error("CW20 tokens can be swapped via Cw20::Send message only")]
     Cw20DirectSwap {},
 
@@ -39,6 +57,36 @@ pub enum ContractError {
     #[error("Operation exceeds max spread limit")]
     MaxSpreadAssertion {},
 
+    #[error("Swap return {return_amount} is less than min_receive {min_receive}")]
+    MinReceiveAssertion {
+        min_receive: Uint128,
+        return_amount: Uint128,
+    },
+
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
+    #[error("Swap amount {amount} is below the min trade size {min}")]
+    BelowMinTradeSize { amount: Uint128, min: Uint128 },
+
+    #[error(
+        "Min trade floor must be greater than zero and at most {}",
+        MAX_MIN_TRADE_FLOOR
+    )]
+    MinTradeFloorOutOfBounds {},
+
+    #[error("Address {address} is not approved by the pair allowlist")]
+    AddressNotAllowed { address: Addr },
+
+    #[error("Maintenance window must end after it starts and after the current block time")]
+    InvalidMaintenanceWindow {},
+
+    #[error("Swaps are halted for maintenance until {end}")]
+    SwapsHaltedForMaintenance { end: u64 },
+
+    #[error("Liquidity provision and withdrawals are halted for maintenance until {end}")]
+    LiquidityHaltedForMaintenance { end: u64 },
+
     #[error("Native token balance mismatch between the argument and the transferred")]
     AssetMismatch {},
 
@@ -63,9 +111,6 @@ pub enum ContractError {
     #[error("You need to provide init params")]
     InitParamsNotFound {},
 
-    #[error("Generator address is not set in factory. Cannot autostake")]
-    AutoStakeError {},
-
     #[error("It is not possible to provide liquidity with one token for an empty pool")]
     InvalidProvideLPsWithSingleToken {},
 
@@ -81,6 +126,9 @@ pub enum ContractError {
     #[error("Invalid number of assets. This pair support only {0} assets")]
     InvalidNumberOfAssets(usize),
 
+    #[error("Invalid number of assets. The pool supports from {min} to {max} assets")]
+    UnsupportedNumberOfAssets { min: usize, max: usize },
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
@@ -95,6 +143,22 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error("Refreshed precisions change the greatest pool precision from {0} to {1}")]
+    GreatestPrecisionChanged(u8, u8),
+
+    #[error(
+        "Virtual price MA half-time must be within [{}, {}] seconds",
+        MA_HALF_TIME_LIMITS.start(),
+        MA_HALF_TIME_LIMITS.end()
+    )]
+    InvalidMaHalfTime {},
+
+    #[error("Combined fee {combined_fee_bps} bps exceeds the cap of {max_combined_fee_bps} bps")]
+    CombinedFeeExceedsCap {
+        combined_fee_bps: u32,
+        max_combined_fee_bps: u16,
+    },
 }
 
 impl From<OverflowError> for ContractError {
@@ -1,6 +1,6 @@
 use cosmwasm_std::{Decimal256, StdError, StdResult, Uint128, Uint64};
 
-use gridiron::asset::Decimal256Ext;
+use gridiron::asset::{AssetInfo, Decimal256Ext, DecimalAsset};
 
 /// The maximum number of calculation steps for Newton's method.
 const ITERATIONS: u8 = 64;
@@ -9,8 +9,14 @@ pub const MAX_AMP: u64 = 1_000_000;
 pub const MAX_AMP_CHANGE: u64 = 10;
 pub const MIN_AMP_CHANGING_TIME: u64 = 86400;
 pub const AMP_PRECISION: u64 = 100;
-/// N = 2
-pub const N_COINS: Decimal256 = Decimal256::raw(2000000000000000000);
+/// The default half-time of the virtual price moving average in seconds
+pub const DEFAULT_VIRTUAL_PRICE_MA_HALF_TIME: u64 = 600;
+/// The minimum number of assets in a pool
+pub const MIN_N_COINS: usize = 2;
+/// The maximum number of assets in a pool. The invariant math doesn't overflow with more assets,
+/// but every Newton iteration, provide and withdrawal loops over all of them, which keeps gas usage
+/// and the number of transfers per withdrawal bounded.
+pub const MAX_N_COINS: usize = 5;
 /// 1e-6
 pub const TOL: Decimal256 = Decimal256::raw(1000000000000);
 
@@ -21,11 +27,12 @@ pub const TOL: Decimal256 = Decimal256::raw(1000000000000);
 /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
 ///
 pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
-    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
-    let amount_a_times_coins = pools[0] * N_COINS;
-    let amount_b_times_coins = pools[1] * N_COINS;
+    let n_coins = Decimal256::from_ratio(pools.len() as u64, 1u8);
+    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * n_coins;
 
-    let sum_x = pools[0].checked_add(pools[1])?; // sum(x_i), a.k.a S
+    let sum_x = pools
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, pool| acc.checked_add(*pool))?; // sum(x_i), a.k.a S
     if sum_x.is_zero() {
         Ok(Decimal256::zero())
     } else {
@@ -34,9 +41,15 @@ pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
 
         // Newton's method to approximate D
         for _ in 0..ITERATIONS {
-            let d_product = d.pow(3) / (amount_a_times_coins * amount_b_times_coins);
+            // D**(n+1) / prod(x_i * n), built one asset at a time so that it doesn't overflow
+            let d_product = pools.iter().try_fold(d, |d_product, pool| {
+                d_product
+                    .checked_mul(d)?
+                    .checked_div(pool.checked_mul(n_coins)?)
+                    .map_err(|e| StdError::generic_err(e.to_string()))
+            })?;
             d_previous = d;
-            d = calculate_step(d, leverage, sum_x, d_product)?;
+            d = calculate_step(d, leverage, sum_x, d_product, n_coins)?;
             // Equality with the precision of 1e-6
             if d.abs_diff(d_previous) <= TOL {
                 return Ok(d);
@@ -59,14 +72,15 @@ fn calculate_step(
     leverage: Decimal256,
     sum_x: Decimal256,
     d_product: Decimal256,
+    n_coins: Decimal256,
 ) -> StdResult<Decimal256> {
     let leverage_mul = leverage.checked_mul(sum_x)?;
-    let d_p_mul = d_product.checked_mul(N_COINS)?;
+    let d_p_mul = d_product.checked_mul(n_coins)?;
 
     let l_val = leverage_mul.checked_add(d_p_mul)?.checked_mul(initial_d)?;
 
     let leverage_sub = initial_d.checked_mul(leverage - Decimal256::one())?;
-    let n_coins_sum = d_product.checked_mul(N_COINS.checked_add(Decimal256::one())?)?;
+    let n_coins_sum = d_product.checked_mul(n_coins.checked_add(Decimal256::one())?)?;
 
     let r_val = leverage_sub.checked_add(n_coins_sum)?;
 
@@ -75,29 +89,56 @@ fn calculate_step(
         .map_err(|e| StdError::generic_err(e.to_string()))
 }
 
-/// Compute the swap amount `y` in proportion to `x`.
+/// Compute the new balance `y` of the `to` asset after the balance of `from_asset` changes to
+/// `new_amount`. Balances of the other assets stay the same.
 ///
 /// * **Solve for y**
 ///
 /// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
 ///
 /// y**2 + b*y = c
+///
+/// where sum' and prod' are the sum and the product of all balances except `y`.
 pub(crate) fn calc_y(
-    amp: Uint64,
+    from_asset: &DecimalAsset,
+    to: &AssetInfo,
     new_amount: Decimal256,
-    xp: &[Decimal256],
+    pools: &[DecimalAsset],
+    amp: Uint64,
     target_precision: u8,
 ) -> StdResult<Uint128> {
-    let d = compute_d(amp, xp)?;
-    let leverage = Decimal256::from_ratio(amp, 1u8) * N_COINS;
+    if from_asset.info.equal(to) {
+        return Err(StdError::generic_err("The assets cannot be the same"));
+    }
+
+    let xp = pools.iter().map(|pool| pool.amount).collect::<Vec<_>>();
+    let d = compute_d(amp, &xp)?;
+    let n_coins = Decimal256::from_ratio(pools.len() as u64, 1u8);
+    let leverage = Decimal256::from_ratio(amp, 1u8) * n_coins;
     let amp_prec = Decimal256::from_ratio(AMP_PRECISION, 1u8);
 
-    let c = d.checked_pow(3)?.checked_mul(amp_prec)?
-        / new_amount
-            .checked_mul(N_COINS * N_COINS)?
-            .checked_mul(leverage)?;
+    // sum' and c, which is built one asset at a time so that D**(n+1) doesn't overflow
+    let mut sum = Decimal256::zero();
+    let mut c = d;
+    for pool in pools.iter().filter(|pool| !pool.info.equal(to)) {
+        let amount = if pool.info.equal(&from_asset.info) {
+            new_amount
+        } else {
+            pool.amount
+        };
+        sum = sum.checked_add(amount)?;
+        c = c
+            .checked_mul(d)?
+            .checked_div(amount.checked_mul(n_coins)?)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+    }
+    let c = c
+        .checked_mul(d)?
+        .checked_mul(amp_prec)?
+        .checked_div(n_coins.checked_mul(leverage)?)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
 
-    let b = new_amount.checked_add(d.checked_mul(amp_prec)? / leverage)?;
+    let b = sum.checked_add(d.checked_mul(amp_prec)? / leverage)?;
 
     // Solve for y by approximating: y**2 + b*y = c
     let mut y_prev;
@@ -107,7 +148,7 @@ pub(crate) fn calc_y(
         y = y
             .checked_pow(2)?
             .checked_add(c)?
-            .checked_div(y.checked_mul(N_COINS)?.checked_add(b)?.checked_sub(d)?)
+            .checked_div(y.checked_add(y)?.checked_add(b)?.checked_sub(d)?)
             .map_err(|e| StdError::generic_err(e.to_string()))?;
         if y.abs_diff(y_prev) <= TOL {
             return y.to_uint128_with_precision(target_precision);
@@ -1,4 +1,4 @@
-use gridiron::factory::QueryMsg::{Config, FeeInfo};
+use gridiron::factory::QueryMsg::{Config, FeeInfo, PairFeeInfo};
 use gridiron::factory::{Config as FactoryConfig, ConfigResponse, FeeInfoResponse};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
@@ -80,11 +80,14 @@ impl WasmMockQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 if contract_addr == "factory" {
                     match from_binary(&msg).unwrap() {
-                        FeeInfo { .. } => SystemResult::Ok(
+                        FeeInfo { .. } | PairFeeInfo { .. } => SystemResult::Ok(
                             to_binary(&FeeInfoResponse {
                                 fee_address: Some(Addr::unchecked("fee_address")),
                                 total_fee_bps: 30,
                                 maker_fee_bps: 1660,
+                                points_contract: None,
+                                fee_discounts: None,
+                                max_referral_bps: None,
                             })
                             .into(),
                         ),
@@ -97,6 +100,9 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                max_combined_fee_bps: None,
+                                points_contract: None,
+                                max_referral_bps: None,
                             })
                             .into(),
                         ),
@@ -164,6 +170,9 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                max_combined_fee_bps: None,
+                                points_contract: None,
+                                max_referral_bps: None,
                             })
                             .into(),
                         )
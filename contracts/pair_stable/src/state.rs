@@ -1,12 +1,15 @@
 use gridiron::asset::{AssetInfo, PairInfo};
 use gridiron::common::OwnershipProposal;
 use gridiron::observation::Observation;
-use gridiron::pair::FeeShareConfig;
+use gridiron::pair::{FeeShareConfig, MaintenanceWindow, PAIR_CONFIG_KEY, PAIR_PRECISIONS_KEY};
 use gridiron_circular_buffer::CircularBuffer;
+use gridiron_pcl_common::half_float_pow;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, DepsMut, StdResult, Storage};
+use cosmwasm_std::{Addr, Decimal256, DepsMut, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
 
+use crate::math::DEFAULT_VIRTUAL_PRICE_MA_HALF_TIME;
+
 /// This structure stores the main stableswap pair parameters.
 #[cw_serde]
 pub struct Config {
@@ -36,14 +39,78 @@ pub struct Config {
 pub const OBSERVATIONS: CircularBuffer<Observation> =
     CircularBuffer::new("observations_state", "observations_buffer");
 
-pub const CONFIG: Item<Config> = Item::new("config");
+pub const CONFIG: Item<Config> = Item::new(PAIR_CONFIG_KEY);
 
 /// Stores map of AssetInfo (as String) -> precision
-pub const PRECISIONS: Map<String, u8> = Map::new("precisions");
+pub const PRECISIONS: Map<String, u8> = Map::new(PAIR_PRECISIONS_KEY);
 
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Stores the exponential moving average of the LP token virtual price
+pub const VIRTUAL_PRICE_MA: Item<VirtualPriceMa> = Item::new("virtual_price_ma");
+
+/// Stores the floor (in decimal form) below which swaps are rejected
+pub const MIN_TRADE_FLOOR: Item<Decimal256> = Item::new("min_trade_floor");
+
+/// Stores the scheduled maintenance window
+pub const MAINTENANCE_WINDOW: Item<MaintenanceWindow> = Item::new("maintenance_window");
+
+/// This structure stores the exponential moving average of the LP token virtual price.
+#[cw_serde]
+pub struct VirtualPriceMa {
+    /// The half-time of the moving average in seconds
+    pub ma_half_time: u64,
+    /// The moving average as of `last_update`
+    pub ma: Decimal256,
+    /// The last timestamp when the moving average was updated
+    pub last_update: u64,
+}
+
+impl Default for VirtualPriceMa {
+    fn default() -> Self {
+        Self {
+            ma_half_time: DEFAULT_VIRTUAL_PRICE_MA_HALF_TIME,
+            ma: Decimal256::zero(),
+            last_update: 0,
+        }
+    }
+}
+
+impl VirtualPriceMa {
+    /// Returns the moving average at `block_time`.
+    ///
+    /// * **price** virtual price that held since `last_update`. The moving average doesn't change
+    /// within a block, so the virtual price can't be manipulated into it within a single block.
+    pub fn ma_at(&self, block_time: u64, price: Decimal256) -> StdResult<Decimal256> {
+        if self.ma.is_zero() || block_time <= self.last_update {
+            return Ok(self.ma);
+        }
+
+        let alpha = half_float_pow(Decimal256::from_ratio(
+            block_time - self.last_update,
+            self.ma_half_time,
+        ))?;
+
+        Ok(price * (Decimal256::one() - alpha) + self.ma * alpha)
+    }
+
+    /// Moves the moving average to `block_time`.
+    ///
+    /// * **price** virtual price before the current pool interaction.
+    pub fn update(&mut self, block_time: u64, price: Decimal256) -> StdResult<()> {
+        if self.ma.is_zero() {
+            // The first recorded price initializes the moving average
+            self.ma = price;
+        } else {
+            self.ma = self.ma_at(block_time, price)?;
+        }
+        self.last_update = self.last_update.max(block_time);
+
+        Ok(())
+    }
+}
+
 /// Store all token precisions and return the greatest one.
 pub(crate) fn store_precisions(
     deps: DepsMut,
@@ -61,7 +128,26 @@ pub(crate) fn store_precisions(
     Ok(max)
 }
 
-/// Loads precision of the given asset info.
-pub(crate) fn get_precision(storage: &dyn Storage, asset_info: &AssetInfo) -> StdResult<u8> {
-    PRECISIONS.load(storage, asset_info.to_string())
+/// Token precisions loaded once per contract call so that swap and simulation paths
+/// don't hit storage for every precision lookup.
+pub(crate) struct Precisions(Vec<(String, u8)>);
+
+impl Precisions {
+    /// Loads all stored precisions in a single range query.
+    pub fn new(storage: &dyn Storage) -> StdResult<Self> {
+        let items = PRECISIONS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(Self(items))
+    }
+
+    /// Returns the precision of the given asset info.
+    pub fn get_precision(&self, asset_info: &AssetInfo) -> StdResult<u8> {
+        let key = asset_info.to_string();
+        self.0
+            .iter()
+            .find_map(|(info, precision)| (*info == key).then_some(*precision))
+            .ok_or_else(|| StdError::generic_err(format!("Asset {key} is not found")))
+    }
 }
@@ -30,7 +30,7 @@ use crate::contract::{
 };
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{CONFIG, OBSERVATIONS};
+use crate::state::{Precisions, CONFIG, OBSERVATIONS};
 use crate::utils::{compute_swap, select_pools};
 
 #[derive(Clone, PartialEq, Message)]
@@ -74,6 +74,7 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         factory_addr: String::from("factory"),
+        creator: None,
         asset_infos: vec![
             AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
@@ -173,6 +174,7 @@ fn provide_liquidity() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -209,6 +211,8 @@ fn provide_liquidity() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env();
@@ -326,6 +330,8 @@ fn provide_liquidity() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -397,6 +403,8 @@ fn provide_liquidity() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env();
@@ -473,6 +481,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -513,6 +523,8 @@ fn provide_liquidity() {
         slippage_tolerance: Some(Decimal::percent(1)),
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -555,6 +567,7 @@ fn withdraw_liquidity() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -575,7 +588,11 @@ fn withdraw_liquidity() {
     // Withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: String::from("addr0000"),
-        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        })
+        .unwrap(),
         amount: Uint128::new(100u128),
     });
 
@@ -682,6 +699,7 @@ fn try_native_to_token() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -710,7 +728,10 @@ fn try_native_to_token() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: Some(Decimal::percent(50)),
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info(
@@ -839,6 +860,7 @@ fn try_token_to_native() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -867,7 +889,10 @@ fn try_token_to_native() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: None,
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info("addr0000", &[]);
@@ -882,7 +907,10 @@ fn try_token_to_native() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -980,7 +1008,10 @@ fn try_token_to_native() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
     });
@@ -1061,6 +1092,7 @@ fn test_query_pool() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -1132,6 +1164,7 @@ fn test_query_share() {
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -1332,6 +1365,7 @@ proptest! {
 
         let msg = InstantiateMsg {
             factory_addr: String::from("factory"),
+            creator: None,
             asset_infos: vec![offer_asset.info.clone(), ask_asset.clone()],
             token_code_id: 10u64,
             init_params: Some(to_binary(&StablePoolParams { amp, owner: None }).unwrap()),
@@ -1351,7 +1385,7 @@ proptest! {
         select_pools(Some(&offer_asset.info), None, &pools).unwrap();
 
         let result = compute_swap(
-            deps.as_ref().storage,
+            &Precisions::new(deps.as_ref().storage).unwrap(),
             &env,
             &config,
             &offer_asset.to_decimal_asset(offer_asset.info.decimals(&deps.as_ref().querier, &config.factory_addr).unwrap()).unwrap(),
@@ -1419,6 +1453,7 @@ fn update_owner() {
             },
         ],
         factory_addr: "factory".to_owned(),
+        creator: None,
         token_code_id: 123u64,
         init_params: Some(
             to_binary(&StablePoolParams {
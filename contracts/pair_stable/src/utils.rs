@@ -1,25 +1,22 @@
 use std::cmp::Ordering;
 
 use cosmwasm_std::{
-    to_binary, wasm_execute, Addr, Api, CosmosMsg, Decimal, Env, QuerierWrapper, StdResult,
-    Storage, Uint128, Uint64,
+    Addr, Api, Decimal, Decimal256, Env, QuerierWrapper, StdResult, Storage, Uint128, Uint64,
 };
-use cw20::Cw20ExecuteMsg;
 use itertools::Itertools;
 
 use gridiron::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset};
 use gridiron::observation::{
     safe_sma_buffer_not_full, safe_sma_calculation, Observation, PrecommitObservation,
 };
-use gridiron::querier::query_factory_config;
 use gridiron_circular_buffer::error::BufferResult;
 use gridiron_circular_buffer::BufferManager;
 
 use crate::error::ContractError;
-use crate::math::calc_y;
-use crate::state::{get_precision, Config, OBSERVATIONS};
+use crate::math::{calc_y, compute_d};
+use crate::state::{Config, Precisions, MAINTENANCE_WINDOW, OBSERVATIONS, VIRTUAL_PRICE_MA};
 
-/// Helper function to check if the given asset infos are valid.
+/// Helper function to check the given asset infos of a new pool are valid.
 pub(crate) fn check_asset_infos(
     api: &dyn Api,
     asset_infos: &[AssetInfo],
@@ -30,14 +27,21 @@ pub(crate) fn check_asset_infos(
 
     asset_infos
         .iter()
-        .try_for_each(|asset_info| asset_info.check(api))
+        .try_for_each(|asset_info| asset_info.check_strict(api))
         .map_err(Into::into)
 }
 
 /// Helper function to check that the assets in a given array are valid.
 pub(crate) fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), ContractError> {
-    let asset_infos = assets.iter().map(|asset| asset.info.clone()).collect_vec();
-    check_asset_infos(api, &asset_infos)
+    if !assets.iter().map(|asset| &asset.info).all_unique() {
+        return Err(ContractError::DoublingAssets {});
+    }
+
+    // Pools created before strict validation must keep accepting liquidity
+    assets
+        .iter()
+        .try_for_each(|asset| asset.info.check(api))
+        .map_err(Into::into)
 }
 
 /// Checks that cw20 token is part of the pool.
@@ -54,6 +58,48 @@ pub(crate) fn check_cw20_in_pool(config: &Config, cw20_sender: &Addr) -> Result<
     Err(ContractError::Unauthorized {})
 }
 
+/// Checks that the pair allowlist approves the address. Every address is allowed in
+/// permissionless pairs.
+///
+/// * **address** is the address which provides liquidity or swaps.
+pub(crate) fn check_allowed(
+    querier: &QuerierWrapper,
+    config: &Config,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !config.pair_info.is_allowed(querier, address)? {
+        return Err(ContractError::AddressNotAllowed {
+            address: address.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that swaps are not halted by a post-only maintenance window.
+pub(crate) fn check_swaps_enabled(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_swaps(env.block.time.seconds()) => {
+            Err(ContractError::SwapsHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that liquidity provision and withdrawals are not halted by a swap-only maintenance
+/// window.
+pub(crate) fn check_liquidity_enabled(
+    storage: &dyn Storage,
+    env: &Env,
+) -> Result<(), ContractError> {
+    match MAINTENANCE_WINDOW.may_load(storage)? {
+        Some(window) if window.halts_liquidity(env.block.time.seconds()) => {
+            Err(ContractError::LiquidityHaltedForMaintenance { end: window.end })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Select offer and ask pools based on given offer and ask infos.
 /// This function works with pools with up to 5 assets. Returns (offer_pool, ask_pool) in case of success.
 /// If it is impossible to define offer and ask pools, returns [`ContractError`].
@@ -134,6 +180,49 @@ pub fn compute_current_amp(config: &Config, env: &Env) -> StdResult<Uint64> {
     }
 }
 
+/// Calculates the LP token virtual price which is the D invariant per LP token.
+/// Returns [`None`] if no LP tokens were minted yet.
+pub(crate) fn calc_virtual_price(
+    amp: Uint64,
+    pools: &[Decimal256],
+    total_share: Uint128,
+    greatest_precision: u8,
+) -> StdResult<Option<Decimal256>> {
+    if total_share.is_zero() {
+        return Ok(None);
+    }
+
+    let d = compute_d(amp, pools)?;
+    let total_share = Decimal256::with_precision(total_share, greatest_precision)?;
+
+    Ok(Some(d / total_share))
+}
+
+/// Moves the virtual price moving average to the current block using the virtual price of the pool
+/// before the current interaction.
+///
+/// * **pools** pool balances before the interaction.
+///
+/// * **total_share** total amount of LP tokens before the interaction.
+pub(crate) fn record_virtual_price(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    pools: &[Decimal256],
+    total_share: Uint128,
+) -> StdResult<()> {
+    let amp = compute_current_amp(config, env)?;
+    if let Some(virtual_price) =
+        calc_virtual_price(amp, pools, total_share, config.greatest_precision)?
+    {
+        let mut virtual_price_ma = VIRTUAL_PRICE_MA.may_load(storage)?.unwrap_or_default();
+        virtual_price_ma.update(env.block.time.seconds(), virtual_price)?;
+        VIRTUAL_PRICE_MA.save(storage, &virtual_price_ma)?;
+    }
+
+    Ok(())
+}
+
 /// Returns a value using a newly specified precision.
 ///
 /// * **value** value that will have its precision adjusted.
@@ -157,68 +246,6 @@ pub(crate) fn adjust_precision(
     })
 }
 
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Generator contract (if auto staking is specified).
-///
-/// * **recipient** LP token recipient.
-///
-/// * **amount** amount of LP tokens that will be minted for the recipient.
-///
-/// * **auto_stake** whether the newly minted LP tokens will be automatically staked in the Generator on behalf of the recipient.
-pub(crate) fn mint_liquidity_token_message(
-    querier: QuerierWrapper,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg>, ContractError> {
-    let lp_token = &config.pair_info.liquidity_token;
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(vec![wasm_execute(
-            lp_token,
-            &Cw20ExecuteMsg::Mint {
-                recipient: recipient.to_string(),
-                amount,
-            },
-            vec![],
-        )?
-        .into()]);
-    }
-
-    // Mint for the pair contract and stake into the Generator contract
-    let generator = query_factory_config(&querier, &config.factory_addr)?.generator_address;
-
-    if let Some(generator) = generator {
-        Ok(vec![
-            wasm_execute(
-                lp_token,
-                &Cw20ExecuteMsg::Mint {
-                    recipient: contract_address.to_string(),
-                    amount,
-                },
-                vec![],
-            )?
-            .into(),
-            wasm_execute(
-                lp_token,
-                &Cw20ExecuteMsg::Send {
-                    contract: generator.to_string(),
-                    amount,
-                    msg: to_binary(&gridiron::generator::Cw20HookMsg::DepositFor(
-                        recipient.to_string(),
-                    ))?,
-                },
-                vec![],
-            )?
-            .into(),
-        ])
-    } else {
-        Err(ContractError::AutoStakeError {})
-    }
-}
-
 /// Return the amount of tokens that a specific amount of LP tokens would withdraw.
 ///
 /// * **pools** array with assets available in the pool.
@@ -245,6 +272,28 @@ pub(crate) fn get_share_in_assets(
         .collect()
 }
 
+/// Returns the pool balances normalized with the cached asset precisions.
+/// Unlike [`PairInfo::query_pools_decimal`](gridiron::asset::PairInfo::query_pools_decimal)
+/// this doesn't query the coin registry.
+pub(crate) fn query_pools_decimal(
+    querier: &QuerierWrapper,
+    config: &Config,
+    precisions: &Precisions,
+) -> StdResult<Vec<DecimalAsset>> {
+    config
+        .pair_info
+        .query_pools(querier, &config.pair_info.contract_addr)?
+        .into_iter()
+        .map(|pool| {
+            let precision = precisions.get_precision(&pool.info)?;
+            Ok(DecimalAsset {
+                info: pool.info,
+                amount: Decimal256::with_precision(pool.amount, precision)?,
+            })
+        })
+        .collect()
+}
+
 /// Structure for internal use which represents swap result.
 pub(crate) struct SwapResult {
     pub return_amount: Uint128,
@@ -261,7 +310,7 @@ pub(crate) struct SwapResult {
 ///
 /// * **pools** array with assets available in the pool.
 pub(crate) fn compute_swap(
-    storage: &dyn Storage,
+    precisions: &Precisions,
     env: &Env,
     config: &Config,
     offer_asset: &DecimalAsset,
@@ -269,13 +318,14 @@ pub(crate) fn compute_swap(
     ask_pool: &DecimalAsset,
     pools: &[DecimalAsset],
 ) -> Result<SwapResult, ContractError> {
-    let token_precision = get_precision(storage, &ask_pool.info)?;
-    let xp = pools.iter().map(|p| p.amount).collect_vec();
+    let token_precision = precisions.get_precision(&ask_pool.info)?;
 
     let new_ask_pool = calc_y(
-        compute_current_amp(config, env)?,
+        offer_pool,
+        &ask_pool.info,
         offer_pool.amount + offer_asset.amount,
-        &xp,
+        pools,
+        compute_current_amp(config, env)?,
         token_precision,
     )?;
 
@@ -353,23 +403,25 @@ pub fn accumulate_swap_sizes(storage: &mut dyn Storage, env: &Env) -> BufferResu
     Ok(())
 }
 
-/// Internal function to determine which asset is base one, which is quote one
+/// Internal function to determine which asset is base one, which is quote one.
+/// Observations track the price of the first pool asset in the second one, so swaps between
+/// other assets of pools with more than two assets are not observed and [`None`] is returned.
 pub(crate) fn determine_base_quote_amount(
     pools: &[DecimalAsset],
     offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
     return_amount: Uint128,
-) -> Result<(Uint128, Uint128), ContractError> {
-    let offer_index = pools
-        .iter()
-        .find_position(|asset| asset.info == offer_asset.info)
-        .ok_or_else(|| ContractError::InvalidAsset(offer_asset.info.to_string()))?
-        .0;
-
-    let (base_amount, quote_amount) = if offer_index == 0 {
-        (offer_asset.amount, return_amount)
-    } else {
-        (return_amount, offer_asset.amount)
+) -> Result<Option<(Uint128, Uint128)>, ContractError> {
+    let position = |info: &AssetInfo| {
+        pools
+            .iter()
+            .position(|asset| asset.info == *info)
+            .ok_or_else(|| ContractError::InvalidAsset(info.to_string()))
     };
 
-    Ok((base_amount, quote_amount))
+    match (position(&offer_asset.info)?, position(ask_asset_info)?) {
+        (0, 1) => Ok(Some((offer_asset.amount, return_amount))),
+        (1, 0) => Ok(Some((return_amount, offer_asset.amount))),
+        _ => Ok(None),
+    }
 }
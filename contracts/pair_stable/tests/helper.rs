@@ -15,8 +15,8 @@ use gridiron::asset::{native_asset_info, token_asset_info, Asset, AssetInfo, Pai
 use gridiron::factory::{PairConfig, PairType};
 use gridiron::observation::OracleObservation;
 use gridiron::pair::{
-    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, QueryMsg, ReverseSimulationResponse,
-    SimulationResponse, StablePoolParams,
+    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, QueryMsg,
+    ReverseSimulationResponse, SimulationResponse, StablePoolParams, VirtualPriceResponse,
 };
 pub const NATIVE_TOKEN_PRECISION: u8 = 6;
 use gridiron_pair_stable::contract::{execute, instantiate, query, reply};
@@ -243,6 +243,8 @@ impl Helper {
             slippage_tolerance: None,
             auto_stake: None,
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         };
 
         self.app
@@ -258,7 +260,11 @@ impl Helper {
         let msg = Cw20ExecuteMsg::Send {
             contract: self.pair_addr.to_string(),
             amount: Uint128::from(amount),
-            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets }).unwrap(),
+            msg: to_binary(&Cw20HookMsg::WithdrawLiquidity {
+                assets,
+                deadline: None,
+            })
+            .unwrap(),
         };
 
         self.app
@@ -280,7 +286,10 @@ impl Helper {
                         ask_asset_info,
                         belief_price: None,
                         max_spread: None,
+                        min_receive: None,
                         to: None,
+                        deadline: None,
+                        referral: None,
                     })
                     .unwrap(),
                 };
@@ -301,7 +310,10 @@ impl Helper {
                     ask_asset_info,
                     belief_price: None,
                     max_spread: None,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 };
 
                 self.app
@@ -344,6 +356,18 @@ impl Helper {
             .query_wasm_smart(&self.pair_addr, &QueryMsg::CumulativePrices {})
     }
 
+    pub fn query_config(&self) -> StdResult<ConfigResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::Config {})
+    }
+
+    pub fn query_virtual_price(&self) -> StdResult<VirtualPriceResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::VirtualPrice {})
+    }
+
     pub fn query_observe(&self, seconds_ago: u64) -> StdResult<OracleObservation> {
         self.app
             .wrap()
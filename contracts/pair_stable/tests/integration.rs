@@ -159,6 +159,7 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_addr.to_string(),
+        creator: None,
         init_params: None,
     };
 
@@ -188,6 +189,7 @@ fn instantiate_pair(mut router: &mut App, owner: &Addr) -> Addr {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_addr.to_string(),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -359,6 +361,56 @@ fn test_provide_and_withdraw_liquidity() {
         res.events[3].attributes[3],
         attr("amount", 200000.to_string())
     );
+
+    // Auto-staking fails while the factory has no generator
+    let (mut msg, coins) = provide_liquidity_msg(
+        Uint128::new(100000),
+        Uint128::new(100000),
+        Some("bob".to_string()),
+    );
+    if let ExecuteMsg::ProvideLiquidity { auto_stake, .. } = &mut msg {
+        *auto_stake = Some(true);
+    }
+    let err = router
+        .execute_contract(alice_address.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap_err();
+    assert_eq!(
+        "Generator address is not set in factory. Cannot auto-stake",
+        err.root_cause().to_string()
+    );
+
+    // The provision must mint at least min_lp_to_receive LP tokens
+    if let ExecuteMsg::ProvideLiquidity {
+        auto_stake,
+        min_lp_to_receive,
+        ..
+    } = &mut msg
+    {
+        *auto_stake = None;
+        *min_lp_to_receive = Some(Uint128::new(200001));
+    }
+    let err = router
+        .execute_contract(alice_address.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap_err();
+    assert_eq!(
+        "Provision mints 200000 LP tokens, less than the minimum of 200001",
+        err.root_cause().to_string()
+    );
+
+    if let ExecuteMsg::ProvideLiquidity {
+        min_lp_to_receive, ..
+    } = &mut msg
+    {
+        *min_lp_to_receive = Some(Uint128::new(200000));
+    }
+    let res = router
+        .execute_contract(alice_address.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+    assert_eq!(res.events[1].attributes[3], attr("receiver", "bob"));
+    assert_eq!(
+        res.events[1].attributes[5],
+        attr("share", 200000u128.to_string())
+    );
 }
 
 fn provide_liquidity_msg(
@@ -384,6 +436,8 @@ fn provide_liquidity_msg(
         slippage_tolerance: None,
         auto_stake: None,
         receiver,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let coins = [
@@ -575,7 +629,10 @@ fn provide_lp_for_single_token() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -607,6 +664,8 @@ fn provide_lp_for_single_token() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     let err = app
@@ -635,6 +694,8 @@ fn provide_lp_for_single_token() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -659,6 +720,8 @@ fn provide_lp_for_single_token() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -691,6 +754,8 @@ fn provide_lp_for_single_token() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -703,7 +768,10 @@ fn provide_lp_for_single_token() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -719,7 +787,10 @@ fn provide_lp_for_single_token() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: None,
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -921,6 +992,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -940,7 +1013,10 @@ fn test_compatibility_of_tokens_with_different_precision() {
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: Some(user.to_string()),
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -998,6 +1074,7 @@ fn create_pair_with_same_assets() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: String::from("factory"),
+        creator: None,
         init_params: None,
     };
 
@@ -1077,6 +1154,7 @@ fn update_pair_config() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -1324,6 +1402,7 @@ fn enable_disable_fee_sharing() {
         ],
         token_code_id: token_contract_code_id,
         factory_addr: factory_instance.to_string(),
+        creator: None,
         init_params: Some(
             to_binary(&StablePoolParams {
                 amp: 100,
@@ -1435,6 +1514,122 @@ fn enable_disable_fee_sharing() {
     assert!(params.fee_share.is_none());
 }
 
+#[test]
+fn refresh_precisions() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(owner.clone(), vec![]);
+
+    let pair = instantiate_pair(&mut router, &owner);
+    let coin_registry = Addr::unchecked("contract0");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_binary(&StablePoolUpdateParams::RefreshPrecisions {}).unwrap(),
+    };
+
+    let err = router
+        .execute_contract(Addr::unchecked("user"), pair.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let set_decimals = |router: &mut App, decimals: u8| {
+        router
+            .execute_contract(
+                owner.clone(),
+                coin_registry.clone(),
+                &gridiron::native_coin_registry::ExecuteMsg::Add {
+                    native_coins: vec![("uluna".to_string(), decimals)],
+                },
+                &[],
+            )
+            .unwrap();
+    };
+
+    // The greatest precision defines the LP token decimals and can't change
+    set_decimals(&mut router, 8);
+    let err = router
+        .execute_contract(owner.clone(), pair.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::GreatestPrecisionChanged(6, 8)
+    );
+
+    set_decimals(&mut router, 5);
+    let resp = router
+        .execute_contract(owner.clone(), pair.clone(), &msg, &[])
+        .unwrap();
+    assert_eq!(
+        resp.events[1].attributes[1],
+        attr("action", "refresh_precisions")
+    );
+}
+
+#[test]
+fn share_value_in() {
+    let owner = Addr::unchecked("owner");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000000u128),
+            },
+        ],
+    );
+
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(1000000_000000),
+        Uint128::new(1000000_000000),
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+
+    let value: Asset = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::ShareValueIn {
+                amount: pool.total_share.multiply_ratio(1u8, 2u8),
+                asset_info: native_asset_info("uusd".to_string()),
+            },
+        )
+        .unwrap();
+
+    // 500k uusd plus 500k uluna swapped to uusd with a small spread
+    assert_eq!(value.info, native_asset_info("uusd".to_string()));
+    assert!(value.amount < Uint128::new(1000000_000000));
+    assert!(value.amount > Uint128::new(990000_000000));
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Asset>(
+            &pair_instance,
+            &QueryMsg::ShareValueIn {
+                amount: pool.total_share,
+                asset_info: native_asset_info("ibc/usdc".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Given asset does not belong in the pair"));
+}
+
 #[test]
 fn check_observe_queries() {
     let owner = Addr::unchecked("owner");
@@ -1494,7 +1689,10 @@ fn check_observe_queries() {
         ask_asset_info: None,
         belief_price: None,
         max_spread: None,
+        min_receive: None,
         to: None,
+        deadline: None,
+        referral: None,
     };
     let send_funds = vec![Coin {
         denom: "uusd".to_owned(),
@@ -1668,6 +1866,7 @@ fn test_imbalance_withdraw_is_disabled() {
                 },
                 amount: Uint128::from(100u8),
             }],
+            deadline: None,
         })
         .unwrap(),
     };
@@ -1998,6 +2197,8 @@ fn test_fee_share(
         slippage_tolerance: None,
         auto_stake: None,
         receiver: None,
+        deadline: None,
+        min_lp_to_receive: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -2031,7 +2232,10 @@ fn test_fee_share(
             ask_asset_info: None,
             belief_price: None,
             max_spread: None,
+            min_receive: None,
             to: Some(user.to_string()),
+            deadline: None,
+            referral: None,
         })
         .unwrap(),
         amount: x_offer,
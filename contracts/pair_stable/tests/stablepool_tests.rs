@@ -1,11 +1,13 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{Addr, Decimal, StdError};
+use cosmwasm_std::{to_binary, Addr, Decimal, Decimal256, StdError, Uint128};
+use gridiron_mocks::cw_multi_test::Executor;
 use std::str::FromStr;
 
 use gridiron::asset::AssetInfoExt;
 use gridiron::cosmwasm_ext::AbsDiff;
 use gridiron::observation::OracleObservation;
+use gridiron::pair::{ExecuteMsg, MinTradeSize, StablePoolUpdateParams};
 use gridiron_pair_stable::error::ContractError;
 use helper::AppExtension;
 
@@ -224,7 +226,6 @@ fn swap_different_precisions() {
     assert_eq!(99_94902, helper.coin_balance(&test_coins[1], &user));
 }
 
-#[ignore]
 #[test]
 fn check_swaps() {
     let owner = Addr::unchecked("owner");
@@ -281,7 +282,7 @@ fn check_wrong_initializations() {
     let err = Helper::new(&owner, vec![TestCoin::native("uluna")], 100u64, None).unwrap_err();
 
     assert_eq!(
-        ContractError::InvalidNumberOfAssets(2),
+        ContractError::UnsupportedNumberOfAssets { min: 2, max: 5 },
         err.downcast().unwrap()
     );
 
@@ -301,7 +302,7 @@ fn check_wrong_initializations() {
     .unwrap_err();
 
     assert_eq!(
-        ContractError::InvalidNumberOfAssets(2),
+        ContractError::UnsupportedNumberOfAssets { min: 2, max: 5 },
         err.downcast().unwrap()
     );
 
@@ -326,6 +327,164 @@ fn check_wrong_initializations() {
         None,
     )
     .unwrap();
+
+    // As well as 5 assets
+    Helper::new(
+        &owner,
+        vec![
+            TestCoin::native("one"),
+            TestCoin::cw20("two"),
+            TestCoin::native("three"),
+            TestCoin::cw20("four"),
+            TestCoin::native("five"),
+        ],
+        100u64,
+        None,
+    )
+    .unwrap();
+}
+
+#[test]
+fn provide_withdraw_and_swap_three_assets() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![
+        TestCoin::native("uluna"),
+        TestCoin::cw20("USDC"),
+        TestCoin::cw20("USDD"),
+    ];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let user1 = Addr::unchecked("user1");
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000000u128),
+        helper.assets[&test_coins[2]].with_balance(100_000000u128),
+    ];
+    helper.give_me_money(&assets, &user1);
+    helper.provide_liquidity(&user1, &assets).unwrap();
+    assert_eq!(299999000, helper.token_balance(&helper.lp_token, &user1));
+
+    // All assets must be listed, omitted ones are rejected
+    let user2 = Addr::unchecked("user2");
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(200_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000000u128),
+    ];
+    helper.give_me_money(&assets, &user2);
+    let err = helper.provide_liquidity(&user2, &assets).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidNumberOfAssets(3),
+        err.downcast().unwrap()
+    );
+
+    // Imbalanced provide with an explicit zero amount
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(200_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000000u128),
+        helper.assets[&test_coins[2]].with_balance(0u128),
+    ];
+    helper.provide_liquidity(&user2, &assets).unwrap();
+    assert_eq!(299_342806, helper.token_balance(&helper.lp_token, &user2));
+
+    helper
+        .withdraw_liquidity(&user1, 299999000, vec![])
+        .unwrap();
+    assert_eq!(0, helper.token_balance(&helper.lp_token, &user1));
+    assert_eq!(150163978, helper.coin_balance(&test_coins[0], &user1));
+    assert_eq!(100109318, helper.coin_balance(&test_coins[1], &user1));
+    assert_eq!(50054659, helper.coin_balance(&test_coins[2], &user1));
+
+    // Swap between the second and the third asset
+    let user3 = Addr::unchecked("user3");
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(10_000000u128);
+    let sim_resp = helper
+        .simulate_swap(&offer_asset, Some(helper.assets[&test_coins[2]].clone()))
+        .unwrap();
+    assert_eq!(9_820557, sim_resp.return_amount.u128());
+    let reverse_sim_resp = helper
+        .simulate_reverse_swap(
+            &helper.assets[&test_coins[2]].with_balance(sim_resp.return_amount.u128()),
+            Some(helper.assets[&test_coins[1]].clone()),
+        )
+        .unwrap();
+    assert_eq!(offer_asset.amount, reverse_sim_resp.offer_amount);
+
+    helper.give_me_money(&[offer_asset.clone()], &user3);
+    helper
+        .swap(
+            &user3,
+            &offer_asset,
+            Some(helper.assets[&test_coins[2]].clone()),
+        )
+        .unwrap();
+    assert_eq!(0, helper.coin_balance(&test_coins[1], &user3));
+    assert_eq!(9_820557, helper.coin_balance(&test_coins[2], &user3));
+}
+
+#[test]
+fn five_assets_with_large_reserves() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![
+        TestCoin::native("uluna"),
+        TestCoin::cw20("USDC"),
+        TestCoin::native("uusd"),
+        TestCoin::cw20("USDD"),
+        TestCoin::native("uusdt"),
+    ];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    // 1e9 whole units of every asset. D**(n+1) * AMP_PRECISION doesn't fit into Decimal256.
+    let user1 = Addr::unchecked("user1");
+    let assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(1_000_000_000_000000u128))
+        .collect::<Vec<_>>();
+    helper.give_me_money(&assets, &user1);
+    helper.provide_liquidity(&user1, &assets).unwrap();
+    let lp_amount = helper.token_balance(&helper.lp_token, &user1);
+    assert!(lp_amount > 4_999_999_000_000000);
+
+    // Imbalanced provide on top of the large reserves
+    let user2 = Addr::unchecked("user2");
+    let mut assets = test_coins
+        .iter()
+        .map(|coin| helper.assets[coin].with_balance(0u128))
+        .collect::<Vec<_>>();
+    assets[2].amount = Uint128::new(500_000_000_000000);
+    helper.give_me_money(&assets[2..3], &user2);
+    helper.provide_liquidity(&user2, &assets).unwrap();
+    assert!(helper.token_balance(&helper.lp_token, &user2) > 0);
+
+    // A swap against the large reserves barely moves the price
+    let user3 = Addr::unchecked("user3");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000_000000u128);
+    let sim_resp = helper
+        .simulate_swap(&offer_asset, Some(helper.assets[&test_coins[4]].clone()))
+        .unwrap();
+    assert!(sim_resp.return_amount.u128() > 999_000000);
+    assert!(sim_resp.return_amount.u128() < 1_000_000000);
+
+    helper.give_me_money(&[offer_asset.clone()], &user3);
+    helper
+        .swap(
+            &user3,
+            &offer_asset,
+            Some(helper.assets[&test_coins[4]].clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        sim_resp.return_amount.u128(),
+        helper.coin_balance(&test_coins[4], &user3)
+    );
+
+    helper
+        .withdraw_liquidity(&user1, lp_amount, vec![])
+        .unwrap();
+    assert_eq!(0, helper.token_balance(&helper.lp_token, &user1));
 }
 
 #[ignore]
@@ -516,3 +675,203 @@ fn check_pool_prices() {
     );
     helper.app.next_block(10);
 }
+
+#[test]
+fn check_virtual_price_ma() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::cw20("USDX")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, Some(500u16)).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block(1000);
+
+    // The moving average is not initialized until the first interaction with a funded pool
+    let res = helper.query_virtual_price().unwrap();
+    let initial_price = res.virtual_price;
+    assert!(initial_price.abs_diff(Decimal256::one()) < Decimal256::from_ratio(1u8, 1_000_000u32));
+    assert_eq!(res.virtual_price_ma, Decimal256::zero());
+    assert_eq!(res.ma_half_time, 600);
+
+    let user1 = Addr::unchecked("user1");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user1);
+    helper
+        .swap(
+            &user1,
+            &offer_asset,
+            Some(helper.assets[&test_coins[1]].clone()),
+        )
+        .unwrap();
+
+    let res = helper.query_virtual_price().unwrap();
+    assert_eq!(res.virtual_price_ma, initial_price);
+
+    // A large swap in the same block moves the virtual price but not the moving average
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(50_000_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user1);
+    helper
+        .swap(
+            &user1,
+            &offer_asset,
+            Some(helper.assets[&test_coins[1]].clone()),
+        )
+        .unwrap();
+
+    let res = helper.query_virtual_price().unwrap();
+    let price = res.virtual_price;
+    assert!(price > initial_price);
+    assert_eq!(res.virtual_price_ma, initial_price);
+
+    // After one half-time the moving average is halfway to the new price
+    helper.app.next_block(600);
+    let res = helper.query_virtual_price().unwrap();
+    assert_eq!(res.virtual_price, price);
+    assert!(
+        (res.virtual_price_ma + res.virtual_price_ma).abs_diff(initial_price + price)
+            < Decimal256::raw(10)
+    );
+
+    // The moving average converges to the virtual price over time
+    helper.app.next_block(30 * 86400);
+    let res = helper.query_virtual_price().unwrap();
+    assert_eq!(res.virtual_price_ma, price);
+
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_binary(&StablePoolUpdateParams::UpdateVirtualPriceMaHalfTime {
+                    ma_half_time: 0,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidMaHalfTime {}
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_binary(&StablePoolUpdateParams::UpdateVirtualPriceMaHalfTime {
+                    ma_half_time: 3600,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res = helper.query_virtual_price().unwrap();
+    assert_eq!(res.ma_half_time, 3600);
+    assert_eq!(res.virtual_price_ma, price);
+}
+
+#[test]
+fn check_min_trade_floor() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::cw20precise("USDX", 18)];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000_000000000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    // Observation thresholds are always reported while the swap floor is disabled
+    let config = helper.query_config().unwrap();
+    assert_eq!(
+        config.min_trade_sizes,
+        vec![
+            MinTradeSize {
+                asset_info: helper.assets[&test_coins[0]].clone(),
+                observation: Uint128::new(10),
+                swap: Uint128::zero(),
+            },
+            MinTradeSize {
+                asset_info: helper.assets[&test_coins[1]].clone(),
+                observation: Uint128::new(10_000_000_000_000),
+                swap: Uint128::zero(),
+            },
+        ]
+    );
+
+    let update_floor = |floor: Option<Decimal256>| ExecuteMsg::UpdateConfig {
+        params: to_binary(&StablePoolUpdateParams::UpdateMinTradeFloor {
+            min_trade_floor: floor,
+        })
+        .unwrap(),
+    };
+
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &update_floor(Some(Decimal256::percent(101))),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MinTradeFloorOutOfBounds {}
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &update_floor(Some(Decimal256::permille(1))),
+            &[],
+        )
+        .unwrap();
+
+    let config = helper.query_config().unwrap();
+    assert_eq!(config.min_trade_sizes[0].swap, Uint128::new(1000));
+    assert_eq!(
+        config.min_trade_sizes[1].swap,
+        Uint128::new(1_000_000_000_000_000)
+    );
+
+    let user1 = Addr::unchecked("user1");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(999u128);
+    helper.give_me_money(&[offer_asset.clone()], &user1);
+    let err = helper.swap(&user1, &offer_asset, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BelowMinTradeSize {
+            amount: Uint128::new(999),
+            min: Uint128::new(1000),
+        }
+    );
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user1);
+    helper.swap(&user1, &offer_asset, None).unwrap();
+
+    // Removing the floor enables dust swaps again
+    helper
+        .app
+        .execute_contract(owner, helper.pair_addr.clone(), &update_floor(None), &[])
+        .unwrap();
+    let config = helper.query_config().unwrap();
+    assert_eq!(config.min_trade_sizes[0].swap, Uint128::zero());
+}
@@ -0,0 +1,870 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, Storage, Uint128, Uint256,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::Cw20ReceiveMsg;
+
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, AssetInfoExt};
+use gridiron::factory::{
+    ConfigResponse as FactoryConfigResponse, FeeInfoResponse, PairType, QueryMsg as FactoryQueryMsg,
+};
+use gridiron::observation::PrecommitObservation;
+use gridiron::pair::{
+    CombinedFees, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    Referral, ReferralFee, ReverseSimulationResponse, SimulationResponse, XYKPoolParams,
+    INTERFACE_DEADLINE, INTERFACE_FEE_DISCOUNTS, INTERFACE_MIN_LP_TO_RECEIVE,
+    INTERFACE_OBSERVATIONS, INTERFACE_REFERRAL, INTERFACE_VERSIONED_QUERIES,
+};
+use gridiron::pair_xyk_sale_tax::{
+    split_sale_tax, SaleTaxConfig, SaleTaxInitParams, SaleTaxPoolConfig, SaleTaxSide,
+    SaleTaxUpdateParams, MAX_SALE_TAX_BPS, SALE_TAX_PAIR_TYPE,
+};
+use gridiron::points::swap_hook_msg;
+use gridiron::querier::{query_factory_config, query_pair_fee_info};
+use gridiron_pair::contract::{
+    accumulate_observations, accumulate_prices, accumulate_rounding_dust, assert_deadline,
+    assert_max_spread, assert_min_receive, assert_price_move, calculate_maker_fee,
+    compute_offer_amount, compute_swap, compute_swap_with_dust, query_fee_discount_bps,
+    rounding_remainder,
+};
+use gridiron_pair::error::ContractError as PairContractError;
+use gridiron_pair::state::{BALANCES, CONFIG};
+
+use crate::error::ContractError;
+use crate::state::TAX_CONFIG;
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-pair-xyk-sale-tax";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+gridiron::supported_interfaces!(
+    INTERFACE_DEADLINE,
+    INTERFACE_MIN_LP_TO_RECEIVE,
+    INTERFACE_VERSIONED_QUERIES,
+    INTERFACE_FEE_DISCOUNTS,
+    INTERFACE_OBSERVATIONS,
+    INTERFACE_REFERRAL,
+);
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// The init params must be a [`SaleTaxInitParams`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let params: SaleTaxInitParams = from_binary(
+        msg.init_params
+            .as_ref()
+            .ok_or(ContractError::InitParamsNotFound {})?,
+    )?;
+
+    if !msg
+        .asset_infos
+        .iter()
+        .any(|asset_info| asset_info.equal(&params.tax_asset))
+    {
+        return Err(ContractError::InvalidTaxAsset {});
+    }
+
+    let max_sale_tax_bps = max_sale_tax_bps(deps.as_ref(), &msg.factory_addr)?;
+    validate_tax_bps(params.buy_tax_bps, max_sale_tax_bps)?;
+    validate_tax_bps(params.sell_tax_bps, max_sale_tax_bps)?;
+
+    let tax_config = SaleTaxConfig {
+        tax_asset: params.tax_asset,
+        buy_tax_bps: params.buy_tax_bps,
+        sell_tax_bps: params.sell_tax_bps,
+        tax_recipient: deps.api.addr_validate(&params.tax_recipient)?,
+        tax_config_admin: deps.api.addr_validate(&params.tax_config_admin)?,
+    };
+    TAX_CONFIG.save(deps.storage, &tax_config)?;
+
+    // The constant product pair handles the rest of the initialization
+    let response = gridiron_pair::contract::instantiate(
+        deps.branch(),
+        env.clone(),
+        info,
+        InstantiateMsg {
+            init_params: Some(to_binary(&XYKPoolParams {
+                track_asset_balances: params.track_asset_balances,
+            })?),
+            ..msg
+        },
+    )?;
+
+    // The factory doesn't pass the pair type, so it is looked up in the factory pair configs
+    let mut config = CONFIG.load(deps.storage)?;
+    config.pair_info.pair_type = factory_pair_type(deps.as_ref(), &env, &config.factory_addr)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(response.add_attributes(tax_config_attributes(&tax_config)))
+}
+
+/// The entry point to the contract for processing replies from submessages.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    gridiron_pair::contract::reply(deps, env, msg).map_err(Into::into)
+}
+
+/// Exposes all the execute functions available in the contract.
+/// Swaps charge the sale tax, other messages are processed by the constant product pair.
+///
+/// ## Variants
+/// * **ExecuteMsg::UpdateConfig { params: Binary }** Updates the tax config if `params` is
+/// a [`SaleTaxUpdateParams`] object, otherwise updates the pool config.
+///
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
+/// it depending on the received template.
+///
+/// * **ExecuteMsg::ProvideLiquidity {
+///             assets,
+///             slippage_tolerance,
+///             auto_stake,
+///             receiver,
+///             deadline,
+///             min_lp_to_receive,
+///         }** Provides liquidity in the pair with the specified input parameters.
+///
+/// * **ExecuteMsg::Swap {
+///             offer_asset,
+///             belief_price,
+///             max_spread,
+///             min_receive,
+///             to,
+///             deadline,
+///             referral,
+///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::BatchSwap { .. }** Is rejected as the hops would bypass the sale tax.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread,
+            min_receive,
+            to,
+            deadline,
+            referral,
+            ..
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(PairContractError::Cw20DirectSwap {}.into());
+            }
+
+            let to_addr = addr_opt_validate(deps.api, &to)?;
+
+            swap(
+                deps,
+                env,
+                info.clone(),
+                info.sender,
+                offer_asset,
+                belief_price,
+                max_spread,
+                min_receive,
+                to_addr,
+                referral,
+            )
+        }
+        ExecuteMsg::UpdateConfig { params } => match from_binary(&params) {
+            Ok(SaleTaxUpdateParams::UpdateTaxConfig {
+                buy_tax_bps,
+                sell_tax_bps,
+                tax_recipient,
+                tax_config_admin,
+            }) => update_tax_config(
+                deps,
+                info,
+                buy_tax_bps,
+                sell_tax_bps,
+                tax_recipient,
+                tax_config_admin,
+            ),
+            Err(_) => {
+                let previous_fees = pair_fees(deps.storage)?;
+                let response =
+                    gridiron_pair::contract::update_config(deps.branch(), env, info, params)?;
+                check_combined_fee(deps.as_ref(), previous_fees)?;
+
+                Ok(response)
+            }
+        },
+        // Hops executed by the xyk pair logic would bypass the sale tax
+        ExecuteMsg::BatchSwap { .. } => Err(ContractError::BatchSwapNotSupported {}),
+        _ => gridiron_pair::contract::execute(deps, env, info, msg).map_err(Into::into),
+    }
+}
+
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+///
+/// * **cw20_msg** is the CW20 message that has to be processed.
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Swap {
+            belief_price,
+            max_spread,
+            min_receive,
+            to,
+            deadline,
+            referral,
+            ..
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            // Only asset contract can execute this message
+            let config = CONFIG.load(deps.storage)?;
+            let authorized = config.pair_info.asset_infos.iter().any(|asset_info| {
+                matches!(asset_info, AssetInfo::Token { contract_addr } if *contract_addr == info.sender)
+            });
+
+            if !authorized {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let to_addr = addr_opt_validate(deps.api, &to)?;
+            let contract_addr = info.sender.clone();
+
+            swap(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                Asset {
+                    info: AssetInfo::Token { contract_addr },
+                    amount: cw20_msg.amount,
+                },
+                belief_price,
+                max_spread,
+                min_receive,
+                to_addr,
+                referral,
+            )
+        }
+        Cw20HookMsg::BatchSwap { .. } => Err(ContractError::BatchSwapNotSupported {}),
+        Cw20HookMsg::WithdrawLiquidity { .. } => {
+            gridiron_pair::contract::receive_cw20(deps, env, info, cw20_msg).map_err(Into::into)
+        }
+    }
+}
+
+/// Performs a swap operation with the specified parameters and charges the sale tax.
+/// The sell tax is deducted from the offer amount before the swap, while the buy tax is deducted
+/// from the return amount. The tax is sent to the tax recipient and isn't counted as a part of the spread.
+/// Otherwise the swap follows the constant product pair, including the per block price move limit
+/// and the price observations.
+///
+/// * **sender** is the sender of the swap operation.
+///
+/// * **offer_asset** proposed asset for swapping.
+///
+/// * **belief_price** is used to calculate the maximum swap spread.
+///
+/// * **max_spread** sets the maximum spread of the swap operation.
+///
+/// * **min_receive** sets the minimum amount of ask tokens to receive after the buy tax.
+/// When set, it replaces the `belief_price` and `max_spread` check.
+///
+/// * **to** sets the recipient of the swap operation.
+///
+/// * **referral** the frontend which takes a fee from the amount left after the buy tax.
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
+    to: Option<Addr>,
+    referral: Option<Referral>,
+) -> Result<Response, ContractError> {
+    offer_asset.assert_sent_native_token_balance(&info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let tax_config = TAX_CONFIG.load(deps.storage)?;
+
+    gridiron_pair::contract::assert_allowed(&deps.querier, &config, &sender)?;
+    gridiron_pair::contract::assert_swaps_enabled(deps.storage, &env)?;
+    gridiron_pair::contract::assert_min_trade_size(deps.as_ref(), &config, &offer_asset)?;
+
+    // If the asset balance is already increased, we should subtract the user deposit from the pool amount
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?
+        .into_iter()
+        .map(|mut p| {
+            if p.info.equal(&offer_asset.info) {
+                p.amount = p.amount.checked_sub(offer_asset.amount)?;
+            }
+            Ok(p)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let (offer_pool, ask_pool) =
+        select_pools(&pools, &offer_asset.info).ok_or(PairContractError::AssetMismatch {})?;
+
+    // Get fee info from the factory
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        &config.pair_info.contract_addr,
+    )?;
+
+    // Traders holding xGRID pay a discounted fee
+    let fee_discount_bps = match &fee_info.fee_discounts {
+        Some(fee_discounts) => {
+            query_fee_discount_bps(deps.storage, &deps.querier, fee_discounts, &sender)?
+        }
+        None => 0,
+    };
+    let total_fee_rate = fee_info.total_fee_rate
+        * Decimal::from_ratio(10_000u16.saturating_sub(fee_discount_bps), 10_000u16);
+
+    let tax = tax_config.tax_for_swap(&offer_pool.info, &ask_pool.info);
+
+    let (offer_amount, sell_tax_amount) = match tax {
+        Some((SaleTaxSide::Sell, tax_bps)) => split_sale_tax(offer_asset.amount, tax_bps),
+        _ => (offer_asset.amount, Uint128::zero()),
+    };
+
+    let (return_amount, spread_amount, commission_amount, mut rounding_dust) =
+        compute_swap_with_dust(
+            offer_pool.amount,
+            ask_pool.amount,
+            offer_amount,
+            total_fee_rate,
+        )?;
+
+    let (receive_amount, buy_tax_amount) = match tax {
+        Some((SaleTaxSide::Buy, tax_bps)) => split_sale_tax(return_amount, tax_bps),
+        _ => (return_amount, Uint128::zero()),
+    };
+
+    let referral_fee = ReferralFee::new(
+        deps.api,
+        referral,
+        fee_info.max_referral_bps,
+        receive_amount,
+    )?;
+    let receive_amount = receive_amount - referral_fee.amount;
+
+    if let Some(min_receive) = min_receive {
+        assert_min_receive(min_receive, receive_amount)?;
+    } else {
+        // Check the max spread limit (if it was specified)
+        assert_max_spread(
+            belief_price,
+            max_spread,
+            offer_amount,
+            return_amount + commission_amount,
+            spread_amount,
+        )?;
+    }
+
+    let receiver = to.unwrap_or_else(|| sender.clone());
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !receive_amount.is_zero() {
+        messages.push(
+            ask_pool
+                .info
+                .with_balance(receive_amount)
+                .into_msg(receiver.clone())?,
+        );
+    }
+    messages.extend(referral_fee.into_msg(&ask_pool.info)?);
+
+    let (tax_side, sale_tax) = match tax {
+        Some((SaleTaxSide::Sell, _)) => ("sell", offer_pool.info.with_balance(sell_tax_amount)),
+        Some((SaleTaxSide::Buy, _)) => ("buy", ask_pool.info.with_balance(buy_tax_amount)),
+        None => ("none", ask_pool.info.with_balance(Uint128::zero())),
+    };
+    if !sale_tax.amount.is_zero() {
+        messages.push(
+            sale_tax
+                .clone()
+                .into_msg(tax_config.tax_recipient.clone())?,
+        );
+    }
+
+    // If this pool is configured to share fees, calculate the amount to send
+    // to the receiver and add the transfer message
+    let mut fees_commission_amount = commission_amount;
+    let mut fee_share_amount = Uint128::zero();
+    if let Some(fee_share) = config.fee_share.clone() {
+        // Calculate the fee share amount from the full commission amount
+        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
+        fee_share_amount = fees_commission_amount * share_fee_rate;
+        rounding_dust +=
+            rounding_remainder(fees_commission_amount, share_fee_rate, fee_share_amount)?;
+
+        if !fee_share_amount.is_zero() {
+            // Subtract the fee share amount from the commission
+            fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
+
+            messages.push(
+                ask_pool
+                    .info
+                    .with_balance(fee_share_amount)
+                    .into_msg(fee_share.recipient)?,
+            );
+        }
+    }
+
+    // Compute the Maker fee
+    let mut maker_fee_amount = Uint128::zero();
+    if let Some(fee_address) = fee_info.fee_address {
+        if let Some(f) = calculate_maker_fee(
+            &ask_pool.info,
+            fees_commission_amount,
+            fee_info.maker_fee_rate,
+        ) {
+            maker_fee_amount = f.amount;
+            messages.push(f.into_msg(fee_address)?);
+        }
+        rounding_dust += rounding_remainder(
+            fees_commission_amount,
+            fee_info.maker_fee_rate,
+            maker_fee_amount,
+        )?;
+    }
+
+    // Report the swap to the points contract (if any)
+    if let Some(points_contract) = &fee_info.points_contract {
+        messages.push(swap_hook_msg(points_contract, &receiver, &offer_asset)?);
+    }
+
+    accumulate_rounding_dust(deps.storage, &ask_pool.info, rounding_dust)?;
+
+    // The sell tax never reaches the pool while the buy tax is a part of the return amount
+    let offer_pool_new = offer_pool.amount + offer_amount;
+    let ask_pool_new = ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount;
+
+    if config.track_asset_balances {
+        BALANCES.save(
+            deps.storage,
+            &offer_pool.info,
+            &offer_pool_new,
+            env.block.height,
+        )?;
+        BALANCES.save(
+            deps.storage,
+            &ask_pool.info,
+            &ask_pool_new,
+            env.block.height,
+        )?;
+    }
+
+    let (base_amount, quote_amount) = if offer_asset.info.equal(&pools[0].info) {
+        (offer_pool_new, ask_pool_new)
+    } else {
+        (ask_pool_new, offer_pool_new)
+    };
+    assert_price_move(
+        deps.storage,
+        &env,
+        (pools[0].amount, pools[1].amount),
+        (base_amount, quote_amount),
+    )?;
+
+    // Store observation from precommit data
+    accumulate_observations(deps.storage, &env).map_err(PairContractError::from)?;
+
+    // Store the pool price after the swap in precommit observation.
+    // This data will be reflected in observations on the next action.
+    if !base_amount.is_zero() && !quote_amount.is_zero() {
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
+    // Accumulate prices for the assets in the pool
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "swap"),
+            attr("sender", sender),
+            attr("receiver", receiver),
+            attr("offer_asset", offer_asset.info.to_string()),
+            attr("ask_asset", ask_pool.info.to_string()),
+            attr("offer_amount", offer_asset.amount),
+            attr("return_amount", receive_amount),
+            attr("spread_amount", spread_amount),
+            attr("commission_amount", commission_amount),
+            attr("maker_fee_amount", maker_fee_amount),
+            attr("fee_share_amount", fee_share_amount),
+            attr("sale_tax_side", tax_side),
+            attr("sale_tax_asset", sale_tax.info.to_string()),
+            attr("sale_tax_amount", sale_tax.amount),
+            attr("sale_tax_recipient", tax_config.tax_recipient),
+        ])
+        .add_attributes(
+            (fee_discount_bps > 0).then(|| attr("fee_discount_bps", fee_discount_bps.to_string())),
+        )
+        .add_attributes(referral_fee.attributes()))
+}
+
+/// Updates the sale tax config.
+///
+/// ## Executor
+/// Only the tax config admin can execute this.
+fn update_tax_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    buy_tax_bps: Option<u16>,
+    sell_tax_bps: Option<u16>,
+    tax_recipient: Option<String>,
+    tax_config_admin: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut tax_config = TAX_CONFIG.load(deps.storage)?;
+
+    if info.sender != tax_config.tax_config_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let previous_fees = pair_fees(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let max_sale_tax_bps = max_sale_tax_bps(deps.as_ref(), &config.factory_addr)?;
+
+    if let Some(buy_tax_bps) = buy_tax_bps {
+        validate_tax_bps(buy_tax_bps, max_sale_tax_bps)?;
+        tax_config.buy_tax_bps = buy_tax_bps;
+    }
+
+    if let Some(sell_tax_bps) = sell_tax_bps {
+        validate_tax_bps(sell_tax_bps, max_sale_tax_bps)?;
+        tax_config.sell_tax_bps = sell_tax_bps;
+    }
+
+    if let Some(tax_recipient) = tax_recipient {
+        tax_config.tax_recipient = deps.api.addr_validate(&tax_recipient)?;
+    }
+
+    if let Some(tax_config_admin) = tax_config_admin {
+        tax_config.tax_config_admin = deps.api.addr_validate(&tax_config_admin)?;
+    }
+
+    TAX_CONFIG.save(deps.storage, &tax_config)?;
+    check_combined_fee(deps.as_ref(), previous_fees)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_tax_config")
+        .add_attributes(tax_config_attributes(&tax_config)))
+}
+
+/// Returns the fees the pair combines on a swap except for the total fee which is set in the factory.
+fn pair_fees(storage: &dyn Storage) -> StdResult<CombinedFees> {
+    let config = CONFIG.load(storage)?;
+    let tax_config = TAX_CONFIG.load(storage)?;
+
+    Ok(CombinedFees {
+        fee_share_bps: config.fee_share.map_or(0, |share| share.bps),
+        sale_tax_bps: tax_config.buy_tax_bps.max(tax_config.sell_tax_bps),
+        ..Default::default()
+    })
+}
+
+/// Checks that a config update doesn't raise the fees the pair combines on a swap above the
+/// protocol-wide cap set in the factory.
+///
+/// * **previous_fees** the fees combined by the pair before the update.
+fn check_combined_fee(deps: Deps, previous_fees: CombinedFees) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+    let max_combined_fee_bps = match factory_config.max_combined_fee_bps {
+        Some(max_combined_fee_bps) => max_combined_fee_bps,
+        None => return Ok(()),
+    };
+
+    let fee_info: FeeInfoResponse = deps.querier.query_wasm_smart(
+        &config.factory_addr,
+        &FactoryQueryMsg::PairFeeInfo {
+            pair_addr: config.pair_info.contract_addr.to_string(),
+        },
+    )?;
+    let previous_fees = CombinedFees {
+        total_fee_bps: fee_info.total_fee_bps,
+        ..previous_fees
+    };
+    let fees = CombinedFees {
+        total_fee_bps: fee_info.total_fee_bps,
+        ..pair_fees(deps.storage)?
+    };
+
+    if fees.exceed_cap(&previous_fees, max_combined_fee_bps) {
+        return Err(ContractError::CombinedFeeExceedsCap {
+            combined_fee_bps: fees.combined_fee_bps(),
+            max_combined_fee_bps,
+        });
+    }
+
+    Ok(())
+}
+
+/// Exposes all the queries available in the contract.
+/// Simulations take the sale tax into account, other queries are processed by the constant product pair.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Simulation { offer_asset, .. } => {
+            to_binary(&query_simulation(deps, offer_asset)?)
+        }
+        QueryMsg::ReverseSimulation { ask_asset, .. } => {
+            to_binary(&query_reverse_simulation(deps, ask_asset)?)
+        }
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        // Versioned queries must be answered by the overridden handlers above
+        msg @ (QueryMsg::VersionedPair {}
+        | QueryMsg::VersionedPool {}
+        | QueryMsg::VersionedConfig {}
+        | QueryMsg::VersionedSimulation { .. }) => msg.query_versioned(|msg| query(deps, env, msg)),
+        QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
+        _ => gridiron_pair::contract::query(deps, env, msg),
+    }
+}
+
+/// Returns information about a swap simulation in a [`SimulationResponse`] object.
+/// The return amount is the amount received after the sale tax.
+///
+/// * **offer_asset** is the asset to swap as well as an amount of the said asset.
+pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let tax_config = TAX_CONFIG.load(deps.storage)?;
+
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+
+    let (offer_pool, ask_pool) = select_pools(&pools, &offer_asset.info)
+        .ok_or_else(|| StdError::generic_err("Given offer asset does not belong in the pair"))?;
+
+    // Get fee info from the factory contract
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        config.factory_addr,
+        &config.pair_info.contract_addr,
+    )?;
+
+    let tax = tax_config.tax_for_swap(&offer_pool.info, &ask_pool.info);
+
+    let offer_amount = match tax {
+        Some((SaleTaxSide::Sell, tax_bps)) => split_sale_tax(offer_asset.amount, tax_bps).0,
+        _ => offer_asset.amount,
+    };
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    let return_amount = match tax {
+        Some((SaleTaxSide::Buy, tax_bps)) => split_sale_tax(return_amount, tax_bps).0,
+        _ => return_amount,
+    };
+
+    Ok(SimulationResponse {
+        return_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Returns information about a reverse swap simulation in a [`ReverseSimulationResponse`] object.
+/// The offer amount includes the sale tax.
+///
+/// * **ask_asset** is the asset to swap to as well as the desired amount of ask
+/// assets to receive from the swap after the sale tax.
+pub fn query_reverse_simulation(
+    deps: Deps,
+    ask_asset: Asset,
+) -> StdResult<ReverseSimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let tax_config = TAX_CONFIG.load(deps.storage)?;
+
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+
+    let (ask_pool, offer_pool) = select_pools(&pools, &ask_asset.info)
+        .ok_or_else(|| StdError::generic_err("Given ask asset doesn't belong to pairs"))?;
+
+    // Get fee info from factory
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        config.factory_addr,
+        &config.pair_info.contract_addr,
+    )?;
+
+    let tax = tax_config.tax_for_swap(&offer_pool.info, &ask_pool.info);
+
+    let ask_amount = match tax {
+        Some((SaleTaxSide::Buy, tax_bps)) => amount_before_tax(ask_asset.amount, tax_bps)?,
+        _ => ask_asset.amount,
+    };
+
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    let offer_amount = match tax {
+        Some((SaleTaxSide::Sell, tax_bps)) => amount_before_tax(offer_amount, tax_bps)?,
+        _ => offer_amount,
+    };
+
+    Ok(ReverseSimulationResponse {
+        offer_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Returns the pair contract configuration in a [`ConfigResponse`] object.
+/// The params are returned as a [`SaleTaxPoolConfig`] object.
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        params: Some(to_binary(&SaleTaxPoolConfig {
+            track_asset_balances: config.track_asset_balances,
+            fee_share: config.fee_share,
+            tax_config: TAX_CONFIG.load(deps.storage)?,
+        })?),
+        ..gridiron_pair::contract::query_config(deps)?
+    })
+}
+
+/// Manages the contract migration.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        CONTRACT_NAME => match contract_version.version.as_ref() {
+            "1.0.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default().add_attributes([
+        ("previous_contract_name", contract_version.contract.as_str()),
+        (
+            "previous_contract_version",
+            contract_version.version.as_str(),
+        ),
+        ("new_contract_name", CONTRACT_NAME),
+        ("new_contract_version", CONTRACT_VERSION),
+    ]))
+}
+
+/// Returns the pair type of the enabled factory pair config which uses the code of this contract.
+/// Falls back to the [`SALE_TAX_PAIR_TYPE`] custom pair type if there is no such config.
+///
+/// * **factory_addr** the factory which creates the pair.
+fn factory_pair_type(deps: Deps, env: &Env, factory_addr: &Addr) -> StdResult<PairType> {
+    let code_id = deps
+        .querier
+        .query_wasm_contract_info(&env.contract.address)?
+        .code_id;
+    let factory_config: FactoryConfigResponse = deps
+        .querier
+        .query_wasm_smart(factory_addr, &FactoryQueryMsg::Config {})?;
+
+    Ok(factory_config
+        .pair_configs
+        .into_iter()
+        .find(|pair_config| pair_config.code_id == code_id && !pair_config.is_disabled)
+        .map_or_else(
+            || PairType::Custom(SALE_TAX_PAIR_TYPE.to_string()),
+            |pair_config| pair_config.pair_type,
+        ))
+}
+
+/// Returns the (first, second) pools where the first pool holds `asset_info`.
+fn select_pools(pools: &[Asset], asset_info: &AssetInfo) -> Option<(Asset, Asset)> {
+    if asset_info.equal(&pools[0].info) {
+        Some((pools[0].clone(), pools[1].clone()))
+    } else if asset_info.equal(&pools[1].info) {
+        Some((pools[1].clone(), pools[0].clone()))
+    } else {
+        None
+    }
+}
+
+/// Returns the smallest amount which is at least `amount` after the tax is deducted.
+fn amount_before_tax(amount: Uint128, tax_bps: u16) -> StdResult<Uint128> {
+    let denominator = Uint256::from(10000u16 - tax_bps);
+    let amount = (Uint256::from(amount) * Uint256::from(10000u16) + denominator
+        - Uint256::from(1u8))
+        / denominator;
+
+    Ok(amount.try_into()?)
+}
+
+/// Returns the cap on the buy and sell taxes set in the factory. Falls back to [`MAX_SALE_TAX_BPS`]
+/// if the factory doesn't set one.
+///
+/// * **factory_addr** the factory which creates the pair.
+fn max_sale_tax_bps(deps: Deps, factory_addr: impl Into<String>) -> StdResult<u16> {
+    let factory_config = query_factory_config(&deps.querier, factory_addr)?;
+
+    Ok(factory_config.max_sale_tax_bps.unwrap_or(MAX_SALE_TAX_BPS))
+}
+
+fn validate_tax_bps(tax_bps: u16, max_sale_tax_bps: u16) -> Result<(), ContractError> {
+    if tax_bps > max_sale_tax_bps {
+        return Err(ContractError::SaleTaxOutOfBounds { max_sale_tax_bps });
+    }
+
+    Ok(())
+}
+
+fn tax_config_attributes(tax_config: &SaleTaxConfig) -> Vec<cosmwasm_std::Attribute> {
+    vec![
+        attr("tax_asset", tax_config.tax_asset.to_string()),
+        attr("buy_tax_bps", tax_config.buy_tax_bps.to_string()),
+        attr("sell_tax_bps", tax_config.sell_tax_bps.to_string()),
+        attr("tax_recipient", &tax_config.tax_recipient),
+        attr("tax_config_admin", &tax_config.tax_config_admin),
+    ]
+}
@@ -0,0 +1,47 @@
+use cosmwasm_std::{OverflowError, StdError};
+use gridiron::pair::ReferralError;
+use gridiron_pair::error::ContractError as PairContractError;
+use thiserror::Error;
+
+/// This enum describes sale tax pair contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Pair(#[from] PairContractError),
+
+    #[error("{0}")]
+    Referral(#[from] ReferralError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("You need to provide init params")]
+    InitParamsNotFound {},
+
+    #[error("Sale tax exceeds maximum allowed value of {max_sale_tax_bps} bps")]
+    SaleTaxOutOfBounds { max_sale_tax_bps: u16 },
+
+    #[error("Tax asset must be one of the pair assets")]
+    InvalidTaxAsset {},
+
+    #[error("Batch swaps are not supported as their hops would bypass the sale tax")]
+    BatchSwapNotSupported {},
+
+    #[error("Failed to migrate the contract")]
+    MigrationError {},
+
+    #[error("Combined fee {combined_fee_bps} bps exceeds the cap of {max_combined_fee_bps} bps")]
+    CombinedFeeExceedsCap {
+        combined_fee_bps: u32,
+        max_combined_fee_bps: u16,
+    },
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
@@ -0,0 +1,5 @@
+use cw_storage_plus::Item;
+use gridiron::pair_xyk_sale_tax::SaleTaxConfig;
+
+/// Stores the sale tax config. The rest of the pair state is shared with the constant product pair.
+pub const TAX_CONFIG: Item<SaleTaxConfig> = Item::new("sale_tax_config");
@@ -0,0 +1,475 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{coin, from_binary, to_binary, Addr, Coin, Decimal, Uint128};
+use gridiron::asset::{native_asset_info, Asset, AssetInfoExt, PairInfo};
+use gridiron::factory::{
+    ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType,
+    QueryMsg as FactoryQueryMsg,
+};
+use gridiron::observation::OracleObservation;
+use gridiron::pair::{
+    ConfigResponse, ExecuteMsg, QueryMsg, SimulationResponse, XYKPoolUpdateParams,
+};
+use gridiron::pair_xyk_sale_tax::{
+    SaleTaxInitParams, SaleTaxPoolConfig, SaleTaxUpdateParams, SALE_TAX_PAIR_TYPE,
+};
+use gridiron::router::SwapOperation;
+use gridiron_mocks::cw_multi_test::{App, ContractWrapper, Executor};
+use gridiron_pair::error::ContractError as PairContractError;
+use gridiron_pair_xyk_sale_tax::error::ContractError;
+
+const OWNER: &str = "owner";
+const TAX_RECIPIENT: &str = "tax_recipient";
+const TAX_ADMIN: &str = "tax_admin";
+
+fn mock_app(owner: Addr, coins: Vec<Coin>) -> App {
+    App::new(|router, _, storage| {
+        // initialization moved to App construction
+        router.bank.init_balance(storage, &owner, coins).unwrap()
+    })
+}
+
+fn store_token_code(app: &mut App) -> u64 {
+    let grid_token_contract = Box::new(ContractWrapper::new_with_empty(
+        gridiron_token::contract::execute,
+        gridiron_token::contract::instantiate,
+        gridiron_token::contract::query,
+    ));
+
+    app.store_code(grid_token_contract)
+}
+
+fn store_pair_code(app: &mut App) -> u64 {
+    let pair_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_pair_xyk_sale_tax::contract::execute,
+            gridiron_pair_xyk_sale_tax::contract::instantiate,
+            gridiron_pair_xyk_sale_tax::contract::query,
+        )
+        .with_reply_empty(gridiron_pair_xyk_sale_tax::contract::reply),
+    );
+
+    app.store_code(pair_contract)
+}
+
+fn store_factory_code(app: &mut App) -> u64 {
+    let factory_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_factory::contract::execute,
+            gridiron_factory::contract::instantiate,
+            gridiron_factory::contract::query,
+        )
+        .with_reply_empty(gridiron_factory::contract::reply),
+    );
+
+    app.store_code(factory_contract)
+}
+
+fn instantiate_pair(app: &mut App, owner: &Addr, buy_tax_bps: u16, sell_tax_bps: u16) -> Addr {
+    instantiate_pair_with_type(
+        app,
+        owner,
+        PairType::Custom(SALE_TAX_PAIR_TYPE.to_string()),
+        buy_tax_bps,
+        sell_tax_bps,
+    )
+}
+
+fn instantiate_pair_with_type(
+    app: &mut App,
+    owner: &Addr,
+    pair_type: PairType,
+    buy_tax_bps: u16,
+    sell_tax_bps: u16,
+) -> Addr {
+    let token_code_id = store_token_code(app);
+    let pair_code_id = store_pair_code(app);
+    let factory_code_id = store_factory_code(app);
+
+    let init_msg = FactoryInstantiateMsg {
+        fee_address: None,
+        pair_configs: vec![PairConfig {
+            code_id: pair_code_id,
+            maker_fee_bps: 0,
+            pair_type: pair_type.clone(),
+            total_fee_bps: 0,
+            is_disabled: false,
+            is_generator_disabled: false,
+        }],
+        token_code_id,
+        generator_address: None,
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let asset_infos = vec![
+        native_asset_info("umeme".to_string()),
+        native_asset_info("uusd".to_string()),
+    ];
+
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &FactoryExecuteMsg::CreatePair {
+            pair_type,
+            asset_infos: asset_infos.clone(),
+            init_params: Some(
+                to_binary(&SaleTaxInitParams {
+                    track_asset_balances: None,
+                    tax_asset: native_asset_info("umeme".to_string()),
+                    buy_tax_bps,
+                    sell_tax_bps,
+                    tax_recipient: TAX_RECIPIENT.to_string(),
+                    tax_config_admin: TAX_ADMIN.to_string(),
+                })
+                .unwrap(),
+            ),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&factory, &FactoryQueryMsg::Pair { asset_infos })
+        .unwrap();
+
+    res.contract_addr
+}
+
+fn swap_msg(offer_asset: Asset) -> ExecuteMsg {
+    ExecuteMsg::Swap {
+        offer_asset,
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        min_receive: None,
+        to: None,
+        deadline: None,
+        referral: None,
+    }
+}
+
+fn query_balance(app: &App, address: &str, denom: &str) -> Uint128 {
+    app.wrap().query_balance(address, denom).unwrap().amount
+}
+
+#[test]
+fn swap_with_sale_tax() {
+    let owner = Addr::unchecked(OWNER);
+    let user = Addr::unchecked("user");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10_000_000_000, "umeme"), coin(10_000_000_000, "uusd")],
+    );
+
+    let pair = instantiate_pair(&mut app, &owner, 200, 500);
+
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("umeme".to_string()).with_balance(1_000_000_000u128),
+                native_asset_info("uusd".to_string()).with_balance(1_000_000_000u128),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
+        &[coin(1_000_000_000, "umeme"), coin(1_000_000_000, "uusd")],
+    )
+    .unwrap();
+
+    app.send_tokens(
+        owner.clone(),
+        user.clone(),
+        &[coin(1_000_000, "umeme"), coin(1_000_000, "uusd")],
+    )
+    .unwrap();
+
+    // Selling the taxed asset charges the sell tax on the offer amount
+    let offer_asset = native_asset_info("umeme".to_string()).with_balance(1_000_000u128);
+    let sim: SimulationResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pair,
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(sim.return_amount.u128(), 949_098);
+
+    app.execute_contract(
+        user.clone(),
+        pair.clone(),
+        &swap_msg(offer_asset),
+        &[coin(1_000_000, "umeme")],
+    )
+    .unwrap();
+
+    assert_eq!(query_balance(&app, TAX_RECIPIENT, "umeme").u128(), 50_000);
+    assert_eq!(
+        query_balance(&app, user.as_str(), "uusd").u128(),
+        1_000_000 + 949_098
+    );
+
+    // Buying the taxed asset charges the buy tax on the return amount
+    let offer_asset = native_asset_info("uusd".to_string()).with_balance(1_000_000u128);
+    let sim: SimulationResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pair,
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(sim.return_amount.u128(), 980_882);
+
+    app.execute_contract(
+        user.clone(),
+        pair.clone(),
+        &swap_msg(offer_asset),
+        &[coin(1_000_000, "uusd")],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_balance(&app, TAX_RECIPIENT, "umeme").u128(),
+        50_000 + 20_017
+    );
+    assert_eq!(query_balance(&app, user.as_str(), "umeme").u128(), 980_882);
+}
+
+#[test]
+fn update_tax_config() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(owner.clone(), vec![]);
+
+    let pair = instantiate_pair(&mut app, &owner, 200, 500);
+
+    let update_msg = |sell_tax_bps: u16| ExecuteMsg::UpdateConfig {
+        params: to_binary(&SaleTaxUpdateParams::UpdateTaxConfig {
+            buy_tax_bps: None,
+            sell_tax_bps: Some(sell_tax_bps),
+            tax_recipient: Some("new_recipient".to_string()),
+            tax_config_admin: None,
+        })
+        .unwrap(),
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), pair.clone(), &update_msg(300), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(TAX_ADMIN),
+            pair.clone(),
+            &update_msg(1001),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SaleTaxOutOfBounds {
+            max_sale_tax_bps: 1000
+        },
+        err.downcast().unwrap()
+    );
+
+    // The factory can lower the cap
+    let res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        res.factory_addr,
+        &FactoryExecuteMsg::SetMaxSaleTaxBps {
+            max_sale_tax_bps: Some(250),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(TAX_ADMIN),
+            pair.clone(),
+            &update_msg(300),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SaleTaxOutOfBounds {
+            max_sale_tax_bps: 250
+        },
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        Addr::unchecked(TAX_ADMIN),
+        pair.clone(),
+        &update_msg(250),
+        &[],
+    )
+    .unwrap();
+
+    let res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    let params: SaleTaxPoolConfig = from_binary(&res.params.unwrap()).unwrap();
+
+    assert_eq!(params.tax_config.buy_tax_bps, 200);
+    assert_eq!(params.tax_config.sell_tax_bps, 250);
+    assert_eq!(
+        params.tax_config.tax_recipient,
+        Addr::unchecked("new_recipient")
+    );
+    assert_eq!(
+        params.tax_config.tax_config_admin,
+        Addr::unchecked(TAX_ADMIN)
+    );
+}
+
+#[test]
+fn pair_type_from_factory() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(owner.clone(), vec![]);
+
+    // The pair keeps the type it is registered under in the factory
+    let pair_type = PairType::Custom("xyk_sale_tax_v2".to_string());
+    let pair = instantiate_pair_with_type(&mut app, &owner, pair_type.clone(), 200, 500);
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Pair {})
+        .unwrap();
+    assert_eq!(res.pair_type, pair_type);
+
+    // Batch swap hops would bypass the sale tax
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::BatchSwap {
+                operations: vec![SwapOperation::GridSwap {
+                    offer_asset_info: native_asset_info("uusd".to_string()),
+                    ask_asset_info: native_asset_info("umeme".to_string()),
+                }],
+                minimum_receive: None,
+                to: None,
+                deadline: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::BatchSwapNotSupported {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn swap_price_move_limit_and_observations() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10_000_000_000, "umeme"), coin(10_000_000_000, "uusd")],
+    );
+
+    let pair = instantiate_pair(&mut app, &owner, 200, 500);
+
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("umeme".to_string()).with_balance(1_000_000_000u128),
+                native_asset_info("uusd".to_string()).with_balance(1_000_000_000u128),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
+        &[coin(1_000_000_000, "umeme"), coin(1_000_000_000, "uusd")],
+    )
+    .unwrap();
+
+    let limit = Decimal::percent(1);
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &ExecuteMsg::UpdateConfig {
+            params: to_binary(&XYKPoolUpdateParams::UpdatePriceMoveLimit {
+                max_price_move_per_block: Some(limit),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The pool receives 19_000_000 umeme after the sell tax which moves the price by about 4%
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &swap_msg(native_asset_info("umeme".to_string()).with_balance(20_000_000u128)),
+            &[coin(20_000_000, "umeme")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Pair(PairContractError::PriceMoveLimitExceeded { limit }),
+        err.downcast().unwrap()
+    );
+
+    // Swaps within the limit are observed once a swap happens in a later block
+    let small_swap = swap_msg(native_asset_info("umeme".to_string()).with_balance(1_000_000u128));
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &small_swap,
+        &[coin(1_000_000, "umeme")],
+    )
+    .unwrap();
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(60);
+    });
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &small_swap,
+        &[coin(1_000_000, "umeme")],
+    )
+    .unwrap();
+
+    let observation: OracleObservation = app
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Observe { seconds_ago: 0 })
+        .unwrap();
+    assert!(observation.price > Decimal::one());
+}
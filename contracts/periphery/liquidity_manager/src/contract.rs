@@ -167,6 +167,8 @@ fn provide_liquidity(
         slippage_tolerance,
         auto_stake,
         receiver,
+        deadline,
+        min_lp_to_receive: pair_min_lp_to_receive,
     } = exec_msg
     {
         if assets.len() != 2 {
@@ -295,6 +297,8 @@ fn provide_liquidity(
             slippage_tolerance,
             auto_stake,
             receiver: Some(receiver.to_string()),
+            deadline,
+            min_lp_to_receive: pair_min_lp_to_receive,
         };
         let mut provide_msg = SubMsg::new(wasm_execute(&pair_addr, &tweaked_exec_msg, funds)?);
 
@@ -162,6 +162,8 @@ mod tests {
                 slippage_tolerance: Some(Decimal::from_str("0.02").unwrap()),
                 auto_stake: Some(true),
                 receiver: Some("wasm1...addr".to_string()),
+                deadline: None,
+                min_lp_to_receive: None,
             },
         };
 
@@ -194,6 +196,8 @@ mod tests {
                 slippage_tolerance: Some(Decimal::from_str("0.02").unwrap()),
                 auto_stake: Some(true),
                 receiver: Some("wasm1...addr".to_string()),
+                deadline: None,
+                min_lp_to_receive: None,
             },
             min_lp_to_receive: Some(100000u128.into()),
         };
@@ -204,7 +208,10 @@ mod tests {
         );
 
         let cw20hook_msg = Cw20HookMsg::WithdrawLiquidity {
-            pair_msg: PairCw20HookMsg::WithdrawLiquidity { assets: vec![] },
+            pair_msg: PairCw20HookMsg::WithdrawLiquidity {
+                assets: vec![],
+                deadline: None,
+            },
             min_assets_to_receive: vec![
                 native_asset_info("uusd".to_string()).with_balance(100000u128),
                 token_asset_info(Addr::unchecked("wasm1...cw20address".to_string()))
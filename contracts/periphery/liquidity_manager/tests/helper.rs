@@ -310,6 +310,7 @@ impl Helper {
                 generator_address: Some(generator.to_string()),
                 whitelist_code_id: None,
                 coin_registry_address: None,
+                max_combined_fee_bps: None,
             },
             &[],
         )
@@ -370,6 +371,8 @@ impl Helper {
             slippage_tolerance,
             auto_stake: None,
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         };
 
         self.app
@@ -429,6 +432,8 @@ impl Helper {
             slippage_tolerance,
             auto_stake: Some(auto_stake),
             receiver,
+            deadline: None,
+            min_lp_to_receive: None,
         };
 
         if min_lp_receive.is_some() {
@@ -464,13 +469,17 @@ impl Helper {
         amount: u128,
         min_assets: Option<Vec<Asset>>,
     ) -> AnyResult<AppResponse> {
-        let pair_msg = PairCw20HookMsg::WithdrawLiquidity { assets: vec![] };
+        let pair_msg = PairCw20HookMsg::WithdrawLiquidity {
+            assets: vec![],
+            deadline: None,
+        };
         let (contract, msg);
         if let Some(min_assets_to_receive) = min_assets {
             contract = self.liquidity_manager.to_string();
             msg = to_binary(&Cw20HookMsg::WithdrawLiquidity {
                 pair_msg,
                 min_assets_to_receive,
+                deadline: None,
             })
             .unwrap();
         } else {
@@ -503,7 +512,10 @@ impl Helper {
                         ask_asset_info: None,
                         belief_price: None,
                         max_spread,
+                        min_receive: None,
                         to: None,
+                        deadline: None,
+                        referral: None,
                     })
                     .unwrap(),
                 };
@@ -524,7 +536,10 @@ impl Helper {
                     ask_asset_info: None,
                     belief_price: None,
                     max_spread,
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 };
 
                 self.app
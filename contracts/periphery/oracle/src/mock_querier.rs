@@ -107,6 +107,9 @@ impl WasmMockQuerier {
                                 contract_addr: Addr::unchecked("pair"),
                                 liquidity_token: Addr::unchecked("lp_token"),
                                 pair_type: PairType::Xyk {},
+                                creator: None,
+                                created_at: None,
+                                allowlist: None,
                             })
                             .into(),
                         ),
@@ -320,6 +320,8 @@ fn provide_liquidity(
             slippage_tolerance: None,
             auto_stake: None,
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         },
         &funds,
     )
@@ -437,6 +439,8 @@ fn change_provide_liquidity(
                 slippage_tolerance: Some(Decimal::percent(50)),
                 auto_stake: None,
                 receiver: None,
+                deadline: None,
+                min_lp_to_receive: None,
             },
             &vec![],
         )
@@ -3,17 +3,19 @@ use std::cmp::Ordering;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
+    attr, to_binary, to_vec, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use sha2::{Digest, Sha256};
 
 use gridiron::asset::{addr_opt_validate, validate_native_denom, Asset, AssetInfo};
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 
 use gridiron::shared_multisig::{
-    Config, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, MultisigRole, PoolType,
-    ProvideParams, QueryMsg, DEFAULT_WEIGHT, TOTAL_WEIGHT,
+    Config, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, MsgSimulationStatus,
+    MultisigRole, PoolType, ProposalHashPayload, ProposalHashResponse, ProvideParams, QueryMsg,
+    SimulateProposalResponse, DEFAULT_WEIGHT, TOTAL_WEIGHT,
 };
 
 use gridiron::generator::{
@@ -21,7 +23,7 @@ use gridiron::generator::{
 };
 
 use gridiron::querier::{query_balance, query_token_balance};
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw3::{
     Proposal, ProposalListResponse, ProposalResponse, Status, Vote, VoteInfo, VoteListResponse,
     VoteResponse, Votes,
@@ -36,7 +38,7 @@ use crate::state::{
 };
 use crate::utils::{
     check_generator_deposit, check_pool, check_provide_assets, get_pool_info,
-    prepare_provide_after_withdraw_msg, prepare_provide_msg, prepare_withdraw_msg,
+    prepare_provide_after_withdraw_msg, prepare_provide_msg, prepare_withdraw_msg, simulate_msgs,
 };
 
 // version info for migration info
@@ -625,6 +627,10 @@ pub fn execute_propose(
         return Err(ContractError::Unauthorized {});
     }
 
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyProposal {});
+    }
+
     // max expires also used as default
     let max_expires = cfg.max_voting_period.after(&env.block);
     let mut expires = latest.unwrap_or(max_expires);
@@ -930,8 +936,24 @@ pub fn end_target_pool_migration(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Err(ContractError::MigrationError {})
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        "gridiron-shared-multisig" => match contract_version.version.as_ref() {
+            "1.0.0" | "1.1.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    };
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -948,6 +970,17 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             limit,
         } => to_binary(&reverse_proposals(deps, env, start_before, limit)?),
         QueryMsg::ListVotes { proposal_id } => to_binary(&list_votes(deps, proposal_id)?),
+        QueryMsg::SimulateProposal { proposal_id } => {
+            to_binary(&query_simulate_proposal(deps, env, proposal_id)?)
+        }
+        QueryMsg::ProposalHash { proposal_id } => {
+            to_binary(&query_proposal_hash(deps, env, proposal_id)?)
+        }
+        QueryMsg::ExpiringProposals {
+            within,
+            start_after,
+            limit,
+        } => to_binary(&expiring_proposals(deps, env, within, start_after, limit)?),
     }
 }
 
@@ -986,6 +1019,80 @@ fn query_proposal(deps: Deps, env: Env, id: u64) -> StdResult<ProposalResponse>
     })
 }
 
+fn query_simulate_proposal(
+    deps: Deps,
+    env: Env,
+    proposal_id: u64,
+) -> StdResult<SimulateProposalResponse> {
+    let prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    let results = simulate_msgs(&deps.querier, &env.contract.address, &prop.msgs)?;
+    let success = results
+        .iter()
+        .all(|res| !matches!(res.status, MsgSimulationStatus::WouldFail { .. }));
+
+    Ok(SimulateProposalResponse {
+        proposal_id,
+        success,
+        results,
+    })
+}
+
+/// Returns the SHA-256 hash of the canonical JSON serialization of a proposal along with the
+/// serialized payload, so it can be recomputed offline.
+fn query_proposal_hash(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalHashResponse> {
+    let prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    let payload = to_vec(&ProposalHashPayload {
+        chain_id: env.block.chain_id,
+        contract: env.contract.address.to_string(),
+        proposal_id,
+        title: prop.title,
+        description: prop.description,
+        msgs: prop.msgs,
+        expires: prop.expires,
+    })?;
+
+    Ok(ProposalHashResponse {
+        proposal_id,
+        hash: hex::encode(Sha256::digest(&payload)),
+        payload: payload.into(),
+    })
+}
+
+/// Returns open and passed proposals which are not expired yet but expire within the specified
+/// duration from now. Proposals with an expiration of another kind than `within` are skipped.
+fn expiring_proposals(
+    deps: Deps,
+    env: Env,
+    within: Duration,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let deadline = within.after(&env.block);
+
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, prop)) => {
+                matches!(
+                    prop.current_status(&env.block),
+                    Status::Open | Status::Passed
+                ) && !prop.expires.is_expired(&env.block)
+                    && matches!(
+                        prop.expires.partial_cmp(&deadline),
+                        Some(Ordering::Less | Ordering::Equal)
+                    )
+            }
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|p| map_proposal(&env.block, p))
+        .collect::<StdResult<_>>()?;
+
+    Ok(ProposalListResponse { proposals })
+}
+
 fn list_proposals(
     deps: Deps,
     env: Env,
@@ -89,6 +89,9 @@ pub enum ContractError {
 
     #[error("Claim all rewards from the generator before migrating the target pool")]
     ClaimAmountError {},
+
+    #[error("Proposal must contain at least one message")]
+    EmptyProposal {},
 }
 
 impl From<OverflowError> for ContractError {
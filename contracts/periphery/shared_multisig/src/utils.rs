@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::ContractError;
 use gridiron::asset::{Asset, AssetInfo, PairInfo};
 use gridiron::pair::ExecuteMsg as PairExecuteMsg;
@@ -6,10 +8,12 @@ use gridiron::pair::{Cw20HookMsg as PairCw20HookMsg, QueryMsg as PairQueryMsg};
 use gridiron::factory::PairType;
 use gridiron::generator::QueryMsg as GeneratorQueryMsg;
 use gridiron::querier::{query_balance, query_pair_info, query_token_balance};
-use gridiron::shared_multisig::{Config, PoolType, ProvideParams};
+use gridiron::shared_multisig::{
+    Config, MsgSimulationResult, MsgSimulationStatus, PoolType, ProvideParams,
+};
 use cosmwasm_std::{
-    attr, to_binary, Addr, Attribute, CosmosMsg, Decimal, QuerierWrapper, StdError, StdResult,
-    Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal,
+    QuerierWrapper, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 use itertools::Itertools;
@@ -65,7 +69,10 @@ pub(crate) fn prepare_withdraw_msg(
             contract_addr: lp_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Send {
                 contract: pair.to_string(),
-                msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+                msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity {
+                    assets: vec![],
+                    deadline: None,
+                })?,
                 amount: burn_amount,
             })?,
             funds: vec![],
@@ -91,6 +98,8 @@ pub(crate) fn prepare_provide_msg(
             slippage_tolerance,
             auto_stake,
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         })?,
     }))
 }
@@ -206,3 +215,135 @@ pub(crate) fn check_generator_deposit(
 
     Ok(())
 }
+
+/// Tracks the Multisig balances while proposal messages are being simulated one by one.
+struct SimulatedBalances<'a> {
+    querier: &'a QuerierWrapper<'a>,
+    account: &'a Addr,
+    native: HashMap<String, Uint128>,
+    cw20: HashMap<String, Uint128>,
+}
+
+impl<'a> SimulatedBalances<'a> {
+    fn new(querier: &'a QuerierWrapper<'a>, account: &'a Addr) -> Self {
+        Self {
+            querier,
+            account,
+            native: HashMap::new(),
+            cw20: HashMap::new(),
+        }
+    }
+
+    fn spend_native(&mut self, coins: &[Coin]) -> StdResult<MsgSimulationStatus> {
+        for coin in coins {
+            let balance = match self.native.get(&coin.denom) {
+                Some(balance) => *balance,
+                None => query_balance(self.querier, self.account, &coin.denom)?,
+            };
+
+            if coin.amount > balance {
+                return Ok(MsgSimulationStatus::WouldFail {
+                    reason: format!("Insufficient {} balance: {}", coin.denom, balance),
+                });
+            }
+
+            self.native
+                .insert(coin.denom.clone(), balance.checked_sub(coin.amount)?);
+        }
+
+        Ok(MsgSimulationStatus::Ok)
+    }
+
+    fn spend_cw20(&mut self, token: &str, amount: Uint128) -> StdResult<MsgSimulationStatus> {
+        let balance = match self.cw20.get(token) {
+            Some(balance) => *balance,
+            None => query_token_balance(self.querier, token, self.account)?,
+        };
+
+        if amount > balance {
+            return Ok(MsgSimulationStatus::WouldFail {
+                reason: format!("Insufficient {} balance: {}", token, balance),
+            });
+        }
+
+        self.cw20
+            .insert(token.to_string(), balance.checked_sub(amount)?);
+
+        Ok(MsgSimulationStatus::Ok)
+    }
+
+    fn check_admin(&self, contract_addr: &str) -> MsgSimulationStatus {
+        match self.querier.query_wasm_contract_info(contract_addr) {
+            Ok(info) if info.admin.as_deref() == Some(self.account.as_str()) => {
+                MsgSimulationStatus::Ok
+            }
+            Ok(_) => MsgSimulationStatus::WouldFail {
+                reason: format!("Multisig is not an admin of {}", contract_addr),
+            },
+            Err(_) => MsgSimulationStatus::WouldFail {
+                reason: format!("Contract {} not found", contract_addr),
+            },
+        }
+    }
+
+    fn simulate(&mut self, msg: &CosmosMsg) -> StdResult<MsgSimulationStatus> {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. })
+            | CosmosMsg::Bank(BankMsg::Burn { amount }) => self.spend_native(amount),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                if self
+                    .querier
+                    .query_wasm_contract_info(contract_addr)
+                    .is_err()
+                {
+                    return Ok(MsgSimulationStatus::WouldFail {
+                        reason: format!("Contract {} not found", contract_addr),
+                    });
+                }
+
+                let status = self.spend_native(funds)?;
+                if status != MsgSimulationStatus::Ok {
+                    return Ok(status);
+                }
+
+                match from_binary(msg) {
+                    Ok(Cw20ExecuteMsg::Transfer { amount, .. })
+                    | Ok(Cw20ExecuteMsg::Send { amount, .. })
+                    | Ok(Cw20ExecuteMsg::Burn { amount }) => self.spend_cw20(contract_addr, amount),
+                    _ => Ok(MsgSimulationStatus::Ok),
+                }
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => self.spend_native(funds),
+            CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. })
+            | CosmosMsg::Wasm(WasmMsg::UpdateAdmin { contract_addr, .. })
+            | CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) => {
+                Ok(self.check_admin(contract_addr))
+            }
+            _ => Ok(MsgSimulationStatus::Unknown),
+        }
+    }
+}
+
+/// Dry-runs the messages against the current state using the querier.
+/// Balances spent by previous messages in the batch are taken into account.
+pub(crate) fn simulate_msgs(
+    querier: &QuerierWrapper,
+    account: &Addr,
+    msgs: &[CosmosMsg],
+) -> StdResult<Vec<MsgSimulationResult>> {
+    let mut balances = SimulatedBalances::new(querier, account);
+
+    msgs.iter()
+        .enumerate()
+        .map(|(index, msg)| {
+            Ok(MsgSimulationResult {
+                index: index as u32,
+                status: balances.simulate(msg)?,
+            })
+        })
+        .collect()
+}
@@ -2,15 +2,25 @@
 
 use gridiron::asset::{Asset, AssetInfo};
 use gridiron::generator::PendingTokenResponse;
-use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Empty,
+    Env, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::{set_contract_version, ContractVersion};
 use cw20::Cw20ExecuteMsg;
 use cw3::{Status, Vote, VoteInfo, VoteListResponse, VoteResponse};
-use cw_utils::{Duration, ThresholdResponse};
+use cw_utils::{Duration, Expiration, ThresholdResponse};
+use sha2::{Digest, Sha256};
 use std::{cell::RefCell, rc::Rc};
 
-use gridiron::shared_multisig::{ExecuteMsg, PoolType, ProvideParams};
+use gridiron::shared_multisig::{
+    ExecuteMsg, MigrateMsg, MsgSimulationResult, MsgSimulationStatus, PoolType,
+    ProposalHashPayload, ProvideParams,
+};
+use gridiron_shared_multisig::contract::{execute, instantiate, migrate, query};
+use gridiron_shared_multisig::ContractError;
 
-use gridiron_mocks::cw_multi_test::{App, Executor};
+use gridiron_mocks::cw_multi_test::{App, ContractWrapper, Executor};
 use gridiron_mocks::shared_multisig::MockSharedMultisigBuilder;
 use gridiron_mocks::{gridiron_address, MockFactoryBuilder, MockGeneratorBuilder};
 
@@ -507,6 +517,233 @@ fn test_proposal() {
     assert_eq!(res.migration_pool, Some(pcl.address));
 }
 
+#[test]
+fn test_simulate_proposal() {
+    let manager1 = Addr::unchecked(MANAGER1);
+    let manager2 = Addr::unchecked(MANAGER2);
+    let gridiron = gridiron_address();
+
+    let router = Rc::new(RefCell::new(mock_app(
+        &gridiron,
+        Some(vec![Coin {
+            denom: String::from("untrn"),
+            amount: Uint128::new(100_000_000_000u128),
+        }]),
+    )));
+
+    let factory = MockFactoryBuilder::new(&router).instantiate();
+    let shared_multisig =
+        MockSharedMultisigBuilder::new(&router).instantiate(&factory.address, None, None);
+
+    // Empty batches are not allowed
+    let err = shared_multisig.propose(&manager1, vec![]).unwrap_err();
+    assert_eq!(
+        "Proposal must contain at least one message",
+        err.root_cause().to_string()
+    );
+
+    shared_multisig
+        .send_tokens(
+            &gridiron,
+            Some(vec![Coin {
+                denom: String::from("untrn"),
+                amount: Uint128::new(1_000u128),
+            }]),
+            None,
+        )
+        .unwrap();
+
+    let send_msg = |amount: u128| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: manager2.to_string(),
+            amount: vec![Coin {
+                denom: String::from("untrn"),
+                amount: Uint128::new(amount),
+            }],
+        })
+    };
+    let setup_pools_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: shared_multisig.address.to_string(),
+        msg: to_binary(&ExecuteMsg::SetupPools {
+            target_pool: None,
+            migration_pool: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let unknown_contract_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "unknown".to_string(),
+        msg: to_binary(&ExecuteMsg::StartRageQuit {}).unwrap(),
+        funds: vec![],
+    });
+
+    // The second transfer exceeds the balance left after the first one
+    shared_multisig
+        .propose(
+            &manager1,
+            vec![
+                send_msg(600),
+                setup_pools_msg,
+                send_msg(600),
+                unknown_contract_msg,
+            ],
+        )
+        .unwrap();
+
+    let res = shared_multisig.query_simulate_proposal(1).unwrap();
+    assert!(!res.success);
+    assert_eq!(
+        res.results,
+        vec![
+            MsgSimulationResult {
+                index: 0,
+                status: MsgSimulationStatus::Ok
+            },
+            MsgSimulationResult {
+                index: 1,
+                status: MsgSimulationStatus::Ok
+            },
+            MsgSimulationResult {
+                index: 2,
+                status: MsgSimulationStatus::WouldFail {
+                    reason: "Insufficient untrn balance: 400".to_string()
+                }
+            },
+            MsgSimulationResult {
+                index: 3,
+                status: MsgSimulationStatus::WouldFail {
+                    reason: "Contract unknown not found".to_string()
+                }
+            },
+        ]
+    );
+
+    shared_multisig
+        .propose(&manager1, vec![send_msg(500), send_msg(500)])
+        .unwrap();
+    let res = shared_multisig.query_simulate_proposal(2).unwrap();
+    assert!(res.success);
+
+    shared_multisig.vote(&manager2, 2, Vote::Yes).unwrap();
+    shared_multisig.execute(&manager1, 2).unwrap();
+
+    // Nothing left to send
+    let res = shared_multisig.query_simulate_proposal(2).unwrap();
+    assert!(!res.success);
+}
+
+#[test]
+fn test_proposal_hash_and_expiring_proposals() {
+    let manager1 = Addr::unchecked(MANAGER1);
+    let manager2 = Addr::unchecked(MANAGER2);
+
+    let gridiron = gridiron_address();
+
+    let router = Rc::new(RefCell::new(mock_app(
+        &gridiron,
+        Some(vec![Coin {
+            denom: String::from("untrn"),
+            amount: Uint128::new(100_000_000_000u128),
+        }]),
+    )));
+
+    let factory = MockFactoryBuilder::new(&router).instantiate();
+    let shared_multisig =
+        MockSharedMultisigBuilder::new(&router).instantiate(&factory.address, None, None);
+    shared_multisig
+        .send_tokens(
+            &gridiron,
+            Some(vec![Coin {
+                denom: String::from("untrn"),
+                amount: Uint128::new(1_000u128),
+            }]),
+            None,
+        )
+        .unwrap();
+
+    let send_msg = |amount: u128| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: manager2.to_string(),
+            amount: vec![Coin {
+                denom: String::from("untrn"),
+                amount: Uint128::new(amount),
+            }],
+        })
+    };
+
+    shared_multisig
+        .propose(&manager1, vec![send_msg(100)])
+        .unwrap();
+    let block = router.borrow().block_info();
+    router.borrow_mut().update_block(|b| b.height += 1);
+    shared_multisig
+        .propose(&manager1, vec![send_msg(200)])
+        .unwrap();
+
+    // The hash can be recomputed from the canonical payload
+    let res = shared_multisig.query_proposal_hash(1).unwrap();
+    assert_eq!(
+        res.hash,
+        hex::encode(Sha256::digest(res.payload.as_slice()))
+    );
+    assert_eq!(
+        from_binary::<ProposalHashPayload>(&res.payload).unwrap(),
+        ProposalHashPayload {
+            chain_id: block.chain_id,
+            contract: shared_multisig.address.to_string(),
+            proposal_id: 1,
+            title: "Create a new proposal".to_string(),
+            description: "Create a new proposal".to_string(),
+            msgs: vec![send_msg(100)],
+            expires: Expiration::AtHeight(block.height + 3),
+        }
+    );
+    assert_ne!(
+        res.hash,
+        shared_multisig.query_proposal_hash(2).unwrap().hash
+    );
+
+    // Only the first proposal expires within the next 2 blocks
+    let res = shared_multisig
+        .query_expiring_proposals(Duration::Height(2))
+        .unwrap();
+    assert_eq!(
+        res.proposals.iter().map(|p| p.id).collect::<Vec<_>>(),
+        vec![1]
+    );
+    let res = shared_multisig
+        .query_expiring_proposals(Duration::Height(3))
+        .unwrap();
+    assert_eq!(
+        res.proposals.iter().map(|p| p.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    // Proposals expiring at a specific time are not comparable with heights
+    let res = shared_multisig
+        .query_expiring_proposals(Duration::Time(86400))
+        .unwrap();
+    assert!(res.proposals.is_empty());
+
+    // Executed proposals are skipped
+    shared_multisig.vote(&manager2, 1, Vote::Yes).unwrap();
+    shared_multisig.execute(&manager1, 1).unwrap();
+    let res = shared_multisig
+        .query_expiring_proposals(Duration::Height(3))
+        .unwrap();
+    assert_eq!(
+        res.proposals.iter().map(|p| p.id).collect::<Vec<_>>(),
+        vec![2]
+    );
+
+    // Expired proposals are skipped as well
+    router.borrow_mut().update_block(|b| b.height += 3);
+    let res = shared_multisig
+        .query_expiring_proposals(Duration::Height(3))
+        .unwrap();
+    assert!(res.proposals.is_empty());
+}
+
 #[test]
 fn test_transfer() {
     let manager1 = Addr::unchecked(MANAGER1);
@@ -2289,3 +2526,83 @@ fn test_autostake_and_withdraw() {
         Uint128::zero(),
     );
 }
+
+fn set_version_v100(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    set_contract_version(deps.storage, "gridiron-shared-multisig", "1.0.0")?;
+    Ok(Response::new())
+}
+
+#[test]
+fn migrate_from_v100() {
+    let router = Rc::new(RefCell::new(App::default()));
+    let gridiron = gridiron_address();
+
+    let factory = MockFactoryBuilder::new(&router).instantiate();
+    let shared_multisig =
+        MockSharedMultisigBuilder::new(&router).instantiate(&factory.address, None, None);
+
+    let downgrade_code_id = router.borrow_mut().store_code(Box::new(
+        ContractWrapper::new_with_empty(execute, instantiate, query)
+            .with_migrate_empty(set_version_v100),
+    ));
+    let code_id = router.borrow_mut().store_code(Box::new(
+        ContractWrapper::new_with_empty(execute, instantiate, query).with_migrate(migrate),
+    ));
+
+    router
+        .borrow_mut()
+        .migrate_contract(
+            gridiron.clone(),
+            shared_multisig.address.clone(),
+            &Empty {},
+            downgrade_code_id,
+        )
+        .unwrap();
+
+    router
+        .borrow_mut()
+        .migrate_contract(
+            gridiron.clone(),
+            shared_multisig.address.clone(),
+            &MigrateMsg {},
+            code_id,
+        )
+        .unwrap();
+
+    let version: ContractVersion = from_slice(
+        &router
+            .borrow()
+            .wrap()
+            .query_wasm_raw(&shared_multisig.address, b"contract_info")
+            .unwrap()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        version,
+        ContractVersion {
+            contract: "gridiron-shared-multisig".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
+
+    // The state is preserved
+    let config_res = shared_multisig.query_config().unwrap();
+    assert_eq!(Addr::unchecked(MANAGER1), config_res.manager1);
+    assert_eq!(Addr::unchecked(MANAGER2), config_res.manager2);
+
+    // The current version can't be migrated again
+    let err = router
+        .borrow_mut()
+        .migrate_contract(
+            gridiron,
+            shared_multisig.address.clone(),
+            &MigrateMsg {},
+            code_id,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::MigrationError {}.to_string()
+    );
+}
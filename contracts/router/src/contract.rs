@@ -1,21 +1,31 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, wasm_execute, Addr, Api, Binary, Decimal, Deps, DepsMut,
-    Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128,
+    attr, entry_point, from_binary, to_binary, wasm_execute, Addr, Api, Binary, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
 
-use gridiron::asset::{addr_opt_validate, Asset, AssetInfo};
-use gridiron::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
+use gridiron::pair::{QueryMsg as PairQueryMsg, SimulationResponse, MAX_ALLOWED_SLIPPAGE};
 use gridiron::querier::query_pair_info;
 use gridiron::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, SwapResponseData, MAX_SWAP_OPERATIONS,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OptimizeSplitResponse,
+    QueryMsg, SimulateSwapOperationsResponse, SplitAllocation, SwapOperation, SwapResponseData,
+    MAX_SPLIT_PARTS, MAX_SPLIT_POOLS, MAX_SWAP_OPERATIONS,
 };
 
+use crate::dust::{refund_intermediate_balances, sweep_dust};
 use crate::error::ContractError;
+use crate::escrow::{
+    authorize_order, cancel_order, deposit_escrow, execute_order, execute_order_reply,
+    execute_orders, increase_escrow, query_escrow, withdraw_escrow, EXECUTE_ORDER_REPLY_ID,
+};
 use crate::operations::execute_swap_operation;
-use crate::state::{Config, ReplyData, CONFIG, REPLY_DATA};
+use crate::relayer::{approve_relayer, query_relayer_approval, resolve_trader, revoke_relayer};
+use crate::state::{Config, ReplyData, CONFIG, ORDERS, REPLY_DATA};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "gridiron-router";
@@ -53,8 +63,14 @@ pub fn instantiate(
 /// * **ExecuteMsg::ExecuteSwapOperations {
 ///             operations,
 ///             minimum_receive,
-///             to
+///             to,
+///             max_spread,
+///             max_intermediate_spread_bps,
+///             deadline,
+///             trader,
+///             fee_payer_memo
 ///         }** Performs swap operations with the specified parameters.
+/// A relayer approved by the trader can execute them on behalf of the trader.
 ///
 /// * **ExecuteMsg::ExecuteSwapOperation { operation, to }** Execute a single swap operation.
 ///
@@ -64,6 +80,33 @@ pub fn instantiate(
 ///             minimum_receive,
 ///             receiver
 ///         }** Checks if an ask amount is higher than or equal to the minimum amount to receive.
+///
+/// * **ExecuteMsg::DepositEscrow {}** Adds the attached native tokens to the sender's escrow balance.
+///
+/// * **ExecuteMsg::WithdrawEscrow { asset }** Withdraws tokens from the sender's escrow balance.
+///
+/// * **ExecuteMsg::AuthorizeOrder {
+///             offer,
+///             operations,
+///             minimum_receive,
+///             to,
+///             max_spread,
+///             deadline
+///         }** Authorizes keepers to swap tokens from the sender's escrow balance.
+///
+/// * **ExecuteMsg::CancelOrder { order_id }** Cancels an order of the sender.
+///
+/// * **ExecuteMsg::ExecuteOrders { order_ids }** Executes a batch of orders isolating failures per order.
+///
+/// * **ExecuteMsg::ExecuteOrder { order_id }** Executes a single order.
+///
+/// * **ExecuteMsg::ApproveRelayer { relayer, expires }** Allows a relayer to execute swap
+/// operations on behalf of the sender.
+///
+/// * **ExecuteMsg::RevokeRelayer { relayer }** Removes the approval of a relayer.
+///
+/// * **ExecuteMsg::SweepDust { assets, to_maker }** Sends the dust balances of the router to the
+/// Maker or to the sender.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -72,36 +115,80 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ExecuteSwapOperations {
             operations,
             minimum_receive,
             to,
             max_spread,
-        } => execute_swap_operations(
+            max_intermediate_spread_bps,
+            deadline,
+            trader,
+            fee_payer_memo,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            let (trader, attrs) =
+                resolve_trader(deps.as_ref(), &env, info.sender, trader, fee_payer_memo)?;
+
+            Ok(execute_swap_operations(
+                deps,
+                env,
+                trader,
+                operations,
+                minimum_receive,
+                to,
+                max_spread,
+                max_intermediate_spread_bps,
+            )?
+            .add_attributes(attrs))
+        }
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            to,
+            max_spread,
+            single,
+        } => execute_swap_operation(deps, env, info, operation, to, max_spread, single),
+        ExecuteMsg::DepositEscrow {} => deposit_escrow(deps, info),
+        ExecuteMsg::WithdrawEscrow { asset } => withdraw_escrow(deps, info, asset),
+        ExecuteMsg::AuthorizeOrder {
+            offer,
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+            deadline,
+        } => authorize_order(
             deps,
             env,
-            info.sender,
+            info,
+            offer,
             operations,
             minimum_receive,
             to,
             max_spread,
+            deadline,
         ),
-        ExecuteMsg::ExecuteSwapOperation {
-            operation,
-            to,
-            max_spread,
-            single,
-        } => execute_swap_operation(deps, env, info, operation, to, max_spread, single),
+        ExecuteMsg::CancelOrder { order_id } => cancel_order(deps, info, order_id),
+        ExecuteMsg::ExecuteOrders { order_ids } => execute_orders(deps, env, order_ids),
+        ExecuteMsg::ExecuteOrder { order_id } => execute_order(deps, env, info, order_id),
+        ExecuteMsg::ApproveRelayer { relayer, expires } => {
+            approve_relayer(deps, env, info, relayer, expires)
+        }
+        ExecuteMsg::RevokeRelayer { relayer } => revoke_relayer(deps, info, relayer),
+        ExecuteMsg::SweepDust { assets, to_maker } => sweep_dust(deps, env, info, assets, to_maker),
     }
 }
 
 /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// A route started this way doesn't need an allowance: the received tokens are offered in the first
+/// swap and intermediate CW20 hops are sent to the pairs from the router balance.
 ///
 /// * **cw20_msg** is an object of type [`Cw20ReceiveMsg`].
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     match from_binary(&cw20_msg.msg)? {
@@ -110,15 +197,64 @@ pub fn receive_cw20(
             minimum_receive,
             to,
             max_spread,
-        } => execute_swap_operations(
-            deps,
-            env,
-            Addr::unchecked(cw20_msg.sender),
-            operations,
-            minimum_receive,
-            to,
-            max_spread,
-        ),
+            max_intermediate_spread_bps,
+            deadline,
+            trader,
+            fee_payer_memo,
+        } => {
+            assert_deadline(&env, deadline)?;
+
+            // The route must start with the received token
+            if let Some(first_operation) = operations.first() {
+                let offer_asset_info = first_operation.get_offer_asset_info();
+                let received_asset_info = AssetInfo::Token {
+                    contract_addr: info.sender,
+                };
+
+                if !offer_asset_info.equal(&received_asset_info) {
+                    return Err(ContractError::ReceivedAssetMismatch {
+                        received: received_asset_info.to_string(),
+                        offered: offer_asset_info.to_string(),
+                    });
+                }
+            }
+
+            let (trader, attrs) = resolve_trader(
+                deps.as_ref(),
+                &env,
+                Addr::unchecked(cw20_msg.sender),
+                trader,
+                fee_payer_memo,
+            )?;
+
+            Ok(execute_swap_operations(
+                deps,
+                env,
+                trader,
+                operations,
+                minimum_receive,
+                to,
+                max_spread,
+                max_intermediate_spread_bps,
+            )?
+            .add_attributes(attrs))
+        }
+        Cw20HookMsg::DepositEscrow {} => {
+            let user = deps.api.addr_validate(&cw20_msg.sender)?;
+            let asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            };
+            increase_escrow(deps.storage, &user, &asset)?;
+
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "deposit_escrow"),
+                attr("user", user),
+                attr("deposit", asset.to_string()),
+            ]))
+        }
     }
 }
 
@@ -131,6 +267,8 @@ pub fn receive_cw20(
 /// * **minimum_receive** used to guarantee that the ask amount is above a minimum amount.
 ///
 /// * **to** recipient of the ask tokens.
+///
+/// * **max_intermediate_spread_bps** max spread enforced on every hop but the last one.
 #[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
@@ -140,13 +278,33 @@ pub fn execute_swap_operations(
     minimum_receive: Option<Uint128>,
     to: Option<String>,
     max_spread: Option<Decimal>,
+    max_intermediate_spread_bps: Option<u16>,
 ) -> Result<Response, ContractError> {
     assert_operations(deps.api, &operations)?;
 
-    let to = addr_opt_validate(deps.api, &to)?.unwrap_or(sender);
+    let max_intermediate_spread = max_intermediate_spread_bps
+        .map(|bps| {
+            let max_intermediate_spread = Decimal::from_ratio(bps, 10000u16);
+            if max_intermediate_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+                return Err(ContractError::InvalidMaxIntermediateSpread {});
+            }
+            Ok(max_intermediate_spread)
+        })
+        .transpose()?;
+
+    let to = addr_opt_validate(deps.api, &to)?.unwrap_or_else(|| sender.clone());
     let target_asset_info = operations.last().unwrap().get_target_asset_info();
     let operations_len = operations.len();
 
+    // The assets received by the router before the last hop. Their leftovers are refunded
+    let mut intermediate_assets: Vec<AssetInfo> = vec![];
+    for op in &operations[..operations_len - 1] {
+        let asset_info = op.get_target_asset_info();
+        if !intermediate_assets.contains(&asset_info) {
+            intermediate_assets.push(asset_info);
+        }
+    }
+
     let messages = operations
         .into_iter()
         .enumerate()
@@ -164,13 +322,20 @@ pub fn execute_swap_operations(
                 )
                 .map(|inner_msg| SubMsg::reply_on_success(inner_msg, AFTER_SWAP_REPLY_ID))
             } else {
+                // Intermediate hops assert their own spread only if the limit is set,
+                // otherwise the whole route is checked against minimum_receive
+                let (max_spread, single) = match max_intermediate_spread {
+                    Some(max_intermediate_spread) => (Some(max_intermediate_spread), true),
+                    None => (max_spread, false),
+                };
+
                 wasm_execute(
                     env.contract.address.to_string(),
                     &ExecuteMsg::ExecuteSwapOperation {
                         operation: op,
                         to: None,
                         max_spread,
-                        single: operations_len == 1,
+                        single,
                     },
                     vec![],
                 )
@@ -187,6 +352,8 @@ pub fn execute_swap_operations(
             prev_balance,
             minimum_receive,
             receiver: to.to_string(),
+            refund_to: sender,
+            intermediate_assets,
         },
     )?;
 
@@ -194,7 +361,7 @@ pub fn execute_swap_operations(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: AFTER_SWAP_REPLY_ID,
@@ -215,13 +382,27 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 }
             }
 
+            let (refund_msgs, refund_attrs) = refund_intermediate_balances(
+                deps.as_ref(),
+                &env,
+                &reply_data.intermediate_assets,
+                &reply_data.refund_to,
+            )?;
+
             // Reply data makes sense ONLY if the first token in multi-hop swap is native.
             let data = to_binary(&SwapResponseData {
                 return_amount: swap_amount,
             })?;
 
-            Ok(Response::new().set_data(data))
+            Ok(Response::new()
+                .add_messages(refund_msgs)
+                .add_attributes(refund_attrs)
+                .set_data(data))
         }
+        Reply {
+            id: EXECUTE_ORDER_REPLY_ID,
+            result,
+        } => execute_order_reply(deps, result),
         _ => Err(StdError::generic_err("Failed to process reply").into()),
     }
 }
@@ -233,6 +414,9 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             offer_amount,
 ///             operations,
 ///         }** Simulates one or multiple swap operations and returns the end result in a [`SimulateSwapOperationsResponse`] object.
+/// * **QueryMsg::Escrow { user, asset_info }** Returns the escrow balance of a user.
+/// * **QueryMsg::Order { order_id }** Returns an order using an [`Order`] object.
+/// * **QueryMsg::RelayerApproval { trader, relayer }** Returns the approval of a relayer by a trader.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -245,6 +429,20 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             offer_amount,
             operations,
         )?)?),
+        QueryMsg::OptimizeSplit {
+            offer,
+            pools,
+            parts,
+        } => Ok(to_binary(&optimize_split(deps, offer, pools, parts)?)?),
+        QueryMsg::Escrow { user, asset_info } => Ok(to_binary(&query_escrow(
+            deps.storage,
+            &deps.api.addr_validate(&user)?,
+            &asset_info,
+        )?)?),
+        QueryMsg::Order { order_id } => Ok(to_binary(&ORDERS.load(deps.storage, order_id)?)?),
+        QueryMsg::RelayerApproval { trader, relayer } => {
+            Ok(to_binary(&query_relayer_approval(deps, trader, relayer)?)?)
+        }
     }
 }
 
@@ -266,7 +464,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     match contract_version.contract.as_ref() {
         "gridiron-router" => match contract_version.version.as_ref() {
-            "1.1.1" => {}
+            "1.1.1" | "1.2.0" | "1.2.1" | "1.3.0" | "1.4.0" | "1.5.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -335,10 +533,147 @@ fn simulate_swap_operations(
     })
 }
 
+/// Splits an offer across several pools holding the same assets.
+/// The offer is divided into `parts` chunks and each chunk is assigned to the pool with the best
+/// marginal return given the chunks it already received, so price impact is taken into account.
+///
+/// * **offer** is the asset and amount to swap.
+///
+/// * **pools** is the list of candidate pair contracts.
+///
+/// * **parts** is the number of chunks the offer is divided into.
+fn optimize_split(
+    deps: Deps,
+    offer: Asset,
+    pools: Vec<String>,
+    parts: u8,
+) -> Result<OptimizeSplitResponse, ContractError> {
+    if parts == 0 || parts > MAX_SPLIT_PARTS {
+        return Err(ContractError::InvalidSplitParts {});
+    }
+
+    let mut uniq = HashSet::new();
+    if pools.is_empty()
+        || pools.len() > MAX_SPLIT_POOLS
+        || !pools.iter().all(|pool| uniq.insert(pool))
+    {
+        return Err(ContractError::InvalidSplitPools {});
+    }
+
+    offer.info.check(deps.api)?;
+
+    let pools = pools
+        .iter()
+        .map(|pool| deps.api.addr_validate(pool))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // All pools must trade the offered asset against the same set of assets
+    let mut asset_infos: Option<Vec<AssetInfo>> = None;
+    for pool in &pools {
+        let pair_info: PairInfo = deps
+            .querier
+            .query_wasm_smart(pool, &PairQueryMsg::Pair {})?;
+        let same_assets = asset_infos.as_ref().map_or(true, |infos| {
+            infos.len() == pair_info.asset_infos.len()
+                && infos
+                    .iter()
+                    .all(|info| pair_info.asset_infos.contains(info))
+        });
+        if !same_assets || !pair_info.asset_infos.contains(&offer.info) {
+            return Err(ContractError::PoolAssetsMismatch {
+                pool: pool.to_string(),
+            });
+        }
+        asset_infos.get_or_insert(pair_info.asset_infos);
+    }
+
+    // Pools which fail to simulate the amount are skipped
+    let simulate = |pool: &Addr, amount: Uint128| -> Option<Uint128> {
+        deps.querier
+            .query_wasm_smart::<SimulationResponse>(
+                pool,
+                &PairQueryMsg::Simulation {
+                    offer_asset: Asset {
+                        info: offer.info.clone(),
+                        amount,
+                    },
+                    ask_asset_info: None,
+                },
+            )
+            .ok()
+            .map(|res| res.return_amount)
+    };
+
+    let parts = Uint128::from(parts);
+    let chunk = offer.amount / parts;
+    let remainder = offer.amount - chunk * parts;
+
+    let mut offer_amounts = vec![Uint128::zero(); pools.len()];
+    let mut return_amounts = vec![Uint128::zero(); pools.len()];
+
+    for part in 0..parts.u128() {
+        // The last chunk also carries the rounding remainder
+        let amount = if part + 1 == parts.u128() {
+            chunk + remainder
+        } else {
+            chunk
+        };
+        if amount.is_zero() {
+            continue;
+        }
+
+        let mut best: Option<(usize, Uint128, Uint128)> = None;
+        for (ind, pool) in pools.iter().enumerate() {
+            if let Some(return_amount) = simulate(pool, offer_amounts[ind] + amount) {
+                let marginal = return_amount.saturating_sub(return_amounts[ind]);
+                if best.map_or(true, |(_, best_marginal, _)| marginal > best_marginal) {
+                    best = Some((ind, marginal, return_amount));
+                }
+            }
+        }
+
+        let (ind, _, return_amount) = best.ok_or(ContractError::NoSplitRoute {})?;
+        offer_amounts[ind] += amount;
+        return_amounts[ind] = return_amount;
+    }
+
+    let total_return = return_amounts.iter().sum();
+    let splits = pools
+        .into_iter()
+        .zip(offer_amounts)
+        .zip(return_amounts)
+        .map(|((pool, offer_amount), return_amount)| SplitAllocation {
+            pool: pool.to_string(),
+            offer_amount,
+            return_amount,
+        })
+        .collect();
+
+    Ok(OptimizeSplitResponse {
+        splits,
+        total_return,
+    })
+}
+
+/// Ensures the transaction is not included after `deadline`.
+///
+/// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+pub(crate) fn assert_deadline(env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if env.block.time.seconds() > deadline => {
+            Err(ContractError::TransactionExpired {})
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Validates swap operations.
 ///
 /// * **operations** is a vector that contains objects of type [`SwapOperation`]. These are all the swap operations we check.
-fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<(), ContractError> {
+pub(crate) fn assert_operations(
+    api: &dyn Api,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
     let operations_len = operations.len();
     if operations_len == 0 {
         return Err(ContractError::MustProvideOperations {});
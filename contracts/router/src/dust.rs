@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{attr, Addr, Attribute, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response};
+
+use gridiron::asset::{Asset, AssetInfo};
+use gridiron::querier::query_factory_config;
+use gridiron::router::DUST_THRESHOLD;
+
+use crate::error::ContractError;
+use crate::escrow::unescrowed_balance;
+use crate::state::CONFIG;
+
+/// Joins the assets into a single attribute value.
+fn join_assets(assets: &[Asset]) -> String {
+    assets
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the router balance of an asset which doesn't belong to escrow balances.
+fn free_balance(deps: Deps, env: &Env, asset_info: &AssetInfo) -> Result<Asset, ContractError> {
+    let balance = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+
+    Ok(Asset {
+        info: asset_info.clone(),
+        amount: unescrowed_balance(deps.storage, asset_info, balance)?,
+    })
+}
+
+/// Builds the messages which refund the balances of intermediate route assets left in the router
+/// to the trader. Balances below [`DUST_THRESHOLD`] are left for [`sweep_dust`].
+///
+/// * **intermediate_assets** assets received by the router in the middle of a route.
+///
+/// * **trader** address which receives the refunds.
+pub fn refund_intermediate_balances(
+    deps: Deps,
+    env: &Env,
+    intermediate_assets: &[AssetInfo],
+    trader: &Addr,
+) -> Result<(Vec<CosmosMsg>, Vec<Attribute>), ContractError> {
+    let mut refunds = vec![];
+    for asset_info in intermediate_assets {
+        let asset = free_balance(deps, env, asset_info)?;
+        if asset.amount >= DUST_THRESHOLD {
+            refunds.push(asset);
+        }
+    }
+
+    if refunds.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+
+    let messages = refunds
+        .iter()
+        .map(|asset| asset.clone().into_msg(trader))
+        .collect::<Result<_, _>>()?;
+    let attrs = vec![
+        attr("refund_receiver", trader),
+        attr("refunded_assets", join_assets(&refunds)),
+    ];
+
+    Ok((messages, attrs))
+}
+
+/// Sends the dust balances of the specified assets to the Maker or to the sender. Only balances
+/// below [`DUST_THRESHOLD`] can be swept, larger ones are refunded to traders at the end of a route.
+///
+/// * **assets** assets whose dust is swept.
+///
+/// * **to_maker** whether the dust is sent to the Maker. Otherwise it is sent to the sender.
+pub fn sweep_dust(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<AssetInfo>,
+    to_maker: bool,
+) -> Result<Response, ContractError> {
+    let mut unique = HashSet::new();
+    if assets.is_empty() || !assets.iter().all(|asset_info| unique.insert(asset_info)) {
+        return Err(ContractError::InvalidDustAssets {});
+    }
+
+    let receiver = if to_maker {
+        let config = CONFIG.load(deps.storage)?;
+        query_factory_config(&deps.querier, config.gridiron_factory)?
+            .fee_address
+            .ok_or(ContractError::MakerNotSet {})?
+    } else {
+        info.sender
+    };
+
+    let mut swept = vec![];
+    for asset_info in &assets {
+        let asset = free_balance(deps.as_ref(), &env, asset_info)?;
+        if asset.amount >= DUST_THRESHOLD {
+            return Err(ContractError::NotDust {
+                asset: asset.to_string(),
+            });
+        }
+        if !asset.amount.is_zero() {
+            swept.push(asset);
+        }
+    }
+
+    let messages = swept
+        .iter()
+        .map(|asset| asset.clone().into_msg(&receiver))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "sweep_dust"),
+        attr("receiver", receiver),
+        attr("swept_assets", join_assets(&swept)),
+    ]))
+}
@@ -1,4 +1,8 @@
 use cosmwasm_std::{OverflowError, StdError, Uint128};
+use gridiron::pair::MAX_ALLOWED_SLIPPAGE;
+use gridiron::router::{
+    DUST_THRESHOLD, MAX_BATCH_ORDERS, MAX_FEE_PAYER_MEMO_LENGTH, MAX_SPLIT_PARTS, MAX_SPLIT_POOLS,
+};
 use thiserror::Error;
 
 /// This enum describes oracle contract errors
@@ -29,12 +33,21 @@ pub enum ContractError {
         ask_asset: String,
     },
 
+    #[error("The first swap operation must offer the received token {received}, got {offered}")]
+    ReceivedAssetMismatch { received: String, offered: String },
+
     #[error("Must specify swap operations!")]
     MustProvideOperations {},
 
     #[error("Assertion failed; minimum receive amount: {receive}, swap amount: {amount}")]
     AssertionMinimumReceive { receive: Uint128, amount: Uint128 },
 
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
+    #[error("Max intermediate spread must not exceed {}", MAX_ALLOWED_SLIPPAGE)]
+    InvalidMaxIntermediateSpread {},
+
     #[error("The swap operation limit was exceeded!")]
     SwapLimitExceeded {},
 
@@ -43,4 +56,61 @@ pub enum ContractError {
 
     #[error("Contract can't be migrated!")]
     MigrationError {},
+
+    #[error("The number of split parts must be between 1 and {}", MAX_SPLIT_PARTS)]
+    InvalidSplitParts {},
+
+    #[error(
+        "Must provide between 1 and {} unique pools to split across",
+        MAX_SPLIT_POOLS
+    )]
+    InvalidSplitPools {},
+
+    #[error("Pool {pool} doesn't trade the same assets as the other pools")]
+    PoolAssetsMismatch { pool: String },
+
+    #[error("None of the pools can simulate the offer")]
+    NoSplitRoute {},
+
+    #[error("No funds received")]
+    NoFundsReceived {},
+
+    #[error(
+        "Insufficient escrow balance of {asset_info}: available {available}, required {required}"
+    )]
+    InsufficientEscrow {
+        asset_info: String,
+        available: Uint128,
+        required: Uint128,
+    },
+
+    #[error("The first swap operation must offer the order asset {offer}, got {offered}")]
+    OrderOfferMismatch { offer: String, offered: String },
+
+    #[error("Must provide between 1 and {} orders", MAX_BATCH_ORDERS)]
+    InvalidBatchSize {},
+
+    #[error("Relayer {relayer} is not approved by trader {trader}")]
+    RelayerNotApproved { trader: String, relayer: String },
+
+    #[error("Relayer approval must expire in the future")]
+    InvalidRelayerExpiration {},
+
+    #[error(
+        "Fee payer memo must not be longer than {} characters",
+        MAX_FEE_PAYER_MEMO_LENGTH
+    )]
+    FeePayerMemoTooLong {},
+
+    #[error("Must provide at least one asset to sweep and no duplicates")]
+    InvalidDustAssets {},
+
+    #[error(
+        "Balance {asset} is not dust: only balances below {} can be swept",
+        DUST_THRESHOLD
+    )]
+    NotDust { asset: String },
+
+    #[error("The Maker address is not set in the factory")]
+    MakerNotSet {},
 }
@@ -0,0 +1,307 @@
+use cosmwasm_std::{
+    attr, wasm_execute, Addr, Attribute, Decimal, DepsMut, Env, MessageInfo, Response, StdResult,
+    Storage, SubMsg, SubMsgResult, Uint128,
+};
+
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo};
+use gridiron::router::{ExecuteMsg, Order, SwapOperation, MAX_BATCH_ORDERS};
+
+use crate::contract::{assert_deadline, assert_operations, execute_swap_operations};
+use crate::error::ContractError;
+use crate::state::{BATCH_QUEUE, ESCROW, ESCROW_TOTALS, LAST_ORDER_ID, ORDERS};
+
+pub const EXECUTE_ORDER_REPLY_ID: u64 = 2;
+
+/// Adds tokens to the escrow balance of a user.
+///
+/// * **user** owner of the escrow balance.
+///
+/// * **asset** tokens to add.
+pub fn increase_escrow(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    asset: &Asset,
+) -> Result<(), ContractError> {
+    ESCROW.update(storage, (user, &asset.info), |balance| {
+        balance.unwrap_or_default().checked_add(asset.amount)
+    })?;
+    ESCROW_TOTALS.update(storage, &asset.info, |total| {
+        total.unwrap_or_default().checked_add(asset.amount)
+    })?;
+
+    Ok(())
+}
+
+/// Removes tokens from the escrow balance of a user. The tokens become available to swap operations.
+///
+/// * **user** owner of the escrow balance.
+///
+/// * **asset** tokens to remove.
+pub fn decrease_escrow(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    asset: &Asset,
+) -> Result<(), ContractError> {
+    let balance = ESCROW
+        .may_load(storage, (user, &asset.info))?
+        .unwrap_or_default();
+    if balance < asset.amount {
+        return Err(ContractError::InsufficientEscrow {
+            asset_info: asset.info.to_string(),
+            available: balance,
+            required: asset.amount,
+        });
+    }
+
+    let balance = balance - asset.amount;
+    if balance.is_zero() {
+        ESCROW.remove(storage, (user, &asset.info));
+    } else {
+        ESCROW.save(storage, (user, &asset.info), &balance)?;
+    }
+
+    let total = ESCROW_TOTALS
+        .load(storage, &asset.info)?
+        .checked_sub(asset.amount)?;
+    ESCROW_TOTALS.save(storage, &asset.info, &total)?;
+
+    Ok(())
+}
+
+/// Returns the tokens held by the router which don't belong to any escrow balance.
+///
+/// * **asset_info** asset whose balance is returned.
+///
+/// * **balance** total router balance of the asset.
+pub fn unescrowed_balance(
+    storage: &dyn Storage,
+    asset_info: &AssetInfo,
+    balance: Uint128,
+) -> Result<Uint128, ContractError> {
+    let escrowed = ESCROW_TOTALS
+        .may_load(storage, asset_info)?
+        .unwrap_or_default();
+
+    Ok(balance.checked_sub(escrowed)?)
+}
+
+/// Adds the received native tokens to the escrow balance of the sender.
+pub fn deposit_escrow(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFundsReceived {});
+    }
+
+    let mut attrs = vec![attr("action", "deposit_escrow"), attr("user", &info.sender)];
+    for coin in info.funds {
+        let asset = Asset {
+            info: AssetInfo::NativeToken { denom: coin.denom },
+            amount: coin.amount,
+        };
+        increase_escrow(deps.storage, &info.sender, &asset)?;
+        attrs.push(attr("deposit", asset.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sends tokens from the escrow balance back to the sender.
+///
+/// * **asset** tokens to withdraw.
+pub fn withdraw_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    asset.info.check(deps.api)?;
+    decrease_escrow(deps.storage, &info.sender, &asset)?;
+
+    Ok(Response::new()
+        .add_message(asset.clone().into_msg(&info.sender)?)
+        .add_attributes(vec![
+            attr("action", "withdraw_escrow"),
+            attr("user", info.sender),
+            attr("withdraw", asset.to_string()),
+        ]))
+}
+
+/// Saves an order which keepers can execute from the escrow balance of the sender.
+/// The escrow balance is checked only upon execution.
+#[allow(clippy::too_many_arguments)]
+pub fn authorize_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer: Asset,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Uint128,
+    to: Option<String>,
+    max_spread: Option<Decimal>,
+    deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    assert_deadline(&env, deadline)?;
+    assert_operations(deps.api, &operations)?;
+    offer.info.check(deps.api)?;
+
+    let offered = operations[0].get_offer_asset_info();
+    if !offered.equal(&offer.info) {
+        return Err(ContractError::OrderOfferMismatch {
+            offer: offer.info.to_string(),
+            offered: offered.to_string(),
+        });
+    }
+
+    let to = addr_opt_validate(deps.api, &to)?.unwrap_or_else(|| info.sender.clone());
+    let order_id = LAST_ORDER_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LAST_ORDER_ID.save(deps.storage, &order_id)?;
+
+    ORDERS.save(
+        deps.storage,
+        order_id,
+        &Order {
+            owner: info.sender.clone(),
+            offer,
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+            deadline,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "authorize_order"),
+        attr("owner", info.sender),
+        attr("order_id", order_id.to_string()),
+    ]))
+}
+
+/// Removes an order of the sender.
+pub fn cancel_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let order = ORDERS.load(deps.storage, order_id)?;
+    if order.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ORDERS.remove(deps.storage, order_id);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_order"),
+        attr("order_id", order_id.to_string()),
+    ]))
+}
+
+/// Dispatches every order as a separate submessage so a failing order doesn't revert the others.
+/// Unknown orders are skipped and expired orders are removed.
+pub fn execute_orders(
+    deps: DepsMut,
+    env: Env,
+    order_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if order_ids.is_empty() || order_ids.len() > MAX_BATCH_ORDERS {
+        return Err(ContractError::InvalidBatchSize {});
+    }
+
+    let mut messages = vec![];
+    let mut queue = vec![];
+    let mut attrs = vec![attr("action", "execute_orders")];
+    for order_id in order_ids {
+        match ORDERS.may_load(deps.storage, order_id)? {
+            None => attrs.extend(order_status(order_id, "not_found")),
+            Some(order) if assert_deadline(&env, order.deadline).is_err() => {
+                ORDERS.remove(deps.storage, order_id);
+                attrs.extend(order_status(order_id, "expired"));
+            }
+            Some(_) => {
+                messages.push(SubMsg::reply_always(
+                    wasm_execute(
+                        &env.contract.address,
+                        &ExecuteMsg::ExecuteOrder { order_id },
+                        vec![],
+                    )?,
+                    EXECUTE_ORDER_REPLY_ID,
+                ));
+                queue.push(order_id);
+            }
+        }
+    }
+
+    if !queue.is_empty() {
+        BATCH_QUEUE.save(deps.storage, &queue)?;
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(attrs))
+}
+
+/// Executes a single order. Any error reverts the order execution, including the escrow update.
+pub fn execute_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let order = ORDERS.load(deps.storage, order_id)?;
+    ORDERS.remove(deps.storage, order_id);
+
+    decrease_escrow(deps.storage, &order.owner, &order.offer)?;
+
+    execute_swap_operations(
+        deps,
+        env,
+        order.owner,
+        order.operations,
+        Some(order.minimum_receive),
+        Some(order.to.to_string()),
+        order.max_spread,
+        None,
+    )
+}
+
+/// Reports the result of an order executed by [`execute_orders`].
+pub fn execute_order_reply(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let mut queue = BATCH_QUEUE.load(deps.storage)?;
+    let order_id = queue.remove(0);
+    if queue.is_empty() {
+        BATCH_QUEUE.remove(deps.storage);
+    } else {
+        BATCH_QUEUE.save(deps.storage, &queue)?;
+    }
+
+    let attrs = match result {
+        SubMsgResult::Ok(..) => order_status(order_id, "executed"),
+        SubMsgResult::Err(err) => {
+            let mut attrs = order_status(order_id, "failed");
+            attrs.push(attr("error", err));
+            attrs
+        }
+    };
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+fn order_status(order_id: u64, status: &str) -> Vec<Attribute> {
+    vec![
+        attr("order_id", order_id.to_string()),
+        attr("status", status),
+    ]
+}
+
+/// Returns the escrow balance of a user.
+pub fn query_escrow(
+    storage: &dyn Storage,
+    user: &Addr,
+    asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    Ok(ESCROW
+        .may_load(storage, (user, asset_info))?
+        .unwrap_or_default())
+}
@@ -3,7 +3,10 @@ pub mod state;
 
 pub mod error;
 
+mod dust;
+mod escrow;
 mod operations;
+mod relayer;
 
 #[cfg(test)]
 mod testing;
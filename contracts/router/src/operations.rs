@@ -8,6 +8,7 @@ use cosmwasm_std::{
 use cw20::Cw20ExecuteMsg;
 
 use crate::error::ContractError;
+use crate::escrow::unescrowed_balance;
 use crate::state::CONFIG;
 
 /// Execute a swap operation.
@@ -42,7 +43,7 @@ pub fn execute_swap_operation(
                 &[offer_asset_info.clone(), ask_asset_info.clone()],
             )?;
 
-            let amount = match &offer_asset_info {
+            let balance = match &offer_asset_info {
                 AssetInfo::NativeToken { denom } => {
                     query_balance(&deps.querier, env.contract.address, denom)?
                 }
@@ -50,6 +51,8 @@ pub fn execute_swap_operation(
                     query_token_balance(&deps.querier, contract_addr, env.contract.address)?
                 }
             };
+            // Escrow balances are never swapped on behalf of other users
+            let amount = unescrowed_balance(deps.storage, &offer_asset_info, balance)?;
             let offer_asset = Asset {
                 info: offer_asset_info,
                 amount,
@@ -109,7 +112,10 @@ pub fn asset_into_swap_msg(
                 ask_asset_info: Some(ask_asset_info),
                 belief_price,
                 max_spread,
+                min_receive: None,
                 to,
+                deadline: None,
+                referral: None,
             })?,
         })),
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -122,7 +128,10 @@ pub fn asset_into_swap_msg(
                     ask_asset_info: Some(ask_asset_info),
                     belief_price,
                     max_spread,
+                    min_receive: None,
                     to,
+                    deadline: None,
+                    referral: None,
                 })?,
             })?,
         })),
@@ -0,0 +1,124 @@
+use cosmwasm_std::{attr, Addr, Attribute, Deps, DepsMut, Env, MessageInfo, Response};
+
+use gridiron::router::{RelayerApproval, MAX_FEE_PAYER_MEMO_LENGTH};
+
+use crate::error::ContractError;
+use crate::state::RELAYER_APPROVALS;
+
+/// Allows a relayer to execute swap operations on behalf of the sender.
+///
+/// * **relayer** address that is approved.
+///
+/// * **expires** timestamp (in seconds) after which the approval expires.
+pub fn approve_relayer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    relayer: String,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let relayer = deps.api.addr_validate(&relayer)?;
+    if matches!(expires, Some(expires) if expires <= env.block.time.seconds()) {
+        return Err(ContractError::InvalidRelayerExpiration {});
+    }
+
+    RELAYER_APPROVALS.save(
+        deps.storage,
+        (&info.sender, &relayer),
+        &RelayerApproval { expires },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "approve_relayer"),
+        attr("trader", info.sender),
+        attr("relayer", relayer),
+        attr(
+            "expires",
+            expires
+                .map(|expires| expires.to_string())
+                .unwrap_or_default(),
+        ),
+    ]))
+}
+
+/// Removes the approval of a relayer by the sender.
+///
+/// * **relayer** address whose approval is removed.
+pub fn revoke_relayer(
+    deps: DepsMut,
+    info: MessageInfo,
+    relayer: String,
+) -> Result<Response, ContractError> {
+    let relayer = deps.api.addr_validate(&relayer)?;
+    RELAYER_APPROVALS.remove(deps.storage, (&info.sender, &relayer));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_relayer"),
+        attr("trader", info.sender),
+        attr("relayer", relayer),
+    ]))
+}
+
+/// Returns the trader on whose behalf swap operations are executed together with the attributes
+/// which attribute them to the relayer and the fee payer.
+/// The sender is the trader unless it relays the swap for a trader which approved it.
+///
+/// * **sender** address which sent the swap operations and the offer tokens.
+///
+/// * **trader** the logical trader specified by the sender.
+///
+/// * **fee_payer_memo** memo of the fee payer.
+pub fn resolve_trader(
+    deps: Deps,
+    env: &Env,
+    sender: Addr,
+    trader: Option<String>,
+    fee_payer_memo: Option<String>,
+) -> Result<(Addr, Vec<Attribute>), ContractError> {
+    let mut attrs = vec![];
+
+    let trader = match trader {
+        Some(trader) if trader != sender.as_str() => {
+            let trader = deps.api.addr_validate(&trader)?;
+            let approved = RELAYER_APPROVALS
+                .may_load(deps.storage, (&trader, &sender))?
+                .map_or(false, |approval| {
+                    approval
+                        .expires
+                        .map_or(true, |expires| env.block.time.seconds() < expires)
+                });
+            if !approved {
+                return Err(ContractError::RelayerNotApproved {
+                    trader: trader.to_string(),
+                    relayer: sender.to_string(),
+                });
+            }
+
+            attrs.push(attr("trader", &trader));
+            attrs.push(attr("relayer", sender));
+            trader
+        }
+        _ => sender,
+    };
+
+    if let Some(fee_payer_memo) = fee_payer_memo {
+        if fee_payer_memo.chars().count() > MAX_FEE_PAYER_MEMO_LENGTH {
+            return Err(ContractError::FeePayerMemoTooLong {});
+        }
+        attrs.push(attr("fee_payer_memo", fee_payer_memo));
+    }
+
+    Ok((trader, attrs))
+}
+
+/// Returns the approval of a relayer by a trader, if any.
+pub fn query_relayer_approval(
+    deps: Deps,
+    trader: String,
+    relayer: String,
+) -> Result<Option<RelayerApproval>, ContractError> {
+    let trader = deps.api.addr_validate(&trader)?;
+    let relayer = deps.api.addr_validate(&relayer)?;
+
+    Ok(RELAYER_APPROVALS.may_load(deps.storage, (&trader, &relayer))?)
+}
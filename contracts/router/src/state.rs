@@ -1,7 +1,8 @@
 use gridiron::asset::AssetInfo;
+use gridiron::router::{Order, RelayerApproval};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -21,4 +22,26 @@ pub struct ReplyData {
     pub prev_balance: Uint128,
     pub minimum_receive: Option<Uint128>,
     pub receiver: String,
+    /// The trader which receives the intermediate route balances left in the router
+    pub refund_to: Addr,
+    /// The assets received by the router in the middle of the route
+    pub intermediate_assets: Vec<AssetInfo>,
 }
+
+/// Stores the escrow balances of users which fund their orders
+pub const ESCROW: Map<(&Addr, &AssetInfo), Uint128> = Map::new("escrow");
+
+/// Stores the sum of all escrow balances per asset. These tokens are never used by swap operations
+pub const ESCROW_TOTALS: Map<&AssetInfo, Uint128> = Map::new("escrow_totals");
+
+/// Stores the orders which keepers can execute
+pub const ORDERS: Map<u64, Order> = Map::new("orders");
+
+/// Stores the id of the last authorized order
+pub const LAST_ORDER_ID: Item<u64> = Item::new("last_order_id");
+
+/// Stores the ids of the orders of the batch being executed whose replies are not processed yet
+pub const BATCH_QUEUE: Item<Vec<u64>> = Item::new("batch_queue");
+
+/// Stores the relayers approved by traders. The key is (trader, relayer)
+pub const RELAYER_APPROVALS: Map<(&Addr, &Addr), RelayerApproval> = Map::new("relayer_approvals");
@@ -148,6 +148,9 @@ impl WasmMockQuerier {
                             },
                         ],
                         pair_type: PairType::Xyk {},
+                        creator: None,
+                        created_at: None,
+                        allowlist: None,
                     }))),
                     None => SystemResult::Err(SystemError::InvalidRequest {
                         error: "No pair info exists".to_string(),
@@ -50,6 +50,10 @@ fn execute_swap_operations() {
         minimum_receive: None,
         to: None,
         max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: None,
+        trader: None,
+        fee_payer_memo: None,
     };
 
     let env = mock_env();
@@ -87,6 +91,10 @@ fn execute_swap_operations() {
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
         max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: None,
+        trader: None,
+        fee_payer_memo: None,
     };
 
     let env = mock_env();
@@ -176,8 +184,8 @@ fn execute_swap_operations() {
         msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
             operations: vec![
                 SwapOperation::GridSwap {
-                    offer_asset_info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
+                    offer_asset_info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked("asset0000"),
                     },
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: Addr::unchecked("asset0001"),
@@ -203,6 +211,10 @@ fn execute_swap_operations() {
             minimum_receive: None,
             to: Some(String::from("addr0002")),
             max_spread: None,
+            max_intermediate_spread_bps: None,
+            deadline: None,
+            trader: None,
+            fee_payer_memo: None,
         })
         .unwrap(),
     });
@@ -219,8 +231,8 @@ fn execute_swap_operations() {
                     funds: vec![],
                     msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
                         operation: SwapOperation::GridSwap {
-                            offer_asset_info: AssetInfo::NativeToken {
-                                denom: "ukrw".to_string(),
+                            offer_asset_info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked("asset0000"),
                             },
                             ask_asset_info: AssetInfo::Token {
                                 contract_addr: Addr::unchecked("asset0001"),
@@ -350,7 +362,10 @@ fn execute_swap_operation() {
                         ask_asset_info: Some(native_asset_info("uusd".to_string())),
                         belief_price: None,
                         max_spread: None,
+                        min_receive: None,
                         to: Some(String::from("addr0000")),
+                        deadline: None,
+                        referral: None,
                     })
                     .unwrap()
                 })
@@ -455,6 +470,10 @@ fn assert_maximum_receive_swap_operations() {
         minimum_receive: None,
         to: None,
         max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: None,
+        trader: None,
+        fee_payer_memo: None,
     };
 
     let env = mock_env();
@@ -463,3 +482,63 @@ fn assert_maximum_receive_swap_operations() {
 
     assert_eq!(res, ContractError::SwapLimitExceeded {});
 }
+
+#[test]
+fn execute_swap_operations_after_deadline() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        gridiron_factory: String::from("gridironfactory"),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let operations = vec![SwapOperation::GridSwap {
+        offer_asset_info: native_asset_info("ukrw".to_string()),
+        ask_asset_info: native_asset_info("uluna".to_string()),
+    }];
+
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: Some(env.block.time.seconds() - 1),
+        trader: None,
+        fee_payer_memo: None,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::TransactionExpired {});
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: String::from("addr0000"),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: operations.clone(),
+            minimum_receive: None,
+            to: None,
+            max_spread: None,
+            max_intermediate_spread_bps: None,
+            deadline: Some(env.block.time.seconds() - 1),
+            trader: None,
+            fee_payer_memo: None,
+        })
+        .unwrap(),
+    });
+    let err = execute(deps.as_mut(), env.clone(), mock_info("asset0000", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::TransactionExpired {});
+
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations,
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: Some(env.block.time.seconds()),
+        trader: None,
+        fee_payer_memo: None,
+    };
+    execute(deps.as_mut(), env, info, msg).unwrap();
+}
@@ -13,6 +13,7 @@ pub struct FactoryHelper {
     pub grid_token: Addr,
     pub factory: Addr,
     pub cw20_token_code_id: u64,
+    pub pair_code_id: u64,
 }
 
 impl FactoryHelper {
@@ -113,6 +114,7 @@ impl FactoryHelper {
             grid_token,
             factory,
             cw20_token_code_id,
+            pair_code_id,
         }
     }
 
@@ -1,12 +1,16 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{coins, from_binary, to_binary, Addr, Empty, StdError};
-use cw20::Cw20ExecuteMsg;
+use cosmwasm_std::{coins, from_binary, to_binary, Addr, Decimal, Empty, StdError, Uint128};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
-use gridiron::asset::{native_asset_info, token_asset_info};
+use gridiron::asset::{native_asset_info, token_asset_info, AssetInfoExt};
 use gridiron::factory::PairType;
-use gridiron::router::{ExecuteMsg, InstantiateMsg, SwapOperation, SwapResponseData};
+use gridiron::pair::SimulationResponse;
+use gridiron::router::{
+    ExecuteMsg, InstantiateMsg, OptimizeSplitResponse, Order, QueryMsg, RelayerApproval,
+    SwapOperation, SwapResponseData, DUST_THRESHOLD, MAX_FEE_PAYER_MEMO_LENGTH,
+};
 use gridiron_router::error::ContractError;
 
 use crate::factory_helper::{instantiate_token, mint, mint_native, FactoryHelper};
@@ -89,6 +93,10 @@ fn router_does_not_enforce_spread_assertion() {
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    max_intermediate_spread_bps: None,
+                    deadline: None,
+                    trader: None,
+                    fee_payer_memo: None,
                 })
                 .unwrap(),
             },
@@ -119,6 +127,10 @@ fn router_does_not_enforce_spread_assertion() {
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    max_intermediate_spread_bps: None,
+                    deadline: None,
+                    trader: None,
+                    fee_payer_memo: None,
                 })
                 .unwrap(),
             },
@@ -131,6 +143,110 @@ fn router_does_not_enforce_spread_assertion() {
     )
 }
 
+#[test]
+fn cw20_route_via_receive() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token_x = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOX", None);
+    let token_y = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOY", None);
+    let token_z = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOZ", None);
+
+    for (a, b) in [(&token_x, &token_y), (&token_y, &token_z)] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [token_asset_info(a.clone()), token_asset_info(b.clone())],
+                None,
+            )
+            .unwrap();
+        mint(&mut app, &owner, a, 100_000_000000, &pair).unwrap();
+        mint(&mut app, &owner, b, 100_000_000000, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    mint(&mut app, &owner, &token_x, 1_000000, &user).unwrap();
+    mint(&mut app, &owner, &token_y, 1_000000, &user).unwrap();
+
+    let swap_msg = |amount: u128| Cw20ExecuteMsg::Send {
+        contract: router.to_string(),
+        amount: amount.into(),
+        msg: to_binary(&ExecuteMsg::ExecuteSwapOperations {
+            operations: vec![
+                SwapOperation::GridSwap {
+                    offer_asset_info: token_asset_info(token_x.clone()),
+                    ask_asset_info: token_asset_info(token_y.clone()),
+                },
+                SwapOperation::GridSwap {
+                    offer_asset_info: token_asset_info(token_y.clone()),
+                    ask_asset_info: token_asset_info(token_z.clone()),
+                },
+            ],
+            minimum_receive: None,
+            to: None,
+            max_spread: None,
+            max_intermediate_spread_bps: None,
+            deadline: None,
+            trader: None,
+            fee_payer_memo: None,
+        })
+        .unwrap(),
+    };
+
+    // The route must start with the sent token
+    let err = app
+        .execute_contract(user.clone(), token_y.clone(), &swap_msg(1_000000), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ReceivedAssetMismatch {
+            received: token_y.to_string(),
+            offered: token_x.to_string(),
+        },
+        err.downcast().unwrap()
+    );
+
+    // No allowance is needed to swap through the intermediate cw20 token
+    app.execute_contract(user.clone(), token_x.clone(), &swap_msg(1_000000), &[])
+        .unwrap();
+
+    let balance = |app: &App, token: &Addr, address: &Addr| -> Uint128 {
+        let res: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    };
+
+    assert_eq!(balance(&app, &token_x, &user), Uint128::zero());
+    assert_eq!(balance(&app, &token_y, &user).u128(), 1_000000);
+    assert!(!balance(&app, &token_z, &user).is_zero());
+    assert_eq!(balance(&app, &token_x, &router), Uint128::zero());
+    assert_eq!(balance(&app, &token_y, &router), Uint128::zero());
+}
+
 #[test]
 fn route_through_pairs_with_natives() {
     let mut app = App::default();
@@ -210,6 +326,10 @@ fn route_through_pairs_with_natives() {
                 to: None,
                 max_spread: None,
                 minimum_receive: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &[],
         )
@@ -231,6 +351,10 @@ fn route_through_pairs_with_natives() {
                 to: None,
                 max_spread: None,
                 minimum_receive: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &[],
         )
@@ -264,6 +388,10 @@ fn route_through_pairs_with_natives() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -292,6 +420,10 @@ fn route_through_pairs_with_natives() {
                 minimum_receive: Some(50_000_000000u128.into()), // <--- enforcing minimum receive with 1:1 rate (which practically impossible)
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -306,6 +438,116 @@ fn route_through_pairs_with_natives() {
     );
 }
 
+#[test]
+fn intermediate_hop_spread_limit() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+    let denom_z = "denom_z";
+
+    for (a, b, typ, liq) in [
+        (&denom_x, &denom_y, PairType::Xyk {}, 100_000_000000),
+        (&denom_y, &denom_z, PairType::Stable {}, 1_000_000_000000),
+    ] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                typ,
+                [
+                    native_asset_info(a.to_string()),
+                    native_asset_info(b.to_string()),
+                ],
+                None,
+            )
+            .unwrap();
+        mint_native(&mut app, a, liq, &pair).unwrap();
+        mint_native(&mut app, b, liq, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let swap_msg = |max_intermediate_spread_bps| ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![
+            SwapOperation::GridSwap {
+                offer_asset_info: native_asset_info(denom_x.to_string()),
+                ask_asset_info: native_asset_info(denom_y.to_string()),
+            },
+            SwapOperation::GridSwap {
+                offer_asset_info: native_asset_info(denom_y.to_string()),
+                ask_asset_info: native_asset_info(denom_z.to_string()),
+            },
+        ],
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        max_intermediate_spread_bps,
+        deadline: None,
+        trader: None,
+        fee_payer_memo: None,
+    };
+
+    mint_native(&mut app, &denom_x, 10_000_000000, &owner).unwrap();
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg(Some(5001)),
+            &coins(10_000_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidMaxIntermediateSpread {}
+    );
+
+    // The first hop takes 10% of the xyk pool which results in ~9% spread
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg(Some(500)),
+            &coins(10_000_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Operation exceeds max spread limit"
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        router,
+        &swap_msg(Some(1000)),
+        &coins(10_000_000000, denom_x),
+    )
+    .unwrap();
+
+    assert!(!app
+        .wrap()
+        .query_balance(&owner, denom_z)
+        .unwrap()
+        .amount
+        .is_zero());
+}
+
 #[test]
 fn test_swap_route() {
     use crate::factory_helper::{instantiate_token, mint, FactoryHelper};
@@ -478,6 +720,10 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             })
             .unwrap(),
         },
@@ -565,6 +811,10 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             })
             .unwrap(),
         },
@@ -657,6 +907,10 @@ fn test_swap_route() {
                 minimum_receive: Some(Uint128::new(9_997_000)),
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &[],
         )
@@ -789,6 +1043,10 @@ fn test_swap_route() {
                 minimum_receive: Some(donated_atom),
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             })
             .unwrap(),
         },
@@ -847,6 +1105,10 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
             },
             &[],
         )
@@ -880,3 +1142,697 @@ fn test_swap_route() {
     let profit = balance_res.balance.saturating_sub(donated_atom);
     println!("Attacker2's profit: {:?}", profit);
 }
+
+#[test]
+fn optimize_split() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token_x = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOX", None);
+    let token_y = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOY", None);
+    let token_z = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOZ", None);
+    let asset_infos = [
+        token_asset_info(token_x.clone()),
+        token_asset_info(token_y.clone()),
+    ];
+
+    // The factory keeps one pair per asset set, so the second X/Y pool is instantiated directly
+    let small_pool = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            asset_infos.clone(),
+            None,
+        )
+        .unwrap();
+    let big_pool = app
+        .instantiate_contract(
+            helper.pair_code_id,
+            owner.clone(),
+            &gridiron::pair::InstantiateMsg {
+                asset_infos: asset_infos.to_vec(),
+                token_code_id: helper.cw20_token_code_id,
+                factory_addr: helper.factory.to_string(),
+                init_params: None,
+                creator: None,
+            },
+            &[],
+            "big pool",
+            None,
+        )
+        .unwrap();
+    let other_pool = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [
+                token_asset_info(token_x.clone()),
+                token_asset_info(token_z.clone()),
+            ],
+            None,
+        )
+        .unwrap();
+
+    for (pool, liq) in [
+        (&small_pool, 1_000_000000),
+        (&big_pool, 3_000_000000),
+        (&other_pool, 1_000_000000),
+    ] {
+        mint(&mut app, &owner, &token_x, liq, pool).unwrap();
+        mint(&mut app, &owner, &token_y, liq, pool).unwrap();
+        mint(&mut app, &owner, &token_z, liq, pool).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let offer = token_asset_info(token_x.clone()).with_balance(400_000000u128);
+    let query_split = |app: &App, pools: &[&Addr], parts: u8| {
+        app.wrap().query_wasm_smart::<OptimizeSplitResponse>(
+            &router,
+            &QueryMsg::OptimizeSplit {
+                offer: offer.clone(),
+                pools: pools.iter().map(|pool| pool.to_string()).collect(),
+                parts,
+            },
+        )
+    };
+
+    let err = query_split(&app, &[&small_pool, &big_pool], 0).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: The number of split parts must be between 1 and 20"
+        )
+    );
+    let err = query_split(&app, &[&small_pool, &small_pool], 10).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: Must provide between 1 and 10 unique pools to split across"
+        )
+    );
+    let err = query_split(&app, &[&small_pool, &other_pool], 10).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "Querier contract error: Pool {other_pool} doesn't trade the same assets as the other pools"
+        ))
+    );
+
+    let res = query_split(&app, &[&small_pool, &big_pool], 10).unwrap();
+    assert_eq!(res.splits[0].pool, small_pool.to_string());
+    assert_eq!(res.splits[1].pool, big_pool.to_string());
+    assert_eq!(
+        res.splits[0].offer_amount + res.splits[1].offer_amount,
+        offer.amount
+    );
+    // The deeper pool absorbs most of the offer, but not all of it
+    assert!(res.splits[1].offer_amount > res.splits[0].offer_amount);
+    assert!(!res.splits[0].offer_amount.is_zero());
+    assert_eq!(
+        res.total_return,
+        res.splits[0].return_amount + res.splits[1].return_amount
+    );
+
+    // Splitting beats swapping the whole amount in the deepest pool
+    let single: SimulationResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &big_pool,
+            &gridiron::pair::QueryMsg::Simulation {
+                offer_asset: offer.clone(),
+                ask_asset_info: None,
+            },
+        )
+        .unwrap();
+    assert!(res.total_return > single.return_amount);
+}
+
+#[test]
+fn keeper_executes_escrow_orders() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let user1 = Addr::unchecked("user1");
+    let user2 = Addr::unchecked("user2");
+    let keeper = Addr::unchecked("keeper");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+
+    let pair = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [
+                native_asset_info(denom_x.to_string()),
+                native_asset_info(denom_y.to_string()),
+            ],
+            None,
+        )
+        .unwrap();
+    mint_native(&mut app, denom_x, 100_000_000000, &pair).unwrap();
+    mint_native(&mut app, denom_y, 100_000_000000, &pair).unwrap();
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    for user in [&user1, &user2] {
+        mint_native(&mut app, denom_x, 10_000000, user).unwrap();
+        app.execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::DepositEscrow {},
+            &coins(10_000000, denom_x),
+        )
+        .unwrap();
+    }
+    let query_escrow = |app: &App, user: &Addr| {
+        app.wrap()
+            .query_wasm_smart::<Uint128>(
+                &router,
+                &QueryMsg::Escrow {
+                    user: user.to_string(),
+                    asset_info: native_asset_info(denom_x.to_string()),
+                },
+            )
+            .unwrap()
+            .u128()
+    };
+    assert_eq!(query_escrow(&app, &user1), 10_000000);
+
+    // Regular swaps don't touch escrow balances
+    mint_native(&mut app, denom_x, 1_000000, &owner).unwrap();
+    let operations = vec![SwapOperation::GridSwap {
+        offer_asset_info: native_asset_info(denom_x.to_string()),
+        ask_asset_info: native_asset_info(denom_y.to_string()),
+    }];
+    let resp = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: operations.clone(),
+                minimum_receive: None,
+                to: None,
+                max_spread: None,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
+            },
+            &coins(1_000000, denom_x),
+        )
+        .unwrap();
+    let resp_data: SwapResponseData = from_binary(&resp.data.unwrap()).unwrap();
+    assert!(resp_data.return_amount.u128() < 1_000000);
+    let router_balance = app.wrap().query_balance(&router, denom_x).unwrap();
+    assert_eq!(router_balance.amount.u128(), 20_000000);
+
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            router.clone(),
+            &ExecuteMsg::AuthorizeOrder {
+                offer: native_asset_info(denom_y.to_string()).with_balance(10_000000u128),
+                operations: operations.clone(),
+                minimum_receive: 9_000000u128.into(),
+                to: None,
+                max_spread: None,
+                deadline: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::OrderOfferMismatch {
+            offer: denom_y.to_string(),
+            offered: denom_x.to_string()
+        }
+    );
+
+    // The second order can't be filled at its limit price
+    for (user, minimum_receive) in [(&user1, 9_000000u128), (&user2, 20_000000u128)] {
+        app.execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::AuthorizeOrder {
+                offer: native_asset_info(denom_x.to_string()).with_balance(10_000000u128),
+                operations: operations.clone(),
+                minimum_receive: minimum_receive.into(),
+                to: None,
+                max_spread: Some(Decimal::percent(50)),
+                deadline: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let err = app
+        .execute_contract(
+            keeper.clone(),
+            router.clone(),
+            &ExecuteMsg::ExecuteOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let resp = app
+        .execute_contract(
+            keeper.clone(),
+            router.clone(),
+            &ExecuteMsg::ExecuteOrders {
+                order_ids: vec![1, 2, 3],
+            },
+            &[],
+        )
+        .unwrap();
+    let statuses = resp
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .filter(|attr| attr.key == "status")
+        .map(|attr| attr.value.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, vec!["not_found", "executed", "failed"]);
+
+    let user1_balance = app.wrap().query_balance(&user1, denom_y).unwrap();
+    assert!(user1_balance.amount.u128() >= 9_000000);
+    assert_eq!(query_escrow(&app, &user1), 0);
+    app.wrap()
+        .query_wasm_smart::<Order>(&router, &QueryMsg::Order { order_id: 1 })
+        .unwrap_err();
+
+    // The failed order is still available and its escrow is intact
+    assert_eq!(query_escrow(&app, &user2), 10_000000);
+    let order: Order = app
+        .wrap()
+        .query_wasm_smart(&router, &QueryMsg::Order { order_id: 2 })
+        .unwrap();
+    assert_eq!(order.owner, user2);
+
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            router.clone(),
+            &ExecuteMsg::CancelOrder { order_id: 2 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    app.execute_contract(
+        user2.clone(),
+        router.clone(),
+        &ExecuteMsg::CancelOrder { order_id: 2 },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        user2.clone(),
+        router.clone(),
+        &ExecuteMsg::WithdrawEscrow {
+            asset: native_asset_info(denom_x.to_string()).with_balance(10_000000u128),
+        },
+        &[],
+    )
+    .unwrap();
+    let user2_balance = app.wrap().query_balance(&user2, denom_x).unwrap();
+    assert_eq!(user2_balance.amount.u128(), 10_000000);
+    assert_eq!(query_escrow(&app, &user2), 0);
+}
+
+#[test]
+fn relayer_swaps_on_behalf_of_trader() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let trader = Addr::unchecked("trader");
+    let relayer = Addr::unchecked("relayer");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+
+    let pair = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [
+                native_asset_info(denom_x.to_string()),
+                native_asset_info(denom_y.to_string()),
+            ],
+            None,
+        )
+        .unwrap();
+    mint_native(&mut app, denom_x, 100_000_000000, &pair).unwrap();
+    mint_native(&mut app, denom_y, 100_000_000000, &pair).unwrap();
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    mint_native(&mut app, denom_x, 10_000000, &relayer).unwrap();
+    let swap_msg = |fee_payer_memo: &str| ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![SwapOperation::GridSwap {
+            offer_asset_info: native_asset_info(denom_x.to_string()),
+            ask_asset_info: native_asset_info(denom_y.to_string()),
+        }],
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        max_intermediate_spread_bps: None,
+        deadline: None,
+        trader: Some(trader.to_string()),
+        fee_payer_memo: Some(fee_payer_memo.to_string()),
+    };
+
+    let err = app
+        .execute_contract(
+            relayer.clone(),
+            router.clone(),
+            &swap_msg("grant-1"),
+            &coins(1_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RelayerNotApproved {
+            trader: trader.to_string(),
+            relayer: relayer.to_string(),
+        }
+    );
+
+    let now = app.block_info().time.seconds();
+    let err = app
+        .execute_contract(
+            trader.clone(),
+            router.clone(),
+            &ExecuteMsg::ApproveRelayer {
+                relayer: relayer.to_string(),
+                expires: Some(now),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidRelayerExpiration {}
+    );
+    app.execute_contract(
+        trader.clone(),
+        router.clone(),
+        &ExecuteMsg::ApproveRelayer {
+            relayer: relayer.to_string(),
+            expires: Some(now + 100),
+        },
+        &[],
+    )
+    .unwrap();
+    let approval: Option<RelayerApproval> = app
+        .wrap()
+        .query_wasm_smart(
+            &router,
+            &QueryMsg::RelayerApproval {
+                trader: trader.to_string(),
+                relayer: relayer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        approval,
+        Some(RelayerApproval {
+            expires: Some(now + 100)
+        })
+    );
+
+    let err = app
+        .execute_contract(
+            relayer.clone(),
+            router.clone(),
+            &swap_msg(&"a".repeat(MAX_FEE_PAYER_MEMO_LENGTH + 1)),
+            &coins(1_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FeePayerMemoTooLong {}
+    );
+
+    // The relayer pays the offer while the trader receives the ask tokens
+    let resp = app
+        .execute_contract(
+            relayer.clone(),
+            router.clone(),
+            &swap_msg("grant-1"),
+            &coins(1_000000, denom_x),
+        )
+        .unwrap();
+    let attributes = resp
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .filter(|attr| ["trader", "relayer", "fee_payer_memo"].contains(&attr.key.as_str()))
+        .map(|attr| (attr.key.as_str(), attr.value.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attributes,
+        vec![
+            ("trader", "trader"),
+            ("relayer", "relayer"),
+            ("fee_payer_memo", "grant-1")
+        ]
+    );
+    let trader_balance = app.wrap().query_balance(&trader, denom_y).unwrap();
+    assert!(trader_balance.amount.u128() >= 990000);
+    let relayer_balance = app.wrap().query_balance(&relayer, denom_y).unwrap();
+    assert!(relayer_balance.amount.is_zero());
+
+    // The approval expires
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    let err = app
+        .execute_contract(
+            relayer.clone(),
+            router.clone(),
+            &swap_msg("grant-2"),
+            &coins(1_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RelayerNotApproved {
+            trader: trader.to_string(),
+            relayer: relayer.to_string(),
+        }
+    );
+
+    app.execute_contract(
+        trader.clone(),
+        router.clone(),
+        &ExecuteMsg::RevokeRelayer {
+            relayer: relayer.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    let approval: Option<RelayerApproval> = app
+        .wrap()
+        .query_wasm_smart(
+            &router,
+            &QueryMsg::RelayerApproval {
+                trader: trader.to_string(),
+                relayer: relayer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(approval, None);
+}
+
+#[test]
+fn sweep_dust() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+    let maker = Addr::unchecked("maker");
+    let helper = FactoryHelper::init(&mut app, &owner);
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                gridiron_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+    mint_native(&mut app, denom_x, 999, &router).unwrap();
+    mint_native(&mut app, denom_y, DUST_THRESHOLD.u128(), &router).unwrap();
+
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::SweepDust {
+                assets: vec![
+                    native_asset_info(denom_x.to_string()),
+                    native_asset_info(denom_x.to_string()),
+                ],
+                to_maker: false,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidDustAssets {}
+    );
+
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::SweepDust {
+                assets: vec![
+                    native_asset_info(denom_x.to_string()),
+                    native_asset_info(denom_y.to_string()),
+                ],
+                to_maker: false,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotDust {
+            asset: format!("{DUST_THRESHOLD}{denom_y}")
+        }
+    );
+
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::SweepDust {
+                assets: vec![native_asset_info(denom_x.to_string())],
+                to_maker: true,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MakerNotSet {}
+    );
+
+    // Anyone can sweep dust to themselves
+    app.execute_contract(
+        user.clone(),
+        router.clone(),
+        &ExecuteMsg::SweepDust {
+            assets: vec![native_asset_info(denom_x.to_string())],
+            to_maker: false,
+        },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(&user, denom_x).unwrap();
+    assert_eq!(balance.amount.u128(), 999);
+
+    // Escrow balances are never swept
+    mint_native(&mut app, denom_x, 2_000, &user).unwrap();
+    app.execute_contract(
+        user.clone(),
+        router.clone(),
+        &ExecuteMsg::DepositEscrow {},
+        &coins(2_000, denom_x),
+    )
+    .unwrap();
+    mint_native(&mut app, denom_x, 100, &router).unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &gridiron::factory::ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: Some(maker.to_string()),
+            generator_address: None,
+            whitelist_code_id: None,
+            coin_registry_address: None,
+            max_combined_fee_bps: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        user.clone(),
+        router.clone(),
+        &ExecuteMsg::SweepDust {
+            assets: vec![native_asset_info(denom_x.to_string())],
+            to_maker: true,
+        },
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(&maker, denom_x).unwrap();
+    assert_eq!(balance.amount.u128(), 100);
+    let balance = app.wrap().query_balance(&router, denom_x).unwrap();
+    assert_eq!(balance.amount.u128(), 2_000);
+}
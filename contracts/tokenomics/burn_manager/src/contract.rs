@@ -0,0 +1,355 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, from_binary, to_binary, wasm_execute, BankMsg, Binary, CosmosMsg,
+    Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use gridiron::asset::AssetInfo;
+use gridiron::burn_manager::{
+    BurnStats, BurnStatsResponse, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use gridiron::querier::query_token_balance;
+use gridiron::staking::{
+    ConfigResponse as StakingConfigResponse, Cw20HookMsg as StakingCw20HookMsg,
+    QueryMsg as StakingQueryMsg,
+};
+
+use crate::error::ContractError;
+use crate::state::{BURN_STATS, CONFIG, OWNERSHIP_PROPOSAL};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-burn-manager";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    msg.grid_token.check(deps.api)?;
+
+    if msg.epoch_length == 0 {
+        return Err(ContractError::InvalidEpochLength {});
+    }
+
+    let mut config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        grid_token: msg.grid_token,
+        staking_contract: None,
+        xgrid_token: None,
+        epoch_length: msg.epoch_length,
+        epoch_burn_cap: msg.epoch_burn_cap.filter(|cap| !cap.is_zero()),
+        start_time: env.block.time.seconds(),
+    };
+
+    if let Some(staking_contract) = msg.staking_contract {
+        set_staking_contract(deps.as_ref(), &mut config, &staking_contract)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    BURN_STATS.save(deps.storage, &BurnStats::default())?;
+
+    Ok(Response::new())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::Burn {}** Burns GRID held by the contract up to the remaining cap of the current epoch.
+///
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
+/// depending on the received template.
+///
+/// * **ExecuteMsg::UpdateConfig { staking_contract, epoch_burn_cap }** Updates contract parameters.
+///
+/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
+///
+/// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
+///
+/// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Burn {} => burn(deps, env),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::UpdateConfig {
+            staking_contract,
+            epoch_burn_cap,
+        } => update_config(deps, info, staking_contract, epoch_burn_cap),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Records GRID and xGRID donations. Donated tokens are burned by the next [`ExecuteMsg::Burn`] call.
+///
+/// * **cw20_msg** CW20 message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Donate {} => {
+            let mut stats = BURN_STATS.load(deps.storage)?;
+
+            let is_grid = matches!(
+                &config.grid_token,
+                AssetInfo::Token { contract_addr } if *contract_addr == info.sender
+            );
+            if is_grid {
+                stats.total_grid_donated = stats.total_grid_donated.checked_add(cw20_msg.amount)?;
+            } else if config.xgrid_token.as_ref() == Some(&info.sender) {
+                stats.total_xgrid_donated =
+                    stats.total_xgrid_donated.checked_add(cw20_msg.amount)?;
+            } else {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            BURN_STATS.save(deps.storage, &stats)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "donate"),
+                attr("from", cw20_msg.sender),
+                attr("token", info.sender),
+                attr("amount", cw20_msg.amount),
+            ]))
+        }
+    }
+}
+
+/// Burns GRID held by the contract up to the amount still allowed in the current epoch.
+/// xGRID held by the contract is sent to the staking contract to be unstaked; the GRID it
+/// returns is burned by the next call.
+fn burn(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut stats = BURN_STATS.load(deps.storage)?;
+
+    let epoch = current_epoch(&config, &env);
+    if stats.epoch != epoch {
+        stats.epoch = epoch;
+        stats.epoch_burned = Uint128::zero();
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    let mut xgrid_amount = Uint128::zero();
+    if let (Some(staking_contract), Some(xgrid_token)) =
+        (&config.staking_contract, &config.xgrid_token)
+    {
+        xgrid_amount = query_token_balance(&deps.querier, xgrid_token, &env.contract.address)?;
+        if !xgrid_amount.is_zero() {
+            messages.push(
+                wasm_execute(
+                    xgrid_token,
+                    &Cw20ExecuteMsg::Send {
+                        contract: staking_contract.to_string(),
+                        amount: xgrid_amount,
+                        msg: to_binary(&StakingCw20HookMsg::Leave {})?,
+                    },
+                    vec![],
+                )?
+                .into(),
+            );
+            stats.total_xgrid_unstaked = stats.total_xgrid_unstaked.checked_add(xgrid_amount)?;
+        }
+    }
+
+    let balance = config
+        .grid_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    let burn_amount = match config.epoch_burn_cap {
+        Some(cap) => balance.min(cap.saturating_sub(stats.epoch_burned)),
+        None => balance,
+    };
+
+    if burn_amount.is_zero() && xgrid_amount.is_zero() {
+        return Err(ContractError::NothingToBurn {});
+    }
+
+    if !burn_amount.is_zero() {
+        messages.push(burn_msg(&config.grid_token, burn_amount)?);
+
+        stats.total_burned = stats.total_burned.checked_add(burn_amount)?;
+        stats.epoch_burned = stats.epoch_burned.checked_add(burn_amount)?;
+        stats.burns_count += 1;
+        stats.last_burn_time = Some(env.block.time.seconds());
+    }
+
+    BURN_STATS.save(deps.storage, &stats)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "burn"),
+        attr("grid_burned", burn_amount),
+        attr("xgrid_unstaked", xgrid_amount),
+        attr("epoch", epoch.to_string()),
+        attr("epoch_burned", stats.epoch_burned),
+        attr("total_burned", stats.total_burned),
+    ]))
+}
+
+/// Updates contract parameters.
+///
+/// * **staking_contract** new xGRID staking contract address.
+///
+/// * **epoch_burn_cap** new maximum amount of GRID burned per epoch. Zero removes the cap.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_contract: Option<String>,
+    epoch_burn_cap: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(staking_contract) = staking_contract {
+        set_staking_contract(deps.as_ref(), &mut config, &staking_contract)?;
+        attributes.push(attr("staking_contract", staking_contract));
+    }
+
+    if let Some(epoch_burn_cap) = epoch_burn_cap {
+        config.epoch_burn_cap = Some(epoch_burn_cap).filter(|cap| !cap.is_zero());
+        attributes.push(attr("epoch_burn_cap", epoch_burn_cap));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Sets the staking contract and its xGRID token in the config. The staking contract must
+/// stake the GRID token of this contract.
+fn set_staking_contract(
+    deps: Deps,
+    config: &mut Config,
+    staking_contract: &str,
+) -> Result<(), ContractError> {
+    let staking_contract = deps.api.addr_validate(staking_contract)?;
+    let staking_config: StakingConfigResponse = deps
+        .querier
+        .query_wasm_smart(&staking_contract, &StakingQueryMsg::Config {})?;
+
+    let stakes_grid = matches!(
+        &config.grid_token,
+        AssetInfo::Token { contract_addr } if *contract_addr == staking_config.deposit_token_addr
+    );
+    if !stakes_grid {
+        return Err(ContractError::InvalidStakingContract(
+            staking_contract.to_string(),
+        ));
+    }
+
+    config.staking_contract = Some(staking_contract);
+    config.xgrid_token = Some(staking_config.share_token_addr);
+
+    Ok(())
+}
+
+/// Builds a message that burns `amount` of GRID held by the contract.
+fn burn_msg(grid_token: &AssetInfo, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(match grid_token {
+        AssetInfo::Token { contract_addr } => {
+            wasm_execute(contract_addr, &Cw20ExecuteMsg::Burn { amount }, vec![])?.into()
+        }
+        AssetInfo::NativeToken { denom } => BankMsg::Burn {
+            amount: coins(amount.u128(), denom),
+        }
+        .into(),
+    })
+}
+
+/// Returns the epoch that contains the current block.
+fn current_epoch(config: &Config, env: &Env) -> u64 {
+    env.block.time.seconds().saturating_sub(config.start_time) / config.epoch_length
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`Config`] object.
+///
+/// * **QueryMsg::BurnStats {}** Returns cumulative burn statistics using a [`BurnStatsResponse`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::BurnStats {} => to_binary(&query_burn_stats(deps, env)?),
+    }
+}
+
+/// Returns cumulative burn statistics and the state of the current epoch.
+fn query_burn_stats(deps: Deps, env: Env) -> StdResult<BurnStatsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let stats = BURN_STATS.load(deps.storage)?;
+
+    let current_epoch = current_epoch(&config, &env);
+    let epoch_burned = if stats.epoch == current_epoch {
+        stats.epoch_burned
+    } else {
+        Uint128::zero()
+    };
+
+    Ok(BurnStatsResponse {
+        current_epoch,
+        epoch_remaining: config
+            .epoch_burn_cap
+            .map(|cap| cap.saturating_sub(epoch_burned)),
+        pending_burn: config
+            .grid_token
+            .query_pool(&deps.querier, &env.contract.address)?,
+        stats,
+    })
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// This enum describes burn manager contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Epoch length must be greater than zero")]
+    InvalidEpochLength {},
+
+    #[error("Staking contract {0} doesn't stake the GRID token")]
+    InvalidStakingContract(String),
+
+    #[error("Nothing to burn")]
+    NothingToBurn {},
+}
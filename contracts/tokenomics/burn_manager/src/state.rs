@@ -0,0 +1,12 @@
+use cw_storage_plus::Item;
+use gridiron::burn_manager::{BurnStats, Config};
+use gridiron::common::OwnershipProposal;
+
+/// Stores the contract config at the given key.
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores cumulative burn statistics.
+pub const BURN_STATS: Item<BurnStats> = Item::new("burn_stats");
+
+/// Contains a proposal to change contract ownership.
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -0,0 +1,374 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{to_binary, Addr, Uint128};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse, TokenInfoResponse};
+use cw_multi_test::{App, ContractWrapper, Executor};
+
+use gridiron::asset::token_asset_info;
+use gridiron::burn_manager::{
+    BurnStatsResponse, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+use gridiron::staking::{
+    ConfigResponse as StakingConfigResponse, Cw20HookMsg as StakingCw20HookMsg,
+    InstantiateMsg as StakingInstantiateMsg, QueryMsg as StakingQueryMsg,
+};
+use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
+use gridiron_burn_manager::error::ContractError;
+
+const OWNER: &str = "owner";
+const ALICE: &str = "alice";
+const EPOCH_LENGTH: u64 = 86400;
+
+struct Contracts {
+    grid_token: Addr,
+    xgrid_token: Addr,
+    staking: Addr,
+    burn_manager_code_id: u64,
+    token_code_id: u64,
+}
+
+fn mock_app() -> App {
+    App::default()
+}
+
+fn instantiate_token(app: &mut App, token_code_id: u64, name: &str) -> Addr {
+    let msg = TokenInstantiateMsg {
+        name: name.to_string(),
+        symbol: name.to_string(),
+        decimals: 6,
+        initial_balances: vec![],
+        mint: Some(MinterResponse {
+            minter: OWNER.to_string(),
+            cap: None,
+        }),
+        marketing: None,
+    };
+
+    app.instantiate_contract(token_code_id, Addr::unchecked(OWNER), &msg, &[], name, None)
+        .unwrap()
+}
+
+fn instantiate_contracts(app: &mut App) -> Contracts {
+    let token_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_token::contract::execute,
+        gridiron_token::contract::instantiate,
+        gridiron_token::contract::query,
+    )));
+    let xgrid_token_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_xgrid_token::contract::execute,
+        gridiron_xgrid_token::contract::instantiate,
+        gridiron_xgrid_token::contract::query,
+    )));
+    let staking_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_staking::contract::execute,
+            gridiron_staking::contract::instantiate,
+            gridiron_staking::contract::query,
+        )
+        .with_reply_empty(gridiron_staking::contract::reply),
+    ));
+    let burn_manager_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_burn_manager::contract::execute,
+        gridiron_burn_manager::contract::instantiate,
+        gridiron_burn_manager::contract::query,
+    )));
+
+    let grid_token = instantiate_token(app, token_code_id, "GRID");
+
+    let staking = app
+        .instantiate_contract(
+            staking_code_id,
+            Addr::unchecked(OWNER),
+            &StakingInstantiateMsg {
+                owner: OWNER.to_string(),
+                token_code_id: xgrid_token_code_id,
+                deposit_token_addr: grid_token.to_string(),
+                marketing: None,
+                revenue_asset: None,
+                redemption: None,
+            },
+            &[],
+            "xGRID",
+            None,
+        )
+        .unwrap();
+    let xgrid_token = app
+        .wrap()
+        .query_wasm_smart::<StakingConfigResponse>(&staking, &StakingQueryMsg::Config {})
+        .unwrap()
+        .share_token_addr;
+
+    Contracts {
+        grid_token,
+        xgrid_token,
+        staking,
+        burn_manager_code_id,
+        token_code_id,
+    }
+}
+
+fn mint(app: &mut App, token: &Addr, recipient: &Addr, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        token.clone(),
+        &Cw20ExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn balance(app: &App, token: &Addr, address: &Addr) -> u128 {
+    app.wrap()
+        .query_wasm_smart::<BalanceResponse>(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+        .balance
+        .u128()
+}
+
+fn total_supply(app: &App, token: &Addr) -> u128 {
+    app.wrap()
+        .query_wasm_smart::<TokenInfoResponse>(token, &Cw20QueryMsg::TokenInfo {})
+        .unwrap()
+        .total_supply
+        .u128()
+}
+
+fn burn_stats(app: &App, burn_manager: &Addr) -> BurnStatsResponse {
+    app.wrap()
+        .query_wasm_smart(burn_manager, &QueryMsg::BurnStats {})
+        .unwrap()
+}
+
+#[test]
+fn burn_with_epoch_cap() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked(ALICE);
+    let contracts = instantiate_contracts(&mut app);
+
+    let mut init_msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        grid_token: token_asset_info(contracts.grid_token.clone()),
+        staking_contract: Some(contracts.staking.to_string()),
+        epoch_length: 0,
+        epoch_burn_cap: Some(Uint128::new(1000)),
+    };
+    let err = app
+        .instantiate_contract(
+            contracts.burn_manager_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "Burn manager",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidEpochLength {}
+    );
+
+    init_msg.epoch_length = EPOCH_LENGTH;
+    let burn_manager = app
+        .instantiate_contract(
+            contracts.burn_manager_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "Burn manager",
+            None,
+        )
+        .unwrap();
+
+    let config: Config = app
+        .wrap()
+        .query_wasm_smart(&burn_manager, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.xgrid_token, Some(contracts.xgrid_token.clone()));
+
+    // GRID sent directly, e.g. by the Maker
+    mint(&mut app, &contracts.grid_token, &burn_manager, 5000);
+    mint(&mut app, &contracts.grid_token, &alice, 10000);
+
+    let res = app
+        .execute_contract(
+            alice.clone(),
+            burn_manager.clone(),
+            &ExecuteMsg::Burn {},
+            &[],
+        )
+        .unwrap();
+    let burn_event = res.events.iter().find(|event| event.ty == "wasm").unwrap();
+    assert!(burn_event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "grid_burned" && attr.value == "1000"));
+
+    assert_eq!(balance(&app, &contracts.grid_token, &burn_manager), 4000);
+    assert_eq!(total_supply(&app, &contracts.grid_token), 14000);
+
+    let stats = burn_stats(&app, &burn_manager);
+    assert_eq!(stats.stats.total_burned.u128(), 1000);
+    assert_eq!(stats.stats.burns_count, 1);
+    assert_eq!(stats.current_epoch, 0);
+    assert_eq!(stats.epoch_remaining, Some(Uint128::zero()));
+    assert_eq!(stats.pending_burn.u128(), 4000);
+
+    // The epoch cap is exhausted
+    let err = app
+        .execute_contract(
+            alice.clone(),
+            burn_manager.clone(),
+            &ExecuteMsg::Burn {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToBurn {}
+    );
+
+    // Donations
+    app.execute_contract(
+        alice.clone(),
+        contracts.grid_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: burn_manager.to_string(),
+            amount: Uint128::new(500),
+            msg: to_binary(&Cw20HookMsg::Donate {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        alice.clone(),
+        contracts.grid_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: contracts.staking.to_string(),
+            amount: Uint128::new(2000),
+            msg: to_binary(&StakingCw20HookMsg::Enter {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        alice.clone(),
+        contracts.xgrid_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: burn_manager.to_string(),
+            amount: Uint128::new(500),
+            msg: to_binary(&Cw20HookMsg::Donate {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let other_token = instantiate_token(&mut app, contracts.token_code_id, "OTHER");
+    mint(&mut app, &other_token, &alice, 100);
+    let err = app
+        .execute_contract(
+            alice.clone(),
+            other_token,
+            &Cw20ExecuteMsg::Send {
+                contract: burn_manager.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&Cw20HookMsg::Donate {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let stats = burn_stats(&app, &burn_manager);
+    assert_eq!(stats.stats.total_grid_donated.u128(), 500);
+    assert_eq!(stats.stats.total_xgrid_donated.u128(), 500);
+
+    // A new epoch starts. xGRID is unstaked and GRID is burned up to the cap
+    app.update_block(|block| block.time = block.time.plus_seconds(EPOCH_LENGTH));
+    app.execute_contract(
+        alice.clone(),
+        burn_manager.clone(),
+        &ExecuteMsg::Burn {},
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, &contracts.xgrid_token, &burn_manager), 0);
+    assert_eq!(balance(&app, &contracts.grid_token, &burn_manager), 4000);
+
+    let stats = burn_stats(&app, &burn_manager);
+    assert_eq!(stats.stats.total_burned.u128(), 2000);
+    assert_eq!(stats.stats.total_xgrid_unstaked.u128(), 500);
+    assert_eq!(stats.stats.epoch, 1);
+    assert_eq!(stats.stats.epoch_burned.u128(), 1000);
+    assert_eq!(stats.current_epoch, 1);
+
+    // Only the owner can update the config
+    let update_msg = ExecuteMsg::UpdateConfig {
+        staking_contract: None,
+        epoch_burn_cap: Some(Uint128::zero()),
+    };
+    let err = app
+        .execute_contract(alice.clone(), burn_manager.clone(), &update_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // Removing the cap burns the whole balance
+    app.execute_contract(owner, burn_manager.clone(), &update_msg, &[])
+        .unwrap();
+    app.execute_contract(alice, burn_manager.clone(), &ExecuteMsg::Burn {}, &[])
+        .unwrap();
+
+    assert_eq!(balance(&app, &contracts.grid_token, &burn_manager), 0);
+
+    let stats = burn_stats(&app, &burn_manager);
+    assert_eq!(stats.stats.total_burned.u128(), 6000);
+    assert_eq!(stats.stats.burns_count, 3);
+    assert_eq!(stats.epoch_remaining, None);
+    assert_eq!(stats.pending_burn.u128(), 0);
+}
+
+#[test]
+fn staking_contract_must_stake_grid() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
+    let contracts = instantiate_contracts(&mut app);
+
+    let other_token = instantiate_token(&mut app, contracts.token_code_id, "OTHER");
+
+    let err = app
+        .instantiate_contract(
+            contracts.burn_manager_code_id,
+            owner,
+            &InstantiateMsg {
+                owner: OWNER.to_string(),
+                grid_token: token_asset_info(other_token),
+                staking_contract: Some(contracts.staking.to_string()),
+                epoch_length: EPOCH_LENGTH,
+                epoch_burn_cap: None,
+            },
+            &[],
+            "Burn manager",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidStakingContract(contracts.staking.to_string())
+    );
+}
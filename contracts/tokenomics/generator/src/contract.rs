@@ -1,45 +1,79 @@
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 
+#[cfg(not(feature = "bank"))]
+use cosmwasm_std::from_binary;
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal,
-    Deps, DepsMut, Empty, Env, MessageInfo, Order, QuerierWrapper, Reply, Response, StdError,
-    StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint64, WasmMsg,
+    attr, entry_point, to_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Env, MessageInfo, Order, QuerierWrapper, Reply, Response, StdError, StdResult,
+    Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint64, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
-use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw20::Cw20ExecuteMsg;
+#[cfg(not(feature = "bank"))]
+use cw20::{BalanceResponse, Cw20QueryMsg, Cw20ReceiveMsg, MinterResponse, TokenInfoResponse};
 use cw_storage_plus::Bound;
-use cw_utils::parse_instantiate_response_data;
+use cw_utils::{one_coin, parse_instantiate_response_data};
 
 use crate::error::ContractError;
+use crate::lp_token::{
+    assert_bank_mode, lp_asset_info, lp_pair_info, validate_lp_token, validate_lp_tokens,
+};
+use crate::merkle_drop::{
+    claim_merkle_drop, query_merkle_drop, query_merkle_drop_claimed, reclaim_merkle_drop,
+    register_merkle_drop,
+};
 use crate::migration;
 
-use gridiron::asset::{addr_opt_validate, pair_info_by_pool, Asset, AssetInfo, PairInfo};
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, AssetInfoExt, PairInfo};
 
-use gridiron::common::{
-    claim_ownership, drop_ownership_proposal, propose_new_owner, validate_addresses,
-};
+use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use gridiron::events::ClaimEvent;
 use gridiron::factory::PairType;
-use gridiron::generator::{Config, ExecuteOnReply, PoolInfo};
-use gridiron::generator::{StakerResponse, UserInfoV2};
-use gridiron::querier::query_token_balance;
+use gridiron::generator::{Config, ExecuteOnReply, PoolInfo, RewardProxyInfo};
+use gridiron::generator::{
+    PoolSnapshot, ScheduledEmission, StakerResponse, StateSnapshotResponse, UserInfoV2,
+    MAX_SCHEDULED_EMISSIONS, STATE_SNAPSHOT_SCHEMA_VERSION,
+};
 use gridiron::{
     factory::{ConfigResponse as FactoryConfigResponse, QueryMsg as FactoryQueryMsg},
     generator::{
-        Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PendingTokenResponse,
-        PoolInfoResponse, QueryMsg, RewardInfoResponse,
+        ExecuteMsg, InstantiateMsg, MigrateMsg, PendingTokenResponse, PoolInfoResponse,
+        PoolTvlResponse, QueryMsg, RewardInfoResponse, UserEmissionRateResponse,
     },
     generator_proxy::{
         Cw20HookMsg as ProxyCw20HookMsg, ExecuteMsg as ProxyExecuteMsg, QueryMsg as ProxyQueryMsg,
     },
+    pair::{PoolResponse, QueryMsg as PairQueryMsg, MAX_ALLOWED_SLIPPAGE},
+    points::lp_hook_msg,
+    querier::{query_factory_config, query_token_balance},
+    router::{Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    staking::{
+        ConfigResponse as StakingConfigResponse, Cw20HookMsg as StakingCw20HookMsg,
+        QueryMsg as StakingQueryMsg,
+    },
     vesting::ExecuteMsg as VestingExecuteMsg,
     DecimalCheckedOps,
 };
+#[cfg(not(feature = "bank"))]
+use gridiron::{
+    generator::Cw20HookMsg, pair::Cw20HookMsg as PairCw20HookMsg,
+    token::InstantiateMsg as TokenInstantiateMsg,
+};
 
+use crate::state::REENTRANCY_LOCK;
 use crate::state::{
     accumulate_pool_proxy_rewards, query_lp_balance, update_proxy_asset, update_user_balance,
     update_virtual_amount, CompatibleLoader, CHECKPOINT_GENERATORS_LIMIT, CONFIG, DEFAULT_LIMIT,
-    MAX_LIMIT, OWNERSHIP_PROPOSAL, POOL_INFO, PROXY_REWARDS_HOLDER, PROXY_REWARD_ASSET, USER_INFO,
+    DEFAULT_UPDATE_POOLS_LIMIT, MAX_LIMIT, MAX_UPDATE_POOLS_LIMIT, OWNERSHIP_PROPOSAL, POOL_INFO,
+    PROXY_REWARDS_HOLDER, PROXY_REWARD_ASSET, UPDATE_POOLS_CURSOR, USER_INFO,
 };
+use crate::state::{AutoStakeContext, AUTO_STAKE_CONTEXT, AUTO_STAKE_REWARDS};
+use crate::state::{SwapRewardsContext, SWAP_REWARDS_CONTEXT};
+#[cfg(not(feature = "bank"))]
+use crate::state::{UnwrapContext, UNWRAP_CONTEXT};
+use crate::state::{EMISSION_SCHEDULE, MERKLE_DROPS, MERKLE_DROP_COUNT};
+use crate::state::{RECEIPT_BACKED, RECEIPT_TOKENS, RECEIPT_TOKEN_CONTEXT, RECEIPT_TOKEN_LP};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "gridiron-generator";
@@ -47,6 +81,14 @@ const CONTRACT_NAME: &str = "gridiron-generator";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INIT_REWARDS_HOLDER_ID: u64 = 1;
+const AUTO_STAKE_REWARDS_ID: u64 = 2;
+const INIT_RECEIPT_TOKEN_ID: u64 = 3;
+#[cfg(not(feature = "bank"))]
+const WITHDRAW_AND_UNWRAP_ID: u64 = 4;
+const SWAP_REWARDS_ID: u64 = 5;
+/// Symbol of generator receipt tokens
+#[cfg(not(feature = "bank"))]
+const RECEIPT_TOKEN_SYMBOL: &str = "stkLP";
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`] struct.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -64,6 +106,10 @@ pub fn instantiate(
     let voting_escrow = addr_opt_validate(deps.api, &msg.voting_escrow)?;
 
     msg.grid_token.check(deps.api)?;
+    #[cfg(feature = "bank")]
+    if !msg.grid_token.is_native_token() {
+        return Err(ContractError::Cw20Disabled {});
+    }
 
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
@@ -80,6 +126,13 @@ pub fn instantiate(
         checkpoint_generator_limit: None,
         voting_escrow_delegation,
         voting_escrow,
+        proxy_rewards_claim_interval: None,
+        emissions_paused: false,
+        staking_contract: None,
+        max_pool_share: None,
+        blocked_pools: vec![],
+        grid_pool_boost: None,
+        router: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -102,6 +155,7 @@ pub fn instantiate(
 ///         }** Changes the address of the Generator vesting contract, Generator controller contract or Generator guardian.
 ///
 /// * **ExecuteMsg::SetupPools { pools }** Setting up a new list of pools with allocation points.
+/// Blocked pools are skipped and the share of each pool is capped by `max_pool_share`.
 ///
 /// * **UpdatePool {
 ///             lp_token,
@@ -110,8 +164,20 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::ClaimRewards { lp_token }** Updates reward and returns it to user.
 ///
+/// * **ExecuteMsg::ClaimRewardsAs { lp_token, target_asset, min_received }** Updates reward and
+/// returns it to user with GRID swapped into the target asset via the router.
+///
 /// * **ExecuteMsg::Withdraw { lp_token, amount }** Withdraw LP tokens from the Generator.
 ///
+/// * **ExecuteMsg::WithdrawAndUnwrap { lp_token, amount, min_assets }** Withdraw LP tokens from the
+/// Generator and send the pool assets they represent to the sender.
+///
+/// * **ExecuteMsg::DepositFor { lp_token, beneficiary, amount }** Stakes LP tokens taken from the sender's
+/// allowance on behalf of the beneficiary.
+///
+/// * **ExecuteMsg::Deposit { beneficiary }** Stakes native LP tokens sent along with the message.
+/// Only available in bank mode.
+///
 /// * **ExecuteMsg::EmergencyWithdraw { lp_token }** Withdraw LP tokens without caring about reward claiming.
 /// TO BE USED IN EMERGENCY SITUATIONS ONLY.
 ///
@@ -125,6 +191,15 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::SetTokensPerBlock { amount }** Sets a new amount of GRID that's distributed per block among all active generators.
 ///
+/// * **ExecuteMsg::ScheduleTokensPerBlock { amount, activation_height }** Queues a new amount of
+/// GRID per block which takes effect at the activation height.
+///
+/// * **ExecuteMsg::CancelScheduledTokensPerBlock { activation_height }** Cancels a queued change
+/// of GRID per block.
+///
+/// * **ExecuteMsg::SetEmissionsPaused { lp_token, paused }** Pauses or resumes accrual of new GRID emissions
+/// for a specific generator or for all generators.
+///
 /// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
 /// Only the current owner can call this.
 ///
@@ -140,8 +215,35 @@ pub fn instantiate(
 /// * **ExecuteMsg::DeactivatePools { pair_types }** Sets the allocation point to zero for each pool
 /// by the pair type
 ///
+/// * **ExecuteMsg::UpdateBlockedPools { add, remove }** Adds or removes pools to and from the
+/// blocked list.
+///
 /// * **ExecuteMsg::CheckpointUserBoost { user, generators }** Updates the boost emissions for
 /// specified user and generators
+///
+/// * **ExecuteMsg::SetAutoStakeRewards { enabled }** Enables or disables auto-staking of the
+/// sender's claimed GRID rewards into xGRID.
+///
+/// * **ExecuteMsg::SetupReceiptToken { lp_token, token_code_id }** Instantiates a receipt token
+/// for positions staked in a specific generator.
+///
+/// * **ExecuteMsg::MintReceipt { lp_token, amount }** Mints receipt tokens backed by the sender's
+/// staked LP tokens.
+///
+/// * **ExecuteMsg::BurnReceipt { lp_token, amount }** Burns the sender's receipt tokens.
+///
+/// * **ExecuteMsg::ReceiptTransferHook { from, to, amount }** Moves the staked position backing
+/// transferred receipt tokens.
+///
+/// * **ExecuteMsg::RegisterMerkleDrop { merkle_root, total_amount, expires_at }** Posts a merkle root
+/// of retroactive GRID rewards.
+///
+/// * **ExecuteMsg::ClaimMerkleDrop { drop_id, amount, proof }** Claims retroactive GRID rewards.
+///
+/// * **ExecuteMsg::ReclaimMerkleDrop { drop_id }** Returns unclaimed GRID of an expired merkle drop.
+///
+/// * **ExecuteMsg::UpdatePools { start_after, limit }** Updates the accrued GRID rewards of a page
+/// of active generators.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     mut deps: DepsMut,
@@ -149,6 +251,16 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Reward proxies are external contracts. While their reward claims are in flight the pools
+    // are only half updated, so nothing but the generator's own callback may run.
+    if !matches!(msg, ExecuteMsg::Callback { .. })
+        && REENTRANCY_LOCK.may_load(deps.storage)?.unwrap_or_default()
+    {
+        return Err(ContractError::ReentrancyDetected {});
+    }
+
+    apply_emission_schedule(deps.branch(), &env)?;
+
     match msg {
         ExecuteMsg::CheckpointUserBoost { generators, user } => {
             checkpoint_user_boost(deps, env, info, generators, user)
@@ -161,7 +273,7 @@ pub fn execute(
             if info.sender != cfg.factory {
                 return Err(ContractError::Unauthorized {});
             }
-            let lp_token_addr = deps.api.addr_validate(&lp_token)?;
+            let lp_token_addr = validate_lp_token(deps.api, &lp_token)?;
             let active_pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
             mass_update_pools(deps.branch(), &env, &cfg, &active_pools)?;
             deactivate_pool(deps, cfg, lp_token_addr)
@@ -169,13 +281,25 @@ pub fn execute(
         ExecuteMsg::UpdateBlockedTokenslist { add, remove } => {
             update_blocked_tokens_list(deps, env, info, add, remove)
         }
+        ExecuteMsg::UpdateBlockedPools { add, remove } => {
+            update_blocked_pools(deps, env, info, add, remove)
+        }
+        #[cfg(not(feature = "bank"))]
         ExecuteMsg::MoveToProxy { lp_token, proxy } => {
             move_to_proxy(deps, env, info, lp_token, proxy)
         }
+        #[cfg(not(feature = "bank"))]
         ExecuteMsg::MigrateProxy {
             lp_token,
             new_proxy,
         } => migrate_proxy(deps, env, info, lp_token, new_proxy),
+        #[cfg(not(feature = "bank"))]
+        ExecuteMsg::AddRewardProxy { lp_token, proxy } => {
+            add_reward_proxy(deps, env, info, lp_token, proxy)
+        }
+        ExecuteMsg::DeactivateRewardProxy { lp_token, proxy } => {
+            deactivate_reward_proxy(deps, env, info, lp_token, proxy)
+        }
         ExecuteMsg::UpdateConfig {
             vesting_contract,
             generator_controller,
@@ -183,6 +307,11 @@ pub fn execute(
             voting_escrow_delegation,
             voting_escrow,
             checkpoint_generator_limit,
+            proxy_rewards_claim_interval,
+            staking_contract,
+            max_pool_share,
+            grid_pool_boost,
+            router,
         } => execute_update_config(
             deps,
             info,
@@ -192,10 +321,15 @@ pub fn execute(
             voting_escrow_delegation,
             voting_escrow,
             checkpoint_generator_limit,
+            proxy_rewards_claim_interval,
+            staking_contract,
+            max_pool_share,
+            grid_pool_boost,
+            router,
         ),
         ExecuteMsg::SetupPools { pools } => execute_setup_pools(deps, env, info, pools),
         ExecuteMsg::ClaimRewards { lp_tokens } => {
-            let lp_tokens_addr = validate_addresses(deps.api, &lp_tokens)?;
+            let lp_tokens_addr = validate_lp_tokens(deps.api, &lp_tokens)?;
 
             update_rewards_and_execute(
                 deps,
@@ -207,11 +341,38 @@ pub fn execute(
                 },
             )
         }
+        ExecuteMsg::ClaimRewardsAs {
+            lp_token,
+            target_asset,
+            min_received,
+        } => {
+            let cfg = CONFIG.load(deps.storage)?;
+            if cfg.router.is_none() {
+                return Err(ContractError::ClaimAsNotAvailable {});
+            }
+            target_asset.check(deps.api)?;
+            if target_asset == cfg.grid_token {
+                return Err(ContractError::InvalidClaimAsTarget {});
+            }
+            let lp_token = validate_lp_token(deps.api, &lp_token)?;
+
+            update_rewards_and_execute(
+                deps,
+                env,
+                Some(vec![lp_token.clone()]),
+                ExecuteOnReply::ClaimRewardsAs {
+                    lp_token,
+                    account: info.sender,
+                    target_asset,
+                    min_received,
+                },
+            )
+        }
         ExecuteMsg::Withdraw { lp_token, amount } => {
             if amount.is_zero() {
                 return Err(ContractError::ZeroWithdraw {});
             }
-            let lp_token = deps.api.addr_validate(&lp_token)?;
+            let lp_token = validate_lp_token(deps.api, &lp_token)?;
 
             update_rewards_and_execute(
                 deps.branch(),
@@ -224,12 +385,99 @@ pub fn execute(
                 },
             )
         }
+        #[cfg(not(feature = "bank"))]
+        ExecuteMsg::WithdrawAndUnwrap {
+            lp_token,
+            amount,
+            min_assets,
+        } => {
+            if amount.is_zero() {
+                return Err(ContractError::ZeroWithdraw {});
+            }
+            let lp_token = validate_lp_token(deps.api, &lp_token)?;
+
+            update_rewards_and_execute(
+                deps.branch(),
+                env,
+                Some(vec![lp_token.clone()]),
+                ExecuteOnReply::WithdrawAndUnwrap {
+                    lp_token,
+                    account: info.sender,
+                    amount,
+                    min_assets: min_assets.unwrap_or_default(),
+                },
+            )
+        }
+        #[cfg(not(feature = "bank"))]
+        ExecuteMsg::DepositFor {
+            lp_token,
+            beneficiary,
+            amount,
+        } => deposit_for(deps, env, info, lp_token, beneficiary, amount),
+        ExecuteMsg::Deposit { beneficiary } => deposit_native(deps, env, info, beneficiary),
         ExecuteMsg::EmergencyWithdraw { lp_token } => emergency_withdraw(deps, info, lp_token),
         ExecuteMsg::SendOrphanProxyReward {
             recipient,
             lp_token,
         } => send_orphan_proxy_rewards(deps, info, recipient, lp_token),
+        #[cfg(not(feature = "bank"))]
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::SetEmissionsPaused { lp_token, paused } => {
+            set_emissions_paused(deps, env, info, lp_token, paused)
+        }
+        ExecuteMsg::SetAutoStakeRewards { enabled } => set_auto_stake_rewards(deps, info, enabled),
+        #[cfg(not(feature = "bank"))]
+        ExecuteMsg::SetupReceiptToken {
+            lp_token,
+            token_code_id,
+        } => setup_receipt_token(deps, env, info, lp_token, token_code_id),
+        // CW20 LP tokens, receipt tokens and reward proxies are compiled out of bank builds
+        #[cfg(feature = "bank")]
+        ExecuteMsg::Receive(_)
+        | ExecuteMsg::DepositFor { .. }
+        | ExecuteMsg::WithdrawAndUnwrap { .. }
+        | ExecuteMsg::SetupReceiptToken { .. }
+        | ExecuteMsg::MoveToProxy { .. }
+        | ExecuteMsg::MigrateProxy { .. }
+        | ExecuteMsg::AddRewardProxy { .. } => Err(ContractError::Cw20Disabled {}),
+        ExecuteMsg::MintReceipt { lp_token, amount } => mint_receipt(deps, info, lp_token, amount),
+        ExecuteMsg::BurnReceipt { lp_token, amount } => burn_receipt(deps, info, lp_token, amount),
+        ExecuteMsg::ReceiptTransferHook { from, to, amount } => {
+            let lp_token = RECEIPT_TOKEN_LP
+                .may_load(deps.storage, &info.sender)?
+                .ok_or(ContractError::Unauthorized {})?;
+            let from = deps.api.addr_validate(&from)?;
+            let to = deps.api.addr_validate(&to)?;
+            if from == to {
+                return Ok(Response::new());
+            }
+
+            update_rewards_and_execute(
+                deps,
+                env,
+                Some(vec![lp_token.clone()]),
+                ExecuteOnReply::MoveReceiptPosition {
+                    lp_token,
+                    from,
+                    to,
+                    amount,
+                },
+            )
+        }
+        ExecuteMsg::RegisterMerkleDrop {
+            merkle_root,
+            total_amount,
+            expires_at,
+        } => register_merkle_drop(deps, env, info, merkle_root, total_amount, expires_at),
+        ExecuteMsg::ClaimMerkleDrop {
+            drop_id,
+            amount,
+            proof,
+        } => claim_merkle_drop(deps, env, info, drop_id, amount, proof),
+        ExecuteMsg::ReclaimMerkleDrop { drop_id } => reclaim_merkle_drop(deps, env, drop_id),
+        ExecuteMsg::UpdatePools { start_after, limit } => {
+            update_pools(deps, env, start_after, limit)
+        }
         ExecuteMsg::SetTokensPerBlock { amount } => {
             let cfg = CONFIG.load(deps.storage)?;
             if info.sender != cfg.owner {
@@ -243,6 +491,13 @@ pub fn execute(
                 ExecuteOnReply::SetTokensPerBlock { amount },
             )
         }
+        ExecuteMsg::ScheduleTokensPerBlock {
+            amount,
+            activation_height,
+        } => schedule_tokens_per_block(deps, env, info, amount, activation_height),
+        ExecuteMsg::CancelScheduledTokensPerBlock { activation_height } => {
+            cancel_scheduled_tokens_per_block(deps, info, activation_height)
+        }
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
 
@@ -279,6 +534,7 @@ pub fn execute(
                 return Err(ContractError::Unauthorized {});
             }
 
+            REENTRANCY_LOCK.remove(deps.storage);
             handle_callback(deps, env, action)
         }
     }
@@ -312,7 +568,7 @@ fn checkpoint_user_boost(
 
     let mut send_rewards_msg: Vec<WasmMsg> = vec![];
     for generator in generators {
-        let lp_token = deps.api.addr_validate(&generator)?;
+        let lp_token = validate_lp_token(deps.api, &generator)?;
 
         // calculates the emission boost only for user who has LP in generator
         if USER_INFO.has(deps.storage, (&lp_token, &recipient_addr)) {
@@ -320,10 +576,18 @@ fn checkpoint_user_boost(
                 USER_INFO.compatible_load(deps.storage, (&lp_token, &recipient_addr))?;
 
             let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
-            accumulate_rewards_per_share(&deps.querier, &env, &lp_token, &mut pool, &config)?;
+            accumulate_rewards_per_share(
+                &deps.querier,
+                &env,
+                &lp_token,
+                &mut pool,
+                &config,
+                Uint128::zero(),
+            )?;
 
             send_rewards_msg.append(&mut send_pending_rewards(
                 deps.as_ref(),
+                &env,
                 &config,
                 &pool,
                 &user_info,
@@ -334,7 +598,7 @@ fn checkpoint_user_boost(
             let amount = user_info.amount;
             let mut user_info = update_user_balance(user_info, &pool, amount)?;
             let lp_balance =
-                query_lp_balance(deps.as_ref(), &env.contract.address, &lp_token, &pool)?;
+                query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
 
             // Update user's virtual amount
             update_virtual_amount(
@@ -395,7 +659,7 @@ fn deactivate_blacklisted(
     // find active pools with blacklisted pair type
     for pool in &mut cfg.active_pools {
         if !pool.1.is_zero() {
-            let pair_info = pair_info_by_pool(&deps.querier, &pool.0)?;
+            let pair_info = lp_pair_info(&deps.querier, &pool.0)?;
             if pair_types.contains(&pair_info.pair_type) {
                 // recalculate total allocation point before resetting the allocation point of pool
                 cfg.total_alloc_point = cfg.total_alloc_point.checked_sub(pool.1)?;
@@ -466,7 +730,7 @@ fn update_blocked_tokens_list(
 
                 // Find active pools with blacklisted tokens
                 for pool in &mut cfg.active_pools {
-                    let pair_info = pair_info_by_pool(&deps.querier, &pool.0)?;
+                    let pair_info = lp_pair_info(&deps.querier, &pool.0)?;
                     if pair_info.asset_infos.contains(&asset_info) {
                         // Recalculate total allocation points before resetting the pool allocation points
                         cfg.total_alloc_point = cfg.total_alloc_point.checked_sub(pool.1)?;
@@ -482,6 +746,63 @@ fn update_blocked_tokens_list(
     Ok(Response::new().add_attribute("action", "update_tokens_blockedlist"))
 }
 
+/// Add or remove pools to and from the blocked list.
+/// Allocation points of newly blocked pools are set to zero.
+fn update_blocked_pools(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    if add.is_none() && remove.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Need to provide add or remove parameters",
+        )));
+    }
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Remove pools from the blocked list
+    if let Some(lp_tokens) = remove {
+        for lp_token in validate_lp_tokens(deps.api, &lp_tokens)? {
+            let index = cfg
+                .blocked_pools
+                .iter()
+                .position(|x| *x == lp_token)
+                .ok_or_else(|| {
+                    StdError::generic_err("Can't remove pool. It is not found in the blocked list.")
+                })?;
+            cfg.blocked_pools.remove(index);
+        }
+    }
+
+    // Add pools to the blocked list
+    if let Some(lp_tokens) = add {
+        let active_pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
+        mass_update_pools(deps.branch(), &env, &cfg, &active_pools)?;
+
+        for lp_token in validate_lp_tokens(deps.api, &lp_tokens)? {
+            if !cfg.blocked_pools.contains(&lp_token) {
+                if let Some(pool) = cfg.active_pools.iter_mut().find(|pool| pool.0 == lp_token) {
+                    // Recalculate total allocation points before resetting the pool allocation points
+                    cfg.total_alloc_point = cfg.total_alloc_point.checked_sub(pool.1)?;
+                    pool.1 = Uint128::zero();
+                }
+                cfg.blocked_pools.push(lp_token);
+            }
+        }
+    }
+
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attribute("action", "update_blocked_pools"))
+}
+
 /// Sets a new Generator vesting contract address.
 ///
 /// * **vesting_contract** new vesting contract address.
@@ -490,6 +811,15 @@ fn update_blocked_tokens_list(
 ///
 /// * **guardian** new generator guardian address.
 ///
+/// * **proxy_rewards_claim_interval** minimum number of blocks between proxy reward claims
+/// triggered by deposits and withdrawals. Zero disables batching.
+///
+/// * **max_pool_share** maximum share of emissions a single pool can receive. Zero removes the cap.
+///
+/// * **grid_pool_boost** multiplier of allocation points of pools paired with GRID or xGRID. One removes the boost.
+///
+/// * **router** router contract used to swap claimed GRID with `ClaimRewardsAs`.
+///
 /// ## Executor
 /// Only the owner can execute this.
 #[allow(clippy::too_many_arguments)]
@@ -502,6 +832,11 @@ pub fn execute_update_config(
     voting_escrow_delegation: Option<String>,
     voting_escrow: Option<String>,
     checkpoint_generator_limit: Option<u32>,
+    proxy_rewards_claim_interval: Option<u64>,
+    staking_contract: Option<String>,
+    max_pool_share: Option<Decimal>,
+    grid_pool_boost: Option<Decimal>,
+    router: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
@@ -534,6 +869,32 @@ pub fn execute_update_config(
         config.voting_escrow = Some(deps.api.addr_validate(&voting_escrow)?);
     }
 
+    if let Some(interval) = proxy_rewards_claim_interval {
+        config.proxy_rewards_claim_interval = Some(interval).filter(|blocks| *blocks > 0);
+    }
+
+    if let Some(staking_contract) = staking_contract {
+        config.staking_contract = Some(deps.api.addr_validate(&staking_contract)?);
+    }
+
+    if let Some(max_pool_share) = max_pool_share {
+        if max_pool_share > Decimal::one() {
+            return Err(ContractError::InvalidMaxPoolShare {});
+        }
+        config.max_pool_share = Some(max_pool_share).filter(|share| !share.is_zero());
+    }
+
+    if let Some(grid_pool_boost) = grid_pool_boost {
+        if grid_pool_boost < Decimal::one() {
+            return Err(ContractError::InvalidGridPoolBoost {});
+        }
+        config.grid_pool_boost = Some(grid_pool_boost).filter(|boost| *boost != Decimal::one());
+    }
+
+    if let Some(router) = router {
+        config.router = Some(deps.api.addr_validate(&router)?);
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("action", "update_config"))
@@ -543,6 +904,9 @@ pub fn execute_update_config(
 /// total allocation points (in [`Config`]).
 ///
 /// * **pools** is a vector of set that contains LP token address and allocation point.
+/// Blocked pools are skipped, so their allocation points are redistributed among the other pools.
+/// If `grid_pool_boost` is set, allocation points of pools paired with GRID or xGRID are multiplied by it.
+/// If `max_pool_share` is set, each pool's share of the total allocation points is capped.
 ///
 /// ## Executor
 /// Can only be called by the owner or generator controller
@@ -568,9 +932,15 @@ pub fn execute_setup_pools(
         .querier
         .query_wasm_smart(&cfg.factory, &FactoryQueryMsg::BlacklistedPairTypes {})?;
 
+    let boosted_assets = grid_pool_boost_assets(deps.as_ref(), &cfg)?;
+
     for (addr, alloc_point) in pools {
-        let pool_addr = deps.api.addr_validate(&addr)?;
-        let pair_info = pair_info_by_pool(&deps.querier, &pool_addr)?;
+        let pool_addr = validate_lp_token(deps.api, &addr)?;
+        if cfg.blocked_pools.contains(&pool_addr) {
+            continue;
+        }
+
+        let pair_info = lp_pair_info(&deps.querier, &pool_addr)?;
 
         // check if assets in the blocked list
         for asset in &pair_info.asset_infos {
@@ -604,8 +974,26 @@ pub fn execute_setup_pools(
                 )))
             })?;
 
+        // The pair is registered, so its assets can be trusted
+        let alloc_point = match cfg.grid_pool_boost {
+            Some(boost)
+                if pair_info
+                    .asset_infos
+                    .iter()
+                    .any(|asset| boosted_assets.contains(asset)) =>
+            {
+                boost.checked_mul_uint128(alloc_point)?
+            }
+            _ => alloc_point,
+        };
+
         setup_pools.push((pool_addr, alloc_point));
     }
+
+    if let Some(max_pool_share) = cfg.max_pool_share {
+        apply_max_pool_share(&mut setup_pools, max_pool_share)?;
+    }
+
     let prev_pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
 
     mass_update_pools(deps.branch(), &env, &cfg, &prev_pools)?;
@@ -627,6 +1015,101 @@ pub fn execute_setup_pools(
     Ok(Response::new().add_attribute("action", "setup_pools"))
 }
 
+/// Returns the assets whose pools get their allocation points boosted: GRID and xGRID if the
+/// staking contract is set. Returns an empty list if `grid_pool_boost` is not set.
+fn grid_pool_boost_assets(deps: Deps, cfg: &Config) -> StdResult<Vec<AssetInfo>> {
+    if cfg.grid_pool_boost.is_none() {
+        return Ok(vec![]);
+    }
+
+    let mut assets = vec![cfg.grid_token.clone()];
+    if let Some(staking) = &cfg.staking_contract {
+        let staking_config: StakingConfigResponse = deps
+            .querier
+            .query_wasm_smart(staking, &StakingQueryMsg::Config {})?;
+        assets.push(AssetInfo::Token {
+            contract_addr: staking_config.share_token_addr,
+        });
+    }
+
+    Ok(assets)
+}
+
+/// Caps the share of total allocation points each pool gets at `max_pool_share`. Allocation
+/// points above the cap are redistributed among the other pools pro-rata to their own allocation
+/// points, capping them in turn if needed. The total amount of allocation points is kept
+/// (up to rounding).
+///
+/// * **pools** list of pools with their allocation points.
+///
+/// * **max_pool_share** maximum share of total allocation points a single pool can get.
+fn apply_max_pool_share(
+    pools: &mut [(Addr, Uint128)],
+    max_pool_share: Decimal,
+) -> Result<(), ContractError> {
+    let total_alloc_point: Uint128 = pools.iter().map(|(_, alloc_point)| alloc_point).sum();
+    if total_alloc_point.is_zero() {
+        return Ok(());
+    }
+
+    let voted_pools = pools
+        .iter()
+        .filter(|(_, alloc_point)| !alloc_point.is_zero())
+        .count();
+    if Decimal::from_ratio(1u128, voted_pools as u128) > max_pool_share {
+        return Err(ContractError::PoolShareCapTooLow {
+            max_pool_share,
+            pools: voted_pools,
+        });
+    }
+
+    let mut capped = vec![false; pools.len()];
+    loop {
+        let capped_amount = capped.iter().filter(|is_capped| **is_capped).count();
+        let remaining_share =
+            Decimal::one() - max_pool_share * Decimal::from_ratio(capped_amount as u128, 1u128);
+        let remaining_alloc_point: Uint128 = pools
+            .iter()
+            .zip(&capped)
+            .filter(|(_, is_capped)| !**is_capped)
+            .map(|((_, alloc_point), _)| alloc_point)
+            .sum();
+
+        let mut newly_capped = false;
+        if !remaining_alloc_point.is_zero() {
+            for ((_, alloc_point), is_capped) in pools.iter().zip(capped.iter_mut()) {
+                if !*is_capped
+                    && Decimal::from_ratio(*alloc_point, remaining_alloc_point) * remaining_share
+                        > max_pool_share
+                {
+                    *is_capped = true;
+                    newly_capped = true;
+                }
+            }
+        }
+
+        if !newly_capped {
+            if capped_amount == 0 {
+                // No pool exceeds the cap
+                return Ok(());
+            }
+
+            for ((_, alloc_point), is_capped) in pools.iter_mut().zip(&capped) {
+                let share = if *is_capped {
+                    max_pool_share
+                } else if remaining_alloc_point.is_zero() {
+                    Decimal::zero()
+                } else {
+                    Decimal::from_ratio(*alloc_point, remaining_alloc_point) * remaining_share
+                };
+                *alloc_point = share.checked_mul_uint128(total_alloc_point)?;
+            }
+
+            return Ok(());
+        }
+    }
+}
+
 /// Updates the amount of accrued rewards for a specific generator (if specified in input parameters), otherwise updates rewards for
 /// all pools that are in [`POOL_INFO`].
 ///
@@ -667,18 +1150,67 @@ fn update_rewards_and_execute(
         }
     };
 
+    // Deposits and withdrawals may skip the external claim if the pool's proxy rewards were
+    // claimed recently. Everything else must see up to date proxy rewards. Rewards left on the
+    // proxy are still accounted to the current stakers, so only the payouts depend on the claim.
+    let (claim_interval, paid_accounts, lp_received) = match &action_on_reply {
+        ExecuteOnReply::Deposit {
+            account, amount, ..
+        } => (
+            CONFIG.load(deps.storage)?.proxy_rewards_claim_interval,
+            vec![account],
+            *amount,
+        ),
+        ExecuteOnReply::Withdraw { account, .. }
+        | ExecuteOnReply::WithdrawAndUnwrap { account, .. } => (
+            CONFIG.load(deps.storage)?.proxy_rewards_claim_interval,
+            vec![account],
+            Uint128::zero(),
+        ),
+        ExecuteOnReply::MoveReceiptPosition { from, to, .. } => (
+            CONFIG.load(deps.storage)?.proxy_rewards_claim_interval,
+            vec![from, to],
+            Uint128::zero(),
+        ),
+        _ => (None, vec![], Uint128::zero()),
+    };
+
     let mut messages = vec![];
     for (lp_token, mut pool) in pools {
-        if let Some(reward_proxy) = pool.reward_proxy.clone() {
-            let msg_opt = get_proxy_rewards(deps.querier, &mut pool, &reward_proxy)?;
-            POOL_INFO.save(deps.storage, &lp_token, &pool)?;
-            if let Some(msg) = msg_opt {
-                messages.push(msg);
+        if !pool.reward_proxies.is_empty() {
+            let claim = match (claim_interval, pool.last_proxy_rewards_claim_block) {
+                (Some(interval), Some(last_claim))
+                    if env.block.height < last_claim.u64().saturating_add(interval) =>
+                {
+                    // The claim can only be skipped if the proxies hold the rewards paid out
+                    !claimed_proxy_rewards_cover(
+                        deps.as_ref(),
+                        &env,
+                        &lp_token,
+                        &pool,
+                        &paid_accounts,
+                        lp_received,
+                    )?
+                }
+                _ => true,
+            };
+            let mut claimed = false;
+            for reward_proxy in pool.reward_proxies.iter_mut() {
+                if let Some(msg) = get_proxy_rewards(deps.querier, reward_proxy, claim)? {
+                    messages.push(msg);
+                    claimed = true;
+                }
+            }
+            if claimed {
+                pool.last_proxy_rewards_claim_block = Some(Uint64::from(env.block.height));
             }
+            POOL_INFO.save(deps.storage, &lp_token, &pool)?;
         }
     }
 
     if !messages.is_empty() {
+        // Released by the callback once the claimed proxy rewards are accounted
+        REENTRANCY_LOCK.save(deps.storage, &true)?;
         messages.push(action_on_reply.into_submsg(&env)?);
         Ok(Response::new().add_submessages(messages))
     } else {
@@ -690,24 +1222,34 @@ fn update_rewards_and_execute(
 /// on failure. Otherwise returns object of type [`Some(SubMsg)`] if there is pending tokens
 /// or returns [`None`] in opposite case.
 ///
-/// * **pool** generator associated with the `lp_token`.
+/// * **reward_proxy** one of the dual rewards proxies for the target LP/generator.
 ///
-/// * **reward_proxy** address of the dual rewards proxy for the target LP/generator.
+/// * **claim** whether pending rewards should be claimed from the proxy. The snapshot is refreshed
+/// either way so that rewards paid out in between claims are not counted again. Rewards which
+/// stay pending on the proxy are accounted by [`accumulate_rewards_per_share`] all the same.
 fn get_proxy_rewards(
     querier: QuerierWrapper,
-    pool: &mut PoolInfo,
-    reward_proxy: &Addr,
+    reward_proxy: &mut RewardProxyInfo,
+    claim: bool,
 ) -> Result<Option<SubMsg>, ContractError> {
+    // Rewards of a proxy in emergency mode are neither claimed nor accounted
+    if is_proxy_in_emergency(&querier, &reward_proxy.proxy) {
+        return Ok(None);
+    }
+
     let reward_amount: Uint128 =
-        querier.query_wasm_smart(reward_proxy, &ProxyQueryMsg::Reward {})?;
+        querier.query_wasm_smart(&reward_proxy.proxy, &ProxyQueryMsg::Reward {})?;
 
-    pool.proxy_reward_balance_before_update = reward_amount;
+    reward_proxy.reward_balance_before_update = reward_amount;
 
-    let res: Uint128 = querier.query_wasm_smart(reward_proxy, &ProxyQueryMsg::PendingToken {})?;
+    // Pending rewards only decrease when they are claimed. Whatever was claimed since the last
+    // update is hidden by the snapshot refresh, so it must not be deducted from new rewards again.
+    let pending = query_proxy_pending_rewards(&querier, &reward_proxy.proxy)?;
+    reward_proxy.accounted_pending_rewards = reward_proxy.accounted_pending_rewards.min(pending);
 
-    Ok(if !res.is_zero() {
+    Ok(if claim && !pending.is_zero() {
         Some(SubMsg::new(WasmMsg::Execute {
-            contract_addr: reward_proxy.to_string(),
+            contract_addr: reward_proxy.proxy.to_string(),
             funds: vec![],
             msg: to_binary(&ProxyExecuteMsg::UpdateRewards {})?,
         }))
@@ -716,9 +1258,93 @@ fn get_proxy_rewards(
     })
 }
 
+/// Returns the amount of rewards which can be claimed from the reward proxy right now.
+///
+/// * **proxy** the reward proxy to query.
+fn query_proxy_pending_rewards(querier: &QuerierWrapper, proxy: &Addr) -> StdResult<Uint128> {
+    let pending: Option<Uint128> =
+        querier.query_wasm_smart(proxy, &ProxyQueryMsg::PendingToken {})?;
+    Ok(pending.unwrap_or_default())
+}
+
+/// Returns true if the rewards already claimed by each reward proxy of the generator cover what
+/// the given accounts are paid out by a deposit or withdrawal, including their share of the
+/// pending rewards which were not accounted yet.
+///
+/// * **lp_token** LP token of the generator.
+///
+/// * **pool** generator associated with the `lp_token`.
+///
+/// * **accounts** accounts whose proxy rewards are paid out.
+///
+/// * **lp_received** LP tokens which were already received by the generator but are not staked yet.
+fn claimed_proxy_rewards_cover(
+    deps: Deps,
+    env: &Env,
+    lp_token: &Addr,
+    pool: &PoolInfo,
+    accounts: &[&Addr],
+    lp_received: Uint128,
+) -> StdResult<bool> {
+    let mut lp_supply = query_lp_balance(&deps.querier, &env.contract.address, lp_token, pool)?;
+    if pool.lp_proxy().is_none() {
+        lp_supply = lp_supply.saturating_sub(lp_received);
+    }
+
+    let users = accounts
+        .iter()
+        .map(|account| {
+            USER_INFO
+                .compatible_load(deps.storage, (lp_token, *account))
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let mut owed = HashMap::new();
+    for user in &users {
+        for (proxy, amount) in accumulate_pool_proxy_rewards(pool, user)? {
+            *owed.entry(proxy).or_insert_with(Uint128::zero) += amount;
+        }
+    }
+
+    for reward_proxy in &pool.reward_proxies {
+        // Rewards of a proxy in emergency mode are not paid out
+        if is_proxy_in_emergency(&deps.querier, &reward_proxy.proxy) {
+            continue;
+        }
+
+        let mut proxy_owed = owed.get(&reward_proxy.proxy).cloned().unwrap_or_default();
+        if !lp_supply.is_zero() {
+            let unaccounted = query_proxy_pending_rewards(&deps.querier, &reward_proxy.proxy)?
+                .saturating_sub(reward_proxy.accounted_pending_rewards);
+            for user in &users {
+                proxy_owed += unaccounted.multiply_ratio(user.amount, lp_supply);
+            }
+        }
+
+        let claimed: Uint128 = deps
+            .querier
+            .query_wasm_smart(&reward_proxy.proxy, &ProxyQueryMsg::Reward {})?;
+        if proxy_owed > claimed {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Returns true if the reward proxy flagged itself as being in emergency mode. Proxies which
+/// don't support the query are considered to operate normally.
+///
+/// * **proxy** the reward proxy to check.
+fn is_proxy_in_emergency(querier: &QuerierWrapper, proxy: &Addr) -> bool {
+    querier
+        .query_wasm_smart(proxy, &ProxyQueryMsg::EmergencyMode {})
+        .unwrap_or(false)
+}
+
 /// The entry point to the contract for processing replies from submessages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: INIT_REWARDS_HOLDER_ID,
@@ -735,6 +1361,41 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             Ok(Response::new().add_attribute("action", "init_rewards_holder"))
         }
+        Reply {
+            id: AUTO_STAKE_REWARDS_ID,
+            result,
+        } => auto_stake_rewards_reply(deps, env, result),
+        Reply {
+            id: INIT_RECEIPT_TOKEN_ID,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }),
+        } => {
+            let init_response = parse_instantiate_response_data(data.as_slice())
+                .map_err(|e| StdError::generic_err(format!("{e}")))?;
+
+            let receipt_token = deps.api.addr_validate(&init_response.contract_address)?;
+            let lp_token = RECEIPT_TOKEN_CONTEXT.load(deps.storage)?;
+            RECEIPT_TOKEN_CONTEXT.remove(deps.storage);
+            RECEIPT_TOKENS.save(deps.storage, &lp_token, &receipt_token)?;
+            RECEIPT_TOKEN_LP.save(deps.storage, &receipt_token, &lp_token)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "init_receipt_token"),
+                attr("lp_token", lp_token),
+                attr("receipt_token", receipt_token),
+            ]))
+        }
+        #[cfg(not(feature = "bank"))]
+        Reply {
+            id: WITHDRAW_AND_UNWRAP_ID,
+            result: SubMsgResult::Ok(_),
+        } => unwrap_lp_reply(deps, env),
+        Reply {
+            id: SWAP_REWARDS_ID,
+            result,
+        } => swap_rewards_reply(deps, result),
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
@@ -747,8 +1408,20 @@ fn handle_callback(
 ) -> Result<Response, ContractError> {
     match action {
         ExecuteOnReply::ClaimRewards { lp_tokens, account } => {
-            claim_rewards(deps, env, lp_tokens, account)
+            claim_rewards(deps, env, lp_tokens, account, None)
         }
+        ExecuteOnReply::ClaimRewardsAs {
+            lp_token,
+            account,
+            target_asset,
+            min_received,
+        } => claim_rewards(
+            deps,
+            env,
+            vec![lp_token],
+            account,
+            Some((target_asset, min_received)),
+        ),
         ExecuteOnReply::Deposit {
             lp_token,
             account,
@@ -758,62 +1431,880 @@ fn handle_callback(
             lp_token,
             account,
             amount,
-        } => withdraw(deps, env, lp_token, account, amount),
-        ExecuteOnReply::SetTokensPerBlock { amount } => set_tokens_per_block(deps, env, amount),
-        ExecuteOnReply::MigrateProxy {
-            lp_addr,
-            new_proxy_addr,
-        } => migrate_proxy_callback(deps, env, lp_addr, new_proxy_addr),
-        ExecuteOnReply::MigrateProxyDepositLP {
-            lp_addr,
-            prev_proxy_addr,
+        } => {
+            let recipient = account.clone();
+            withdraw(deps, env, lp_token, account, recipient, amount)
+        }
+        #[cfg(not(feature = "bank"))]
+        ExecuteOnReply::WithdrawAndUnwrap {
+            lp_token,
+            account,
+            amount,
+            min_assets,
+        } => {
+            // The LP tokens are withdrawn to the Generator and unwrapped once they are received
+            let unwrap_msg = ExecuteOnReply::UnwrapLp {
+                lp_token: lp_token.clone(),
+                account: account.clone(),
+                amount,
+                min_assets,
+            }
+            .into_submsg(&env)?;
+            let recipient = env.contract.address.clone();
+
+            Ok(withdraw(deps, env, lp_token, account, recipient, amount)?
+                .add_submessage(unwrap_msg))
+        }
+        #[cfg(not(feature = "bank"))]
+        ExecuteOnReply::UnwrapLp {
+            lp_token,
+            account,
+            amount,
+            min_assets,
+        } => unwrap_lp(deps, env, lp_token, account, amount, min_assets),
+        #[cfg(feature = "bank")]
+        ExecuteOnReply::WithdrawAndUnwrap { .. } | ExecuteOnReply::UnwrapLp { .. } => {
+            Err(ContractError::Cw20Disabled {})
+        }
+        ExecuteOnReply::SetTokensPerBlock { amount } => set_tokens_per_block(deps, env, amount),
+        ExecuteOnReply::MigrateProxy {
+            lp_addr,
+            new_proxy_addr,
+        } => migrate_proxy_callback(deps, env, lp_addr, new_proxy_addr),
+        ExecuteOnReply::MigrateProxyDepositLP {
+            lp_addr,
+            prev_proxy_addr,
+            amount,
+        } => migrate_proxy_deposit_lp(deps, lp_addr, prev_proxy_addr, amount),
+        ExecuteOnReply::DeactivateRewardProxy {
+            lp_addr,
+            proxy_addr,
+        } => deactivate_reward_proxy_callback(deps, env, lp_addr, proxy_addr),
+        ExecuteOnReply::AutoStakeRewards { account, amount } => {
+            auto_stake_rewards(deps, env, account, amount)
+        }
+        ExecuteOnReply::SwapRewards {
+            account,
+            amount,
+            target_asset,
+            min_received,
+        } => swap_rewards(deps, account, amount, target_asset, min_received),
+        ExecuteOnReply::MoveReceiptPosition {
+            lp_token,
+            from,
+            to,
+            amount,
+        } => move_receipt_position(deps, env, lp_token, from, to, amount),
+    }
+}
+
+/// Sets the allocation points to zero for the generator associated with the specified LP token. Recalculates total allocation points.
+pub fn deactivate_pool(
+    deps: DepsMut,
+    mut cfg: Config,
+    lp_token: Addr,
+) -> Result<Response, ContractError> {
+    // Get old allocation points for the pool and subtract them from the total allocation points
+    let old_alloc_point = get_alloc_point(&cfg.active_pools, &lp_token);
+    cfg.total_alloc_point = cfg.total_alloc_point.checked_sub(old_alloc_point)?;
+
+    // Set the pool allocation points to zero
+    for pool in &mut cfg.active_pools {
+        if pool.0 == lp_token {
+            pool.1 = Uint128::zero();
+            break;
+        }
+    }
+
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attribute("action", "deactivate_pool"))
+}
+
+/// Sets a new amount of GRID distributed per block among all active generators. Before that, we
+/// will need to update all pools in order to correctly account for accrued rewards.
+///
+/// * **amount** new count of tokens per block.
+fn set_tokens_per_block(
+    mut deps: DepsMut,
+    env: Env,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    let pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
+
+    mass_update_pools(deps.branch(), &env, &cfg, &pools)?;
+
+    cfg.tokens_per_block = amount;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attribute("action", "set_tokens_per_block"))
+}
+
+/// Queues a new amount of GRID distributed per block among all active generators. The amount is
+/// applied by the first transaction at or after the activation height.
+///
+/// * **amount** new count of tokens per block.
+///
+/// * **activation_height** block height at which the new amount takes effect.
+fn schedule_tokens_per_block(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    activation_height: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if activation_height <= env.block.height {
+        return Err(ContractError::InvalidActivationHeight {});
+    }
+
+    if EMISSION_SCHEDULE.has(deps.storage, activation_height) {
+        return Err(ContractError::EmissionAlreadyScheduled { activation_height });
+    }
+
+    let scheduled = EMISSION_SCHEDULE
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if scheduled >= MAX_SCHEDULED_EMISSIONS {
+        return Err(ContractError::TooManyScheduledEmissions {});
+    }
+
+    EMISSION_SCHEDULE.save(deps.storage, activation_height, &amount)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "schedule_tokens_per_block"),
+        attr("amount", amount),
+        attr("activation_height", activation_height.to_string()),
+    ]))
+}
+
+/// Cancels a queued change of the amount of GRID distributed per block. Changes which reached
+/// their activation height are already applied and can't be cancelled.
+///
+/// * **activation_height** activation height of the queued change.
+fn cancel_scheduled_tokens_per_block(
+    deps: DepsMut,
+    info: MessageInfo,
+    activation_height: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !EMISSION_SCHEDULE.has(deps.storage, activation_height) {
+        return Err(ContractError::ScheduledEmissionNotFound { activation_height });
+    }
+    EMISSION_SCHEDULE.remove(deps.storage, activation_height);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_scheduled_tokens_per_block"),
+        attr("activation_height", activation_height.to_string()),
+    ]))
+}
+
+/// Applies the queued changes of GRID per block whose activation height has been reached. All
+/// active generators are updated up to each activation height first, so rewards accrued before
+/// it are accounted with the previous amount.
+fn apply_emission_schedule(mut deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    let due = due_emissions(deps.storage, env)?;
+    if due.is_empty() {
+        return Ok(());
+    }
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
+    for (activation_height, amount) in due {
+        let mut activation_env = env.clone();
+        activation_env.block.height = activation_height;
+        mass_update_pools(deps.branch(), &activation_env, &cfg, &pools)?;
+
+        cfg.tokens_per_block = amount;
+        EMISSION_SCHEDULE.remove(deps.storage, activation_height);
+    }
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(())
+}
+
+/// Returns the queued changes of GRID per block whose activation height has been reached ordered
+/// by activation height.
+fn due_emissions(storage: &dyn Storage, env: &Env) -> StdResult<Vec<(u64, Uint128)>> {
+    EMISSION_SCHEDULE
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(env.block.height)),
+            Order::Ascending,
+        )
+        .collect()
+}
+
+/// Returns the config as the next transaction sees it, i.e. with the queued changes of GRID per
+/// block whose activation height has been reached applied. Nothing is saved.
+fn load_config_with_due_emissions(deps: Deps, env: &Env) -> StdResult<Config> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    if let Some((_, amount)) = due_emissions(deps.storage, env)?.pop() {
+        cfg.tokens_per_block = amount;
+    }
+
+    Ok(cfg)
+}
+
+/// Returns the config and a generator as the next transaction sees them, i.e. with the queued
+/// changes of GRID per block whose activation height has been reached applied the same way
+/// [`apply_emission_schedule`] does. Nothing is saved.
+///
+/// * **lp_token** LP token whose generator is loaded.
+fn load_pool_with_due_emissions(
+    deps: Deps,
+    env: &Env,
+    lp_token: &Addr,
+) -> StdResult<(Config, PoolInfo)> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let mut pool = POOL_INFO.load(deps.storage, lp_token)?;
+    let is_active = cfg
+        .active_pools
+        .iter()
+        .any(|(active, _)| active == lp_token);
+
+    for (activation_height, amount) in due_emissions(deps.storage, env)? {
+        // Only active generators are updated up to the activation height
+        if is_active {
+            accumulate_grid_rewards_per_share(activation_height, lp_token, &mut pool, &cfg)?;
+        }
+        cfg.tokens_per_block = amount;
+    }
+
+    Ok((cfg, pool))
+}
+
+/// Pauses or resumes accrual of new GRID emissions. Pools are updated first so rewards accrued
+/// before the switch are accounted correctly, while the paused period is skipped.
+///
+/// * **lp_token** LP token whose generator is switched. All generators are switched if not specified.
+///
+/// * **paused** whether emissions are paused.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn set_emissions_paused(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: Option<String>,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![
+        attr("action", "set_emissions_paused"),
+        attr("paused", paused.to_string()),
+    ];
+
+    if let Some(lp_token) = lp_token {
+        let lp_addr = validate_lp_token(deps.api, &lp_token)?;
+        let mut pool = POOL_INFO.load(deps.storage, &lp_addr)?;
+        accumulate_rewards_per_share(
+            &deps.querier,
+            &env,
+            &lp_addr,
+            &mut pool,
+            &cfg,
+            Uint128::zero(),
+        )?;
+        pool.emissions_paused = paused;
+        POOL_INFO.save(deps.storage, &lp_addr, &pool)?;
+
+        attributes.push(attr("lp_token", lp_addr));
+    } else {
+        let pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
+        mass_update_pools(deps.branch(), &env, &cfg, &pools)?;
+
+        cfg.emissions_paused = paused;
+        CONFIG.save(deps.storage, &cfg)?;
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Enables or disables auto-staking of the sender's claimed GRID rewards into xGRID.
+///
+/// * **enabled** whether claimed GRID rewards are auto-staked.
+fn set_auto_stake_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if enabled {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.staking_contract.is_none() {
+            return Err(ContractError::AutoStakeNotAvailable {});
+        }
+        AUTO_STAKE_REWARDS.save(deps.storage, &info.sender, &true)?;
+    } else {
+        AUTO_STAKE_REWARDS.remove(deps.storage, &info.sender);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_auto_stake_rewards"),
+        attr("user", info.sender),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+/// Instantiates a receipt token for positions staked in a specific generator. The generator
+/// is the token's minter.
+///
+/// * **lp_token** LP token whose staked positions are represented by the receipt token.
+///
+/// * **token_code_id** code ID of the receipt token contract.
+///
+/// ## Executor
+/// Only the owner can execute this.
+#[cfg(not(feature = "bank"))]
+fn setup_receipt_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    token_code_id: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    // Ensure the generator exists
+    POOL_INFO.load(deps.storage, &lp_token)?;
+
+    if RECEIPT_TOKENS.has(deps.storage, &lp_token) {
+        return Err(ContractError::ReceiptTokenAlreadySet {});
+    }
+
+    let lp_token_info: TokenInfoResponse = deps
+        .querier
+        .query_wasm_smart(&lp_token, &Cw20QueryMsg::TokenInfo {})?;
+
+    RECEIPT_TOKEN_CONTEXT.save(deps.storage, &lp_token)?;
+
+    let init_msg = SubMsg::reply_on_success(
+        WasmMsg::Instantiate {
+            admin: Some(cfg.owner.to_string()),
+            code_id: token_code_id,
+            msg: to_binary(&TokenInstantiateMsg {
+                name: format!("Staked {}", lp_token_info.name)
+                    .chars()
+                    .take(50)
+                    .collect(),
+                symbol: RECEIPT_TOKEN_SYMBOL.to_string(),
+                decimals: lp_token_info.decimals,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: env.contract.address.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            })?,
+            funds: vec![],
+            label: "Gridiron generator receipt token".to_string(),
+        },
+        INIT_RECEIPT_TOKEN_ID,
+    );
+
+    Ok(Response::new().add_submessage(init_msg).add_attributes([
+        attr("action", "setup_receipt_token"),
+        attr("lp_token", lp_token),
+    ]))
+}
+
+/// Mints receipt tokens backed 1:1 by the sender's staked LP tokens.
+///
+/// * **lp_token** LP token whose receipt tokens are minted.
+///
+/// * **amount** amount of staked LP tokens to back receipt tokens with.
+fn mint_receipt(
+    deps: DepsMut,
+    info: MessageInfo,
+    lp_token: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let receipt_token = RECEIPT_TOKENS
+        .may_load(deps.storage, &lp_token)?
+        .ok_or(ContractError::ReceiptTokenNotSet {})?;
+
+    let user = USER_INFO
+        .compatible_load(deps.storage, (&lp_token, &info.sender))
+        .unwrap_or_default();
+    let backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &info.sender))?
+        .unwrap_or_default()
+        .checked_add(amount)?;
+    if backed > user.amount {
+        return Err(ContractError::NotEnoughUnbackedBalance {});
+    }
+    RECEIPT_BACKED.save(deps.storage, (&lp_token, &info.sender), &backed)?;
+
+    let mint_msg = wasm_execute(
+        receipt_token,
+        &Cw20ExecuteMsg::Mint {
+            recipient: info.sender.to_string(),
+            amount,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_message(mint_msg).add_attributes([
+        attr("action", "mint_receipt"),
+        attr("lp_token", lp_token),
+        attr("user", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Burns the sender's receipt tokens. The backing LP tokens stay staked.
+///
+/// * **lp_token** LP token whose receipt tokens are burned.
+///
+/// * **amount** amount of receipt tokens to burn.
+fn burn_receipt(
+    deps: DepsMut,
+    info: MessageInfo,
+    lp_token: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &info.sender))?
+        .unwrap_or_default();
+    if backed < amount {
+        return Err(ContractError::BalanceTooSmall {});
+    }
+
+    let burn_msg = burn_receipt_msg(deps.as_ref(), &lp_token, &info.sender, amount)?;
+    update_receipt_backed(deps.storage, &lp_token, &info.sender, backed - amount)?;
+
+    Ok(Response::new().add_message(burn_msg).add_attributes([
+        attr("action", "burn_receipt"),
+        attr("lp_token", lp_token),
+        attr("user", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Moves a staked position backing transferred receipt tokens. Pending rewards are sent to both
+/// holders beforehand, so rewards accrued from now on follow the receipt tokens.
+///
+/// * **lp_token** LP token of the generator.
+///
+/// * **from** previous holder of the receipt tokens.
+///
+/// * **to** new holder of the receipt tokens.
+///
+/// * **amount** amount of staked LP tokens to move.
+fn move_receipt_position(
+    deps: DepsMut,
+    env: Env,
+    lp_token: Addr,
+    from: Addr,
+    to: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let from_backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &from))?
+        .unwrap_or_default();
+    if from_backed < amount {
+        return Err(ContractError::BalanceTooSmall {});
+    }
+    let to_backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &to))?
+        .unwrap_or_default();
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
+
+    accumulate_rewards_per_share(
+        &deps.querier,
+        &env,
+        &lp_token,
+        &mut pool,
+        &cfg,
+        Uint128::zero(),
+    )?;
+
+    let from_user = USER_INFO.compatible_load(deps.storage, (&lp_token, &from))?;
+    let to_user = USER_INFO
+        .compatible_load(deps.storage, (&lp_token, &to))
+        .unwrap_or_default();
+
+    // Send pending rewards to both holders
+    let mut messages = send_pending_rewards(deps.as_ref(), &env, &cfg, &pool, &from_user, &from)?;
+    messages.extend(send_pending_rewards(
+        deps.as_ref(),
+        &env,
+        &cfg,
+        &pool,
+        &to_user,
+        &to,
+    )?);
+
+    let lp_balance = query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
+
+    let from_amount = from_user.amount.checked_sub(amount)?;
+    let mut from_user = update_user_balance(from_user, &pool, from_amount)?;
+    update_virtual_amount(
+        deps.querier,
+        &cfg,
+        &mut pool,
+        &mut from_user,
+        &from,
+        lp_balance,
+    )?;
+
+    let to_amount = to_user.amount.checked_add(amount)?;
+    let mut to_user = update_user_balance(to_user, &pool, to_amount)?;
+    update_virtual_amount(deps.querier, &cfg, &mut pool, &mut to_user, &to, lp_balance)?;
+
+    POOL_INFO.save(deps.storage, &lp_token, &pool)?;
+    if !from_user.amount.is_zero() {
+        USER_INFO.save(deps.storage, (&lp_token, &from), &from_user)?;
+    } else {
+        USER_INFO.remove(deps.storage, (&lp_token, &from));
+    }
+    USER_INFO.save(deps.storage, (&lp_token, &to), &to_user)?;
+
+    update_receipt_backed(deps.storage, &lp_token, &from, from_backed - amount)?;
+    update_receipt_backed(deps.storage, &lp_token, &to, to_backed.checked_add(amount)?)?;
+
+    let points_msgs = [
+        points_hook_msg(deps.as_ref(), &cfg, &from, &lp_token, from_user.amount)?,
+        points_hook_msg(deps.as_ref(), &cfg, &to, &lp_token, to_user.amount)?,
+    ];
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_messages(points_msgs.into_iter().flatten())
+        .add_attributes([
+            attr("action", "move_receipt_position"),
+            attr("lp_token", lp_token),
+            attr("from", from),
+            attr("to", to),
+            attr("amount", amount),
+        ]))
+}
+
+/// Builds the message which burns a user's receipt tokens of a specific generator.
+fn burn_receipt_msg(
+    deps: Deps,
+    lp_token: &Addr,
+    owner: &Addr,
+    amount: Uint128,
+) -> Result<WasmMsg, ContractError> {
+    let receipt_token = RECEIPT_TOKENS
+        .may_load(deps.storage, lp_token)?
+        .ok_or(ContractError::ReceiptTokenNotSet {})?;
+
+    Ok(wasm_execute(
+        receipt_token,
+        &Cw20ExecuteMsg::BurnFrom {
+            owner: owner.to_string(),
+            amount,
+        },
+        vec![],
+    )?)
+}
+
+/// Builds the message which reports the new LP position of a user to the points contract set in
+/// the factory, if any.
+///
+/// * **amount** amount of LP tokens the user has staked after the change.
+fn points_hook_msg(
+    deps: Deps,
+    cfg: &Config,
+    user: &Addr,
+    lp_token: &Addr,
+    amount: Uint128,
+) -> StdResult<Option<CosmosMsg>> {
+    query_factory_config(&deps.querier, &cfg.factory)?
+        .points_contract
+        .map(|points_contract| lp_hook_msg(&points_contract, user, lp_token, amount))
+        .transpose()
+}
+
+/// Saves the amount of a user's staked LP tokens that back receipt tokens.
+fn update_receipt_backed(
+    storage: &mut dyn Storage,
+    lp_token: &Addr,
+    user: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        RECEIPT_BACKED.remove(storage, (lp_token, user));
+        Ok(())
+    } else {
+        RECEIPT_BACKED.save(storage, (lp_token, user), &amount)
+    }
+}
+
+/// Stakes GRID rewards which were claimed by the generator on behalf of the account. The xGRID
+/// received is forwarded to the account in the reply. If the staking contract cannot be
+/// queried, the GRID is sent to the account as is.
+///
+/// * **account** address that receives the rewards.
+///
+/// * **amount** amount of GRID to stake.
+fn auto_stake_rewards(
+    deps: DepsMut,
+    env: Env,
+    account: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let staking_config = match (&cfg.staking_contract, &cfg.grid_token) {
+        (Some(staking), AssetInfo::Token { contract_addr }) => deps
+            .querier
+            .query_wasm_smart::<StakingConfigResponse>(staking, &StakingQueryMsg::Config {})
+            .ok()
+            .filter(|staking_config| staking_config.deposit_token_addr == *contract_addr)
+            .map(|staking_config| (staking.clone(), staking_config)),
+        _ => None,
+    };
+
+    let (staking, staking_config) = match staking_config {
+        Some(staking_config) => staking_config,
+        None => {
+            return Ok(Response::new()
+                .add_message(
+                    cfg.grid_token
+                        .with_balance(amount)
+                        .into_msg::<Empty>(&account)?,
+                )
+                .add_attributes([
+                    attr("action", "auto_stake_rewards_fallback"),
+                    attr("user", account),
+                    attr("grid_amount", amount),
+                ]));
+        }
+    };
+
+    let xgrid_balance_before = query_token_balance(
+        &deps.querier,
+        &staking_config.share_token_addr,
+        &env.contract.address,
+    )?;
+    AUTO_STAKE_CONTEXT.save(
+        deps.storage,
+        &AutoStakeContext {
+            account,
+            amount,
+            xgrid_token: staking_config.share_token_addr,
+            xgrid_balance_before,
+        },
+    )?;
+
+    let stake_msg = wasm_execute(
+        staking_config.deposit_token_addr,
+        &Cw20ExecuteMsg::Send {
+            contract: staking.to_string(),
             amount,
-        } => migrate_proxy_deposit_lp(deps, lp_addr, prev_proxy_addr, amount),
-    }
+            msg: to_binary(&StakingCw20HookMsg::Enter {})?,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_always(stake_msg, AUTO_STAKE_REWARDS_ID)))
 }
 
-/// Sets the allocation points to zero for the generator associated with the specified LP token. Recalculates total allocation points.
-pub fn deactivate_pool(
+/// Finalizes auto-staking of claimed GRID rewards. On success the minted xGRID is sent to the
+/// account, otherwise the staking is reverted and the GRID is sent instead.
+fn auto_stake_rewards_reply(
     deps: DepsMut,
-    mut cfg: Config,
-    lp_token: Addr,
+    env: Env,
+    result: SubMsgResult,
 ) -> Result<Response, ContractError> {
-    // Get old allocation points for the pool and subtract them from the total allocation points
-    let old_alloc_point = get_alloc_point(&cfg.active_pools, &lp_token);
-    cfg.total_alloc_point = cfg.total_alloc_point.checked_sub(old_alloc_point)?;
+    let ctx = AUTO_STAKE_CONTEXT.load(deps.storage)?;
+    AUTO_STAKE_CONTEXT.remove(deps.storage);
+
+    match result {
+        SubMsgResult::Ok(_) => {
+            let xgrid_amount =
+                query_token_balance(&deps.querier, &ctx.xgrid_token, &env.contract.address)?
+                    .checked_sub(ctx.xgrid_balance_before)?;
+
+            Ok(Response::new()
+                .add_message(wasm_execute(
+                    &ctx.xgrid_token,
+                    &Cw20ExecuteMsg::Transfer {
+                        recipient: ctx.account.to_string(),
+                        amount: xgrid_amount,
+                    },
+                    vec![],
+                )?)
+                .add_attributes([
+                    attr("action", "auto_stake_rewards"),
+                    attr("user", ctx.account),
+                    attr("grid_amount", ctx.amount),
+                    attr("xgrid_amount", xgrid_amount),
+                ]))
+        }
+        SubMsgResult::Err(err) => {
+            let cfg = CONFIG.load(deps.storage)?;
 
-    // Set the pool allocation points to zero
-    for pool in &mut cfg.active_pools {
-        if pool.0 == lp_token {
-            pool.1 = Uint128::zero();
-            break;
+            Ok(Response::new()
+                .add_message(
+                    cfg.grid_token
+                        .with_balance(ctx.amount)
+                        .into_msg::<Empty>(&ctx.account)?,
+                )
+                .add_attributes([
+                    attr("action", "auto_stake_rewards_fallback"),
+                    attr("user", ctx.account),
+                    attr("grid_amount", ctx.amount),
+                    attr("error", err),
+                ]))
         }
     }
-
-    CONFIG.save(deps.storage, &cfg)?;
-
-    Ok(Response::new().add_attribute("action", "deactivate_pool"))
 }
 
-/// Sets a new amount of GRID distributed per block among all active generators. Before that, we
-/// will need to update all pools in order to correctly account for accrued rewards.
+/// Swaps GRID rewards which were claimed by the generator on behalf of the account into the
+/// target asset via the router. The router sends the target asset to the account directly.
+/// If the router is not set, the GRID is sent to the account as is.
 ///
-/// * **amount** new count of tokens per block.
-fn set_tokens_per_block(
-    mut deps: DepsMut,
-    env: Env,
+/// * **account** address that receives the rewards.
+///
+/// * **amount** amount of GRID to swap.
+///
+/// * **target_asset** asset GRID is swapped into.
+///
+/// * **min_received** minimum amount of the target asset to receive.
+fn swap_rewards(
+    deps: DepsMut,
+    account: Addr,
     amount: Uint128,
+    target_asset: AssetInfo,
+    min_received: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let mut cfg = CONFIG.load(deps.storage)?;
+    let cfg = CONFIG.load(deps.storage)?;
 
-    let pools: Vec<_> = cfg.active_pools.iter().map(|pool| pool.0.clone()).collect();
+    let router = match cfg.router {
+        Some(router) => router,
+        None => {
+            return Ok(Response::new()
+                .add_message(
+                    cfg.grid_token
+                        .with_balance(amount)
+                        .into_msg::<Empty>(&account)?,
+                )
+                .add_attributes([
+                    attr("action", "swap_rewards_fallback"),
+                    attr("user", account),
+                    attr("grid_amount", amount),
+                ]));
+        }
+    };
 
-    mass_update_pools(deps.branch(), &env, &cfg, &pools)?;
+    let operations = vec![SwapOperation::GridSwap {
+        offer_asset_info: cfg.grid_token.clone(),
+        ask_asset_info: target_asset.clone(),
+    }];
+    // The minimum amount to receive replaces the default spread limit
+    let max_spread = min_received
+        .map(|_| Decimal::from_str(MAX_ALLOWED_SLIPPAGE))
+        .transpose()?;
 
-    cfg.tokens_per_block = amount;
-    CONFIG.save(deps.storage, &cfg)?;
+    let swap_msg = match &cfg.grid_token {
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount,
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: min_received,
+                    to: Some(account.to_string()),
+                    max_spread,
+                    max_intermediate_spread_bps: None,
+                    deadline: None,
+                    trader: None,
+                    fee_payer_memo: None,
+                })?,
+            },
+            vec![],
+        )?,
+        AssetInfo::NativeToken { denom } => wasm_execute(
+            router,
+            &RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: min_received,
+                to: Some(account.to_string()),
+                max_spread,
+                max_intermediate_spread_bps: None,
+                deadline: None,
+                trader: None,
+                fee_payer_memo: None,
+            },
+            vec![Coin::new(amount.u128(), denom)],
+        )?,
+    };
 
-    Ok(Response::new().add_attribute("action", "set_tokens_per_block"))
+    SWAP_REWARDS_CONTEXT.save(
+        deps.storage,
+        &SwapRewardsContext {
+            account,
+            amount,
+            target_asset,
+        },
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_always(swap_msg, SWAP_REWARDS_ID)))
+}
+
+/// Finalizes a swap of claimed GRID rewards. If the swap failed, it is reverted and the GRID is
+/// sent to the account instead.
+fn swap_rewards_reply(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let ctx = SWAP_REWARDS_CONTEXT.load(deps.storage)?;
+    SWAP_REWARDS_CONTEXT.remove(deps.storage);
+
+    match result {
+        SubMsgResult::Ok(_) => Ok(Response::new().add_attributes([
+            attr("action", "swap_rewards"),
+            attr("user", ctx.account),
+            attr("grid_amount", ctx.amount),
+            attr("target_asset", ctx.target_asset.to_string()),
+        ])),
+        SubMsgResult::Err(err) => {
+            let cfg = CONFIG.load(deps.storage)?;
+
+            Ok(Response::new()
+                .add_message(
+                    cfg.grid_token
+                        .with_balance(ctx.amount)
+                        .into_msg::<Empty>(&ctx.account)?,
+                )
+                .add_attributes([
+                    attr("action", "swap_rewards_fallback"),
+                    attr("user", ctx.account),
+                    attr("grid_amount", ctx.amount),
+                    attr("error", err),
+                ]))
+        }
+    }
 }
 
 /// Updates the amount of accrued rewards for all generators.
@@ -827,23 +2318,85 @@ pub fn mass_update_pools(
 ) -> Result<(), ContractError> {
     for lp_token in lp_tokens {
         let mut pool = POOL_INFO.load(deps.storage, lp_token)?;
-        accumulate_rewards_per_share(&deps.querier, env, lp_token, &mut pool, cfg)?;
+        accumulate_rewards_per_share(
+            &deps.querier,
+            env,
+            lp_token,
+            &mut pool,
+            cfg,
+            Uint128::zero(),
+        )?;
         POOL_INFO.save(deps.storage, lp_token, &pool)?;
     }
 
     Ok(())
 }
 
+/// Updates the accrued GRID rewards of a page of active generators ordered by LP token address.
+/// If the page doesn't reach the last generator, its position is stored and the next call
+/// without `start_after` continues from it.
+///
+/// Each generator accrues rewards since its own `last_reward_block` under the current config, so
+/// generators left behind by an unfinished pass lose nothing and partially updated sets are safe.
+///
+/// * **start_after** the LP token after which generators are updated.
+///
+/// * **limit** the maximum amount of generators to update.
+fn update_pools(
+    mut deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_validate(&start_after)?),
+        None => UPDATE_POOLS_CURSOR.may_load(deps.storage)?,
+    };
+    let limit = limit
+        .unwrap_or(DEFAULT_UPDATE_POOLS_LIMIT)
+        .clamp(1, MAX_UPDATE_POOLS_LIMIT) as usize;
+
+    let mut lp_tokens: Vec<_> = cfg
+        .active_pools
+        .iter()
+        .map(|pool| pool.0.clone())
+        .filter(|lp_token| start_after.as_ref().map_or(true, |start| lp_token > start))
+        .collect();
+    lp_tokens.sort();
+    let finished = lp_tokens.len() <= limit;
+    lp_tokens.truncate(limit);
+
+    mass_update_pools(deps.branch(), &env, &cfg, &lp_tokens)?;
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "update_pools"),
+        attr("updated", lp_tokens.len().to_string()),
+    ]);
+    match lp_tokens.last() {
+        Some(last) if !finished => {
+            UPDATE_POOLS_CURSOR.save(deps.storage, last)?;
+            response = response.add_attribute("next_start_after", last);
+        }
+        _ => UPDATE_POOLS_CURSOR.remove(deps.storage),
+    }
+
+    Ok(response)
+}
+
 /// Updates the amount of accrued rewards for a specific generator.
 ///
 /// * **lp_token** sets the liquidity pool to be updated and claimed.
 ///
 /// * **account** receiver address.
+///
+/// * **swap_to** the asset GRID rewards are swapped into and the minimum amount to receive, if any.
 pub fn claim_rewards(
     mut deps: DepsMut,
     env: Env,
     lp_tokens: Vec<Addr>,
     account: Addr,
+    swap_to: Option<(AssetInfo, Option<Uint128>)>,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
@@ -854,18 +2407,20 @@ pub fn claim_rewards(
         let mut pool = POOL_INFO.load(deps.storage, lp_token)?;
         let user = USER_INFO.compatible_load(deps.storage, (lp_token, &account))?;
 
-        send_rewards_msg.append(&mut send_pending_rewards(
+        send_rewards_msg.append(&mut send_pending_rewards_as(
             deps.as_ref(),
+            &env,
             &cfg,
             &pool,
             &user,
             &account,
+            swap_to.as_ref(),
         )?);
 
         // Update user's amount
         let amount = user.amount;
         let mut user = update_user_balance(user, &pool, amount)?;
-        let lp_balance = query_lp_balance(deps.as_ref(), &env.contract.address, lp_token, &pool)?;
+        let lp_balance = query_lp_balance(&deps.querier, &env.contract.address, lp_token, &pool)?;
 
         // Update user's virtual amount
         update_virtual_amount(
@@ -882,7 +2437,13 @@ pub fn claim_rewards(
     }
 
     Ok(Response::default()
-        .add_attribute("action", "claim_rewards")
+        .add_attributes(
+            ClaimEvent {
+                user: account,
+                lp_tokens,
+            }
+            .into_attributes(),
+        )
         .add_messages(send_rewards_msg))
 }
 
@@ -892,48 +2453,80 @@ pub fn claim_rewards(
 /// * **lp_token** LP token whose rewards per share we update.
 ///
 /// * **pool** generator associated with the `lp_token`.
+///
+/// * **lp_received** LP tokens which were already received by the generator but are not staked yet.
 pub fn accumulate_rewards_per_share(
     querier: &QuerierWrapper,
     env: &Env,
     lp_token: &Addr,
     pool: &mut PoolInfo,
     cfg: &Config,
+    lp_received: Uint128,
 ) -> StdResult<()> {
-    if let Some(proxy) = &pool.reward_proxy {
-        let proxy_lp_supply: Uint128 =
-            querier.query_wasm_smart(proxy, &ProxyQueryMsg::Deposit {})?;
-
-        if !proxy_lp_supply.is_zero() {
-            let reward_amount: Uint128 =
-                querier.query_wasm_smart(proxy, &ProxyQueryMsg::Reward {})?;
+    if !pool.reward_proxies.is_empty() {
+        let mut lp_supply = query_lp_balance(querier, &env.contract.address, lp_token, pool)?;
+        if pool.lp_proxy().is_none() {
+            lp_supply = lp_supply.saturating_sub(lp_received);
+        }
 
-            let token_rewards =
-                reward_amount.saturating_sub(pool.proxy_reward_balance_before_update);
+        if !lp_supply.is_zero() {
+            for reward_proxy in pool.reward_proxies.iter_mut() {
+                // No further rewards accrue from a proxy in emergency mode
+                if is_proxy_in_emergency(querier, &reward_proxy.proxy) {
+                    continue;
+                }
 
-            let share = Decimal::from_ratio(token_rewards, proxy_lp_supply);
-            pool.accumulated_proxy_rewards_per_share
-                .update(proxy, share)?;
-            pool.proxy_reward_balance_before_update = reward_amount;
+                let reward_amount: Uint128 =
+                    querier.query_wasm_smart(&reward_proxy.proxy, &ProxyQueryMsg::Reward {})?;
+
+                let pending = query_proxy_pending_rewards(querier, &reward_proxy.proxy)?;
+                // Claimed rewards which were already accounted while pending are not counted again
+                let token_rewards = reward_amount
+                    .saturating_sub(reward_proxy.reward_balance_before_update)
+                    .checked_add(pending)?
+                    .saturating_sub(reward_proxy.accounted_pending_rewards);
+
+                let share = Decimal::from_ratio(token_rewards, lp_supply);
+                pool.accumulated_proxy_rewards_per_share
+                    .update(&reward_proxy.proxy, share)?;
+                reward_proxy.reward_balance_before_update = reward_amount;
+                reward_proxy.accounted_pending_rewards = pending;
+            }
         }
     }
 
+    accumulate_grid_rewards_per_share(env.block.height, lp_token, pool, cfg)
+}
+
+/// Accrues the amount of GRID distributed for each staked LP token in a specific generator up to
+/// the specified block height.
+///
+/// * **height** block height up to which rewards are accrued.
+///
+/// * **lp_token** LP token whose rewards per share we update.
+///
+/// * **pool** generator associated with the `lp_token`.
+fn accumulate_grid_rewards_per_share(
+    height: u64,
+    lp_token: &Addr,
+    pool: &mut PoolInfo,
+    cfg: &Config,
+) -> StdResult<()> {
     // we should calculate rewards by previous virtual amount
     let lp_supply = pool.total_virtual_supply;
 
-    if env.block.height > pool.last_reward_block.u64() {
-        if !lp_supply.is_zero() {
+    if height > pool.last_reward_block.u64() {
+        // No new emissions accrue while paused, the paused period is skipped
+        if !lp_supply.is_zero() && !pool.is_emissions_paused(cfg) {
             let alloc_point = get_alloc_point(&cfg.active_pools, lp_token);
-            let token_rewards = calculate_rewards(
-                env.block.height - pool.last_reward_block.u64(),
-                &alloc_point,
-                cfg,
-            )?;
+            let token_rewards =
+                calculate_rewards(height - pool.last_reward_block.u64(), &alloc_point, cfg)?;
 
             let share = Decimal::from_ratio(token_rewards, lp_supply);
             pool.reward_global_index = pool.reward_global_index.checked_add(share)?;
         }
 
-        pool.last_reward_block = Uint64::from(env.block.height);
+        pool.last_reward_block = Uint64::from(height);
     }
 
     Ok(())
@@ -941,6 +2534,7 @@ pub fn accumulate_rewards_per_share(
 
 /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
 /// * **cw20_msg** CW20 message to process.
+#[cfg(not(feature = "bank"))]
 fn receive_cw20(
     mut deps: DepsMut,
     env: Env,
@@ -968,33 +2562,134 @@ fn receive_cw20(
         ),
         Cw20HookMsg::DepositFor(beneficiary) => {
             let account = deps.api.addr_validate(&beneficiary)?;
-            update_rewards_and_execute(
+            // The Generator is the CW20 sender of the `SendFrom` issued by `deposit_for`, which
+            // reports the owner of the LP tokens as the sponsor itself
+            let sponsor = (cw20_msg.sender != env.contract.address.as_str())
+                .then(|| attr("sponsor", cw20_msg.sender));
+            let response = update_rewards_and_execute(
                 deps,
                 env,
                 Some(vec![lp_token.clone()]),
                 ExecuteOnReply::Deposit {
                     lp_token,
-                    account,
+                    account: account.clone(),
                     amount,
                 },
-            )
+            )?;
+
+            Ok(response
+                .add_attributes(sponsor)
+                .add_attribute("beneficiary", account))
         }
     }
 }
 
+/// Stakes native LP tokens sent along with the message. Only available in bank mode, where the
+/// LP token must be the only coin sent.
+///
+/// * **beneficiary** address that will take ownership of the staked LP tokens. Defaults to the sender.
+fn deposit_native(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    beneficiary: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_bank_mode()?;
+
+    let coin = one_coin(&info)?;
+    let lp_token = validate_lp_token(deps.api, &coin.denom)?;
+    let account = addr_opt_validate(deps.api, &beneficiary)?.unwrap_or(info.sender);
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if !POOL_INFO.has(deps.storage, &lp_token) {
+        create_pool(deps.branch(), &env, &lp_token, &cfg)?;
+    }
+
+    update_rewards_and_execute(
+        deps,
+        env,
+        Some(vec![lp_token.clone()]),
+        ExecuteOnReply::Deposit {
+            lp_token,
+            account,
+            amount: coin.amount,
+        },
+    )
+}
+
+/// Stakes LP tokens on behalf of the beneficiary. The tokens are pulled from the sender's allowance
+/// and sent back to the Generator with [`Cw20HookMsg::DepositFor`], so the position is created
+/// in the same transaction.
+///
+/// * **lp_token** LP token to deposit.
+///
+/// * **beneficiary** address that will take ownership of the staked LP tokens.
+///
+/// * **amount** amount of LP tokens to deposit.
+#[cfg(not(feature = "bank"))]
+fn deposit_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    beneficiary: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+
+    let send_msg = wasm_execute(
+        &lp_token,
+        &Cw20ExecuteMsg::SendFrom {
+            owner: info.sender.to_string(),
+            contract: env.contract.address.to_string(),
+            amount,
+            msg: to_binary(&Cw20HookMsg::DepositFor(beneficiary.to_string()))?,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_message(send_msg).add_attributes([
+        attr("action", "deposit_for"),
+        attr("lp_token", lp_token),
+        attr("sponsor", info.sender),
+        attr("beneficiary", beneficiary),
+        attr("amount", amount),
+    ]))
+}
+
 /// Distributes pending proxy rewards for a specific staker.
 ///
 /// * **pool** generator where the a user was staked.
 ///
 /// * **user** staker for which we claim accrued proxy rewards.
 ///
-/// * **to** address that will receive the proxy rewards.
+/// * **to** address that will receive the proxy rewards. GRID rewards are claimed by the generator
+/// and auto-staked if the address opted in.
 pub fn send_pending_rewards(
     deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    pool: &PoolInfo,
+    user: &UserInfoV2,
+    to: &Addr,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    send_pending_rewards_as(deps, env, cfg, pool, user, to, None)
+}
+
+/// Same as [`send_pending_rewards`], but GRID rewards are swapped into another asset if `swap_to`
+/// is set.
+///
+/// * **swap_to** the asset GRID rewards are swapped into and the minimum amount to receive.
+/// Takes precedence over auto-staking.
+pub fn send_pending_rewards_as(
+    deps: Deps,
+    env: &Env,
     cfg: &Config,
     pool: &PoolInfo,
     user: &UserInfoV2,
     to: &Addr,
+    swap_to: Option<&(AssetInfo, Option<Uint128>)>,
 ) -> Result<Vec<WasmMsg>, ContractError> {
     if user.amount.is_zero() {
         return Ok(vec![]);
@@ -1006,14 +2701,61 @@ pub fn send_pending_rewards(
         .checked_mul_uint128(user.virtual_amount)?;
 
     if !pending_rewards.is_zero() {
-        messages.push(WasmMsg::Execute {
-            contract_addr: cfg.vesting_contract.to_string(),
-            msg: to_binary(&VestingExecuteMsg::Claim {
-                recipient: Some(to.to_string()),
-                amount: Some(pending_rewards),
-            })?,
-            funds: vec![],
-        });
+        let auto_stake = cfg.staking_contract.is_some()
+            && AUTO_STAKE_REWARDS
+                .may_load(deps.storage, to)?
+                .unwrap_or_default();
+
+        if let Some((target_asset, min_received)) = swap_to {
+            messages.push(WasmMsg::Execute {
+                contract_addr: cfg.vesting_contract.to_string(),
+                msg: to_binary(&VestingExecuteMsg::Claim {
+                    recipient: Some(env.contract.address.to_string()),
+                    amount: Some(pending_rewards),
+                })?,
+                funds: vec![],
+            });
+            messages.push(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback {
+                    action: ExecuteOnReply::SwapRewards {
+                        account: to.clone(),
+                        amount: pending_rewards,
+                        target_asset: target_asset.clone(),
+                        min_received: *min_received,
+                    },
+                })?,
+                funds: vec![],
+            });
+        } else if auto_stake {
+            messages.push(WasmMsg::Execute {
+                contract_addr: cfg.vesting_contract.to_string(),
+                msg: to_binary(&VestingExecuteMsg::Claim {
+                    recipient: Some(env.contract.address.to_string()),
+                    amount: Some(pending_rewards),
+                })?,
+                funds: vec![],
+            });
+            messages.push(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback {
+                    action: ExecuteOnReply::AutoStakeRewards {
+                        account: to.clone(),
+                        amount: pending_rewards,
+                    },
+                })?,
+                funds: vec![],
+            });
+        } else {
+            messages.push(WasmMsg::Execute {
+                contract_addr: cfg.vesting_contract.to_string(),
+                msg: to_binary(&VestingExecuteMsg::Claim {
+                    recipient: Some(to.to_string()),
+                    amount: Some(pending_rewards),
+                })?,
+                funds: vec![],
+            });
+        }
     }
 
     let proxy_rewards = accumulate_pool_proxy_rewards(pool, user)?;
@@ -1021,32 +2763,33 @@ pub fn send_pending_rewards(
     let proxy_rewards_holder = PROXY_REWARDS_HOLDER.load(deps.storage)?;
     for (proxy, pending_proxy_rewards) in proxy_rewards {
         if !pending_proxy_rewards.is_zero() {
-            match &pool.reward_proxy {
-                Some(reward_proxy) if reward_proxy == proxy => {
-                    messages.push(WasmMsg::Execute {
-                        contract_addr: proxy.to_string(),
-                        funds: vec![],
-                        msg: to_binary(&ProxyExecuteMsg::SendRewards {
-                            account: to.to_string(),
-                            amount: pending_proxy_rewards,
-                        })?,
-                    });
-                }
-                _ => {
-                    // Old proxy rewards are paid from reward holder
-                    let asset_info = PROXY_REWARD_ASSET.load(deps.storage, &proxy)?;
-                    messages.push(WasmMsg::Execute {
-                        contract_addr: proxy_rewards_holder.to_string(),
-                        funds: vec![],
-                        msg: to_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
-                            msgs: vec![Asset {
-                                info: asset_info,
-                                amount: pending_proxy_rewards,
-                            }
-                            .into_msg::<Empty>(to.clone())?],
-                        })?,
-                    });
+            if pool.is_active_proxy(&proxy) {
+                // Rewards of a proxy in emergency mode are forfeited
+                if is_proxy_in_emergency(&deps.querier, &proxy) {
+                    continue;
                 }
+                messages.push(WasmMsg::Execute {
+                    contract_addr: proxy.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&ProxyExecuteMsg::SendRewards {
+                        account: to.to_string(),
+                        amount: pending_proxy_rewards,
+                    })?,
+                });
+            } else {
+                // Old proxy rewards are paid from reward holder
+                let asset_info = PROXY_REWARD_ASSET.load(deps.storage, &proxy)?;
+                messages.push(WasmMsg::Execute {
+                    contract_addr: proxy_rewards_holder.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
+                        msgs: vec![Asset {
+                            info: asset_info,
+                            amount: pending_proxy_rewards,
+                        }
+                        .into_msg::<Empty>(to.clone())?],
+                    })?,
+                });
             }
         }
     }
@@ -1075,21 +2818,27 @@ pub fn deposit(
     let cfg = CONFIG.load(deps.storage)?;
     let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
 
-    accumulate_rewards_per_share(&deps.querier, &env, &lp_token, &mut pool, &cfg)?;
+    accumulate_rewards_per_share(&deps.querier, &env, &lp_token, &mut pool, &cfg, amount)?;
 
     // Send pending rewards (if any) to the depositor
-    let mut messages = send_pending_rewards(deps.as_ref(), &cfg, &pool, &user, &beneficiary)?;
+    let mut messages = send_pending_rewards(deps.as_ref(), &env, &cfg, &pool, &user, &beneficiary)?;
+
+    let mut lp_balance = query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
 
-    let mut lp_balance = query_lp_balance(deps.as_ref(), &env.contract.address, &lp_token, &pool)?;
+    // If a reward proxy holds the pool's LP tokens - send LP tokens to the proxy
+    if let Some(proxy) = pool.lp_proxy().filter(|_| !amount.is_zero()) {
+        if is_proxy_in_emergency(&deps.querier, proxy) {
+            return Err(ContractError::ProxyInEmergency {
+                proxy: proxy.clone(),
+            });
+        }
 
-    // If a reward proxy is set - send LP tokens to the proxy
-    if !amount.is_zero() && pool.reward_proxy.is_some() {
         // Consider deposited LP tokens
         lp_balance += amount;
         messages.push(wasm_execute(
             &lp_token,
             &Cw20ExecuteMsg::Send {
-                contract: pool.reward_proxy.clone().unwrap().to_string(),
+                contract: proxy.to_string(),
                 msg: to_binary(&ProxyCw20HookMsg::Deposit {})?,
                 amount,
             },
@@ -1113,8 +2862,11 @@ pub fn deposit(
     POOL_INFO.save(deps.storage, &lp_token, &pool)?;
     USER_INFO.save(deps.storage, (&lp_token, &beneficiary), &user)?;
 
+    let points_msg = points_hook_msg(deps.as_ref(), &cfg, &beneficiary, &lp_token, user.amount)?;
+
     Ok(Response::new()
         .add_messages(messages)
+        .add_messages(points_msg)
         .add_attribute("action", "deposit")
         .add_attribute("amount", amount))
 }
@@ -1125,12 +2877,15 @@ pub fn deposit(
 ///
 /// * **account** user whose LP tokens we withdraw.
 ///
+/// * **recipient** address that receives the LP tokens.
+///
 /// * **amount** amount of LP tokens to withdraw.
 pub fn withdraw(
     deps: DepsMut,
     env: Env,
     lp_token: Addr,
     account: Addr,
+    recipient: Addr,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     let user = USER_INFO
@@ -1143,36 +2898,64 @@ pub fn withdraw(
     let cfg = CONFIG.load(deps.storage)?;
     let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
 
-    accumulate_rewards_per_share(&deps.querier, &env, &lp_token, &mut pool, &cfg)?;
+    accumulate_rewards_per_share(
+        &deps.querier,
+        &env,
+        &lp_token,
+        &mut pool,
+        &cfg,
+        Uint128::zero(),
+    )?;
 
     // Send pending rewards to the user
-    let mut send_rewards_msgs = send_pending_rewards(deps.as_ref(), &cfg, &pool, &user, &account)?;
+    let mut send_rewards_msgs =
+        send_pending_rewards(deps.as_ref(), &env, &cfg, &pool, &user, &account)?;
+
+    // Burn receipt tokens backed by the withdrawn LP tokens
+    let backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &account))?
+        .unwrap_or_default();
+    let burn_amount = (backed + amount).saturating_sub(user.amount);
+    if !burn_amount.is_zero() {
+        send_rewards_msgs.push(burn_receipt_msg(
+            deps.as_ref(),
+            &lp_token,
+            &account,
+            burn_amount,
+        )?);
+        update_receipt_backed(deps.storage, &lp_token, &account, backed - burn_amount)?;
+    }
 
     // Instantiate the transfer call for the LP token
-    let transfer_msg = match &pool.reward_proxy {
-        Some(proxy) => WasmMsg::Execute {
+    let transfer_msg: CosmosMsg = match pool.lp_proxy() {
+        // A proxy in emergency mode releases LP tokens without touching its rewards
+        Some(proxy) if is_proxy_in_emergency(&deps.querier, proxy) => WasmMsg::Execute {
             contract_addr: proxy.to_string(),
             funds: vec![],
-            msg: to_binary(&ProxyExecuteMsg::Withdraw {
-                account: account.to_string(),
+            msg: to_binary(&ProxyExecuteMsg::EmergencyWithdraw {
+                account: recipient.to_string(),
                 amount,
             })?,
-        },
-        None => WasmMsg::Execute {
-            contract_addr: lp_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: account.to_string(),
+        }
+        .into(),
+        Some(proxy) => WasmMsg::Execute {
+            contract_addr: proxy.to_string(),
+            funds: vec![],
+            msg: to_binary(&ProxyExecuteMsg::Withdraw {
+                account: recipient.to_string(),
                 amount,
             })?,
-            funds: vec![],
-        },
+        }
+        .into(),
+        None => lp_asset_info(&lp_token)
+            .with_balance(amount)
+            .into_msg(&recipient)?,
     };
-    send_rewards_msgs.push(transfer_msg);
 
     // Update user's balance
     let updated_amount = user.amount.checked_sub(amount)?;
     let mut user = update_user_balance(user, &pool, updated_amount)?;
-    let lp_balance = query_lp_balance(deps.as_ref(), &env.contract.address, &lp_token, &pool)?;
+    let lp_balance = query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
 
     update_virtual_amount(
         deps.querier,
@@ -1191,11 +2974,111 @@ pub fn withdraw(
         USER_INFO.remove(deps.storage, (&lp_token, &account));
     }
 
+    let points_msg = points_hook_msg(deps.as_ref(), &cfg, &account, &lp_token, user.amount)?;
+
     Ok(Response::new()
         .add_messages(send_rewards_msgs)
+        .add_message(transfer_msg)
+        .add_messages(points_msg)
         .add_attribute("action", "withdraw")
         .add_attribute("amount", amount))
 }
+
+/// Withdraws liquidity from the pair with LP tokens held by the Generator. The pool assets are
+/// sent to the account once the withdrawal succeeds.
+///
+/// * **lp_token** LP token to unwrap.
+///
+/// * **account** address that receives the pool assets.
+///
+/// * **amount** amount of LP tokens to unwrap.
+///
+/// * **min_assets** minimum amounts of the pool assets to receive.
+#[cfg(not(feature = "bank"))]
+fn unwrap_lp(
+    deps: DepsMut,
+    env: Env,
+    lp_token: Addr,
+    account: Addr,
+    amount: Uint128,
+    min_assets: Vec<Asset>,
+) -> Result<Response, ContractError> {
+    let pair_info = lp_pair_info(&deps.querier, &lp_token)?;
+
+    // Check that `min_assets` belong to the pair
+    for asset in &min_assets {
+        if !pair_info.asset_infos.contains(&asset.info) {
+            return Err(ContractError::AssetNotInPair(asset.info.to_string()));
+        }
+    }
+
+    // The Generator may already hold some of the pool assets, so only the difference is sent
+    let balances_before = pair_info.query_pools(&deps.querier, &env.contract.address)?;
+    UNWRAP_CONTEXT.save(
+        deps.storage,
+        &UnwrapContext {
+            account,
+            min_assets,
+            balances_before,
+        },
+    )?;
+
+    let withdraw_msg = wasm_execute(
+        lp_token,
+        &Cw20ExecuteMsg::Send {
+            contract: pair_info.contract_addr.to_string(),
+            amount,
+            msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity {
+                assets: vec![],
+                deadline: None,
+            })?,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+        withdraw_msg,
+        WITHDRAW_AND_UNWRAP_ID,
+    )))
+}
+
+/// Sends the pool assets received from the pair to the account after checking them against the
+/// minimum amounts.
+#[cfg(not(feature = "bank"))]
+fn unwrap_lp_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let ctx = UNWRAP_CONTEXT.load(deps.storage)?;
+    UNWRAP_CONTEXT.remove(deps.storage);
+
+    let mut messages = vec![];
+    let mut attrs = vec![
+        attr("action", "withdraw_and_unwrap"),
+        attr("user", &ctx.account),
+    ];
+    for before in ctx.balances_before {
+        let received = before
+            .info
+            .query_pool(&deps.querier, &env.contract.address)?
+            .checked_sub(before.amount)?;
+
+        if let Some(min_asset) = ctx.min_assets.iter().find(|a| a.info == before.info) {
+            if received < min_asset.amount {
+                return Err(ContractError::WithdrawSlippageViolation {
+                    asset_name: before.info.to_string(),
+                    received,
+                    expected: min_asset.amount,
+                });
+            }
+        }
+
+        let received = before.info.with_balance(received);
+        attrs.push(attr("asset", received.to_string()));
+        if !received.amount.is_zero() {
+            messages.push(received.into_msg::<Empty>(&ctx.account)?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(attrs))
+}
 /// Withdraw LP tokens without caring about rewards. TO BE USED IN EMERGENCY SITUATIONS ONLY.
 ///
 /// * **lp_token** LP token to withdraw.
@@ -1204,14 +3087,12 @@ pub fn emergency_withdraw(
     info: MessageInfo,
     lp_token: String,
 ) -> Result<Response, ContractError> {
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
 
     let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
     let user = USER_INFO.compatible_load(deps.storage, (&lp_token, &info.sender))?;
 
-    // Instantiate the transfer call for the LP token
-    let transfer_msg;
-    if let Some(proxy) = &pool.reward_proxy {
+    if !pool.reward_proxies.is_empty() {
         let accumulated_proxy_rewards: HashMap<_, _> = accumulate_pool_proxy_rewards(&pool, &user)?
             .into_iter()
             .collect();
@@ -1230,32 +3111,52 @@ pub fn emergency_withdraw(
             })
             .collect::<StdResult<Vec<_>>>()?
             .into();
+    }
 
-        transfer_msg = WasmMsg::Execute {
+    // Instantiate the transfer call for the LP token
+    let transfer_msg: CosmosMsg = match pool.lp_proxy() {
+        Some(proxy) => WasmMsg::Execute {
             contract_addr: proxy.to_string(),
             msg: to_binary(&ProxyExecuteMsg::EmergencyWithdraw {
                 account: info.sender.to_string(),
                 amount: user.amount,
             })?,
             funds: vec![],
-        };
-    } else {
-        transfer_msg = WasmMsg::Execute {
-            contract_addr: lp_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
-                amount: user.amount,
-            })?,
-            funds: vec![],
-        };
+        }
+        .into(),
+        None => lp_asset_info(&lp_token)
+            .with_balance(user.amount)
+            .into_msg(&info.sender)?,
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    // Burn all receipt tokens backed by the user's position
+    let backed = RECEIPT_BACKED
+        .may_load(deps.storage, (&lp_token, &info.sender))?
+        .unwrap_or_default();
+    if !backed.is_zero() {
+        messages.push(burn_receipt_msg(deps.as_ref(), &lp_token, &info.sender, backed)?.into());
+        RECEIPT_BACKED.remove(deps.storage, (&lp_token, &info.sender));
     }
+    messages.push(transfer_msg);
 
     // Change the user's balance
     USER_INFO.remove(deps.storage, (&lp_token, &info.sender));
     POOL_INFO.save(deps.storage, &lp_token, &pool)?;
 
+    let cfg = CONFIG.load(deps.storage)?;
+    let points_msg = points_hook_msg(
+        deps.as_ref(),
+        &cfg,
+        &info.sender,
+        &lp_token,
+        Uint128::zero(),
+    )?;
+
     Ok(Response::new()
-        .add_message(transfer_msg)
+        .add_messages(messages)
+        .add_messages(points_msg)
         .add_attribute("action", "emergency_withdraw")
         .add_attribute("amount", user.amount))
 }
@@ -1277,7 +3178,7 @@ fn send_orphan_proxy_rewards(
         return Err(ContractError::Unauthorized {});
     };
 
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
     let recipient = deps.api.addr_validate(&recipient)?;
 
     let mut pool = POOL_INFO.load(deps.storage, &lp_token)?;
@@ -1293,29 +3194,28 @@ fn send_orphan_proxy_rewards(
         .iter()
         .filter(|(_, value)| !value.is_zero())
         .map(|(proxy, amount)| {
-            let msg = match &pool.reward_proxy {
-                Some(reward_proxy) if reward_proxy == proxy => SubMsg::new(WasmMsg::Execute {
-                    contract_addr: reward_proxy.to_string(),
+            let msg = if pool.is_active_proxy(proxy) {
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: proxy.to_string(),
                     funds: vec![],
                     msg: to_binary(&ProxyExecuteMsg::SendRewards {
                         account: recipient.to_string(),
                         amount: *amount,
                     })?,
-                }),
-                _ => {
-                    let asset_info = PROXY_REWARD_ASSET.load(deps.storage, proxy)?;
-                    SubMsg::new(WasmMsg::Execute {
-                        contract_addr: proxy_rewards_holder.to_string(),
-                        funds: vec![],
-                        msg: to_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
-                            msgs: vec![Asset {
-                                info: asset_info,
-                                amount: *amount,
-                            }
-                            .into_msg::<Empty>(&recipient)?],
-                        })?,
-                    })
-                }
+                })
+            } else {
+                let asset_info = PROXY_REWARD_ASSET.load(deps.storage, proxy)?;
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: proxy_rewards_holder.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
+                        msgs: vec![Asset {
+                            info: asset_info,
+                            amount: *amount,
+                        }
+                        .into_msg::<Empty>(&recipient)?],
+                    })?,
+                })
             };
 
             Ok(msg)
@@ -1362,6 +3262,7 @@ fn init_proxy_rewards_holder(
 
 /// Entry point of proxy migration process. Updates rewards state and appends callback to process
 /// the next stage.
+#[cfg(not(feature = "bank"))]
 fn migrate_proxy(
     deps: DepsMut,
     env: Env,
@@ -1369,7 +3270,7 @@ fn migrate_proxy(
     lp_token: String,
     new_proxy: String,
 ) -> Result<Response, ContractError> {
-    let lp_addr = deps.api.addr_validate(&lp_token)?;
+    let lp_addr = validate_lp_token(deps.api, &lp_token)?;
     let new_proxy_addr = deps.api.addr_validate(&new_proxy)?;
 
     let cfg = CONFIG.load(deps.storage)?;
@@ -1379,15 +3280,18 @@ fn migrate_proxy(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Check the pool has reward proxy
+    // Check the pool has a reward proxy which holds LP tokens
     let pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
-    if let Some(proxy) = &pool_info.reward_proxy {
+    if let Some(proxy) = pool_info.lp_proxy() {
         if proxy == new_proxy_addr {
             return Err(StdError::generic_err("Can not migrate to the same proxy").into());
         }
     } else {
         return Err(StdError::generic_err("Pool does not have proxy").into());
     }
+    if pool_info.is_active_proxy(&new_proxy_addr) {
+        return Err(ContractError::RewardProxyAlreadyUsed {});
+    }
 
     update_rewards_and_execute(
         deps,
@@ -1410,17 +3314,31 @@ fn migrate_proxy_callback(
 ) -> Result<Response, ContractError> {
     let mut pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
     let cfg = CONFIG.load(deps.storage)?;
-    accumulate_rewards_per_share(&deps.querier, &env, &lp_addr, &mut pool_info, &cfg)?;
+    accumulate_rewards_per_share(
+        &deps.querier,
+        &env,
+        &lp_addr,
+        &mut pool_info,
+        &cfg,
+        Uint128::zero(),
+    )?;
 
     // We've checked this before the callback so it's safe to unwrap here
-    let prev_proxy_addr = pool_info.reward_proxy.clone().unwrap();
+    let lp_proxy = pool_info
+        .reward_proxies
+        .iter_mut()
+        .find(|reward_proxy| reward_proxy.holds_lp_tokens)
+        .unwrap();
+    let prev_proxy_addr = lp_proxy.proxy.clone();
 
     let proxy_lp_balance: Uint128 = deps
         .querier
         .query_wasm_smart(&prev_proxy_addr, &ProxyQueryMsg::Deposit {})?;
 
-    // Since we migrate to another proxy the proxy reward balance becomes 0.
-    pool_info.proxy_reward_balance_before_update = Uint128::zero();
+    // Set new proxy. Since we migrate to another proxy the proxy reward balance becomes 0.
+    lp_proxy.proxy = new_proxy_addr.clone();
+    lp_proxy.reward_balance_before_update = Uint128::zero();
+    lp_proxy.accounted_pending_rewards = Uint128::zero();
     // Save a new index and orphan rewards for the new proxy
     pool_info
         .accumulated_proxy_rewards_per_share
@@ -1428,8 +3346,6 @@ fn migrate_proxy_callback(
     pool_info
         .orphan_proxy_rewards
         .update(&new_proxy_addr, Uint128::zero())?;
-    // Set new proxy
-    pool_info.reward_proxy = Some(new_proxy_addr.clone());
 
     POOL_INFO.save(deps.storage, &lp_addr, &pool_info)?;
 
@@ -1493,7 +3409,7 @@ fn migrate_proxy_deposit_lp(
 ) -> Result<Response, ContractError> {
     let pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
     // We've set it before the callback so it's safe to unwrap here
-    let new_proxy = pool_info.reward_proxy.unwrap();
+    let new_proxy = pool_info.lp_proxy().cloned().unwrap();
 
     // Depositing LP tokens to new proxy
     let deposit_msg = WasmMsg::Execute {
@@ -1515,6 +3431,7 @@ fn migrate_proxy_deposit_lp(
 }
 
 /// Sets the reward proxy contract for a specific generator.
+#[cfg(not(feature = "bank"))]
 fn move_to_proxy(
     mut deps: DepsMut,
     env: Env,
@@ -1522,7 +3439,7 @@ fn move_to_proxy(
     lp_token: String,
     proxy: String,
 ) -> Result<Response, ContractError> {
-    let lp_addr = deps.api.addr_validate(&lp_token)?;
+    let lp_addr = validate_lp_token(deps.api, &lp_token)?;
     let proxy_addr = deps.api.addr_validate(&proxy)?;
 
     let cfg = CONFIG.load(deps.storage)?;
@@ -1537,18 +3454,11 @@ fn move_to_proxy(
     }
 
     let mut pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
-    if pool_info.reward_proxy.is_some() {
+    if pool_info.lp_proxy().is_some() {
         return Err(ContractError::PoolAlreadyHasRewardProxyContract {});
     }
 
-    update_proxy_asset(deps.branch(), &proxy_addr)?;
-    pool_info
-        .orphan_proxy_rewards
-        .update(&proxy_addr, Uint128::zero())?;
-    pool_info
-        .accumulated_proxy_rewards_per_share
-        .update(&proxy_addr, Decimal::zero())?;
-    pool_info.reward_proxy = Some(proxy_addr);
+    attach_reward_proxy(deps.branch(), &mut pool_info, &proxy_addr, true)?;
 
     let res: BalanceResponse = deps.querier.query_wasm_smart(
         &lp_addr,
@@ -1561,7 +3471,7 @@ fn move_to_proxy(
         vec![WasmMsg::Execute {
             contract_addr: lp_addr.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: pool_info.reward_proxy.clone().unwrap().to_string(),
+                contract: proxy_addr.to_string(),
                 msg: to_binary(&ProxyCw20HookMsg::Deposit {})?,
                 amount: res.balance,
             })?,
@@ -1578,6 +3488,183 @@ fn move_to_proxy(
         .add_attributes(vec![attr("action", "move_to_proxy"), attr("proxy", proxy)]))
 }
 
+/// Attaches a reward proxy to the pool with empty reward indexes.
+/// A proxy which was ever used by the pool can't be attached again because its old rewards are
+/// paid from the proxy rewards holder.
+#[cfg(not(feature = "bank"))]
+fn attach_reward_proxy(
+    deps: DepsMut,
+    pool_info: &mut PoolInfo,
+    proxy_addr: &Addr,
+    holds_lp_tokens: bool,
+) -> Result<(), ContractError> {
+    if pool_info
+        .accumulated_proxy_rewards_per_share
+        .load(proxy_addr)
+        .is_some()
+    {
+        return Err(ContractError::RewardProxyAlreadyUsed {});
+    }
+
+    update_proxy_asset(deps, proxy_addr)?;
+    pool_info
+        .orphan_proxy_rewards
+        .update(proxy_addr, Uint128::zero())?;
+    pool_info
+        .accumulated_proxy_rewards_per_share
+        .update(proxy_addr, Decimal::zero())?;
+    pool_info.reward_proxies.push(RewardProxyInfo {
+        proxy: proxy_addr.clone(),
+        holds_lp_tokens,
+        reward_balance_before_update: Uint128::zero(),
+        accounted_pending_rewards: Uint128::zero(),
+    });
+
+    Ok(())
+}
+
+/// Attaches an additional reward proxy to a specific generator. The proxy doesn't hold LP tokens,
+/// its rewards are distributed pro rata to the LP tokens staked in the generator.
+#[cfg(not(feature = "bank"))]
+fn add_reward_proxy(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    proxy: String,
+) -> Result<Response, ContractError> {
+    let lp_addr = validate_lp_token(deps.api, &lp_token)?;
+    let proxy_addr = deps.api.addr_validate(&proxy)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !POOL_INFO.has(deps.storage, &lp_addr) {
+        create_pool(deps.branch(), &env, &lp_addr, &cfg)?;
+    }
+
+    let mut pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
+    attach_reward_proxy(deps.branch(), &mut pool_info, &proxy_addr, false)?;
+    POOL_INFO.save(deps.storage, &lp_addr, &pool_info)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "add_reward_proxy"),
+        attr("lp_token", lp_addr),
+        attr("proxy", proxy_addr),
+    ]))
+}
+
+/// Entry point of reward proxy deactivation. Updates rewards state and appends callback to
+/// detach the proxy.
+fn deactivate_reward_proxy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    proxy: String,
+) -> Result<Response, ContractError> {
+    let lp_addr = validate_lp_token(deps.api, &lp_token)?;
+    let proxy_addr = deps.api.addr_validate(&proxy)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
+    if !pool_info.is_active_proxy(&proxy_addr) {
+        return Err(ContractError::RewardProxyNotFound {});
+    }
+
+    update_rewards_and_execute(
+        deps,
+        env,
+        Some(vec![lp_addr.clone()]),
+        ExecuteOnReply::DeactivateRewardProxy {
+            lp_addr,
+            proxy_addr,
+        },
+    )
+}
+
+/// Detaches the reward proxy from the pool. The whole proxy reward balance is transferred to the
+/// rewards holder which pays out rewards accrued so far. If the proxy holds the pool's LP tokens
+/// they are withdrawn to the generator.
+fn deactivate_reward_proxy_callback(
+    deps: DepsMut,
+    env: Env,
+    lp_addr: Addr,
+    proxy_addr: Addr,
+) -> Result<Response, ContractError> {
+    let mut pool_info = POOL_INFO.load(deps.storage, &lp_addr)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    accumulate_rewards_per_share(
+        &deps.querier,
+        &env,
+        &lp_addr,
+        &mut pool_info,
+        &cfg,
+        Uint128::zero(),
+    )?;
+
+    // We've checked this before the callback so it's safe to unwrap here
+    let position = pool_info
+        .reward_proxies
+        .iter()
+        .position(|reward_proxy| reward_proxy.proxy == proxy_addr)
+        .unwrap();
+    let reward_proxy = pool_info.reward_proxies.remove(position);
+
+    POOL_INFO.save(deps.storage, &lp_addr, &pool_info)?;
+
+    let mut messages = vec![];
+
+    // Transfer whole proxy reward balance to the rewards holder
+    let rewards_amount: Uint128 = deps
+        .querier
+        .query_wasm_smart(&proxy_addr, &ProxyQueryMsg::Reward {})?;
+    if !rewards_amount.is_zero() {
+        let rewards_holder = PROXY_REWARDS_HOLDER.load(deps.storage)?;
+        messages.push(WasmMsg::Execute {
+            contract_addr: proxy_addr.to_string(),
+            msg: to_binary(&ProxyExecuteMsg::SendRewards {
+                account: rewards_holder.to_string(),
+                amount: rewards_amount,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    // Return LP tokens to the generator
+    if reward_proxy.holds_lp_tokens {
+        let proxy_lp_balance: Uint128 = deps
+            .querier
+            .query_wasm_smart(&proxy_addr, &ProxyQueryMsg::Deposit {})?;
+        if !proxy_lp_balance.is_zero() {
+            messages.push(WasmMsg::Execute {
+                contract_addr: proxy_addr.to_string(),
+                msg: to_binary(&ProxyExecuteMsg::Withdraw {
+                    account: env.contract.address.to_string(),
+                    amount: proxy_lp_balance,
+                })?,
+                funds: vec![],
+            });
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "deactivate_reward_proxy"),
+        attr("lp_token", lp_addr),
+        attr("proxy", proxy_addr),
+    ]))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -1599,6 +3686,18 @@ fn move_to_proxy(
 ///
 /// * **QueryMsg::SimulateFutureReward { lp_token, future_block }** Returns the amount of token rewards a generator will
 /// distribute up to a future block.
+///
+/// * **QueryMsg::MerkleDrop { drop_id }** Returns a merkle drop of retroactive GRID rewards.
+///
+/// * **QueryMsg::MerkleDropClaimed { drop_id, user }** Returns the amount of GRID a user claimed from a merkle drop.
+///
+/// * **QueryMsg::PoolTvl { lp_token }** Returns the underlying assets staked in a generator using a [`PoolTvlResponse`] object.
+///
+/// * **QueryMsg::UserEmissionRate { lp_token, user }** Returns the rewards a user receives per block using a [`UserEmissionRateResponse`] object.
+///
+/// * **QueryMsg::UpdatePoolsCursor {}** Returns the position of an unfinished `UpdatePools` pass.
+///
+/// * **QueryMsg::EmissionSchedule {}** Returns the queued changes of GRID per block.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -1624,7 +3723,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::PendingToken { lp_token, user } => {
             Ok(to_binary(&pending_token(deps, env, lp_token, user)?)?)
         }
-        QueryMsg::Config {} => Ok(to_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::Config {} => Ok(to_binary(&load_config_with_due_emissions(deps, &env)?)?),
         QueryMsg::RewardInfo { lp_token } => Ok(to_binary(&query_reward_info(deps, lp_token)?)?),
         QueryMsg::OrphanProxyRewards { lp_token } => {
             Ok(to_binary(&query_orphan_proxy_rewards(deps, lp_token)?)?)
@@ -1634,9 +3733,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             lp_token,
             future_block,
         } => {
-            let cfg = CONFIG.load(deps.storage)?;
+            let cfg = load_config_with_due_emissions(deps, &env)?;
             let alloc_point =
-                get_alloc_point(&cfg.active_pools, &deps.api.addr_validate(&lp_token)?);
+                get_alloc_point(&cfg.active_pools, &validate_lp_token(deps.api, &lp_token)?);
 
             Ok(to_binary(&calculate_rewards(
                 future_block - env.block.height,
@@ -1647,6 +3746,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::BlockedTokensList {} => {
             Ok(to_binary(&CONFIG.load(deps.storage)?.blocked_tokens_list)?)
         }
+        QueryMsg::BlockedPools {} => Ok(to_binary(&CONFIG.load(deps.storage)?.blocked_pools)?),
         QueryMsg::PoolStakers {
             lp_token,
             start_after,
@@ -1662,12 +3762,54 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
                 .keys(deps.storage, None, None, Order::Ascending)
                 .collect::<Result<Vec<Addr>, StdError>>()?,
         )?),
+        QueryMsg::AutoStakeRewards { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            Ok(to_binary(
+                &AUTO_STAKE_REWARDS
+                    .may_load(deps.storage, &user)?
+                    .unwrap_or_default(),
+            )?)
+        }
+        QueryMsg::ReceiptToken { lp_token } => {
+            let lp_token = validate_lp_token(deps.api, &lp_token)?;
+            Ok(to_binary(
+                &RECEIPT_TOKENS.may_load(deps.storage, &lp_token)?,
+            )?)
+        }
+        QueryMsg::ReceiptBacked { lp_token, user } => {
+            let lp_token = validate_lp_token(deps.api, &lp_token)?;
+            let user = deps.api.addr_validate(&user)?;
+            Ok(to_binary(
+                &RECEIPT_BACKED
+                    .may_load(deps.storage, (&lp_token, &user))?
+                    .unwrap_or_default(),
+            )?)
+        }
+        QueryMsg::MerkleDrop { drop_id } => Ok(to_binary(&query_merkle_drop(deps, drop_id)?)?),
+        QueryMsg::MerkleDropClaimed { drop_id, user } => {
+            Ok(to_binary(&query_merkle_drop_claimed(deps, drop_id, user)?)?)
+        }
+        QueryMsg::PoolTvl { lp_token } => Ok(to_binary(&query_pool_tvl(deps, env, lp_token)?)?),
+        QueryMsg::UserEmissionRate { lp_token, user } => {
+            let res = query_user_emission_rate(deps, env, lp_token, user)?;
+            Ok(to_binary(&res)?)
+        }
+        QueryMsg::UpdatePoolsCursor {} => {
+            Ok(to_binary(&UPDATE_POOLS_CURSOR.may_load(deps.storage)?)?)
+        }
+        QueryMsg::StateSnapshot { start_after, limit } => Ok(to_binary(&query_state_snapshot(
+            deps,
+            env,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::EmissionSchedule {} => Ok(to_binary(&query_emission_schedule(deps)?)?),
     }
 }
 
 /// Return total virtual supply by pool
 pub fn total_virtual_supply(deps: Deps, generator: String) -> Result<Uint128, ContractError> {
-    let generator_addr = deps.api.addr_validate(&generator)?;
+    let generator_addr = validate_lp_token(deps.api, &generator)?;
     let pool = POOL_INFO.load(deps.storage, &generator_addr)?;
 
     Ok(pool.total_virtual_supply)
@@ -1679,7 +3821,7 @@ pub fn total_virtual_supply(deps: Deps, generator: String) -> Result<Uint128, Co
 ///
 /// * **user** user whose balance we query.
 pub fn query_deposit(deps: Deps, lp_token: String, user: String) -> Result<Uint128, ContractError> {
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
     let user = deps.api.addr_validate(&user)?;
 
     let user_info = USER_INFO
@@ -1698,7 +3840,7 @@ pub fn query_virtual_amount(
     lp_token: String,
     user: String,
 ) -> Result<Uint128, ContractError> {
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
     let user = deps.api.addr_validate(&user)?;
 
     let user_info = USER_INFO
@@ -1718,38 +3860,41 @@ pub fn pending_token(
     lp_token: String,
     user: String,
 ) -> Result<PendingTokenResponse, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
-
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
     let user = deps.api.addr_validate(&user)?;
 
-    let pool = POOL_INFO.load(deps.storage, &lp_token)?;
+    let (cfg, pool) = load_pool_with_due_emissions(deps, &env, &lp_token)?;
     let user_info = USER_INFO
         .compatible_load(deps.storage, (&lp_token, &user))
         .unwrap_or_default();
 
     let mut pending_on_proxy = None;
 
-    if let Some(proxy) = &pool.reward_proxy {
-        let proxy_lp_supply: Uint128 = deps
-            .querier
-            .query_wasm_smart(proxy, &ProxyQueryMsg::Deposit {})?;
+    if !pool.reward_proxies.is_empty() {
+        let proxy_lp_supply =
+            query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
 
         if !proxy_lp_supply.is_zero() {
             let proxy_rewards = accumulate_pool_proxy_rewards(&pool, &user_info)?
                 .into_iter()
                 .map(|(proxy_addr, mut reward)| {
-                    // Add reward pending on proxy
-                    if proxy_addr.eq(proxy) {
-                        let res: Option<Uint128> = deps
-                            .querier
-                            .query_wasm_smart(proxy, &ProxyQueryMsg::PendingToken {})?;
-                        if let Some(token_rewards) = res {
-                            let share = user_info
-                                .amount
-                                .multiply_ratio(token_rewards, proxy_lp_supply);
-                            reward = reward.checked_add(share)?;
-                        }
+                    if pool.is_active_proxy(&proxy_addr)
+                        && is_proxy_in_emergency(&deps.querier, &proxy_addr)
+                    {
+                        // Rewards of a proxy in emergency mode are forfeited
+                        reward = Uint128::zero();
+                    } else if let Some(reward_proxy) = pool
+                        .reward_proxies
+                        .iter()
+                        .find(|reward_proxy| reward_proxy.proxy == proxy_addr)
+                    {
+                        // Add reward pending on proxy
+                        let token_rewards =
+                            query_unaccounted_proxy_rewards(&deps.querier, reward_proxy)?;
+                        let share = user_info
+                            .amount
+                            .multiply_ratio(token_rewards, proxy_lp_supply);
+                        reward = reward.checked_add(share)?;
                     }
                     let info = PROXY_REWARD_ASSET.load(deps.storage, &proxy_addr)?;
                     Ok(Asset {
@@ -1766,7 +3911,10 @@ pub fn pending_token(
     let lp_supply = pool.total_virtual_supply;
 
     let mut acc_per_share = pool.reward_global_index;
-    if env.block.height > pool.last_reward_block.u64() && !lp_supply.is_zero() {
+    if env.block.height > pool.last_reward_block.u64()
+        && !lp_supply.is_zero()
+        && !pool.is_emissions_paused(&cfg)
+    {
         let alloc_point = get_alloc_point(&cfg.active_pools, &lp_token);
 
         let token_rewards = calculate_rewards(
@@ -1788,6 +3936,30 @@ pub fn pending_token(
     })
 }
 
+/// Returns the proxy rewards which are not accounted in the generator yet: rewards pending on the
+/// proxy which were not accounted while pending plus rewards already claimed by the proxy since
+/// the last snapshot. A proxy claim moves rewards from the former to the latter, so the result is
+/// the same before, during and after the generator processes the claim.
+///
+/// * **reward_proxy** one of the dual rewards proxies for the target LP/generator.
+fn query_unaccounted_proxy_rewards(
+    querier: &QuerierWrapper,
+    reward_proxy: &RewardProxyInfo,
+) -> StdResult<Uint128> {
+    if is_proxy_in_emergency(querier, &reward_proxy.proxy) {
+        return Ok(Uint128::zero());
+    }
+
+    let reward_amount: Uint128 =
+        querier.query_wasm_smart(&reward_proxy.proxy, &ProxyQueryMsg::Reward {})?;
+    let pending = query_proxy_pending_rewards(querier, &reward_proxy.proxy)?;
+
+    Ok(reward_amount
+        .saturating_sub(reward_proxy.reward_balance_before_update)
+        .checked_add(pending)?
+        .saturating_sub(reward_proxy.accounted_pending_rewards))
+}
+
 /// Returns reward information for a specific generator using a [`RewardInfoResponse`] object.
 /// ## Params
 ///
@@ -1795,21 +3967,22 @@ pub fn pending_token(
 fn query_reward_info(deps: Deps, lp_token: String) -> Result<RewardInfoResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
 
     let pool = POOL_INFO.load(deps.storage, &lp_token)?;
 
-    let proxy_reward_token = pool
-        .reward_proxy
-        .map(|proxy| {
+    let proxy_reward_tokens = pool
+        .reward_proxies
+        .iter()
+        .map(|reward_proxy| {
             deps.querier
-                .query_wasm_smart(proxy, &ProxyQueryMsg::RewardInfo {})
+                .query_wasm_smart(&reward_proxy.proxy, &ProxyQueryMsg::RewardInfo {})
         })
-        .transpose()?;
+        .collect::<StdResult<Vec<Addr>>>()?;
 
     Ok(RewardInfoResponse {
         base_reward_token: config.grid_token,
-        proxy_reward_token,
+        proxy_reward_tokens,
     })
 }
 
@@ -1821,10 +3994,10 @@ fn query_orphan_proxy_rewards(
     deps: Deps,
     lp_token: String,
 ) -> Result<Vec<(AssetInfo, Uint128)>, ContractError> {
-    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
 
     let pool = POOL_INFO.load(deps.storage, &lp_token)?;
-    if pool.reward_proxy.is_some() {
+    if !pool.accumulated_proxy_rewards_per_share.is_empty() {
         let orphan_rewards = pool
             .orphan_proxy_rewards
             .inner_ref()
@@ -1848,46 +4021,31 @@ fn query_pool_info(
     env: Env,
     lp_token: String,
 ) -> Result<PoolInfoResponse, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let (config, pool) = load_pool_with_due_emissions(deps, &env, &lp_token)?;
 
-    let lp_token = deps.api.addr_validate(&lp_token)?;
-    let pool = POOL_INFO.load(deps.storage, &lp_token)?;
-
-    let lp_supply: Uint128;
-    let mut pending_on_proxy = None;
+    let lp_supply = query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
+    let mut pending_on_proxy = vec![];
     let mut pending_grid_rewards = Uint128::zero();
 
-    // If proxy rewards are live for this LP token, calculate its pending proxy rewards
-    match &pool.reward_proxy {
-        Some(proxy) => {
-            lp_supply = deps
-                .querier
-                .query_wasm_smart(proxy, &ProxyQueryMsg::Deposit {})?;
-
-            // If LP tokens are staked via a proxy contract, fetch current pending proxy rewards
-            if !lp_supply.is_zero() {
-                let res: Uint128 = deps
-                    .querier
-                    .query_wasm_smart(proxy, &ProxyQueryMsg::PendingToken {})?;
-
-                if !res.is_zero() {
-                    pending_on_proxy = Some(res);
-                }
+    // If proxy rewards are live for this LP token, fetch current pending proxy rewards
+    if !lp_supply.is_zero() {
+        for reward_proxy in &pool.reward_proxies {
+            let res = query_unaccounted_proxy_rewards(&deps.querier, reward_proxy)?;
+
+            if !res.is_zero() {
+                pending_on_proxy.push((reward_proxy.proxy.clone(), res));
             }
         }
-        None => {
-            lp_supply = query_token_balance(
-                &deps.querier,
-                lp_token.clone(),
-                env.contract.address.clone(),
-            )?;
-        }
     }
 
     let alloc_point = get_alloc_point(&config.active_pools, &lp_token);
 
     // Calculate pending GRID rewards
-    if env.block.height > pool.last_reward_block.u64() && !lp_supply.is_zero() {
+    if env.block.height > pool.last_reward_block.u64()
+        && !lp_supply.is_zero()
+        && !pool.is_emissions_paused(&config)
+    {
         pending_grid_rewards = calculate_rewards(
             env.block.height - pool.last_reward_block.u64(),
             &alloc_point,
@@ -1902,26 +4060,145 @@ fn query_pool_info(
         .checked_div(config.total_alloc_point)
         .unwrap_or_else(|_| Uint128::zero());
 
+    let emissions_paused = pool.is_emissions_paused(&config);
+
     Ok(PoolInfoResponse {
         alloc_point,
         grid_tokens_per_block,
         last_reward_block: pool.last_reward_block.u64(),
         current_block: env.block.height,
         pending_grid_rewards,
-        reward_proxy: pool.reward_proxy,
+        reward_proxies: pool.reward_proxies,
         pending_proxy_rewards: pending_on_proxy,
         accumulated_proxy_rewards_per_share: pool
             .accumulated_proxy_rewards_per_share
             .inner_ref()
             .clone(),
-        proxy_reward_balance_before_update: pool.proxy_reward_balance_before_update,
         orphan_proxy_rewards: pool.orphan_proxy_rewards.inner_ref().clone(),
         lp_supply,
         global_reward_index: pool.reward_global_index,
+        last_proxy_rewards_claim_block: pool.last_proxy_rewards_claim_block.map(|b| b.u64()),
+        emissions_paused,
+    })
+}
+
+/// Returns the underlying assets of the LP tokens staked in a generator. The staked amounts are
+/// prorated from the pair reserves by the share of the total LP supply staked in the generator.
+///
+/// * **lp_token** LP token whose generator we query.
+fn query_pool_tvl(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+) -> Result<PoolTvlResponse, ContractError> {
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let pool = POOL_INFO.load(deps.storage, &lp_token)?;
+
+    let staked_lp = query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
+
+    let pair = lp_pair_info(&deps.querier, &lp_token)?.contract_addr;
+    let pool_res: PoolResponse = deps
+        .querier
+        .query_wasm_smart(&pair, &PairQueryMsg::Pool {})?;
+
+    let total_lp = pool_res.total_share;
+    let staked_share = if total_lp.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(staked_lp, total_lp)
+    };
+    let staked_assets = pool_res
+        .assets
+        .iter()
+        .map(|asset| {
+            let amount = if total_lp.is_zero() {
+                Uint128::zero()
+            } else {
+                asset.amount.multiply_ratio(staked_lp, total_lp)
+            };
+            asset.info.with_balance(amount)
+        })
+        .collect();
+
+    Ok(PoolTvlResponse {
+        pair,
+        staked_lp,
+        total_lp,
+        staked_share,
+        staked_assets,
+        total_assets: pool_res.assets,
+    })
+}
+
+/// Returns the rewards a user currently receives per block in a specific generator using a
+/// [`UserEmissionRateResponse`] object. GRID emissions are shared by virtual amounts while proxy
+/// rewards are shared by staked amounts.
+///
+/// * **lp_token** LP token staked by the user.
+///
+/// * **user** user whose emission rate we query.
+fn query_user_emission_rate(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+    user: String,
+) -> Result<UserEmissionRateResponse, ContractError> {
+    let cfg = load_config_with_due_emissions(deps, &env)?;
+
+    let lp_token = validate_lp_token(deps.api, &lp_token)?;
+    let user = deps.api.addr_validate(&user)?;
+
+    let pool = POOL_INFO.load(deps.storage, &lp_token)?;
+    let user_info = USER_INFO
+        .compatible_load(deps.storage, (&lp_token, &user))
+        .unwrap_or_default();
+
+    let mut share = Decimal::zero();
+    let mut grid_per_block = Uint128::zero();
+    if !pool.total_virtual_supply.is_zero() {
+        share = Decimal::from_ratio(user_info.virtual_amount, pool.total_virtual_supply);
+        if !pool.is_emissions_paused(&cfg) {
+            let alloc_point = get_alloc_point(&cfg.active_pools, &lp_token);
+            grid_per_block = calculate_rewards(1, &alloc_point, &cfg)?
+                .multiply_ratio(user_info.virtual_amount, pool.total_virtual_supply);
+        }
+    }
+
+    let mut proxy_rewards_per_block = vec![];
+    if !pool.reward_proxies.is_empty() {
+        let proxy_lp_supply =
+            query_lp_balance(&deps.querier, &env.contract.address, &lp_token, &pool)?;
+        // Proxy rewards accrued since the proxies were last claimed, accounted or not
+        let blocks = env.block.height.saturating_sub(
+            pool.last_proxy_rewards_claim_block
+                .unwrap_or(pool.last_reward_block)
+                .u64(),
+        );
+
+        for reward_proxy in &pool.reward_proxies {
+            let mut amount = Uint128::zero();
+            if !proxy_lp_supply.is_zero() && blocks > 0 {
+                let rewards = query_unaccounted_proxy_rewards(&deps.querier, reward_proxy)?
+                    .checked_add(reward_proxy.accounted_pending_rewards)?;
+                amount = rewards.multiply_ratio(
+                    user_info.amount,
+                    proxy_lp_supply.checked_mul(Uint128::from(blocks))?,
+                );
+            }
+            let info = PROXY_REWARD_ASSET.load(deps.storage, &reward_proxy.proxy)?;
+            proxy_rewards_per_block.push(info.with_balance(amount));
+        }
+    }
+
+    Ok(UserEmissionRateResponse {
+        share,
+        grid_per_block,
+        proxy_rewards_per_block,
     })
 }
 
-/// Returns a list of stakers that currently have funds in a specific generator.
+/// Returns a list of stakers that currently have funds in a specific generator ordered by address,
+/// along with their staked and boosted (virtual) amounts.
 ///
 /// * **lp_token** LP token whose generator we query for stakers.
 ///
@@ -1935,7 +4212,7 @@ pub fn query_list_of_stakers(
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> Result<Vec<StakerResponse>, ContractError> {
-    let lp_addr = deps.api.addr_validate(&lp_token)?;
+    let lp_addr = validate_lp_token(deps.api, &lp_token)?;
     let mut active_stakers: Vec<StakerResponse> = vec![];
 
     if POOL_INFO.has(deps.storage, &lp_addr) {
@@ -1943,25 +4220,111 @@ pub fn query_list_of_stakers(
         let start = addr_opt_validate(deps.api, &start_after)?;
         let start = start.as_ref().map(Bound::exclusive);
 
-        active_stakers = USER_INFO
+        // Keys are read separately so positions stored in the old format are listed as well
+        for account in USER_INFO
             .prefix(&lp_addr)
-            .range(deps.storage, start, None, Order::Ascending)
-            .filter_map(|stakers| {
-                stakers
-                    .ok()
-                    .map(|staker| StakerResponse {
-                        account: staker.0.to_string(),
-                        amount: staker.1.amount,
-                    })
-                    .filter(|active_staker| !active_staker.amount.is_zero())
-            })
-            .take(limit)
-            .collect();
+            .keys(deps.storage, start, None, Order::Ascending)
+        {
+            let account = account?;
+            let user = USER_INFO.compatible_load(deps.storage, (&lp_addr, &account))?;
+            if user.amount.is_zero() {
+                continue;
+            }
+
+            active_stakers.push(StakerResponse {
+                account: account.to_string(),
+                amount: user.amount,
+                virtual_amount: user.virtual_amount,
+            });
+            if active_stakers.len() == limit {
+                break;
+            }
+        }
     }
 
     Ok(active_stakers)
 }
 
+/// Returns a snapshot of the contract state. Lists are sorted so that snapshots taken before and
+/// after an upgrade can be diffed directly. Per-user positions are not included, they are
+/// listed by the [`QueryMsg::PoolStakers`] query.
+///
+/// * **start_after** LP token after which generators are listed.
+///
+/// * **limit** max amount of generators to list.
+pub fn query_state_snapshot(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<StateSnapshotResponse, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.active_pools.sort_by(|(a, _), (b, _)| a.cmp(b));
+    config.blocked_pools.sort();
+    config
+        .blocked_tokens_list
+        .sort_by_key(|asset_info| asset_info.to_string());
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = addr_opt_validate(deps.api, &start_after)?;
+    let start = start.as_ref().map(Bound::exclusive);
+
+    let pools = POOL_INFO
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (lp_token, pool_info) = item?;
+            Ok(PoolSnapshot {
+                alloc_point: get_alloc_point(&config.active_pools, &lp_token),
+                receipt_token: RECEIPT_TOKENS.may_load(deps.storage, &lp_token)?,
+                lp_supply: query_lp_balance(
+                    &deps.querier,
+                    &env.contract.address,
+                    &lp_token,
+                    &pool_info,
+                )?,
+                lp_token,
+                pool_info,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(StateSnapshotResponse {
+        schema_version: STATE_SNAPSHOT_SCHEMA_VERSION,
+        ownership_proposal: OWNERSHIP_PROPOSAL.may_load(deps.storage)?,
+        proxy_rewards_holder: PROXY_REWARDS_HOLDER.may_load(deps.storage)?,
+        proxy_reward_assets: PROXY_REWARD_ASSET
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?,
+        merkle_drops: MERKLE_DROPS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?,
+        merkle_drop_count: MERKLE_DROP_COUNT
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        update_pools_cursor: UPDATE_POOLS_CURSOR.may_load(deps.storage)?,
+        reentrancy_lock: REENTRANCY_LOCK.may_load(deps.storage)?.unwrap_or_default(),
+        emission_schedule: query_emission_schedule(deps)?,
+        config,
+        pools,
+    })
+}
+
+/// Returns the queued changes of the amount of GRID distributed per block ordered by activation
+/// height.
+pub fn query_emission_schedule(deps: Deps) -> StdResult<Vec<ScheduledEmission>> {
+    EMISSION_SCHEDULE
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (activation_height, tokens_per_block) = item?;
+            Ok(ScheduledEmission {
+                activation_height,
+                tokens_per_block,
+            })
+        })
+        .collect()
+}
+
 /// Calculates and returns the amount of accrued rewards since the last reward checkpoint for a specific generator.
 ///
 /// * **alloc_point** allocation points for specific generator.
@@ -2001,7 +4364,7 @@ pub fn create_pool(
         .querier
         .query_wasm_smart(&cfg.factory, &FactoryQueryMsg::Config {})?;
 
-    let pair_info = pair_info_by_pool(&deps.querier, lp_token)?;
+    let pair_info = lp_pair_info(&deps.querier, lp_token)?;
     let pair_config = factory_cfg
         .pair_configs
         .into_iter()
@@ -2017,13 +4380,14 @@ pub fn create_pool(
         lp_token,
         &PoolInfo {
             last_reward_block: cfg.start_block.max(Uint64::from(env.block.height)),
-            reward_proxy: None,
+            reward_proxies: vec![],
             accumulated_proxy_rewards_per_share: Default::default(),
-            proxy_reward_balance_before_update: Uint128::zero(),
             orphan_proxy_rewards: Default::default(),
             has_asset_rewards: false,
             reward_global_index: Decimal::zero(),
             total_virtual_supply: Default::default(),
+            last_proxy_rewards_claim_block: None,
+            emissions_paused: false,
         },
     )?;
 
@@ -2039,13 +4403,20 @@ pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response,
         "gridiron-generator" => match contract_version.version.as_ref() {
             "2.2.0" | "2.2.0+togrb" => {
                 migration::migrate_configs_from_v220(&mut deps, &msg)?;
+                migration::migrate_pool_infos_to_v250(deps.storage)?;
             }
             "2.3.0" => {
                 if env.block.chain_id == "neutron-1" {
                     migration::fix_neutron_users_reward_indexes(&mut deps)?;
                 }
+                migration::migrate_pool_infos_to_v250(deps.storage)?;
+            }
+            "2.3.1" | "2.3.2" | "2.4.0" => {
+                migration::migrate_pool_infos_to_v250(deps.storage)?;
             }
-            "2.3.1" => {}
+            "2.5.0" | "2.6.0" | "2.7.0" | "2.8.0" | "2.9.0" | "2.10.0" | "2.11.0" | "2.12.0"
+            | "2.13.0" | "2.14.0" | "2.15.0" | "2.16.0" | "2.17.0" | "2.18.0" | "2.19.0"
+            | "2.20.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -1,4 +1,6 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{Addr, Decimal, OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
+use gridiron::generator::MAX_SCHEDULED_EMISSIONS;
 use thiserror::Error;
 
 /// This enum describes generator contract errors
@@ -7,6 +9,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -28,6 +33,12 @@ pub enum ContractError {
     #[error("The pool already has a reward proxy contract!")]
     PoolAlreadyHasRewardProxyContract {},
 
+    #[error("The reward proxy is already used by the pool!")]
+    RewardProxyAlreadyUsed {},
+
+    #[error("The reward proxy is not attached to the pool!")]
+    RewardProxyNotFound {},
+
     #[error("Generator is disabled!")]
     GeneratorIsDisabled {},
 
@@ -48,6 +59,97 @@ pub enum ContractError {
 
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("Auto-staking of rewards is not available: staking contract is not set")]
+    AutoStakeNotAvailable {},
+
+    #[error("Claiming rewards as another asset is not available: router is not set")]
+    ClaimAsNotAvailable {},
+
+    #[error("Rewards can't be claimed as GRID, use ClaimRewards instead")]
+    InvalidClaimAsTarget {},
+
+    #[error("Receipt token is already set up for the generator!")]
+    ReceiptTokenAlreadySet {},
+
+    #[error("Receipt token is not set up for the generator!")]
+    ReceiptTokenNotSet {},
+
+    #[error("Not enough staked LP tokens that don't back receipt tokens!")]
+    NotEnoughUnbackedBalance {},
+
+    #[error("Max pool share can't be greater than 1!")]
+    InvalidMaxPoolShare {},
+
+    #[error("GRID pool boost can't be less than 1!")]
+    InvalidGridPoolBoost {},
+
+    #[error("Max pool share {max_pool_share} is too low for {pools} pools!")]
+    PoolShareCapTooLow {
+        max_pool_share: Decimal,
+        pools: usize,
+    },
+
+    #[error("Re-entrant call while proxy rewards are being updated!")]
+    ReentrancyDetected {},
+
+    #[error("Reward proxy {proxy} is in emergency mode!")]
+    ProxyInEmergency { proxy: Addr },
+
+    #[error("CW20 tokens are not supported in bank mode!")]
+    Cw20Disabled {},
+
+    #[error("Native LP tokens are only supported in bank mode!")]
+    NativeLpNotSupported {},
+
+    #[error("Invalid merkle root!")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid merkle proof!")]
+    InvalidMerkleProof {},
+
+    #[error("Merkle drop must be funded with exactly {expected} GRID!")]
+    MerkleDropFundsMismatch { expected: Uint128 },
+
+    #[error("Merkle drop expiry must be in the future!")]
+    InvalidMerkleDropExpiry {},
+
+    #[error("Merkle drop {drop_id} is expired!")]
+    MerkleDropExpired { drop_id: u64 },
+
+    #[error("Merkle drop {drop_id} is not expired yet!")]
+    MerkleDropNotExpired { drop_id: u64 },
+
+    #[error("Merkle drop {drop_id} is already claimed!")]
+    MerkleDropAlreadyClaimed { drop_id: u64 },
+
+    #[error("Unclaimed GRID of merkle drop {drop_id} is already reclaimed!")]
+    MerkleDropReclaimed { drop_id: u64 },
+
+    #[error("Asset {0} is not in the pair")]
+    AssetNotInPair(String),
+
+    #[error("Received {received} {asset_name} but expected {expected}")]
+    WithdrawSlippageViolation {
+        asset_name: String,
+        received: Uint128,
+        expected: Uint128,
+    },
+
+    #[error("The activation height must be in the future!")]
+    InvalidActivationHeight {},
+
+    #[error("A change of GRID per block is already scheduled at height {activation_height}!")]
+    EmissionAlreadyScheduled { activation_height: u64 },
+
+    #[error("No change of GRID per block is scheduled at height {activation_height}!")]
+    ScheduledEmissionNotFound { activation_height: u64 },
+
+    #[error(
+        "Can't schedule more than {} changes of GRID per block!",
+        MAX_SCHEDULED_EMISSIONS
+    )]
+    TooManyScheduledEmissions {},
 }
 
 impl From<OverflowError> for ContractError {
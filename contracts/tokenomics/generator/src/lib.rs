@@ -1,4 +1,6 @@
 pub mod contract;
 pub mod error;
+mod lp_token;
+mod merkle_drop;
 mod migration;
 pub mod state;
@@ -0,0 +1,141 @@
+//! LP tokens are CW20 contracts by default. With the `bank` feature the Generator is built for
+//! chains without CW20, where pairs mint their LP tokens as token factory denoms
+//! `factory/{pair}/{subdenom}`. LP tokens are still stored as [`Addr`] keys in both modes.
+
+use cosmwasm_std::{Addr, Api, QuerierWrapper, StdResult};
+
+use gridiron::asset::{AssetInfo, PairInfo};
+
+use crate::error::ContractError;
+
+/// Validates an LP token and returns it in the form used as a storage key.
+#[cfg(not(feature = "bank"))]
+pub fn validate_lp_token(api: &dyn Api, lp_token: &str) -> StdResult<Addr> {
+    api.addr_validate(lp_token)
+}
+
+/// Validates an LP token and returns it in the form used as a storage key.
+#[cfg(feature = "bank")]
+pub fn validate_lp_token(api: &dyn Api, lp_token: &str) -> StdResult<Addr> {
+    api.addr_validate(denom_creator(lp_token)?)?;
+
+    Ok(Addr::unchecked(lp_token))
+}
+
+/// Validates a list of LP tokens.
+pub fn validate_lp_tokens(api: &dyn Api, lp_tokens: &[String]) -> StdResult<Vec<Addr>> {
+    lp_tokens
+        .iter()
+        .map(|lp_token| validate_lp_token(api, lp_token))
+        .collect()
+}
+
+/// Returns the LP token as an asset, which is used to transfer it and to query balances.
+#[cfg(not(feature = "bank"))]
+pub fn lp_asset_info(lp_token: &Addr) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: lp_token.clone(),
+    }
+}
+
+/// Returns the LP token as an asset, which is used to transfer it and to query balances.
+#[cfg(feature = "bank")]
+pub fn lp_asset_info(lp_token: &Addr) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: lp_token.to_string(),
+    }
+}
+
+/// Returns the pair which issued the LP token.
+#[cfg(not(feature = "bank"))]
+pub fn lp_pair_info(querier: &QuerierWrapper, lp_token: &Addr) -> StdResult<PairInfo> {
+    gridiron::asset::pair_info_by_pool(querier, lp_token)
+}
+
+/// Returns the pair which issued the LP token. The pair is the creator of the denom and must
+/// report the denom as its liquidity token.
+#[cfg(feature = "bank")]
+pub fn lp_pair_info(querier: &QuerierWrapper, lp_token: &Addr) -> StdResult<PairInfo> {
+    let pair_info: PairInfo = querier.query_wasm_smart(
+        denom_creator(lp_token.as_str())?,
+        &gridiron::pair::QueryMsg::Pair {},
+    )?;
+
+    if pair_info.liquidity_token != *lp_token {
+        return Err(cosmwasm_std::StdError::generic_err(format!(
+            "{lp_token} is not the LP token of {}",
+            pair_info.contract_addr
+        )));
+    }
+
+    Ok(pair_info)
+}
+
+/// Returns the creator of a token factory denom `factory/{creator}/{subdenom}`.
+#[cfg(feature = "bank")]
+fn denom_creator(denom: &str) -> StdResult<&str> {
+    denom
+        .strip_prefix("factory/")
+        .and_then(|creator_and_subdenom| creator_and_subdenom.split_once('/'))
+        .filter(|(_, subdenom)| !subdenom.is_empty())
+        .map(|(creator, _)| creator)
+        .ok_or_else(|| {
+            cosmwasm_std::StdError::generic_err(format!("Invalid LP token denom: {denom}"))
+        })
+}
+
+/// Rejects deposits of native LP tokens unless the Generator is built in bank mode.
+pub fn assert_bank_mode() -> Result<(), ContractError> {
+    if cfg!(feature = "bank") {
+        Ok(())
+    } else {
+        Err(ContractError::NativeLpNotSupported {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_dependencies;
+
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "bank"))]
+    fn cw20_lp_tokens() {
+        let deps = mock_dependencies();
+
+        let lp_token = validate_lp_token(&deps.api, "lp_token").unwrap();
+        assert_eq!(lp_token, Addr::unchecked("lp_token"));
+        assert_eq!(
+            lp_asset_info(&lp_token),
+            AssetInfo::Token {
+                contract_addr: lp_token.clone()
+            }
+        );
+
+        assert_eq!(
+            assert_bank_mode().unwrap_err(),
+            ContractError::NativeLpNotSupported {}
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bank")]
+    fn bank_lp_tokens() {
+        let deps = mock_dependencies();
+
+        let lp_token = validate_lp_token(&deps.api, "factory/pair/lp").unwrap();
+        assert_eq!(lp_token, Addr::unchecked("factory/pair/lp"));
+        assert_eq!(
+            lp_asset_info(&lp_token),
+            AssetInfo::NativeToken {
+                denom: "factory/pair/lp".to_string()
+            }
+        );
+        for invalid in ["lp_token", "factory/pair", "factory/pair/", "ibc/pair/lp"] {
+            validate_lp_token(&deps.api, invalid).unwrap_err();
+        }
+
+        assert_bank_mode().unwrap();
+    }
+}
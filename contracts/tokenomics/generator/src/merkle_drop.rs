@@ -0,0 +1,224 @@
+use cosmwasm_std::{
+    attr, wasm_execute, Addr, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_utils::must_pay;
+use sha2::{Digest, Sha256};
+
+use gridiron::asset::{AssetInfo, AssetInfoExt};
+use gridiron::generator::MerkleDrop;
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, MERKLE_DROPS, MERKLE_DROP_CLAIMS, MERKLE_DROP_COUNT};
+
+/// Registers a merkle drop of retroactive GRID rewards funded by the owner.
+///
+/// * **merkle_root** hex encoded merkle root of the (address, amount) leaves.
+///
+/// * **total_amount** total amount of GRID distributed by the drop.
+///
+/// * **expires_at** timestamp (in seconds) after which rewards can't be claimed anymore.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn register_merkle_drop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: String,
+    total_amount: Uint128,
+    expires_at: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut root_buf = [0u8; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)
+        .map_err(|_| ContractError::InvalidMerkleRoot {})?;
+
+    if expires_at <= env.block.time.seconds() {
+        return Err(ContractError::InvalidMerkleDropExpiry {});
+    }
+
+    let mut response = Response::new();
+    match &cfg.grid_token {
+        AssetInfo::NativeToken { denom } => {
+            if must_pay(&info, denom)? != total_amount {
+                return Err(ContractError::MerkleDropFundsMismatch {
+                    expected: total_amount,
+                });
+            }
+        }
+        AssetInfo::Token { contract_addr } => {
+            if !info.funds.is_empty() || total_amount.is_zero() {
+                return Err(ContractError::MerkleDropFundsMismatch {
+                    expected: total_amount,
+                });
+            }
+            response = response.add_message(wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: total_amount,
+                },
+                vec![],
+            )?);
+        }
+    }
+
+    let drop_id = MERKLE_DROP_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    MERKLE_DROP_COUNT.save(deps.storage, &drop_id)?;
+    MERKLE_DROPS.save(
+        deps.storage,
+        drop_id,
+        &MerkleDrop {
+            merkle_root: merkle_root.to_lowercase(),
+            total_amount,
+            claimed_amount: Uint128::zero(),
+            expires_at,
+            reclaimed: false,
+        },
+    )?;
+
+    Ok(response.add_attributes([
+        attr("action", "register_merkle_drop"),
+        attr("drop_id", drop_id.to_string()),
+        attr("merkle_root", merkle_root),
+        attr("total_amount", total_amount),
+    ]))
+}
+
+/// Sends the sender's retroactive GRID rewards after verifying the merkle proof of its leaf.
+///
+/// * **drop_id** merkle drop identifier.
+///
+/// * **amount** amount of GRID allotted to the sender.
+///
+/// * **proof** hex encoded merkle proof of the sender's leaf.
+pub fn claim_merkle_drop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    drop_id: u64,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut merkle_drop = MERKLE_DROPS.load(deps.storage, drop_id)?;
+
+    if env.block.time.seconds() >= merkle_drop.expires_at {
+        return Err(ContractError::MerkleDropExpired { drop_id });
+    }
+
+    if MERKLE_DROP_CLAIMS.has(deps.storage, (drop_id, &info.sender)) {
+        return Err(ContractError::MerkleDropAlreadyClaimed { drop_id });
+    }
+
+    verify_proof(&merkle_drop.merkle_root, &info.sender, amount, proof)?;
+
+    merkle_drop.claimed_amount = merkle_drop.claimed_amount.checked_add(amount)?;
+    // A malformed tree must not pay out GRID funding other drops
+    if merkle_drop.claimed_amount > merkle_drop.total_amount {
+        return Err(ContractError::InvalidMerkleProof {});
+    }
+    MERKLE_DROPS.save(deps.storage, drop_id, &merkle_drop)?;
+    MERKLE_DROP_CLAIMS.save(deps.storage, (drop_id, &info.sender), &amount)?;
+
+    Ok(Response::new()
+        .add_message(
+            cfg.grid_token
+                .with_balance(amount)
+                .into_msg::<Empty>(&info.sender)?,
+        )
+        .add_attributes([
+            attr("action", "claim_merkle_drop"),
+            attr("drop_id", drop_id.to_string()),
+            attr("user", info.sender),
+            attr("amount", amount),
+        ]))
+}
+
+/// Returns the unclaimed GRID of an expired merkle drop to the owner. Anyone can execute this.
+///
+/// * **drop_id** merkle drop identifier.
+pub fn reclaim_merkle_drop(
+    deps: DepsMut,
+    env: Env,
+    drop_id: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut merkle_drop = MERKLE_DROPS.load(deps.storage, drop_id)?;
+
+    if env.block.time.seconds() < merkle_drop.expires_at {
+        return Err(ContractError::MerkleDropNotExpired { drop_id });
+    }
+
+    if merkle_drop.reclaimed {
+        return Err(ContractError::MerkleDropReclaimed { drop_id });
+    }
+
+    merkle_drop.reclaimed = true;
+    MERKLE_DROPS.save(deps.storage, drop_id, &merkle_drop)?;
+
+    let unclaimed = merkle_drop.total_amount - merkle_drop.claimed_amount;
+    let mut response = Response::new();
+    if !unclaimed.is_zero() {
+        response = response.add_message(
+            cfg.grid_token
+                .with_balance(unclaimed)
+                .into_msg::<Empty>(&cfg.owner)?,
+        );
+    }
+
+    Ok(response.add_attributes([
+        attr("action", "reclaim_merkle_drop"),
+        attr("drop_id", drop_id.to_string()),
+        attr("amount", unclaimed),
+    ]))
+}
+
+/// Returns a merkle drop of retroactive GRID rewards.
+pub fn query_merkle_drop(deps: Deps, drop_id: u64) -> StdResult<MerkleDrop> {
+    MERKLE_DROPS.load(deps.storage, drop_id)
+}
+
+/// Returns the amount of GRID the user claimed from a merkle drop.
+pub fn query_merkle_drop_claimed(deps: Deps, drop_id: u64, user: String) -> StdResult<Uint128> {
+    let user = deps.api.addr_validate(&user)?;
+    Ok(MERKLE_DROP_CLAIMS
+        .may_load(deps.storage, (drop_id, &user))?
+        .unwrap_or_default())
+}
+
+/// Checks that the (user, amount) leaf is a part of the merkle tree with the given root.
+/// Leaves are hashed as `sha256(user + amount)` and pairs of nodes are sorted before hashing.
+fn verify_proof(
+    merkle_root: &str,
+    user: &Addr,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<(), ContractError> {
+    let leaf: [u8; 32] = Sha256::digest(format!("{user}{amount}").as_bytes()).into();
+
+    let hash = proof.into_iter().try_fold(leaf, |hash, node| {
+        let mut node_buf = [0u8; 32];
+        hex::decode_to_slice(node, &mut node_buf)
+            .map_err(|_| ContractError::InvalidMerkleProof {})?;
+
+        let mut nodes = [hash, node_buf];
+        nodes.sort_unstable();
+        Ok::<_, ContractError>(Sha256::digest(nodes.concat()).into())
+    })?;
+
+    if hex::encode(hash) != merkle_root {
+        return Err(ContractError::InvalidMerkleProof {});
+    }
+
+    Ok(())
+}
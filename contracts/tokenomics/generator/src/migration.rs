@@ -1,10 +1,11 @@
-use crate::state::{CONFIG, USER_INFO};
+use crate::state::{CONFIG, POOL_INFO, USER_INFO};
 use gridiron::asset::AssetInfo;
 
-use gridiron::generator::{Config, MigrateMsg};
+use gridiron::generator::{Config, MigrateMsg, PoolInfo, RewardProxyInfo};
+use gridiron::restricted_vector::RestrictedVector;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, DepsMut, StdError, StdResult, Uint128, Uint64};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, DepsMut, Order, StdError, StdResult, Storage, Uint128, Uint64};
+use cw_storage_plus::{Item, Map};
 
 /// This structure stores the core parameters for the Generator contract.
 #[cw_serde]
@@ -59,6 +60,13 @@ pub fn migrate_configs_from_v220(deps: &mut DepsMut, msg: &MigrateMsg) -> StdRes
         blocked_tokens_list: cfg_220.blocked_tokens_list,
         guardian: cfg_220.guardian,
         checkpoint_generator_limit: cfg_220.checkpoint_generator_limit,
+        proxy_rewards_claim_interval: None,
+        emissions_paused: false,
+        staking_contract: None,
+        max_pool_share: None,
+        blocked_pools: vec![],
+        grid_pool_boost: None,
+        router: None,
     };
 
     if let Some(voting_escrow_delegation) = &msg.voting_escrow_delegation {
@@ -91,3 +99,61 @@ pub fn fix_neutron_users_reward_indexes(deps: &mut DepsMut) -> StdResult<()> {
 
     Ok(())
 }
+
+/// This structure describes the main information of pool before V2.5.0
+#[cw_serde]
+pub struct PoolInfoV240 {
+    pub last_reward_block: Uint64,
+    pub reward_global_index: Decimal,
+    /// the reward proxy contract
+    pub reward_proxy: Option<Addr>,
+    pub accumulated_proxy_rewards_per_share: RestrictedVector<Addr, Decimal>,
+    /// for calculation of new proxy rewards
+    pub proxy_reward_balance_before_update: Uint128,
+    pub orphan_proxy_rewards: RestrictedVector<Addr, Uint128>,
+    pub has_asset_rewards: bool,
+    pub total_virtual_supply: Uint128,
+    pub last_proxy_rewards_claim_block: Option<Uint64>,
+}
+
+/// Stores the pool info(V2.4.0) at the given key
+pub const POOL_INFO_V240: Map<&Addr, PoolInfoV240> = Map::new("pool_info");
+
+/// Moves the single reward proxy of each pool into the list of reward proxies.
+/// Before V2.5.0 the reward proxy always held the pool's LP tokens.
+pub fn migrate_pool_infos_to_v250(storage: &mut dyn Storage) -> StdResult<()> {
+    let pools = POOL_INFO_V240
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (lp_token, pool) in pools {
+        let reward_proxies = pool
+            .reward_proxy
+            .map(|proxy| RewardProxyInfo {
+                proxy,
+                holds_lp_tokens: true,
+                reward_balance_before_update: pool.proxy_reward_balance_before_update,
+                accounted_pending_rewards: Uint128::zero(),
+            })
+            .into_iter()
+            .collect();
+
+        POOL_INFO.save(
+            storage,
+            &lp_token,
+            &PoolInfo {
+                last_reward_block: pool.last_reward_block,
+                reward_global_index: pool.reward_global_index,
+                reward_proxies,
+                accumulated_proxy_rewards_per_share: pool.accumulated_proxy_rewards_per_share,
+                orphan_proxy_rewards: pool.orphan_proxy_rewards,
+                has_asset_rewards: pool.has_asset_rewards,
+                total_virtual_supply: pool.total_virtual_supply,
+                last_proxy_rewards_claim_block: pool.last_proxy_rewards_claim_block,
+                emissions_paused: false,
+            },
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,21 +1,23 @@
-use gridiron::asset::AssetInfo;
+use gridiron::asset::{Asset, AssetInfo};
 use gridiron::common::OwnershipProposal;
 use gridiron::restricted_vector::RestrictedVector;
 use gridiron::DecimalCheckedOps;
 use gridiron::{
-    generator::{PoolInfo, UserInfo, UserInfoV2},
+    generator::{MerkleDrop, PoolInfo, UserInfo, UserInfoV2},
     generator_proxy::QueryMsg as ProxyQueryMsg,
 };
 use gridiron_governance::voting_escrow::{get_total_voting_power, get_voting_power};
 use gridiron_governance::voting_escrow_delegation::get_adjusted_balance;
-use cosmwasm_std::{Addr, Decimal, Deps, DepsMut, QuerierWrapper, StdResult, Storage, Uint128};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, DepsMut, QuerierWrapper, StdResult, Storage, Uint128};
 
 use gridiron::generator::Config;
-use cw20::BalanceResponse;
 use cw_storage_plus::{Item, Map};
 
 use std::collections::HashMap;
 
+use crate::lp_token::lp_asset_info;
+
 /// Constants to update user's virtual amount. For more info see update_virtual_amount() documentation.
 /// 0.4 of the LP tokens amount.
 const REAL_SHARE: Decimal = Decimal::raw(400000000000000000);
@@ -39,6 +41,70 @@ pub const OLD_USER_INFO: Map<(&Addr, &Addr), UserInfo> = Map::new("user_info");
 pub const PROXY_REWARDS_HOLDER: Item<Addr> = Item::new("proxy_rewards_holder");
 /// The struct which maps previous proxy addresses to reward assets
 pub const PROXY_REWARD_ASSET: Map<&Addr, AssetInfo> = Map::new("proxy_reward_asset");
+/// Users whose claimed GRID rewards are auto-staked into xGRID
+pub const AUTO_STAKE_REWARDS: Map<&Addr, bool> = Map::new("auto_stake_rewards");
+/// Context of the auto-stake submessage which is being processed
+pub const AUTO_STAKE_CONTEXT: Item<AutoStakeContext> = Item::new("auto_stake_context");
+/// Context of the rewards swap submessage which is being processed
+pub const SWAP_REWARDS_CONTEXT: Item<SwapRewardsContext> = Item::new("swap_rewards_context");
+/// Context of the LP unwrapping submessage which is being processed
+pub const UNWRAP_CONTEXT: Item<UnwrapContext> = Item::new("unwrap_context");
+/// The receipt token of each generator which has one, keyed by LP token
+pub const RECEIPT_TOKENS: Map<&Addr, Addr> = Map::new("receipt_tokens");
+/// The LP token of each receipt token
+pub const RECEIPT_TOKEN_LP: Map<&Addr, Addr> = Map::new("receipt_token_lp");
+/// The amount of a user's staked LP tokens that back receipt tokens. Always equals the user's receipt token balance
+pub const RECEIPT_BACKED: Map<(&Addr, &Addr), Uint128> = Map::new("receipt_backed");
+/// The LP token whose receipt token is being instantiated
+pub const RECEIPT_TOKEN_CONTEXT: Item<Addr> = Item::new("receipt_token_context");
+/// Set while reward claims dispatched to proxies are in flight, cleared by the generator callback
+/// which accounts the claimed rewards.
+pub const REENTRANCY_LOCK: Item<bool> = Item::new("reentrancy_lock");
+/// Merkle drops of retroactive GRID rewards
+pub const MERKLE_DROPS: Map<u64, MerkleDrop> = Map::new("merkle_drops");
+/// The number of registered merkle drops
+pub const MERKLE_DROP_COUNT: Item<u64> = Item::new("merkle_drop_count");
+/// The amount of GRID each user claimed from a merkle drop
+pub const MERKLE_DROP_CLAIMS: Map<(u64, &Addr), Uint128> = Map::new("merkle_drop_claims");
+/// The LP token of the last generator updated by an unfinished `UpdatePools` pass
+pub const UPDATE_POOLS_CURSOR: Item<Addr> = Item::new("update_pools_cursor");
+/// Queued changes of the amount of GRID distributed per block, keyed by activation height
+pub const EMISSION_SCHEDULE: Map<u64, Uint128> = Map::new("emission_schedule");
+
+/// This structure holds the data needed to finalize auto-staking of claimed GRID rewards.
+#[cw_serde]
+pub struct AutoStakeContext {
+    /// The account that receives the rewards
+    pub account: Addr,
+    /// The amount of GRID being staked
+    pub amount: Uint128,
+    /// The xGRID token address
+    pub xgrid_token: Addr,
+    /// The generator's xGRID balance before staking
+    pub xgrid_balance_before: Uint128,
+}
+
+/// This structure holds the data needed to finalize a swap of claimed GRID rewards.
+#[cw_serde]
+pub struct SwapRewardsContext {
+    /// The account that receives the rewards
+    pub account: Addr,
+    /// The amount of GRID being swapped
+    pub amount: Uint128,
+    /// The asset GRID is swapped into
+    pub target_asset: AssetInfo,
+}
+
+/// This structure holds the data needed to finalize unwrapping of withdrawn LP tokens.
+#[cw_serde]
+pub struct UnwrapContext {
+    /// The account that receives the pool assets
+    pub account: Addr,
+    /// The minimum amounts of the pool assets to receive
+    pub min_assets: Vec<Asset>,
+    /// The generator's balances of the pool assets before unwrapping
+    pub balances_before: Vec<Asset>,
+}
 
 pub trait CompatibleLoader<K, R> {
     fn compatible_load(&self, store: &dyn Storage, key: K) -> StdResult<R>;
@@ -88,6 +154,12 @@ pub const MAX_LIMIT: u32 = 30;
 /// The default amount of users to read from [`USER_INFO`]
 pub const DEFAULT_LIMIT: u32 = 10;
 
+/// The maximum amount of generators that can be updated at once by `UpdatePools`
+pub const MAX_UPDATE_POOLS_LIMIT: u32 = 100;
+
+/// The default amount of generators updated by `UpdatePools`
+pub const DEFAULT_UPDATE_POOLS_LIMIT: u32 = 30;
+
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
@@ -222,22 +294,15 @@ pub(crate) fn update_virtual_amount(
 /// Query total LP tokens balance for specified generator.
 /// If tokens are staked in proxy, then query proxy balance. Otherwise query generator contract balance.
 pub(crate) fn query_lp_balance(
-    deps: Deps,
+    querier: &QuerierWrapper,
     generator_addr: &Addr,
     lp_token: &Addr,
     pool_info: &PoolInfo,
 ) -> StdResult<Uint128> {
-    let lp_amount = if let Some(proxy) = &pool_info.reward_proxy {
-        deps.querier
-            .query_wasm_smart(proxy, &ProxyQueryMsg::Deposit {})?
+    let lp_amount = if let Some(proxy) = pool_info.lp_proxy() {
+        querier.query_wasm_smart(proxy, &ProxyQueryMsg::Deposit {})?
     } else {
-        let res: BalanceResponse = deps.querier.query_wasm_smart(
-            lp_token,
-            &cw20::Cw20QueryMsg::Balance {
-                address: generator_addr.to_string(),
-            },
-        )?;
-        res.balance
+        lp_asset_info(lp_token).query_pool(querier, generator_addr)?
     };
     Ok(lp_amount)
 }
@@ -249,6 +314,7 @@ mod tests {
         testing::{mock_dependencies, MOCK_CONTRACT_ADDR},
         Uint64,
     };
+    use gridiron::generator::RewardProxyInfo;
 
     use super::*;
 
@@ -265,15 +331,21 @@ mod tests {
                 &PoolInfo {
                     last_reward_block: Uint64::zero(),
                     reward_global_index: Decimal::from_ratio(10u128, 1u128),
-                    reward_proxy: Some(mock_address.clone()),
+                    reward_proxies: vec![RewardProxyInfo {
+                        proxy: mock_address.clone(),
+                        holds_lp_tokens: true,
+                        reward_balance_before_update: Uint128::new(20),
+                        accounted_pending_rewards: Uint128::zero(),
+                    }],
                     accumulated_proxy_rewards_per_share: RestrictedVector::new(
                         mock_address.clone(),
                         Decimal::from_ratio(10u128, 1u128),
                     ),
-                    proxy_reward_balance_before_update: Uint128::new(20),
                     orphan_proxy_rewards: RestrictedVector::default(),
                     has_asset_rewards: false,
                     total_virtual_supply: Uint128::new(2),
+                    last_proxy_rewards_claim_block: None,
+                    emissions_paused: false,
                 },
             )
             .unwrap();
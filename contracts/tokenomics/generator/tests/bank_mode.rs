@@ -0,0 +1,509 @@
+#![cfg(not(tarpaulin_include))]
+#![cfg(feature = "bank")]
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, coins, to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, Uint64,
+};
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Item;
+use cw_utils::PaymentError;
+
+use gridiron::asset::{native_asset_info, token_asset_info, AssetInfo, PairInfo};
+use gridiron::factory::{
+    Config as FactoryConfig, ConfigResponse as FactoryConfigResponse, PairConfig, PairType,
+    QueryMsg as FactoryQueryMsg,
+};
+use gridiron::generator::{
+    Cw20HookMsg as GeneratorHookMsg, ExecuteMsg as GeneratorExecuteMsg,
+    InstantiateMsg as GeneratorInstantiateMsg, PendingTokenResponse, QueryMsg as GeneratorQueryMsg,
+};
+use gridiron::pair::QueryMsg as PairQueryMsg;
+use gridiron::vesting::{
+    ExecuteMsg as VestingExecuteMsg, InstantiateMsg as VestingInstantiateMsg, VestingAccount,
+    VestingSchedule, VestingSchedulePoint,
+};
+use gridiron_generator::error::ContractError;
+use gridiron_mocks::cw_multi_test::{App, ContractWrapper, Executor};
+use gridiron_mocks::MockAppExt;
+
+const OWNER: &str = "owner";
+const USER: &str = "user";
+const GRID_DENOM: &str = "ugrid";
+
+/// The pair info of the mock pair, the LP token is a token factory denom minted by the pair.
+const PAIR_INFO: Item<PairInfo> = Item::new("pair_info");
+/// The factory config, which the Generator reads with a raw query.
+const FACTORY_CONFIG: Item<FactoryConfig> = Item::new("config");
+/// The pairs registered in the mock factory.
+const FACTORY_PAIRS: Item<Vec<PairInfo>> = Item::new("pairs");
+
+#[cw_serde]
+struct MockPairInstantiateMsg {
+    asset_infos: Vec<AssetInfo>,
+}
+
+fn mock_pair_instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: MockPairInstantiateMsg,
+) -> StdResult<Response> {
+    PAIR_INFO.save(
+        deps.storage,
+        &PairInfo {
+            asset_infos: msg.asset_infos,
+            liquidity_token: Addr::unchecked(format!("factory/{}/lp", env.contract.address)),
+            contract_addr: env.contract.address,
+            pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+fn mock_pair_query(deps: Deps, _env: Env, msg: PairQueryMsg) -> StdResult<Binary> {
+    match msg {
+        PairQueryMsg::Pair {} => to_binary(&PAIR_INFO.load(deps.storage)?),
+        _ => unimplemented!(),
+    }
+}
+
+fn mock_factory_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pairs: Vec<PairInfo>,
+) -> StdResult<Response> {
+    FACTORY_CONFIG.save(
+        deps.storage,
+        &FactoryConfig {
+            owner: info.sender,
+            token_code_id: 0,
+            generator_address: None,
+            fee_address: None,
+            whitelist_code_id: 0,
+            coin_registry_address: Addr::unchecked("coin_registry"),
+            max_combined_fee_bps: None,
+            points_contract: None,
+            max_referral_bps: None,
+            max_sale_tax_bps: None,
+        },
+    )?;
+    FACTORY_PAIRS.save(deps.storage, &pairs)?;
+
+    Ok(Response::new())
+}
+
+fn mock_factory_query(deps: Deps, _env: Env, msg: FactoryQueryMsg) -> StdResult<Binary> {
+    match msg {
+        FactoryQueryMsg::Config {} => {
+            let config = FACTORY_CONFIG.load(deps.storage)?;
+            to_binary(&FactoryConfigResponse {
+                owner: config.owner,
+                pair_configs: vec![PairConfig {
+                    code_id: 0,
+                    pair_type: PairType::Xyk {},
+                    total_fee_bps: 30,
+                    maker_fee_bps: 3333,
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                }],
+                token_code_id: config.token_code_id,
+                fee_address: config.fee_address,
+                generator_address: config.generator_address,
+                whitelist_code_id: config.whitelist_code_id,
+                coin_registry_address: config.coin_registry_address,
+                max_combined_fee_bps: config.max_combined_fee_bps,
+                points_contract: config.points_contract,
+                max_referral_bps: config.max_referral_bps,
+                max_sale_tax_bps: config.max_sale_tax_bps,
+            })
+        }
+        FactoryQueryMsg::BlacklistedPairTypes {} => to_binary(&Vec::<PairType>::new()),
+        FactoryQueryMsg::Pair { asset_infos } => {
+            let pair = FACTORY_PAIRS
+                .load(deps.storage)?
+                .into_iter()
+                .find(|pair| pair.asset_infos == asset_infos)
+                .ok_or_else(|| StdError::not_found("PairInfo"))?;
+            to_binary(&pair)
+        }
+        _ => unimplemented!(),
+    }
+}
+
+fn mock_execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    unimplemented!()
+}
+
+struct Suite {
+    app: App,
+    generator: Addr,
+    lp_token: String,
+}
+
+impl Suite {
+    fn new() -> Self {
+        let mut app = App::default();
+        let owner = Addr::unchecked(OWNER);
+
+        let pair_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            mock_execute,
+            mock_pair_instantiate,
+            mock_pair_query,
+        )));
+        let pair = app
+            .instantiate_contract(
+                pair_code_id,
+                owner.clone(),
+                &MockPairInstantiateMsg {
+                    asset_infos: vec![
+                        native_asset_info(GRID_DENOM.to_string()),
+                        native_asset_info("uusd".to_string()),
+                    ],
+                },
+                &[],
+                "Pair",
+                None,
+            )
+            .unwrap();
+        let pair_info: PairInfo = app
+            .wrap()
+            .query_wasm_smart(&pair, &PairQueryMsg::Pair {})
+            .unwrap();
+
+        let factory_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            mock_execute,
+            mock_factory_instantiate,
+            mock_factory_query,
+        )));
+        let factory = app
+            .instantiate_contract(
+                factory_code_id,
+                owner.clone(),
+                &vec![pair_info.clone()],
+                &[],
+                "Factory",
+                None,
+            )
+            .unwrap();
+
+        let vesting_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            gridiron_vesting::contract::execute,
+            gridiron_vesting::contract::instantiate,
+            gridiron_vesting::contract::query,
+        )));
+        let vesting = app
+            .instantiate_contract(
+                vesting_code_id,
+                owner.clone(),
+                &VestingInstantiateMsg {
+                    owner: OWNER.to_string(),
+                    vesting_token: native_asset_info(GRID_DENOM.to_string()),
+                    generator: None,
+                },
+                &[],
+                "Vesting",
+                None,
+            )
+            .unwrap();
+
+        let whitelist_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            gridiron_whitelist::contract::execute,
+            gridiron_whitelist::contract::instantiate,
+            gridiron_whitelist::contract::query,
+        )));
+        let generator_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(
+                gridiron_generator::contract::execute,
+                gridiron_generator::contract::instantiate,
+                gridiron_generator::contract::query,
+            )
+            .with_reply_empty(gridiron_generator::contract::reply),
+        ));
+
+        let mut init_msg = GeneratorInstantiateMsg {
+            owner: OWNER.to_string(),
+            factory: factory.to_string(),
+            generator_controller: None,
+            voting_escrow_delegation: None,
+            voting_escrow: None,
+            guardian: None,
+            grid_token: token_asset_info(Addr::unchecked("grid_token")),
+            tokens_per_block: Uint128::new(10_000000),
+            start_block: Uint64::from(app.block_info().height),
+            vesting_contract: vesting.to_string(),
+            whitelist_code_id,
+        };
+
+        // A CW20 GRID token can't be used in bank mode
+        let err = app
+            .instantiate_contract(
+                generator_code_id,
+                owner.clone(),
+                &init_msg,
+                &[],
+                "Gen",
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Cw20Disabled {}
+        );
+
+        init_msg.grid_token = native_asset_info(GRID_DENOM.to_string());
+        let generator = app
+            .instantiate_contract(
+                generator_code_id,
+                owner.clone(),
+                &init_msg,
+                &[],
+                "Gen",
+                None,
+            )
+            .unwrap();
+
+        let amount = Uint128::new(1_000_000_000000);
+        app.fund(&owner, &coins(amount.u128(), GRID_DENOM)).unwrap();
+        app.execute_contract(
+            owner,
+            vesting,
+            &VestingExecuteMsg::RegisterVestingAccounts {
+                vesting_accounts: vec![VestingAccount {
+                    address: generator.to_string(),
+                    schedules: vec![VestingSchedule {
+                        start_point: VestingSchedulePoint {
+                            time: app.block_info().time.seconds(),
+                            amount,
+                        },
+                        end_point: None,
+                    }],
+                }],
+            },
+            &coins(amount.u128(), GRID_DENOM),
+        )
+        .unwrap();
+
+        Self {
+            app,
+            generator,
+            lp_token: pair_info.liquidity_token.to_string(),
+        }
+    }
+
+    fn setup_pool(&mut self) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.generator.clone(),
+                &GeneratorExecuteMsg::SetupPools {
+                    pools: vec![(self.lp_token.clone(), Uint128::new(100))],
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn balance(&self, user: &str, denom: &str) -> Uint128 {
+        self.app.wrap().query_balance(user, denom).unwrap().amount
+    }
+
+    fn pending_grid(&self, user: &str) -> Uint128 {
+        let res: PendingTokenResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &GeneratorQueryMsg::PendingToken {
+                    lp_token: self.lp_token.clone(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap();
+        res.pending
+    }
+}
+
+#[test]
+fn deposit_and_withdraw_native_lp() {
+    let mut suite = Suite::new();
+    let user = Addr::unchecked(USER);
+    let lp_token = suite.lp_token.clone();
+    suite.setup_pool();
+
+    suite.app.fund(&user, &coins(100, &lp_token)).unwrap();
+
+    // The deposit must contain exactly one coin
+    let err = suite
+        .app
+        .execute_contract(
+            user.clone(),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Deposit { beneficiary: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Payment(PaymentError::NoFunds {})
+    );
+
+    // Only token factory denoms are LP tokens
+    suite.app.fund(&user, &coins(100, "uusd")).unwrap();
+    suite
+        .app
+        .execute_contract(
+            user.clone(),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Deposit { beneficiary: None },
+            &coins(100, "uusd"),
+        )
+        .unwrap_err();
+
+    suite
+        .app
+        .execute_contract(
+            user.clone(),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Deposit { beneficiary: None },
+            &[coin(100, &lp_token)],
+        )
+        .unwrap();
+    assert_eq!(suite.balance(USER, &lp_token), Uint128::zero());
+    assert_eq!(
+        suite.balance(suite.generator.as_str(), &lp_token),
+        Uint128::new(100)
+    );
+
+    suite.app.next_blocks(5);
+    assert_eq!(suite.pending_grid(USER), Uint128::new(50_000000));
+
+    suite
+        .app
+        .execute_contract(
+            user,
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Withdraw {
+                lp_token: lp_token.clone(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(suite.balance(USER, &lp_token), Uint128::new(100));
+    assert_eq!(suite.balance(USER, GRID_DENOM), Uint128::new(50_000000));
+    assert_eq!(suite.pending_grid(USER), Uint128::zero());
+}
+
+#[test]
+fn deposit_for_beneficiary() {
+    let mut suite = Suite::new();
+    let lp_token = suite.lp_token.clone();
+
+    // The pool is created on the first deposit
+    suite
+        .app
+        .fund(&Addr::unchecked(OWNER), &coins(100, &lp_token))
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Deposit {
+                beneficiary: Some(USER.to_string()),
+            },
+            &coins(100, &lp_token),
+        )
+        .unwrap();
+
+    let deposit: Uint128 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.generator,
+            &GeneratorQueryMsg::Deposit {
+                lp_token: lp_token.clone(),
+                user: USER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(deposit, Uint128::new(100));
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::EmergencyWithdraw { lp_token },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(suite.balance(USER, &suite.lp_token), Uint128::new(100));
+}
+
+#[test]
+fn cw20_messages_are_rejected() {
+    let mut suite = Suite::new();
+    suite.setup_pool();
+
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked("cw20_lp_token"),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: USER.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cw20Disabled {}
+    );
+
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::DepositFor {
+                lp_token: suite.lp_token.clone(),
+                beneficiary: USER.to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cw20Disabled {}
+    );
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.generator.clone(),
+            &GeneratorExecuteMsg::WithdrawAndUnwrap {
+                lp_token: suite.lp_token.clone(),
+                amount: Uint128::new(100),
+                min_assets: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cw20Disabled {}
+    );
+}
@@ -1,24 +1,34 @@
 #![cfg(not(tarpaulin_include))]
+#![cfg(not(feature = "bank"))]
 
 use std::{cell::RefCell, rc::Rc};
 
 use gridiron::asset::{
     native_asset_info, token_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo,
 };
-use gridiron::generator::{ExecuteMsg, QueryMsg, RewardInfoResponse, StakerResponse};
+use gridiron::generator::{
+    ExecuteMsg, QueryMsg, RewardInfoResponse, RewardProxyInfo, StakerResponse,
+};
 use gridiron_governance::utils::WEEK;
 
 use gridiron::{
     factory::{
         ConfigResponse as FactoryConfigResponse, ExecuteMsg as FactoryExecuteMsg,
-        InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType, QueryMsg as FactoryQueryMsg,
+        GeneratorProposal, InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType,
+        QueryMsg as FactoryQueryMsg, GENERATOR_ROTATION_DELAY,
     },
     generator::{
         Config, Cw20HookMsg as GeneratorHookMsg, ExecuteMsg as GeneratorExecuteMsg,
-        InstantiateMsg as GeneratorInstantiateMsg, PendingTokenResponse, PoolInfoResponse,
-        QueryMsg as GeneratorQueryMsg,
+        InstantiateMsg as GeneratorInstantiateMsg, MerkleDrop, MigrateMsg as GeneratorMigrateMsg,
+        PendingTokenResponse, PoolInfoResponse, PoolTvlResponse, QueryMsg as GeneratorQueryMsg,
+        ScheduledEmission, StateSnapshotResponse, UserEmissionRateResponse,
+        MAX_SCHEDULED_EMISSIONS, STATE_SNAPSHOT_SCHEMA_VERSION,
     },
     generator_proxy::{ExecuteMsg as ProxyExecuteMsg, InstantiateMsg as ProxyInstantiateMsg},
+    staking::{
+        ConfigResponse as StakingConfigResponse, InstantiateMsg as StakingInstantiateMsg,
+        QueryMsg as StakingQueryMsg,
+    },
     token::InstantiateMsg as TokenInstantiateMsg,
     vesting::{
         Cw20HookMsg as VestingHookMsg, InstantiateMsg as VestingInstantiateMsg, VestingAccount,
@@ -27,14 +37,23 @@ use gridiron::{
 };
 
 use gridiron::generator_proxy::ConfigResponse;
-use gridiron::pair::StablePoolParams;
+use gridiron::pair::{ExecuteMsg as PairExecuteMsg, StablePoolParams};
+use gridiron::router::InstantiateMsg as RouterInstantiateMsg;
 use gridiron_generator::error::ContractError;
 use gridiron_mocks::cw_multi_test::{next_block, App, ContractWrapper, Executor};
-use gridiron_mocks::{gridiron_address, MockGeneratorBuilder, MockToken, MockTokenBuilder};
-use cosmwasm_std::{from_slice, to_binary, Addr, Binary, StdResult, Uint128, Uint64};
+use gridiron_mocks::{
+    gridiron_address, MockAppExt, MockGeneratorBuilder, MockToken, MockTokenBuilder,
+};
+use cosmwasm_std::{
+    from_slice, to_binary, Addr, Binary, Decimal, DepsMut, Empty, Env, Response, StdResult,
+    Uint128, Uint64,
+};
 use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use sha2::{Digest, Sha256};
 
 use crate::test_utils::controller_helper::ControllerHelper;
+use crate::test_utils::emergency_proxy;
+use crate::test_utils::malicious_proxy::{self, Attack};
 use crate::test_utils::{mock_app as mock_app_helper, mock_app, AppExtension};
 
 #[cfg(test)]
@@ -880,6 +899,160 @@ fn proper_deposit_and_withdraw() {
     check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER1, (0, None));
 }
 
+#[test]
+fn deposit_for_beneficiary() {
+    let mut app = mock_app();
+
+    let sponsor = Addr::unchecked(USER1);
+    let beneficiary = Addr::unchecked(USER2);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::from(100u32)),
+            proxy: None,
+        }],
+    );
+
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &sponsor, 20);
+
+    // Deposit without allowance fails
+    let msg = GeneratorExecuteMsg::DepositFor {
+        lp_token: lp_cny_eur.to_string(),
+        beneficiary: beneficiary.to_string(),
+        amount: Uint128::new(10),
+    };
+    app.execute_contract(sponsor.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+
+    app.execute_contract(
+        sponsor.clone(),
+        lp_cny_eur.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: generator_instance.to_string(),
+            amount: Uint128::new(10),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res = app
+        .execute_contract(sponsor.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    let has_attr = |key: &str, value: &str| {
+        res.events.iter().any(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == key && attr.value == value)
+        })
+    };
+    assert!(has_attr("action", "deposit_for"));
+    assert!(has_attr("sponsor", sponsor.as_str()));
+    assert!(has_attr("beneficiary", beneficiary.as_str()));
+    // The Generator pulls the LP tokens itself, it is not reported as the sponsor by the CW20 hook
+    assert!(!has_attr("sponsor", generator_instance.as_str()));
+
+    // The same can be done via the CW20 hook
+    let msg = Cw20ExecuteMsg::Send {
+        contract: generator_instance.to_string(),
+        msg: to_binary(&GeneratorHookMsg::DepositFor(beneficiary.to_string())).unwrap(),
+        amount: Uint128::new(10),
+    };
+    let res = app
+        .execute_contract(sponsor.clone(), lp_cny_eur.clone(), &msg, &[])
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "sponsor" && attr.value == sponsor.as_str())));
+
+    // Native LP tokens can only be deposited in bank mode
+    let err = app
+        .execute_contract(
+            sponsor.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::Deposit {
+                beneficiary: Some(beneficiary.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NativeLpNotSupported {}
+    );
+
+    check_token_balance(&mut app, &lp_cny_eur, &sponsor, 0);
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 20);
+
+    let deposit = |app: &mut App, user: &Addr| -> Uint128 {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::Deposit {
+                    lp_token: lp_cny_eur.to_string(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    assert_eq!(deposit(&mut app, &beneficiary).u128(), 20);
+    assert_eq!(deposit(&mut app, &sponsor).u128(), 0);
+
+    app.update_block(|bi| next_block(bi));
+
+    // Rewards accrue to the beneficiary only
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_cny_eur,
+        USER2,
+        (10000000, None),
+    );
+    check_pending_rewards(&mut app, &generator_instance, &lp_cny_eur, USER1, (0, None));
+
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_cny_eur.to_string(),
+        amount: Uint128::new(20),
+    };
+    app.execute_contract(beneficiary.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &lp_cny_eur, &beneficiary, 20);
+}
+
 #[test]
 fn set_tokens_per_block() {
     let mut app = mock_app();
@@ -931,8 +1104,10 @@ fn set_tokens_per_block() {
 }
 
 #[test]
-fn update_config() {
+fn scheduled_tokens_per_block() {
     let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
 
     let token_code_id = store_token_code(&mut app);
     let grid_token_instance =
@@ -950,107 +1125,449 @@ fn update_config() {
         Some(OWNER.to_string()),
     );
 
-    let msg = QueryMsg::Config {};
-    let res: Config = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg)
-        .unwrap();
+    let (pair, lp_token) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            native_asset_info("uusd".to_string()),
+            native_asset_info("uluna".to_string()),
+        ],
+    );
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_token.to_string(), Uint128::new(100)),
+            proxy: None,
+        }],
+    );
+    mint_tokens(&mut app, pair, &lp_token, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_token, 10)]);
 
-    assert_eq!(res.owner, OWNER);
-    assert_eq!(res.generator_controller, Some(Addr::unchecked(OWNER)));
-    assert_eq!(res.grid_token.to_string(), "contract0");
-    assert_eq!(res.factory.to_string(), "contract2");
-    assert_eq!(res.vesting_contract.to_string(), "contract3");
+    let height = app.block_info().height;
+    let schedule = |app: &mut App, sender: &Addr, amount: u128, activation_height: u64| {
+        app.execute_contract(
+            sender.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ScheduleTokensPerBlock {
+                amount: Uint128::new(amount),
+                activation_height,
+            },
+            &[],
+        )
+    };
 
-    let new_vesting = Addr::unchecked("new_vesting");
+    let err = schedule(&mut app, &user1, 1_000000, height + 2).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let err = schedule(&mut app, &owner, 1_000000, height).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidActivationHeight {},
+        err.downcast().unwrap()
+    );
 
-    let msg = ExecuteMsg::UpdateConfig {
-        vesting_contract: Some(new_vesting.to_string()),
-        generator_controller: None,
-        guardian: None,
-        voting_escrow_delegation: None,
-        voting_escrow: None,
-        checkpoint_generator_limit: None,
-    };
+    schedule(&mut app, &owner, 1_000000, height + 2).unwrap();
+    let err = schedule(&mut app, &owner, 5_000000, height + 2).unwrap_err();
+    assert_eq!(
+        ContractError::EmissionAlreadyScheduled {
+            activation_height: height + 2
+        },
+        err.downcast().unwrap()
+    );
 
-    // Assert cannot update with improper owner
-    let e = app
+    for i in 1..MAX_SCHEDULED_EMISSIONS as u64 {
+        schedule(&mut app, &owner, 5_000000, height + 10 + i).unwrap();
+    }
+    let err = schedule(&mut app, &owner, 5_000000, height + 100).unwrap_err();
+    assert_eq!(
+        ContractError::TooManyScheduledEmissions {},
+        err.downcast().unwrap()
+    );
+
+    // Queued changes can be cancelled before their activation
+    for i in 1..MAX_SCHEDULED_EMISSIONS as u64 {
+        app.execute_contract(
+            owner.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::CancelScheduledTokensPerBlock {
+                activation_height: height + 10 + i,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    let err = app
         .execute_contract(
-            Addr::unchecked("not_owner"),
+            owner.clone(),
             generator_instance.clone(),
-            &msg,
+            &GeneratorExecuteMsg::CancelScheduledTokensPerBlock {
+                activation_height: height + 11,
+            },
             &[],
         )
         .unwrap_err();
+    assert_eq!(
+        ContractError::ScheduledEmissionNotFound {
+            activation_height: height + 11
+        },
+        err.downcast().unwrap()
+    );
 
-    assert_eq!(e.root_cause().to_string(), "Unauthorized");
+    let query_schedule = |app: &mut App| -> Vec<ScheduledEmission> {
+        app.wrap()
+            .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::EmissionSchedule {})
+            .unwrap()
+    };
+    assert_eq!(
+        query_schedule(&mut app),
+        vec![ScheduledEmission {
+            activation_height: height + 2,
+            tokens_per_block: Uint128::new(1_000000),
+        }]
+    );
+
+    for _ in 0..5 {
+        app.update_block(|bi| next_block(bi));
+    }
+
+    // Queries reflect the change before a transaction applies it
+    let config: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.tokens_per_block.u128(), 1_000000);
+    let pool_info: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::PoolInfo {
+                lp_token: lp_token.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(pool_info.grid_tokens_per_block.u128(), 1_000000);
+    assert_eq!(pool_info.last_reward_block, height + 2);
+    assert_eq!(pool_info.pending_grid_rewards.u128(), 3 * 1_000000);
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_token,
+        USER1,
+        (2 * 10_000000 + 3 * 1_000000, None),
+    );
 
+    // The change is applied by the first transaction after the activation height
     app.execute_contract(
-        Addr::unchecked(OWNER),
+        user1.clone(),
         generator_instance.clone(),
-        &msg,
+        &GeneratorExecuteMsg::UpdatePools {
+            start_after: None,
+            limit: None,
+        },
         &[],
     )
     .unwrap();
-
-    let msg = QueryMsg::Config {};
-    let res: Config = app
+    assert_eq!(query_schedule(&mut app), vec![]);
+    let config: Config = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg)
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
         .unwrap();
+    assert_eq!(config.tokens_per_block.u128(), 1_000000);
 
-    assert_eq!(res.vesting_contract, new_vesting);
+    // 2 blocks before the activation height with the previous amount and 3 blocks after it
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_token,
+        USER1,
+        (2 * 10_000000 + 3 * 1_000000, None),
+    );
 }
 
 #[test]
-fn update_owner() {
+fn merkle_drop() {
     let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
 
     let token_code_id = store_token_code(&mut app);
-    let pair_code_id = store_pair_code_id(&mut app);
-    let factory_code_id = store_factory_code(&mut app);
     let grid_token_instance =
         instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let generator_instance = instantiate_generator(
-        &mut app,
-        &factory_instance,
-        &grid_token_instance,
-        Some(OWNER.to_string()),
-    );
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    let new_owner = String::from("new_owner");
+    // Two leaf tree: user1 gets 100 GRID, user2 gets 200 GRID
+    let leaf1: [u8; 32] = Sha256::digest(format!("{user1}100").as_bytes()).into();
+    let leaf2: [u8; 32] = Sha256::digest(format!("{user2}200").as_bytes()).into();
+    let mut leaves = [leaf1, leaf2];
+    leaves.sort_unstable();
+    let merkle_root = hex::encode(Sha256::digest(leaves.concat()));
 
-    // New owner
-    let msg = ExecuteMsg::ProposeNewOwner {
-        owner: new_owner.clone(),
-        expires_in: 100, // seconds
+    mint_tokens(&mut app, owner.clone(), &grid_token_instance, &owner, 500);
+    app.execute_contract(
+        owner.clone(),
+        grid_token_instance.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: generator_instance.to_string(),
+            amount: Uint128::new(500),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let expires_at = app.block_info().time.seconds() + 1000;
+    let register_msg = GeneratorExecuteMsg::RegisterMerkleDrop {
+        merkle_root: merkle_root.clone(),
+        total_amount: Uint128::new(500),
+        expires_at,
     };
 
-    // Unauthorized check
     let err = app
         .execute_contract(
-            Addr::unchecked("not_owner"),
+            user1.clone(),
             generator_instance.clone(),
-            &msg,
+            &register_msg,
             &[],
         )
         .unwrap_err();
-    assert_eq!(err.root_cause().to_string(), "Generic error: Unauthorized");
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
 
-    // Claim before proposal
-    let err = app
-        .execute_contract(
-            Addr::unchecked(new_owner.clone()),
-            generator_instance.clone(),
-            &ExecuteMsg::ClaimOwnership {},
-            &[],
-        )
-        .unwrap_err();
-    assert_eq!(
-        err.root_cause().to_string(),
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &register_msg,
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 500);
+
+    let claim = |app: &mut App, user: &Addr, amount: u128, proof: &[u8; 32]| {
+        app.execute_contract(
+            user.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ClaimMerkleDrop {
+                drop_id: 1,
+                amount: Uint128::new(amount),
+                proof: vec![hex::encode(proof)],
+            },
+            &[],
+        )
+    };
+
+    // Claiming more than allotted fails
+    let err = claim(&mut app, &user1, 200, &leaf2).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidMerkleProof {},
+        err.downcast().unwrap()
+    );
+
+    claim(&mut app, &user1, 100, &leaf2).unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &user1, 100);
+
+    let err = claim(&mut app, &user1, 100, &leaf2).unwrap_err();
+    assert_eq!(
+        ContractError::MerkleDropAlreadyClaimed { drop_id: 1 },
+        err.downcast().unwrap()
+    );
+
+    let claimed: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::MerkleDropClaimed {
+                drop_id: 1,
+                user: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(claimed, Uint128::new(100));
+
+    // Unclaimed GRID can be returned only after expiry
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ReclaimMerkleDrop { drop_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MerkleDropNotExpired { drop_id: 1 },
+        err.downcast().unwrap()
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(1000));
+
+    let err = claim(&mut app, &user2, 200, &leaf1).unwrap_err();
+    assert_eq!(
+        ContractError::MerkleDropExpired { drop_id: 1 },
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user2.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::ReclaimMerkleDrop { drop_id: 1 },
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &owner, 400);
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 0);
+
+    let merkle_drop: MerkleDrop = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::MerkleDrop { drop_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        merkle_drop,
+        MerkleDrop {
+            merkle_root,
+            total_amount: Uint128::new(500),
+            claimed_amount: Uint128::new(100),
+            expires_at,
+            reclaimed: true,
+        }
+    );
+}
+
+#[test]
+fn update_config() {
+    let mut app = mock_app();
+
+    let token_code_id = store_token_code(&mut app);
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let msg = QueryMsg::Config {};
+    let res: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg)
+        .unwrap();
+
+    assert_eq!(res.owner, OWNER);
+    assert_eq!(res.generator_controller, Some(Addr::unchecked(OWNER)));
+    assert_eq!(res.grid_token.to_string(), "contract0");
+    assert_eq!(res.factory.to_string(), "contract2");
+    assert_eq!(res.vesting_contract.to_string(), "contract3");
+
+    let new_vesting = Addr::unchecked("new_vesting");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        vesting_contract: Some(new_vesting.to_string()),
+        generator_controller: None,
+        guardian: None,
+        voting_escrow_delegation: None,
+        voting_escrow: None,
+        checkpoint_generator_limit: None,
+        proxy_rewards_claim_interval: None,
+        staking_contract: None,
+        max_pool_share: None,
+        grid_pool_boost: None,
+        router: None,
+    };
+
+    // Assert cannot update with improper owner
+    let e = app
+        .execute_contract(
+            Addr::unchecked("not_owner"),
+            generator_instance.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap_err();
+
+    assert_eq!(e.root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        generator_instance.clone(),
+        &msg,
+        &[],
+    )
+    .unwrap();
+
+    let msg = QueryMsg::Config {};
+    let res: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg)
+        .unwrap();
+
+    assert_eq!(res.vesting_contract, new_vesting);
+}
+
+#[test]
+fn update_owner() {
+    let mut app = mock_app();
+
+    let token_code_id = store_token_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let new_owner = String::from("new_owner");
+
+    // New owner
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: new_owner.clone(),
+        expires_in: 100, // seconds
+    };
+
+    // Unauthorized check
+    let err = app
+        .execute_contract(
+            Addr::unchecked("not_owner"),
+            generator_instance.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: Unauthorized");
+
+    // Claim before proposal
+    let err = app
+        .execute_contract(
+            Addr::unchecked(new_owner.clone()),
+            generator_instance.clone(),
+            &ExecuteMsg::ClaimOwnership {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
         "Generic error: Ownership proposal not found"
     );
 
@@ -1196,12 +1713,11 @@ fn disabling_pool() {
 }
 
 #[test]
-fn generator_without_reward_proxies() {
+fn set_emissions_paused() {
     let mut app = mock_app();
 
-    let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
-    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER);
 
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
@@ -1212,27 +1728,10 @@ fn generator_without_reward_proxies() {
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let cny_eur_token_code_id = store_token_code(&mut app);
-    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let usd_token = instantiate_token(&mut app, cny_eur_token_code_id, "USD", None);
-    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
-
-    let (pair_cny_eur, lp_cny_eur) = create_pair(
-        &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: cny_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: eur_token.clone(),
-            },
-        ],
-    );
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let usdt_token = instantiate_token(&mut app, token_code_id, "USDT", None);
 
-    let (pair_eur_usd, lp_eur_usd) = create_pair(
+    let (pair_eur_usdt, lp_eur_usdt) = create_pair(
         &mut app,
         &factory_instance,
         None,
@@ -1242,7 +1741,7 @@ fn generator_without_reward_proxies() {
                 contract_addr: eur_token.clone(),
             },
             AssetInfo::Token {
-                contract_addr: usd_token.clone(),
+                contract_addr: usdt_token.clone(),
             },
         ],
     );
@@ -1253,289 +1752,137 @@ fn generator_without_reward_proxies() {
     register_lp_tokens_in_generator(
         &mut app,
         &generator_instance,
-        vec![
-            PoolWithProxy {
-                pool: (lp_cny_eur.to_string(), Uint128::from(50u32)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_eur_usd.to_string(), Uint128::from(50u32)),
-                proxy: None,
-            },
-        ],
-    );
-
-    // Mint tokens, so user can deposit
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 9);
-    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user1, 10);
-
-    let msg = Cw20ExecuteMsg::Send {
-        contract: generator_instance.to_string(),
-        msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
-        amount: Uint128::new(10),
-    };
-
-    assert_eq!(
-        app.execute_contract(user1.clone(), lp_cny_eur.clone(), &msg, &[])
-            .unwrap_err()
-            .root_cause()
-            .to_string(),
-        "Cannot Sub with 9 and 10".to_string()
+        vec![PoolWithProxy {
+            pool: (lp_eur_usdt.to_string(), Uint128::from(10u32)),
+            proxy: None,
+        }],
     );
 
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 1);
+    mint_tokens(&mut app, pair_eur_usdt.clone(), &lp_eur_usdt, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_eur_usdt, 10)]);
 
-    deposit_lp_tokens_to_generator(
+    app.update_block(|bi| next_block(bi));
+    check_pending_rewards(
         &mut app,
         &generator_instance,
+        &lp_eur_usdt,
         USER1,
-        &[(&lp_cny_eur, 10), (&lp_eur_usd, 10)],
+        (10000000, None),
     );
 
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 10);
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
+    let pool_paused = |app: &mut App| -> bool {
+        let res: PoolInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: lp_eur_usdt.to_string(),
+                },
+            )
+            .unwrap();
+        res.emissions_paused
+    };
 
-    check_pending_rewards(&mut app, &generator_instance, &lp_cny_eur, USER1, (0, None));
-    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER1, (0, None));
-
-    // User can't withdraw if they didn't deposit
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_cny_eur.to_string(),
-        amount: Uint128::new(1_000000),
+    // Only the owner can pause emissions
+    let msg = GeneratorExecuteMsg::SetEmissionsPaused {
+        lp_token: Some(lp_eur_usdt.to_string()),
+        paused: true,
     };
-    assert_eq!(
-        app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
-            .unwrap_err()
-            .root_cause()
-            .to_string(),
-        "Insufficient balance in contract to process claim".to_string()
-    );
+    let err = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
 
-    // User can't emergency withdraw if they didn't deposit
-    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
-        lp_token: lp_cny_eur.to_string(),
-    };
-    assert_eq!(
-        app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
-            .unwrap_err()
-            .root_cause()
-            .to_string(),
-        "gridiron::generator::UserInfo not found".to_string()
-    );
+    // Pause the pool: rewards accrued so far are kept, no new GRID is emitted
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    assert!(pool_paused(&mut app));
 
     app.update_block(|bi| next_block(bi));
-
-    // 10 tokens per block split equally between 2 pools
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER1,
-        (5_000000, None),
-    );
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_eur_usd,
-        USER1,
-        (5_000000, None),
-    );
-
-    // User 2
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user2, 10);
-    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user2, 10);
-
-    deposit_lp_tokens_to_generator(
-        &mut app,
-        &generator_instance,
-        USER2,
-        &[(&lp_cny_eur, 10), (&lp_eur_usd, 10)],
-    );
-
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 20);
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 20);
-
-    // 10 tokens have been distributed to depositors since the last deposit
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER1,
-        (5_000000, None),
-    );
+    app.update_block(|bi| next_block(bi));
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_eur_usd,
+        &lp_eur_usdt,
         USER1,
-        (5_000000, None),
+        (10000000, None),
     );
 
-    // New deposits can't receive already calculated rewards
-    check_pending_rewards(&mut app, &generator_instance, &lp_cny_eur, USER2, (0, None));
-    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER2, (0, None));
-
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_eur_usd.to_string(), Uint128::from(40u32)),
-        ],
+    // Resume the pool
+    let msg = GeneratorExecuteMsg::SetEmissionsPaused {
+        lp_token: Some(lp_eur_usdt.to_string()),
+        paused: false,
     };
     app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
+    assert!(!pool_paused(&mut app));
 
     app.update_block(|bi| next_block(bi));
-
-    // 60 to cny_eur, 40 to eur_usd. Each is divided for two users
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER1,
-        (8_000000, None),
-    );
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_eur_usd,
+        &lp_eur_usdt,
         USER1,
-        (7_000000, None),
-    );
-
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER2,
-        (3_000000, None),
-    );
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_eur_usd,
-        USER2,
-        (2_000000, None),
+        (20000000, None),
     );
 
-    // User1 emergency withdraws and loses already accrued rewards (5).
-    // Pending tokens (3) will be redistributed to other staked users.
-    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
-        lp_token: lp_cny_eur.to_string(),
+    // Pause emissions for all pools
+    let msg = GeneratorExecuteMsg::SetEmissionsPaused {
+        lp_token: None,
+        paused: true,
     };
-    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    let cfg: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &QueryMsg::Config {})
         .unwrap();
+    assert!(cfg.emissions_paused);
+    assert!(pool_paused(&mut app));
 
+    app.update_block(|bi| next_block(bi));
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_cny_eur,
-        USER1,
-        (0_000000, None),
-    );
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_eur_usd,
+        &lp_eur_usdt,
         USER1,
-        (7_000000, None),
-    );
-
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER2,
-        (3_000000, None),
-    );
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_eur_usd,
-        USER2,
-        (2_000000, None),
-    );
-
-    // Balance of the generator should be decreased
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 10);
-
-    // User1 can't withdraw after emergency withdraw
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_cny_eur.to_string(),
-        amount: Uint128::new(1_000000),
-    };
-    assert_eq!(
-        app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-            .unwrap_err()
-            .root_cause()
-            .to_string(),
-        "Insufficient balance in contract to process claim".to_string(),
+        (20000000, None),
     );
 
-    // User2 withdraw and get rewards
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_cny_eur.to_string(),
-        amount: Uint128::new(10),
-    };
-    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_cny_eur, &user1, 10);
-    check_token_balance(&mut app, &lp_cny_eur, &user2, 10);
-
-    check_token_balance(&mut app, &grid_token_instance, &user1, 0);
-    check_token_balance(&mut app, &grid_token_instance, &user2, 3_000000);
-    // 7 + 2 distributed GRID (for other pools). 5 orphaned by emergency withdrawals, 6 transfered to User2
-
-    // User1 withdraws and gets rewards
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_eur_usd.to_string(),
-        amount: Uint128::new(5),
-    };
-    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 15);
-    check_token_balance(&mut app, &lp_eur_usd, &user1, 5);
-
-    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-
-    // User1 withdraws and gets rewards
+    // Withdrawals keep working while emissions are paused
     let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_eur_usd.to_string(),
+        lp_token: lp_eur_usdt.to_string(),
         amount: Uint128::new(5),
     };
     app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
+    check_token_balance(&mut app, &lp_eur_usdt, &user1, 5);
+    check_token_balance(&mut app, &grid_token_instance, &user1, 20000000);
 
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
-    check_token_balance(&mut app, &lp_eur_usd, &user1, 10);
-    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-
-    // User2 withdraws and gets rewards
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_eur_usd.to_string(),
-        amount: Uint128::new(10),
+    let msg = GeneratorExecuteMsg::SetEmissionsPaused {
+        lp_token: None,
+        paused: false,
     };
-    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+    app.execute_contract(owner, generator_instance.clone(), &msg, &[])
         .unwrap();
+    assert!(!pool_paused(&mut app));
 
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_eur_usd, &user1, 10);
-    check_token_balance(&mut app, &lp_eur_usd, &user2, 10);
-
-    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-    check_token_balance(&mut app, &grid_token_instance, &user2, 5_000000);
+    app.update_block(|bi| next_block(bi));
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usdt,
+        USER1,
+        (10000000, None),
+    );
 }
 
 #[test]
-fn generator_update_proxy_balance_failed() {
+fn auto_stake_rewards() {
     let mut app = mock_app();
 
-    let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
-    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER);
 
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
@@ -1546,21 +1893,20 @@ fn generator_update_proxy_balance_failed() {
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let cny_eur_token_code_id = store_token_code(&mut app);
-    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let usdt_token = instantiate_token(&mut app, token_code_id, "USDT", None);
 
-    let (pair_val_eur, lp_val_eur) = create_pair(
+    let (pair_eur_usdt, lp_eur_usdt) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
             AssetInfo::Token {
-                contract_addr: val_token.clone(),
+                contract_addr: eur_token.clone(),
             },
             AssetInfo::Token {
-                contract_addr: eur_token.clone(),
+                contract_addr: usdt_token.clone(),
             },
         ],
     );
@@ -1568,285 +1914,156 @@ fn generator_update_proxy_balance_failed() {
     let generator_instance =
         instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    let vkr_staking_instance =
-        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
-
-    let proxy_code_id = store_proxy_code(&mut app);
-
-    let proxy_to_vkr_instance = instantiate_proxy(
+    register_lp_tokens_in_generator(
         &mut app,
-        proxy_code_id,
         &generator_instance,
-        &pair_val_eur,
-        &lp_val_eur,
-        &vkr_staking_instance,
-        &val_token,
+        vec![PoolWithProxy {
+            pool: (lp_eur_usdt.to_string(), Uint128::from(10u32)),
+            proxy: None,
+        }],
     );
 
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![(lp_val_eur.to_string(), Uint128::from(50u64))],
-    };
+    mint_tokens(&mut app, pair_eur_usdt.clone(), &lp_eur_usdt, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_eur_usdt, 10)]);
 
-    app.execute_contract(
-        Addr::unchecked(OWNER),
-        generator_instance.clone(),
-        &msg,
-        &[],
-    )
-    .unwrap();
+    // Auto-staking can't be enabled until the owner sets the staking contract
+    let enable_msg = GeneratorExecuteMsg::SetAutoStakeRewards { enabled: true };
+    let err = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &enable_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AutoStakeNotAvailable {}
+    );
 
-    let msg = GeneratorExecuteMsg::MoveToProxy {
-        lp_token: lp_val_eur.to_string(),
-        proxy: proxy_to_vkr_instance.to_string(),
-    };
-
-    app.execute_contract(
-        Addr::unchecked(OWNER),
-        generator_instance.clone(),
-        &msg,
-        &[],
-    )
-    .unwrap();
-
-    // Mint tokens, so user can deposit
-    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
-    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
-
-    // With the proxy, the Generator contract doesn't have the deposited LP tokens
-    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
-    // The LP tokens are in the 3rd party contract now
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
-
-    // Mint tokens on staking for distributing
-    mint_tokens(
-        &mut app,
-        owner.clone(),
-        &val_token,
-        &vkr_staking_instance,
-        200_000_000,
-    );
-
-    app.update_block(|bi| next_block(bi));
-
-    // User 2
-    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 10);
-    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 10)]);
-
-    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
-
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER1,
-        (10_000000, Some(vec![50_000000])),
-    );
-
-    // New deposits can't receive already calculated rewards
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER2,
-        (0, Some(vec![0])),
-    );
-
-    // Change pool alloc points
-    app.execute_contract(
-        owner.clone(),
-        generator_instance.clone(),
-        &GeneratorExecuteMsg::SetupPools {
-            pools: vec![(lp_val_eur.to_string(), Uint128::new(60))],
-        },
-        &[],
-    )
-    .unwrap();
-
-    app.update_block(|bi| next_block(bi));
-
-    // check pending rewards for user1
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER1,
-        (15_000000, Some(vec![80_000_000])),
-    );
-
-    // check pending rewards for user2
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER2,
-        (5_000000, Some(vec![30000000])),
-    );
-
-    // check staking balance
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
-
-    // Check user1, user2 and proxy balances
-    check_token_balance(&mut app, &val_token, &user1, 0);
-    check_token_balance(&mut app, &val_token, &user2, 0);
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
-
-    // Let's try withdraw for user1
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_val_eur.to_string(),
-        amount: Uint128::new(5),
-    };
-    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    // check pending rewards for user1 after withdraw
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER1,
-        (0, Some(vec![0])),
-    );
-
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER2,
-        (5_000_000, Some(vec![30_000_000])),
+    let staking_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_staking::contract::execute,
+            gridiron_staking::contract::instantiate,
+            gridiron_staking::contract::query,
+        )
+        .with_reply_empty(gridiron_staking::contract::reply),
     );
-
-    // Check user1, user2 and proxy balances
-    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
-    check_token_balance(&mut app, &val_token, &user2, 0);
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 30_000_000);
-
-    // Compare rewards on proxy and generator
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(
-            &generator_instance,
-            &QueryMsg::PoolInfo {
-                lp_token: lp_val_eur.to_string(),
+    let staking_code_id = app.store_code(staking_contract);
+    let staking_instance = app
+        .instantiate_contract(
+            staking_code_id,
+            owner.clone(),
+            &StakingInstantiateMsg {
+                owner: owner.to_string(),
+                token_code_id,
+                deposit_token_addr: grid_token_instance.to_string(),
+                marketing: None,
+                revenue_asset: None,
+                redemption: None,
             },
+            &[],
+            "xGRID",
+            None,
         )
         .unwrap();
+    let xgrid_token = app
+        .wrap()
+        .query_wasm_smart::<StakingConfigResponse>(&staking_instance, &StakingQueryMsg::Config {})
+        .unwrap()
+        .share_token_addr;
 
-    // Generator proxy reward balance before update is 110_000_000
-    assert_eq!(
-        Uint128::new(110_000_000),
-        reps.proxy_reward_balance_before_update
-    );
-
-    // Let's try checkpoint user boost
     app.execute_contract(
-        user1.clone(),
+        owner.clone(),
         generator_instance.clone(),
-        &GeneratorExecuteMsg::CheckpointUserBoost {
-            generators: vec![lp_val_eur.to_string()],
-            user: None,
+        &GeneratorExecuteMsg::UpdateConfig {
+            vesting_contract: None,
+            generator_controller: None,
+            guardian: None,
+            voting_escrow_delegation: None,
+            voting_escrow: None,
+            checkpoint_generator_limit: None,
+            proxy_rewards_claim_interval: None,
+            staking_contract: Some(staking_instance.to_string()),
+            max_pool_share: None,
+            grid_pool_boost: None,
+            router: None,
         },
         &[],
     )
     .unwrap();
 
-    // Compare rewards on proxy and generator
-    let reps: PoolInfoResponse = app
+    app.execute_contract(user1.clone(), generator_instance.clone(), &enable_msg, &[])
+        .unwrap();
+    let enabled: bool = app
         .wrap()
         .query_wasm_smart(
             &generator_instance,
-            &QueryMsg::PoolInfo {
-                lp_token: lp_val_eur.to_string(),
+            &GeneratorQueryMsg::AutoStakeRewards {
+                user: USER1.to_string(),
             },
         )
         .unwrap();
+    assert!(enabled);
 
-    // Proxies val_token balance is 30_000_000
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 30_000_000);
-
-    // Generator proxy reward balance before update is 30_000_000
-    assert_eq!(
-        Uint128::new(30_000_000),
-        reps.proxy_reward_balance_before_update
-    );
-
-    // Let's try claim rewards for user2
-    let msg = GeneratorExecuteMsg::ClaimRewards {
-        lp_tokens: vec![lp_val_eur.to_string()],
+    // Claimed GRID is staked and the user receives xGRID.
+    // The first deposit into staking locks the minimum stake amount
+    app.update_block(|bi| next_block(bi));
+    let claim_msg = GeneratorExecuteMsg::ClaimRewards {
+        lp_tokens: vec![lp_eur_usdt.to_string()],
     };
-    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+    app.execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
         .unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &user1, 0);
+    check_token_balance(&mut app, &xgrid_token, &user1, 9999000);
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 0);
+    check_token_balance(&mut app, &xgrid_token, &generator_instance, 0);
 
-    // Check user1, user2 and proxy balances
-    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
-    check_token_balance(&mut app, &val_token, &user2, 30_000_000);
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
-
-    // Let's try deactivate pool
+    // Make staking fail: after a large donation the rewards are too small to mint any xGRID
     app.execute_contract(
-        factory_instance.clone(),
-        generator_instance.clone(),
-        &GeneratorExecuteMsg::DeactivatePool {
-            lp_token: lp_val_eur.to_string(),
+        owner.clone(),
+        grid_token_instance.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: staking_instance.to_string(),
+            amount: Uint128::new(100_000_000_000000),
         },
         &[],
     )
     .unwrap();
-
     app.update_block(|bi| next_block(bi));
-
-    // Let's try claim rewards for user1
-    let msg = GeneratorExecuteMsg::ClaimRewards {
-        lp_tokens: vec![lp_val_eur.to_string()],
-    };
-    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+    app.execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
         .unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &user1, 10000000);
+    check_token_balance(&mut app, &xgrid_token, &user1, 9999000);
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 0);
 
-    // Let's try checkpoint user boost
+    // Rewards are sent as GRID once auto-staking is disabled
     app.execute_contract(
-        factory_instance.clone(),
+        user1.clone(),
         generator_instance.clone(),
-        &GeneratorExecuteMsg::CheckpointUserBoost {
-            generators: vec![lp_val_eur.to_string()],
-            user: None,
-        },
+        &GeneratorExecuteMsg::SetAutoStakeRewards { enabled: false },
         &[],
     )
     .unwrap();
+    let enabled: bool = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::AutoStakeRewards {
+                user: USER1.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(!enabled);
 
-    // check pending rewards for user1 after withdraw
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER1,
-        (0, Some(vec![0])),
-    );
-
-    check_pending_rewards(
-        &mut app,
-        &generator_instance,
-        &lp_val_eur,
-        USER2,
-        (0, Some(vec![0])),
-    );
-
-    // check staking balance
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 15);
-
-    // Check user1, user2 and proxy balances
-    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
-    check_token_balance(&mut app, &val_token, &user2, 30_000_000);
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
+    app.update_block(|bi| next_block(bi));
+    app.execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &grid_token_instance, &user1, 20000000);
+    check_token_balance(&mut app, &xgrid_token, &user1, 9999000);
 }
 
 #[test]
-fn generator_with_vkr_reward_proxy() {
+fn claim_rewards_as() {
     let mut app = mock_app();
 
-    let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
-    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER);
 
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
@@ -1857,234 +2074,562 @@ fn generator_with_vkr_reward_proxy() {
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let cny_eur_token_code_id = store_token_code(&mut app);
-    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let usd_token = instantiate_token(&mut app, cny_eur_token_code_id, "USD", None);
-    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let usdt_token = instantiate_token(&mut app, token_code_id, "USDT", None);
 
-    let (pair_val_eur, lp_val_eur) = create_pair(
+    let (pair_eur_usdt, lp_eur_usdt) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
             AssetInfo::Token {
-                contract_addr: val_token.clone(),
+                contract_addr: eur_token.clone(),
             },
             AssetInfo::Token {
-                contract_addr: eur_token.clone(),
+                contract_addr: usdt_token.clone(),
             },
         ],
     );
-
-    let (pair_eur_usd, lp_eur_usd) = create_pair(
+    let (pair_grid_eur, _) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
             AssetInfo::Token {
-                contract_addr: eur_token.clone(),
+                contract_addr: grid_token_instance.clone(),
             },
             AssetInfo::Token {
-                contract_addr: usd_token.clone(),
+                contract_addr: eur_token.clone(),
             },
         ],
     );
 
-    let generator_instance =
-        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
-
-    let vkr_staking_instance =
-        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
-
-    let proxy_code_id = store_proxy_code(&mut app);
-
-    let proxy_to_vkr_instance = instantiate_proxy(
-        &mut app,
-        proxy_code_id,
-        &generator_instance,
-        &pair_val_eur,
-        &lp_val_eur,
-        &vkr_staking_instance,
-        &val_token,
-    );
-
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_val_eur.to_string(), Uint128::from(50u64)),
-            (lp_eur_usd.to_string(), Uint128::from(50u64)),
-        ],
-    };
-
+    // Provide liquidity to the GRID/EUR pair used to swap rewards
+    let liquidity = 100_000_000000u128;
+    for token in [&grid_token_instance, &eur_token] {
+        mint_tokens(&mut app, owner.clone(), token, &owner, liquidity);
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_grid_eur.to_string(),
+                amount: Uint128::new(liquidity),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
     app.execute_contract(
-        Addr::unchecked(OWNER),
-        generator_instance.clone(),
-        &msg,
+        owner.clone(),
+        pair_grid_eur.clone(),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                token_asset_info(grid_token_instance.clone()).with_balance(liquidity),
+                token_asset_info(eur_token.clone()).with_balance(liquidity),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
         &[],
     )
     .unwrap();
 
-    let msg = GeneratorExecuteMsg::MoveToProxy {
-        lp_token: lp_val_eur.to_string(),
-        proxy: proxy_to_vkr_instance.to_string(),
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_eur_usdt.to_string(), Uint128::from(10u32)),
+            proxy: None,
+        }],
+    );
+
+    mint_tokens(&mut app, pair_eur_usdt.clone(), &lp_eur_usdt, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_eur_usdt, 10)]);
+
+    // Rewards can't be claimed as another asset until the owner sets the router
+    let claim_msg = GeneratorExecuteMsg::ClaimRewardsAs {
+        lp_token: lp_eur_usdt.to_string(),
+        target_asset: token_asset_info(eur_token.clone()),
+        min_received: None,
     };
+    app.update_block(|bi| next_block(bi));
+    let err = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ClaimAsNotAvailable {}
+    );
+
+    let router_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_router::contract::execute,
+            gridiron_router::contract::instantiate,
+            gridiron_router::contract::query,
+        )
+        .with_reply_empty(gridiron_router::contract::reply),
+    );
+    let router_code_id = app.store_code(router_contract);
+    let router_instance = app
+        .instantiate_contract(
+            router_code_id,
+            owner.clone(),
+            &RouterInstantiateMsg {
+                gridiron_factory: factory_instance.to_string(),
+            },
+            &[],
+            "Router",
+            None,
+        )
+        .unwrap();
 
     app.execute_contract(
-        Addr::unchecked(OWNER),
+        owner.clone(),
         generator_instance.clone(),
-        &msg,
+        &GeneratorExecuteMsg::UpdateConfig {
+            vesting_contract: None,
+            generator_controller: None,
+            guardian: None,
+            voting_escrow_delegation: None,
+            voting_escrow: None,
+            checkpoint_generator_limit: None,
+            proxy_rewards_claim_interval: None,
+            staking_contract: None,
+            max_pool_share: None,
+            grid_pool_boost: None,
+            router: Some(router_instance.to_string()),
+        },
         &[],
     )
     .unwrap();
 
-    // Mint tokens, so user can deposit
-    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 9);
-    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user1, 10);
-
-    let msg = Cw20ExecuteMsg::Send {
-        contract: generator_instance.to_string(),
-        msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
-        amount: Uint128::new(10),
-    };
-
+    // GRID can only be claimed with ClaimRewards
     let err = app
-        .execute_contract(user1.clone(), lp_val_eur.clone(), &msg, &[])
+        .execute_contract(
+            user1.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ClaimRewardsAs {
+                lp_token: lp_eur_usdt.to_string(),
+                target_asset: token_asset_info(grid_token_instance.clone()),
+                min_received: None,
+            },
+            &[],
+        )
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "Cannot Sub with 9 and 10".to_string()
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidClaimAsTarget {}
     );
 
-    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 1);
+    let eur_balance = |app: &mut App| -> Uint128 {
+        app.wrap()
+            .query_wasm_smart::<BalanceResponse>(
+                &eur_token,
+                &Cw20QueryMsg::Balance {
+                    address: USER1.to_string(),
+                },
+            )
+            .unwrap()
+            .balance
+    };
 
-    deposit_lp_tokens_to_generator(
+    // Claimed GRID is swapped to EUR and sent to the user
+    let res = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "action" && attr.value == "swap_rewards")));
+    check_token_balance(&mut app, &grid_token_instance, &user1, 0);
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 0);
+    let received = eur_balance(&mut app);
+    assert!(!received.is_zero());
+
+    // If the swap fails the rewards are sent as GRID
+    app.update_block(|bi| next_block(bi));
+    let res = app
+        .execute_contract(
+            user1.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ClaimRewardsAs {
+                lp_token: lp_eur_usdt.to_string(),
+                target_asset: token_asset_info(eur_token.clone()),
+                min_received: Some(Uint128::new(liquidity)),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "action" && attr.value == "swap_rewards_fallback")));
+    check_token_balance(&mut app, &grid_token_instance, &user1, 10000000);
+    check_token_balance(&mut app, &grid_token_instance, &generator_instance, 0);
+    assert_eq!(eur_balance(&mut app), received);
+}
+
+#[test]
+fn receipt_tokens() {
+    let mut app = mock_app();
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+    let receipt_token_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_generator_receipt_token::contract::execute,
+        gridiron_generator_receipt_token::contract::instantiate,
+        gridiron_generator_receipt_token::contract::query,
+    )));
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let usdt_token = instantiate_token(&mut app, token_code_id, "USDT", None);
+
+    let (pair_eur_usdt, lp_eur_usdt) = create_pair(
         &mut app,
-        &generator_instance,
-        USER1,
-        &[(&lp_val_eur, 10), (&lp_eur_usd, 10)],
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: usdt_token.clone(),
+            },
+        ],
     );
 
-    // With the proxy, the Generator contract doesn't have the deposited LP tokens
-    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
-    // The LP tokens are in the 3rd party contract now
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
-
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
-    check_token_balance(&mut app, &lp_eur_usd, &vkr_staking_instance, 0);
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    check_pending_rewards(
+    register_lp_tokens_in_generator(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
-        USER1,
-        (0, Some(vec![0])),
+        vec![PoolWithProxy {
+            pool: (lp_eur_usdt.to_string(), Uint128::from(10u32)),
+            proxy: None,
+        }],
     );
-    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER1, (0, None));
 
-    // User can't withdraw if they didn't deposit previously
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_val_eur.to_string(),
-        amount: Uint128::new(1_000000),
+    mint_tokens(&mut app, pair_eur_usdt.clone(), &lp_eur_usdt, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_eur_usdt, 10)]);
+
+    let mint_msg = GeneratorExecuteMsg::MintReceipt {
+        lp_token: lp_eur_usdt.to_string(),
+        amount: Uint128::new(6),
     };
     let err = app
-        .execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(user1.clone(), generator_instance.clone(), &mint_msg, &[])
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "Insufficient balance in contract to process claim".to_string()
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReceiptTokenNotSet {}
     );
 
-    // User can't emergency withdraw if they didn't deposit previously
-    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
-        lp_token: lp_val_eur.to_string(),
+    let setup_msg = GeneratorExecuteMsg::SetupReceiptToken {
+        lp_token: lp_eur_usdt.to_string(),
+        token_code_id: receipt_token_code_id,
     };
-
     let err = app
-        .execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(user1.clone(), generator_instance.clone(), &setup_msg, &[])
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "gridiron::generator::UserInfo not found".to_string()
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    app.execute_contract(owner.clone(), generator_instance.clone(), &setup_msg, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(owner, generator_instance.clone(), &setup_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReceiptTokenAlreadySet {}
     );
 
-    app.update_block(|bi| next_block(bi));
+    let receipt_token: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::ReceiptToken {
+                lp_token: lp_eur_usdt.to_string(),
+            },
+        )
+        .unwrap();
+    let receipt_token = receipt_token.unwrap();
 
-    // Mint tokens on staking for distributing
-    mint_tokens(
-        &mut app,
-        owner.clone(),
-        &val_token,
-        &vkr_staking_instance,
-        200_000_000,
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::MintReceipt {
+                lp_token: lp_eur_usdt.to_string(),
+                amount: Uint128::new(11),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotEnoughUnbackedBalance {}
     );
 
-    // Check if proxy reward exists
-    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
+    app.execute_contract(user1.clone(), generator_instance.clone(), &mint_msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &receipt_token, &user1, 6);
+
+    // Holders can't burn receipt tokens directly
+    app.execute_contract(
+        user1.clone(),
+        receipt_token.clone(),
+        &Cw20ExecuteMsg::Burn {
+            amount: Uint128::new(1),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // Transferring receipt tokens moves the staked position
+    app.update_block(|bi| next_block(bi));
+    app.execute_contract(
+        user1.clone(),
+        receipt_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: USER2.to_string(),
+            amount: Uint128::new(6),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The sender received the rewards accrued before the transfer
+    check_token_balance(&mut app, &grid_token_instance, &user1, 10_000000);
+    check_token_balance(&mut app, &receipt_token, &user2, 6);
+    let deposit: Uint128 = app
         .wrap()
         .query_wasm_smart(
-            &vkr_staking_instance,
-            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
-                staker: proxy_to_vkr_instance.to_string(),
+            &generator_instance,
+            &GeneratorQueryMsg::Deposit {
+                lp_token: lp_eur_usdt.to_string(),
+                user: USER2.to_string(),
             },
         )
         .unwrap();
-    assert_eq!(Uint128::new(50_000_000), reps.pending_reward);
-    assert_eq!(Uint128::new(10), reps.bond_amount);
+    assert_eq!(deposit.u128(), 6);
+    let backed: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::ReceiptBacked {
+                lp_token: lp_eur_usdt.to_string(),
+                user: USER2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(backed.u128(), 6);
 
-    // check pending rewards before calling update rewards directly
+    // Rewards follow the receipt tokens
+    app.update_block(|bi| next_block(bi));
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_eur_usdt,
         USER1,
-        (5_000000, Some(vec![50_000_000])),
+        (4_000000, None),
     );
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_eur_usd,
-        USER1,
-        (5_000000, None),
+        &lp_eur_usdt,
+        USER2,
+        (6_000000, None),
     );
 
-    let err = app
-        .execute_contract(
-            user1.clone(),
-            proxy_to_vkr_instance.clone(),
-            &ProxyExecuteMsg::UpdateRewards {},
-            &[],
-        )
-        .unwrap_err();
-    assert_eq!("Unauthorized", err.root_cause().to_string());
+    // Withdrawing burns the receipt tokens backed by the withdrawn LP tokens
+    app.execute_contract(
+        user2.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::Withdraw {
+            lp_token: lp_eur_usdt.to_string(),
+            amount: Uint128::new(6),
+        },
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &receipt_token, &user2, 0);
+    check_token_balance(&mut app, &lp_eur_usdt, &user2, 6);
+    check_token_balance(&mut app, &grid_token_instance, &user2, 6_000000);
 
-    // User 2
-    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 10);
-    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user2, 10);
+    app.execute_contract(
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::Withdraw {
+            lp_token: lp_eur_usdt.to_string(),
+            amount: Uint128::new(4),
+        },
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &lp_eur_usdt, &user1, 4);
+    check_token_balance(&mut app, &grid_token_instance, &user1, 14_000000);
+}
+
+#[test]
+fn generator_without_reward_proxies() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let cny_eur_token_code_id = store_token_code(&mut app);
+    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
+    let usd_token = instantiate_token(&mut app, cny_eur_token_code_id, "USD", None);
+    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let (pair_eur_usd, lp_eur_usd) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: usd_token.clone(),
+            },
+        ],
+    );
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![
+            PoolWithProxy {
+                pool: (lp_cny_eur.to_string(), Uint128::from(50u32)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_eur_usd.to_string(), Uint128::from(50u32)),
+                proxy: None,
+            },
+        ],
+    );
+
+    // Mint tokens, so user can deposit
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 9);
+    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user1, 10);
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: generator_instance.to_string(),
+        msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
+        amount: Uint128::new(10),
+    };
+
+    assert_eq!(
+        app.execute_contract(user1.clone(), lp_cny_eur.clone(), &msg, &[])
+            .unwrap_err()
+            .root_cause()
+            .to_string(),
+        "Cannot Sub with 9 and 10".to_string()
+    );
+
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 1);
 
     deposit_lp_tokens_to_generator(
         &mut app,
         &generator_instance,
-        USER2,
-        &[(&lp_val_eur, 10), (&lp_eur_usd, 10)],
+        USER1,
+        &[(&lp_cny_eur, 10), (&lp_eur_usd, 10)],
     );
 
-    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 10);
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
 
-    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 20);
-    check_token_balance(&mut app, &lp_eur_usd, &vkr_staking_instance, 0);
+    check_pending_rewards(&mut app, &generator_instance, &lp_cny_eur, USER1, (0, None));
+    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER1, (0, None));
 
-    // 10 tokens distributed to depositors since the last deposit
-    // 5 distrubuted to proxy contract sicne the last deposit
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
+    // User can't withdraw if they didn't deposit
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_cny_eur.to_string(),
+        amount: Uint128::new(1_000000),
+    };
+    assert_eq!(
+        app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+            .unwrap_err()
+            .root_cause()
+            .to_string(),
+        "Insufficient balance in contract to process claim".to_string()
+    );
+
+    // User can't emergency withdraw if they didn't deposit
+    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
+        lp_token: lp_cny_eur.to_string(),
+    };
+    assert_eq!(
+        app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+            .unwrap_err()
+            .root_cause()
+            .to_string(),
+        "gridiron::generator::UserInfo not found".to_string()
+    );
+
+    app.update_block(|bi| next_block(bi));
 
+    // 10 tokens per block split equally between 2 pools
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_cny_eur,
         USER1,
-        (5_000000, Some(vec![50_000000])),
+        (5_000000, None),
     );
     check_pending_rewards(
         &mut app,
@@ -2094,49 +2639,59 @@ fn generator_with_vkr_reward_proxy() {
         (5_000000, None),
     );
 
-    // New deposits can't receive already calculated rewards
-    check_pending_rewards(
+    // User 2
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user2, 10);
+    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user2, 10);
+
+    deposit_lp_tokens_to_generator(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
         USER2,
-        (0, Some(vec![0])),
+        &[(&lp_cny_eur, 10), (&lp_eur_usd, 10)],
+    );
+
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 20);
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 20);
+
+    // 10 tokens have been distributed to depositors since the last deposit
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_cny_eur,
+        USER1,
+        (5_000000, None),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER1,
+        (5_000000, None),
     );
+
+    // New deposits can't receive already calculated rewards
+    check_pending_rewards(&mut app, &generator_instance, &lp_cny_eur, USER2, (0, None));
     check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER2, (0, None));
 
     // Change pool alloc points
     let msg = GeneratorExecuteMsg::SetupPools {
         pools: vec![
-            (lp_val_eur.to_string(), Uint128::new(60)),
-            (lp_eur_usd.to_string(), Uint128::new(40)),
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_eur_usd.to_string(), Uint128::from(40u32)),
         ],
     };
-
     app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
 
     app.update_block(|bi| next_block(bi));
 
-    // Check if proxy reward exists
-    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
-        .wrap()
-        .query_wasm_smart(
-            &vkr_staking_instance,
-            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
-                staker: proxy_to_vkr_instance.to_string(),
-            },
-        )
-        .unwrap();
-    assert_eq!(Uint128::new(60_000_000), reps.pending_reward);
-    assert_eq!(Uint128::new(20), reps.bond_amount);
-
-    // check pending rewards before calling update rewards directly
+    // 60 to cny_eur, 40 to eur_usd. Each is divided for two users
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_cny_eur,
         USER1,
-        (8_000000, Some(vec![80_000_000])),
+        (8_000000, None),
     );
     check_pending_rewards(
         &mut app,
@@ -2146,25 +2701,12 @@ fn generator_with_vkr_reward_proxy() {
         (7_000000, None),
     );
 
-    // Check if proxy reward exists
-    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
-        .wrap()
-        .query_wasm_smart(
-            &vkr_staking_instance,
-            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
-                staker: proxy_to_vkr_instance.to_string(),
-            },
-        )
-        .unwrap();
-    assert_eq!(Uint128::new(60000000), reps.pending_reward);
-    assert_eq!(Uint128::new(20), reps.bond_amount);
-
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_cny_eur,
         USER2,
-        (3_000000, Some(vec![30000000])),
+        (3_000000, None),
     );
     check_pending_rewards(
         &mut app,
@@ -2174,10 +2716,10 @@ fn generator_with_vkr_reward_proxy() {
         (2_000000, None),
     );
 
-    // User1 emergency withdraws and loses already distributed rewards (5).
+    // User1 emergency withdraws and loses already accrued rewards (5).
     // Pending tokens (3) will be redistributed to other staked users.
     let msg = GeneratorExecuteMsg::EmergencyWithdraw {
-        lp_token: lp_val_eur.to_string(),
+        lp_token: lp_cny_eur.to_string(),
     };
     app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
@@ -2185,9 +2727,9 @@ fn generator_with_vkr_reward_proxy() {
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_cny_eur,
         USER1,
-        (0_000000, Some(vec![0])),
+        (0_000000, None),
     );
     check_pending_rewards(
         &mut app,
@@ -2200,9 +2742,9 @@ fn generator_with_vkr_reward_proxy() {
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_val_eur,
+        &lp_cny_eur,
         USER2,
-        (3_000000, Some(vec![60000000])),
+        (3_000000, None),
     );
     check_pending_rewards(
         &mut app,
@@ -2212,80 +2754,37 @@ fn generator_with_vkr_reward_proxy() {
         (2_000000, None),
     );
 
-    // Balance of the end contract should be decreased
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
+    // Balance of the generator should be decreased
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 10);
 
-    // User1 can't withdraw after emergency withdrawal
+    // User1 can't withdraw after emergency withdraw
     let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_val_eur.to_string(),
+        lp_token: lp_cny_eur.to_string(),
         amount: Uint128::new(1_000000),
     };
-    let err = app
-        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
+        app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+            .unwrap_err()
+            .root_cause()
+            .to_string(),
         "Insufficient balance in contract to process claim".to_string(),
     );
 
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50000000);
-    check_token_balance(&mut app, &val_token, &owner, 0);
-
-    // Check if there are orphaned proxy rewards
-    let msg = GeneratorQueryMsg::OrphanProxyRewards {
-        lp_token: lp_val_eur.to_string(),
-    };
-    let orphan_rewards: Vec<(AssetInfo, Uint128)> = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg)
-        .unwrap();
-    assert_eq!(orphan_rewards[0].1, Uint128::new(50000000));
-
-    // Owner sends orphaned proxy rewards
-    let msg = GeneratorExecuteMsg::SendOrphanProxyReward {
-        recipient: owner.to_string(),
-        lp_token: lp_val_eur.to_string(),
-    };
-
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
-    check_token_balance(&mut app, &val_token, &owner, 50000000);
-
-    // Owner can't send proxy rewards for distribution to users
-    let msg = GeneratorExecuteMsg::SendOrphanProxyReward {
-        recipient: owner.to_string(),
-        lp_token: lp_val_eur.to_string(),
-    };
-
-    let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap_err();
-    assert_eq!(
-        err.root_cause().to_string(),
-        "Insufficient amount of orphan rewards!"
-    );
-
-    // User2 withdraws and gets rewards
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_val_eur.to_string(),
-        amount: Uint128::new(10),
+    // User2 withdraw and get rewards
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_cny_eur.to_string(),
+        amount: Uint128::new(10),
     };
     app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
 
-    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 0);
-    check_token_balance(&mut app, &lp_val_eur, &user1, 10);
-    check_token_balance(&mut app, &lp_val_eur, &user2, 10);
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_cny_eur, &user1, 10);
+    check_token_balance(&mut app, &lp_cny_eur, &user2, 10);
 
     check_token_balance(&mut app, &grid_token_instance, &user1, 0);
-    check_token_balance(&mut app, &val_token, &user1, 0);
     check_token_balance(&mut app, &grid_token_instance, &user2, 3_000000);
-    check_token_balance(&mut app, &val_token, &user2, 60000000);
-
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
+    // 7 + 2 distributed GRID (for other pools). 5 orphaned by emergency withdrawals, 6 transfered to User2
 
     // User1 withdraws and gets rewards
     let msg = GeneratorExecuteMsg::Withdraw {
@@ -2299,7 +2798,6 @@ fn generator_with_vkr_reward_proxy() {
     check_token_balance(&mut app, &lp_eur_usd, &user1, 5);
 
     check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-    check_token_balance(&mut app, &val_token, &user1, 0);
 
     // User1 withdraws and gets rewards
     let msg = GeneratorExecuteMsg::Withdraw {
@@ -2312,7 +2810,6 @@ fn generator_with_vkr_reward_proxy() {
     check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
     check_token_balance(&mut app, &lp_eur_usd, &user1, 10);
     check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-    check_token_balance(&mut app, &val_token, &user1, 0);
 
     // User2 withdraws and gets rewards
     let msg = GeneratorExecuteMsg::Withdraw {
@@ -2327,20 +2824,17 @@ fn generator_with_vkr_reward_proxy() {
     check_token_balance(&mut app, &lp_eur_usd, &user2, 10);
 
     check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
-    check_token_balance(&mut app, &val_token, &user1, 0_000000);
     check_token_balance(&mut app, &grid_token_instance, &user2, 5_000000);
-    check_token_balance(&mut app, &val_token, &user2, 60000000);
-
-    // Proxies val_token balance
-    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
 }
 
 #[test]
-fn move_to_proxy() {
+fn generator_update_proxy_balance_failed() {
     let mut app = mock_app();
 
     let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
     let pair_code_id = store_pair_code_id(&mut app);
@@ -2352,17 +2846,16 @@ fn move_to_proxy() {
 
     let cny_eur_token_code_id = store_token_code(&mut app);
     let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
-    let vkr_token_instance = instantiate_token(&mut app, token_code_id, "VAL", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
 
-    let (pair_cny_eur, lp_cny_eur) = create_pair(
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
             AssetInfo::Token {
-                contract_addr: cny_token.clone(),
+                contract_addr: val_token.clone(),
             },
             AssetInfo::Token {
                 contract_addr: eur_token.clone(),
@@ -2373,28 +2866,8 @@ fn move_to_proxy() {
     let generator_instance =
         instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    register_lp_tokens_in_generator(
-        &mut app,
-        &generator_instance,
-        vec![PoolWithProxy {
-            pool: (lp_cny_eur.to_string(), Uint128::from(50u32)),
-            proxy: None,
-        }],
-    );
-
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
-    };
-
-    // Check if proxy reward is none
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(None, reps.reward_proxy);
-
     let vkr_staking_instance =
-        instantiate_valkyrie_protocol(&mut app, &vkr_token_instance, &pair_cny_eur, &lp_cny_eur);
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
 
     let proxy_code_id = store_proxy_code(&mut app);
 
@@ -2402,263 +2875,299 @@ fn move_to_proxy() {
         &mut app,
         proxy_code_id,
         &generator_instance,
-        &pair_cny_eur,
-        &lp_cny_eur,
+        &pair_val_eur,
+        &lp_val_eur,
         &vkr_staking_instance,
-        &vkr_token_instance,
+        &val_token,
     );
-    assert_eq!(Addr::unchecked("contract12"), proxy_to_vkr_instance);
 
-    // Set the proxy for the pool
-    let msg = ExecuteMsg::MoveToProxy {
-        lp_token: lp_cny_eur.to_string(),
-        proxy: proxy_to_vkr_instance.to_string(),
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![(lp_val_eur.to_string(), Uint128::from(50u64))],
     };
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        generator_instance.clone(),
+        &msg,
+        &[],
+    )
+    .unwrap();
+
+    let msg = GeneratorExecuteMsg::MoveToProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: proxy_to_vkr_instance.to_string(),
     };
 
-    // Check if proxy reward exists
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Some(Addr::unchecked("contract12")), reps.reward_proxy);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        generator_instance.clone(),
+        &msg,
+        &[],
+    )
+    .unwrap();
 
     // Mint tokens, so user can deposit
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 10);
-
-    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_cny_eur, 10)]);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
 
-    // With the proxy set up, the Generator contract doesn't have the deposited LP tokens
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
+    // With the proxy, the Generator contract doesn't have the deposited LP tokens
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
     // The LP tokens are in the 3rd party contract now
-    check_token_balance(&mut app, &lp_cny_eur, &vkr_staking_instance, 10);
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
 
-    check_pending_rewards(
+    // Mint tokens on staking for distributing
+    mint_tokens(
         &mut app,
-        &generator_instance,
-        &lp_cny_eur,
-        USER1,
-        (0, Some(vec![0])),
+        owner.clone(),
+        &val_token,
+        &vkr_staking_instance,
+        200_000_000,
     );
 
     app.update_block(|bi| next_block(bi));
 
-    // Check if proxy reward configs
-    let reps: ConfigResponse = app
-        .wrap()
-        .query_wasm_smart(&proxy_to_vkr_instance, &QueryMsg::Config {})
-        .unwrap();
-    assert_eq!("contract7".to_string(), reps.lp_token_addr);
+    // User 2
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 10)]);
+
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
 
     check_pending_rewards(
         &mut app,
         &generator_instance,
-        &lp_cny_eur,
+        &lp_val_eur,
         USER1,
-        (10_000000, Some(vec![50_000_000])),
+        (10_000000, Some(vec![50_000000])),
     );
 
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
-    check_token_balance(&mut app, &lp_cny_eur, &vkr_staking_instance, 10);
+    // New deposits can't receive already calculated rewards
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (0, Some(vec![0])),
+    );
 
-    // Check if the pool already has a reward proxy contract set
-    let msg = ExecuteMsg::MoveToProxy {
-        lp_token: lp_cny_eur.to_string(),
-        proxy: proxy_to_vkr_instance.to_string(),
-    };
-    let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap_err();
-    assert_eq!(
-        "The pool already has a reward proxy contract!",
-        err.root_cause().to_string()
+    // Change pool alloc points
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::SetupPools {
+            pools: vec![(lp_val_eur.to_string(), Uint128::new(60))],
+        },
+        &[],
     )
-}
-
-#[test]
-fn query_all_stakers() {
-    let mut app = mock_app();
-
-    let user1 = Addr::unchecked(USER1);
-    let user2 = Addr::unchecked(USER2);
-    let user3 = Addr::unchecked(USER3);
-    let user4 = Addr::unchecked(USER4);
-    let user5 = Addr::unchecked(USER5);
-    let token_code_id = store_token_code(&mut app);
-    let factory_code_id = store_factory_code(&mut app);
-    let pair_code_id = store_pair_code_id(&mut app);
-
-    let factory_instance =
-        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+    .unwrap();
 
-    let cny_eur_token_code_id = store_token_code(&mut app);
-    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+    app.update_block(|bi| next_block(bi));
 
-    let (pair_cny_eur, lp_cny_eur) = create_pair(
+    // check pending rewards for user1
+    check_pending_rewards(
         &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: cny_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: eur_token.clone(),
-            },
-        ],
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (15_000000, Some(vec![80_000_000])),
     );
 
-    let grid_token_instance =
-        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
-
-    let generator_instance =
-        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
-
-    register_lp_tokens_in_generator(
+    // check pending rewards for user2
+    check_pending_rewards(
         &mut app,
         &generator_instance,
-        vec![PoolWithProxy {
-            pool: (lp_cny_eur.to_string(), Uint128::new(100)),
-            proxy: None,
-        }],
+        &lp_val_eur,
+        USER2,
+        (5_000000, Some(vec![30000000])),
     );
 
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 10);
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user2, 10);
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user3, 10);
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user4, 10);
-    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user5, 10);
-
-    let msg_cny_eur = QueryMsg::PoolStakers {
-        lp_token: lp_cny_eur.to_string(),
-        start_after: None,
-        limit: None,
+    // check staking balance
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
+
+    // Check user1, user2 and proxy balances
+    check_token_balance(&mut app, &val_token, &user1, 0);
+    check_token_balance(&mut app, &val_token, &user2, 0);
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
+
+    // Let's try withdraw for user1
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(5),
     };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
 
-    // Check there are no stakers when there's no deposit
-    let reps: Vec<StakerResponse> = app
+    // check pending rewards for user1 after withdraw
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (0, Some(vec![0])),
+    );
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (5_000_000, Some(vec![30_000_000])),
+    );
+
+    // Check user1, user2 and proxy balances
+    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
+    check_token_balance(&mut app, &val_token, &user2, 0);
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 30_000_000);
+
+    // Compare rewards on proxy and generator
+    let reps: PoolInfoResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &generator_instance,
+            &QueryMsg::PoolInfo {
+                lp_token: lp_val_eur.to_string(),
+            },
+        )
         .unwrap();
-    let empty: Vec<StakerResponse> = vec![];
-    assert_eq!(empty, reps);
 
-    for user in [USER1, USER2, USER3, USER4, USER5] {
-        deposit_lp_tokens_to_generator(&mut app, &generator_instance, user, &[(&lp_cny_eur, 10)]);
-    }
+    // Generator proxy reward balance before update is 110_000_000
+    assert_eq!(
+        Uint128::new(110_000_000),
+        reps.reward_proxies[0].reward_balance_before_update
+    );
 
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 50);
+    // Let's try checkpoint user boost
+    app.execute_contract(
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::CheckpointUserBoost {
+            generators: vec![lp_val_eur.to_string()],
+            user: None,
+        },
+        &[],
+    )
+    .unwrap();
 
-    let reps: Vec<StakerResponse> = app
+    // Compare rewards on proxy and generator
+    let reps: PoolInfoResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &generator_instance,
+            &QueryMsg::PoolInfo {
+                lp_token: lp_val_eur.to_string(),
+            },
+        )
         .unwrap();
 
+    // Proxies val_token balance is 30_000_000
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 30_000_000);
+
+    // Generator proxy reward balance before update is 30_000_000
     assert_eq!(
-        vec![
-            StakerResponse {
-                account: "user1".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user2".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user3".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user4".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user5".to_string(),
-                amount: Uint128::new(10)
-            }
-        ],
-        reps
+        Uint128::new(30_000_000),
+        reps.reward_proxies[0].reward_balance_before_update
     );
 
-    let msg = GeneratorExecuteMsg::Withdraw {
-        lp_token: lp_cny_eur.to_string(),
-        amount: Uint128::new(10),
+    // Let's try claim rewards for user2
+    let msg = GeneratorExecuteMsg::ClaimRewards {
+        lp_tokens: vec![lp_val_eur.to_string()],
     };
-
-    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
 
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 40);
+    // Check user1, user2 and proxy balances
+    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
+    check_token_balance(&mut app, &val_token, &user2, 30_000_000);
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
 
-    // Check the amount of stakers after withdrawal
-    let reps: Vec<StakerResponse> = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    // Let's try deactivate pool
+    app.execute_contract(
+        factory_instance.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::DeactivatePool {
+            lp_token: lp_val_eur.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|bi| next_block(bi));
+
+    // Let's try claim rewards for user1
+    let msg = GeneratorExecuteMsg::ClaimRewards {
+        lp_tokens: vec![lp_val_eur.to_string()],
+    };
+    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
 
-    assert_eq!(
-        vec![
-            StakerResponse {
-                account: "user2".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user3".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user4".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user5".to_string(),
-                amount: Uint128::new(10)
-            }
-        ],
-        reps
+    // Let's try checkpoint user boost
+    app.execute_contract(
+        factory_instance.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::CheckpointUserBoost {
+            generators: vec![lp_val_eur.to_string()],
+            user: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // check pending rewards for user1 after withdraw
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (0, Some(vec![0])),
     );
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (0, Some(vec![0])),
+    );
+
+    // check staking balance
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 15);
+
+    // Check user1, user2 and proxy balances
+    check_token_balance(&mut app, &val_token, &user1, 80_000_000);
+    check_token_balance(&mut app, &val_token, &user2, 30_000_000);
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
 }
 
 #[test]
-fn query_pagination_stakers() {
+fn generator_with_vkr_reward_proxy() {
     let mut app = mock_app();
 
+    let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
     let user2 = Addr::unchecked(USER2);
-    let user3 = Addr::unchecked(USER3);
-    let user4 = Addr::unchecked(USER4);
-    let user5 = Addr::unchecked(USER5);
-    let user6 = Addr::unchecked(USER6);
-    let user7 = Addr::unchecked(USER7);
-    let user8 = Addr::unchecked(USER8);
-    let user9 = Addr::unchecked(USER9);
 
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
     let pair_code_id = store_pair_code_id(&mut app);
 
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
     let cny_eur_token_code_id = store_token_code(&mut app);
     let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
-    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+    let usd_token = instantiate_token(&mut app, cny_eur_token_code_id, "USD", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
 
-    let (pair_cny_eur, lp_cny_eur) = create_pair(
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
             AssetInfo::Token {
-                contract_addr: cny_token.clone(),
+                contract_addr: val_token.clone(),
             },
             AssetInfo::Token {
                 contract_addr: eur_token.clone(),
@@ -2666,962 +3175,1256 @@ fn query_pagination_stakers() {
         ],
     );
 
-    let grid_token_instance =
-        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let (pair_eur_usd, lp_eur_usd) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: usd_token.clone(),
+            },
+        ],
+    );
 
     let generator_instance =
         instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    register_lp_tokens_in_generator(
+    let vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
+
+    let proxy_code_id = store_proxy_code(&mut app);
+
+    let proxy_to_vkr_instance = instantiate_proxy(
         &mut app,
+        proxy_code_id,
         &generator_instance,
-        vec![PoolWithProxy {
-            pool: (lp_cny_eur.to_string(), Uint128::from(100u32)),
-            proxy: None,
-        }],
+        &pair_val_eur,
+        &lp_val_eur,
+        &vkr_staking_instance,
+        &val_token,
     );
 
-    for user in [
-        user1, user2, user3, user4, user5, user6, user7, user8, user9,
-    ] {
-        mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user, 10);
-    }
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_val_eur.to_string(), Uint128::from(50u64)),
+            (lp_eur_usd.to_string(), Uint128::from(50u64)),
+        ],
+    };
 
-    for user in [
-        USER1, USER2, USER3, USER4, USER5, USER6, USER7, USER8, USER9,
-    ] {
-        deposit_lp_tokens_to_generator(&mut app, &generator_instance, user, &[(&lp_cny_eur, 10)]);
-    }
-
-    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 90);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        generator_instance.clone(),
+        &msg,
+        &[],
+    )
+    .unwrap();
 
-    // Get the first two stakers
-    let msg_cny_eur = QueryMsg::PoolStakers {
-        lp_token: lp_cny_eur.to_string(),
-        start_after: None,
-        limit: Some(2),
+    let msg = GeneratorExecuteMsg::MoveToProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: proxy_to_vkr_instance.to_string(),
     };
 
-    let reps: Vec<StakerResponse> = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-
-    // check count of users
-    assert_eq!(reps.len(), 2 as usize);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        generator_instance.clone(),
+        &msg,
+        &[],
+    )
+    .unwrap();
 
-    assert_eq!(
-        vec![
-            StakerResponse {
-                account: "user1".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user2".to_string(),
-                amount: Uint128::new(10)
-            },
-        ],
-        reps
-    );
+    // Mint tokens, so user can deposit
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 9);
+    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user1, 10);
 
-    // Get the next seven stakers
-    let msg_cny_eur = QueryMsg::PoolStakers {
-        lp_token: lp_cny_eur.to_string(),
-        start_after: Some("user2".to_string()),
-        limit: Some(7),
+    let msg = Cw20ExecuteMsg::Send {
+        contract: generator_instance.to_string(),
+        msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
+        amount: Uint128::new(10),
     };
 
-    let reps: Vec<StakerResponse> = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-
+    let err = app
+        .execute_contract(user1.clone(), lp_val_eur.clone(), &msg, &[])
+        .unwrap_err();
     assert_eq!(
-        vec![
-            StakerResponse {
-                account: "user3".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user4".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user5".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user6".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user7".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user8".to_string(),
-                amount: Uint128::new(10)
-            },
-            StakerResponse {
-                account: "user9".to_string(),
-                amount: Uint128::new(10)
-            },
-        ],
-        reps
-    );
-}
-
-#[test]
-fn update_tokens_blocked_list() {
-    let mut app = mock_app();
-
-    let owner = Addr::unchecked(OWNER);
-    let user1 = Addr::unchecked(USER1);
-    let token_code_id = store_token_code(&mut app);
-    let factory_code_id = store_factory_code(&mut app);
-    let pair_code_id = store_pair_code_id(&mut app);
-
-    let grid_token_instance =
-        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
-
-    let factory_instance =
-        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
-
-    let generator_instance = instantiate_generator(
-        &mut app,
-        &factory_instance,
-        &grid_token_instance,
-        Some(OWNER.to_string()),
+        err.root_cause().to_string(),
+        "Cannot Sub with 9 and 10".to_string()
     );
 
-    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
-    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
-    let ukr_token = instantiate_token(&mut app, token_code_id, "UKR", None);
-    let msi_token = instantiate_token(&mut app, token_code_id, "MSI", None);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 1);
 
-    let (_, lp_cny_eur) = create_pair(
+    deposit_lp_tokens_to_generator(
         &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: cny_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: eur_token.clone(),
-            },
-        ],
+        &generator_instance,
+        USER1,
+        &[(&lp_val_eur, 10), (&lp_eur_usd, 10)],
     );
 
-    let (_, lp_cny_ukr) = create_pair(
-        &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: cny_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: ukr_token.clone(),
-            },
-        ],
-    );
+    // With the proxy, the Generator contract doesn't have the deposited LP tokens
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
+    // The LP tokens are in the 3rd party contract now
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
 
-    let (_, lp_eur_msi) = create_pair(
-        &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: eur_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: msi_token.clone(),
-            },
-        ],
-    );
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
+    check_token_balance(&mut app, &lp_eur_usd, &vkr_staking_instance, 0);
 
-    register_lp_tokens_in_generator(
+    check_pending_rewards(
         &mut app,
         &generator_instance,
-        vec![
-            PoolWithProxy {
-                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_cny_ukr.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_eur_msi.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-        ],
+        &lp_val_eur,
+        USER1,
+        (0, Some(vec![0])),
     );
+    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER1, (0, None));
 
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: None,
-        remove: None,
+    // User can't withdraw if they didn't deposit previously
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(1_000000),
     };
-
     let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap_err();
     assert_eq!(
-        "Generic error: Need to provide add or remove parameters",
-        err.root_cause().to_string()
+        err.root_cause().to_string(),
+        "Insufficient balance in contract to process claim".to_string()
     );
 
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: Some(vec![native_asset_info("uusd".to_string())]),
-        remove: None,
+    // User can't emergency withdraw if they didn't deposit previously
+    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
+        lp_token: lp_val_eur.to_string(),
     };
 
     let err = app
-        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap_err();
-    assert_eq!("Unauthorized", err.root_cause().to_string());
-
-    let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap_err();
     assert_eq!(
-        ContractError::AssetCannotBeBlocked {
-            asset: "uusd".to_string()
-        },
-        err.downcast().unwrap()
+        err.root_cause().to_string(),
+        "gridiron::generator::UserInfo not found".to_string()
     );
 
-    // IBC tokens are allowed to be blocked
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: Some(vec![native_asset_info(
-            "ibc/0E9C2DD45862E4BE5D15B73C2A0999E2A1163BF347645422A2A283524148C14D".to_string(),
-        )]),
-        remove: None,
-    };
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
+    app.update_block(|bi| next_block(bi));
 
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: Some(vec![token_asset_info(cny_token.clone())]),
-        remove: None,
-    };
+    // Mint tokens on staking for distributing
+    mint_tokens(
+        &mut app,
+        owner.clone(),
+        &val_token,
+        &vkr_staking_instance,
+        200_000_000,
+    );
 
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+    // Check if proxy reward exists
+    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vkr_staking_instance,
+            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
+                staker: proxy_to_vkr_instance.to_string(),
+            },
+        )
         .unwrap();
-
-    // check if we cannot change the allocation point for blocked token
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_cny_ukr.to_string(), Uint128::from(40u32)),
-            (lp_eur_msi.to_string(), Uint128::from(140u32)),
-        ],
-    };
+    assert_eq!(Uint128::new(50_000_000), reps.pending_reward);
+    assert_eq!(Uint128::new(10), reps.bond_amount);
+
+    // check pending rewards before calling update rewards directly
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (5_000000, Some(vec![50_000_000])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER1,
+        (5_000000, None),
+    );
+
     let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(
+            user1.clone(),
+            proxy_to_vkr_instance.clone(),
+            &ProxyExecuteMsg::UpdateRewards {},
+            &[],
+        )
         .unwrap_err();
+    assert_eq!("Unauthorized", err.root_cause().to_string());
 
-    assert_eq!(
-        format!("Generic error: Token {} is blocked!", cny_token.to_string()),
-        err.root_cause().to_string()
+    // User 2
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 10);
+    mint_tokens(&mut app, pair_eur_usd.clone(), &lp_eur_usd, &user2, 10);
+
+    deposit_lp_tokens_to_generator(
+        &mut app,
+        &generator_instance,
+        USER2,
+        &[(&lp_val_eur, 10), (&lp_eur_usd, 10)],
+    );
+
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 20);
+
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 20);
+    check_token_balance(&mut app, &lp_eur_usd, &vkr_staking_instance, 0);
+
+    // 10 tokens distributed to depositors since the last deposit
+    // 5 distrubuted to proxy contract sicne the last deposit
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (5_000000, Some(vec![50_000000])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER1,
+        (5_000000, None),
+    );
+
+    // New deposits can't receive already calculated rewards
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (0, Some(vec![0])),
     );
+    check_pending_rewards(&mut app, &generator_instance, &lp_eur_usd, USER2, (0, None));
 
     // Change pool alloc points
     let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![(lp_eur_msi.to_string(), Uint128::from(140u32))],
+        pools: vec![
+            (lp_val_eur.to_string(), Uint128::new(60)),
+            (lp_eur_usd.to_string(), Uint128::new(40)),
+        ],
     };
 
     app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
-    };
+    app.update_block(|bi| next_block(bi));
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
+    // Check if proxy reward exists
+    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &vkr_staking_instance,
+            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
+                staker: proxy_to_vkr_instance.to_string(),
+            },
+        )
         .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    assert_eq!(Uint128::new(60_000_000), reps.pending_reward);
+    assert_eq!(Uint128::new(20), reps.bond_amount);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_ukr.to_string(),
-    };
+    // check pending rewards before calling update rewards directly
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (8_000000, Some(vec![80_000_000])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER1,
+        (7_000000, None),
+    );
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
+    // Check if proxy reward exists
+    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &vkr_staking_instance,
+            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
+                staker: proxy_to_vkr_instance.to_string(),
+            },
+        )
         .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    assert_eq!(Uint128::new(60000000), reps.pending_reward);
+    assert_eq!(Uint128::new(20), reps.bond_amount);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_eur_msi.to_string(),
-    };
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (3_000000, Some(vec![30000000])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER2,
+        (2_000000, None),
+    );
 
-    // Check if alloc point is equal to 140
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    // User1 emergency withdraws and loses already distributed rewards (5).
+    // Pending tokens (3) will be redistributed to other staked users.
+    let msg = GeneratorExecuteMsg::EmergencyWithdraw {
+        lp_token: lp_val_eur.to_string(),
+    };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::new(140), reps.alloc_point);
 
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: None,
-        remove: Some(vec![native_asset_info("eur".to_string())]),
-    };
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (0_000000, Some(vec![0])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER1,
+        (7_000000, None),
+    );
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER2,
+        (3_000000, Some(vec![60000000])),
+    );
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_eur_usd,
+        USER2,
+        (2_000000, None),
+    );
+
+    // Balance of the end contract should be decreased
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 10);
 
+    // User1 can't withdraw after emergency withdrawal
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(1_000000),
+    };
     let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap_err();
     assert_eq!(
-        "Generic error: Can't remove token. It is not found in the blocked list.",
-        err.root_cause().to_string()
+        err.root_cause().to_string(),
+        "Insufficient balance in contract to process claim".to_string(),
     );
 
-    let msg = ExecuteMsg::UpdateBlockedTokenslist {
-        add: None,
-        remove: Some(vec![token_asset_info(cny_token)]),
-    };
-
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50000000);
+    check_token_balance(&mut app, &val_token, &owner, 0);
 
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_cny_ukr.to_string(), Uint128::from(40u32)),
-            (lp_eur_msi.to_string(), Uint128::from(140u32)),
-        ],
+    // Check if there are orphaned proxy rewards
+    let msg = GeneratorQueryMsg::OrphanProxyRewards {
+        lp_token: lp_val_eur.to_string(),
     };
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+    let orphan_rewards: Vec<(AssetInfo, Uint128)> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg)
         .unwrap();
+    assert_eq!(orphan_rewards[0].1, Uint128::new(50000000));
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
+    // Owner sends orphaned proxy rewards
+    let msg = GeneratorExecuteMsg::SendOrphanProxyReward {
+        recipient: owner.to_string(),
+        lp_token: lp_val_eur.to_string(),
     };
 
-    // Check if alloc point is equal to 60
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::new(60), reps.alloc_point);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_ukr.to_string(),
-    };
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
+    check_token_balance(&mut app, &val_token, &owner, 50000000);
 
-    // Check if alloc point is equal to 40
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    // Owner can't send proxy rewards for distribution to users
+    let msg = GeneratorExecuteMsg::SendOrphanProxyReward {
+        recipient: owner.to_string(),
+        lp_token: lp_val_eur.to_string(),
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Insufficient amount of orphan rewards!"
+    );
+
+    // User2 withdraws and gets rewards
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(10),
+    };
+    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::new(40), reps.alloc_point);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_eur_msi.to_string(),
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_val_eur, &vkr_staking_instance, 0);
+    check_token_balance(&mut app, &lp_val_eur, &user1, 10);
+    check_token_balance(&mut app, &lp_val_eur, &user2, 10);
+
+    check_token_balance(&mut app, &grid_token_instance, &user1, 0);
+    check_token_balance(&mut app, &val_token, &user1, 0);
+    check_token_balance(&mut app, &grid_token_instance, &user2, 3_000000);
+    check_token_balance(&mut app, &val_token, &user2, 60000000);
+
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
+
+    // User1 withdraws and gets rewards
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_eur_usd.to_string(),
+        amount: Uint128::new(5),
     };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
 
-    // Check if alloc point is equal to 140
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 15);
+    check_token_balance(&mut app, &lp_eur_usd, &user1, 5);
+
+    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
+    check_token_balance(&mut app, &val_token, &user1, 0);
+
+    // User1 withdraws and gets rewards
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_eur_usd.to_string(),
+        amount: Uint128::new(5),
+    };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::new(140), reps.alloc_point);
+
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 10);
+    check_token_balance(&mut app, &lp_eur_usd, &user1, 10);
+    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
+    check_token_balance(&mut app, &val_token, &user1, 0);
+
+    // User2 withdraws and gets rewards
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_eur_usd.to_string(),
+        amount: Uint128::new(10),
+    };
+    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    check_token_balance(&mut app, &lp_eur_usd, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_eur_usd, &user1, 10);
+    check_token_balance(&mut app, &lp_eur_usd, &user2, 10);
+
+    check_token_balance(&mut app, &grid_token_instance, &user1, 7_000000);
+    check_token_balance(&mut app, &val_token, &user1, 0_000000);
+    check_token_balance(&mut app, &grid_token_instance, &user2, 5_000000);
+    check_token_balance(&mut app, &val_token, &user2, 60000000);
+
+    // Proxies val_token balance
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 0);
 }
 
 #[test]
-fn setup_pools() {
+fn proxy_rewards_claim_interval() {
     let mut app = mock_app();
 
     let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
     let pair_code_id = store_pair_code_id(&mut app);
 
     let grid_token_instance =
         instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
-
     let factory_instance =
         instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let generator_instance = instantiate_generator(
-        &mut app,
-        &factory_instance,
-        &grid_token_instance,
-        Some(OWNER.to_string()),
-    );
-
-    // add generator to factory
-    let msg = FactoryExecuteMsg::UpdateConfig {
-        token_code_id: None,
-        fee_address: None,
-        generator_address: Some(generator_instance.to_string()),
-        whitelist_code_id: None,
-        coin_registry_address: None,
-    };
-
-    app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
-        .unwrap();
-
-    let res: FactoryConfigResponse = app
-        .wrap()
-        .query_wasm_smart(&factory_instance.clone(), &FactoryQueryMsg::Config {})
-        .unwrap();
-
-    assert_eq!(res.generator_address, Some(generator_instance.clone()));
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
 
-    let (_, lp_cny_eur) = create_pair(
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
-            AssetInfo::NativeToken {
-                denom: "cny".to_string(),
+            AssetInfo::Token {
+                contract_addr: val_token.clone(),
             },
-            AssetInfo::NativeToken {
-                denom: "eur".to_string(),
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
             },
         ],
     );
 
-    let (_, lp_cny_uusd) = create_pair(
-        &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::NativeToken {
-                denom: "cny".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-        ],
-    );
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    let (_, lp_eur_uusd) = create_pair(
+    let vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
+
+    let proxy_code_id = store_proxy_code(&mut app);
+    let proxy_to_vkr_instance = instantiate_proxy(
         &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::NativeToken {
-                denom: "eur".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-        ],
+        proxy_code_id,
+        &generator_instance,
+        &pair_val_eur,
+        &lp_val_eur,
+        &vkr_staking_instance,
+        &val_token,
     );
 
     register_lp_tokens_in_generator(
         &mut app,
         &generator_instance,
-        vec![
-            PoolWithProxy {
-                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_cny_uusd.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_eur_uusd.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-        ],
+        vec![PoolWithProxy {
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
+            proxy: Some(proxy_to_vkr_instance.clone()),
+        }],
     );
 
-    // deregister pair and set the allocation point to zero for pool
-    app.execute_contract(
-        Addr::unchecked(OWNER),
-        factory_instance.clone(),
-        &FactoryExecuteMsg::Deregister {
-            asset_infos: vec![
-                AssetInfo::NativeToken {
-                    denom: "cny".to_string(),
-                },
-                AssetInfo::NativeToken {
-                    denom: "eur".to_string(),
-                },
-            ],
-        },
-        &[],
-    )
-    .unwrap();
-
-    // Check if the allocation point for lp_cny_eur is equal to zero
-    let res: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(
-            generator_instance.to_owned(),
-            &GeneratorQueryMsg::PoolInfo {
-                lp_token: lp_cny_eur.to_string(),
-            },
-        )
+    let msg = GeneratorExecuteMsg::UpdateConfig {
+        vesting_contract: None,
+        generator_controller: None,
+        guardian: None,
+        voting_escrow_delegation: None,
+        voting_escrow: None,
+        checkpoint_generator_limit: None,
+        proxy_rewards_claim_interval: Some(10),
+        staking_contract: None,
+        max_pool_share: None,
+        grid_pool_boost: None,
+        router: None,
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::zero(), res.alloc_point);
 
-    // Check pool length
-    let res: usize = app
+    let config: Config = app
         .wrap()
-        .query_wasm_smart(
-            generator_instance.to_owned(),
-            &GeneratorQueryMsg::ActivePoolLength {},
-        )
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
         .unwrap();
-    assert_eq!(3, res);
-
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
-            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
-        ],
-    };
+    assert_eq!(config.proxy_rewards_claim_interval, Some(10));
 
-    let err = app
-        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap_err();
-    assert_eq!(
-        "Generic error: The pair is not registered: cny-eur",
-        err.root_cause().to_string()
+    mint_tokens(
+        &mut app,
+        owner.clone(),
+        &val_token,
+        &vkr_staking_instance,
+        200_000_000,
     );
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 20);
 
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
-            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
-        ],
+    let query_last_claim_block = |app: &App| {
+        let res: PoolInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: lp_val_eur.to_string(),
+                },
+            )
+            .unwrap();
+        res.last_proxy_rewards_claim_block
     };
 
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
-    };
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+    // Nothing was pending on the proxy yet
+    assert_eq!(query_last_claim_block(&app), None);
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    app.update_block(|bi| next_block(bi));
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_uusd.to_string(),
-    };
+    // The pool was never claimed, so the first deposit with pending rewards claims them
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 10)]);
+    let first_claim_block = app.block_info().height;
+    assert_eq!(query_last_claim_block(&app), Some(first_claim_block));
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
 
-    // Check if alloc point is equal to 140
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Uint128::new(140), reps.alloc_point);
+    app.update_block(|bi| next_block(bi));
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_eur_uusd.to_string(),
-    };
+    // The claim is deferred: rewards stay on the 3rd party contract
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 10)]);
+    assert_eq!(query_last_claim_block(&app), Some(first_claim_block));
+    check_token_balance(&mut app, &val_token, &proxy_to_vkr_instance, 50_000_000);
 
-    // Check if alloc point is equal to 40
-    let reps: PoolInfoResponse = app
+    let reps: valkyrie::lp_staking::query_msgs::StakerInfoResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &vkr_staking_instance,
+            &valkyrie::lp_staking::query_msgs::QueryMsg::StakerInfo {
+                staker: proxy_to_vkr_instance.to_string(),
+            },
+        )
         .unwrap();
-    assert_eq!(Uint128::new(40), reps.alloc_point);
+    assert_eq!(Uint128::new(60_000_000), reps.pending_reward);
 
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_eur_uusd.to_string(), Uint128::from(80u32)),
-            (lp_cny_uusd.to_string(), Uint128::from(80u32)),
-        ],
-    };
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
+    app.next_blocks(10);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
+    // The interval has passed, so the withdrawal claims all deferred rewards.
+    // They are shared by the stake present at claim time.
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(10),
     };
-
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_uusd.to_string(),
-    };
+    assert_eq!(query_last_claim_block(&app), Some(app.block_info().height));
+    check_token_balance(&mut app, &val_token, &user1, 70_000_000);
 
-    // Check if alloc point is equal to 80
-    let reps: PoolInfoResponse = app
+    let res: PendingTokenResponse = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(
+            &generator_instance,
+            &GeneratorQueryMsg::PendingToken {
+                lp_token: lp_val_eur.to_string(),
+                user: USER2.to_string(),
+            },
+        )
         .unwrap();
-    assert_eq!(Uint128::new(80), reps.alloc_point);
+    assert_eq!(
+        res.pending_on_proxy.unwrap(),
+        vec![token_asset_info(val_token.clone()).with_balance(40_000_000u128)]
+    );
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_eur_uusd.to_string(),
+    // Zero disables batching
+    let msg = GeneratorExecuteMsg::UpdateConfig {
+        vesting_contract: None,
+        generator_controller: None,
+        guardian: None,
+        voting_escrow_delegation: None,
+        voting_escrow: None,
+        checkpoint_generator_limit: None,
+        proxy_rewards_claim_interval: Some(0),
+        staking_contract: None,
+        max_pool_share: None,
+        grid_pool_boost: None,
+        router: None,
     };
+    app.execute_contract(owner, generator_instance.clone(), &msg, &[])
+        .unwrap();
 
-    // Check if alloc point is equal to 80
-    let reps: PoolInfoResponse = app
+    let config: Config = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
         .unwrap();
-    assert_eq!(Uint128::new(80), reps.alloc_point);
+    assert_eq!(config.proxy_rewards_claim_interval, None);
 }
 
 #[test]
-fn deactivate_pools_by_pair_types() {
+fn deferred_proxy_rewards_accounting() {
     let mut app = mock_app();
 
     let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let user3 = Addr::unchecked(USER3);
+
     let token_code_id = store_token_code(&mut app);
     let factory_code_id = store_factory_code(&mut app);
     let pair_code_id = store_pair_code_id(&mut app);
-    let pair_stable_code_id = store_pair_stable_code_id(&mut app);
 
     let grid_token_instance =
         instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let factory_instance = instantiate_factory(
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
-        factory_code_id,
-        token_code_id,
-        pair_code_id,
-        Some(pair_stable_code_id),
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: val_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
     );
 
-    let generator_instance = instantiate_generator(
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    let proxy_code_id = emergency_proxy::store_code(&mut app);
+    let proxy_instance = app
+        .instantiate_contract(
+            proxy_code_id,
+            owner.clone(),
+            &ProxyInstantiateMsg {
+                generator_contract_addr: generator_instance.to_string(),
+                pair_addr: pair_val_eur.to_string(),
+                lp_token_addr: lp_val_eur.to_string(),
+                reward_contract_addr: owner.to_string(),
+                reward_token_addr: val_token.to_string(),
+            },
+            &[],
+            "Proxy",
+            None,
+        )
+        .unwrap();
+
+    register_lp_tokens_in_generator(
         &mut app,
-        &factory_instance,
-        &grid_token_instance,
-        Some(OWNER.to_string()),
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
+            proxy: Some(proxy_instance.clone()),
+        }],
     );
 
-    // add generator to factory
-    let msg = FactoryExecuteMsg::UpdateConfig {
-        token_code_id: None,
-        fee_address: None,
-        generator_address: Some(generator_instance.to_string()),
-        whitelist_code_id: None,
-        coin_registry_address: None,
+    let msg = GeneratorExecuteMsg::UpdateConfig {
+        vesting_contract: None,
+        generator_controller: None,
+        guardian: None,
+        voting_escrow_delegation: None,
+        voting_escrow: None,
+        checkpoint_generator_limit: None,
+        proxy_rewards_claim_interval: Some(10),
+        staking_contract: None,
+        max_pool_share: None,
+        grid_pool_boost: None,
+        router: None,
     };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
 
-    app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
+    mint_tokens(&mut app, owner.clone(), &val_token, &proxy_instance, 1000);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 10);
+    mint_tokens(&mut app, pair_val_eur, &lp_val_eur, &user3, 10);
+
+    let set_pending = |app: &mut App, amount: u128| {
+        app.execute_contract(
+            owner.clone(),
+            proxy_instance.clone(),
+            &emergency_proxy::ExecuteMsg::SetPending {
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+    };
+    let pending_on_proxy = |app: &App, user: &str| {
+        let res: PendingTokenResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PendingToken {
+                    lp_token: lp_val_eur.to_string(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap();
+        res.pending_on_proxy.unwrap()[0].amount.u128()
+    };
+
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+
+    // The first claim accounts 300 VAL to user1 alone
+    set_pending(&mut app, 300);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 10)]);
+    assert_eq!(pending_on_proxy(&app, USER1), 300);
+    assert_eq!(pending_on_proxy(&app, USER2), 0);
+
+    // 200 VAL accrue during the interval. The deposit doesn't claim them but they are still
+    // accounted to the stakers present when they accrued.
+    set_pending(&mut app, 200);
+    app.update_block(|bi| next_block(bi));
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER3, &[(&lp_val_eur, 10)]);
+    check_token_balance(&mut app, &val_token, &user1, 0);
+    assert_eq!(pending_on_proxy(&app, USER1), 400);
+    assert_eq!(pending_on_proxy(&app, USER2), 100);
+    assert_eq!(pending_on_proxy(&app, USER3), 0);
+
+    // 300 more VAL accrue. The 300 VAL claimed so far cover what user2 withdraws, so the
+    // withdrawal doesn't claim either.
+    set_pending(&mut app, 500);
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(10),
+    };
+    app.execute_contract(user2.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
+    check_token_balance(&mut app, &val_token, &user2, 200);
+    check_token_balance(&mut app, &val_token, &proxy_instance, 800);
+    assert_eq!(pending_on_proxy(&app, USER1), 500);
+    assert_eq!(pending_on_proxy(&app, USER3), 100);
 
-    let res: FactoryConfigResponse = app
+    // The claimed rewards left don't cover user1's withdrawal, so it claims before paying out
+    app.update_block(|bi| next_block(bi));
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &val_token, &user1, 500);
+    let pending: Uint128 = app
         .wrap()
-        .query_wasm_smart(&factory_instance.clone(), &FactoryQueryMsg::Config {})
+        .query_wasm_smart(&proxy_instance, &emergency_proxy::QueryMsg::PendingToken {})
         .unwrap();
+    assert_eq!(pending, Uint128::zero());
 
-    assert_eq!(res.generator_address, Some(generator_instance.clone()));
+    app.next_blocks(10);
 
-    let (_, lp_cny_uusd) = create_pair(
-        &mut app,
-        &factory_instance,
-        Some(PairType::Stable {}),
-        Some(
-            to_binary(&StablePoolParams {
-                amp: 100,
-                owner: None,
-            })
-            .unwrap(),
-        ),
-        vec![
-            AssetInfo::NativeToken {
-                denom: "cny".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-        ],
-    );
+    let msg = GeneratorExecuteMsg::ClaimRewards {
+        lp_tokens: vec![lp_val_eur.to_string()],
+    };
+    app.execute_contract(user3.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &val_token, &user3, 100);
+    check_token_balance(&mut app, &val_token, &proxy_instance, 200);
+}
 
-    let (_, lp_cny_eur) = create_pair(
-        &mut app,
-        &factory_instance,
-        None,
-        None,
-        vec![
-            AssetInfo::NativeToken {
-                denom: "cny".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "eur".to_string(),
-            },
-        ],
-    );
+#[test]
+fn multiple_reward_proxies() {
+    let mut app = mock_app();
 
-    let (_, lp_eur_uusd) = create_pair(
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
         &factory_instance,
         None,
         None,
         vec![
-            AssetInfo::NativeToken {
-                denom: "eur".to_string(),
+            AssetInfo::Token {
+                contract_addr: val_token.clone(),
             },
-            AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
             },
         ],
     );
 
-    register_lp_tokens_in_generator(
-        &mut app,
-        &generator_instance,
-        vec![
-            PoolWithProxy {
-                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_cny_uusd.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-            PoolWithProxy {
-                pool: (lp_eur_uusd.to_string(), Uint128::new(100)),
-                proxy: None,
-            },
-        ],
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    let vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
+    let second_vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
+
+    let proxy_code_id = store_proxy_code(&mut app);
+    let lp_proxy_instance = instantiate_proxy(
+        &mut app,
+        proxy_code_id,
+        &generator_instance,
+        &pair_val_eur,
+        &lp_val_eur,
+        &vkr_staking_instance,
+        &val_token,
+    );
+    let reward_proxy_instance = instantiate_proxy(
+        &mut app,
+        proxy_code_id,
+        &generator_instance,
+        &pair_val_eur,
+        &lp_val_eur,
+        &second_vkr_staking_instance,
+        &val_token,
     );
 
-    // try to deactivate pools for not blacklisted pair types
-    let msg = GeneratorExecuteMsg::DeactivateBlacklistedPools {
-        pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
+            proxy: Some(lp_proxy_instance.clone()),
+        }],
+    );
+
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+
+    // Only the owner can attach proxies
+    let msg = GeneratorExecuteMsg::AddRewardProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: reward_proxy_instance.to_string(),
     };
     let err = app
         .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
     assert_eq!(
-        format!(
-            "Generic error: Pair type ({}) is not blacklisted!",
-            PairType::Xyk {}
-        ),
-        err.root_cause().to_string()
+        ContractError::RewardProxyAlreadyUsed {},
+        err.downcast().unwrap()
     );
 
-    // Add stable pair type to blacklist
-    let msg = FactoryExecuteMsg::UpdatePairConfig {
-        config: PairConfig {
-            code_id: pair_stable_code_id,
-            pair_type: PairType::Stable {},
-            total_fee_bps: 100,
-            maker_fee_bps: 10,
-            is_disabled: false,
-            is_generator_disabled: true,
-        },
+    let query_pool_info = |app: &App| {
+        let res: PoolInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: lp_val_eur.to_string(),
+                },
+            )
+            .unwrap();
+        res
     };
 
-    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
-        .unwrap();
+    let pool_info = query_pool_info(&app);
+    assert_eq!(
+        pool_info.reward_proxies,
+        vec![
+            RewardProxyInfo {
+                proxy: lp_proxy_instance.clone(),
+                holds_lp_tokens: true,
+                reward_balance_before_update: Uint128::zero(),
+                accounted_pending_rewards: Uint128::zero(),
+            },
+            RewardProxyInfo {
+                proxy: reward_proxy_instance.clone(),
+                holds_lp_tokens: false,
+                reward_balance_before_update: Uint128::zero(),
+                accounted_pending_rewards: Uint128::zero(),
+            }
+        ]
+    );
 
-    // check if we add stable pair type to blacklist
-    let res: Vec<PairType> = app
+    let reps: RewardInfoResponse = app
         .wrap()
         .query_wasm_smart(
-            &factory_instance.clone(),
-            &FactoryQueryMsg::BlacklistedPairTypes {},
+            &generator_instance,
+            &QueryMsg::RewardInfo {
+                lp_token: lp_val_eur.to_string(),
+            },
         )
         .unwrap();
-    assert_eq!(res, vec![PairType::Stable {}]);
-
-    let msg = GeneratorExecuteMsg::DeactivateBlacklistedPools {
-        pair_types: vec![PairType::Stable {}],
-    };
-    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
+    assert_eq!(
+        reps.proxy_reward_tokens,
+        vec![val_token.clone(), val_token.clone()]
+    );
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_uusd.to_string(),
+    // The reward only proxy can't hold LP tokens of the pool
+    let msg = GeneratorExecuteMsg::MoveToProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: reward_proxy_instance.to_string(),
     };
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PoolAlreadyHasRewardProxyContract {},
+        err.downcast().unwrap()
+    );
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    app.update_block(|bi| next_block(bi));
 
-    // try to change alloc point for blacklisted pool by pair type
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
-            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
-        ],
+    let msg = GeneratorExecuteMsg::DeactivateRewardProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: reward_proxy_instance.to_string(),
     };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
 
     let err = app
         .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
         .unwrap_err();
     assert_eq!(
-        "Generic error: Pair type (stable) is blacklisted!",
-        err.root_cause().to_string()
+        ContractError::RewardProxyNotFound {},
+        err.downcast().unwrap()
     );
 
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_cny_eur.to_string(), Uint128::from(60u32)),
-            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
-        ],
+    // A detached proxy can't be attached again
+    let msg = GeneratorExecuteMsg::AddRewardProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: reward_proxy_instance.to_string(),
     };
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RewardProxyAlreadyUsed {},
+        err.downcast().unwrap()
+    );
 
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
+    let pool_info = query_pool_info(&app);
+    assert_eq!(pool_info.reward_proxies.len(), 1);
+    assert_eq!(pool_info.reward_proxies[0].proxy, lp_proxy_instance);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
+    // Deactivating the LP proxy returns LP tokens to the generator
+    let msg = GeneratorExecuteMsg::DeactivateRewardProxy {
+        lp_token: lp_val_eur.to_string(),
+        proxy: lp_proxy_instance.to_string(),
     };
-
-    // Check if alloc point is equal to 60
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    app.execute_contract(owner, generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::new(60), reps.alloc_point);
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_uusd.to_string(),
-    };
+    let pool_info = query_pool_info(&app);
+    assert!(pool_info.reward_proxies.is_empty());
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 10);
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_val_eur.to_string(),
+        amount: Uint128::new(10),
+    };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
         .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    check_token_balance(&mut app, &lp_val_eur, &user1, 10);
+    check_token_balance(&mut app, &lp_val_eur, &generator_instance, 0);
+}
 
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_eur_uusd.to_string(),
-    };
+#[test]
+fn reward_proxy_reentrancy() {
+    let mut app = mock_app();
 
-    // Check if alloc point is equal to 40
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Uint128::new(40), reps.alloc_point);
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
 
-    // remove stable pair type from blacklist
-    let msg = FactoryExecuteMsg::UpdatePairConfig {
-        config: PairConfig {
-            code_id: pair_stable_code_id,
-            pair_type: PairType::Stable {},
-            total_fee_bps: 100,
-            maker_fee_bps: 10,
-            is_disabled: false,
-            is_generator_disabled: false,
-        },
-    };
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
 
-    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
-        .unwrap();
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    // check if we remove stable pair type from blacklist
-    let res: Vec<PairType> = app
-        .wrap()
-        .query_wasm_smart(
-            &factory_instance.clone(),
-            &FactoryQueryMsg::BlacklistedPairTypes {},
-        )
-        .unwrap();
-    assert_eq!(res, vec![]);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
 
-    // Change pool alloc points
-    let msg = GeneratorExecuteMsg::SetupPools {
-        pools: vec![
-            (lp_eur_uusd.to_string(), Uint128::from(80u32)),
-            (lp_cny_uusd.to_string(), Uint128::from(80u32)),
+    let (pair_val_eur, lp_val_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: val_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
         ],
-    };
-    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
-        .unwrap();
-
-    let msg_cny_eur = QueryMsg::PoolInfo {
-        lp_token: lp_cny_eur.to_string(),
-    };
+    );
 
-    // Check if alloc point is equal to 0
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_eur)
-        .unwrap();
-    assert_eq!(Uint128::zero(), reps.alloc_point);
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
 
-    let msg_cny_uusd = QueryMsg::PoolInfo {
-        lp_token: lp_cny_uusd.to_string(),
-    };
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
+            proxy: None,
+        }],
+    );
 
-    // Check if alloc point is equal to 80
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(&generator_instance, &msg_cny_uusd)
+    let proxy_code_id = malicious_proxy::store_code(&mut app);
+    let proxy_instance = app
+        .instantiate_contract(
+            proxy_code_id,
+            owner.clone(),
+            &ProxyInstantiateMsg {
+                generator_contract_addr: generator_instance.to_string(),
+                pair_addr: pair_val_eur.to_string(),
+                lp_token_addr: lp_val_eur.to_string(),
+                reward_contract_addr: owner.to_string(),
+                reward_token_addr: val_token.to_string(),
+            },
+            &[],
+            "Malicious proxy",
+            None,
+        )
         .unwrap();
-    assert_eq!(Uint128::new(80), reps.alloc_point);
 
-    let msg_eur_uusd = QueryMsg::PoolInfo {
-        lp_token: lp_eur_uusd.to_string(),
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::AddRewardProxy {
+            lp_token: lp_val_eur.to_string(),
+            proxy: proxy_instance.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The proxy holds a position in the generator just like user1
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    mint_tokens(&mut app, pair_val_eur, &lp_val_eur, &proxy_instance, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+    deposit_lp_tokens_to_generator(
+        &mut app,
+        &generator_instance,
+        proxy_instance.as_str(),
+        &[(&lp_val_eur, 10)],
+    );
+
+    mint_tokens(&mut app, owner.clone(), &val_token, &proxy_instance, 2000);
+    let set_pending = |app: &mut App, amount: u128| {
+        app.execute_contract(
+            owner.clone(),
+            proxy_instance.clone(),
+            &malicious_proxy::ExecuteMsg::SetPending {
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+    };
+    let set_attack = |app: &mut App, attack: Option<Attack>| {
+        app.execute_contract(
+            owner.clone(),
+            proxy_instance.clone(),
+            &malicious_proxy::ExecuteMsg::SetAttack { attack },
+            &[],
+        )
+        .unwrap();
     };
 
-    // Check if alloc point is equal to 80
-    let reps: PoolInfoResponse = app
+    set_pending(&mut app, 1000);
+    set_attack(
+        &mut app,
+        Some(Attack::QueryPendingToken {
+            user: user1.to_string(),
+        }),
+    );
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (0, Some(vec![500])),
+    );
+
+    // Proxy rewards are claimed to the proxy before the generator accounts them. The pending
+    // rewards queried in between must not lose them.
+    app.execute_contract(
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::ClaimRewards {
+            lp_tokens: vec![lp_val_eur.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let observed: Option<PendingTokenResponse> = app
         .wrap()
-        .query_wasm_smart(&generator_instance, &msg_eur_uusd)
+        .query_wasm_smart(&proxy_instance, &malicious_proxy::QueryMsg::Observed {})
         .unwrap();
-    assert_eq!(Uint128::new(80), reps.alloc_point);
+    let pending_on_proxy = observed
+        .unwrap()
+        .pending_on_proxy
+        .unwrap()
+        .into_iter()
+        .map(|asset| asset.amount.u128())
+        .collect::<Vec<_>>();
+    assert_eq!(pending_on_proxy, vec![500]);
+    check_token_balance(&mut app, &val_token, &user1, 500);
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        proxy_instance.as_str(),
+        (0, Some(vec![500])),
+    );
+
+    // The proxy can't call the generator while its rewards are being claimed
+    set_pending(&mut app, 1000);
+    set_attack(
+        &mut app,
+        Some(Attack::Execute {
+            msg: GeneratorExecuteMsg::ClaimRewards {
+                lp_tokens: vec![lp_val_eur.to_string()],
+            },
+        }),
+    );
+
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::ClaimRewards {
+                lp_tokens: vec![lp_val_eur.to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::ReentrancyDetected {}.to_string()
+    );
+
+    // The failed transaction doesn't leave the generator locked
+    set_attack(&mut app, None);
+    app.execute_contract(
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::ClaimRewards {
+            lp_tokens: vec![lp_val_eur.to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &val_token, &user1, 1000);
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        proxy_instance.as_str(),
+        (0, Some(vec![1000])),
+    );
 }
 
 #[test]
-fn test_proxy_generator_incorrect_virtual_amount() {
-    let mut app = mock_app_helper();
-    let owner = Addr::unchecked("owner");
-    let helper_controller = ControllerHelper::init(&mut app, &owner);
+fn reward_proxy_emergency_mode() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
     let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+
     let token_code_id = store_token_code(&mut app);
-    // init cw20 tokens
-    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
     let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
     let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
-    // create two lp pairs, one with proxy another without proxy
-    let (pair_cny_eur, lp_without_proxy) = create_pair(
-        &mut app,
-        &helper_controller.factory,
-        None,
-        None,
-        vec![
-            AssetInfo::Token {
-                contract_addr: cny_token.clone(),
-            },
-            AssetInfo::Token {
-                contract_addr: eur_token.clone(),
-            },
-        ],
-    );
-    let (pair_val_eur, lp_with_proxy) = create_pair(
+
+    let (pair_val_eur, lp_val_eur) = create_pair(
         &mut app,
-        &helper_controller.factory,
+        &factory_instance,
         None,
         None,
         vec![
@@ -3633,81 +4436,2288 @@ fn test_proxy_generator_incorrect_virtual_amount() {
             },
         ],
     );
-    // register lp token to pool
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
     register_lp_tokens_in_generator(
         &mut app,
-        &helper_controller.generator,
+        &generator_instance,
         vec![PoolWithProxy {
-            pool: (lp_without_proxy.to_string(), Uint128::from(100u32)),
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
             proxy: None,
         }],
     );
-    // verify no proxy set
-    let reps: PoolInfoResponse = app
-        .wrap()
-        .query_wasm_smart(
-            &helper_controller.generator,
-            &QueryMsg::PoolInfo {
-                lp_token: lp_without_proxy.to_string(),
+
+    let proxy_code_id = emergency_proxy::store_code(&mut app);
+    let proxy_instance = app
+        .instantiate_contract(
+            proxy_code_id,
+            owner.clone(),
+            &ProxyInstantiateMsg {
+                generator_contract_addr: generator_instance.to_string(),
+                pair_addr: pair_val_eur.to_string(),
+                lp_token_addr: lp_val_eur.to_string(),
+                reward_contract_addr: owner.to_string(),
+                reward_token_addr: val_token.to_string(),
             },
+            &[],
+            "Emergency proxy",
+            None,
         )
         .unwrap();
-    assert_eq!(None, reps.reward_proxy);
-    // mint lp without proxy to user
-    mint_tokens(
+
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::MoveToProxy {
+            lp_token: lp_val_eur.to_string(),
+            proxy: proxy_instance.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    mint_tokens(&mut app, pair_val_eur, &lp_val_eur, &user2, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+    check_token_balance(&mut app, &lp_val_eur, &proxy_instance, 10);
+
+    mint_tokens(&mut app, owner.clone(), &val_token, &proxy_instance, 1000);
+    let set_pending = |app: &mut App, amount: u128| {
+        app.execute_contract(
+            owner.clone(),
+            proxy_instance.clone(),
+            &emergency_proxy::ExecuteMsg::SetPending {
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+    };
+
+    set_pending(&mut app, 100);
+    check_pending_rewards(
         &mut app,
-        pair_cny_eur.clone(),
-        &lp_without_proxy,
-        &user1,
-        10,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (0, Some(vec![100])),
     );
-    helper_controller
-        .escrow_helper
-        .mint_xgrid(&mut app, USER1, 200);
-    helper_controller
-        .escrow_helper
-        .create_lock(&mut app, USER1, WEEK * 3, 100f32)
+
+    let claim_msg = GeneratorExecuteMsg::ClaimRewards {
+        lp_tokens: vec![lp_val_eur.to_string()],
+    };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
         .unwrap();
-    // user deposits lp tokens
-    deposit_lp_tokens_to_generator(
+    check_token_balance(&mut app, &val_token, &user1, 100);
+
+    // The proxy flags itself. Its rewards are not claimed and don't accrue anymore
+    set_pending(&mut app, 50);
+    app.execute_contract(
+        owner.clone(),
+        proxy_instance.clone(),
+        &emergency_proxy::ExecuteMsg::SetEmergency {},
+        &[],
+    )
+    .unwrap();
+    check_pending_rewards(
         &mut app,
-        &helper_controller.generator,
+        &generator_instance,
+        &lp_val_eur,
         USER1,
-        &[(&lp_without_proxy, 10)],
+        (0, Some(vec![0])),
     );
-    // NOTE: user virtual amount should be calculated correctly when deposit
-    // first we try query the virtual amount and grab the value
-    // secondly we call CheckpointUserBoost to update the user's virtual amount
-    // to latest value
-    // third we query the virtual amount
-    // lastly we compare it, should be equal
-    // 1: query before checkpoint
-    let virtual_amount_before_checkpoint: Uint128 = app
-        .wrap()
-        .query_wasm_smart(
-            &helper_controller.generator,
-            &QueryMsg::UserVirtualAmount {
-                lp_token: lp_without_proxy.to_string(),
-                user: USER1.to_string(),
+
+    // The proxy rejects any reward related call so claiming succeeds only if it is skipped
+    app.execute_contract(user1.clone(), generator_instance.clone(), &claim_msg, &[])
+        .unwrap();
+    check_token_balance(&mut app, &val_token, &user1, 100);
+
+    // LP tokens can't be sent to the proxy anymore
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            lp_val_eur.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: generator_instance.to_string(),
+                msg: to_binary(&GeneratorHookMsg::Deposit {}).unwrap(),
+                amount: Uint128::new(10),
             },
+            &[],
         )
-        .unwrap();
-    // 2: perform checkpoint, user virtual amount will be updated
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::ProxyInEmergency {
+            proxy: proxy_instance.clone()
+        }
+        .to_string()
+    );
+
+    // Withdrawals bypass the reward claim on the proxy
     app.execute_contract(
-        Addr::unchecked(USER1),
-        helper_controller.generator.clone(),
-        &ExecuteMsg::CheckpointUserBoost {
-            generators: vec![lp_without_proxy.to_string()],
-            user: Some(USER1.to_string()),
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::Withdraw {
+            lp_token: lp_val_eur.to_string(),
+            amount: Uint128::new(5),
         },
         &[],
     )
     .unwrap();
-    // 3: query after checkpoint
-    let virtual_amount_after_checkpoint: Uint128 = app
-        .wrap()
-        .query_wasm_smart(
-            &helper_controller.generator,
+    check_token_balance(&mut app, &lp_val_eur, &user1, 5);
+
+    app.execute_contract(
+        user1.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::EmergencyWithdraw {
+            lp_token: lp_val_eur.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    check_token_balance(&mut app, &lp_val_eur, &user1, 10);
+    check_token_balance(&mut app, &lp_val_eur, &proxy_instance, 0);
+}
+
+#[test]
+fn move_to_proxy() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let cny_eur_token_code_id = store_token_code(&mut app);
+    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
+    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+    let vkr_token_instance = instantiate_token(&mut app, token_code_id, "VAL", None);
+
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::from(50u32)),
+            proxy: None,
+        }],
+    );
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if proxy reward is none
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert!(reps.reward_proxies.is_empty());
+
+    let vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &vkr_token_instance, &pair_cny_eur, &lp_cny_eur);
+
+    let proxy_code_id = store_proxy_code(&mut app);
+
+    let proxy_to_vkr_instance = instantiate_proxy(
+        &mut app,
+        proxy_code_id,
+        &generator_instance,
+        &pair_cny_eur,
+        &lp_cny_eur,
+        &vkr_staking_instance,
+        &vkr_token_instance,
+    );
+    assert_eq!(Addr::unchecked("contract12"), proxy_to_vkr_instance);
+
+    // Set the proxy for the pool
+    let msg = ExecuteMsg::MoveToProxy {
+        lp_token: lp_cny_eur.to_string(),
+        proxy: proxy_to_vkr_instance.to_string(),
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if proxy reward exists
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(
+        vec![RewardProxyInfo {
+            proxy: Addr::unchecked("contract12"),
+            holds_lp_tokens: true,
+            reward_balance_before_update: Uint128::zero(),
+            accounted_pending_rewards: Uint128::zero(),
+        }],
+        reps.reward_proxies
+    );
+
+    // Mint tokens, so user can deposit
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 10);
+
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_cny_eur, 10)]);
+
+    // With the proxy set up, the Generator contract doesn't have the deposited LP tokens
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
+    // The LP tokens are in the 3rd party contract now
+    check_token_balance(&mut app, &lp_cny_eur, &vkr_staking_instance, 10);
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_cny_eur,
+        USER1,
+        (0, Some(vec![0])),
+    );
+
+    app.update_block(|bi| next_block(bi));
+
+    // Check if proxy reward configs
+    let reps: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&proxy_to_vkr_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!("contract7".to_string(), reps.lp_token_addr);
+
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_cny_eur,
+        USER1,
+        (10_000000, Some(vec![50_000_000])),
+    );
+
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 0);
+    check_token_balance(&mut app, &lp_cny_eur, &vkr_staking_instance, 10);
+
+    // Check if the pool already has a reward proxy contract set
+    let msg = ExecuteMsg::MoveToProxy {
+        lp_token: lp_cny_eur.to_string(),
+        proxy: proxy_to_vkr_instance.to_string(),
+    };
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "The pool already has a reward proxy contract!",
+        err.root_cause().to_string()
+    )
+}
+
+#[test]
+fn pcl_dual_rewards() {
+    let app = Rc::new(RefCell::new(App::default()));
+
+    let gridiron = gridiron_address();
+    let user1 = Addr::unchecked(USER1);
+
+    let mut generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+
+    let grid = MockToken::try_from((&app, &generator.grid_token_info())).unwrap();
+    let val = MockTokenBuilder::new(&app, "VAL").instantiate();
+    let tkn = MockTokenBuilder::new(&app, "TKN").instantiate();
+
+    let pair = factory.instantiate_concentrated_pair(&[grid.asset_info(), val.asset_info()], None);
+    let lp_token = pair.lp_token();
+
+    generator.setup_pools(&[(lp_token.address.to_string(), Uint128::one())]);
+
+    let generator_address = generator.address.to_string();
+    let instantiate_msg = |reward_token: &Addr| ProxyInstantiateMsg {
+        generator_contract_addr: generator_address.clone(),
+        pair_addr: pair.address.to_string(),
+        lp_token_addr: lp_token.address.to_string(),
+        reward_contract_addr: gridiron.to_string(),
+        reward_token_addr: reward_token.to_string(),
+    };
+
+    // The LP proxy stakes the pool's LP tokens and pays VAL
+    let lp_proxy_code_id = emergency_proxy::store_code(&mut app.borrow_mut());
+    let lp_proxy = app
+        .borrow_mut()
+        .instantiate_contract(
+            lp_proxy_code_id,
+            gridiron.clone(),
+            &instantiate_msg(&val.address),
+            &[],
+            "LP proxy",
+            None,
+        )
+        .unwrap();
+    generator.move_to_proxy(&lp_token.address, &lp_proxy);
+
+    // The reward only proxy pays TKN on top
+    let reward_proxy_code_id = malicious_proxy::store_code(&mut app.borrow_mut());
+    let reward_proxy = app
+        .borrow_mut()
+        .instantiate_contract(
+            reward_proxy_code_id,
+            gridiron.clone(),
+            &instantiate_msg(&tkn.address),
+            &[],
+            "Reward proxy",
+            None,
+        )
+        .unwrap();
+    generator.add_reward_proxy(&lp_token.address, &reward_proxy);
+
+    pair.mint_allow_provide_and_stake(
+        &user1,
+        &[
+            grid.asset_info().with_balance(Uint128::new(1000_000000)),
+            val.asset_info().with_balance(Uint128::new(1000_000000)),
+        ],
+    );
+    let deposit = generator.query_deposit(&lp_token, &user1);
+    assert!(!deposit.is_zero());
+    assert_eq!(lp_token.balance(&lp_proxy), deposit);
+    assert_eq!(lp_token.balance(&generator.address), Uint128::zero());
+
+    // Reward amounts are multiples of the deposit so the per share indexes are exact
+    let val_rewards = deposit * Uint128::new(3);
+    let tkn_rewards = deposit * Uint128::new(2);
+    val.mint(&lp_proxy, val_rewards);
+    tkn.mint(&reward_proxy, tkn_rewards);
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            lp_proxy.clone(),
+            &emergency_proxy::ExecuteMsg::SetPending {
+                amount: val_rewards,
+            },
+            &[],
+        )
+        .unwrap();
+    app.borrow_mut()
+        .execute_contract(
+            gridiron,
+            reward_proxy.clone(),
+            &malicious_proxy::ExecuteMsg::SetPending {
+                amount: tkn_rewards,
+            },
+            &[],
+        )
+        .unwrap();
+
+    app.borrow_mut().next_block(1);
+
+    let pending = generator.pending_token(&lp_token.address, &user1);
+    assert!(!pending.pending.is_zero());
+    assert_eq!(
+        pending.pending_on_proxy,
+        Some(vec![
+            val.asset_info().with_balance(val_rewards),
+            tkn.asset_info().with_balance(tkn_rewards),
+        ])
+    );
+
+    app.borrow_mut()
+        .execute_contract(
+            user1.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::ClaimRewards {
+                lp_tokens: vec![lp_token.address.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(grid.balance(&user1), pending.pending);
+    assert_eq!(val.balance(&user1), val_rewards);
+    assert_eq!(tkn.balance(&user1), tkn_rewards);
+    assert_eq!(val.balance(&lp_proxy), Uint128::zero());
+    assert_eq!(tkn.balance(&reward_proxy), Uint128::zero());
+
+    let pending = generator.pending_token(&lp_token.address, &user1);
+    assert_eq!(pending.pending, Uint128::zero());
+    assert_eq!(
+        pending.pending_on_proxy,
+        Some(vec![
+            val.asset_info().with_balance(Uint128::zero()),
+            tkn.asset_info().with_balance(Uint128::zero()),
+        ])
+    );
+}
+
+#[test]
+fn query_all_stakers() {
+    let mut app = mock_app();
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let user3 = Addr::unchecked(USER3);
+    let user4 = Addr::unchecked(USER4);
+    let user5 = Addr::unchecked(USER5);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let cny_eur_token_code_id = store_token_code(&mut app);
+    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
+    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+            proxy: None,
+        }],
+    );
+
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user1, 10);
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user2, 10);
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user3, 10);
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user4, 10);
+    mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user5, 10);
+
+    let msg_cny_eur = QueryMsg::PoolStakers {
+        lp_token: lp_cny_eur.to_string(),
+        start_after: None,
+        limit: None,
+    };
+
+    // Check there are no stakers when there's no deposit
+    let reps: Vec<StakerResponse> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    let empty: Vec<StakerResponse> = vec![];
+    assert_eq!(empty, reps);
+
+    for user in [USER1, USER2, USER3, USER4, USER5] {
+        deposit_lp_tokens_to_generator(&mut app, &generator_instance, user, &[(&lp_cny_eur, 10)]);
+    }
+
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 50);
+
+    let reps: Vec<StakerResponse> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            StakerResponse {
+                account: "user1".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user2".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user3".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user4".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user5".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            }
+        ],
+        reps
+    );
+
+    let msg = GeneratorExecuteMsg::Withdraw {
+        lp_token: lp_cny_eur.to_string(),
+        amount: Uint128::new(10),
+    };
+
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 40);
+
+    // Check the amount of stakers after withdrawal
+    let reps: Vec<StakerResponse> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            StakerResponse {
+                account: "user2".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user3".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user4".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user5".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            }
+        ],
+        reps
+    );
+}
+
+#[test]
+fn query_pagination_stakers() {
+    let mut app = mock_app();
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let user3 = Addr::unchecked(USER3);
+    let user4 = Addr::unchecked(USER4);
+    let user5 = Addr::unchecked(USER5);
+    let user6 = Addr::unchecked(USER6);
+    let user7 = Addr::unchecked(USER7);
+    let user8 = Addr::unchecked(USER8);
+    let user9 = Addr::unchecked(USER9);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let cny_eur_token_code_id = store_token_code(&mut app);
+    let eur_token = instantiate_token(&mut app, cny_eur_token_code_id, "EUR", None);
+    let cny_token = instantiate_token(&mut app, cny_eur_token_code_id, "CNY", None);
+
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::from(100u32)),
+            proxy: None,
+        }],
+    );
+
+    for user in [
+        user1, user2, user3, user4, user5, user6, user7, user8, user9,
+    ] {
+        mint_tokens(&mut app, pair_cny_eur.clone(), &lp_cny_eur, &user, 10);
+    }
+
+    for user in [
+        USER1, USER2, USER3, USER4, USER5, USER6, USER7, USER8, USER9,
+    ] {
+        deposit_lp_tokens_to_generator(&mut app, &generator_instance, user, &[(&lp_cny_eur, 10)]);
+    }
+
+    check_token_balance(&mut app, &lp_cny_eur, &generator_instance, 90);
+
+    // Get the first two stakers
+    let msg_cny_eur = QueryMsg::PoolStakers {
+        lp_token: lp_cny_eur.to_string(),
+        start_after: None,
+        limit: Some(2),
+    };
+
+    let reps: Vec<StakerResponse> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+
+    // check count of users
+    assert_eq!(reps.len(), 2 as usize);
+
+    assert_eq!(
+        vec![
+            StakerResponse {
+                account: "user1".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user2".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+        ],
+        reps
+    );
+
+    // Get the next seven stakers
+    let msg_cny_eur = QueryMsg::PoolStakers {
+        lp_token: lp_cny_eur.to_string(),
+        start_after: Some("user2".to_string()),
+        limit: Some(7),
+    };
+
+    let reps: Vec<StakerResponse> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            StakerResponse {
+                account: "user3".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user4".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user5".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user6".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user7".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user8".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+            StakerResponse {
+                account: "user9".to_string(),
+                amount: Uint128::new(10),
+                virtual_amount: Uint128::new(4)
+            },
+        ],
+        reps
+    );
+}
+
+#[test]
+fn update_tokens_blocked_list() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let ukr_token = instantiate_token(&mut app, token_code_id, "UKR", None);
+    let msi_token = instantiate_token(&mut app, token_code_id, "MSI", None);
+
+    let (_, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+
+    let (_, lp_cny_ukr) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: ukr_token.clone(),
+            },
+        ],
+    );
+
+    let (_, lp_eur_msi) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: msi_token.clone(),
+            },
+        ],
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![
+            PoolWithProxy {
+                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_cny_ukr.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_eur_msi.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+        ],
+    );
+
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: None,
+        remove: None,
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: Need to provide add or remove parameters",
+        err.root_cause().to_string()
+    );
+
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: Some(vec![native_asset_info("uusd".to_string())]),
+        remove: None,
+    };
+
+    let err = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!("Unauthorized", err.root_cause().to_string());
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AssetCannotBeBlocked {
+            asset: "uusd".to_string()
+        },
+        err.downcast().unwrap()
+    );
+
+    // IBC tokens are allowed to be blocked
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: Some(vec![native_asset_info(
+            "ibc/0E9C2DD45862E4BE5D15B73C2A0999E2A1163BF347645422A2A283524148C14D".to_string(),
+        )]),
+        remove: None,
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: Some(vec![token_asset_info(cny_token.clone())]),
+        remove: None,
+    };
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    // check if we cannot change the allocation point for blocked token
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_cny_ukr.to_string(), Uint128::from(40u32)),
+            (lp_eur_msi.to_string(), Uint128::from(140u32)),
+        ],
+    };
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+
+    assert_eq!(
+        format!("Generic error: Token {} is blocked!", cny_token.to_string()),
+        err.root_cause().to_string()
+    );
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![(lp_eur_msi.to_string(), Uint128::from(140u32))],
+    };
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_ukr.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_eur_msi.to_string(),
+    };
+
+    // Check if alloc point is equal to 140
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(140), reps.alloc_point);
+
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: None,
+        remove: Some(vec![native_asset_info("eur".to_string())]),
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: Can't remove token. It is not found in the blocked list.",
+        err.root_cause().to_string()
+    );
+
+    let msg = ExecuteMsg::UpdateBlockedTokenslist {
+        add: None,
+        remove: Some(vec![token_asset_info(cny_token)]),
+    };
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_cny_ukr.to_string(), Uint128::from(40u32)),
+            (lp_eur_msi.to_string(), Uint128::from(140u32)),
+        ],
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 60
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(60), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_ukr.to_string(),
+    };
+
+    // Check if alloc point is equal to 40
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(40), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_eur_msi.to_string(),
+    };
+
+    // Check if alloc point is equal to 140
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(140), reps.alloc_point);
+}
+
+#[test]
+fn setup_pools() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    // add generator to factory
+    let msg = FactoryExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: Some(generator_instance.to_string()),
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        max_combined_fee_bps: None,
+    };
+
+    app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let res: FactoryConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&factory_instance.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+
+    assert_eq!(res.generator_address, Some(generator_instance.clone()));
+
+    let (_, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::NativeToken {
+                denom: "cny".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "eur".to_string(),
+            },
+        ],
+    );
+
+    let (_, lp_cny_uusd) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::NativeToken {
+                denom: "cny".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+    );
+
+    let (_, lp_eur_uusd) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::NativeToken {
+                denom: "eur".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![
+            PoolWithProxy {
+                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_cny_uusd.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_eur_uusd.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+        ],
+    );
+
+    // deregister pair and set the allocation point to zero for pool
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory_instance.clone(),
+        &FactoryExecuteMsg::Deregister {
+            asset_infos: vec![
+                AssetInfo::NativeToken {
+                    denom: "cny".to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "eur".to_string(),
+                },
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Check if the allocation point for lp_cny_eur is equal to zero
+    let res: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            generator_instance.to_owned(),
+            &GeneratorQueryMsg::PoolInfo {
+                lp_token: lp_cny_eur.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(Uint128::zero(), res.alloc_point);
+
+    // Check pool length
+    let res: usize = app
+        .wrap()
+        .query_wasm_smart(
+            generator_instance.to_owned(),
+            &GeneratorQueryMsg::ActivePoolLength {},
+        )
+        .unwrap();
+    assert_eq!(3, res);
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
+            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
+        ],
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: The pair is not registered: cny-eur",
+        err.root_cause().to_string()
+    );
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
+            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
+        ],
+    };
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 140
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(140), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_eur_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 40
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(40), reps.alloc_point);
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_eur_uusd.to_string(), Uint128::from(80u32)),
+            (lp_cny_uusd.to_string(), Uint128::from(80u32)),
+        ],
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 80
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(80), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_eur_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 80
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(80), reps.alloc_point);
+}
+
+#[test]
+fn max_pool_share_and_blocked_pools() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let lp_tokens: Vec<Addr> = [
+        ("cny", "eur"),
+        ("cny", "uusd"),
+        ("eur", "uusd"),
+        ("jpy", "uusd"),
+    ]
+    .into_iter()
+    .map(|(denom1, denom2)| {
+        create_pair(
+            &mut app,
+            &factory_instance,
+            None,
+            None,
+            vec![
+                AssetInfo::NativeToken {
+                    denom: denom1.to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: denom2.to_string(),
+                },
+            ],
+        )
+        .1
+    })
+    .collect();
+
+    let update_max_pool_share = |app: &mut App, max_pool_share: Decimal| {
+        app.execute_contract(
+            owner.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::UpdateConfig {
+                vesting_contract: None,
+                generator_controller: None,
+                guardian: None,
+                voting_escrow_delegation: None,
+                voting_escrow: None,
+                checkpoint_generator_limit: None,
+                proxy_rewards_claim_interval: None,
+                staking_contract: None,
+                max_pool_share: Some(max_pool_share),
+                grid_pool_boost: None,
+                router: None,
+            },
+            &[],
+        )
+    };
+    let setup_pools = |app: &mut App, alloc_points: &[u128]| {
+        app.execute_contract(
+            owner.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::SetupPools {
+                pools: lp_tokens
+                    .iter()
+                    .zip(alloc_points)
+                    .map(|(lp_token, alloc_point)| {
+                        (lp_token.to_string(), Uint128::new(*alloc_point))
+                    })
+                    .collect(),
+            },
+            &[],
+        )
+    };
+    let alloc_points = |app: &App| -> Vec<u128> {
+        lp_tokens
+            .iter()
+            .map(|lp_token| {
+                let res: PoolInfoResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        &generator_instance,
+                        &GeneratorQueryMsg::PoolInfo {
+                            lp_token: lp_token.to_string(),
+                        },
+                    )
+                    .unwrap();
+                res.alloc_point.u128()
+            })
+            .collect()
+    };
+
+    let err = update_max_pool_share(&mut app, Decimal::percent(101)).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidMaxPoolShare {}
+    );
+    update_max_pool_share(&mut app, Decimal::percent(30)).unwrap();
+
+    // The first pool exceeds the cap and its excess pushes the second pool over the cap as well
+    setup_pools(&mut app, &[600, 200, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![300, 300, 200, 200]);
+
+    // Pools within the cap are left untouched
+    setup_pools(&mut app, &[30, 30, 20, 20]).unwrap();
+    assert_eq!(alloc_points(&app), vec![30, 30, 20, 20]);
+
+    // Three pools can't get 100% of emissions with a 30% cap
+    let err = setup_pools(&mut app, &[600, 200, 100, 0]).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolShareCapTooLow {
+            max_pool_share: Decimal::percent(30),
+            pools: 3
+        }
+    );
+
+    // Only the owner can block pools
+    let block_msg = GeneratorExecuteMsg::UpdateBlockedPools {
+        add: Some(vec![lp_tokens[0].to_string()]),
+        remove: None,
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            generator_instance.clone(),
+            &block_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    app.execute_contract(owner.clone(), generator_instance.clone(), &block_msg, &[])
+        .unwrap();
+
+    let blocked_pools: Vec<Addr> = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::BlockedPools {})
+        .unwrap();
+    assert_eq!(blocked_pools, vec![lp_tokens[0].clone()]);
+    assert_eq!(alloc_points(&app), vec![0, 30, 20, 20]);
+
+    // Votes for the blocked pool are ignored
+    update_max_pool_share(&mut app, Decimal::percent(50)).unwrap();
+    setup_pools(&mut app, &[600, 200, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![0, 200, 100, 100]);
+
+    // Removing the cap
+    update_max_pool_share(&mut app, Decimal::zero()).unwrap();
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::UpdateBlockedPools {
+            add: None,
+            remove: Some(vec![lp_tokens[0].to_string()]),
+        },
+        &[],
+    )
+    .unwrap();
+    setup_pools(&mut app, &[600, 200, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![600, 200, 100, 100]);
+}
+
+#[test]
+fn grid_pool_boost() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let staking_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_staking::contract::execute,
+            gridiron_staking::contract::instantiate,
+            gridiron_staking::contract::query,
+        )
+        .with_reply_empty(gridiron_staking::contract::reply),
+    );
+    let staking_code_id = app.store_code(staking_contract);
+    let staking_instance = app
+        .instantiate_contract(
+            staking_code_id,
+            owner.clone(),
+            &StakingInstantiateMsg {
+                owner: owner.to_string(),
+                token_code_id,
+                deposit_token_addr: grid_token_instance.to_string(),
+                marketing: None,
+                revenue_asset: None,
+                redemption: None,
+            },
+            &[],
+            "xGRID",
+            None,
+        )
+        .unwrap();
+    let xgrid_token = app
+        .wrap()
+        .query_wasm_smart::<StakingConfigResponse>(&staking_instance, &StakingQueryMsg::Config {})
+        .unwrap()
+        .share_token_addr;
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let lp_tokens: Vec<Addr> = [
+        AssetInfo::Token {
+            contract_addr: grid_token_instance.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: xgrid_token,
+        },
+        AssetInfo::NativeToken {
+            denom: "eur".to_string(),
+        },
+    ]
+    .into_iter()
+    .map(|asset_info| {
+        create_pair(
+            &mut app,
+            &factory_instance,
+            None,
+            None,
+            vec![asset_info, uusd.clone()],
+        )
+        .1
+    })
+    .collect();
+
+    let update_config =
+        |app: &mut App, staking_contract: Option<String>, grid_pool_boost: Option<Decimal>| {
+            app.execute_contract(
+                owner.clone(),
+                generator_instance.clone(),
+                &GeneratorExecuteMsg::UpdateConfig {
+                    vesting_contract: None,
+                    generator_controller: None,
+                    guardian: None,
+                    voting_escrow_delegation: None,
+                    voting_escrow: None,
+                    checkpoint_generator_limit: None,
+                    proxy_rewards_claim_interval: None,
+                    staking_contract,
+                    max_pool_share: None,
+                    grid_pool_boost,
+                    router: None,
+                },
+                &[],
+            )
+        };
+    let setup_pools = |app: &mut App, alloc_points: &[u128]| {
+        app.execute_contract(
+            owner.clone(),
+            generator_instance.clone(),
+            &GeneratorExecuteMsg::SetupPools {
+                pools: lp_tokens
+                    .iter()
+                    .zip(alloc_points)
+                    .map(|(lp_token, alloc_point)| {
+                        (lp_token.to_string(), Uint128::new(*alloc_point))
+                    })
+                    .collect(),
+            },
+            &[],
+        )
+    };
+    let alloc_points = |app: &App| -> Vec<u128> {
+        lp_tokens
+            .iter()
+            .map(|lp_token| {
+                let res: PoolInfoResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        &generator_instance,
+                        &GeneratorQueryMsg::PoolInfo {
+                            lp_token: lp_token.to_string(),
+                        },
+                    )
+                    .unwrap();
+                res.alloc_point.u128()
+            })
+            .collect()
+    };
+
+    let err = update_config(&mut app, None, Some(Decimal::percent(99))).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidGridPoolBoost {}
+    );
+    update_config(&mut app, None, Some(Decimal::percent(150))).unwrap();
+
+    // xGRID is unknown until the staking contract is set
+    setup_pools(&mut app, &[100, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![150, 100, 100]);
+    let cfg: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(cfg.total_alloc_point.u128(), 350);
+
+    update_config(&mut app, Some(staking_instance.to_string()), None).unwrap();
+    setup_pools(&mut app, &[100, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![150, 150, 100]);
+
+    // Removing the boost
+    update_config(&mut app, None, Some(Decimal::one())).unwrap();
+    setup_pools(&mut app, &[100, 100, 100]).unwrap();
+    assert_eq!(alloc_points(&app), vec![100, 100, 100]);
+}
+
+#[test]
+fn pool_tvl() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let (pair_cny_eur, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            token_asset_info(cny_token.clone()),
+            token_asset_info(eur_token.clone()),
+        ],
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::from(100u32)),
+            proxy: None,
+        }],
+    );
+
+    let query_tvl = |app: &App| -> PoolTvlResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolTvl {
+                    lp_token: lp_cny_eur.to_string(),
+                },
+            )
+            .unwrap()
+    };
+
+    // Nothing is staked in an empty pool
+    let res = query_tvl(&app);
+    assert_eq!(res.pair, pair_cny_eur);
+    assert_eq!(res.staked_lp, Uint128::zero());
+    assert_eq!(res.total_lp, Uint128::zero());
+    assert_eq!(res.staked_share, Decimal::zero());
+    assert_eq!(
+        res.staked_assets,
+        vec![
+            token_asset_info(cny_token.clone()).with_balance(0u128),
+            token_asset_info(eur_token.clone()).with_balance(0u128),
+        ]
+    );
+
+    let assets = vec![
+        token_asset_info(cny_token.clone()).with_balance(100_000u128),
+        token_asset_info(eur_token.clone()).with_balance(400_000u128),
+    ];
+    for (token, amount) in [(&cny_token, 100_000u128), (&eur_token, 400_000u128)] {
+        mint_tokens(&mut app, owner.clone(), token, &user1, amount);
+        app.execute_contract(
+            user1.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair_cny_eur.to_string(),
+                amount: Uint128::new(amount),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+    app.execute_contract(
+        user1.clone(),
+        pair_cny_eur.clone(),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: assets.clone(),
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // A quarter of the LP supply is staked in the generator
+    deposit_lp_tokens_to_generator(
+        &mut app,
+        &generator_instance,
+        USER1,
+        &[(&lp_cny_eur, 50_000)],
+    );
+
+    let res = query_tvl(&app);
+    assert_eq!(res.staked_lp.u128(), 50_000);
+    assert_eq!(res.total_lp.u128(), 200_000);
+    assert_eq!(res.staked_share, Decimal::percent(25));
+    assert_eq!(
+        res.staked_assets,
+        vec![
+            token_asset_info(cny_token.clone()).with_balance(25_000u128),
+            token_asset_info(eur_token.clone()).with_balance(100_000u128),
+        ]
+    );
+    assert_eq!(res.total_assets, assets);
+
+    // Unregistered LP tokens are rejected
+    app.wrap()
+        .query_wasm_smart::<PoolTvlResponse>(
+            &generator_instance,
+            &GeneratorQueryMsg::PoolTvl {
+                lp_token: grid_token_instance.to_string(),
+            },
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn user_emission_rate() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+
+    let (pair_val_eur, lp_val_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            token_asset_info(val_token.clone()),
+            token_asset_info(eur_token.clone()),
+        ],
+    );
+
+    let generator_instance =
+        instantiate_generator(&mut app, &factory_instance, &grid_token_instance, None);
+
+    let vkr_staking_instance =
+        instantiate_valkyrie_protocol(&mut app, &val_token, &pair_val_eur, &lp_val_eur);
+
+    let proxy_code_id = store_proxy_code(&mut app);
+    let proxy_to_vkr_instance = instantiate_proxy(
+        &mut app,
+        proxy_code_id,
+        &generator_instance,
+        &pair_val_eur,
+        &lp_val_eur,
+        &vkr_staking_instance,
+        &val_token,
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_val_eur.to_string(), Uint128::new(100)),
+            proxy: Some(proxy_to_vkr_instance),
+        }],
+    );
+
+    mint_tokens(
+        &mut app,
+        owner.clone(),
+        &val_token,
+        &vkr_staking_instance,
+        200_000_000,
+    );
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user1, 10);
+    mint_tokens(&mut app, pair_val_eur.clone(), &lp_val_eur, &user2, 30);
+
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(&lp_val_eur, 10)]);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER2, &[(&lp_val_eur, 30)]);
+
+    let query_rate = |app: &App, user: &str| -> UserEmissionRateResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::UserEmissionRate {
+                    lp_token: lp_val_eur.to_string(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    };
+
+    // Nothing accrued on the proxy yet
+    let res = query_rate(&app, USER2);
+    assert_eq!(res.share, Decimal::percent(75));
+    assert_eq!(res.grid_per_block.u128(), 7_500000);
+    assert_eq!(
+        res.proxy_rewards_per_block,
+        vec![token_asset_info(val_token.clone()).with_balance(0u128)]
+    );
+
+    app.update_block(|bi| next_block(bi));
+
+    // 50 VAL accrued on the proxy over the last block
+    let res = query_rate(&app, USER2);
+    assert_eq!(res.grid_per_block.u128(), 7_500000);
+    assert_eq!(
+        res.proxy_rewards_per_block,
+        vec![token_asset_info(val_token.clone()).with_balance(37_500000u128)]
+    );
+
+    let res = query_rate(&app, USER1);
+    assert_eq!(res.share, Decimal::percent(25));
+    assert_eq!(res.grid_per_block.u128(), 2_500000);
+    assert_eq!(
+        res.proxy_rewards_per_block,
+        vec![token_asset_info(val_token.clone()).with_balance(12_500000u128)]
+    );
+
+    // The rates match the rewards accrued over the block
+    check_pending_rewards(
+        &mut app,
+        &generator_instance,
+        &lp_val_eur,
+        USER1,
+        (2_500000, Some(vec![12_500000])),
+    );
+
+    // Users without a position receive nothing
+    let res = query_rate(&app, USER3);
+    assert_eq!(res.share, Decimal::zero());
+    assert_eq!(res.grid_per_block, Uint128::zero());
+    assert_eq!(
+        res.proxy_rewards_per_block,
+        vec![token_asset_info(val_token).with_balance(0u128)]
+    );
+}
+
+#[test]
+fn deactivate_pools_by_pair_types() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+    let pair_stable_code_id = store_pair_stable_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance = instantiate_factory(
+        &mut app,
+        factory_code_id,
+        token_code_id,
+        pair_code_id,
+        Some(pair_stable_code_id),
+    );
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    // add generator to factory
+    let msg = FactoryExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: Some(generator_instance.to_string()),
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        max_combined_fee_bps: None,
+    };
+
+    app.execute_contract(Addr::unchecked(OWNER), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let res: FactoryConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&factory_instance.clone(), &FactoryQueryMsg::Config {})
+        .unwrap();
+
+    assert_eq!(res.generator_address, Some(generator_instance.clone()));
+
+    let (_, lp_cny_uusd) = create_pair(
+        &mut app,
+        &factory_instance,
+        Some(PairType::Stable {}),
+        Some(
+            to_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+            })
+            .unwrap(),
+        ),
+        vec![
+            AssetInfo::NativeToken {
+                denom: "cny".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+    );
+
+    let (_, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::NativeToken {
+                denom: "cny".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "eur".to_string(),
+            },
+        ],
+    );
+
+    let (_, lp_eur_uusd) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            AssetInfo::NativeToken {
+                denom: "eur".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![
+            PoolWithProxy {
+                pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_cny_uusd.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+            PoolWithProxy {
+                pool: (lp_eur_uusd.to_string(), Uint128::new(100)),
+                proxy: None,
+            },
+        ],
+    );
+
+    // try to deactivate pools for not blacklisted pair types
+    let msg = GeneratorExecuteMsg::DeactivateBlacklistedPools {
+        pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+    };
+    let err = app
+        .execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        format!(
+            "Generic error: Pair type ({}) is not blacklisted!",
+            PairType::Xyk {}
+        ),
+        err.root_cause().to_string()
+    );
+
+    // Add stable pair type to blacklist
+    let msg = FactoryExecuteMsg::UpdatePairConfig {
+        config: PairConfig {
+            code_id: pair_stable_code_id,
+            pair_type: PairType::Stable {},
+            total_fee_bps: 100,
+            maker_fee_bps: 10,
+            is_disabled: false,
+            is_generator_disabled: true,
+        },
+    };
+
+    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    // check if we add stable pair type to blacklist
+    let res: Vec<PairType> = app
+        .wrap()
+        .query_wasm_smart(
+            &factory_instance.clone(),
+            &FactoryQueryMsg::BlacklistedPairTypes {},
+        )
+        .unwrap();
+    assert_eq!(res, vec![PairType::Stable {}]);
+
+    let msg = GeneratorExecuteMsg::DeactivateBlacklistedPools {
+        pair_types: vec![PairType::Stable {}],
+    };
+    app.execute_contract(user1.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    // try to change alloc point for blacklisted pool by pair type
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
+            (lp_cny_uusd.to_string(), Uint128::from(140u32)),
+        ],
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: Pair type (stable) is blacklisted!",
+        err.root_cause().to_string()
+    );
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_cny_eur.to_string(), Uint128::from(60u32)),
+            (lp_eur_uusd.to_string(), Uint128::from(40u32)),
+        ],
+    };
+
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 60
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(60), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_eur_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 40
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::new(40), reps.alloc_point);
+
+    // remove stable pair type from blacklist
+    let msg = FactoryExecuteMsg::UpdatePairConfig {
+        config: PairConfig {
+            code_id: pair_stable_code_id,
+            pair_type: PairType::Stable {},
+            total_fee_bps: 100,
+            maker_fee_bps: 10,
+            is_disabled: false,
+            is_generator_disabled: false,
+        },
+    };
+
+    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    // check if we remove stable pair type from blacklist
+    let res: Vec<PairType> = app
+        .wrap()
+        .query_wasm_smart(
+            &factory_instance.clone(),
+            &FactoryQueryMsg::BlacklistedPairTypes {},
+        )
+        .unwrap();
+    assert_eq!(res, vec![]);
+
+    // Change pool alloc points
+    let msg = GeneratorExecuteMsg::SetupPools {
+        pools: vec![
+            (lp_eur_uusd.to_string(), Uint128::from(80u32)),
+            (lp_cny_uusd.to_string(), Uint128::from(80u32)),
+        ],
+    };
+    app.execute_contract(owner.clone(), generator_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let msg_cny_eur = QueryMsg::PoolInfo {
+        lp_token: lp_cny_eur.to_string(),
+    };
+
+    // Check if alloc point is equal to 0
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_eur)
+        .unwrap();
+    assert_eq!(Uint128::zero(), reps.alloc_point);
+
+    let msg_cny_uusd = QueryMsg::PoolInfo {
+        lp_token: lp_cny_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 80
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_cny_uusd)
+        .unwrap();
+    assert_eq!(Uint128::new(80), reps.alloc_point);
+
+    let msg_eur_uusd = QueryMsg::PoolInfo {
+        lp_token: lp_eur_uusd.to_string(),
+    };
+
+    // Check if alloc point is equal to 80
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &msg_eur_uusd)
+        .unwrap();
+    assert_eq!(Uint128::new(80), reps.alloc_point);
+}
+
+#[test]
+fn test_proxy_generator_incorrect_virtual_amount() {
+    let mut app = mock_app_helper();
+    let owner = Addr::unchecked("owner");
+    let helper_controller = ControllerHelper::init(&mut app, &owner);
+    let user1 = Addr::unchecked(USER1);
+    let token_code_id = store_token_code(&mut app);
+    // init cw20 tokens
+    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let val_token = instantiate_token(&mut app, token_code_id, "VAL", None);
+    // create two lp pairs, one with proxy another without proxy
+    let (pair_cny_eur, lp_without_proxy) = create_pair(
+        &mut app,
+        &helper_controller.factory,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: cny_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+    let (pair_val_eur, lp_with_proxy) = create_pair(
+        &mut app,
+        &helper_controller.factory,
+        None,
+        None,
+        vec![
+            AssetInfo::Token {
+                contract_addr: val_token.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: eur_token.clone(),
+            },
+        ],
+    );
+    // register lp token to pool
+    register_lp_tokens_in_generator(
+        &mut app,
+        &helper_controller.generator,
+        vec![PoolWithProxy {
+            pool: (lp_without_proxy.to_string(), Uint128::from(100u32)),
+            proxy: None,
+        }],
+    );
+    // verify no proxy set
+    let reps: PoolInfoResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper_controller.generator,
+            &QueryMsg::PoolInfo {
+                lp_token: lp_without_proxy.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(reps.reward_proxies.is_empty());
+    // mint lp without proxy to user
+    mint_tokens(
+        &mut app,
+        pair_cny_eur.clone(),
+        &lp_without_proxy,
+        &user1,
+        10,
+    );
+    helper_controller
+        .escrow_helper
+        .mint_xgrid(&mut app, USER1, 200);
+    helper_controller
+        .escrow_helper
+        .create_lock(&mut app, USER1, WEEK * 3, 100f32)
+        .unwrap();
+    // user deposits lp tokens
+    deposit_lp_tokens_to_generator(
+        &mut app,
+        &helper_controller.generator,
+        USER1,
+        &[(&lp_without_proxy, 10)],
+    );
+    // NOTE: user virtual amount should be calculated correctly when deposit
+    // first we try query the virtual amount and grab the value
+    // secondly we call CheckpointUserBoost to update the user's virtual amount
+    // to latest value
+    // third we query the virtual amount
+    // lastly we compare it, should be equal
+    // 1: query before checkpoint
+    let virtual_amount_before_checkpoint: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &helper_controller.generator,
+            &QueryMsg::UserVirtualAmount {
+                lp_token: lp_without_proxy.to_string(),
+                user: USER1.to_string(),
+            },
+        )
+        .unwrap();
+    // 2: perform checkpoint, user virtual amount will be updated
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        helper_controller.generator.clone(),
+        &ExecuteMsg::CheckpointUserBoost {
+            generators: vec![lp_without_proxy.to_string()],
+            user: Some(USER1.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    // 3: query after checkpoint
+    let virtual_amount_after_checkpoint: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &helper_controller.generator,
             &QueryMsg::UserVirtualAmount {
                 lp_token: lp_without_proxy.to_string(),
                 user: USER1.to_string(),
@@ -3754,7 +6764,7 @@ fn test_proxy_generator_incorrect_virtual_amount() {
             },
         )
         .unwrap();
-    assert_eq!(Some(proxy_instance), reps.reward_proxy);
+    assert_eq!(proxy_instance, reps.reward_proxies[0].proxy);
     // mint lp tokens to user
     mint_tokens(&mut app, pair_val_eur.clone(), &lp_with_proxy, &user1, 10);
     // user deposits lp tokens
@@ -3775,39 +6785,251 @@ fn test_proxy_generator_incorrect_virtual_amount() {
                 user: USER1.to_string(),
             },
         )
-        .unwrap();
-    // 2: perform checkpoint, user virtual amount will be updated
+        .unwrap();
+    // 2: perform checkpoint, user virtual amount will be updated
+    app.execute_contract(
+        Addr::unchecked(USER1),
+        helper_controller.generator.clone(),
+        &ExecuteMsg::CheckpointUserBoost {
+            generators: vec![lp_with_proxy.to_string()],
+            user: Some(USER1.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    // 3: query after checkpoint
+    let virtual_amount_after_checkpoint: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &helper_controller.generator,
+            &QueryMsg::UserVirtualAmount {
+                lp_token: lp_with_proxy.to_string(),
+                user: USER1.to_string(),
+            },
+        )
+        .unwrap();
+    /*
+        4: compare: error here
+        panicked at 'assertion failed: `(left == right)`
+            left: `Uint128(4)`,
+            right: `Uint128(10)`
+    */
+    assert_eq!(
+        virtual_amount_before_checkpoint,
+        virtual_amount_after_checkpoint
+    );
+}
+
+#[test]
+fn rotate_generator_in_factory() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let msg = FactoryExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: Some(generator_instance.to_string()),
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        max_combined_fee_bps: None,
+    };
+    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let (_, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            native_asset_info("cny".to_string()),
+            native_asset_info("eur".to_string()),
+        ],
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+            proxy: None,
+        }],
+    );
+
+    let new_generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    // Once set, the generator can't be replaced through UpdateConfig
+    let msg = FactoryExecuteMsg::UpdateConfig {
+        token_code_id: None,
+        fee_address: None,
+        generator_address: Some(new_generator_instance.to_string()),
+        whitelist_code_id: None,
+        coin_registry_address: None,
+        max_combined_fee_bps: None,
+    };
+    let err = app
+        .execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        "The generator address can only be changed through a generator proposal",
+        err.root_cause().to_string()
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(USER1),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::ProposeNewGenerator {
+                generator: new_generator_instance.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!("Unauthorized", err.root_cause().to_string());
+
+    // The proposed address has to be a contract
+    app.execute_contract(
+        owner.clone(),
+        factory_instance.clone(),
+        &FactoryExecuteMsg::ProposeNewGenerator {
+            generator: USER1.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(GENERATOR_ROTATION_DELAY));
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::ApplyNewGenerator {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("Generator {USER1} doesn't respond to a version query"),
+        err.root_cause().to_string()
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        factory_instance.clone(),
+        &FactoryExecuteMsg::DropGeneratorProposal {},
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::ApplyNewGenerator {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!("Generator proposal not found", err.root_cause().to_string());
+
     app.execute_contract(
-        Addr::unchecked(USER1),
-        helper_controller.generator.clone(),
-        &ExecuteMsg::CheckpointUserBoost {
-            generators: vec![lp_with_proxy.to_string()],
-            user: Some(USER1.to_string()),
+        owner.clone(),
+        factory_instance.clone(),
+        &FactoryExecuteMsg::ProposeNewGenerator {
+            generator: new_generator_instance.to_string(),
         },
         &[],
     )
     .unwrap();
-    // 3: query after checkpoint
-    let virtual_amount_after_checkpoint: Uint128 = app
+
+    let proposal: Option<GeneratorProposal> = app
         .wrap()
-        .query_wasm_smart(
-            &helper_controller.generator,
-            &QueryMsg::UserVirtualAmount {
-                lp_token: lp_with_proxy.to_string(),
-                user: USER1.to_string(),
-            },
-        )
+        .query_wasm_smart(&factory_instance, &FactoryQueryMsg::GeneratorProposal {})
         .unwrap();
-    /*
-        4: compare: error here
-        panicked at 'assertion failed: `(left == right)`
-            left: `Uint128(4)`,
-            right: `Uint128(10)`
-    */
+    let apply_after = app.block_info().time.seconds() + GENERATOR_ROTATION_DELAY;
     assert_eq!(
-        virtual_amount_before_checkpoint,
-        virtual_amount_after_checkpoint
+        proposal,
+        Some(GeneratorProposal {
+            generator: new_generator_instance.clone(),
+            apply_after,
+        })
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::ApplyNewGenerator {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("Generator proposal can't be applied before {apply_after}"),
+        err.root_cause().to_string()
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(GENERATOR_ROTATION_DELAY));
+
+    // The active pool isn't registered in the new generator yet
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &FactoryExecuteMsg::ApplyNewGenerator {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("Pool {lp_cny_eur} is active in the current generator but not in the new one"),
+        err.root_cause().to_string()
+    );
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &new_generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::new(100)),
+            proxy: None,
+        }],
     );
+
+    app.execute_contract(
+        owner.clone(),
+        factory_instance.clone(),
+        &FactoryExecuteMsg::ApplyNewGenerator {},
+        &[],
+    )
+    .unwrap();
+
+    let res: FactoryConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&factory_instance, &FactoryQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.generator_address, Some(new_generator_instance));
+
+    let proposal: Option<GeneratorProposal> = app
+        .wrap()
+        .query_wasm_smart(&factory_instance, &FactoryQueryMsg::GeneratorProposal {})
+        .unwrap();
+    assert_eq!(proposal, None);
 }
 
 fn store_token_code(app: &mut App) -> u64 {
@@ -3987,6 +7209,7 @@ fn instantiate_generator(
     let init_msg = VestingInstantiateMsg {
         owner: owner.to_string(),
         vesting_token: token_asset_info(grid_token_instance.clone()),
+        generator: None,
     };
 
     let vesting_instance = app
@@ -4261,288 +7484,849 @@ fn check_pending_rewards(
         .query_wasm_smart(generator_instance.to_owned(), &msg)
         .unwrap();
 
-    assert_eq!(res.pending.u128(), expected);
-    let pending_on_proxy = res.pending_on_proxy.map(|rewards| {
-        rewards
-            .into_iter()
-            .map(|Asset { amount, .. }| amount.u128())
-            .collect::<Vec<_>>()
-    });
-    assert_eq!(pending_on_proxy, expected_on_proxy)
-}
-
-fn create_pair(
-    app: &mut App,
-    factory: &Addr,
-    pair_type: Option<PairType>,
-    init_param: Option<Binary>,
-    assets: Vec<AssetInfo>,
-) -> (Addr, Addr) {
-    app.execute_contract(
-        Addr::unchecked(OWNER),
-        factory.clone(),
-        &FactoryExecuteMsg::CreatePair {
-            pair_type: pair_type.unwrap_or_else(|| PairType::Xyk {}),
-            asset_infos: assets.clone(),
-            init_params: init_param,
-        },
-        &[],
+    assert_eq!(res.pending.u128(), expected);
+    let pending_on_proxy = res.pending_on_proxy.map(|rewards| {
+        rewards
+            .into_iter()
+            .map(|Asset { amount, .. }| amount.u128())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(pending_on_proxy, expected_on_proxy)
+}
+
+fn create_pair(
+    app: &mut App,
+    factory: &Addr,
+    pair_type: Option<PairType>,
+    init_param: Option<Binary>,
+    assets: Vec<AssetInfo>,
+) -> (Addr, Addr) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &FactoryExecuteMsg::CreatePair {
+            pair_type: pair_type.unwrap_or_else(|| PairType::Xyk {}),
+            asset_infos: assets.clone(),
+            init_params: init_param,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            factory,
+            &FactoryQueryMsg::Pair {
+                asset_infos: assets,
+            },
+        )
+        .unwrap();
+
+    (res.contract_addr, res.liquidity_token)
+}
+
+fn store_whitelist_code(app: &mut App) -> u64 {
+    let whitelist_contract = Box::new(ContractWrapper::new_with_empty(
+        gridiron_whitelist::contract::execute,
+        gridiron_whitelist::contract::instantiate,
+        gridiron_whitelist::contract::query,
+    ));
+
+    app.store_code(whitelist_contract)
+}
+
+#[test]
+fn migrate_proxy() {
+    let app = Rc::new(RefCell::new(App::default()));
+
+    let gridiron = gridiron_address();
+
+    let user1 = Addr::unchecked("user1");
+    let user2 = Addr::unchecked("user2");
+    let user3 = Addr::unchecked("user3");
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+
+    let factory = generator.factory();
+
+    let grid = MockToken::try_from((&app, &generator.grid_token_info())).unwrap();
+    let val = MockTokenBuilder::new(&app, "VAL").instantiate();
+
+    let pair = factory.instantiate_xyk_pair(&[grid.asset_info(), val.asset_info()]);
+
+    pair.mint_allow_provide_and_stake(
+        &user1,
+        &[
+            grid.asset_info().with_balance(Uint128::new(2000)),
+            val.asset_info().with_balance(Uint128::new(2000)),
+        ],
+    );
+
+    pair.mint_allow_provide_and_stake(
+        &user2,
+        &[
+            grid.asset_info().with_balance(Uint128::new(2000)),
+            val.asset_info().with_balance(Uint128::new(2000)),
+        ],
+    );
+
+    pair.mint_allow_provide_and_stake(
+        &user3,
+        &[
+            grid.asset_info().with_balance(Uint128::new(2000)),
+            val.asset_info().with_balance(Uint128::new(2000)),
+        ],
+    );
+
+    let lp_token = pair.lp_token();
+
+    let vkr_staking = instantiate_valkyrie_protocol(
+        &mut app.borrow_mut(),
+        &val.address,
+        &pair.address,
+        &lp_token.address,
+    );
+
+    val.mint(&vkr_staking, Uint128::new(110_000_000));
+
+    let proxy_code_id = store_proxy_code(&mut app.borrow_mut());
+
+    let proxy_to_vkr = instantiate_proxy(
+        &mut app.borrow_mut(),
+        proxy_code_id,
+        &generator.address,
+        &pair.address,
+        &lp_token.address,
+        &vkr_staking,
+        &val.address,
+    );
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::MoveToProxy {
+                lp_token: lp_token.address.to_string(),
+                proxy: proxy_to_vkr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        app.borrow()
+            .wrap()
+            .query_wasm_smart::<RewardInfoResponse>(
+                generator.address.to_string(),
+                &QueryMsg::RewardInfo {
+                    lp_token: lp_token.address.to_string(),
+                },
+            )
+            .unwrap(),
+        RewardInfoResponse {
+            base_reward_token: generator.grid_token_info(),
+            proxy_reward_tokens: vec![val.address.clone()]
+        }
+    );
+
+    app.borrow_mut().next_block(1);
+
+    app.borrow_mut()
+        .execute_contract(
+            user1.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::ClaimRewards {
+                lp_tokens: vec![lp_token.address.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(val.balance(&user1), Uint128::new(10_000_000));
+    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(40_000_000));
+
+    let new_proxy_to_vkr = instantiate_proxy(
+        &mut app.borrow_mut(),
+        proxy_code_id,
+        &generator.address,
+        &pair.address,
+        &lp_token.address,
+        &vkr_staking,
+        &val.address,
+    );
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::MigrateProxy {
+                lp_token: lp_token.address.to_string(),
+                new_proxy: new_proxy_to_vkr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let proxy_reward_holder: Addr = from_slice(
+        &app.borrow()
+            .wrap()
+            .query_wasm_raw(generator.address.clone(), b"proxy_rewards_holder")
+            .unwrap()
+            .unwrap(),
     )
     .unwrap();
 
-    let res: PairInfo = app
-        .wrap()
-        .query_wasm_smart(
-            factory,
-            &FactoryQueryMsg::Pair {
-                asset_infos: assets,
+    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(0));
+    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(40_000_000));
+
+    app.borrow_mut()
+        .execute_contract(
+            user2.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::ClaimRewards {
+                lp_tokens: vec![lp_token.address.to_string()],
             },
+            &[],
         )
         .unwrap();
 
-    (res.contract_addr, res.liquidity_token)
-}
+    assert_eq!(val.balance(&user1), Uint128::new(10_000_000));
+    assert_eq!(val.balance(&user2), Uint128::new(20_000_000));
+    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(0));
+    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(20_000_000));
 
-fn store_whitelist_code(app: &mut App) -> u64 {
-    let whitelist_contract = Box::new(ContractWrapper::new_with_empty(
-        gridiron_whitelist::contract::execute,
-        gridiron_whitelist::contract::instantiate,
-        gridiron_whitelist::contract::query,
-    ));
+    app.borrow_mut()
+        .execute_contract(
+            user3.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::EmergencyWithdraw {
+                lp_token: lp_token.address.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
 
-    app.store_code(whitelist_contract)
+    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(20_000_000));
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            generator.address.clone(),
+            &ExecuteMsg::SendOrphanProxyReward {
+                recipient: gridiron.to_string(),
+                lp_token: lp_token.address.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(0));
+    assert_eq!(val.balance(&gridiron), Uint128::new(20_000_000));
+
+    assert_eq!(
+        app.borrow()
+            .wrap()
+            .query_wasm_smart::<usize>(generator.address.to_string(), &QueryMsg::PoolLength {})
+            .unwrap(),
+        1
+    );
 }
 
 #[test]
-fn migrate_proxy() {
+fn check_that_last_reward_block_is_reset_when_pool_becomes_incentivised() {
     let app = Rc::new(RefCell::new(App::default()));
 
     let gridiron = gridiron_address();
 
-    let user1 = Addr::unchecked("user1");
-    let user2 = Addr::unchecked("user2");
-    let user3 = Addr::unchecked("user3");
-
-    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let mut generator = MockGeneratorBuilder::new(&app).instantiate();
 
     let factory = generator.factory();
 
     let grid = MockToken::try_from((&app, &generator.grid_token_info())).unwrap();
-    let val = MockTokenBuilder::new(&app, "VAL").instantiate();
-
-    let pair = factory.instantiate_xyk_pair(&[grid.asset_info(), val.asset_info()]);
+    let tkn1 = MockTokenBuilder::new(&app, "TKN1").instantiate();
 
-    pair.mint_allow_provide_and_stake(
-        &user1,
-        &[
-            grid.asset_info().with_balance(Uint128::new(2000)),
-            val.asset_info().with_balance(Uint128::new(2000)),
-        ],
-    );
+    let pair = factory.instantiate_xyk_pair(&[grid.asset_info(), tkn1.asset_info()]);
 
     pair.mint_allow_provide_and_stake(
-        &user2,
+        &gridiron,
         &[
-            grid.asset_info().with_balance(Uint128::new(2000)),
-            val.asset_info().with_balance(Uint128::new(2000)),
+            grid.asset_info().with_balance(Uint128::new(1000_000000)),
+            tkn1.asset_info().with_balance(Uint128::new(1000_000000)),
         ],
     );
 
-    pair.mint_allow_provide_and_stake(
-        &user3,
-        &[
-            grid.asset_info().with_balance(Uint128::new(2000)),
-            val.asset_info().with_balance(Uint128::new(2000)),
-        ],
-    );
+    app.borrow_mut().update_block(|b| b.height += 1000);
 
     let lp_token = pair.lp_token();
 
-    let vkr_staking = instantiate_valkyrie_protocol(
-        &mut app.borrow_mut(),
-        &val.address,
-        &pair.address,
-        &lp_token.address,
+    generator.setup_pools(&[(lp_token.address.to_string(), Uint128::one())]);
+
+    // if last reward block didn't reset, user would get incentives for 1000 blocks
+    assert_eq!(
+        generator
+            .pending_token(&lp_token.address, &gridiron)
+            .pending,
+        Uint128::zero()
     );
+}
 
-    val.mint(&vkr_staking, Uint128::new(110_000_000));
+#[test]
+fn withdraw_and_unwrap() {
+    let app = Rc::new(RefCell::new(App::default()));
 
-    let proxy_code_id = store_proxy_code(&mut app.borrow_mut());
+    let user1 = Addr::unchecked(USER1);
 
-    let proxy_to_vkr = instantiate_proxy(
-        &mut app.borrow_mut(),
-        proxy_code_id,
-        &generator.address,
-        &pair.address,
-        &lp_token.address,
-        &vkr_staking,
-        &val.address,
+    let mut generator = MockGeneratorBuilder::new(&app).instantiate();
+
+    let factory = generator.factory();
+
+    let grid = MockToken::try_from((&app, &generator.grid_token_info())).unwrap();
+    let tkn1 = MockTokenBuilder::new(&app, "TKN1").instantiate();
+    let tkn2 = MockTokenBuilder::new(&app, "TKN2").instantiate();
+
+    let pair = factory.instantiate_xyk_pair(&[grid.asset_info(), tkn1.asset_info()]);
+    let lp_token = pair.lp_token();
+
+    generator.setup_pools(&[(lp_token.address.to_string(), Uint128::one())]);
+
+    pair.mint_allow_provide_and_stake(
+        &user1,
+        &[
+            grid.asset_info().with_balance(Uint128::new(1000_000000)),
+            tkn1.asset_info().with_balance(Uint128::new(1000_000000)),
+        ],
+    );
+    assert_eq!(
+        generator.query_deposit(&lp_token, &user1),
+        Uint128::new(999_999000)
     );
 
-    app.borrow_mut()
-        .execute_contract(
-            gridiron.clone(),
+    let withdraw_and_unwrap = |amount: u128, min_assets: Option<Vec<Asset>>| {
+        app.borrow_mut().execute_contract(
+            user1.clone(),
             generator.address.clone(),
-            &ExecuteMsg::MoveToProxy {
+            &ExecuteMsg::WithdrawAndUnwrap {
                 lp_token: lp_token.address.to_string(),
-                proxy: proxy_to_vkr.to_string(),
+                amount: Uint128::new(amount),
+                min_assets,
             },
             &[],
         )
-        .unwrap();
+    };
+
+    let err = withdraw_and_unwrap(0, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ZeroWithdraw {}
+    );
+
+    let err = withdraw_and_unwrap(
+        500_000000,
+        Some(vec![tkn2.asset_info().with_balance(Uint128::one())]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        format!("Asset {} is not in the pair", tkn2.address),
+        err.root_cause().to_string()
+    );
+
+    let err = withdraw_and_unwrap(
+        500_000000,
+        Some(vec![tkn1
+            .asset_info()
+            .with_balance(Uint128::new(500_000001))]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        format!("Received 500000000 {} but expected 500000001", tkn1.address),
+        err.root_cause().to_string()
+    );
+
+    withdraw_and_unwrap(
+        500_000000,
+        Some(vec![
+            grid.asset_info().with_balance(Uint128::new(500_000000)),
+            tkn1.asset_info().with_balance(Uint128::new(500_000000)),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        generator.query_deposit(&lp_token, &user1),
+        Uint128::new(499_999000)
+    );
+    assert_eq!(grid.balance(&user1), Uint128::new(500_000000));
+    assert_eq!(tkn1.balance(&user1), Uint128::new(500_000000));
+    assert_eq!(lp_token.balance(&user1), Uint128::zero());
+    assert_eq!(
+        lp_token.balance(&generator.address),
+        Uint128::new(499_999000)
+    );
+    assert_eq!(grid.balance(&generator.address), Uint128::zero());
+    assert_eq!(tkn1.balance(&generator.address), Uint128::zero());
+}
+
+#[test]
+fn update_pools_in_pages() {
+    let mut app = mock_app();
+
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
+
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
+
+    let pairs: Vec<_> = (0..250)
+        .map(|i| {
+            create_pair(
+                &mut app,
+                &factory_instance,
+                None,
+                None,
+                vec![
+                    native_asset_info("uusd".to_string()),
+                    native_asset_info(format!("denom{i}")),
+                ],
+            )
+        })
+        .collect();
+
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        pairs
+            .iter()
+            .map(|(_, lp_token)| PoolWithProxy {
+                pool: (lp_token.to_string(), Uint128::new(100)),
+                proxy: None,
+            })
+            .collect(),
+    );
+
+    let mut lp_tokens: Vec<_> = pairs.iter().map(|(_, lp_token)| lp_token.clone()).collect();
+    lp_tokens.sort();
+
+    // Stake in the first and the last generator of a pass
+    let user1 = Addr::unchecked(USER1);
+    let staked: Vec<_> = pairs
+        .iter()
+        .filter(|(_, lp_token)| *lp_token == lp_tokens[0] || *lp_token == lp_tokens[249])
+        .collect();
+    for (pair, lp_token) in &staked {
+        mint_tokens(&mut app, pair.clone(), lp_token, &user1, 10);
+        deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(lp_token, 10)]);
+    }
+
+    let update_pools = |app: &mut App, start_after: Option<&Addr>, limit: Option<u32>| {
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER2),
+                generator_instance.clone(),
+                &GeneratorExecuteMsg::UpdatePools {
+                    start_after: start_after.map(|lp_token| lp_token.to_string()),
+                    limit,
+                },
+                &[],
+            )
+            .unwrap();
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "updated")
+            .map(|attr| attr.value.parse::<usize>().unwrap())
+            .unwrap()
+    };
+    let query_cursor = |app: &mut App| -> Option<Addr> {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::UpdatePoolsCursor {},
+            )
+            .unwrap()
+    };
+
+    app.update_block(|bi| bi.height += 10);
+
+    // A full pass spans several blocks, every call continues where the previous one stopped
+    let mut passes = 0;
+    loop {
+        let updated = update_pools(&mut app, None, None);
+        passes += 1;
+        app.update_block(|bi| next_block(bi));
+
+        match query_cursor(&mut app) {
+            Some(cursor) => {
+                assert_eq!(updated, 30);
+                assert_eq!(cursor, lp_tokens[passes * 30 - 1]);
+            }
+            None => {
+                assert_eq!(updated, 10);
+                break;
+            }
+        }
+    }
+    assert_eq!(passes, 9);
 
-    assert_eq!(
-        app.borrow()
+    let last_reward_block = |app: &mut App, lp_token: &Addr| -> u64 {
+        let res: PoolInfoResponse = app
             .wrap()
-            .query_wasm_smart::<RewardInfoResponse>(
-                generator.address.to_string(),
-                &QueryMsg::RewardInfo {
-                    lp_token: lp_token.address.to_string(),
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: lp_token.to_string(),
                 },
             )
-            .unwrap(),
-        RewardInfoResponse {
-            base_reward_token: generator.grid_token_info(),
-            proxy_reward_token: Some(val.address.clone())
-        }
+            .unwrap();
+        res.last_reward_block
+    };
+    let first_updated_at = last_reward_block(&mut app, &lp_tokens[0]);
+    assert_eq!(
+        last_reward_block(&mut app, &lp_tokens[249]),
+        first_updated_at + 8
     );
 
-    app.borrow_mut().next_block(1);
+    // Generators updated at different blocks owe exactly the same rewards:
+    // 19 blocks * 10_000000 GRID per block * 100 / 25000 alloc points
+    for lp_token in [&lp_tokens[0], &lp_tokens[249]] {
+        check_pending_rewards(
+            &mut app,
+            &generator_instance,
+            lp_token,
+            USER1,
+            (760000, None),
+        );
+    }
 
-    app.borrow_mut()
-        .execute_contract(
-            user1.clone(),
-            generator.address.clone(),
-            &ExecuteMsg::ClaimRewards {
-                lp_tokens: vec![lp_token.address.to_string()],
-            },
-            &[],
-        )
-        .unwrap();
+    // The limit is capped and an explicit start overrides the stored position
+    assert_eq!(
+        update_pools(&mut app, Some(&lp_tokens[99]), Some(1000)),
+        100
+    );
+    assert_eq!(query_cursor(&mut app), Some(lp_tokens[199].clone()));
+    assert_eq!(update_pools(&mut app, None, Some(1000)), 50);
+    assert_eq!(query_cursor(&mut app), None);
+}
 
-    assert_eq!(val.balance(&user1), Uint128::new(10_000_000));
-    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(40_000_000));
+#[test]
+fn state_snapshot() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
+    let user1 = Addr::unchecked(USER1);
 
-    let new_proxy_to_vkr = instantiate_proxy(
-        &mut app.borrow_mut(),
-        proxy_code_id,
-        &generator.address,
-        &pair.address,
-        &lp_token.address,
-        &vkr_staking,
-        &val.address,
-    );
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
 
-    app.borrow_mut()
-        .execute_contract(
-            gridiron.clone(),
-            generator.address.clone(),
-            &ExecuteMsg::MigrateProxy {
-                lp_token: lp_token.address.to_string(),
-                new_proxy: new_proxy_to_vkr.to_string(),
-            },
-            &[],
-        )
-        .unwrap();
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
+    let generator_instance = instantiate_generator(
+        &mut app,
+        &factory_instance,
+        &grid_token_instance,
+        Some(OWNER.to_string()),
+    );
 
-    let proxy_reward_holder: Addr = from_slice(
-        &app.borrow()
-            .wrap()
-            .query_wasm_raw(generator.address.clone(), b"proxy_rewards_holder")
-            .unwrap()
-            .unwrap(),
-    )
-    .unwrap();
+    let pairs: Vec<_> = (0..3)
+        .map(|i| {
+            create_pair(
+                &mut app,
+                &factory_instance,
+                None,
+                None,
+                vec![
+                    native_asset_info("uusd".to_string()),
+                    native_asset_info(format!("denom{i}")),
+                ],
+            )
+        })
+        .collect();
 
-    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(0));
-    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(40_000_000));
+    // Pools are registered in reverse order, the snapshot lists them sorted
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        pairs
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, (_, lp_token))| PoolWithProxy {
+                pool: (lp_token.to_string(), Uint128::new(100 * (i as u128 + 1))),
+                proxy: None,
+            })
+            .collect(),
+    );
 
-    app.borrow_mut()
-        .execute_contract(
-            user2.clone(),
-            generator.address.clone(),
-            &ExecuteMsg::ClaimRewards {
-                lp_tokens: vec![lp_token.address.to_string()],
-            },
-            &[],
-        )
-        .unwrap();
+    let (pair, lp_token) = &pairs[0];
+    mint_tokens(&mut app, pair.clone(), lp_token, &user1, 10);
+    deposit_lp_tokens_to_generator(&mut app, &generator_instance, USER1, &[(lp_token, 10)]);
 
-    assert_eq!(val.balance(&user1), Uint128::new(10_000_000));
-    assert_eq!(val.balance(&user2), Uint128::new(20_000_000));
-    assert_eq!(val.balance(&proxy_to_vkr), Uint128::new(0));
-    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(20_000_000));
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::ProposeNewOwner {
+            owner: USER2.to_string(),
+            expires_in: 100,
+        },
+        &[],
+    )
+    .unwrap();
 
-    app.borrow_mut()
-        .execute_contract(
-            user3.clone(),
-            generator.address.clone(),
-            &ExecuteMsg::EmergencyWithdraw {
-                lp_token: lp_token.address.to_string(),
-            },
-            &[],
-        )
-        .unwrap();
+    mint_tokens(&mut app, owner.clone(), &grid_token_instance, &owner, 500);
+    app.execute_contract(
+        owner.clone(),
+        grid_token_instance.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: generator_instance.to_string(),
+            amount: Uint128::new(500),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let expires_at = app.block_info().time.seconds() + 1000;
+    app.execute_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &GeneratorExecuteMsg::RegisterMerkleDrop {
+            merkle_root: hex::encode([0u8; 32]),
+            total_amount: Uint128::new(500),
+            expires_at,
+        },
+        &[],
+    )
+    .unwrap();
 
-    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(20_000_000));
+    let query_snapshot = |app: &mut App, start_after: Option<&Addr>| -> StateSnapshotResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::StateSnapshot {
+                    start_after: start_after.map(|lp_token| lp_token.to_string()),
+                    limit: Some(2),
+                },
+            )
+            .unwrap()
+    };
 
-    app.borrow_mut()
-        .execute_contract(
-            gridiron.clone(),
-            generator.address.clone(),
-            &ExecuteMsg::SendOrphanProxyReward {
-                recipient: gridiron.to_string(),
-                lp_token: lp_token.address.to_string(),
-            },
-            &[],
-        )
+    let mut lp_tokens: Vec<_> = pairs.iter().map(|(_, lp_token)| lp_token.clone()).collect();
+    lp_tokens.sort();
+
+    // Fields are destructured without a rest pattern so that a new field fails to compile here
+    let StateSnapshotResponse {
+        schema_version,
+        config,
+        ownership_proposal,
+        proxy_rewards_holder,
+        proxy_reward_assets,
+        merkle_drops,
+        merkle_drop_count,
+        update_pools_cursor,
+        reentrancy_lock,
+        emission_schedule,
+        pools,
+    } = query_snapshot(&mut app, None);
+
+    assert_eq!(schema_version, STATE_SNAPSHOT_SCHEMA_VERSION);
+    let config_res: Config = app
+        .wrap()
+        .query_wasm_smart(&generator_instance, &GeneratorQueryMsg::Config {})
         .unwrap();
-    assert_eq!(val.balance(&proxy_reward_holder), Uint128::new(0));
-    assert_eq!(val.balance(&gridiron), Uint128::new(20_000_000));
+    let mut active_pools = config_res.active_pools.clone();
+    active_pools.sort();
+    assert_eq!(
+        config,
+        Config {
+            active_pools,
+            ..config_res
+        }
+    );
+    assert_eq!(ownership_proposal.unwrap().owner.as_str(), USER2);
+    assert!(proxy_rewards_holder.is_some());
+    assert_eq!(proxy_reward_assets, vec![]);
+    assert_eq!(merkle_drops.len(), 1);
+    assert_eq!(merkle_drops[0].0, 1);
+    assert_eq!(merkle_drops[0].1.total_amount.u128(), 500);
+    assert_eq!(merkle_drop_count, 1);
+    assert_eq!(update_pools_cursor, None);
+    assert!(!reentrancy_lock);
+    assert_eq!(emission_schedule, vec![]);
 
     assert_eq!(
-        app.borrow()
+        pools
+            .iter()
+            .map(|pool| pool.lp_token.clone())
+            .collect::<Vec<_>>(),
+        lp_tokens[..2]
+    );
+    for pool in &pools {
+        let pool_info: PoolInfoResponse = app
             .wrap()
-            .query_wasm_smart::<usize>(generator.address.to_string(), &QueryMsg::PoolLength {})
-            .unwrap(),
-        1
-    );
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: pool.lp_token.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pool.alloc_point, pool_info.alloc_point);
+        assert_eq!(pool.lp_supply, pool_info.lp_supply);
+        assert_eq!(
+            pool.pool_info.reward_global_index,
+            pool_info.global_reward_index
+        );
+        assert_eq!(pool.receipt_token, None);
+    }
+    let staked = pools
+        .iter()
+        .find(|pool| pool.lp_token == pairs[0].1)
+        .unwrap();
+    assert_eq!(staked.lp_supply.u128(), 10);
+    assert_eq!(staked.pool_info.total_virtual_supply.u128(), 10);
+
+    // Global items are repeated on every page, generators continue after the last one listed
+    let res = query_snapshot(&mut app, Some(&lp_tokens[1]));
+    assert_eq!(res.config, config);
+    assert_eq!(res.merkle_drop_count, 1);
+    assert_eq!(res.pools.len(), 1);
+    assert_eq!(res.pools[0].lp_token, lp_tokens[2]);
+
+    let res = query_snapshot(&mut app, Some(&lp_tokens[2]));
+    assert_eq!(res.pools, vec![]);
+}
+
+/// Marks the generator as a 2.20.0 contract. The pool info layout hasn't changed since 2.5.0.
+fn set_generator_version_v2_20(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, "gridiron-generator", "2.20.0")?;
+    Ok(Response::new())
 }
 
 #[test]
-fn check_that_last_reward_block_is_reset_when_pool_becomes_incentivised() {
-    let app = Rc::new(RefCell::new(App::default()));
+fn migrate_with_registered_pools() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked(OWNER);
 
-    let gridiron = gridiron_address();
+    let token_code_id = store_token_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+    let pair_code_id = store_pair_code_id(&mut app);
 
-    let mut generator = MockGeneratorBuilder::new(&app).instantiate();
+    let grid_token_instance =
+        instantiate_token(&mut app, token_code_id, "GRID", Some(1_000_000_000_000000));
+    let factory_instance =
+        instantiate_factory(&mut app, factory_code_id, token_code_id, pair_code_id, None);
 
-    let factory = generator.factory();
+    let cny_token = instantiate_token(&mut app, token_code_id, "CNY", None);
+    let eur_token = instantiate_token(&mut app, token_code_id, "EUR", None);
+    let (_, lp_cny_eur) = create_pair(
+        &mut app,
+        &factory_instance,
+        None,
+        None,
+        vec![
+            token_asset_info(cny_token.clone()),
+            token_asset_info(eur_token.clone()),
+        ],
+    );
 
-    let grid = MockToken::try_from((&app, &generator.grid_token_info())).unwrap();
-    let tkn1 = MockTokenBuilder::new(&app, "TKN1").instantiate();
+    let old_generator_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_generator::contract::execute,
+            gridiron_generator::contract::instantiate,
+            gridiron_generator::contract::query,
+        )
+        .with_reply_empty(gridiron_generator::contract::reply)
+        .with_migrate_empty(set_generator_version_v2_20),
+    ));
+    let generator_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_generator::contract::execute,
+            gridiron_generator::contract::instantiate,
+            gridiron_generator::contract::query,
+        )
+        .with_reply_empty(gridiron_generator::contract::reply)
+        .with_migrate_empty(gridiron_generator::contract::migrate),
+    ));
+    let whitelist_code_id = store_whitelist_code(&mut app);
 
-    let pair = factory.instantiate_xyk_pair(&[grid.asset_info(), tkn1.asset_info()]);
+    let generator_instance = app
+        .instantiate_contract(
+            old_generator_code_id,
+            owner.clone(),
+            &GeneratorInstantiateMsg {
+                owner: owner.to_string(),
+                factory: factory_instance.to_string(),
+                guardian: None,
+                start_block: Uint64::from(app.block_info().height),
+                grid_token: token_asset_info(grid_token_instance),
+                tokens_per_block: Uint128::new(10_000000),
+                vesting_contract: "vesting".to_string(),
+                generator_controller: None,
+                voting_escrow_delegation: None,
+                voting_escrow: None,
+                whitelist_code_id,
+            },
+            &[],
+            "Generator",
+            Some(owner.to_string()),
+        )
+        .unwrap();
 
-    pair.mint_allow_provide_and_stake(
-        &gridiron,
-        &[
-            grid.asset_info().with_balance(Uint128::new(1000_000000)),
-            tkn1.asset_info().with_balance(Uint128::new(1000_000000)),
-        ],
+    register_lp_tokens_in_generator(
+        &mut app,
+        &generator_instance,
+        vec![PoolWithProxy {
+            pool: (lp_cny_eur.to_string(), Uint128::from(100u32)),
+            proxy: None,
+        }],
     );
+    let query_pool_info = |app: &App| -> PoolInfoResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &generator_instance,
+                &GeneratorQueryMsg::PoolInfo {
+                    lp_token: lp_cny_eur.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    let pool_info = query_pool_info(&app);
 
-    app.borrow_mut().update_block(|b| b.height += 1000);
-
-    let lp_token = pair.lp_token();
+    // Pretend the generator runs 2.20.0 code, the pools are stored in the current layout
+    app.migrate_contract(
+        owner.clone(),
+        generator_instance.clone(),
+        &Empty {},
+        old_generator_code_id,
+    )
+    .unwrap();
 
-    generator.setup_pools(&[(lp_token.address.to_string(), Uint128::one())]);
+    let migrate_msg = GeneratorMigrateMsg {
+        factory: None,
+        generator_controller: None,
+        blocked_list_tokens: None,
+        guardian: None,
+        whitelist_code_id: None,
+        voting_escrow: None,
+        voting_escrow_delegation: None,
+        generator_limit: None,
+    };
+    app.migrate_contract(
+        owner,
+        generator_instance.clone(),
+        &migrate_msg,
+        generator_code_id,
+    )
+    .unwrap();
 
-    // if last reward block didn't reset, user would get incentives for 1000 blocks
-    assert_eq!(
-        generator
-            .pending_token(&lp_token.address, &gridiron)
-            .pending,
-        Uint128::zero()
-    );
+    assert_eq!(query_pool_info(&app), pool_info);
 }
@@ -92,6 +92,7 @@ impl ControllerHelper {
         let init_msg_vesting = InstantiateMsg {
             owner: owner.to_string(),
             vesting_token: token_asset_info(escrow_helper.grid_token.clone()),
+            generator: None,
         };
 
         let vesting_instance = router
@@ -202,6 +203,11 @@ impl ControllerHelper {
                     guardian: None,
                     voting_escrow: None,
                     checkpoint_generator_limit: None,
+                    proxy_rewards_claim_interval: None,
+                    staking_contract: None,
+                    max_pool_share: None,
+                    grid_pool_boost: None,
+                    router: None,
                     voting_escrow_delegation: None,
                 },
                 &[],
@@ -0,0 +1,194 @@
+#![cfg(not(tarpaulin_include))]
+
+//! Reference reward proxy supporting the emergency mode extension. It stakes LP tokens itself
+//! and refuses any reward related call once it is switched into emergency mode.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Item;
+use gridiron::generator_proxy::{Cw20HookMsg, InstantiateMsg};
+use gridiron_mocks::cw_multi_test::{App, ContractWrapper};
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateRewards {},
+    SendRewards {
+        account: String,
+        amount: Uint128,
+    },
+    Withdraw {
+        account: String,
+        amount: Uint128,
+    },
+    EmergencyWithdraw {
+        account: String,
+        amount: Uint128,
+    },
+    /// Sets the rewards which can be claimed from the proxy
+    SetPending {
+        amount: Uint128,
+    },
+    /// Switches the proxy into emergency mode. Can only be called by the proxy admin
+    SetEmergency {},
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Config {},
+    Deposit {},
+    Reward {},
+    PendingToken {},
+    RewardInfo {},
+    EmergencyMode {},
+}
+
+const CONFIG: Item<InstantiateMsg> = Item::new("config");
+const ADMIN: Item<Addr> = Item::new("admin");
+/// Staked LP tokens
+const DEPOSITED: Item<Uint128> = Item::new("deposited");
+/// Rewards which can be claimed from the proxy
+const PENDING: Item<Uint128> = Item::new("pending");
+/// Rewards claimed but not yet sent to the stakers
+const CLAIMED: Item<Uint128> = Item::new("claimed");
+const EMERGENCY: Item<bool> = Item::new("emergency");
+
+pub fn store_code(app: &mut App) -> u64 {
+    app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )))
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &msg)?;
+    ADMIN.save(deps.storage, &info.sender)?;
+    DEPOSITED.save(deps.storage, &Uint128::zero())?;
+    PENDING.save(deps.storage, &Uint128::zero())?;
+    CLAIMED.save(deps.storage, &Uint128::zero())?;
+    EMERGENCY.save(deps.storage, &false)?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let emergency = EMERGENCY.load(deps.storage)?;
+
+    match msg {
+        ExecuteMsg::Receive(cw20_msg) => {
+            if info.sender != config.lp_token_addr
+                || cw20_msg.sender != config.generator_contract_addr
+            {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            if emergency {
+                return Err(StdError::generic_err("Proxy is in emergency mode"));
+            }
+
+            match from_binary(&cw20_msg.msg)? {
+                Cw20HookMsg::Deposit {} => {
+                    let deposited = DEPOSITED.load(deps.storage)?;
+                    DEPOSITED.save(deps.storage, &deposited.checked_add(cw20_msg.amount)?)?;
+                }
+            }
+
+            Ok(Response::new())
+        }
+        ExecuteMsg::UpdateRewards {} => {
+            if emergency {
+                return Err(StdError::generic_err("Proxy is in emergency mode"));
+            }
+            let pending = PENDING.load(deps.storage)?;
+            let claimed = CLAIMED.load(deps.storage)?;
+            PENDING.save(deps.storage, &Uint128::zero())?;
+            CLAIMED.save(deps.storage, &claimed.checked_add(pending)?)?;
+
+            Ok(Response::new())
+        }
+        ExecuteMsg::SendRewards { account, amount } => {
+            if info.sender != config.generator_contract_addr {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            if emergency {
+                return Err(StdError::generic_err("Proxy is in emergency mode"));
+            }
+            let claimed = CLAIMED.load(deps.storage)?;
+            CLAIMED.save(deps.storage, &claimed.checked_sub(amount)?)?;
+
+            Ok(Response::new().add_message(WasmMsg::Execute {
+                contract_addr: config.reward_token_addr,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: account,
+                    amount,
+                })?,
+                funds: vec![],
+            }))
+        }
+        ExecuteMsg::Withdraw { account, amount } => {
+            // Withdrawals claim rewards from the 3rd party contract which is no longer trusted
+            if emergency {
+                return Err(StdError::generic_err("Proxy is in emergency mode"));
+            }
+            withdraw_lp_tokens(deps, &info, &config, account, amount)
+        }
+        ExecuteMsg::EmergencyWithdraw { account, amount } => {
+            withdraw_lp_tokens(deps, &info, &config, account, amount)
+        }
+        ExecuteMsg::SetPending { amount } => {
+            PENDING.save(deps.storage, &amount)?;
+            Ok(Response::new())
+        }
+        ExecuteMsg::SetEmergency {} => {
+            if info.sender != ADMIN.load(deps.storage)? {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            EMERGENCY.save(deps.storage, &true)?;
+            Ok(Response::new())
+        }
+    }
+}
+
+fn withdraw_lp_tokens(
+    deps: DepsMut,
+    info: &MessageInfo,
+    config: &InstantiateMsg,
+    account: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    if info.sender != config.generator_contract_addr {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+    let deposited = DEPOSITED.load(deps.storage)?;
+    DEPOSITED.save(deps.storage, &deposited.checked_sub(amount)?)?;
+
+    Ok(Response::new().add_message(WasmMsg::Execute {
+        contract_addr: config.lp_token_addr.clone(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: account,
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Deposit {} => to_binary(&DEPOSITED.load(deps.storage)?),
+        QueryMsg::Reward {} => to_binary(&CLAIMED.load(deps.storage)?),
+        QueryMsg::PendingToken {} => to_binary(&PENDING.load(deps.storage)?),
+        QueryMsg::RewardInfo {} => to_binary(&CONFIG.load(deps.storage)?.reward_token_addr),
+        QueryMsg::EmergencyMode {} => to_binary(&EMERGENCY.load(deps.storage)?),
+    }
+}
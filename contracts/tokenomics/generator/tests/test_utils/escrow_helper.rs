@@ -69,6 +69,8 @@ impl EscrowHelper {
             token_code_id: grid_token_code_id,
             deposit_token_addr: grid_token.to_string(),
             marketing: None,
+            revenue_asset: None,
+            redemption: None,
         };
         let staking_instance = router
             .instantiate_contract(
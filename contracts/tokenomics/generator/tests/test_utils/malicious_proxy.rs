@@ -0,0 +1,162 @@
+#![cfg(not(tarpaulin_include))]
+
+//! Reward proxy which calls back into the generator while its rewards are being claimed.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Item;
+use gridiron::generator::{
+    ExecuteMsg as GeneratorExecuteMsg, PendingTokenResponse, QueryMsg as GeneratorQueryMsg,
+};
+use gridiron::generator_proxy::InstantiateMsg;
+use gridiron_mocks::cw_multi_test::{App, ContractWrapper};
+
+/// What the proxy does in the middle of a reward update
+#[cw_serde]
+pub enum Attack {
+    /// Executes a message on the generator
+    Execute { msg: GeneratorExecuteMsg },
+    /// Queries the generator for the user's pending rewards and saves the response
+    QueryPendingToken { user: String },
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateRewards {},
+    SendRewards { account: String, amount: Uint128 },
+    SetPending { amount: Uint128 },
+    SetAttack { attack: Option<Attack> },
+    ExecuteAttack {},
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Config {},
+    Deposit {},
+    Reward {},
+    PendingToken {},
+    RewardInfo {},
+    /// Returns the generator response saved by [`Attack::QueryPendingToken`]
+    Observed {},
+}
+
+const CONFIG: Item<InstantiateMsg> = Item::new("config");
+/// Rewards which can be claimed from the proxy
+const PENDING: Item<Uint128> = Item::new("pending");
+/// Rewards claimed but not yet sent to the stakers
+const CLAIMED: Item<Uint128> = Item::new("claimed");
+const ATTACK: Item<Attack> = Item::new("attack");
+const OBSERVED: Item<PendingTokenResponse> = Item::new("observed");
+
+pub fn store_code(app: &mut App) -> u64 {
+    app.store_code(Box::new(ContractWrapper::new_with_empty(
+        execute,
+        instantiate,
+        query,
+    )))
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &msg)?;
+    PENDING.save(deps.storage, &Uint128::zero())?;
+    CLAIMED.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match msg {
+        ExecuteMsg::UpdateRewards {} => {
+            let pending = PENDING.load(deps.storage)?;
+            let claimed = CLAIMED.load(deps.storage)?;
+            PENDING.save(deps.storage, &Uint128::zero())?;
+            CLAIMED.save(deps.storage, &claimed.checked_add(pending)?)?;
+
+            // Queries issued in the same call don't see the state saved above
+            let mut response = Response::new();
+            if ATTACK.may_load(deps.storage)?.is_some() {
+                response = response.add_message(WasmMsg::Execute {
+                    contract_addr: env.contract.address.to_string(),
+                    msg: to_binary(&ExecuteMsg::ExecuteAttack {})?,
+                    funds: vec![],
+                });
+            }
+
+            Ok(response)
+        }
+        ExecuteMsg::SendRewards { account, amount } => {
+            if info.sender != config.generator_contract_addr {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            let claimed = CLAIMED.load(deps.storage)?;
+            CLAIMED.save(deps.storage, &claimed.checked_sub(amount)?)?;
+
+            Ok(Response::new().add_message(WasmMsg::Execute {
+                contract_addr: config.reward_token_addr,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: account,
+                    amount,
+                })?,
+                funds: vec![],
+            }))
+        }
+        ExecuteMsg::SetPending { amount } => {
+            PENDING.save(deps.storage, &amount)?;
+            Ok(Response::new())
+        }
+        ExecuteMsg::SetAttack { attack } => {
+            match attack {
+                Some(attack) => ATTACK.save(deps.storage, &attack)?,
+                None => ATTACK.remove(deps.storage),
+            }
+            Ok(Response::new())
+        }
+        ExecuteMsg::ExecuteAttack {} => {
+            if info.sender != env.contract.address {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+
+            match ATTACK.load(deps.storage)? {
+                Attack::Execute { msg } => Ok(Response::new().add_message(WasmMsg::Execute {
+                    contract_addr: config.generator_contract_addr,
+                    msg: to_binary(&msg)?,
+                    funds: vec![],
+                })),
+                Attack::QueryPendingToken { user } => {
+                    let response: PendingTokenResponse = deps.querier.query_wasm_smart(
+                        &config.generator_contract_addr,
+                        &GeneratorQueryMsg::PendingToken {
+                            lp_token: config.lp_token_addr,
+                            user,
+                        },
+                    )?;
+                    OBSERVED.save(deps.storage, &response)?;
+
+                    Ok(Response::new())
+                }
+            }
+        }
+    }
+}
+
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Deposit {} => to_binary(&Uint128::zero()),
+        QueryMsg::Reward {} => to_binary(&CLAIMED.load(deps.storage)?),
+        QueryMsg::PendingToken {} => to_binary(&PENDING.load(deps.storage)?),
+        QueryMsg::RewardInfo {} => to_binary(&CONFIG.load(deps.storage)?.reward_token_addr),
+        QueryMsg::Observed {} => to_binary(&OBSERVED.may_load(deps.storage)?),
+    }
+}
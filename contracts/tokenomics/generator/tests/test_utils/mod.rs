@@ -7,9 +7,11 @@ use gridiron_mocks::cw_multi_test::App;
 #[allow(dead_code)]
 pub mod controller_helper;
 pub mod delegation_helper;
+pub mod emergency_proxy;
 #[allow(clippy::all)]
 #[allow(dead_code)]
 pub mod escrow_helper;
+pub mod malicious_proxy;
 
 pub fn mock_app() -> App {
     let mut app = App::default();
@@ -0,0 +1,12 @@
+use cosmwasm_schema::write_api;
+
+use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use gridiron::token::InstantiateMsg;
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+    }
+}
@@ -0,0 +1,140 @@
+use cosmwasm_std::{
+    entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, SubMsg, Uint128, WasmMsg,
+};
+
+use cw2::set_contract_version;
+use cw20_base::contract::{
+    create_accounts, execute as cw20_execute, execute_burn, query as cw20_query,
+};
+use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use cw20_base::state::{MinterData, TokenInfo, TOKEN_INFO};
+use cw20_base::ContractError;
+
+use gridiron::generator::ExecuteMsg as GeneratorExecuteMsg;
+use gridiron::token::InstantiateMsg;
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-generator-receipt-token";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// The minter is the generator which moves staked positions along with receipt tokens.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    msg.validate()?;
+
+    let minter = msg
+        .mint
+        .ok_or_else(|| StdError::generic_err("The generator must be set as the minter"))?;
+
+    let total_supply = create_accounts(&mut deps, &msg.initial_balances)?;
+    if !total_supply.is_zero() {
+        return Err(StdError::generic_err("Initial balances are not allowed").into());
+    }
+
+    TOKEN_INFO.save(
+        deps.storage,
+        &TokenInfo {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+            total_supply,
+            mint: Some(MinterData {
+                minter: deps.api.addr_validate(&minter.minter)?,
+                cap: None,
+            }),
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// Transfers and sends notify the generator so the staked position follows the receipt tokens.
+/// Holders can't burn receipt tokens directly. The generator burns them when the backing
+/// LP tokens are withdrawn.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let generator = TOKEN_INFO
+        .load(deps.storage)?
+        .mint
+        .map(|mint| mint.minter)
+        .ok_or(ContractError::Unauthorized {})?;
+
+    let transfer = match &msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            Some((info.sender.to_string(), recipient.clone(), *amount))
+        }
+        ExecuteMsg::Send {
+            contract, amount, ..
+        } => Some((info.sender.to_string(), contract.clone(), *amount)),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => Some((owner.clone(), recipient.clone(), *amount)),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            ..
+        } => Some((owner.clone(), contract.clone(), *amount)),
+        ExecuteMsg::Burn { .. } => return Err(ContractError::Unauthorized {}),
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            if info.sender != generator {
+                return Err(ContractError::Unauthorized {});
+            }
+            let owner_info = MessageInfo {
+                sender: deps.api.addr_validate(owner)?,
+                funds: vec![],
+            };
+            return execute_burn(deps, env, owner_info, *amount);
+        }
+        _ => None,
+    };
+
+    let mut response = cw20_execute(deps, env, info, msg)?;
+
+    if let Some((from, to, amount)) = transfer {
+        // The position must be moved before the recipient processes a send hook
+        response
+            .messages
+            .insert(0, receipt_transfer_hook(&generator, from, to, amount)?);
+    }
+
+    Ok(response)
+}
+
+/// Builds the generator hook which moves the staked position backing transferred receipt tokens.
+fn receipt_transfer_hook(
+    generator: &Addr,
+    from: String,
+    to: String,
+    amount: Uint128,
+) -> StdResult<SubMsg> {
+    Ok(SubMsg::new(WasmMsg::Execute {
+        contract_addr: generator.to_string(),
+        msg: to_binary(&GeneratorExecuteMsg::ReceiptTransferHook { from, to, amount })?,
+        funds: vec![],
+    }))
+}
+
+/// Exposes queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    cw20_query(deps, env, msg)
+}
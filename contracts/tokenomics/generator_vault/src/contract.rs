@@ -0,0 +1,542 @@
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, wasm_execute, Binary, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse};
+use cw20_base::allowances::{
+    deduct_allowance, execute_decrease_allowance, execute_increase_allowance, execute_send_from,
+    execute_transfer_from, query_allowance,
+};
+use cw20_base::contract::{execute_send, execute_transfer, query_balance, query_token_info};
+use cw20_base::enumerable::{query_all_accounts, query_all_allowances};
+use cw20_base::state::{TokenInfo, BALANCES, TOKEN_INFO};
+
+use gridiron::asset::{Asset, AssetInfo};
+use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use gridiron::generator::{
+    Cw20HookMsg as GeneratorHookMsg, ExecuteMsg as GeneratorExecuteMsg,
+    QueryMsg as GeneratorQueryMsg, RewardInfoResponse,
+};
+use gridiron::generator_vault::{Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, OWNERSHIP_PROPOSAL, TOTAL_ASSETS};
+use crate::utils::{VaultState, DECIMALS_OFFSET};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-generator-vault";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in [`InstantiateMsg`].
+/// Shares get [`DECIMALS_OFFSET`] more decimals than the LP token.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        generator: deps.api.addr_validate(&msg.generator)?,
+        lp_token: deps.api.addr_validate(&msg.lp_token)?,
+        reward_receiver: deps.api.addr_validate(&msg.reward_receiver)?,
+    };
+
+    let lp_info: TokenInfoResponse = deps
+        .querier
+        .query_wasm_smart(&config.lp_token, &Cw20QueryMsg::TokenInfo {})?;
+
+    TOKEN_INFO.save(
+        deps.storage,
+        &TokenInfo {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: lp_info.decimals + DECIMALS_OFFSET as u8,
+            total_supply: Uint128::zero(),
+            mint: None,
+        },
+    )?;
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_ASSETS.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
+/// depending on the received template.
+///
+/// * **ExecuteMsg::Withdraw { assets, receiver, owner }** Burns shares for an exact amount of LP tokens.
+///
+/// * **ExecuteMsg::Redeem { shares, receiver, owner }** Burns an exact amount of shares for LP tokens.
+///
+/// * **ExecuteMsg::ClaimRewards {}** Claims generator rewards and sends them to the reward receiver.
+///
+/// * **ExecuteMsg::UpdateConfig { reward_receiver }** Updates the reward receiver.
+///
+/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
+///
+/// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
+///
+/// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+///
+/// * **ExecuteMsg::ForwardRewards {}** Sends reward tokens held by the vault to the reward receiver.
+///
+/// Share transfers and allowances follow the CW20 standard.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Withdraw {
+            assets,
+            receiver,
+            owner,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let state =
+                VaultState::load(deps.storage, &deps.querier, &config, &env.contract.address)?;
+            let shares = state.to_shares(assets, true)?;
+            burn_and_withdraw(
+                deps, env, info, config, state, shares, assets, receiver, owner,
+            )
+        }
+        ExecuteMsg::Redeem {
+            shares,
+            receiver,
+            owner,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let state =
+                VaultState::load(deps.storage, &deps.querier, &config, &env.contract.address)?;
+            let assets = state.to_assets(shares, false)?;
+            burn_and_withdraw(
+                deps, env, info, config, state, shares, assets, receiver, owner,
+            )
+        }
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env),
+        ExecuteMsg::ForwardRewards {} => forward_rewards(deps, env, info),
+        ExecuteMsg::UpdateConfig { reward_receiver } => update_config(deps, info, reward_receiver),
+        ExecuteMsg::Transfer { recipient, amount } => {
+            Ok(execute_transfer(deps, env, info, recipient, amount)?)
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => Ok(execute_send(deps, env, info, contract, amount, msg)?),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(execute_increase_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(execute_decrease_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => Ok(execute_transfer_from(
+            deps, env, info, owner, recipient, amount,
+        )?),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => Ok(execute_send_from(
+            deps, env, info, owner, contract, amount, msg,
+        )?),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Stakes received LP tokens in the generator and mints shares for them.
+///
+/// * **cw20_msg** CW20 message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.lp_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let state = VaultState::load(deps.storage, &deps.querier, &config, &env.contract.address)?;
+
+    let (action, assets, shares, receiver) = match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Deposit { receiver } => (
+            "deposit",
+            cw20_msg.amount,
+            state.to_shares(cw20_msg.amount, false)?,
+            receiver,
+        ),
+        Cw20HookMsg::Mint { shares, receiver } => {
+            let required = state.to_assets(shares, true)?;
+            if required > cw20_msg.amount {
+                return Err(ContractError::InsufficientAssets {
+                    required,
+                    provided: cw20_msg.amount,
+                });
+            }
+            ("mint", required, shares, receiver)
+        }
+    };
+
+    if assets.is_zero() || shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let receiver = deps
+        .api
+        .addr_validate(receiver.as_deref().unwrap_or(&cw20_msg.sender))?;
+
+    BALANCES.update::<_, StdError>(deps.storage, &receiver, |balance| {
+        Ok(balance.unwrap_or_default().checked_add(shares)?)
+    })?;
+    update_vault(deps.storage, state, |total_assets, total_supply| {
+        Ok((
+            total_assets.checked_add(assets)?,
+            total_supply.checked_add(shares)?,
+        ))
+    })?;
+
+    let mut messages: Vec<CosmosMsg> = vec![wasm_execute(
+        &config.lp_token,
+        &Cw20ExecuteMsg::Send {
+            contract: config.generator.to_string(),
+            amount: assets,
+            msg: to_binary(&GeneratorHookMsg::Deposit {})?,
+        },
+        vec![],
+    )?
+    .into()];
+
+    let refund = cw20_msg.amount - assets;
+    if !refund.is_zero() {
+        messages.push(
+            wasm_execute(
+                &config.lp_token,
+                &Cw20ExecuteMsg::Transfer {
+                    recipient: cw20_msg.sender.clone(),
+                    amount: refund,
+                },
+                vec![],
+            )?
+            .into(),
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", action),
+        attr("sender", cw20_msg.sender),
+        attr("receiver", receiver),
+        attr("assets", assets),
+        attr("shares", shares),
+    ]))
+}
+
+/// Burns shares from the owner, unstakes the LP tokens they represent and sends them
+/// to the receiver.
+///
+/// * **shares** amount of shares to burn.
+///
+/// * **assets** amount of LP tokens to send.
+///
+/// * **receiver** address that receives the LP tokens. Defaults to the sender.
+///
+/// * **owner** address whose shares are burned. Defaults to the sender. Otherwise the sender
+/// spends its share allowance.
+#[allow(clippy::too_many_arguments)]
+fn burn_and_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    state: VaultState,
+    shares: Uint128,
+    assets: Uint128,
+    receiver: Option<String>,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    if assets.is_zero() || shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let owner = deps
+        .api
+        .addr_validate(owner.as_deref().unwrap_or(info.sender.as_str()))?;
+    let receiver = deps
+        .api
+        .addr_validate(receiver.as_deref().unwrap_or(info.sender.as_str()))?;
+
+    if owner != info.sender {
+        deduct_allowance(deps.storage, &owner, &info.sender, &env.block, shares)?;
+    }
+
+    BALANCES.update::<_, StdError>(deps.storage, &owner, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(shares)?)
+    })?;
+    update_vault(deps.storage, state, |total_assets, total_supply| {
+        Ok((
+            total_assets.checked_sub(assets)?,
+            total_supply.checked_sub(shares)?,
+        ))
+    })?;
+
+    let messages: Vec<CosmosMsg> = vec![
+        wasm_execute(
+            &config.generator,
+            &GeneratorExecuteMsg::Withdraw {
+                lp_token: config.lp_token.to_string(),
+                amount: assets,
+            },
+            vec![],
+        )?
+        .into(),
+        wasm_execute(
+            &config.lp_token,
+            &Cw20ExecuteMsg::Transfer {
+                recipient: receiver.to_string(),
+                amount: assets,
+            },
+            vec![],
+        )?
+        .into(),
+    ];
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "withdraw"),
+        attr("sender", info.sender),
+        attr("owner", owner),
+        attr("receiver", receiver),
+        attr("assets", assets),
+        attr("shares", shares),
+    ]))
+}
+
+/// Saves the vault totals. The LP amount is synced with the generator position first, so a
+/// reported shortfall is written down once rather than on every exit.
+fn update_vault(
+    storage: &mut dyn Storage,
+    state: VaultState,
+    f: impl FnOnce(Uint128, Uint128) -> StdResult<(Uint128, Uint128)>,
+) -> StdResult<()> {
+    let (total_assets, total_supply) = f(state.total_assets, state.total_supply)?;
+
+    TOTAL_ASSETS.save(storage, &total_assets)?;
+    TOKEN_INFO.update::<_, StdError>(storage, |mut info| {
+        info.total_supply = total_supply;
+        Ok(info)
+    })?;
+
+    Ok(())
+}
+
+/// Claims generator rewards for the vault position and forwards every reward token held by the
+/// vault to the reward receiver. This also forwards rewards the generator paid out during
+/// deposits and withdrawals.
+fn claim_rewards(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let messages: Vec<CosmosMsg> = vec![
+        wasm_execute(
+            &config.generator,
+            &GeneratorExecuteMsg::ClaimRewards {
+                lp_tokens: vec![config.lp_token.to_string()],
+            },
+            vec![],
+        )?
+        .into(),
+        wasm_execute(
+            &env.contract.address,
+            &ExecuteMsg::ForwardRewards {},
+            vec![],
+        )?
+        .into(),
+    ];
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "claim_rewards"))
+}
+
+/// Sends the vault balances of the generator reward tokens to the reward receiver.
+///
+/// ## Executor
+/// Only the contract itself can execute this.
+fn forward_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let reward_info: RewardInfoResponse = deps.querier.query_wasm_smart(
+        &config.generator,
+        &GeneratorQueryMsg::RewardInfo {
+            lp_token: config.lp_token.to_string(),
+        },
+    )?;
+
+    let reward_tokens = std::iter::once(reward_info.base_reward_token).chain(
+        reward_info
+            .proxy_reward_tokens
+            .into_iter()
+            .filter(|token| *token != config.lp_token)
+            .map(AssetInfo::cw20),
+    );
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![attr("action", "forward_rewards")];
+    for info in reward_tokens {
+        let amount = info.query_pool(&deps.querier, &env.contract.address)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let asset = Asset { info, amount };
+        attributes.push(attr("reward", asset.to_string()));
+        messages.push(asset.into_msg(&config.reward_receiver)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// Updates the reward receiver.
+///
+/// * **reward_receiver** the new reward receiver.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    reward_receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(reward_receiver) = reward_receiver {
+        config.reward_receiver = deps.api.addr_validate(&reward_receiver)?;
+        attributes.push(attr("reward_receiver", reward_receiver));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`Config`] object.
+///
+/// * **QueryMsg::Asset {}** Returns the LP token managed by the vault.
+///
+/// * **QueryMsg::TotalAssets {}** Returns the amount of LP tokens backing all shares.
+///
+/// * **QueryMsg::ConvertToShares { assets }**, **QueryMsg::ConvertToAssets { shares }** and the
+/// preview queries return conversions rounded in favour of the vault.
+///
+/// * **QueryMsg::MaxWithdraw { owner }** Returns the LP amount the owner's shares are worth.
+///
+/// Share balances and allowances follow the CW20 standard.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let load_state = || {
+        let config = CONFIG.load(deps.storage)?;
+        VaultState::load(deps.storage, &deps.querier, &config, &env.contract.address)
+    };
+
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Asset {} => to_binary(&CONFIG.load(deps.storage)?.lp_token),
+        QueryMsg::TotalAssets {} => to_binary(&load_state()?.total_assets),
+        QueryMsg::ConvertToShares { assets } | QueryMsg::PreviewDeposit { assets } => {
+            to_binary(&load_state()?.to_shares(assets, false)?)
+        }
+        QueryMsg::ConvertToAssets { shares } | QueryMsg::PreviewRedeem { shares } => {
+            to_binary(&load_state()?.to_assets(shares, false)?)
+        }
+        QueryMsg::PreviewMint { shares } => to_binary(&load_state()?.to_assets(shares, true)?),
+        QueryMsg::PreviewWithdraw { assets } => to_binary(&load_state()?.to_shares(assets, true)?),
+        QueryMsg::MaxWithdraw { owner } => {
+            let balance = query_balance(deps, owner)?.balance;
+            to_binary(&load_state()?.to_assets(balance, false)?)
+        }
+        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// This enum describes generator vault contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    Cw20Base(#[from] cw20_base::ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("The operation results in zero shares or zero LP tokens")]
+    ZeroAmount {},
+
+    #[error("Not enough LP tokens to mint shares: {required} required, {provided} provided")]
+    InsufficientAssets {
+        required: Uint128,
+        provided: Uint128,
+    },
+}
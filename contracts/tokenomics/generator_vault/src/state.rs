@@ -0,0 +1,14 @@
+use cosmwasm_std::Uint128;
+use cw_storage_plus::Item;
+use gridiron::common::OwnershipProposal;
+use gridiron::generator_vault::Config;
+
+/// Stores the contract config at the given key.
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the amount of LP tokens the vault staked on behalf of share holders. LP tokens
+/// deposited in the generator for the vault by anyone else are not counted.
+pub const TOTAL_ASSETS: Item<Uint128> = Item::new("total_assets");
+
+/// Contains a proposal to change contract ownership.
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
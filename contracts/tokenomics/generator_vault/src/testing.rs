@@ -0,0 +1,339 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, ContractResult, CosmosMsg, OwnedDeps, Response,
+    SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse};
+
+use gridiron::generator::QueryMsg as GeneratorQueryMsg;
+use gridiron::generator_vault::{Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+/// Mocks the LP token info and the vault position reported by the generator.
+fn set_staked(deps: &mut MockDeps, staked: u128) {
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "lp_token" => {
+            match from_slice(msg).unwrap() {
+                Cw20QueryMsg::TokenInfo {} => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&TokenInfoResponse {
+                        name: "LP".to_string(),
+                        symbol: "LP".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::zero(),
+                    })
+                    .unwrap(),
+                )),
+                _ => panic!("unexpected LP token query"),
+            }
+        }
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "generator" => {
+            match from_slice(msg).unwrap() {
+                GeneratorQueryMsg::Deposit { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&Uint128::new(staked)).unwrap(),
+                )),
+                _ => panic!("unexpected generator query"),
+            }
+        }
+        _ => panic!("unexpected query"),
+    });
+}
+
+fn mock_instantiate() -> MockDeps {
+    let mut deps = mock_dependencies();
+    set_staked(&mut deps, 0);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        generator: "generator".to_string(),
+        lp_token: "lp_token".to_string(),
+        reward_receiver: "treasury".to_string(),
+        name: "Vault LP".to_string(),
+        symbol: "vLP".to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps
+}
+
+fn receive(
+    deps: &mut MockDeps,
+    sender: &str,
+    amount: u128,
+    hook: &Cw20HookMsg,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lp_token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(hook).unwrap(),
+        }),
+    )
+}
+
+fn deposit(deps: &mut MockDeps, sender: &str, amount: u128) {
+    receive(
+        deps,
+        sender,
+        amount,
+        &Cw20HookMsg::Deposit { receiver: None },
+    )
+    .unwrap();
+}
+
+fn query_shares(deps: &MockDeps, address: &str) -> Uint128 {
+    let res: BalanceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    res.balance
+}
+
+fn query_uint(deps: &MockDeps, msg: QueryMsg) -> Uint128 {
+    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = mock_instantiate();
+
+    let config: Config =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.generator, "generator");
+    assert_eq!(config.lp_token, "lp_token");
+    assert_eq!(config.reward_receiver, "treasury");
+
+    let info: TokenInfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+    assert_eq!(info.decimals, 9);
+    assert_eq!(info.total_supply, Uint128::zero());
+    assert_eq!(query_uint(&deps, QueryMsg::TotalAssets {}), Uint128::zero());
+}
+
+#[test]
+fn deposit_stakes_in_generator() {
+    let mut deps = mock_instantiate();
+
+    // Only the vault LP token is accepted
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random_token", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_binary(&Cw20HookMsg::Deposit { receiver: None }).unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = receive(
+        &mut deps,
+        "alice",
+        1000,
+        &Cw20HookMsg::Deposit {
+            receiver: Some("bob".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, "lp_token");
+            match from_binary(msg).unwrap() {
+                Cw20ExecuteMsg::Send {
+                    contract, amount, ..
+                } => {
+                    assert_eq!(contract, "generator");
+                    assert_eq!(amount, Uint128::new(1000));
+                }
+                _ => panic!("unexpected message"),
+            }
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    assert_eq!(query_shares(&deps, "bob"), Uint128::new(1_000_000));
+    assert_eq!(query_shares(&deps, "alice"), Uint128::zero());
+
+    // Deposits that mint no shares are rejected
+    set_staked(&mut deps, 1000);
+    let err = receive(
+        &mut deps,
+        "alice",
+        0,
+        &Cw20HookMsg::Deposit { receiver: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ZeroAmount {});
+}
+
+#[test]
+fn donations_do_not_move_share_price() {
+    let mut deps = mock_instantiate();
+
+    deposit(&mut deps, "alice", 1000);
+
+    // Someone deposits LP tokens in the generator on behalf of the vault
+    set_staked(&mut deps, 6000);
+    assert_eq!(
+        query_uint(&deps, QueryMsg::TotalAssets {}),
+        Uint128::new(1000)
+    );
+
+    deposit(&mut deps, "bob", 1000);
+    assert_eq!(query_shares(&deps, "bob"), Uint128::new(1_000_000));
+    set_staked(&mut deps, 7000);
+    assert_eq!(
+        query_uint(&deps, QueryMsg::TotalAssets {}),
+        Uint128::new(2000)
+    );
+    assert_eq!(
+        query_uint(
+            &deps,
+            QueryMsg::MaxWithdraw {
+                owner: "alice".to_string()
+            }
+        ),
+        Uint128::new(1000)
+    );
+}
+
+#[test]
+fn shortfall_is_shared_pro_rata() {
+    let mut deps = mock_instantiate();
+
+    deposit(&mut deps, "alice", 1000);
+    set_staked(&mut deps, 1000);
+    deposit(&mut deps, "bob", 1000);
+
+    // The generator position lost half of its value
+    set_staked(&mut deps, 1000);
+    assert_eq!(
+        query_uint(&deps, QueryMsg::TotalAssets {}),
+        Uint128::new(1000)
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Redeem {
+            shares: Uint128::new(1_000_000),
+            receiver: None,
+            owner: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "assets" && attr.value == "500"));
+
+    // Bob's shares are backed by the rest of the position
+    set_staked(&mut deps, 500);
+    assert_eq!(
+        query_uint(&deps, QueryMsg::TotalAssets {}),
+        Uint128::new(500)
+    );
+    assert_eq!(
+        query_uint(
+            &deps,
+            QueryMsg::PreviewRedeem {
+                shares: Uint128::new(1_000_000)
+            }
+        ),
+        Uint128::new(500)
+    );
+}
+
+#[test]
+fn mint_and_withdraw_on_behalf() {
+    let mut deps = mock_instantiate();
+
+    let err = receive(
+        &mut deps,
+        "alice",
+        400,
+        &Cw20HookMsg::Mint {
+            shares: Uint128::new(500_000),
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientAssets {
+            required: Uint128::new(500),
+            provided: Uint128::new(400),
+        }
+    );
+
+    // The excess LP tokens are refunded
+    let res = receive(
+        &mut deps,
+        "alice",
+        1000,
+        &Cw20HookMsg::Mint {
+            shares: Uint128::new(500_000),
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(query_shares(&deps, "alice"), Uint128::new(500_000));
+    set_staked(&mut deps, 500);
+
+    let withdraw = ExecuteMsg::Withdraw {
+        assets: Uint128::new(200),
+        receiver: Some("carol".to_string()),
+        owner: Some("alice".to_string()),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        withdraw.clone(),
+    )
+    .unwrap_err();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::IncreaseAllowance {
+            spender: "bob".to_string(),
+            amount: Uint128::new(200_000),
+            expires: None,
+        },
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), withdraw).unwrap();
+
+    assert_eq!(query_shares(&deps, "alice"), Uint128::new(300_000));
+    set_staked(&mut deps, 300);
+    assert_eq!(
+        query_uint(&deps, QueryMsg::TotalAssets {}),
+        Uint128::new(300)
+    );
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Storage, Uint128, Uint256};
+use cw20_base::state::TOKEN_INFO;
+
+use gridiron::generator::QueryMsg as GeneratorQueryMsg;
+use gridiron::generator_vault::Config;
+
+use crate::state::TOTAL_ASSETS;
+
+/// The number of extra decimals shares have compared to the LP token. Together with
+/// [`VIRTUAL_ASSETS`] it makes inflating the share price through a first deposit unprofitable.
+pub const DECIMALS_OFFSET: u32 = 3;
+/// The amount of LP tokens that is always considered to back the virtual shares.
+pub const VIRTUAL_ASSETS: Uint128 = Uint128::new(1);
+
+/// This structure holds the values used to convert between LP tokens and shares.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VaultState {
+    /// The amount of LP tokens backing all shares
+    pub total_assets: Uint128,
+    /// The total amount of shares
+    pub total_supply: Uint128,
+}
+
+impl VaultState {
+    /// Loads the vault state. The tracked amount is capped by the position the generator reports,
+    /// so any shortfall of the staked position is shared pro-rata by all holders instead of
+    /// being paid by whoever exits last.
+    pub fn load(
+        storage: &dyn Storage,
+        querier: &QuerierWrapper,
+        config: &Config,
+        vault: &Addr,
+    ) -> StdResult<Self> {
+        let tracked = TOTAL_ASSETS.load(storage)?;
+        let staked: Uint128 = querier.query_wasm_smart(
+            &config.generator,
+            &GeneratorQueryMsg::Deposit {
+                lp_token: config.lp_token.to_string(),
+                user: vault.to_string(),
+            },
+        )?;
+
+        Ok(Self {
+            total_assets: tracked.min(staked),
+            total_supply: TOKEN_INFO.load(storage)?.total_supply,
+        })
+    }
+
+    /// Converts an amount of LP tokens to shares.
+    pub fn to_shares(&self, assets: Uint128, round_up: bool) -> StdResult<Uint128> {
+        mul_div(
+            assets,
+            self.total_supply + Uint128::new(10u128.pow(DECIMALS_OFFSET)),
+            self.total_assets + VIRTUAL_ASSETS,
+            round_up,
+        )
+    }
+
+    /// Converts an amount of shares to LP tokens.
+    pub fn to_assets(&self, shares: Uint128, round_up: bool) -> StdResult<Uint128> {
+        mul_div(
+            shares,
+            self.total_assets + VIRTUAL_ASSETS,
+            self.total_supply + Uint128::new(10u128.pow(DECIMALS_OFFSET)),
+            round_up,
+        )
+    }
+}
+
+/// Returns `value * numerator / denominator` rounded in the requested direction.
+fn mul_div(
+    value: Uint128,
+    numerator: Uint128,
+    denominator: Uint128,
+    round_up: bool,
+) -> StdResult<Uint128> {
+    let product = value.full_mul(numerator);
+    let denominator = Uint256::from(denominator);
+    let mut result = product / denominator;
+    if round_up && !(product % denominator).is_zero() {
+        result += Uint256::one();
+    }
+
+    Ok(result.try_into()?)
+}
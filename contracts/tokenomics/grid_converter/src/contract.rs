@@ -0,0 +1,303 @@
+use cosmwasm_std::{
+    attr, coins, entry_point, from_binary, to_binary, wasm_execute, BankMsg, Binary, CosmosMsg,
+    Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_utils::must_pay;
+
+use gridiron::asset::validate_native_denom;
+use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use gridiron::grid_converter::{
+    Config, ConversionStats, Cw20HookMsg, ExecuteMsg, InstantiateMsg, NativeMode, QueryMsg,
+};
+use gridiron::querier::query_token_balance;
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, OWNERSHIP_PROPOSAL, STATS};
+use crate::tokenfactory;
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-grid-converter";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in [`InstantiateMsg`].
+/// Both conversion directions are enabled.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    validate_native_denom(&msg.native_grid)?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        cw20_grid: deps.api.addr_validate(&msg.cw20_grid)?,
+        native_grid: msg.native_grid,
+        native_mode: msg.native_mode,
+        cw20_to_native_enabled: true,
+        native_to_cw20_enabled: true,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    STATS.save(deps.storage, &ConversionStats::default())?;
+
+    Ok(Response::new())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
+/// depending on the received template.
+///
+/// * **ExecuteMsg::Convert { receiver }** Converts the attached native GRID to CW20 GRID.
+///
+/// * **ExecuteMsg::UpdateConfig { cw20_to_native_enabled, native_to_cw20_enabled }** Pauses or
+/// resumes conversion directions.
+///
+/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
+///
+/// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
+///
+/// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Convert { receiver } => convert_to_cw20(deps, env, info, receiver),
+        ExecuteMsg::UpdateConfig {
+            cw20_to_native_enabled,
+            native_to_cw20_enabled,
+        } => update_config(deps, info, cw20_to_native_enabled, native_to_cw20_enabled),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Converts received CW20 GRID to native GRID. CW20 GRID stays in the contract so it can be
+/// converted back.
+///
+/// * **cw20_msg** CW20 message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.cw20_grid {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Convert { receiver } => {
+            if !config.cw20_to_native_enabled {
+                return Err(ContractError::DirectionPaused(config.cw20_grid.to_string()));
+            }
+
+            let receiver = deps
+                .api
+                .addr_validate(receiver.as_deref().unwrap_or(&cw20_msg.sender))?;
+            let amount = cw20_msg.amount;
+
+            let mut messages: Vec<CosmosMsg> = vec![];
+            match &config.native_mode {
+                NativeMode::MintBurn {} => messages.push(tokenfactory::mint_msg(
+                    &env.contract.address,
+                    &config.native_grid,
+                    amount,
+                )),
+                NativeMode::Escrow {} => {
+                    let available = deps
+                        .querier
+                        .query_balance(&env.contract.address, &config.native_grid)?
+                        .amount;
+                    check_escrow(&config.native_grid, available, amount)?;
+                }
+            }
+            messages.push(
+                BankMsg::Send {
+                    to_address: receiver.to_string(),
+                    amount: coins(amount.u128(), &config.native_grid),
+                }
+                .into(),
+            );
+
+            STATS.update::<_, ContractError>(deps.storage, |mut stats| {
+                stats.total_to_native = stats.total_to_native.checked_add(amount)?;
+                Ok(stats)
+            })?;
+
+            Ok(Response::new().add_messages(messages).add_attributes([
+                attr("action", "convert_to_native"),
+                attr("sender", cw20_msg.sender),
+                attr("receiver", receiver),
+                attr("amount", amount),
+            ]))
+        }
+    }
+}
+
+/// Converts the attached native GRID to CW20 GRID held by the contract.
+///
+/// * **receiver** address that receives CW20 GRID. Defaults to the sender.
+fn convert_to_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let amount = must_pay(&info, &config.native_grid)?;
+
+    if !config.native_to_cw20_enabled {
+        return Err(ContractError::DirectionPaused(config.native_grid));
+    }
+
+    let receiver = deps
+        .api
+        .addr_validate(receiver.as_deref().unwrap_or(info.sender.as_str()))?;
+
+    let available = query_token_balance(&deps.querier, &config.cw20_grid, &env.contract.address)?;
+    check_escrow(config.cw20_grid.as_str(), available, amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if matches!(config.native_mode, NativeMode::MintBurn {}) {
+        messages.push(tokenfactory::burn_msg(
+            &env.contract.address,
+            &config.native_grid,
+            amount,
+        ));
+    }
+    messages.push(
+        wasm_execute(
+            &config.cw20_grid,
+            &Cw20ExecuteMsg::Transfer {
+                recipient: receiver.to_string(),
+                amount,
+            },
+            vec![],
+        )?
+        .into(),
+    );
+
+    STATS.update::<_, ContractError>(deps.storage, |mut stats| {
+        stats.total_to_cw20 = stats.total_to_cw20.checked_add(amount)?;
+        Ok(stats)
+    })?;
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "convert_to_cw20"),
+        attr("sender", info.sender),
+        attr("receiver", receiver),
+        attr("amount", amount),
+    ]))
+}
+
+/// Pauses or resumes conversion directions.
+///
+/// * **cw20_to_native_enabled** whether CW20 GRID can be converted to native GRID.
+///
+/// * **native_to_cw20_enabled** whether native GRID can be converted to CW20 GRID.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_to_native_enabled: Option<bool>,
+    native_to_cw20_enabled: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(enabled) = cw20_to_native_enabled {
+        config.cw20_to_native_enabled = enabled;
+        attributes.push(attr("cw20_to_native_enabled", enabled.to_string()));
+    }
+
+    if let Some(enabled) = native_to_cw20_enabled {
+        config.native_to_cw20_enabled = enabled;
+        attributes.push(attr("native_to_cw20_enabled", enabled.to_string()));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Checks that the contract holds enough tokens to pay out a conversion.
+fn check_escrow(denom: &str, available: Uint128, required: Uint128) -> Result<(), ContractError> {
+    if available < required {
+        return Err(ContractError::InsufficientEscrow {
+            denom: denom.to_string(),
+            available,
+            required,
+        });
+    }
+
+    Ok(())
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`Config`] object.
+///
+/// * **QueryMsg::Stats {}** Returns the total amounts converted in each direction using
+/// a [`ConversionStats`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Stats {} => to_binary(&STATS.load(deps.storage)?),
+    }
+}
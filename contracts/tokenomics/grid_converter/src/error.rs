@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+/// This enum describes GRID converter contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Conversion from {0} is paused")]
+    DirectionPaused(String),
+
+    #[error("Not enough {denom} in escrow: {available} available, {required} required")]
+    InsufficientEscrow {
+        denom: String,
+        available: Uint128,
+        required: Uint128,
+    },
+}
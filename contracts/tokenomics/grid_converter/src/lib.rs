@@ -0,0 +1,7 @@
+pub mod contract;
+pub mod error;
+pub mod state;
+mod tokenfactory;
+
+#[cfg(test)]
+mod testing;
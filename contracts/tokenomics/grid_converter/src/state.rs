@@ -0,0 +1,12 @@
+use cw_storage_plus::Item;
+use gridiron::common::OwnershipProposal;
+use gridiron::grid_converter::{Config, ConversionStats};
+
+/// Stores the contract config at the given key.
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the total amounts converted in each direction.
+pub const STATS: Item<ConversionStats> = Item::new("stats");
+
+/// Contains a proposal to change contract ownership.
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
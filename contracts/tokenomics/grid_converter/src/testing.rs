@@ -0,0 +1,193 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, to_binary, BankMsg, CosmosMsg, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use gridiron::grid_converter::{
+    Config, ConversionStats, Cw20HookMsg, ExecuteMsg, InstantiateMsg, NativeMode, QueryMsg,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+const NATIVE_GRID: &str = "factory/converter/grid";
+
+fn mock_instantiate(deps: cosmwasm_std::DepsMut) {
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        cw20_grid: "grid".to_string(),
+        native_grid: NATIVE_GRID.to_string(),
+        native_mode: NativeMode::MintBurn {},
+    };
+
+    instantiate(deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+}
+
+fn receive_msg(sender: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&Cw20HookMsg::Convert { receiver: None }).unwrap(),
+    })
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+
+    let config: Config =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.owner, "owner");
+    assert_eq!(config.cw20_grid, "grid");
+    assert_eq!(config.native_grid, NATIVE_GRID);
+    assert_eq!(config.native_mode, NativeMode::MintBurn {});
+    assert!(config.cw20_to_native_enabled);
+    assert!(config.native_to_cw20_enabled);
+
+    let stats: ConversionStats =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+    assert_eq!(stats, ConversionStats::default());
+}
+
+#[test]
+fn convert_to_native_mints() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+
+    // Only the CW20 GRID contract can send tokens to convert
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        receive_msg("user", 100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("grid", &[]),
+        receive_msg("user", 100),
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Stargate { type_url, .. } => {
+            assert_eq!(type_url, "/osmosis.tokenfactory.v1beta1.MsgMint")
+        }
+        _ => panic!("Expected a mint message"),
+    }
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: coins(100, NATIVE_GRID),
+        })
+    );
+
+    let stats: ConversionStats =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+    assert_eq!(stats.total_to_native, Uint128::new(100));
+}
+
+#[test]
+fn convert_to_cw20_burns() {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|_| {
+        cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(
+            to_binary(&cw20::BalanceResponse {
+                balance: Uint128::new(1000),
+            })
+            .unwrap(),
+        ))
+    });
+    mock_instantiate(deps.as_mut());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, NATIVE_GRID)),
+        ExecuteMsg::Convert {
+            receiver: Some("receiver".to_string()),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 2);
+    match &res.messages[0].msg {
+        CosmosMsg::Stargate { type_url, .. } => {
+            assert_eq!(type_url, "/osmosis.tokenfactory.v1beta1.MsgBurn")
+        }
+        _ => panic!("Expected a burn message"),
+    }
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "grid".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "receiver".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // The escrowed CW20 balance caps the conversion
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(1001, NATIVE_GRID)),
+        ExecuteMsg::Convert { receiver: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientEscrow {
+            denom: "grid".to_string(),
+            available: Uint128::new(1000),
+            required: Uint128::new(1001),
+        }
+    );
+}
+
+#[test]
+fn pause_directions() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        cw20_to_native_enabled: Some(false),
+        native_to_cw20_enabled: Some(false),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("grid", &[]),
+        receive_msg("user", 100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DirectionPaused("grid".to_string()));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, NATIVE_GRID)),
+        ExecuteMsg::Convert { receiver: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DirectionPaused(NATIVE_GRID.to_string()));
+}
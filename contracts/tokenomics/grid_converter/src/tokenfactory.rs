@@ -0,0 +1,53 @@
+use cosmwasm_std::{Addr, CosmosMsg, Uint128};
+use prost::Message;
+
+#[cfg(not(feature = "injective"))]
+const MSG_MINT_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+#[cfg(not(feature = "injective"))]
+const MSG_BURN_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+#[cfg(feature = "injective")]
+const MSG_MINT_TYPE_URL: &str = "/injective.tokenfactory.v1beta1.MsgMint";
+#[cfg(feature = "injective")]
+const MSG_BURN_TYPE_URL: &str = "/injective.tokenfactory.v1beta1.MsgBurn";
+
+#[derive(Clone, PartialEq, Message)]
+struct Coin {
+    #[prost(string, tag = "1")]
+    denom: String,
+    #[prost(string, tag = "2")]
+    amount: String,
+}
+
+/// Tokenfactory MsgMint and MsgBurn share the same layout.
+#[derive(Clone, PartialEq, Message)]
+struct MsgMintBurn {
+    #[prost(string, tag = "1")]
+    sender: String,
+    #[prost(message, optional, tag = "2")]
+    amount: Option<Coin>,
+}
+
+/// Builds a message that mints `amount` of `denom` to the sender which must be the denom admin.
+pub fn mint_msg(sender: &Addr, denom: &str, amount: Uint128) -> CosmosMsg {
+    stargate_msg(MSG_MINT_TYPE_URL, sender, denom, amount)
+}
+
+/// Builds a message that burns `amount` of `denom` held by the sender which must be the denom admin.
+pub fn burn_msg(sender: &Addr, denom: &str, amount: Uint128) -> CosmosMsg {
+    stargate_msg(MSG_BURN_TYPE_URL, sender, denom, amount)
+}
+
+fn stargate_msg(type_url: &str, sender: &Addr, denom: &str, amount: Uint128) -> CosmosMsg {
+    let msg = MsgMintBurn {
+        sender: sender.to_string(),
+        amount: Some(Coin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
@@ -0,0 +1,245 @@
+#![cfg(not(tarpaulin_include))]
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coins, to_binary, Addr, Uint128};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+
+use gridiron::grid_converter::{
+    ConversionStats, Cw20HookMsg, ExecuteMsg, InstantiateMsg, NativeMode, QueryMsg,
+};
+use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
+use gridiron_grid_converter::error::ContractError;
+
+const OWNER: &str = "owner";
+const ALICE: &str = "alice";
+const NATIVE_GRID: &str = "factory/owner/grid";
+
+fn mock_app() -> App {
+    App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(OWNER), coins(1_000, NATIVE_GRID))
+            .unwrap()
+    })
+}
+
+fn instantiate_contracts(app: &mut App) -> (Addr, Addr) {
+    let token_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_token::contract::execute,
+        gridiron_token::contract::instantiate,
+        gridiron_token::contract::query,
+    )));
+
+    let grid_token = app
+        .instantiate_contract(
+            token_code_id,
+            Addr::unchecked(OWNER),
+            &TokenInstantiateMsg {
+                name: "GRID".to_string(),
+                symbol: "GRID".to_string(),
+                decimals: 6,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: OWNER.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            },
+            &[],
+            "GRID",
+            None,
+        )
+        .unwrap();
+
+    let converter_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_grid_converter::contract::execute,
+        gridiron_grid_converter::contract::instantiate,
+        gridiron_grid_converter::contract::query,
+    )));
+
+    let converter = app
+        .instantiate_contract(
+            converter_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                owner: OWNER.to_string(),
+                cw20_grid: grid_token.to_string(),
+                native_grid: NATIVE_GRID.to_string(),
+                native_mode: NativeMode::Escrow {},
+            },
+            &[],
+            "GRID converter",
+            None,
+        )
+        .unwrap();
+
+    (grid_token, converter)
+}
+
+fn mint_cw20(app: &mut App, grid_token: &Addr, recipient: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        grid_token.clone(),
+        &Cw20ExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn convert_to_native(
+    app: &mut App,
+    grid_token: &Addr,
+    converter: &Addr,
+    sender: &str,
+    amount: u128,
+) -> AnyResult<AppResponse> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        grid_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: converter.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::Convert { receiver: None }).unwrap(),
+        },
+        &[],
+    )
+}
+
+fn query_cw20_balance(app: &App, grid_token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            grid_token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    res.balance
+}
+
+#[test]
+fn convert_both_directions_with_escrow() {
+    let mut app = mock_app();
+    let (grid_token, converter) = instantiate_contracts(&mut app);
+
+    mint_cw20(&mut app, &grid_token, ALICE, 500);
+
+    // The native escrow is empty
+    let err = convert_to_native(&mut app, &grid_token, &converter, ALICE, 100).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InsufficientEscrow {
+            denom: NATIVE_GRID.to_string(),
+            available: Uint128::zero(),
+            required: Uint128::new(100),
+        }
+    );
+
+    app.send_tokens(
+        Addr::unchecked(OWNER),
+        converter.clone(),
+        &coins(1_000, NATIVE_GRID),
+    )
+    .unwrap();
+
+    convert_to_native(&mut app, &grid_token, &converter, ALICE, 300).unwrap();
+
+    assert_eq!(
+        app.wrap()
+            .query_balance(ALICE, NATIVE_GRID)
+            .unwrap()
+            .amount
+            .u128(),
+        300
+    );
+    assert_eq!(query_cw20_balance(&app, &grid_token, ALICE).u128(), 200);
+    assert_eq!(
+        query_cw20_balance(&app, &grid_token, converter.as_str()).u128(),
+        300
+    );
+
+    // Convert part of the native GRID back
+    app.execute_contract(
+        Addr::unchecked(ALICE),
+        converter.clone(),
+        &ExecuteMsg::Convert { receiver: None },
+        &coins(120, NATIVE_GRID),
+    )
+    .unwrap();
+
+    assert_eq!(
+        app.wrap()
+            .query_balance(ALICE, NATIVE_GRID)
+            .unwrap()
+            .amount
+            .u128(),
+        180
+    );
+    assert_eq!(query_cw20_balance(&app, &grid_token, ALICE).u128(), 320);
+    assert_eq!(
+        app.wrap()
+            .query_balance(&converter, NATIVE_GRID)
+            .unwrap()
+            .amount
+            .u128(),
+        820
+    );
+
+    let stats: ConversionStats = app
+        .wrap()
+        .query_wasm_smart(&converter, &QueryMsg::Stats {})
+        .unwrap();
+    assert_eq!(
+        stats,
+        ConversionStats {
+            total_to_native: Uint128::new(300),
+            total_to_cw20: Uint128::new(120),
+        }
+    );
+}
+
+#[test]
+fn paused_direction() {
+    let mut app = mock_app();
+    let (grid_token, converter) = instantiate_contracts(&mut app);
+
+    mint_cw20(&mut app, &grid_token, ALICE, 500);
+    app.send_tokens(
+        Addr::unchecked(OWNER),
+        converter.clone(),
+        &coins(1_000, NATIVE_GRID),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        converter.clone(),
+        &ExecuteMsg::UpdateConfig {
+            cw20_to_native_enabled: None,
+            native_to_cw20_enabled: Some(false),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // CW20 to native still works
+    convert_to_native(&mut app, &grid_token, &converter, ALICE, 100).unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            converter.clone(),
+            &ExecuteMsg::Convert { receiver: None },
+            &coins(100, NATIVE_GRID),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DirectionPaused(NATIVE_GRID.to_string())
+    );
+}
@@ -248,6 +248,7 @@ impl Helper {
                 &vesting::InstantiateMsg {
                     owner: owner.to_string(),
                     vesting_token: grid.clone(),
+                    generator: None,
                 },
                 &[],
                 "Gridiron Vesting",
@@ -345,6 +346,7 @@ impl Helper {
                 generator_address: Some(generator.to_string()),
                 whitelist_code_id: None,
                 coin_registry_address: None,
+                max_combined_fee_bps: None,
             },
             &[],
         )
@@ -963,6 +965,8 @@ impl Helper {
             slippage_tolerance: None,
             auto_stake: Some(auto_stake),
             receiver: None,
+            deadline: None,
+            min_lp_to_receive: None,
         };
 
         self.app
@@ -1,26 +1,35 @@
 use crate::error::ContractError;
-use crate::state::{BRIDGES, CONFIG, OWNERSHIP_PROPOSAL};
+use crate::state::{
+    BRIDGES, CONFIG, CURRENT_EPOCH, DEFAULT_LIMIT, EPOCHS, FEE_SHARES, FEE_SHARE_WHITELIST,
+    MAX_LIMIT, OWNERSHIP_PROPOSAL, PAIR_COLLECTED, STAKING_CARRYOVER, SWAP_VENUES,
+};
 use std::cmp::min;
 
 use crate::migration::{migrate_from_v1, migrate_from_v120};
 
 use crate::utils::{
-    build_distribute_msg, build_send_msg, build_swap_msg, try_build_swap_msg,
-    update_second_receiver_cfg, validate_bridge, BRIDGES_EXECUTION_MAX_DEPTH,
+    build_distribute_msg, build_route, build_send_msg, build_swap_msg, build_venue_swap_msg,
+    format_route, simulate_route, sync_fee_share, try_build_swap_msg, update_second_receiver_cfg,
+    validate_bridge, validate_source_pair, validate_swap_venue, BRIDGES_EXECUTION_MAX_DEPTH,
     BRIDGES_INITIAL_DEPTH,
 };
-use gridiron::asset::{addr_opt_validate, Asset, AssetInfo};
+use gridiron::asset::{addr_opt_validate, native_asset, token_asset_info, Asset, AssetInfo};
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
-use gridiron::factory::UpdateAddr;
+use gridiron::events::DistributeEvent;
+use gridiron::factory::{ExecuteMsg as FactoryExecuteMsg, UpdateAddr};
 use gridiron::maker::{
-    AssetWithLimit, BalancesResponse, Config, ConfigResponse, ExecuteMsg, InstantiateMsg,
-    MigrateMsg, QueryMsg, SecondReceiverConfig, SecondReceiverParams,
+    AssetWithLimit, BalancesResponse, Config, ConfigResponse, EpochStats, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, PairCollectedResponse, PairFeeShareResponse, PendingFee,
+    PendingFeesResponse, QueryMsg, SecondReceiverConfig, SecondReceiverParams, SwapVenueInfo,
 };
+use gridiron::oracle::QueryMsg as OracleQueryMsg;
 use gridiron::pair::MAX_ALLOWED_SLIPPAGE;
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Addr, Attribute, Binary, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, SubMsg, Uint128, Uint64,
+    attr, entry_point, to_binary, wasm_execute, Addr, Attribute, Binary, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Storage, SubMsg, Uint128, Uint256,
+    Uint64,
 };
+use cw_storage_plus::Bound;
 use cw2::{get_contract_version, set_contract_version};
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
@@ -36,7 +45,7 @@ const DEFAULT_MAX_SPREAD: u64 = 5; // 5%
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -87,6 +96,9 @@ pub fn instantiate(
         governance_percent,
         max_spread,
         second_receiver_cfg: None,
+        epoch_length: msg.epoch_length.filter(|length| *length > 0),
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     update_second_receiver_cfg(deps.as_ref(), &mut cfg, &msg.second_receiver_params)?;
@@ -99,6 +111,10 @@ pub fn instantiate(
 
     CONFIG.save(deps.storage, &cfg)?;
 
+    if cfg.epoch_length.is_some() {
+        start_epoch(deps.storage, 1, env.block.time.seconds())?;
+    }
+
     let (second_fee_receiver, second_receiver_cut) = if let Some(SecondReceiverConfig {
         second_fee_receiver,
         second_receiver_cut,
@@ -135,6 +151,10 @@ pub fn instantiate(
         attr("max_spread", max_spread.to_string()),
         attr("second_fee_receiver", second_fee_receiver),
         attr("second_receiver_cut", second_receiver_cut),
+        attr(
+            "epoch_length",
+            cfg.epoch_length.unwrap_or_default().to_string(),
+        ),
     ]))
 }
 
@@ -151,6 +171,9 @@ pub fn instantiate(
 ///             governance_percent,
 ///             max_spread,
 ///             second_receiver_params,
+///             epoch_length,
+///             oracle_contract,
+///             max_staking_per_epoch,
 ///         }** Updates general contract settings stores in the [`Config`].
 ///
 /// * **ExecuteMsg::UpdateBridges { add, remove }** Adds or removes bridge assets used to swap fee tokens to GRID.
@@ -166,6 +189,14 @@ pub fn instantiate(
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
 ///
 /// * **ExecuteMsg::EnableRewards** Enables collected GRID (pre Maker upgrade) to be distributed to xGRID stakers.
+///
+/// * **ExecuteMsg::SyncFeeShares { pairs }** Syncs the fee share configs of the specified pairs.
+///
+/// * **ExecuteMsg::UpdateFeeShareWhitelist { add, remove }** Adds or removes whitelisted fee share recipients.
+///
+/// * **ExecuteMsg::DisableFeeShare { pair }** Disables fee sharing in a pair with a non-whitelisted recipient.
+///
+/// * **ExecuteMsg::UpdateSwapVenues { add, remove }** Adds or removes the swap venues of fee tokens.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -183,8 +214,12 @@ pub fn execute(
             basic_asset,
             max_spread,
             second_receiver_params,
+            epoch_length,
+            oracle_contract,
+            max_staking_per_epoch,
         } => update_config(
             deps,
+            env,
             info,
             factory_contract,
             staking_contract,
@@ -193,6 +228,9 @@ pub fn execute(
             basic_asset,
             max_spread,
             second_receiver_params,
+            epoch_length,
+            oracle_contract,
+            max_staking_per_epoch,
         ),
         ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, info, add, remove),
         ExecuteMsg::SwapBridgeAssets { assets, depth } => {
@@ -256,10 +294,135 @@ pub fn execute(
 
             Ok(Response::default().add_attribute("action", "enable_rewards"))
         }
+        ExecuteMsg::SyncFeeShares { pairs } => sync_fee_shares(deps, env, pairs),
+        ExecuteMsg::UpdateFeeShareWhitelist { add, remove } => {
+            update_fee_share_whitelist(deps, info, add, remove)
+        }
+        ExecuteMsg::DisableFeeShare { pair } => disable_fee_share(deps, env, info, pair),
+        ExecuteMsg::UpdateSwapVenues { add, remove } => update_swap_venues(deps, info, add, remove),
+    }
+}
+
+/// Syncs the fee share configs of the given pairs with the fee share registry.
+///
+/// * **pairs** pair contract addresses.
+fn sync_fee_shares(
+    mut deps: DepsMut,
+    env: Env,
+    pairs: Vec<String>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let mut attributes = vec![attr("action", "sync_fee_shares")];
+    for pair in pairs {
+        let pair = deps.api.addr_validate(&pair)?;
+        let recipient = sync_fee_share(deps.branch(), &env, &cfg, &pair)?
+            .map(|info| info.fee_share.recipient.to_string())
+            .unwrap_or_else(|| String::from("none"));
+        attributes.push(attr(pair, recipient));
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+/// Adds or removes fee share recipients approved by governance.
+///
+/// * **add** recipients to whitelist.
+///
+/// * **remove** recipients to remove from the whitelist.
+fn update_fee_share_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut whitelist = FEE_SHARE_WHITELIST
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    if let Some(remove) = remove {
+        let remove = remove
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect::<StdResult<HashSet<_>>>()?;
+        whitelist.retain(|addr| !remove.contains(addr));
+    }
+
+    if let Some(add) = add {
+        for addr in add {
+            let addr = deps.api.addr_validate(&addr)?;
+            if !whitelist.contains(&addr) {
+                whitelist.push(addr);
+            }
+        }
+    }
+
+    FEE_SHARE_WHITELIST.save(deps.storage, &whitelist)?;
+
+    Ok(Response::default().add_attribute("action", "update_fee_share_whitelist"))
+}
+
+/// Disables fee sharing in a pair via the factory if the fee share recipient is not whitelisted.
+/// The pair config is synced first so the decision is made on the current recipient.
+///
+/// * **pair** pair contract address.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn disable_fee_share(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair = deps.api.addr_validate(&pair)?;
+
+    let fee_share_info = sync_fee_share(deps.branch(), &env, &cfg, &pair)?
+        .ok_or_else(|| ContractError::FeeShareNotEnabled(pair.to_string()))?;
+
+    let whitelist = FEE_SHARE_WHITELIST
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if whitelist.contains(&fee_share_info.fee_share.recipient) {
+        return Err(ContractError::FeeShareRecipientWhitelisted(
+            fee_share_info.fee_share.recipient.to_string(),
+        ));
     }
+
+    FEE_SHARES.remove(deps.storage, &pair);
+
+    let msg = wasm_execute(
+        &cfg.factory_contract,
+        &FactoryExecuteMsg::DisablePairFeeShare {
+            asset_infos: fee_share_info.asset_infos,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::default().add_message(msg).add_attributes([
+        attr("action", "disable_fee_share"),
+        attr("pair", pair),
+        attr("fee_share_recipient", fee_share_info.fee_share.recipient),
+    ]))
 }
 
 /// Swaps fee tokens to GRID and distribute the resulting GRID to xGRID and vxGRID stakers.
+/// If epochs are enabled, swapped fee tokens are recorded in the current epoch and GRID is only
+/// distributed once the epoch is over.
 ///
 /// * **assets** array with fee tokens being swapped to GRID.
 fn collect(
@@ -281,8 +444,24 @@ fn collect(
         return Err(ContractError::DuplicatedAsset {});
     }
 
+    // Validate the pairs the fee tokens are collected from
+    let mut source_pairs = HashMap::new();
+    for a in &assets {
+        if let Some(pair) = &a.pair {
+            let pair = validate_source_pair(deps.as_ref(), &cfg, pair, &a.info)?;
+            source_pairs.insert(a.info.to_string(), pair);
+        }
+    }
+
+    // GRID is collected as is
+    if let Some(pair) = source_pairs.get(&grid.to_string()) {
+        let grid_asset = assets.iter().find(|a| a.info == grid).unwrap();
+        let amount = limited_balance(deps.as_ref(), &env.contract.address, grid_asset)?;
+        record_pair_collected(deps.storage, pair, amount)?;
+    }
+
     // Swap all non GRID tokens
-    let (mut response, bridge_assets) = swap_assets(
+    let (mut response, bridge_assets, swapped_assets) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &cfg,
@@ -290,6 +469,21 @@ fn collect(
         true,
     )?;
 
+    for (asset, grid_amount) in &swapped_assets {
+        if let (Some(pair), Some(grid_amount)) =
+            (source_pairs.get(&asset.info.to_string()), grid_amount)
+        {
+            record_pair_collected(deps.storage, pair, *grid_amount)?;
+        }
+    }
+
+    if cfg.epoch_length.is_some() {
+        record_collected(
+            deps.storage,
+            swapped_assets.into_iter().map(|(asset, _)| asset).collect(),
+        )?;
+    }
+
     // If no swap messages - send GRID directly to x/vxGRID stakers
     if response.messages.is_empty() {
         let (mut distribute_msg, attributes) = distribute(deps, env, &mut cfg)?;
@@ -314,6 +508,17 @@ enum SwapTarget {
     Bridge { asset: AssetInfo, msg: SubMsg },
 }
 
+impl SwapTarget {
+    /// Returns the swap target of a swap to the given asset.
+    fn new(grid: &AssetInfo, asset: AssetInfo, msg: SubMsg) -> Self {
+        if asset.eq(grid) {
+            SwapTarget::Grid(msg)
+        } else {
+            SwapTarget::Bridge { asset, msg }
+        }
+    }
+}
+
 /// Swap all non GRID tokens to GRID.
 ///
 /// * **contract_addr** maker contract address.
@@ -321,29 +526,37 @@ enum SwapTarget {
 /// * **assets** array with assets to swap to GRID.
 ///
 /// * **with_validation** whether the swap operation should be validated or not.
+///
+/// Returns the swap messages, the bridge assets to swap next and the swapped fee tokens together
+/// with their simulated GRID value. The value is only simulated when the swap is validated.
+#[allow(clippy::type_complexity)]
 fn swap_assets(
     deps: Deps,
     contract_addr: &Addr,
     cfg: &Config,
     assets: Vec<AssetWithLimit>,
     with_validation: bool,
-) -> Result<(Response, Vec<AssetInfo>), ContractError> {
+) -> Result<(Response, Vec<AssetInfo>, Vec<(Asset, Option<Uint128>)>), ContractError> {
     let mut response = Response::default();
     let mut bridge_assets = HashMap::new();
+    let mut swapped_assets = vec![];
 
     for a in assets {
-        // Get balance
-        let mut balance = a.info.query_pool(&deps.querier, contract_addr)?;
-        if let Some(limit) = a.limit {
-            if limit < balance && limit > Uint128::zero() {
-                balance = limit;
-            }
-        }
+        let balance = limited_balance(deps, contract_addr, &a)?;
 
         if !balance.is_zero() {
+            let asset = Asset {
+                info: a.info.clone(),
+                amount: balance,
+            };
+
             let swap_msg = if with_validation {
-                swap(deps, cfg, a.info, balance)?
+                let (target, route, grid_amount) = swap(deps, cfg, a.info, balance)?;
+                response = response.add_attribute("swap_route", format_route(&route));
+                swapped_assets.push((asset, grid_amount));
+                target
             } else {
+                swapped_assets.push((asset, None));
                 swap_no_validate(deps, cfg, a.info, balance)?
             };
 
@@ -359,20 +572,69 @@ fn swap_assets(
         }
     }
 
-    Ok((response, bridge_assets.into_values().collect()))
+    Ok((
+        response,
+        bridge_assets.into_values().collect(),
+        swapped_assets,
+    ))
+}
+
+/// Returns the balance of a fee token capped by its limit.
+fn limited_balance(deps: Deps, contract_addr: &Addr, asset: &AssetWithLimit) -> StdResult<Uint128> {
+    let mut balance = asset.info.query_pool(&deps.querier, contract_addr)?;
+    if let Some(limit) = asset.limit {
+        if limit < balance && limit > Uint128::zero() {
+            balance = limit;
+        }
+    }
+
+    Ok(balance)
 }
 
 /// Checks if all required pools and bridges exists and performs a swap operation to GRID.
+/// A swap venue configured for the token is always used. Otherwise every available route
+/// (registered bridge, default bridge, direct pool) is simulated and the one with the highest
+/// GRID output is used.
 ///
 /// * **from_token** token to swap to GRID.
 ///
 /// * **amount_in** amount of tokens to swap.
+///
+/// Returns the swap target, the route and the simulated GRID output of the route.
+#[allow(clippy::type_complexity)]
 fn swap(
     deps: Deps,
     cfg: &Config,
     from_token: AssetInfo,
     amount_in: Uint128,
-) -> Result<SwapTarget, ContractError> {
+) -> Result<(SwapTarget, Vec<AssetInfo>, Option<Uint128>), ContractError> {
+    // 0. Check if a swap venue is configured by governance
+    if let Some(venue_info) = SWAP_VENUES.may_load(deps.storage, from_token.to_string())? {
+        let msg = build_venue_swap_msg(cfg.max_spread, &venue_info, amount_in)?;
+        let route = build_route(
+            deps,
+            &cfg.grid_token,
+            &from_token,
+            &venue_info.ask_asset_info,
+        );
+        let return_amount = simulate_route(
+            deps,
+            &cfg.factory_contract,
+            &cfg.grid_token,
+            &route,
+            amount_in,
+        )
+        .ok();
+
+        return Ok((
+            SwapTarget::new(&cfg.grid_token, venue_info.ask_asset_info, msg),
+            route,
+            return_amount,
+        ));
+    }
+
+    let mut candidates = vec![];
+
     // 1. Check if bridge tokens exist
     let bridge_token = BRIDGES.load(deps.storage, from_token.to_string());
     if let Ok(bridge_token) = bridge_token {
@@ -387,12 +649,12 @@ fn swap(
 
         let msg = build_swap_msg(
             cfg.max_spread,
-            &bridge_pool,
+            bridge_pool.contract_addr.as_str(),
             &from_token,
             Some(&bridge_token),
             amount_in,
         )?;
-        return Ok(SwapTarget::Bridge {
+        candidates.push(SwapTarget::Bridge {
             asset: bridge_token,
             msg,
         });
@@ -400,11 +662,14 @@ fn swap(
 
     // 2. Check for a pair with a default bridge
     if let Some(default_bridge) = &cfg.default_bridge {
-        if from_token.ne(default_bridge) {
+        let already_added = candidates.iter().any(
+            |target| matches!(target, SwapTarget::Bridge { asset, .. } if asset == default_bridge),
+        );
+        if from_token.ne(default_bridge) && !already_added {
             let swap_to_default =
                 try_build_swap_msg(&deps.querier, cfg, &from_token, default_bridge, amount_in);
             if let Ok(msg) = swap_to_default {
-                return Ok(SwapTarget::Bridge {
+                candidates.push(SwapTarget::Bridge {
                     asset: default_bridge.clone(),
                     msg,
                 });
@@ -416,10 +681,34 @@ fn swap(
     let swap_to_grid =
         try_build_swap_msg(&deps.querier, cfg, &from_token, &cfg.grid_token, amount_in);
     if let Ok(msg) = swap_to_grid {
-        return Ok(SwapTarget::Grid(msg));
+        candidates.push(SwapTarget::Grid(msg));
     }
 
-    Err(ContractError::CannotSwap(from_token))
+    // Pick the route with the highest simulated GRID output. Routes that can't be simulated
+    // are only used if nothing else is available, ties keep the priority order above.
+    let mut best: Option<(SwapTarget, Vec<AssetInfo>, Option<Uint128>)> = None;
+    for target in candidates {
+        let first_hop = match &target {
+            SwapTarget::Grid(_) => &cfg.grid_token,
+            SwapTarget::Bridge { asset, .. } => asset,
+        };
+        let route = build_route(deps, &cfg.grid_token, &from_token, first_hop);
+        let return_amount = simulate_route(
+            deps,
+            &cfg.factory_contract,
+            &cfg.grid_token,
+            &route,
+            amount_in,
+        )
+        .ok();
+
+        match &best {
+            Some((_, _, best_amount)) if return_amount <= *best_amount => {}
+            _ => best = Some((target, route, return_amount)),
+        }
+    }
+
+    best.ok_or(ContractError::CannotSwap(from_token))
 }
 
 /// Performs a swap operation to GRID without additional checks.
@@ -436,6 +725,12 @@ fn swap_no_validate(
 ) -> Result<SwapTarget, ContractError> {
     let grid = cfg.grid_token.clone();
 
+    // Check if a swap venue is configured
+    if let Some(venue_info) = SWAP_VENUES.may_load(deps.storage, from_token.to_string())? {
+        let msg = build_venue_swap_msg(cfg.max_spread, &venue_info, amount_in)?;
+        return Ok(SwapTarget::new(&grid, venue_info.ask_asset_info, msg));
+    }
+
     // Check if next level bridge exists
     let bridge_token = BRIDGES.load(deps.storage, from_token.to_string());
     if let Ok(asset) = bridge_token {
@@ -487,10 +782,11 @@ fn swap_bridge_assets(
         .map(|a| AssetWithLimit {
             info: a,
             limit: None,
+            pair: None,
         })
         .collect();
 
-    let (response, bridge_assets) =
+    let (response, bridge_assets, _) =
         swap_assets(deps.as_ref(), &env.contract.address, &cfg, bridges, false)?;
 
     // There should always be some messages, if there are none - something went wrong
@@ -527,21 +823,93 @@ fn distribute_grid(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
 
 type DistributeMsgParts = (Vec<SubMsg>, Vec<Attribute>);
 
+/// This structure holds the amounts of GRID sent to each receiver during a distribution.
+#[derive(Default)]
+struct DistributionAmounts {
+    to_staking: Uint128,
+    to_governance: Uint128,
+    to_second_receiver: Uint128,
+    staking_carryover: Uint128,
+}
+
 /// Private function that performs the GRID token distribution to x/vxGRID.
+/// If epochs are enabled, GRID is only distributed once the current epoch is over. The epoch
+/// is then rolled over and GRID left in the contract is carried over to the next epoch. GRID
+/// above the per-epoch staking cap is also carried over and sent to xGRID stakers later.
 fn distribute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     cfg: &mut Config,
 ) -> Result<DistributeMsgParts, ContractError> {
+    let epoch_length = match cfg.epoch_length {
+        Some(epoch_length) => epoch_length,
+        None => {
+            let (result, attributes, _) = distribute_balance(deps, &env, cfg, None)?;
+            return Ok((result, attributes));
+        }
+    };
+
+    let now = env.block.time.seconds();
+    let mut epoch = load_current_epoch(deps.storage)?;
+    if now < epoch.start_time.saturating_add(epoch_length) {
+        return Ok((vec![], vec![]));
+    }
+
+    let balance = cfg
+        .grid_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    let staking_cap = cfg.max_staking_per_epoch;
+    let (result, mut attributes, amounts) =
+        distribute_balance(deps.branch(), &env, cfg, staking_cap)?;
+
+    epoch.end_time = Some(now);
+    epoch.to_staking = amounts.to_staking;
+    epoch.to_governance = amounts.to_governance;
+    epoch.to_second_receiver = amounts.to_second_receiver;
+    epoch.distributed = amounts.to_staking + amounts.to_governance + amounts.to_second_receiver;
+    epoch.carryover = balance.checked_sub(epoch.distributed)?;
+    epoch.staking_carryover = amounts.staking_carryover;
+    EPOCHS.save(deps.storage, epoch.epoch, &epoch)?;
+
+    start_epoch(deps.storage, epoch.epoch + 1, now)?;
+
+    attributes.extend([
+        attr("action", "epoch_rollover"),
+        attr("epoch", epoch.epoch.to_string()),
+        attr("distributed", epoch.distributed),
+        attr("carryover", epoch.carryover),
+        attr("staking_carryover", epoch.staking_carryover),
+    ]);
+
+    Ok((result, attributes))
+}
+
+/// Distributes the GRID balance of the contract between the second fee receiver, x/vxGRID.
+/// GRID carried over by the staking cap goes to xGRID stakers only.
+///
+/// * **staking_cap** maximum amount of GRID sent to xGRID stakers. The excess is carried over.
+fn distribute_balance(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &mut Config,
+    staking_cap: Option<Uint128>,
+) -> Result<(Vec<SubMsg>, Vec<Attribute>, DistributionAmounts), ContractError> {
     let mut result = vec![];
     let mut attributes = vec![];
+    let mut amounts = DistributionAmounts::default();
 
     let mut amount = cfg
         .grid_token
         .query_pool(&deps.querier, &env.contract.address)?;
     if amount.is_zero() {
-        return Ok((result, attributes));
+        return Ok((result, attributes, amounts));
     }
+
+    // GRID withheld from xGRID stakers isn't split again
+    let staking_carryover = STAKING_CARRYOVER
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    amount = amount.saturating_sub(staking_carryover);
     let mut pure_grid_reward = amount;
     let mut current_preupgrade_distribution = Uint128::zero();
 
@@ -549,11 +917,11 @@ fn distribute(
         cfg.pre_upgrade_grid_amount = amount;
         cfg.remainder_reward = amount;
         CONFIG.save(deps.storage, cfg)?;
-        return Ok((result, attributes));
+        return Ok((result, attributes, amounts));
     } else if !cfg.remainder_reward.is_zero() {
         let blocks_passed = env.block.height - cfg.last_distribution_block;
         if blocks_passed == 0 {
-            return Ok((result, attributes));
+            return Ok((result, attributes, amounts));
         }
         let mut remainder_reward = cfg.remainder_reward;
         let grid_distribution_portion = cfg
@@ -624,7 +992,13 @@ fn distribute(
     };
 
     if let Some(staking_contract) = &cfg.staking_contract {
-        let amount = amount.checked_sub(governance_amount + second_receiver_amount)?;
+        let due = amount
+            .checked_sub(governance_amount + second_receiver_amount)?
+            .checked_add(staking_carryover)?;
+        let amount = staking_cap.map_or(due, |cap| due.min(cap));
+        amounts.staking_carryover = due - amount;
+        STAKING_CARRYOVER.save(deps.storage, &amounts.staking_carryover)?;
+
         if !amount.is_zero() {
             let to_staking_asset = Asset {
                 info: cfg.grid_token.clone(),
@@ -632,20 +1006,76 @@ fn distribute(
             };
             result.push(SubMsg::new(to_staking_asset.into_msg(staking_contract)?));
         }
+        amounts.to_staking = amount;
     }
 
-    attributes = vec![
-        attr("action", "distribute_grid"),
-        attr("grid_distribution", pure_grid_reward),
-    ];
-    if !current_preupgrade_distribution.is_zero() {
-        attributes.push(attr(
-            "preupgrade_grid_distribution",
-            current_preupgrade_distribution,
-        ));
+    amounts.to_governance = governance_amount;
+    amounts.to_second_receiver = second_receiver_amount;
+
+    attributes = DistributeEvent {
+        grid_distribution: pure_grid_reward,
+        preupgrade_grid_distribution: current_preupgrade_distribution,
     }
+    .into_attributes();
 
-    Ok((result, attributes))
+    Ok((result, attributes, amounts))
+}
+
+/// Starts a new distribution epoch at the given time.
+fn start_epoch(storage: &mut dyn Storage, epoch: u64, start_time: u64) -> StdResult<()> {
+    EPOCHS.save(
+        storage,
+        epoch,
+        &EpochStats {
+            epoch,
+            start_time,
+            end_time: None,
+            collected: vec![],
+            distributed: Uint128::zero(),
+            to_staking: Uint128::zero(),
+            to_governance: Uint128::zero(),
+            to_second_receiver: Uint128::zero(),
+            carryover: Uint128::zero(),
+            staking_carryover: Uint128::zero(),
+        },
+    )?;
+    CURRENT_EPOCH.save(storage, &epoch)
+}
+
+/// Loads the accounting of the current distribution epoch.
+fn load_current_epoch(storage: &dyn Storage) -> StdResult<EpochStats> {
+    let epoch = CURRENT_EPOCH.load(storage)?;
+    EPOCHS.load(storage, epoch)
+}
+
+/// Adds the GRID value of collected fees to the total of the pair they were collected from.
+fn record_pair_collected(storage: &mut dyn Storage, pair: &Addr, amount: Uint128) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    PAIR_COLLECTED.update(storage, pair, |collected| -> StdResult<_> {
+        Ok(collected.unwrap_or_default().checked_add(amount)?)
+    })?;
+
+    Ok(())
+}
+
+/// Adds swapped fee tokens to the totals of the current distribution epoch.
+fn record_collected(storage: &mut dyn Storage, assets: Vec<Asset>) -> StdResult<()> {
+    if assets.is_empty() {
+        return Ok(());
+    }
+
+    let mut epoch = load_current_epoch(storage)?;
+    for asset in assets {
+        match epoch.collected.iter_mut().find(|a| a.info == asset.info) {
+            Some(collected) => collected.amount += asset.amount,
+            None => epoch.collected.push(asset),
+        }
+    }
+
+    EPOCHS.save(storage, epoch.epoch, &epoch)
 }
 
 /// Updates general contract parameters.
@@ -664,11 +1094,19 @@ fn distribute(
 ///
 /// * **second_receiver_params** describes the second receiver of fees
 ///
+/// * **epoch_length** length of a distribution epoch in seconds. Zero disables epochs.
+///
+/// * **oracle_contract** address of the oracle contract used to value pending fees in USD.
+///
+/// * **max_staking_per_epoch** maximum amount of GRID sent to xGRID stakers per epoch. Zero
+/// removes the cap.
+///
 /// ## Executor
 /// Only the owner can execute this.
 #[allow(clippy::too_many_arguments)]
 fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     factory_contract: Option<String>,
     staking_contract: Option<String>,
@@ -677,6 +1115,9 @@ fn update_config(
     default_bridge_opt: Option<AssetInfo>,
     max_spread: Option<Decimal>,
     second_receiver_params: Option<SecondReceiverParams>,
+    epoch_length: Option<u64>,
+    oracle_contract: Option<UpdateAddr>,
+    max_staking_per_epoch: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let mut attributes = vec![attr("action", "set_config")];
 
@@ -756,6 +1197,37 @@ fn update_config(
         ));
     }
 
+    if let Some(epoch_length) = epoch_length {
+        if epoch_length == 0 {
+            config.epoch_length = None;
+        } else {
+            // An open epoch left from previously enabled epochs continues
+            if CURRENT_EPOCH.may_load(deps.storage)?.is_none() {
+                start_epoch(deps.storage, 1, env.block.time.seconds())?;
+            }
+            config.epoch_length = Some(epoch_length);
+        }
+        attributes.push(attr("epoch_length", epoch_length.to_string()));
+    }
+
+    if let Some(action) = oracle_contract {
+        match action {
+            UpdateAddr::Set(oracle) => {
+                config.oracle_contract = Some(deps.api.addr_validate(&oracle)?);
+                attributes.push(attr("oracle_contract", &oracle));
+            }
+            UpdateAddr::Remove {} => {
+                config.oracle_contract = None;
+                attributes.push(attr("oracle_contract", "removed"));
+            }
+        }
+    }
+
+    if let Some(max_staking_per_epoch) = max_staking_per_epoch {
+        config.max_staking_per_epoch = Some(max_staking_per_epoch).filter(|cap| !cap.is_zero());
+        attributes.push(attr("max_staking_per_epoch", max_staking_per_epoch));
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(attributes))
@@ -817,6 +1289,39 @@ fn update_bridges(
     Ok(Response::default().add_attribute("action", "update_bridges"))
 }
 
+/// Adds or removes the swap venues used to convert fee tokens.
+///
+/// * **add** array of swap venues to configure. An existing venue of a fee token is replaced.
+///
+/// * **remove** array of fee tokens whose swap venues are removed.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_swap_venues(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<SwapVenueInfo>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for asset in remove.unwrap_or_default() {
+        SWAP_VENUES.remove(deps.storage, asset.to_string());
+    }
+
+    for venue_info in add.unwrap_or_default() {
+        let venue_info = validate_swap_venue(deps.as_ref(), &cfg, venue_info)?;
+        SWAP_VENUES.save(deps.storage, venue_info.asset_info.to_string(), &venue_info)?;
+    }
+
+    Ok(Response::default().add_attribute("action", "update_swap_venues"))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -827,12 +1332,46 @@ fn update_bridges(
 ///
 /// * **QueryMsg::Bridges {}** Returns the bridges used for swapping fee tokens
 /// using a vector of [`(String, String)`] denoting Asset -> Bridge connections.
+///
+/// * **QueryMsg::FeeShares { start_after, limit }** Returns the synced fee share configs
+/// using a vector of [`PairFeeShareResponse`] objects.
+///
+/// * **QueryMsg::FeeShareWhitelist {}** Returns the whitelisted fee share recipients.
+///
+/// * **QueryMsg::Epoch { epoch }** Returns the accounting of a distribution epoch
+/// using an [`EpochStats`] object.
+///
+/// * **QueryMsg::Epochs { start_after, limit }** Returns the accounting of distribution epochs
+/// using a vector of [`EpochStats`] objects.
+///
+/// * **QueryMsg::SwapVenues {}** Returns the swap venues of fee tokens
+/// using a vector of [`SwapVenueInfo`] objects.
+///
+/// * **QueryMsg::PendingFees {}** Returns the fees awaiting distribution
+/// using a [`PendingFeesResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_get_config(deps)?),
         QueryMsg::Balances { assets } => to_binary(&query_get_balances(deps, env, assets)?),
         QueryMsg::Bridges {} => to_binary(&query_bridges(deps)?),
+        QueryMsg::FeeShares { start_after, limit } => {
+            to_binary(&query_fee_shares(deps, start_after, limit)?)
+        }
+        QueryMsg::FeeShareWhitelist {} => to_binary(
+            &FEE_SHARE_WHITELIST
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Epoch { epoch } => to_binary(&query_epoch(deps, epoch)?),
+        QueryMsg::Epochs { start_after, limit } => {
+            to_binary(&query_epochs(deps, start_after, limit)?)
+        }
+        QueryMsg::CollectedByPair { start_after, limit } => {
+            to_binary(&query_collected_by_pair(deps, start_after, limit)?)
+        }
+        QueryMsg::SwapVenues {} => to_binary(&query_swap_venues(deps)?),
+        QueryMsg::PendingFees {} => to_binary(&query_pending_fees(deps, env)?),
     }
 }
 
@@ -851,6 +1390,12 @@ fn query_get_config(deps: Deps) -> StdResult<ConfigResponse> {
         pre_upgrade_grid_amount: config.pre_upgrade_grid_amount,
         default_bridge: config.default_bridge,
         second_receiver_cfg: config.second_receiver_cfg,
+        epoch_length: config.epoch_length,
+        oracle_contract: config.oracle_contract,
+        max_staking_per_epoch: config.max_staking_per_epoch,
+        staking_carryover: STAKING_CARRYOVER
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
     })
 }
 
@@ -885,6 +1430,210 @@ fn query_bridges(deps: Deps) -> StdResult<Vec<(String, String)>> {
         .collect()
 }
 
+/// Returns the swap venues configured for fee tokens.
+fn query_swap_venues(deps: Deps) -> StdResult<Vec<SwapVenueInfo>> {
+    SWAP_VENUES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// Returns the fees awaiting distribution in the Maker using a [`PendingFeesResponse`] object.
+/// Native fee tokens are read from the Maker's balances. Cw20 fee tokens are looked up among
+/// the assets used in bridges, swap venues and synced fee shares.
+fn query_pending_fees(deps: Deps, env: Env) -> StdResult<PendingFeesResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let mut assets: Vec<Asset> = deps
+        .querier
+        .query_all_balances(&env.contract.address)?
+        .into_iter()
+        .map(|coin| native_asset(coin.denom, coin.amount))
+        .collect();
+
+    let mut tokens = vec![cfg.grid_token.clone()];
+    tokens.extend(cfg.default_bridge.clone());
+    for item in BRIDGES.range(deps.storage, None, None, Order::Ascending) {
+        let (from, bridge) = item?;
+        // Bridges are keyed by the fee token's string representation
+        if let Ok(contract_addr) = deps.api.addr_validate(&from) {
+            tokens.push(token_asset_info(contract_addr));
+        }
+        tokens.push(bridge);
+    }
+    for item in SWAP_VENUES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, venue_info) = item?;
+        tokens.push(venue_info.asset_info);
+        tokens.push(venue_info.ask_asset_info);
+    }
+    for item in FEE_SHARES.range(deps.storage, None, None, Order::Ascending) {
+        tokens.extend(item?.1.asset_infos);
+    }
+
+    for info in tokens {
+        if info.is_native_token() || assets.iter().any(|asset| asset.info == info) {
+            continue;
+        }
+        // Addresses which are not cw20 contracts fail the balance query and are skipped
+        if let Ok(amount) = info.query_pool(&deps.querier, &env.contract.address) {
+            if !amount.is_zero() {
+                assets.push(Asset { info, amount });
+            }
+        }
+    }
+
+    let mut total_grid_amount = Uint128::zero();
+    let fees = assets
+        .into_iter()
+        .map(|asset| {
+            let (route, grid_amount) = if asset.info == cfg.grid_token {
+                (vec![cfg.grid_token.clone()], Some(asset.amount))
+            } else {
+                swap(deps, &cfg, asset.info.clone(), asset.amount)
+                    .map(|(_, route, grid_amount)| (route, grid_amount))
+                    .unwrap_or_default()
+            };
+            total_grid_amount += grid_amount.unwrap_or_default();
+
+            PendingFee {
+                usd_value: grid_amount.and_then(|amount| query_usd_value(deps, &cfg, amount)),
+                asset,
+                route,
+                grid_amount,
+            }
+        })
+        .collect();
+
+    Ok(PendingFeesResponse {
+        fees,
+        total_grid_amount,
+        total_usd_value: query_usd_value(deps, &cfg, total_grid_amount),
+    })
+}
+
+/// Returns the USD value of the specified GRID amount according to the oracle contract.
+/// Returns None if the oracle contract is not configured or can't value GRID.
+///
+/// * **grid_amount** amount of GRID to value.
+fn query_usd_value(deps: Deps, cfg: &Config, grid_amount: Uint128) -> Option<Uint128> {
+    let oracle_contract = cfg.oracle_contract.as_ref()?;
+    let prices: Vec<(AssetInfo, Uint256)> = deps
+        .querier
+        .query_wasm_smart(
+            oracle_contract,
+            &OracleQueryMsg::Consult {
+                token: cfg.grid_token.clone(),
+                amount: grid_amount,
+            },
+        )
+        .ok()?;
+
+    prices
+        .first()
+        .and_then(|(_, value)| Uint128::try_from(*value).ok())
+}
+
+/// Returns the fee share configs stored in the registry.
+///
+/// * **start_after** the pair address to start reading from.
+///
+/// * **limit** the number of items to read.
+fn query_fee_shares(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairFeeShareResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let whitelist = FEE_SHARE_WHITELIST
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    FEE_SHARES
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (pair, info) = item?;
+            Ok(PairFeeShareResponse {
+                pair,
+                whitelisted: whitelist.contains(&info.fee_share.recipient),
+                info,
+            })
+        })
+        .collect()
+}
+
+/// Returns the accounting of a distribution epoch.
+///
+/// * **epoch** the epoch number. The current epoch is returned if not specified.
+fn query_epoch(deps: Deps, epoch: Option<u64>) -> StdResult<EpochStats> {
+    match epoch {
+        Some(epoch) => EPOCHS.load(deps.storage, epoch),
+        None => load_current_epoch(deps.storage),
+    }
+}
+
+/// Returns the accounting of distribution epochs.
+///
+/// * **start_after** the epoch to start reading from.
+///
+/// * **limit** the number of items to read.
+fn query_epochs(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<EpochStats>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    EPOCHS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// Returns the GRID value of the fees collected from each pair.
+///
+/// * **start_after** the pair address to start reading from.
+///
+/// * **limit** the number of items to read.
+fn query_collected_by_pair(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairCollectedResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    PAIR_COLLECTED
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (pair, grid_amount) = item?;
+            Ok(PairCollectedResponse { pair, grid_amount })
+        })
+        .collect()
+}
+
 /// Manages contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
@@ -896,7 +1645,7 @@ pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response
                 migrate_from_v1(deps.branch(), &msg)?;
             }
             "1.2.0" => migrate_from_v120(deps.branch(), msg)?,
-            "1.3.0" => {}
+            "1.3.0" | "1.3.1" | "1.4.0" | "1.5.0" | "1.6.0" | "1.7.0" | "1.8.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -41,6 +41,21 @@ pub enum ContractError {
     #[error("Rewards collecting is already enabled")]
     RewardsAlreadyEnabled {},
 
+    #[error("Pair {0} is not registered in the factory")]
+    PairNotRegistered(String),
+
+    #[error("Pair {pair} doesn't hold {asset}")]
+    AssetNotInPair { asset: String, pair: String },
+
+    #[error("Fee sharing is not enabled in pair {0}")]
+    FeeShareNotEnabled(String),
+
+    #[error("Fee share recipient {0} is whitelisted")]
+    FeeShareRecipientWhitelisted(String),
+
+    #[error("Invalid swap venue for {0}: {1}")]
+    InvalidSwapVenue(String, String),
+
     #[error("An error occurred during migration")]
     MigrationError {},
 }
@@ -44,6 +44,9 @@ pub(crate) fn migrate_from_v1(deps: DepsMut, msg: &MigrateMsg) -> StdResult<()>
         remainder_reward: old_config.remainder_reward,
         pre_upgrade_grid_amount: old_config.pre_upgrade_grid_amount,
         second_receiver_cfg: None,
+        epoch_length: None,
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     update_second_receiver_cfg(deps.as_ref(), &mut new_config, &msg.second_receiver_params)?;
@@ -85,6 +88,9 @@ pub(crate) fn migrate_from_v120(deps: DepsMut, msg: MigrateMsg) -> StdResult<()>
         remainder_reward: cfg_v120.remainder_reward,
         pre_upgrade_grid_amount: cfg_v120.pre_upgrade_grid_amount,
         second_receiver_cfg: None,
+        epoch_length: None,
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     update_second_receiver_cfg(deps.as_ref(), &mut new_config, &msg.second_receiver_params)?;
@@ -1,6 +1,7 @@
 use gridiron::asset::AssetInfo;
 use gridiron::common::OwnershipProposal;
-use gridiron::maker::Config;
+use cosmwasm_std::{Addr, Uint128};
+use gridiron::maker::{Config, EpochStats, PairFeeShare, SwapVenueInfo};
 use cw_storage_plus::{Item, Map};
 
 /// Stores the contract configuration at the given key
@@ -11,3 +12,29 @@ pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_pro
 
 /// Stores bridge tokens used to swap fee tokens to GRID
 pub const BRIDGES: Map<String, AssetInfo> = Map::new("bridges");
+
+/// Stores the swap venues used to convert specific fee tokens
+pub const SWAP_VENUES: Map<String, SwapVenueInfo> = Map::new("swap_venues");
+
+/// Stores the fee share configs of pairs synced from the pair contracts
+pub const FEE_SHARES: Map<&Addr, PairFeeShare> = Map::new("fee_shares");
+
+/// Stores the fee share recipients approved by governance
+pub const FEE_SHARE_WHITELIST: Item<Vec<Addr>> = Item::new("fee_share_whitelist");
+
+/// Stores the accounting of distribution epochs
+pub const EPOCHS: Map<u64, EpochStats> = Map::new("epochs");
+
+/// Stores the GRID value of the fees collected from each pair
+pub const PAIR_COLLECTED: Map<&Addr, Uint128> = Map::new("pair_collected");
+
+/// Stores the number of the current distribution epoch
+pub const CURRENT_EPOCH: Item<u64> = Item::new("current_epoch");
+
+/// Stores the GRID withheld from xGRID stakers by the per-epoch cap
+pub const STAKING_CARRYOVER: Item<Uint128> = Item::new("staking_carryover");
+
+/// The default limit for fee share pagination
+pub const DEFAULT_LIMIT: u32 = 10;
+/// The maximum limit for fee share pagination
+pub const MAX_LIMIT: u32 = 30;
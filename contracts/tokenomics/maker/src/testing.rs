@@ -30,6 +30,7 @@ fn proper_initialization() {
         default_bridge: Some(native_asset_info("uluna".to_string())),
         max_spread: None,
         second_receiver_params: None,
+        epoch_length: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
     assert_eq!(0, res.messages.len());
@@ -51,7 +52,10 @@ fn proper_initialization() {
             last_distribution_block: 0,
             remainder_reward: Uint128::zero(),
             pre_upgrade_grid_amount: Uint128::zero(),
-            second_receiver_cfg: None
+            second_receiver_cfg: None,
+            epoch_length: None,
+            oracle_contract: None,
+            max_staking_per_epoch: None,
         }
     )
 }
@@ -78,6 +82,7 @@ fn update_owner() {
         default_bridge: Some(native_asset_info("uluna".to_string())),
         max_spread: None,
         second_receiver_params: None,
+        epoch_length: None,
     };
 
     let env = mock_env();
@@ -1,15 +1,23 @@
 use crate::error::ContractError;
-use crate::state::BRIDGES;
+use crate::state::{BRIDGES, FEE_SHARES, SWAP_VENUES};
 use gridiron::asset::{Asset, AssetInfo, PairInfo};
 use gridiron::maker::{
-    Config, ExecuteMsg, SecondReceiverConfig, SecondReceiverParams, MAX_SECOND_RECEIVER_CUT,
+    Config, ExecuteMsg, PairFeeShare, SecondReceiverConfig, SecondReceiverParams, SwapVenue,
+    SwapVenueInfo, MAX_SECOND_RECEIVER_CUT,
+};
+use gridiron::pair::{
+    ConfigResponse as PairConfigResponse, Cw20HookMsg, PairFeeShareParams,
+    QueryMsg as PairQueryMsg, SimulationResponse,
+};
+use gridiron::querier::{query_pair_info, query_pair_info_raw};
+use gridiron::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, QueryMsg as RouterQueryMsg,
+    SimulateSwapOperationsResponse,
 };
-use gridiron::pair::Cw20HookMsg;
-use gridiron::querier::query_pair_info;
 
 use cosmwasm_std::{
-    coins, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Deps, Empty, Env,
-    QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    coins, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
@@ -35,7 +43,13 @@ pub fn try_build_swap_msg(
     amount_in: Uint128,
 ) -> Result<SubMsg, ContractError> {
     let pool = get_pool(querier, &cfg.factory_contract, from, to)?;
-    let msg = build_swap_msg(cfg.max_spread, &pool, from, Some(to), amount_in)?;
+    let msg = build_swap_msg(
+        cfg.max_spread,
+        pool.contract_addr.as_str(),
+        from,
+        Some(to),
+        amount_in,
+    )?;
     Ok(msg)
 }
 
@@ -43,7 +57,7 @@ pub fn try_build_swap_msg(
 ///
 /// * **max_spread** max allowed spread.
 ///
-/// * **pool** pool's information.
+/// * **contract_addr** address of the pair or of a venue implementing the pair swap interface.
 ///
 /// * **from**  asset we want to swap.
 ///
@@ -52,7 +66,7 @@ pub fn try_build_swap_msg(
 /// * **amount_in** amount of tokens to swap.
 pub fn build_swap_msg(
     max_spread: Decimal,
-    pool: &PairInfo,
+    contract_addr: &str,
     from: &AssetInfo,
     to: Option<&AssetInfo>,
     amount_in: Uint128,
@@ -64,13 +78,16 @@ pub fn build_swap_msg(
         };
 
         Ok(SubMsg::new(WasmMsg::Execute {
-            contract_addr: pool.contract_addr.to_string(),
+            contract_addr: contract_addr.to_string(),
             msg: to_binary(&gridiron::pair::ExecuteMsg::Swap {
                 offer_asset: offer_asset.clone(),
                 ask_asset_info: to.cloned(),
                 belief_price: None,
                 max_spread: Some(max_spread),
+                min_receive: None,
                 to: None,
+                deadline: None,
+                referral: None,
             })?,
             funds: vec![offer_asset.as_coin()?],
         }))
@@ -78,13 +95,16 @@ pub fn build_swap_msg(
         Ok(SubMsg::new(WasmMsg::Execute {
             contract_addr: from.to_string(),
             msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: pool.contract_addr.to_string(),
+                contract: contract_addr.to_string(),
                 amount: amount_in,
                 msg: to_binary(&Cw20HookMsg::Swap {
                     ask_asset_info: to.cloned(),
                     belief_price: None,
                     max_spread: Some(max_spread),
+                    min_receive: None,
                     to: None,
+                    deadline: None,
+                    referral: None,
                 })?,
             })?,
             funds: vec![],
@@ -92,6 +112,194 @@ pub fn build_swap_msg(
     }
 }
 
+/// This function creates a message swapping a fee token through its configured swap venue.
+///
+/// * **venue_info** swap venue of the fee token.
+///
+/// * **amount_in** amount of tokens to swap.
+pub fn build_venue_swap_msg(
+    max_spread: Decimal,
+    venue_info: &SwapVenueInfo,
+    amount_in: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let from = &venue_info.asset_info;
+    match &venue_info.venue {
+        SwapVenue::Pair { contract_addr } | SwapVenue::External { contract_addr } => {
+            build_swap_msg(
+                max_spread,
+                contract_addr,
+                from,
+                Some(&venue_info.ask_asset_info),
+                amount_in,
+            )
+        }
+        SwapVenue::Router {
+            contract_addr,
+            operations,
+        } => {
+            let msg = if from.is_native_token() {
+                let offer_asset = Asset {
+                    info: from.clone(),
+                    amount: amount_in,
+                };
+                wasm_execute(
+                    contract_addr,
+                    &RouterExecuteMsg::ExecuteSwapOperations {
+                        operations: operations.clone(),
+                        minimum_receive: None,
+                        to: None,
+                        max_spread: Some(max_spread),
+                        max_intermediate_spread_bps: None,
+                        deadline: None,
+                        trader: None,
+                        fee_payer_memo: None,
+                    },
+                    vec![offer_asset.as_coin()?],
+                )?
+            } else {
+                wasm_execute(
+                    from.to_string(),
+                    &Cw20ExecuteMsg::Send {
+                        contract: contract_addr.clone(),
+                        amount: amount_in,
+                        msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                            operations: operations.clone(),
+                            minimum_receive: None,
+                            to: None,
+                            max_spread: Some(max_spread),
+                            max_intermediate_spread_bps: None,
+                            deadline: None,
+                            trader: None,
+                            fee_payer_memo: None,
+                        })?,
+                    },
+                    vec![],
+                )?
+            };
+
+            Ok(SubMsg::new(msg))
+        }
+    }
+}
+
+/// This function simulates a swap through a swap venue and returns the amount of ask tokens received.
+///
+/// * **venue_info** swap venue of the fee token.
+///
+/// * **amount_in** amount of tokens to swap.
+pub fn simulate_venue(
+    querier: &QuerierWrapper,
+    venue_info: &SwapVenueInfo,
+    amount_in: Uint128,
+) -> StdResult<Uint128> {
+    match &venue_info.venue {
+        SwapVenue::Pair { contract_addr } | SwapVenue::External { contract_addr } => {
+            let res: SimulationResponse = querier.query_wasm_smart(
+                contract_addr,
+                &PairQueryMsg::Simulation {
+                    offer_asset: Asset {
+                        info: venue_info.asset_info.clone(),
+                        amount: amount_in,
+                    },
+                    ask_asset_info: Some(venue_info.ask_asset_info.clone()),
+                },
+            )?;
+            Ok(res.return_amount)
+        }
+        SwapVenue::Router {
+            contract_addr,
+            operations,
+        } => {
+            let res: SimulateSwapOperationsResponse = querier.query_wasm_smart(
+                contract_addr,
+                &RouterQueryMsg::SimulateSwapOperations {
+                    offer_amount: amount_in,
+                    operations: operations.clone(),
+                },
+            )?;
+            Ok(res.amount)
+        }
+    }
+}
+
+/// Validates a swap venue and returns it with normalized contract addresses.
+///
+/// * **venue_info** swap venue of a fee token.
+pub fn validate_swap_venue(
+    deps: Deps,
+    cfg: &Config,
+    venue_info: SwapVenueInfo,
+) -> Result<SwapVenueInfo, ContractError> {
+    let invalid = |reason: &str| {
+        ContractError::InvalidSwapVenue(venue_info.asset_info.to_string(), reason.to_string())
+    };
+    let from = &venue_info.asset_info;
+    let to = &venue_info.ask_asset_info;
+
+    if from.eq(&cfg.grid_token) {
+        return Err(invalid("GRID is never swapped"));
+    }
+    if from.equal(to) {
+        return Err(invalid("offer and ask assets are the same"));
+    }
+
+    // Check that the ask asset can be converted to GRID further on
+    if to.ne(&cfg.grid_token)
+        && !SWAP_VENUES.has(deps.storage, to.to_string())
+        && !BRIDGES.has(deps.storage, to.to_string())
+        && get_pool(&deps.querier, &cfg.factory_contract, to, &cfg.grid_token).is_err()
+    {
+        return Err(ContractError::InvalidBridgeDestination(from.to_string()));
+    }
+
+    let venue = match venue_info.venue {
+        SwapVenue::Pair { contract_addr } => {
+            let pair = deps.api.addr_validate(&contract_addr)?;
+            let pair_info = query_registered_pair(deps, cfg, &pair)?;
+            if !pair_info.asset_infos.contains(from) || !pair_info.asset_infos.contains(to) {
+                return Err(invalid("the pair doesn't hold both assets"));
+            }
+
+            SwapVenue::Pair {
+                contract_addr: pair.to_string(),
+            }
+        }
+        SwapVenue::Router {
+            contract_addr,
+            operations,
+        } => {
+            let (first, last) = match (operations.first(), operations.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return Err(invalid("no swap operations")),
+            };
+            let chained = operations
+                .windows(2)
+                .all(|ops| ops[0].get_target_asset_info() == ops[1].get_offer_asset_info());
+            if first.get_offer_asset_info().ne(from)
+                || last.get_target_asset_info().ne(to)
+                || !chained
+            {
+                return Err(invalid(
+                    "swap operations don't form a route to the ask asset",
+                ));
+            }
+
+            SwapVenue::Router {
+                contract_addr: deps.api.addr_validate(&contract_addr)?.to_string(),
+                operations,
+            }
+        }
+        SwapVenue::External { contract_addr } => SwapVenue::External {
+            contract_addr: deps.api.addr_validate(&contract_addr)?.to_string(),
+        },
+    };
+
+    Ok(SwapVenueInfo {
+        venue,
+        ..venue_info
+    })
+}
+
 /// This function builds distribute messages. It swap all assets through bridges if needed.
 ///
 /// * **bridge_assets** array with assets we want to swap and then to distribute.
@@ -196,6 +404,92 @@ pub fn get_pool(
     .map_err(|_| ContractError::InvalidBridgeNoPool(from.to_string(), to.to_string()))
 }
 
+/// This function builds the full route a fee token follows to GRID once it's swapped to
+/// `first_hop`. Next hops are picked the same way as during bridge assets swaps: a configured
+/// swap venue first, a registered bridge next, a direct pool to GRID otherwise.
+///
+/// * **grid_token** represents $GRID.
+///
+/// * **from** asset we want to swap.
+///
+/// * **first_hop** asset we swap to first.
+pub fn build_route(
+    deps: Deps,
+    grid_token: &AssetInfo,
+    from: &AssetInfo,
+    first_hop: &AssetInfo,
+) -> Vec<AssetInfo> {
+    let mut route = vec![from.clone(), first_hop.clone()];
+    let mut depth = BRIDGES_INITIAL_DEPTH;
+
+    while route.last() != Some(grid_token) && depth < BRIDGES_EXECUTION_MAX_DEPTH {
+        let last = route.last().unwrap();
+        let next_hop = SWAP_VENUES
+            .load(deps.storage, last.to_string())
+            .map(|venue_info| venue_info.ask_asset_info)
+            .or_else(|_| BRIDGES.load(deps.storage, last.to_string()))
+            .unwrap_or_else(|_| grid_token.clone());
+        route.push(next_hop);
+        depth += 1;
+    }
+
+    route
+}
+
+/// This function simulates swaps along the route and returns the amount of GRID received.
+/// Hops with a configured swap venue are simulated in the venue.
+///
+/// * **factory_contract** address of the factory contract.
+///
+/// * **grid_token** represents $GRID.
+///
+/// * **route** assets to swap through, starting with the fee token.
+///
+/// * **amount_in** amount of tokens to swap.
+pub fn simulate_route(
+    deps: Deps,
+    factory_contract: &Addr,
+    grid_token: &AssetInfo,
+    route: &[AssetInfo],
+    amount_in: Uint128,
+) -> Result<Uint128, ContractError> {
+    if route.last() != Some(grid_token) {
+        return Err(ContractError::InvalidBridgeDestination(
+            route[0].to_string(),
+        ));
+    }
+
+    route.windows(2).try_fold(amount_in, |amount, hop| {
+        if let Some(venue_info) = SWAP_VENUES.may_load(deps.storage, hop[0].to_string())? {
+            if venue_info.ask_asset_info == hop[1] {
+                return Ok(simulate_venue(&deps.querier, &venue_info, amount)?);
+            }
+        }
+
+        let pool = get_pool(&deps.querier, factory_contract, &hop[0], &hop[1])?;
+        let res: SimulationResponse = deps.querier.query_wasm_smart(
+            &pool.contract_addr,
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: hop[0].clone(),
+                    amount,
+                },
+                ask_asset_info: Some(hop[1].clone()),
+            },
+        )?;
+        Ok(res.return_amount)
+    })
+}
+
+/// Returns a human readable representation of the swap route.
+pub fn format_route(route: &[AssetInfo]) -> String {
+    route
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
 /// For native tokens of type [`AssetInfo`] uses method [`grid_satellite_package::ExecuteMsg::TransferGrid`]
 /// to send a token amount to a recipient.
 ///
@@ -253,3 +547,84 @@ pub fn update_second_receiver_cfg(
 
     Ok(())
 }
+
+/// Returns the [`PairInfo`] of a pair after checking that it's registered in the factory.
+///
+/// * **pair** pair contract address.
+pub fn query_registered_pair(
+    deps: Deps,
+    cfg: &Config,
+    pair: &Addr,
+) -> Result<PairInfo, ContractError> {
+    let pair_info = query_pair_info_raw(&deps.querier, pair)?;
+    let registered = query_pair_info(&deps.querier, &cfg.factory_contract, &pair_info.asset_infos)
+        .map(|info| info.contract_addr == *pair)
+        .unwrap_or(false);
+    if !registered {
+        return Err(ContractError::PairNotRegistered(pair.to_string()));
+    }
+
+    Ok(pair_info)
+}
+
+/// Checks that the pair a fee token is collected from is registered in the factory and holds
+/// the fee token.
+///
+/// * **pair** pair contract address.
+///
+/// * **asset_info** fee token collected from the pair.
+pub fn validate_source_pair(
+    deps: Deps,
+    cfg: &Config,
+    pair: &str,
+    asset_info: &AssetInfo,
+) -> Result<Addr, ContractError> {
+    let pair = deps.api.addr_validate(pair)?;
+    let pair_info = query_registered_pair(deps, cfg, &pair)?;
+    if !pair_info.asset_infos.contains(asset_info) {
+        return Err(ContractError::AssetNotInPair {
+            asset: asset_info.to_string(),
+            pair: pair.to_string(),
+        });
+    }
+
+    Ok(pair)
+}
+
+/// Reads the fee share config of a pair and updates the fee share registry.
+/// Returns the synced config or None if fee sharing is disabled in the pair.
+///
+/// * **pair** pair contract address. The pair must be registered in the factory.
+pub fn sync_fee_share(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &Config,
+    pair: &Addr,
+) -> Result<Option<PairFeeShare>, ContractError> {
+    let pair_info = query_registered_pair(deps.as_ref(), cfg, pair)?;
+
+    let pair_config: PairConfigResponse = deps
+        .querier
+        .query_wasm_smart(pair, &PairQueryMsg::Config {})?;
+    // Pair types without fee sharing are treated as if fee sharing is disabled
+    let fee_share = pair_config
+        .params
+        .and_then(|params| from_binary::<PairFeeShareParams>(&params).ok())
+        .and_then(|params| params.fee_share);
+
+    match fee_share {
+        Some(fee_share) => {
+            let info = PairFeeShare {
+                asset_infos: pair_info.asset_infos,
+                fee_share,
+                synced_at: env.block.time.seconds(),
+            };
+            FEE_SHARES.save(deps.storage, pair, &info)?;
+            Ok(Some(info))
+        }
+        None => {
+            FEE_SHARES.remove(deps.storage, pair);
+            Ok(None)
+        }
+    }
+}
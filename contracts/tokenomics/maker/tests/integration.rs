@@ -5,13 +5,16 @@ use gridiron::asset::{
 };
 use gridiron::factory::{PairConfig, PairType, UpdateAddr};
 use gridiron::maker::{
-    AssetWithLimit, BalancesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SecondReceiverConfig, SecondReceiverParams,
+    AssetWithLimit, BalancesResponse, ConfigResponse, EpochStats, ExecuteMsg, InstantiateMsg,
+    PairCollectedResponse, PairFeeShareResponse, PendingFee, PendingFeesResponse, QueryMsg,
+    SecondReceiverConfig, SecondReceiverParams, SwapVenue, SwapVenueInfo,
 };
+use gridiron::oracle::QueryMsg as OracleQueryMsg;
 use gridiron::token::InstantiateMsg as TokenInstantiateMsg;
 use gridiron_governance::utils::EPOCH_START;
 use cosmwasm_std::{
-    attr, coin, to_binary, Addr, Coin, Decimal, QueryRequest, Uint128, Uint64, WasmQuery,
+    attr, coin, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    QueryRequest, Response, StdResult, Uint128, Uint256, Uint64, WasmQuery,
 };
 use cw20::{BalanceResponse, Cw20QueryMsg, MinterResponse};
 use cw_multi_test::{next_block, App, ContractWrapper, Executor};
@@ -238,6 +241,7 @@ fn instantiate_contracts(
         default_bridge: Some(native_asset_info("uluna".to_string())),
         max_spread,
         second_receiver_params,
+        epoch_length: None,
     };
     let maker_instance = router
         .instantiate_contract(
@@ -442,6 +446,8 @@ fn create_pair(
                 slippage_tolerance: None,
                 auto_stake: None,
                 receiver: None,
+                deadline: None,
+                min_lp_to_receive: None,
             },
             &funds,
         )
@@ -508,6 +514,9 @@ fn update_config() {
         basic_asset: None,
         max_spread: Some(new_max_spread),
         second_receiver_params: None,
+        epoch_length: None,
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     // Assert cannot update with improper owner
@@ -549,6 +558,9 @@ fn update_config() {
             second_fee_receiver: "second_fee_receiver".to_string(),
             second_receiver_cut: Default::default(),
         }),
+        epoch_length: None,
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     let err = router
@@ -567,6 +579,9 @@ fn update_config() {
             second_fee_receiver: "second_fee_receiver".to_string(),
             second_receiver_cut: Uint64::new(10),
         }),
+        epoch_length: None,
+        oracle_contract: None,
+        max_staking_per_epoch: None,
     };
 
     router
@@ -797,22 +812,27 @@ fn collect_all() {
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
     ];
 
@@ -1081,14 +1101,17 @@ fn collect_err_no_swap_pair() {
         AssetWithLimit {
             info: native_asset(ukrt_asset.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: native_asset(uabc_asset.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
     ];
 
@@ -1388,10 +1411,12 @@ fn collect_with_asset_limit() {
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
     ];
 
@@ -1400,18 +1425,22 @@ fn collect_with_asset_limit() {
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
     ];
 
@@ -1703,18 +1732,22 @@ fn collect_with_second_receiver() {
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: Option::from(Uint128::new(5)),
+            pair: None,
         },
     ];
 
@@ -2028,22 +2061,27 @@ fn distribute_initially_accrued_fees() {
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
     ];
 
@@ -2180,6 +2218,7 @@ fn distribute_initially_accrued_fees() {
     let grid_asset = AssetWithLimit {
         info: token_asset_info(grid_token_instance.clone()),
         limit: None,
+        pair: None,
     };
     let assets = vec![grid_asset];
 
@@ -2440,14 +2479,17 @@ fn collect_3pools() {
         AssetWithLimit {
             info: token_asset(grid_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
         AssetWithLimit {
             info: token_asset(test_token.clone(), Uint128::zero()).info,
             limit: None,
+            pair: None,
         },
     ];
 
@@ -2574,3 +2616,1261 @@ fn collect_3pools() {
     assert_eq!(balances[0].amount.u128(), 100_000);
     assert_eq!(balances[1].amount.u128(), 100_000);
 }
+
+#[test]
+fn fee_share_registry() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+    let staking = Addr::unchecked("staking");
+    let user = Addr::unchecked("user0000");
+
+    let (_, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking,
+        Uint64::new(10),
+        None,
+        None,
+        None,
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &gridiron::factory::ExecuteMsg::UpdateConfig {
+                token_code_id: None,
+                fee_address: Some(maker_instance.to_string()),
+                generator_address: None,
+                whitelist_code_id: None,
+                coin_registry_address: None,
+                max_combined_fee_bps: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let pair_info = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            native_asset("uluna".to_string(), Uint128::new(100_000)),
+            native_asset("uusd".to_string(), Uint128::new(100_000)),
+        ],
+        None,
+    );
+    let pair = pair_info.contract_addr.clone();
+
+    let disable_msg = ExecuteMsg::DisableFeeShare {
+        pair: pair.to_string(),
+    };
+
+    // Only the owner can disable fee sharing
+    let err = router
+        .execute_contract(user.clone(), maker_instance.clone(), &disable_msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    // Fee sharing is disabled, so nothing to disable
+    let err = router
+        .execute_contract(owner.clone(), maker_instance.clone(), &disable_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("Fee sharing is not enabled in pair {}", pair)
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &gridiron::pair::ExecuteMsg::UpdateConfig {
+                params: to_binary(&gridiron::pair::XYKPoolUpdateParams::EnableFeeShare {
+                    fee_share_bps: 100,
+                    fee_share_address: "recipient".to_string(),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Only pair contracts can be synced
+    router
+        .execute_contract(
+            user.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::SyncFeeShares {
+                pairs: vec![factory_instance.to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    router
+        .execute_contract(
+            user.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::SyncFeeShares {
+                pairs: vec![pair.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let fee_shares: Vec<PairFeeShareResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::FeeShares {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(fee_shares.len(), 1);
+    assert_eq!(fee_shares[0].pair, pair);
+    assert_eq!(fee_shares[0].info.asset_infos, pair_info.asset_infos);
+    assert_eq!(fee_shares[0].info.fee_share.bps, 100);
+    assert_eq!(
+        fee_shares[0].info.fee_share.recipient,
+        Addr::unchecked("recipient")
+    );
+    assert!(!fee_shares[0].whitelisted);
+
+    let msg = ExecuteMsg::UpdateFeeShareWhitelist {
+        add: Some(vec!["recipient".to_string()]),
+        remove: None,
+    };
+    let err = router
+        .execute_contract(user.clone(), maker_instance.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(owner.clone(), maker_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let whitelist: Vec<Addr> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::FeeShareWhitelist {})
+        .unwrap();
+    assert_eq!(whitelist, vec![Addr::unchecked("recipient")]);
+
+    // Whitelisted recipients can't be disabled
+    let err = router
+        .execute_contract(owner.clone(), maker_instance.clone(), &disable_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Fee share recipient recipient is whitelisted"
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateFeeShareWhitelist {
+                add: None,
+                remove: Some(vec!["recipient".to_string()]),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(user.clone(), maker_instance.clone(), &disable_msg, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(owner.clone(), maker_instance.clone(), &disable_msg, &[])
+        .unwrap();
+
+    let pair_config: gridiron::pair::ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair, &gridiron::pair::QueryMsg::Config {})
+        .unwrap();
+    let params: gridiron::pair::XYKPoolConfig =
+        cosmwasm_std::from_binary(&pair_config.params.unwrap()).unwrap();
+    assert_eq!(params.fee_share, None);
+
+    let fee_shares: Vec<PairFeeShareResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::FeeShares {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(fee_shares.is_empty());
+}
+
+#[test]
+fn collect_uses_best_route() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(100_000_000_000u128),
+        }],
+    );
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+    let max_spread = Decimal::from_str("0.5").unwrap();
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(max_spread),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Test token".to_string(),
+        "TEST".to_string(),
+    );
+
+    // USDC can be swapped through the TEST bridge or directly to GRID.
+    // TEST is cheap in terms of GRID thus the direct pool gives more GRID.
+    for assets in vec![
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(10_000_u128)),
+        ],
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+    ] {
+        create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            assets,
+            None,
+        );
+    }
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![(
+                    token_asset_info(usdc_token_instance.clone()),
+                    token_asset_info(test_token_instance.clone()),
+                )]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+
+    let res = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(usdc_token_instance.clone()),
+                    limit: None,
+                    pair: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let expected_route = format!("{} -> {}", usdc_token_instance, grid_token_instance);
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .any(|attribute| attribute == &attr("swap_route", &expected_route)));
+
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        usdc_token_instance,
+        Uint128::zero(),
+    );
+    check_balance(
+        &mut router,
+        maker_instance,
+        test_token_instance,
+        Uint128::zero(),
+    );
+    // 1000 USDC -> 990 GRID (98 GRID via the TEST bridge)
+    check_balance(&mut router, staking, grid_token_instance, Uint128::new(990));
+}
+
+#[test]
+fn collect_through_swap_venue() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![]);
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+    let max_spread = Decimal::from_str("0.5").unwrap();
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(max_spread),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Test token".to_string(),
+        "TEST".to_string(),
+    );
+
+    // The direct USDC-GRID pool gives more GRID than the route through TEST
+    let mut pairs = vec![];
+    for assets in vec![
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(10_000_u128)),
+        ],
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+    ] {
+        pairs.push(create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            assets,
+            None,
+        ));
+    }
+
+    let venue_info = SwapVenueInfo {
+        asset_info: token_asset_info(usdc_token_instance.clone()),
+        ask_asset_info: token_asset_info(test_token_instance.clone()),
+        venue: SwapVenue::Pair {
+            contract_addr: pairs[0].contract_addr.to_string(),
+        },
+    };
+
+    let err = router
+        .execute_contract(
+            user.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateSwapVenues {
+                add: Some(vec![venue_info.clone()]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    // The USDC-GRID pair doesn't hold TEST
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateSwapVenues {
+                add: Some(vec![SwapVenueInfo {
+                    venue: SwapVenue::Pair {
+                        contract_addr: pairs[2].contract_addr.to_string(),
+                    },
+                    ..venue_info.clone()
+                }]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!(
+            "Invalid swap venue for {}: the pair doesn't hold both assets",
+            usdc_token_instance
+        )
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateSwapVenues {
+                add: Some(vec![venue_info.clone()]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let venues: Vec<SwapVenueInfo> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::SwapVenues {})
+        .unwrap();
+    assert_eq!(venues, vec![venue_info]);
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+
+    let res = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(usdc_token_instance.clone()),
+                    limit: None,
+                    pair: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    // The configured venue is used even though the direct pool gives more GRID
+    let expected_route = format!(
+        "{} -> {} -> {}",
+        usdc_token_instance, test_token_instance, grid_token_instance
+    );
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .any(|attribute| attribute == &attr("swap_route", &expected_route)));
+
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        usdc_token_instance.clone(),
+        Uint128::zero(),
+    );
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        test_token_instance,
+        Uint128::zero(),
+    );
+    // 1000 USDC -> 990 TEST -> 98 GRID
+    check_balance(&mut router, staking, grid_token_instance, Uint128::new(98));
+
+    router
+        .execute_contract(
+            owner,
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateSwapVenues {
+                add: None,
+                remove: Some(vec![token_asset_info(usdc_token_instance)]),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let venues: Vec<SwapVenueInfo> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::SwapVenues {})
+        .unwrap();
+    assert!(venues.is_empty());
+}
+
+#[test]
+fn collect_epoch_distribution() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![]);
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(Decimal::from_str("0.5").unwrap()),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        None,
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                factory_contract: None,
+                staking_contract: None,
+                governance_contract: None,
+                governance_percent: None,
+                basic_asset: None,
+                max_spread: None,
+                second_receiver_params: None,
+                epoch_length: Some(100),
+                oracle_contract: None,
+                max_staking_per_epoch: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let start_time = router.block_info().time.seconds();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+
+    let collect_usdc = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: token_asset_info(usdc_token_instance.clone()),
+            limit: None,
+            pair: None,
+        }],
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &collect_usdc,
+            &[],
+        )
+        .unwrap();
+
+    // GRID stays in the Maker until the epoch is over
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        grid_token_instance.clone(),
+        Uint128::new(990),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        grid_token_instance.clone(),
+        Uint128::zero(),
+    );
+
+    let epoch: EpochStats = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Epoch { epoch: None })
+        .unwrap();
+    assert_eq!(epoch.epoch, 1);
+    assert_eq!(epoch.start_time, start_time);
+    assert_eq!(epoch.end_time, None);
+    assert_eq!(
+        epoch.collected,
+        vec![token_asset(usdc_token_instance.clone(), Uint128::new(1000))]
+    );
+
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(100);
+        block.height += 1;
+    });
+
+    // Anyone can roll the epoch over
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect { assets: vec![] },
+            &[],
+        )
+        .unwrap();
+
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        grid_token_instance.clone(),
+        Uint128::zero(),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        grid_token_instance.clone(),
+        Uint128::new(990),
+    );
+
+    let epochs: Vec<EpochStats> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::Epochs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(epochs.len(), 2);
+    assert_eq!(epochs[0].end_time, Some(start_time + 100));
+    assert_eq!(epochs[0].distributed, Uint128::new(990));
+    assert_eq!(epochs[0].to_staking, Uint128::new(990));
+    assert_eq!(epochs[0].to_governance, Uint128::zero());
+    assert_eq!(epochs[0].carryover, Uint128::zero());
+    assert_eq!(epochs[1].epoch, 2);
+    assert_eq!(epochs[1].start_time, start_time + 100);
+    assert_eq!(epochs[1].collected, vec![]);
+
+    // Disabling epochs distributes GRID on every collect again
+    router
+        .execute_contract(
+            owner,
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                factory_contract: None,
+                staking_contract: None,
+                governance_contract: None,
+                governance_percent: None,
+                basic_asset: None,
+                max_spread: None,
+                second_receiver_params: None,
+                epoch_length: Some(0),
+                oracle_contract: None,
+                max_staking_per_epoch: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.epoch_length, None);
+}
+
+#[test]
+fn epoch_staking_cap() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![]);
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(Decimal::from_str("0.5").unwrap()),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    create_pair(
+        &mut router,
+        owner.clone(),
+        user,
+        &factory_instance,
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        None,
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let update_config =
+        |epoch_length: Option<u64>, max_staking_per_epoch: Option<u128>| ExecuteMsg::UpdateConfig {
+            factory_contract: None,
+            staking_contract: None,
+            governance_contract: None,
+            governance_percent: None,
+            basic_asset: None,
+            max_spread: None,
+            second_receiver_params: None,
+            epoch_length,
+            oracle_contract: None,
+            max_staking_per_epoch: max_staking_per_epoch.map(Uint128::new),
+        };
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &update_config(None, Some(400)),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &update_config(Some(100), Some(400)),
+            &[],
+        )
+        .unwrap();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(usdc_token_instance.clone()),
+                    limit: None,
+                    pair: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        grid_token_instance.clone(),
+        Uint128::new(990),
+    );
+
+    // 990 GRID reach xGRID stakers over three epochs
+    let mut staked = 0;
+    for (epoch, to_staking, staking_carryover) in [(1, 400, 590), (2, 400, 190), (3, 190, 0)] {
+        router.update_block(|block| {
+            block.time = block.time.plus_seconds(100);
+            block.height += 1;
+        });
+        router
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                maker_instance.clone(),
+                &ExecuteMsg::Collect { assets: vec![] },
+                &[],
+            )
+            .unwrap();
+
+        staked += to_staking;
+        check_balance(
+            &mut router,
+            staking.clone(),
+            grid_token_instance.clone(),
+            Uint128::new(staked),
+        );
+
+        let stats: EpochStats = router
+            .wrap()
+            .query_wasm_smart(&maker_instance, &QueryMsg::Epoch { epoch: Some(epoch) })
+            .unwrap();
+        assert_eq!(stats.to_staking, Uint128::new(to_staking));
+        assert_eq!(stats.carryover, Uint128::new(staking_carryover));
+        assert_eq!(stats.staking_carryover, Uint128::new(staking_carryover));
+
+        let config: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.max_staking_per_epoch, Some(Uint128::new(400)));
+        assert_eq!(config.staking_carryover, Uint128::new(staking_carryover));
+    }
+
+    // Zero removes the cap
+    router
+        .execute_contract(
+            owner,
+            maker_instance.clone(),
+            &update_config(None, Some(0)),
+            &[],
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.max_staking_per_epoch, None);
+}
+
+#[test]
+fn collected_by_pair_accounting() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![]);
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+    let max_spread = Decimal::from_str("0.5").unwrap();
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(max_spread),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Test token".to_string(),
+        "TEST".to_string(),
+    );
+
+    let usdc_pair = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        None,
+    );
+    let test_pair = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        None,
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+
+    // The pair must hold the collected fee token
+    let err = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(usdc_token_instance.clone()),
+                    limit: None,
+                    pair: Some(test_pair.contract_addr.to_string()),
+                }],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!(
+            "Pair {} doesn't hold {}",
+            test_pair.contract_addr, usdc_token_instance
+        )
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(usdc_token_instance.clone()),
+                    limit: None,
+                    pair: Some(usdc_pair.contract_addr.to_string()),
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    // GRID fees are recorded as is
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(500),
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(grid_token_instance.clone()),
+                    limit: None,
+                    pair: Some(test_pair.contract_addr.to_string()),
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let mut expected = vec![
+        PairCollectedResponse {
+            pair: usdc_pair.contract_addr.clone(),
+            // 1000 USDC -> 990 GRID
+            grid_amount: Uint128::new(990),
+        },
+        PairCollectedResponse {
+            pair: test_pair.contract_addr.clone(),
+            grid_amount: Uint128::new(500),
+        },
+    ];
+    expected.sort_by(|a, b| a.pair.cmp(&b.pair));
+
+    let res: Vec<PairCollectedResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::CollectedByPair {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res, expected);
+
+    let res: Vec<PairCollectedResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::CollectedByPair {
+                start_after: Some(expected[0].pair.to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res, expected[1..]);
+}
+
+fn mock_oracle_query(_deps: Deps, _env: Env, msg: OracleQueryMsg) -> StdResult<Binary> {
+    match msg {
+        // GRID is worth 2 USD
+        OracleQueryMsg::Consult { amount, .. } => to_binary(&vec![(
+            native_asset_info("uusd".to_string()),
+            Uint256::from(amount) * Uint256::from(2u8),
+        )]),
+    }
+}
+
+#[test]
+fn pending_fees() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(100_000_000_000u128),
+        }],
+    );
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+    let max_spread = Decimal::from_str("0.5").unwrap();
+
+    let (grid_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(0),
+        Some(max_spread),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Test token".to_string(),
+        "TEST".to_string(),
+    );
+
+    for assets in vec![
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(10_000_u128)),
+        ],
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(grid_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+    ] {
+        create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            assets,
+            None,
+        );
+    }
+
+    // USDC is a cw20 fee token known to the Maker through its bridge
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![(
+                    token_asset_info(usdc_token_instance.clone()),
+                    token_asset_info(test_token_instance.clone()),
+                )]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        usdc_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(500),
+    );
+    // There is no pool to swap LUNA with
+    router
+        .send_tokens(
+            owner.clone(),
+            maker_instance.clone(),
+            &[coin(1000, "uluna")],
+        )
+        .unwrap();
+
+    let mut expected = PendingFeesResponse {
+        fees: vec![
+            PendingFee {
+                asset: native_asset("uluna".to_string(), Uint128::new(1000)),
+                route: vec![],
+                grid_amount: None,
+                usd_value: None,
+            },
+            PendingFee {
+                asset: token_asset(grid_token_instance.clone(), Uint128::new(500)),
+                route: vec![token_asset_info(grid_token_instance.clone())],
+                grid_amount: Some(Uint128::new(500)),
+                usd_value: None,
+            },
+            PendingFee {
+                asset: token_asset(usdc_token_instance.clone(), Uint128::new(1000)),
+                route: vec![
+                    token_asset_info(usdc_token_instance.clone()),
+                    token_asset_info(grid_token_instance.clone()),
+                ],
+                // 1000 USDC -> 990 GRID
+                grid_amount: Some(Uint128::new(990)),
+                usd_value: None,
+            },
+        ],
+        total_grid_amount: Uint128::new(1490),
+        total_usd_value: None,
+    };
+
+    let res: PendingFeesResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::PendingFees {})
+        .unwrap();
+    assert_eq!(res, expected);
+
+    // Value pending fees in USD once the oracle is configured
+    let oracle_code_id = router.store_code(Box::new(ContractWrapper::new_with_empty(
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        mock_oracle_query,
+    )));
+    let oracle = router
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Oracle",
+            None,
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                factory_contract: None,
+                staking_contract: None,
+                governance_contract: None,
+                governance_percent: None,
+                basic_asset: None,
+                max_spread: None,
+                second_receiver_params: None,
+                epoch_length: None,
+                oracle_contract: Some(UpdateAddr::Set(oracle.to_string())),
+                max_staking_per_epoch: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    expected.fees[1].usd_value = Some(Uint128::new(1000));
+    expected.fees[2].usd_value = Some(Uint128::new(1980));
+    expected.total_usd_value = Some(Uint128::new(2980));
+
+    let res: PendingFeesResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::PendingFees {})
+        .unwrap();
+    assert_eq!(res, expected);
+}
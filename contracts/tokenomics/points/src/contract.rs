@@ -0,0 +1,414 @@
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    OverflowError, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
+use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use gridiron::pair::QueryMsg as PairQueryMsg;
+use gridiron::points::{
+    Config, ExecuteMsg, InstantiateMsg, PointsResponse, QueryMsg, DEFAULT_LIMIT, MAX_LIMIT,
+};
+use gridiron::querier::{query_factory_config, query_pair_info};
+
+use crate::error::ContractError;
+use crate::state::{
+    LpPool, Position, CONFIG, LEADERBOARD, LP_POOLS, OWNERSHIP_PROPOSAL, POINTS, POSITIONS,
+};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "gridiron-points";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            factory: deps.api.addr_validate(&msg.factory)?,
+            swap_rates: vec![],
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::UpdateSwapRates { rates }** Sets the points accrued per unit of swapped offer assets.
+///
+/// * **ExecuteMsg::UpdateLpRates { rates }** Sets the points accrued per second by staked LP tokens.
+///
+/// * **ExecuteMsg::SwapHook { receiver, offer_asset }** Credits the receiver of a swap.
+///
+/// * **ExecuteMsg::LpHook { user, lp_token, amount }** Settles the LP points of a user and updates
+/// the position.
+///
+/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
+///
+/// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
+///
+/// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateSwapRates { rates } => update_swap_rates(deps, info, rates),
+        ExecuteMsg::UpdateLpRates { rates } => update_lp_rates(deps, env, info, rates),
+        ExecuteMsg::SwapHook {
+            receiver,
+            offer_asset,
+        } => swap_hook(deps, info, receiver, offer_asset),
+        ExecuteMsg::LpHook {
+            user,
+            lp_token,
+            amount,
+        } => lp_hook(deps, env, info, user, lp_token, amount),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Sets the points accrued per unit of an offer asset swapped. A zero rate removes the asset.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_swap_rates(
+    deps: DepsMut,
+    info: MessageInfo,
+    rates: Vec<(AssetInfo, Decimal)>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_swap_rates")];
+
+    for (asset_info, rate) in rates {
+        asset_info.check(deps.api)?;
+
+        config.swap_rates.retain(|(info, _)| *info != asset_info);
+        attributes.push(attr(asset_info.to_string(), rate.to_string()));
+        if !rate.is_zero() {
+            config.swap_rates.push((asset_info, rate));
+        }
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Sets the points accrued per second for every staked LP token. The points accrued at the
+/// previous rate are kept in the LP token's index.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_lp_rates(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rates: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let now = env.block.time.seconds();
+    let mut attributes = vec![attr("action", "update_lp_rates")];
+
+    for (lp_token, rate) in rates {
+        let lp_token = deps.api.addr_validate(&lp_token)?;
+
+        let mut pool = LP_POOLS
+            .may_load(deps.storage, &lp_token)?
+            .unwrap_or(LpPool {
+                rate: Decimal::zero(),
+                index: Decimal::zero(),
+                last_update: now,
+            });
+        update_index(&mut pool, now)?;
+        pool.rate = rate;
+        LP_POOLS.save(deps.storage, &lp_token, &pool)?;
+
+        attributes.push(attr(lp_token, rate.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Credits the receiver of a swap with points for the offer asset.
+///
+/// Swaps reported by contracts which aren't pairs registered in the factory are ignored rather
+/// than rejected, so a misconfigured pair never fails swaps.
+fn swap_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    receiver: String,
+    offer_asset: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut attributes = vec![attr("action", "swap_hook")];
+
+    if !is_registered_pair(deps.as_ref(), &config.factory, &info.sender) {
+        attributes.push(attr("ignored", "unregistered_pair"));
+        return Ok(Response::new().add_attributes(attributes));
+    }
+
+    let points = config
+        .swap_rates
+        .iter()
+        .find(|(asset_info, _)| *asset_info == offer_asset.info)
+        .map(|(_, rate)| offer_asset.amount * *rate)
+        .unwrap_or_default();
+
+    let receiver = deps.api.addr_validate(&receiver)?;
+    add_points(deps.storage, &receiver, points)?;
+
+    attributes.extend([attr("receiver", receiver), attr("points", points)]);
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Settles the points accrued by the LP position of a user and updates the position.
+///
+/// * **amount** amount of LP tokens the user has staked after the change.
+///
+/// Positions reported by contracts other than the generator set in the factory are ignored, so
+/// withdrawals from a rotated out generator keep working.
+fn lp_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user: String,
+    lp_token: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut attributes = vec![attr("action", "lp_hook")];
+
+    let generator = query_factory_config(&deps.querier, &config.factory)?.generator_address;
+    if generator.as_ref() != Some(&info.sender) {
+        attributes.push(attr("ignored", "unknown_generator"));
+        return Ok(Response::new().add_attributes(attributes));
+    }
+
+    let user = deps.api.addr_validate(&user)?;
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+
+    let index = match LP_POOLS.may_load(deps.storage, &lp_token)? {
+        Some(mut pool) => {
+            update_index(&mut pool, env.block.time.seconds())?;
+            LP_POOLS.save(deps.storage, &lp_token, &pool)?;
+            pool.index
+        }
+        None => Decimal::zero(),
+    };
+
+    let points = match POSITIONS.may_load(deps.storage, (&user, &lp_token))? {
+        Some(position) => position.amount * index.checked_sub(position.index)?,
+        None => Uint128::zero(),
+    };
+    add_points(deps.storage, &user, points)?;
+
+    if amount.is_zero() {
+        POSITIONS.remove(deps.storage, (&user, &lp_token));
+    } else {
+        POSITIONS.save(
+            deps.storage,
+            (&user, &lp_token),
+            &Position { amount, index },
+        )?;
+    }
+
+    attributes.extend([
+        attr("user", user),
+        attr("lp_token", lp_token),
+        attr("amount", amount),
+        attr("points", points),
+    ]);
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Returns whether the sender is a pair registered in the factory.
+fn is_registered_pair(deps: Deps, factory: &Addr, sender: &Addr) -> bool {
+    deps.querier
+        .query_wasm_smart::<PairInfo>(sender, &PairQueryMsg::Pair {})
+        .and_then(|pair_info| query_pair_info(&deps.querier, factory, &pair_info.asset_infos))
+        .map_or(false, |pair_info| pair_info.contract_addr == *sender)
+}
+
+/// Accrues the points of one LP token since the last update to the index of the pool.
+fn update_index(pool: &mut LpPool, now: u64) -> Result<(), OverflowError> {
+    if now > pool.last_update {
+        let elapsed = Decimal::from_ratio(now - pool.last_update, 1u8);
+        pool.index = pool.index.checked_add(pool.rate.checked_mul(elapsed)?)?;
+        pool.last_update = now;
+    }
+
+    Ok(())
+}
+
+/// Adds settled points to an address and moves it on the leaderboard.
+fn add_points(storage: &mut dyn Storage, address: &Addr, amount: Uint128) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    let points = POINTS.may_load(storage, address)?.unwrap_or_default();
+    let new_points = points.checked_add(amount)?;
+
+    LEADERBOARD.remove(storage, (points.u128(), address));
+    LEADERBOARD.save(storage, (new_points.u128(), address), &())?;
+    POINTS.save(storage, address, &new_points)
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`Config`] object.
+///
+/// * **QueryMsg::LpRates { start_after, limit }** Returns LP tokens and their point rates.
+///
+/// * **QueryMsg::Points { address }** Returns the points of an address using a [`PointsResponse`] object.
+///
+/// * **QueryMsg::Leaderboard { start_after, limit }** Returns the addresses with the most settled points.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::LpRates { start_after, limit } => {
+            to_binary(&query_lp_rates(deps, start_after, limit)?)
+        }
+        QueryMsg::Points { address } => to_binary(&query_points(deps, env, address)?),
+        QueryMsg::Leaderboard { start_after, limit } => {
+            to_binary(&query_leaderboard(deps, start_after, limit)?)
+        }
+    }
+}
+
+/// Returns LP tokens and their point rates ordered by LP token address.
+fn query_lp_rates(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Addr, Decimal)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = addr_opt_validate(deps.api, &start_after)?;
+    let start_after = start_after.as_ref().map(Bound::exclusive);
+
+    LP_POOLS
+        .range(deps.storage, start_after, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(lp_token, pool)| (lp_token, pool.rate)))
+        .collect()
+}
+
+/// Returns the settled points of an address together with the points its LP positions have
+/// accrued since they were last settled.
+fn query_points(deps: Deps, env: Env, address: String) -> StdResult<PointsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let now = env.block.time.seconds();
+
+    let mut points = POINTS.may_load(deps.storage, &address)?.unwrap_or_default();
+
+    for item in POSITIONS
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (lp_token, position) = item?;
+        if let Some(mut pool) = LP_POOLS.may_load(deps.storage, &lp_token)? {
+            update_index(&mut pool, now)?;
+            points =
+                points.checked_add(position.amount * pool.index.checked_sub(position.index)?)?;
+        }
+    }
+
+    Ok(PointsResponse { address, points })
+}
+
+/// Returns the addresses with the most settled points, highest first. Addresses with equal
+/// points are ordered by address, descending.
+///
+/// * **start_after** the last address of the previous page.
+fn query_leaderboard(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PointsResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = addr_opt_validate(deps.api, &start_after)?
+        .map(|address| -> StdResult<_> {
+            let points = POINTS.may_load(deps.storage, &address)?;
+            Ok((points.unwrap_or_default().u128(), address))
+        })
+        .transpose()?;
+    let start_after = start_after
+        .as_ref()
+        .map(|(points, address)| Bound::exclusive((*points, address)));
+
+    LEADERBOARD
+        .keys(deps.storage, None, start_after, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (points, address) = item?;
+            Ok(PointsResponse {
+                address,
+                points: points.into(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use gridiron::common::OwnershipProposal;
+use gridiron::points::Config;
+
+/// This structure describes the points accrual of an LP token.
+#[cw_serde]
+pub struct LpPool {
+    /// The points accrued per second for every staked LP token
+    pub rate: Decimal,
+    /// The points accrued by one LP token since the rate was first set
+    pub index: Decimal,
+    /// The last time the index was updated
+    pub last_update: u64,
+}
+
+/// This structure describes the LP position of a user.
+#[cw_serde]
+pub struct Position {
+    /// The amount of LP tokens staked in the generator
+    pub amount: Uint128,
+    /// The pool index at which the position's points were last settled
+    pub index: Decimal,
+}
+
+/// Stores the contract config at the given key.
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the points accrual of each LP token.
+pub const LP_POOLS: Map<&Addr, LpPool> = Map::new("lp_pools");
+
+/// Stores LP positions by user and LP token.
+pub const POSITIONS: Map<(&Addr, &Addr), Position> = Map::new("positions");
+
+/// Stores the settled points of each address.
+pub const POINTS: Map<&Addr, Uint128> = Map::new("points");
+
+/// Indexes addresses by their settled points to serve the leaderboard.
+pub const LEADERBOARD: Map<(u128, &Addr), ()> = Map::new("leaderboard");
+
+/// Contains a proposal to change contract ownership.
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -0,0 +1,297 @@
+#![cfg(not(tarpaulin_include))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+use gridiron::asset::{native_asset_info, AssetInfoExt};
+use gridiron::factory::ExecuteMsg as FactoryExecuteMsg;
+use gridiron::generator::ExecuteMsg as GeneratorExecuteMsg;
+use gridiron::pair::ExecuteMsg as PairExecuteMsg;
+use gridiron::points::{ExecuteMsg, InstantiateMsg, PointsResponse, QueryMsg};
+use gridiron_mocks::cw_multi_test::{BasicApp, ContractWrapper, Executor};
+use gridiron_mocks::{
+    gridiron_address, named_address, MockAppExt, MockGeneratorBuilder, MockXykPairBuilder,
+};
+use gridiron_points::error::ContractError;
+
+fn points_response(address: &Addr, points: u128) -> PointsResponse {
+    PointsResponse {
+        address: address.clone(),
+        points: Uint128::new(points),
+    }
+}
+
+#[test]
+fn swap_and_lp_points() {
+    let gridiron = gridiron_address();
+    let alice = named_address("alice");
+    let bob = named_address("bob");
+
+    let app = Rc::new(RefCell::new(BasicApp::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &gridiron,
+                vec![
+                    coin(1_000_000_000000, "uusd"),
+                    coin(1_000_000_000000, "ustake"),
+                ],
+            )
+            .unwrap();
+    })));
+
+    let generator = MockGeneratorBuilder::new(&app).instantiate();
+    let factory = generator.factory();
+
+    let uusd = native_asset_info("uusd".to_owned());
+    let ustake = native_asset_info("ustake".to_owned());
+    let pair = MockXykPairBuilder::new(&app)
+        .with_factory(&factory)
+        .with_asset(&uusd)
+        .with_asset(&ustake)
+        .instantiate();
+    let lp_token = pair.lp_token().address;
+
+    let points_code_id = app
+        .borrow_mut()
+        .store_code(Box::new(ContractWrapper::new_with_empty(
+            gridiron_points::contract::execute,
+            gridiron_points::contract::instantiate,
+            gridiron_points::contract::query,
+        )));
+    let points = app
+        .borrow_mut()
+        .instantiate_contract(
+            points_code_id,
+            gridiron.clone(),
+            &InstantiateMsg {
+                owner: gridiron.to_string(),
+                factory: factory.address.to_string(),
+            },
+            &[],
+            "Gridiron Points",
+            None,
+        )
+        .unwrap();
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            factory.address.clone(),
+            &FactoryExecuteMsg::SetPointsContract {
+                points_contract: Some(points.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // 10 points per uusd swapped, 1 point per LP token staked per second
+    let err = app
+        .borrow_mut()
+        .execute_contract(
+            alice.clone(),
+            points.clone(),
+            &ExecuteMsg::UpdateSwapRates {
+                rates: vec![(uusd.clone(), Decimal::from_ratio(10u8, 1_000000u32))],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            points.clone(),
+            &ExecuteMsg::UpdateSwapRates {
+                rates: vec![(uusd.clone(), Decimal::from_ratio(10u8, 1_000000u32))],
+            },
+            &[],
+        )
+        .unwrap();
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            points.clone(),
+            &ExecuteMsg::UpdateLpRates {
+                rates: vec![(lp_token.to_string(), Decimal::from_ratio(1u8, 1_000000u32))],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let lp_rates: Vec<(Addr, Decimal)> = app
+        .borrow()
+        .wrap()
+        .query_wasm_smart(
+            &points,
+            &QueryMsg::LpRates {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        lp_rates,
+        [(lp_token.clone(), Decimal::from_ratio(1u8, 1_000000u32))]
+    );
+
+    // The generator reports the auto staked position
+    pair.mint_allow_provide_and_stake(
+        &gridiron,
+        &[
+            uusd.with_balance(1_000_000000u128),
+            ustake.with_balance(1_000_000000u128),
+        ],
+    );
+
+    let swap = |sender: &Addr, denom: &str, amount: u128| {
+        app.borrow_mut().execute_contract(
+            sender.clone(),
+            pair.address.clone(),
+            &PairExecuteMsg::Swap {
+                offer_asset: native_asset_info(denom.to_owned()).with_balance(amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                min_receive: None,
+                to: None,
+                deadline: None,
+                referral: None,
+            },
+            &[coin(amount, denom)],
+        )
+    };
+
+    app.borrow_mut()
+        .fund(
+            &alice,
+            &[coin(100_000000, "uusd"), coin(100_000000, "ustake")],
+        )
+        .unwrap();
+    app.borrow_mut()
+        .fund(&bob, &[coin(100_000000, "uusd")])
+        .unwrap();
+
+    let query_points = |address: &Addr| -> PointsResponse {
+        app.borrow()
+            .wrap()
+            .query_wasm_smart(
+                &points,
+                &QueryMsg::Points {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    let query_leaderboard = |start_after: Option<&Addr>| -> Vec<PointsResponse> {
+        app.borrow()
+            .wrap()
+            .query_wasm_smart(
+                &points,
+                &QueryMsg::Leaderboard {
+                    start_after: start_after.map(ToString::to_string),
+                    limit: None,
+                },
+            )
+            .unwrap()
+    };
+
+    swap(&alice, "uusd", 10_000000).unwrap();
+    swap(&bob, "uusd", 5_000000).unwrap();
+    // There is no rate for ustake
+    swap(&alice, "ustake", 10_000000).unwrap();
+
+    assert_eq!(query_points(&alice), points_response(&alice, 100));
+    assert_eq!(query_points(&bob), points_response(&bob, 50));
+
+    // Hooks from contracts which aren't registered pairs or the generator are ignored
+    for msg in [
+        ExecuteMsg::SwapHook {
+            receiver: bob.to_string(),
+            offer_asset: uusd.with_balance(10_000000u128),
+        },
+        ExecuteMsg::LpHook {
+            user: bob.to_string(),
+            lp_token: lp_token.to_string(),
+            amount: Uint128::new(1_000_000000),
+        },
+    ] {
+        app.borrow_mut()
+            .execute_contract(bob.clone(), points.clone(), &msg, &[])
+            .unwrap();
+    }
+    assert_eq!(query_points(&bob), points_response(&bob, 50));
+
+    // LP points are pending until the generator reports a change of the position
+    app.borrow_mut().next_block_after(100);
+    assert_eq!(query_points(&gridiron), points_response(&gridiron, 99_999));
+    assert_eq!(
+        query_leaderboard(None),
+        [points_response(&alice, 100), points_response(&bob, 50)]
+    );
+
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            generator.address.clone(),
+            &GeneratorExecuteMsg::Withdraw {
+                lp_token: lp_token.to_string(),
+                amount: Uint128::new(999_999000),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Nothing is accrued without a position
+    app.borrow_mut().next_block_after(100);
+    assert_eq!(query_points(&gridiron), points_response(&gridiron, 99_999));
+    assert_eq!(
+        query_leaderboard(None),
+        [
+            points_response(&gridiron, 99_999),
+            points_response(&alice, 100),
+            points_response(&bob, 50),
+        ]
+    );
+    assert_eq!(
+        query_leaderboard(Some(&gridiron)),
+        [points_response(&alice, 100), points_response(&bob, 50)]
+    );
+
+    // A zero rate removes the swap rate
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            points.clone(),
+            &ExecuteMsg::UpdateSwapRates {
+                rates: vec![(uusd.clone(), Decimal::zero())],
+            },
+            &[],
+        )
+        .unwrap();
+    swap(&bob, "uusd", 5_000000).unwrap();
+    assert_eq!(query_points(&bob), points_response(&bob, 50));
+
+    // Removing the points contract from the factory stops the reports
+    app.borrow_mut()
+        .execute_contract(
+            gridiron.clone(),
+            factory.address.clone(),
+            &FactoryExecuteMsg::SetPointsContract {
+                points_contract: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let res = swap(&alice, "uusd", 10_000000).unwrap();
+    assert!(!res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.value == "swap_hook")));
+}
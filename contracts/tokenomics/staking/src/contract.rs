@@ -1,20 +1,27 @@
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, wasm_execute, Addr, Binary, CosmosMsg, Deps,
-    DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
-    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, entry_point, from_binary, to_binary, wasm_execute, Addr, Api, Binary, CosmosMsg, Decimal,
+    Decimal256, Deps, DepsMut, Env, Event, MessageInfo, Order, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint64, WasmMsg,
 };
-use cw_utils::parse_instantiate_response_data;
+use cw_storage_plus::Bound;
+use cw_utils::{must_pay, parse_instantiate_response_data};
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{
+    Config, Redemption, RevenueDistribution, CONFIG, ENTER_PAUSED, OWNER, QUEUED_GRID, REDEMPTIONS,
+    REDEMPTION_COUNT, REVENUE_CURSORS, REVENUE_DISTRIBUTIONS, REVENUE_STATE, TOTALS,
+};
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo};
 use gridiron::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ConfigResponse, Cw20HookMsg, ExchangeRateResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    PendingRevenueResponse, QueryMsg, RedemptionParams, RedemptionResponse, RevenueInfoResponse,
+    StakingTotals,
 };
 use cw2::{get_contract_version, set_contract_version};
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 
 use gridiron::querier::{query_supply, query_token_balance};
-use gridiron::xgrid_token::InstantiateMsg as TokenInstantiateMsg;
+use gridiron::xgrid_token::{InstantiateMsg as TokenInstantiateMsg, QueryMsg as XGridQueryMsg};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "gridiron-staking";
@@ -31,6 +38,16 @@ const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
 /// Minimum initial xgrid share
 pub(crate) const MINIMUM_STAKE_AMOUNT: Uint128 = Uint128::new(1_000);
 
+/// The default number of revenue distributions processed in a claim
+const DEFAULT_CLAIM_LIMIT: u32 = 10;
+/// The max number of revenue distributions processed in a claim
+const MAX_CLAIM_LIMIT: u32 = 50;
+
+/// The default number of redemptions returned by a query
+const DEFAULT_REDEMPTIONS_LIMIT: u32 = 10;
+/// The max number of redemptions returned by a query
+const MAX_REDEMPTIONS_LIMIT: u32 = 50;
+
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -41,14 +58,25 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let mut config = Config {
+        grid_token_addr: deps.api.addr_validate(&msg.deposit_token_addr)?,
+        xgrid_token_addr: Addr::unchecked(""),
+        revenue_asset: None,
+        redemption: None,
+    };
+    if let Some(revenue_asset) = msg.revenue_asset {
+        validate_revenue_asset(deps.api, &config, &revenue_asset)?;
+        config.revenue_asset = Some(revenue_asset);
+    }
+    if let Some(redemption) = msg.redemption {
+        validate_redemption_params(&redemption)?;
+        config.redemption = Some(redemption);
+    }
+
     // Store config
-    CONFIG.save(
-        deps.storage,
-        &Config {
-            grid_token_addr: deps.api.addr_validate(&msg.deposit_token_addr)?,
-            xgrid_token_addr: Addr::unchecked(""),
-        },
-    )?;
+    CONFIG.save(deps.storage, &config)?;
+    OWNER.save(deps.storage, &deps.api.addr_validate(&msg.owner)?)?;
+    REVENUE_STATE.save(deps.storage, &Default::default())?;
 
     // Create the xGRID token
     let sub_msg: Vec<SubMsg> = vec![SubMsg {
@@ -83,6 +111,16 @@ pub fn instantiate(
 /// ## Variants
 /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
 /// it depending on the received template.
+///
+/// * **ExecuteMsg::DistributeRevenue {}** Distributes the attached native revenue asset to xGRID holders.
+///
+/// * **ExecuteMsg::ClaimRevenue { limit, receiver }** Claims the revenue accrued by the sender's xGRID.
+///
+/// * **ExecuteMsg::ClaimRedemption { id, instant }** Claims the GRID of a queued redemption.
+///
+/// * **ExecuteMsg::Compound { recipients }** Stakes GRID on behalf of several users in one call.
+///
+/// * **ExecuteMsg::SetEnterPaused { paused }** Pauses or resumes new deposits.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -92,7 +130,57 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::DistributeRevenue {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let amount = match &config.revenue_asset {
+                Some(AssetInfo::NativeToken { denom }) => must_pay(&info, denom)?,
+                Some(AssetInfo::Token { .. }) => {
+                    return Err(ContractError::Std(StdError::generic_err(
+                        "CW20 revenue must be distributed via Cw20::Send",
+                    )))
+                }
+                None => return Err(ContractError::RevenueDisabled {}),
+            };
+
+            distribute_revenue(deps, env, &config, amount)
+        }
+        ExecuteMsg::ClaimRevenue { limit, receiver } => claim_revenue(deps, info, limit, receiver),
+        ExecuteMsg::ClaimRedemption { id, instant } => {
+            claim_redemption(deps, env, info, id, instant)
+        }
+        ExecuteMsg::Compound { recipients } => compound(deps, env, info, recipients),
+        ExecuteMsg::SetEnterPaused { paused } => set_enter_paused(deps, info, paused),
+    }
+}
+
+/// Returns an error if new deposits are paused.
+fn assert_enter_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if ENTER_PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::EnterPaused {});
     }
+
+    Ok(())
+}
+
+/// Pauses or resumes new deposits. Leaving, claiming redemptions and claiming revenue stay
+/// available while paused, so stakers can always exit.
+///
+/// * **paused** whether new deposits are paused.
+fn set_enter_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    if OWNER.may_load(deps.storage)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ENTER_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_enter_paused"),
+        attr("paused", paused.to_string()),
+    ]))
 }
 
 /// The entry point to the contract for processing replies from submessages.
@@ -116,6 +204,9 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 .map_err(|e| StdError::generic_err(format!("{e}")))?;
 
             config.xgrid_token_addr = deps.api.addr_validate(&init_response.contract_address)?;
+            if let Some(revenue_asset) = &config.revenue_asset {
+                validate_revenue_asset(deps.api, &config, revenue_asset)?;
+            }
 
             CONFIG.save(deps.storage, &config)?;
 
@@ -136,27 +227,39 @@ fn receive_cw20(
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
-    let recipient = cw20_msg.sender;
+    let sender = cw20_msg.sender;
     let mut amount = cw20_msg.amount;
 
-    let mut total_deposit = query_token_balance(
-        &deps.querier,
-        &config.grid_token_addr,
-        env.contract.address.clone(),
-    )?;
+    let mut total_deposit = query_total_deposit(deps.as_ref(), &env, &config)?;
     let total_shares = query_supply(&deps.querier, &config.xgrid_token_addr)?;
 
-    match from_binary(&cw20_msg.msg)? {
-        Cw20HookMsg::Enter {} => {
+    let hook: Cw20HookMsg = from_binary(&cw20_msg.msg)?;
+    // xGRID can be minted to another address than the one which sent GRID
+    let recipient = match &hook {
+        Cw20HookMsg::EnterFor { recipient } => deps.api.addr_validate(recipient)?.to_string(),
+        _ => sender.clone(),
+    };
+
+    match hook {
+        Cw20HookMsg::DistributeRevenue {} => match &config.revenue_asset {
+            Some(AssetInfo::Token { contract_addr }) if *contract_addr == info.sender => {
+                distribute_revenue(deps, env, &config, amount)
+            }
+            Some(_) => Err(ContractError::Unauthorized {}),
+            None => Err(ContractError::RevenueDisabled {}),
+        },
+        Cw20HookMsg::Enter {} | Cw20HookMsg::EnterFor { .. } => {
             let mut messages = vec![];
             if info.sender != config.grid_token_addr {
                 return Err(ContractError::Unauthorized {});
             }
+            assert_enter_not_paused(deps.storage)?;
 
             // In a CW20 `send`, the total balance of the recipient is already increased.
             // To properly calculate the total amount of GRID deposited in staking, we should subtract the user deposit from the pool
             total_deposit -= amount;
-            let mint_amount: Uint128 = if total_shares.is_zero() || total_deposit.is_zero() {
+            let is_first_deposit = total_shares.is_zero() || total_deposit.is_zero();
+            let mint_amount: Uint128 = if is_first_deposit {
                 amount = amount
                     .checked_sub(MINIMUM_STAKE_AMOUNT)
                     .map_err(|_| ContractError::MinimumStakeAmountError {})?;
@@ -197,12 +300,30 @@ fn receive_cw20(
                 vec![],
             )?);
 
-            Ok(Response::new().add_messages(messages).add_attributes(vec![
-                attr("action", "enter"),
+            // The minimum stake amount is minted to the contract on the first deposit
+            let minted_shares = if is_first_deposit {
+                mint_amount + MINIMUM_STAKE_AMOUNT
+            } else {
+                mint_amount
+            };
+            save_totals(
+                deps.storage,
+                &env,
+                total_deposit + cw20_msg.amount,
+                total_shares + minted_shares,
+            )?;
+
+            let mut attrs = vec![attr("action", "enter")];
+            if recipient != sender {
+                attrs.push(attr("sender", sender));
+            }
+            attrs.extend([
                 attr("recipient", recipient),
                 attr("grid_amount", cw20_msg.amount),
                 attr("xgrid_amount", mint_amount),
-            ]))
+            ]);
+
+            Ok(Response::new().add_messages(messages).add_attributes(attrs))
         }
         Cw20HookMsg::Leave {} => {
             if info.sender != config.xgrid_token_addr {
@@ -213,30 +334,403 @@ fn receive_cw20(
                 .checked_mul(total_deposit)?
                 .checked_div(total_shares)?;
 
+            save_totals(
+                deps.storage,
+                &env,
+                total_deposit.checked_sub(what)?,
+                total_shares.checked_sub(amount)?,
+            )?;
+
             // Burn share
-            let res = Response::new()
-                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr: config.xgrid_token_addr.to_string(),
-                    msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
-                    funds: vec![],
-                }))
-                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            let mut res = Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.xgrid_token_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+                funds: vec![],
+            }));
+            let mut attrs = vec![
+                attr("action", "leave"),
+                attr("recipient", recipient.clone()),
+                attr("xgrid_amount", cw20_msg.amount),
+                attr("grid_amount", what),
+            ];
+
+            if let Some(params) = &config.redemption {
+                // GRID stays in the contract until the redemption is claimed
+                let id = REDEMPTION_COUNT.may_load(deps.storage)?.unwrap_or_default();
+                REDEMPTION_COUNT.save(deps.storage, &(id + 1))?;
+                let start = env.block.time.seconds();
+                REDEMPTIONS.save(
+                    deps.storage,
+                    (&deps.api.addr_validate(&recipient)?, id),
+                    &Redemption {
+                        amount: what,
+                        start,
+                        end: start + params.unbonding_period,
+                        max_penalty: params.max_penalty,
+                    },
+                )?;
+                let queued = QUEUED_GRID.may_load(deps.storage)?.unwrap_or_default();
+                QUEUED_GRID.save(deps.storage, &(queued + what))?;
+
+                attrs.extend([
+                    attr("redemption_id", id.to_string()),
+                    attr("end", (start + params.unbonding_period).to_string()),
+                ]);
+            } else {
+                res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: config.grid_token_addr.to_string(),
                     msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                        recipient: recipient.clone(),
+                        recipient,
                         amount: what,
                     })?,
                     funds: vec![],
                 }));
+            }
 
-            Ok(res.add_attributes(vec![
-                attr("action", "leave"),
-                attr("recipient", recipient),
-                attr("xgrid_amount", cw20_msg.amount),
-                attr("grid_amount", what),
-            ]))
+            Ok(res.add_attributes(attrs))
+        }
+    }
+}
+
+/// Stakes GRID pulled from the sender on behalf of the recipients. All recipients get xGRID at
+/// the exchange rate before the call, so the order of recipients doesn't matter. An event is
+/// emitted per recipient with the GRID staked and the xGRID minted for them.
+///
+/// * **recipients** the recipients of xGRID along with the amount of GRID staked for each of them.
+fn compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_enter_not_paused(deps.storage)?;
+
+    if recipients.is_empty() {
+        return Err(ContractError::NoCompoundRecipients {});
+    }
+
+    // The GRID is pulled after the shares are calculated, so the balance doesn't include it yet
+    let total_deposit = query_total_deposit(deps.as_ref(), &env, &config)?;
+    let total_shares = query_supply(&deps.querier, &config.xgrid_token_addr)?;
+    if total_shares.is_zero() || total_deposit.is_zero() {
+        return Err(ContractError::CompoundWithoutStakers {});
+    }
+
+    let mut grid_amount = Uint128::zero();
+    let mut xgrid_amount = Uint128::zero();
+    let mut messages = vec![];
+    let mut events = vec![];
+    for (recipient, amount) in recipients {
+        let recipient = deps.api.addr_validate(&recipient)?;
+        let mint_amount = amount
+            .checked_mul(total_shares)?
+            .checked_div(total_deposit)?;
+        if mint_amount.is_zero() {
+            return Err(ContractError::StakeAmountTooSmall {});
         }
+
+        grid_amount += amount;
+        xgrid_amount += mint_amount;
+        messages.push(wasm_execute(
+            &config.xgrid_token_addr,
+            &Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount: mint_amount,
+            },
+            vec![],
+        )?);
+        events.push(Event::new("compound_share").add_attributes([
+            attr("recipient", recipient),
+            attr("grid_amount", amount),
+            attr("xgrid_amount", mint_amount),
+        ]));
     }
+
+    save_totals(
+        deps.storage,
+        &env,
+        total_deposit + grid_amount,
+        total_shares + xgrid_amount,
+    )?;
+
+    let transfer_msg = wasm_execute(
+        &config.grid_token_addr,
+        &Cw20ExecuteMsg::TransferFrom {
+            owner: info.sender.to_string(),
+            recipient: env.contract.address.to_string(),
+            amount: grid_amount,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg)
+        .add_messages(messages)
+        .add_events(events)
+        .add_attributes([
+            attr("action", "compound"),
+            attr("sender", info.sender),
+            attr("grid_amount", grid_amount),
+            attr("xgrid_amount", xgrid_amount),
+        ]))
+}
+
+/// Sends the GRID of a queued redemption to the sender. A redemption claimed before the end of
+/// the unbonding period pays the current penalty, which stays in the staking pool and raises
+/// the exchange rate for the remaining xGRID holders.
+///
+/// * **id** the redemption id.
+///
+/// * **instant** whether to claim the redemption before the end of the unbonding period.
+fn claim_redemption(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    instant: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let redemption = REDEMPTIONS
+        .may_load(deps.storage, (&info.sender, id))?
+        .ok_or(ContractError::RedemptionNotFound { id })?;
+
+    let now = env.block.time.seconds();
+    if !instant && now < redemption.end {
+        return Err(ContractError::RedemptionNotMatured {
+            end: redemption.end,
+        });
+    }
+
+    let penalty = redemption.amount * redemption_penalty(&redemption, now);
+    if !penalty.is_zero() {
+        let total_deposit = query_total_deposit(deps.as_ref(), &env, &config)?;
+        let total_shares = query_supply(&deps.querier, &config.xgrid_token_addr)?;
+        save_totals(deps.storage, &env, total_deposit + penalty, total_shares)?;
+    }
+
+    REDEMPTIONS.remove(deps.storage, (&info.sender, id));
+    QUEUED_GRID.update(deps.storage, |queued| -> StdResult<_> {
+        Ok(queued.checked_sub(redemption.amount)?)
+    })?;
+
+    let amount = redemption.amount - penalty;
+
+    Ok(Response::new()
+        .add_message(wasm_execute(
+            &config.grid_token_addr,
+            &Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount,
+            },
+            vec![],
+        )?)
+        .add_attributes(vec![
+            attr("action", "claim_redemption"),
+            attr("user", info.sender),
+            attr("redemption_id", id.to_string()),
+            attr("grid_amount", amount),
+            attr("penalty", penalty),
+        ]))
+}
+
+/// Returns the penalty for claiming the redemption at the given time. It decays linearly from
+/// the max penalty to zero over the unbonding period.
+fn redemption_penalty(redemption: &Redemption, time: u64) -> Decimal {
+    if time >= redemption.end {
+        Decimal::zero()
+    } else {
+        redemption.max_penalty
+            * Decimal::from_ratio(redemption.end - time, redemption.end - redemption.start)
+    }
+}
+
+/// Returns the amount of GRID in the staking pool. GRID locked in queued redemptions is excluded.
+fn query_total_deposit(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    let balance = query_token_balance(
+        &deps.querier,
+        &config.grid_token_addr,
+        env.contract.address.clone(),
+    )?;
+    let queued = QUEUED_GRID.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(balance.checked_sub(queued)?)
+}
+
+/// Checks that the unbonding period is not zero and the penalty is less than 100%.
+fn validate_redemption_params(params: &RedemptionParams) -> Result<(), ContractError> {
+    if params.unbonding_period == 0 || params.max_penalty >= Decimal::one() {
+        return Err(ContractError::InvalidRedemptionParams {});
+    }
+
+    Ok(())
+}
+
+/// Checks that the revenue asset is valid and differs from GRID and xGRID.
+fn validate_revenue_asset(
+    api: &dyn Api,
+    config: &Config,
+    revenue_asset: &AssetInfo,
+) -> Result<(), ContractError> {
+    revenue_asset.check(api)?;
+
+    match revenue_asset {
+        AssetInfo::Token { contract_addr }
+            if *contract_addr == config.grid_token_addr
+                || *contract_addr == config.xgrid_token_addr =>
+        {
+            Err(ContractError::InvalidRevenueAsset {})
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Records a revenue distribution to xGRID holders. Revenue is split pro-rata to xGRID balances
+/// at the start of the current block, so xGRID minted in the same block doesn't receive any.
+///
+/// * **amount** amount of the revenue asset to distribute.
+fn distribute_revenue(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroRevenue {});
+    }
+
+    // xGRID balances are snapshotted at the start of a block while the supply history is recorded
+    // at the end of it. The xGRID locked in this contract on the first deposit can't be claimed.
+    let block_height = env.block.height;
+    let total_supply: Uint128 = deps.querier.query_wasm_smart(
+        &config.xgrid_token_addr,
+        &XGridQueryMsg::TotalSupplyAt {
+            block: block_height.saturating_sub(1),
+        },
+    )?;
+    let locked_shares =
+        query_xgrid_balance_at(deps.as_ref(), config, &env.contract.address, block_height)?;
+    let total_shares = total_supply.saturating_sub(locked_shares);
+    if total_shares.is_zero() {
+        return Err(ContractError::NoRevenueRecipients {});
+    }
+
+    let mut revenue_state = REVENUE_STATE.load(deps.storage)?;
+    REVENUE_DISTRIBUTIONS.save(
+        deps.storage,
+        revenue_state.distributions,
+        &RevenueDistribution {
+            block_height,
+            amount,
+            total_shares,
+        },
+    )?;
+    revenue_state.distributions += 1;
+    revenue_state.total_distributed += amount;
+    revenue_state.revenue_per_share += Decimal256::from_ratio(amount, total_shares);
+    REVENUE_STATE.save(deps.storage, &revenue_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "distribute_revenue"),
+        attr("amount", amount),
+        attr("total_shares", total_shares),
+    ]))
+}
+
+/// Sends the revenue accrued by the sender's xGRID to the receiver.
+///
+/// * **limit** max number of distributions to process.
+///
+/// * **receiver** address which receives the revenue. Defaults to the sender.
+fn claim_revenue(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let revenue_asset = config
+        .revenue_asset
+        .clone()
+        .ok_or(ContractError::RevenueDisabled {})?;
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+
+    let (pending, cursor) = compute_pending_revenue(deps.as_ref(), &config, &info.sender, limit)?;
+    REVENUE_CURSORS.save(deps.storage, &info.sender, &cursor)?;
+
+    let mut response = Response::new();
+    if !pending.is_zero() {
+        let mut revenue_state = REVENUE_STATE.load(deps.storage)?;
+        revenue_state.total_claimed += pending;
+        REVENUE_STATE.save(deps.storage, &revenue_state)?;
+
+        response = response.add_message(
+            Asset {
+                info: revenue_asset,
+                amount: pending,
+            }
+            .into_msg(&receiver)?,
+        );
+    }
+
+    Ok(response.add_attributes(vec![
+        attr("action", "claim_revenue"),
+        attr("user", info.sender),
+        attr("receiver", receiver),
+        attr("amount", pending),
+    ]))
+}
+
+/// Returns the revenue accrued by the user over at most `limit` distributions since the last
+/// claim, along with the id of the next distribution to claim.
+fn compute_pending_revenue(
+    deps: Deps,
+    config: &Config,
+    user: &Addr,
+    limit: Option<u32>,
+) -> StdResult<(Uint128, u64)> {
+    let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let mut cursor = REVENUE_CURSORS
+        .may_load(deps.storage, user)?
+        .unwrap_or_default();
+
+    let mut pending = Uint128::zero();
+    for item in REVENUE_DISTRIBUTIONS
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(cursor)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+    {
+        let (id, distribution) = item?;
+        let balance = query_xgrid_balance_at(deps, config, user, distribution.block_height)?;
+        pending += distribution
+            .amount
+            .multiply_ratio(balance, distribution.total_shares);
+        cursor = id + 1;
+    }
+
+    Ok((pending, cursor))
+}
+
+/// Returns the xGRID balance of the address at the start of the given block.
+fn query_xgrid_balance_at(
+    deps: Deps,
+    config: &Config,
+    address: &Addr,
+    block: u64,
+) -> StdResult<Uint128> {
+    let res: BalanceResponse = deps.querier.query_wasm_smart(
+        &config.xgrid_token_addr,
+        &XGridQueryMsg::BalanceAt {
+            address: address.to_string(),
+            block,
+        },
+    )?;
+
+    Ok(res.balance)
 }
 
 /// Exposes all the queries available in the contract.
@@ -247,6 +741,19 @@ fn receive_cw20(
 /// * **QueryMsg::TotalShares {}** Returns the total xGRID supply using a [`Uint128`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the amount of GRID that's currently in the staking pool using a [`Uint128`] object.
+///
+/// * **QueryMsg::ExchangeRateAt { block_height }** Returns the GRID/xGRID exchange rate at the specified block height
+/// using an [`ExchangeRateResponse`] object.
+///
+/// * **QueryMsg::RevenueInfo {}** Returns the revenue distribution totals using a [`RevenueInfoResponse`] object.
+///
+/// * **QueryMsg::PendingRevenue { address, limit }** Returns the revenue the address can claim
+/// using a [`PendingRevenueResponse`] object.
+///
+/// * **QueryMsg::Redemptions { address, start_after, limit }** Returns the queued redemptions of
+/// the address using a vector of [`RedemptionResponse`] objects.
+///
+/// * **QueryMsg::EnterPaused {}** Returns whether new deposits are paused using a [`bool`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let config = CONFIG.load(deps.storage)?;
@@ -254,38 +761,201 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => Ok(to_binary(&ConfigResponse {
             deposit_token_addr: config.grid_token_addr,
             share_token_addr: config.xgrid_token_addr,
+            revenue_asset: config.revenue_asset,
+            redemption: config.redemption,
         })?),
         QueryMsg::TotalShares {} => {
             to_binary(&query_supply(&deps.querier, &config.xgrid_token_addr)?)
         }
-        QueryMsg::TotalDeposit {} => to_binary(&query_token_balance(
-            &deps.querier,
-            &config.grid_token_addr,
-            env.contract.address,
-        )?),
+        QueryMsg::TotalDeposit {} => to_binary(&query_total_deposit(deps, &env, &config)?),
+        QueryMsg::ExchangeRateAt { block_height } => {
+            to_binary(&query_exchange_rate_at(deps, block_height)?)
+        }
+        QueryMsg::RevenueInfo {} => {
+            let revenue_state = REVENUE_STATE.may_load(deps.storage)?.unwrap_or_default();
+            to_binary(&RevenueInfoResponse {
+                revenue_asset: config.revenue_asset,
+                total_distributed: revenue_state.total_distributed,
+                total_claimed: revenue_state.total_claimed,
+                revenue_per_share: revenue_state.revenue_per_share,
+                distributions: revenue_state.distributions,
+            })
+        }
+        QueryMsg::PendingRevenue { address, limit } => {
+            to_binary(&query_pending_revenue(deps, config, address, limit)?)
+        }
+        QueryMsg::Redemptions {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_redemptions(deps, env, address, start_after, limit)?),
+        QueryMsg::EnterPaused {} => {
+            to_binary(&ENTER_PAUSED.may_load(deps.storage)?.unwrap_or_default())
+        }
     }
 }
 
+/// Returns the queued redemptions of the address along with their current penalties.
+pub fn query_redemptions(
+    deps: Deps,
+    env: Env,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RedemptionResponse>> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_REDEMPTIONS_LIMIT)
+        .min(MAX_REDEMPTIONS_LIMIT) as usize;
+    let now = env.block.time.seconds();
+
+    REDEMPTIONS
+        .prefix(&address)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (id, redemption) = item?;
+            let penalty = redemption_penalty(&redemption, now);
+
+            Ok(RedemptionResponse {
+                id,
+                amount: redemption.amount,
+                end: redemption.end,
+                penalty,
+                instant_amount: redemption.amount - redemption.amount * penalty,
+            })
+        })
+        .collect()
+}
+
+/// Returns the revenue the address can claim from at most `limit` distributions.
+pub fn query_pending_revenue(
+    deps: Deps,
+    config: Config,
+    address: String,
+    limit: Option<u32>,
+) -> StdResult<PendingRevenueResponse> {
+    let revenue_asset = config
+        .revenue_asset
+        .clone()
+        .ok_or_else(|| StdError::generic_err("Revenue distribution is disabled"))?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let (pending, cursor) = compute_pending_revenue(deps, &config, &address, limit)?;
+    let distributions = REVENUE_STATE.load(deps.storage)?.distributions;
+
+    Ok(PendingRevenueResponse {
+        pending: Asset {
+            info: revenue_asset,
+            amount: pending,
+        },
+        distributions_left: distributions.saturating_sub(cursor),
+    })
+}
+
+/// Saves the staking pool totals at the current block height.
+fn save_totals(
+    storage: &mut dyn Storage,
+    env: &Env,
+    total_deposit: Uint128,
+    total_shares: Uint128,
+) -> StdResult<()> {
+    TOTALS.save(
+        storage,
+        &StakingTotals {
+            total_deposit,
+            total_shares,
+        },
+        env.block.height,
+    )
+}
+
+/// Returns the GRID/xGRID exchange rate that was in the staking pool just preceeding
+/// the moment of the specified block height creation.
+pub fn query_exchange_rate_at(
+    deps: Deps,
+    block_height: Uint64,
+) -> StdResult<Option<ExchangeRateResponse>> {
+    let totals = TOTALS.may_load_at_height(deps.storage, block_height.u64())?;
+
+    Ok(totals.map(|totals| ExchangeRateResponse {
+        exchange_rate: if totals.total_shares.is_zero() {
+            Decimal::zero()
+        } else {
+            Decimal::from_ratio(totals.total_deposit, totals.total_shares)
+        },
+        total_deposit: totals.total_deposit,
+        total_shares: totals.total_shares,
+    }))
+}
+
 /// ## Description
 /// Used for migration of contract. Returns the default object of type [`Response`].
 /// ## Params
 /// * **_deps** is the object of type [`DepsMut`].
 ///
-/// * **_env** is the object of type [`Env`].
+/// * **env** is the object of type [`Env`].
 ///
-/// * **_msg** is the object of type [`MigrateMsg`].
+/// * **msg** is the object of type [`MigrateMsg`]. Enables revenue distribution if
+/// `revenue_asset` is set. Stores the owner if `owner` is set, which is required when migrating
+/// from versions which didn't store it.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "gridiron-staking" => match contract_version.version.as_ref() {
-            "1.0.0" | "1.0.1" | "1.0.2" => {}
+            "1.0.0" | "1.0.1" | "1.0.2" | "1.1.0" => {
+                // Record the current totals so the exchange rate can be queried from now on
+                let config = CONFIG.load(deps.storage)?;
+                let total_deposit = query_token_balance(
+                    &deps.querier,
+                    &config.grid_token_addr,
+                    env.contract.address.clone(),
+                )?;
+                let total_shares = query_supply(&deps.querier, &config.xgrid_token_addr)?;
+                save_totals(deps.storage, &env, total_deposit, total_shares)?;
+            }
+            "1.2.0" | "1.3.0" | "1.4.0" | "1.5.0" | "1.6.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    if REVENUE_STATE.may_load(deps.storage)?.is_none() {
+        REVENUE_STATE.save(deps.storage, &Default::default())?;
+    }
+
+    if let Some(revenue_asset) = msg.revenue_asset {
+        let mut config = CONFIG.load(deps.storage)?;
+        if config.revenue_asset.is_some() {
+            return Err(ContractError::RevenueAssetAlreadySet {});
+        }
+
+        validate_revenue_asset(deps.api, &config, &revenue_asset)?;
+        config.revenue_asset = Some(revenue_asset);
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    // Queued redemptions keep the parameters they were queued with
+    if let Some(redemption) = msg.redemption {
+        validate_redemption_params(&redemption)?;
+        let mut config = CONFIG.load(deps.storage)?;
+        config.redemption = Some(redemption);
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    if let Some(owner) = msg.owner {
+        OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+    } else if OWNER.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::OwnerNotSet {});
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
@@ -1,5 +1,6 @@
 use crate::contract::MINIMUM_STAKE_AMOUNT;
 use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 /// This enum describes staking contract errors
@@ -22,6 +23,45 @@ pub enum ContractError {
 
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    #[error("Revenue distribution is disabled")]
+    RevenueDisabled {},
+
+    #[error("Revenue asset is already set")]
+    RevenueAssetAlreadySet {},
+
+    #[error("Revenue asset must differ from GRID and xGRID")]
+    InvalidRevenueAsset {},
+
+    #[error("Revenue amount must be greater than zero")]
+    ZeroRevenue {},
+
+    #[error("There is no xGRID to distribute revenue to")]
+    NoRevenueRecipients {},
+
+    #[error("Unbonding period must be greater than zero and the penalty less than 100%")]
+    InvalidRedemptionParams {},
+
+    #[error("Redemption {id} not found")]
+    RedemptionNotFound { id: u64 },
+
+    #[error("Redemption can be claimed without a penalty after {end}. Set instant to pay it")]
+    RedemptionNotMatured { end: u64 },
+
+    #[error("Compound requires at least one recipient")]
+    NoCompoundRecipients {},
+
+    #[error("GRID can only be compounded into a staking pool which already has stakers")]
+    CompoundWithoutStakers {},
+
+    #[error("New deposits are paused")]
+    EnterPaused {},
+
+    #[error("The owner must be set on migration")]
+    OwnerNotSet {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -1,6 +1,8 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, Strategy};
+use gridiron::asset::AssetInfo;
+use gridiron::staking::{RedemptionParams, StakingTotals};
 
 /// This structure stores the main parameters for the staking contract.
 #[cw_serde]
@@ -9,7 +11,83 @@ pub struct Config {
     pub grid_token_addr: Addr,
     /// The xGRID token contract address
     pub xgrid_token_addr: Addr,
+    /// The asset distributed to xGRID holders in addition to GRID
+    #[serde(default)]
+    pub revenue_asset: Option<AssetInfo>,
+    /// The redemption queue parameters. `None` if xGRID is redeemed instantly
+    #[serde(default)]
+    pub redemption: Option<RedemptionParams>,
+}
+
+/// This structure stores the revenue distribution totals.
+#[cw_serde]
+#[derive(Default)]
+pub struct RevenueState {
+    /// The total amount of revenue distributed
+    pub total_distributed: Uint128,
+    /// The total amount of revenue claimed
+    pub total_claimed: Uint128,
+    /// The cumulative amount of revenue distributed per xGRID
+    pub revenue_per_share: Decimal256,
+    /// The number of distributions made. Also the id of the next distribution
+    pub distributions: u64,
+}
+
+/// This structure describes a single revenue distribution.
+#[cw_serde]
+pub struct RevenueDistribution {
+    /// The block at the start of which xGRID balances are taken into account
+    pub block_height: u64,
+    /// The amount of revenue distributed
+    pub amount: Uint128,
+    /// The xGRID supply eligible for the distribution
+    pub total_shares: Uint128,
+}
+
+/// This structure describes a queued xGRID redemption.
+#[cw_serde]
+pub struct Redemption {
+    /// The amount of GRID locked at the exchange rate of the moment the redemption was queued
+    pub amount: Uint128,
+    /// The timestamp (in seconds) when the redemption was queued
+    pub start: u64,
+    /// The timestamp (in seconds) after which the redemption can be claimed without a penalty
+    pub end: u64,
+    /// The penalty for claiming the redemption right after it was queued
+    pub max_penalty: Decimal,
 }
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the address which can pause new deposits
+pub const OWNER: Item<Addr> = Item::new("owner");
+
+/// Stores whether new deposits are paused
+pub const ENTER_PAUSED: Item<bool> = Item::new("enter_paused");
+
+/// Stores the staking pool totals to query the exchange rate later at any block height
+pub const TOTALS: SnapshotItem<StakingTotals> = SnapshotItem::new(
+    "totals",
+    "totals_check",
+    "totals_change",
+    Strategy::EveryBlock,
+);
+
+/// Stores the revenue distribution totals
+pub const REVENUE_STATE: Item<RevenueState> = Item::new("revenue_state");
+
+/// Stores revenue distributions by id
+pub const REVENUE_DISTRIBUTIONS: Map<u64, RevenueDistribution> = Map::new("revenue_distributions");
+
+/// Stores the id of the next distribution each address is yet to claim
+pub const REVENUE_CURSORS: Map<&Addr, u64> = Map::new("revenue_cursors");
+
+/// Stores queued redemptions by owner and id
+pub const REDEMPTIONS: Map<(&Addr, u64), Redemption> = Map::new("redemptions");
+
+/// Stores the number of redemptions queued so far. Also the id of the next redemption
+pub const REDEMPTION_COUNT: Item<u64> = Item::new("redemption_count");
+
+/// Stores the amount of GRID locked in queued redemptions
+pub const QUEUED_GRID: Item<Uint128> = Item::new("queued_grid");
@@ -1,10 +1,18 @@
 #![cfg(not(tarpaulin_include))]
 
-use gridiron::staking::{ConfigResponse, Cw20HookMsg, InstantiateMsg as xInstatiateMsg, QueryMsg};
+use gridiron::asset::{native_asset_info, AssetInfo};
+use gridiron::staking::{
+    ConfigResponse, Cw20HookMsg, ExchangeRateResponse, ExecuteMsg,
+    InstantiateMsg as xInstatiateMsg, PendingRevenueResponse, QueryMsg, RedemptionParams,
+    RedemptionResponse, RevenueInfoResponse,
+};
 use gridiron::token::InstantiateMsg;
-use cosmwasm_std::{attr, to_binary, Addr, QueryRequest, Uint128, WasmQuery};
+use cosmwasm_std::{
+    attr, coin, to_binary, Addr, Decimal, Decimal256, QueryRequest, Uint128, Uint64, WasmQuery,
+};
 use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 use cw_multi_test::{App, ContractWrapper, Executor};
+use gridiron_staking::error::ContractError;
 
 const ALICE: &str = "alice";
 const BOB: &str = "bob";
@@ -124,6 +132,15 @@ fn mock_app() -> App {
 }
 
 fn instantiate_contracts(router: &mut App, owner: Addr) -> (Addr, Addr, Addr) {
+    instantiate_contracts_with(router, owner, None, None)
+}
+
+fn instantiate_contracts_with(
+    router: &mut App,
+    owner: Addr,
+    revenue_asset: Option<AssetInfo>,
+    redemption: Option<RedemptionParams>,
+) -> (Addr, Addr, Addr) {
     let grid_token_contract = Box::new(ContractWrapper::new_with_empty(
         gridiron_token::contract::execute,
         gridiron_token::contract::instantiate,
@@ -179,6 +196,8 @@ fn instantiate_contracts(router: &mut App, owner: Addr) -> (Addr, Addr, Addr) {
         token_code_id: x_grid_token_code_id,
         deposit_token_addr: grid_token_instance.to_string(),
         marketing: None,
+        revenue_asset,
+        redemption,
     };
     let staking_instance = router
         .instantiate_contract(
@@ -870,3 +889,894 @@ fn should_not_allow_directly_burn_from_xgrid() {
         .unwrap_err();
     assert_eq!(res.root_cause().to_string(), "Unauthorized");
 }
+
+#[test]
+fn exchange_rate_snapshots() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+
+    let (grid_token_instance, staking_instance, _) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    mint_some_grid(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        ALICE,
+    );
+
+    let alice_address = Addr::unchecked(ALICE);
+    let start_height = router.block_info().height;
+
+    let query_rate = |router: &App, block_height: u64| -> Option<ExchangeRateResponse> {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &staking_instance,
+                &QueryMsg::ExchangeRateAt {
+                    block_height: Uint64::new(block_height),
+                },
+            )
+            .unwrap()
+    };
+
+    let enter_msg = |amount: u128| Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::Enter {}).unwrap(),
+        amount: Uint128::from(amount),
+    };
+
+    router
+        .execute_contract(
+            alice_address.clone(),
+            grid_token_instance.clone(),
+            &enter_msg(2000),
+            &[],
+        )
+        .unwrap();
+
+    // The totals are recorded at the end of the block
+    assert_eq!(query_rate(&router, start_height), None);
+
+    router.update_block(|b| b.height += 1);
+
+    assert_eq!(
+        query_rate(&router, start_height + 1),
+        Some(ExchangeRateResponse {
+            total_deposit: Uint128::from(2000u128),
+            total_shares: Uint128::from(2000u128),
+            exchange_rate: Decimal::one(),
+        })
+    );
+
+    // GRID sent to the contract directly is reflected after the next deposit
+    router
+        .execute_contract(
+            alice_address.clone(),
+            grid_token_instance.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: staking_instance.to_string(),
+                amount: Uint128::from(2000u128),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            alice_address.clone(),
+            grid_token_instance.clone(),
+            &enter_msg(1000),
+            &[],
+        )
+        .unwrap();
+
+    router.update_block(|b| b.height += 1);
+
+    assert_eq!(
+        query_rate(&router, start_height + 1).unwrap().exchange_rate,
+        Decimal::one()
+    );
+    assert_eq!(
+        query_rate(&router, start_height + 2),
+        Some(ExchangeRateResponse {
+            total_deposit: Uint128::from(5000u128),
+            total_shares: Uint128::from(2500u128),
+            exchange_rate: Decimal::from_ratio(2u128, 1u128),
+        })
+    );
+}
+
+#[test]
+fn enter_for() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+
+    let (grid_token_instance, staking_instance, x_grid_token_instance) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    mint_some_grid(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        ALICE,
+    );
+
+    let alice_address = Addr::unchecked(ALICE);
+
+    let enter_for_msg = |recipient: &str, amount: u128| Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::EnterFor {
+            recipient: recipient.to_string(),
+        })
+        .unwrap(),
+        amount: Uint128::from(amount),
+    };
+    let xgrid_balance = |router: &App, address: &str| -> Uint128 {
+        router
+            .wrap()
+            .query_wasm_smart::<BalanceResponse>(
+                &x_grid_token_instance,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap()
+            .balance
+    };
+
+    // Alice stakes 1100 GRID for Bob
+    let res = router
+        .execute_contract(
+            alice_address.clone(),
+            grid_token_instance.clone(),
+            &enter_for_msg(BOB, 1100),
+            &[],
+        )
+        .unwrap();
+    let enter_event = res
+        .events
+        .iter()
+        .find(|event| event.attributes.contains(&attr("action", "enter")))
+        .unwrap();
+    assert!(enter_event.attributes.contains(&attr("sender", ALICE)));
+    assert!(enter_event.attributes.contains(&attr("recipient", BOB)));
+    assert!(enter_event
+        .attributes
+        .contains(&attr("xgrid_amount", "100")));
+
+    assert_eq!(xgrid_balance(&router, ALICE), Uint128::zero());
+    assert_eq!(xgrid_balance(&router, BOB), Uint128::from(100u128));
+
+    // Only GRID can be staked on behalf of another address
+    let err = router
+        .execute_contract(
+            Addr::unchecked(BOB),
+            x_grid_token_instance.clone(),
+            &enter_for_msg(CAROL, 10),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    // Staking for yourself is the same as entering
+    let res = router
+        .execute_contract(
+            alice_address.clone(),
+            grid_token_instance.clone(),
+            &enter_for_msg(ALICE, 100),
+            &[],
+        )
+        .unwrap();
+    let enter_event = res
+        .events
+        .iter()
+        .find(|event| event.attributes.contains(&attr("action", "enter")))
+        .unwrap();
+    assert!(!enter_event
+        .attributes
+        .iter()
+        .any(|attribute| attribute.key == "sender"));
+    assert_eq!(xgrid_balance(&router, ALICE), Uint128::from(100u128));
+    assert_eq!(xgrid_balance(&router, BOB), Uint128::from(100u128));
+}
+
+#[test]
+fn compound() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+    let vault = Addr::unchecked("vault");
+
+    let (grid_token_instance, staking_instance, x_grid_token_instance) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    mint_some_grid(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        ALICE,
+    );
+    mint_some_grid(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        vault.as_str(),
+    );
+
+    let compound_msg = |recipients: &[(&str, u128)]| ExecuteMsg::Compound {
+        recipients: recipients
+            .iter()
+            .map(|(recipient, amount)| (recipient.to_string(), Uint128::from(*amount)))
+            .collect(),
+    };
+    let xgrid_balance = |router: &App, address: &str| -> Uint128 {
+        router
+            .wrap()
+            .query_wasm_smart::<BalanceResponse>(
+                &x_grid_token_instance,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap()
+            .balance
+    };
+
+    // GRID can't be compounded before anyone staked
+    let err = router
+        .execute_contract(
+            vault.clone(),
+            staking_instance.clone(),
+            &compound_msg(&[(BOB, 900)]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::CompoundWithoutStakers {}
+    );
+
+    // Alice stakes 2000 GRID and 1000 GRID of rewards accrue in the pool
+    router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            grid_token_instance.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: staking_instance.to_string(),
+                msg: to_binary(&Cw20HookMsg::Enter {}).unwrap(),
+                amount: Uint128::from(2000u128),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            grid_token_instance.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: staking_instance.to_string(),
+                amount: Uint128::from(1000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            vault.clone(),
+            staking_instance.clone(),
+            &compound_msg(&[]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoCompoundRecipients {}
+    );
+
+    let err = router
+        .execute_contract(
+            vault.clone(),
+            staking_instance.clone(),
+            &compound_msg(&[(BOB, 900), (CAROL, 1)]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::StakeAmountTooSmall {}
+    );
+
+    // The GRID is pulled from the vault, so it must be approved first
+    router
+        .execute_contract(
+            vault.clone(),
+            staking_instance.clone(),
+            &compound_msg(&[(BOB, 900), (CAROL, 600)]),
+            &[],
+        )
+        .unwrap_err();
+
+    router
+        .execute_contract(
+            vault.clone(),
+            grid_token_instance.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: staking_instance.to_string(),
+                amount: Uint128::from(1500u128),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Both recipients get xGRID at the rate of 1.5 GRID per xGRID
+    let res = router
+        .execute_contract(
+            vault.clone(),
+            staking_instance.clone(),
+            &compound_msg(&[(BOB, 900), (CAROL, 600)]),
+            &[],
+        )
+        .unwrap();
+    let share_events: Vec<_> = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-compound_share")
+        .map(|event| event.attributes[1..].to_vec())
+        .collect();
+    assert_eq!(
+        share_events,
+        vec![
+            vec![
+                attr("recipient", BOB),
+                attr("grid_amount", "900"),
+                attr("xgrid_amount", "600"),
+            ],
+            vec![
+                attr("recipient", CAROL),
+                attr("grid_amount", "600"),
+                attr("xgrid_amount", "400"),
+            ],
+        ]
+    );
+    let compound_event = res
+        .events
+        .iter()
+        .find(|event| event.attributes.contains(&attr("action", "compound")))
+        .unwrap();
+    assert!(compound_event.attributes.contains(&attr("sender", "vault")));
+    assert!(compound_event
+        .attributes
+        .contains(&attr("grid_amount", "1500")));
+    assert!(compound_event
+        .attributes
+        .contains(&attr("xgrid_amount", "1000")));
+
+    assert_eq!(xgrid_balance(&router, BOB), Uint128::from(600u128));
+    assert_eq!(xgrid_balance(&router, CAROL), Uint128::from(400u128));
+    assert_eq!(xgrid_balance(&router, vault.as_str()), Uint128::zero());
+
+    // The exchange rate is unchanged by compounding
+    let total_deposit: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    let total_shares: Uint128 = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::TotalShares {})
+        .unwrap();
+    assert_eq!(total_deposit.u128(), 4500);
+    assert_eq!(total_shares.u128(), 3000);
+}
+
+#[test]
+fn enter_paused() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+
+    let (grid_token_instance, staking_instance, x_grid_token_instance) =
+        instantiate_contracts(&mut router, owner.clone());
+
+    mint_some_grid(
+        &mut router,
+        owner.clone(),
+        grid_token_instance.clone(),
+        ALICE,
+    );
+
+    let enter_msg = |hook: &Cw20HookMsg| Cw20ExecuteMsg::Send {
+        contract: staking_instance.to_string(),
+        msg: to_binary(hook).unwrap(),
+        amount: Uint128::from(2000u128),
+    };
+    let enter_paused = |router: &App| -> bool {
+        router
+            .wrap()
+            .query_wasm_smart(&staking_instance, &QueryMsg::EnterPaused {})
+            .unwrap()
+    };
+
+    router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            grid_token_instance.clone(),
+            &enter_msg(&Cw20HookMsg::Enter {}),
+            &[],
+        )
+        .unwrap();
+    assert!(!enter_paused(&router));
+
+    // Only the owner can pause deposits
+    let err = router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            staking_instance.clone(),
+            &ExecuteMsg::SetEnterPaused { paused: true },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::SetEnterPaused { paused: true },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        res.events[1].attributes[1..],
+        [attr("action", "set_enter_paused"), attr("paused", "true")]
+    );
+    assert!(enter_paused(&router));
+
+    for hook in [
+        Cw20HookMsg::Enter {},
+        Cw20HookMsg::EnterFor {
+            recipient: BOB.to_string(),
+        },
+    ] {
+        let err = router
+            .execute_contract(
+                Addr::unchecked(ALICE),
+                grid_token_instance.clone(),
+                &enter_msg(&hook),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::EnterPaused {}
+        );
+    }
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            staking_instance.clone(),
+            &ExecuteMsg::Compound {
+                recipients: vec![(BOB.to_string(), Uint128::from(100u128))],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::EnterPaused {}
+    );
+
+    // Leaving is never paused
+    router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            x_grid_token_instance.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: staking_instance.to_string(),
+                msg: to_binary(&Cw20HookMsg::Leave {}).unwrap(),
+                amount: Uint128::from(500u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            owner,
+            staking_instance.clone(),
+            &ExecuteMsg::SetEnterPaused { paused: false },
+            &[],
+        )
+        .unwrap();
+    assert!(!enter_paused(&router));
+
+    router
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            grid_token_instance,
+            &enter_msg(&Cw20HookMsg::Enter {}),
+            &[],
+        )
+        .unwrap();
+}
+
+#[test]
+fn revenue_distribution() {
+    let owner = Addr::unchecked("owner");
+    let mut router = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, vec![coin(10_000, "uusdc")])
+            .unwrap()
+    });
+
+    let (grid_token_instance, staking_instance, x_grid_token_instance) = instantiate_contracts_with(
+        &mut router,
+        owner.clone(),
+        Some(native_asset_info("uusdc".to_string())),
+        None,
+    );
+
+    let alice_address = Addr::unchecked(ALICE);
+    let bob_address = Addr::unchecked(BOB);
+    let carol_address = Addr::unchecked(CAROL);
+
+    // Revenue can't be distributed while there is no xGRID
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::DistributeRevenue {},
+            &[coin(1000, "uusdc")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "There is no xGRID to distribute revenue to"
+    );
+
+    for (user, amount) in [(ALICE, 2000u128), (BOB, 1000u128)] {
+        mint_some_grid(
+            &mut router,
+            owner.clone(),
+            grid_token_instance.clone(),
+            user,
+        );
+        router
+            .execute_contract(
+                Addr::unchecked(user),
+                grid_token_instance.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: staking_instance.to_string(),
+                    msg: to_binary(&Cw20HookMsg::Enter {}).unwrap(),
+                    amount: Uint128::from(amount),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    router.update_block(|b| b.height += 1);
+
+    // The xGRID locked on the first deposit doesn't receive revenue
+    router
+        .execute_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::DistributeRevenue {},
+            &[coin(1000, "uusdc")],
+        )
+        .unwrap();
+
+    // xGRID transferred in the same block is accounted for in the next distribution
+    router
+        .execute_contract(
+            alice_address.clone(),
+            x_grid_token_instance,
+            &Cw20ExecuteMsg::Transfer {
+                recipient: CAROL.to_string(),
+                amount: Uint128::from(500u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    router.update_block(|b| b.height += 1);
+
+    router
+        .execute_contract(
+            owner.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::DistributeRevenue {},
+            &[coin(600, "uusdc")],
+        )
+        .unwrap();
+
+    let info: RevenueInfoResponse = router
+        .wrap()
+        .query_wasm_smart(&staking_instance, &QueryMsg::RevenueInfo {})
+        .unwrap();
+    assert_eq!(
+        info,
+        RevenueInfoResponse {
+            revenue_asset: Some(native_asset_info("uusdc".to_string())),
+            total_distributed: Uint128::from(1600u128),
+            total_claimed: Uint128::zero(),
+            revenue_per_share: Decimal256::from_ratio(8u128, 10u128),
+            distributions: 2,
+        }
+    );
+
+    let query_pending = |router: &App, user: &str, limit: Option<u32>| {
+        router
+            .wrap()
+            .query_wasm_smart::<PendingRevenueResponse>(
+                &staking_instance,
+                &QueryMsg::PendingRevenue {
+                    address: user.to_string(),
+                    limit,
+                },
+            )
+            .unwrap()
+    };
+
+    assert_eq!(
+        query_pending(&router, ALICE, None).pending.amount.u128(),
+        650
+    );
+    assert_eq!(query_pending(&router, BOB, None).pending.amount.u128(), 800);
+    assert_eq!(
+        query_pending(&router, CAROL, None).pending.amount.u128(),
+        150
+    );
+
+    let pending = query_pending(&router, ALICE, Some(1));
+    assert_eq!(pending.pending.amount.u128(), 500);
+    assert_eq!(pending.distributions_left, 1);
+
+    // Claims are processed in batches
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                alice_address.clone(),
+                staking_instance.clone(),
+                &ExecuteMsg::ClaimRevenue {
+                    limit: Some(1),
+                    receiver: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(
+        router
+            .wrap()
+            .query_balance(&alice_address, "uusdc")
+            .unwrap()
+            .amount
+            .u128(),
+        650
+    );
+    assert_eq!(query_pending(&router, ALICE, None).pending.amount.u128(), 0);
+
+    router
+        .execute_contract(
+            bob_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::ClaimRevenue {
+                limit: None,
+                receiver: Some(CAROL.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        router
+            .wrap()
+            .query_balance(&carol_address, "uusdc")
+            .unwrap()
+            .amount
+            .u128(),
+        800
+    );
+
+    // Only the configured revenue asset is accepted
+    let err = router
+        .execute_contract(
+            owner,
+            staking_instance,
+            &ExecuteMsg::DistributeRevenue {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "No funds sent");
+}
+
+#[test]
+fn redemption_queue() {
+    let mut router = mock_app();
+
+    let owner = Addr::unchecked("owner");
+
+    let (grid_token_instance, staking_instance, x_grid_token_instance) = instantiate_contracts_with(
+        &mut router,
+        owner.clone(),
+        None,
+        Some(RedemptionParams {
+            unbonding_period: 1000,
+            max_penalty: Decimal::percent(5),
+        }),
+    );
+
+    let alice_address = Addr::unchecked(ALICE);
+    let bob_address = Addr::unchecked(BOB);
+    for user in [ALICE, BOB] {
+        mint_some_grid(
+            &mut router,
+            owner.clone(),
+            grid_token_instance.clone(),
+            user,
+        );
+    }
+
+    let send_msg = |contract: &Addr, amount: u128, hook: Cw20HookMsg| Cw20ExecuteMsg::Send {
+        contract: contract.to_string(),
+        msg: to_binary(&hook).unwrap(),
+        amount: Uint128::from(amount),
+    };
+    let grid_balance = |router: &App, address: &str| -> Uint128 {
+        router
+            .wrap()
+            .query_wasm_smart::<BalanceResponse>(
+                &grid_token_instance,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap()
+            .balance
+    };
+    let total_deposit = |router: &App| -> Uint128 {
+        router
+            .wrap()
+            .query_wasm_smart(&staking_instance, &QueryMsg::TotalDeposit {})
+            .unwrap()
+    };
+    let redemptions = |router: &App, address: &str| -> Vec<RedemptionResponse> {
+        router
+            .wrap()
+            .query_wasm_smart(
+                &staking_instance,
+                &QueryMsg::Redemptions {
+                    address: address.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    };
+
+    for user in [&alice_address, &bob_address] {
+        router
+            .execute_contract(
+                user.clone(),
+                grid_token_instance.clone(),
+                &send_msg(&staking_instance, 2000, Cw20HookMsg::Enter {}),
+                &[],
+            )
+            .unwrap();
+    }
+
+    // Alice's GRID is queued instead of being sent back
+    router
+        .execute_contract(
+            alice_address.clone(),
+            x_grid_token_instance.clone(),
+            &send_msg(&staking_instance, 1000, Cw20HookMsg::Leave {}),
+            &[],
+        )
+        .unwrap();
+    let start = router.block_info().time.seconds();
+    assert_eq!(grid_balance(&router, ALICE), Uint128::new(8000));
+    assert_eq!(total_deposit(&router), Uint128::new(3000));
+    assert_eq!(
+        redemptions(&router, ALICE),
+        vec![RedemptionResponse {
+            id: 0,
+            amount: Uint128::new(1000),
+            end: start + 1000,
+            penalty: Decimal::percent(5),
+            instant_amount: Uint128::new(950),
+        }]
+    );
+
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::ClaimRedemption {
+                id: 0,
+                instant: false,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RedemptionNotMatured { end: start + 1000 }
+    );
+
+    // The penalty decays over the unbonding period and stays with the remaining stakers
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(400);
+    });
+    router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::ClaimRedemption {
+                id: 0,
+                instant: true,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(grid_balance(&router, ALICE), Uint128::new(8970));
+    assert_eq!(total_deposit(&router), Uint128::new(3030));
+    assert_eq!(redemptions(&router, ALICE), vec![]);
+
+    router
+        .execute_contract(
+            bob_address.clone(),
+            x_grid_token_instance.clone(),
+            &send_msg(&staking_instance, 2000, Cw20HookMsg::Leave {}),
+            &[],
+        )
+        .unwrap();
+    assert_eq!(redemptions(&router, BOB)[0].amount, Uint128::new(2020));
+
+    // Only the owner of a redemption can claim it
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::ClaimRedemption {
+                id: 1,
+                instant: true,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RedemptionNotFound { id: 1 }
+    );
+
+    // No penalty after the unbonding period
+    router.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(1000);
+    });
+    assert_eq!(redemptions(&router, BOB)[0].penalty, Decimal::zero());
+    router
+        .execute_contract(
+            bob_address.clone(),
+            staking_instance.clone(),
+            &ExecuteMsg::ClaimRedemption {
+                id: 1,
+                instant: false,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(grid_balance(&router, BOB), Uint128::new(10020));
+    assert_eq!(total_deposit(&router), Uint128::new(1010));
+}
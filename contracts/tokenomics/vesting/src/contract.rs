@@ -3,15 +3,22 @@ use cosmwasm_std::{
     Response, StdError, StdResult, SubMsg, Uint128,
 };
 
-use crate::state::{read_vesting_infos, Config, CONFIG, OWNERSHIP_PROPOSAL, VESTING_INFO};
+use crate::staking::{
+    accrue_user_rewards, checkpoint_user, claim_rewards_msg, compute_reward_states, stake,
+    take_user_rewards, unstake, update_reward_states,
+};
+use crate::state::{
+    read_claim_history, read_vesting_infos, Config, CLAIM_HISTORY, CLAIM_REDIRECTS, CONFIG,
+    OWNERSHIP_PROPOSAL, USER_REWARDS, VESTING_INFO,
+};
 
 use crate::error::ContractError;
-use gridiron::asset::{addr_opt_validate, token_asset_info, AssetInfo, AssetInfoExt};
+use gridiron::asset::{addr_opt_validate, token_asset_info, Asset, AssetInfo, AssetInfoExt};
 use gridiron::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use gridiron::vesting::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy, QueryMsg,
-    VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo, VestingSchedule,
-    VestingSchedulePoint,
+    ClaimHistoryItem, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy,
+    QueryMsg, VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo,
+    VestingSchedule, VestingSchedulePoint,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
@@ -31,16 +38,23 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     msg.vesting_token.check(deps.api)?;
 
+    let generator = addr_opt_validate(deps.api, &msg.generator)?;
+    // Only CW20 LP tokens can be staked in the generator
+    if generator.is_some() && msg.vesting_token.is_native_token() {
+        return Err(ContractError::StakingNotSupported {});
+    }
+
     CONFIG.save(
         deps.storage,
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
             vesting_token: msg.vesting_token,
+            generator,
         },
     )?;
 
@@ -51,12 +65,21 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::Claim { recipient, amount }** Claims vested tokens and transfers them to the vesting recipient.
 ///
+/// * **ExecuteMsg::ClaimRewards { recipient }** Claims the generator rewards accrued by the
+/// sender's staked vesting tokens.
+///
 /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
 /// depending on the received template.
 ///
 /// * **ExecuteMsg::RegisterVestingAccounts { vesting_accounts }** Registers vesting accounts
 /// using the provided vector of [`VestingAccount`] structures.
 ///
+/// * **ExecuteMsg::RegisterVestingStreams { streams }** Registers continuous vesting streams
+/// using the provided vector of [`gridiron::vesting::VestingStream`] structures.
+///
+/// * **ExecuteMsg::RedirectClaims { recipient }** Sets the address which receives the tokens
+/// claimed by the sender.
+///
 /// * **ExecuteMsg::WithdrawFromActiveSchedule { account, recipient, withdraw_amount }**
 /// Withdraws tokens from the only one active vesting schedule of the specified account.
 ///
@@ -75,6 +98,7 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Claim { recipient, amount } => claim(deps, env, info, recipient, amount),
+        ExecuteMsg::ClaimRewards { recipient } => claim_rewards(deps, env, info, recipient),
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::RegisterVestingAccounts { vesting_accounts } => {
             let config = CONFIG.load(deps.storage)?;
@@ -87,6 +111,19 @@ pub fn execute(
                 _ => Err(ContractError::Unauthorized {}),
             }
         }
+        ExecuteMsg::RegisterVestingStreams { streams } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            match &config.vesting_token {
+                AssetInfo::NativeToken { denom } if info.sender == config.owner => {
+                    let amount = must_pay(&info, denom)?;
+                    let vesting_accounts = streams.into_iter().map(Into::into).collect();
+                    register_vesting_accounts(deps, env, vesting_accounts, amount)
+                }
+                _ => Err(ContractError::Unauthorized {}),
+            }
+        }
+        ExecuteMsg::RedirectClaims { recipient } => redirect_claims(deps, info, recipient),
         ExecuteMsg::WithdrawFromActiveSchedule {
             account,
             recipient,
@@ -146,6 +183,10 @@ fn receive_cw20(
         Cw20HookMsg::RegisterVestingAccounts { vesting_accounts } => {
             register_vesting_accounts(deps, env, vesting_accounts, cw20_msg.amount)
         }
+        Cw20HookMsg::RegisterVestingStreams { streams } => {
+            let vesting_accounts = streams.into_iter().map(Into::into).collect();
+            register_vesting_accounts(deps, env, vesting_accounts, cw20_msg.amount)
+        }
     }
 }
 
@@ -160,7 +201,10 @@ pub fn register_vesting_accounts(
     vesting_accounts: Vec<VestingAccount>,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let response = Response::new();
+    let config = CONFIG.load(deps.storage)?;
+    let mut response = Response::new();
+
+    update_reward_states(deps.storage, &deps.querier, &env, &config)?;
 
     let mut to_deposit = Uint128::zero();
 
@@ -179,16 +223,22 @@ pub fn register_vesting_accounts(
             to_deposit = to_deposit.checked_add(amount)?;
         }
 
+        let mut staked_amount = Uint128::zero();
         if let Some(mut old_info) = VESTING_INFO.may_load(deps.storage, &account_address)? {
             if old_info.schedules.len() + 1 > SCHEDULES_LIMIT {
                 return Err(ContractError::ExceedSchedulesMaximumLimit(
                     vesting_account.address,
                 ));
             };
+            staked_amount = unclaimed_amount(&old_info)?;
             released_amount = old_info.released_amount;
             vesting_account.schedules.append(&mut old_info.schedules);
         }
 
+        if config.generator.is_some() {
+            checkpoint_user(deps.storage, &account_address, staked_amount)?;
+        }
+
         VESTING_INFO.save(
             deps.storage,
             &account_address,
@@ -203,6 +253,12 @@ pub fn register_vesting_accounts(
         return Err(ContractError::VestingScheduleAmountError {});
     }
 
+    if let Some(generator) = &config.generator {
+        if !to_deposit.is_zero() {
+            response = response.add_message(stake(deps.storage, &config, generator, to_deposit)?);
+        }
+    }
+
     Ok(response.add_attributes({
         vec![
             attr("action", "register_vesting_accounts"),
@@ -253,6 +309,9 @@ pub fn claim(
     let available_amount = compute_available_amount(env.block.time.seconds(), &vesting_info)?;
 
     let claim_amount = if let Some(a) = amount {
+        if a.is_zero() {
+            return Err(ContractError::ZeroClaimAmount {});
+        }
         if a > available_amount {
             return Err(ContractError::AmountIsNotAvailable {});
         };
@@ -264,23 +323,174 @@ pub fn claim(
     let mut response = Response::new();
 
     if !claim_amount.is_zero() {
-        let transfer_msg = config.vesting_token.with_balance(claim_amount).into_msg(
-            addr_opt_validate(deps.api, &recipient)?.unwrap_or_else(|| info.sender.clone()),
-        )?;
+        if let Some(generator) = &config.generator {
+            update_reward_states(deps.storage, &deps.querier, &env, &config)?;
+            checkpoint_user(deps.storage, &info.sender, unclaimed_amount(&vesting_info)?)?;
+            response =
+                response.add_message(unstake(deps.storage, &config, generator, claim_amount)?);
+        }
+
+        let recipient = match addr_opt_validate(deps.api, &recipient)? {
+            Some(recipient) => recipient,
+            None => CLAIM_REDIRECTS
+                .may_load(deps.storage, &info.sender)?
+                .unwrap_or_else(|| info.sender.clone()),
+        };
+        let transfer_msg = config
+            .vesting_token
+            .with_balance(claim_amount)
+            .into_msg(recipient)?;
         response = response.add_submessage(SubMsg::new(transfer_msg));
 
         vesting_info.released_amount = vesting_info.released_amount.checked_add(claim_amount)?;
         VESTING_INFO.save(deps.storage, &info.sender, &vesting_info)?;
+
+        // Several claims in the same block are merged into one history item
+        let timestamp = env.block.time.seconds();
+        let claimed_amount = CLAIM_HISTORY
+            .may_load(deps.storage, (&info.sender, timestamp))?
+            .map_or(Ok(claim_amount), |item| {
+                item.claimed_amount.checked_add(claim_amount)
+            })?;
+        CLAIM_HISTORY.save(
+            deps.storage,
+            (&info.sender, timestamp),
+            &ClaimHistoryItem {
+                timestamp,
+                claimed_amount,
+                total_claimed: vesting_info.released_amount,
+            },
+        )?;
     };
 
+    let schedule_index = released_schedule_index(&vesting_info)?
+        .map(|index| index.to_string())
+        .unwrap_or_default();
+
     Ok(response.add_attributes(vec![
         attr("action", "claim"),
         attr("address", &info.sender),
         attr("available_amount", available_amount),
         attr("claimed_amount", claim_amount),
+        attr("schedule_index", schedule_index),
+        attr("remaining_amount", unclaimed_amount(&vesting_info)?),
+        attr(
+            "remaining_available_amount",
+            available_amount - claim_amount,
+        ),
     ]))
 }
 
+/// Sets the address which receives the tokens claimed by the sender unless a claim specifies a recipient.
+///
+/// * **recipient** address that receives the claimed tokens. `None` removes the redirect.
+fn redirect_claims(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    // Only vesting recipients can redirect their claims
+    if !VESTING_INFO.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = addr_opt_validate(deps.api, &recipient)?;
+    let mut attributes = vec![
+        attr("action", "redirect_claims"),
+        attr("address", &info.sender),
+    ];
+    if let Some(recipient) = recipient {
+        CLAIM_REDIRECTS.save(deps.storage, &info.sender, &recipient)?;
+        attributes.push(attr("recipient", recipient));
+    } else {
+        CLAIM_REDIRECTS.remove(deps.storage, &info.sender);
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Claims the generator rewards accrued by the sender's staked vesting tokens and transfers them
+/// to the recipient.
+///
+/// * **recipient** address that receives the rewards. The sender is used if it is not specified.
+fn claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let generator = config
+        .generator
+        .as_ref()
+        .ok_or(ContractError::StakingNotSupported {})?;
+    let vesting_info = VESTING_INFO.load(deps.storage, &info.sender)?;
+
+    update_reward_states(deps.storage, &deps.querier, &env, &config)?;
+    checkpoint_user(deps.storage, &info.sender, unclaimed_amount(&vesting_info)?)?;
+    let rewards = take_user_rewards(deps.storage, &info.sender)?;
+
+    let recipient = addr_opt_validate(deps.api, &recipient)?.unwrap_or_else(|| info.sender.clone());
+    let mut attributes = vec![
+        attr("action", "claim_rewards"),
+        attr("address", &info.sender),
+        attr("recipient", &recipient),
+    ];
+
+    // Pending generator rewards must be received before they are sent out
+    let mut messages = vec![claim_rewards_msg(&config, generator)?];
+    for reward in rewards {
+        attributes.push(attr("reward", reward.to_string()));
+        messages.push(reward.into_msg(&recipient)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// Returns the total amount of tokens vested by a [`VestingSchedule`].
+fn schedule_total_amount(schedule: &VestingSchedule) -> Uint128 {
+    schedule
+        .end_point
+        .map_or(schedule.start_point.amount, |end_point| end_point.amount)
+}
+
+/// Returns the amount of tokens vested by all schedules of a recipient which was not claimed yet.
+/// This amount stays staked in the generator if staking is enabled.
+fn unclaimed_amount(vesting_info: &VestingInfo) -> StdResult<Uint128> {
+    let total_amount = vesting_info
+        .schedules
+        .iter()
+        .try_fold(Uint128::zero(), |acc, sch| {
+            acc.checked_add(schedule_total_amount(sch))
+        })?;
+
+    Ok(total_amount.saturating_sub(vesting_info.released_amount))
+}
+
+/// Returns the index of the schedule which the last claimed tokens were released from.
+/// Released tokens are attributed to schedules in the order of their start time.
+fn released_schedule_index(vesting_info: &VestingInfo) -> StdResult<Option<usize>> {
+    if vesting_info.released_amount.is_zero() {
+        return Ok(None);
+    }
+
+    let mut indexes: Vec<usize> = (0..vesting_info.schedules.len()).collect();
+    indexes.sort_by_key(|&index| vesting_info.schedules[index].start_point.time);
+
+    let mut cumulative_amount = Uint128::zero();
+    for index in indexes {
+        cumulative_amount =
+            cumulative_amount.checked_add(schedule_total_amount(&vesting_info.schedules[index]))?;
+        if cumulative_amount >= vesting_info.released_amount {
+            return Ok(Some(index));
+        }
+    }
+
+    Ok(None)
+}
+
 /// Computes the amount of vested and yet unclaimed tokens for a specific vesting recipient.
 /// Returns the computed amount if the operation is successful.
 ///
@@ -365,6 +575,11 @@ fn withdraw_from_active_schedule(
     let mut vesting_info = VESTING_INFO.load(deps.storage, &acc)?;
     let block_time = env.block.time.seconds();
 
+    if config.generator.is_some() {
+        update_reward_states(deps.storage, &deps.querier, &env, &config)?;
+        checkpoint_user(deps.storage, &acc, unclaimed_amount(&vesting_info)?)?;
+    }
+
     let mut active_schedules = vesting_info.schedules.iter_mut().filter(|schedule| {
         if let Some(end_point) = schedule.end_point {
             block_time >= schedule.start_point.time && block_time < end_point.time
@@ -402,13 +617,18 @@ fn withdraw_from_active_schedule(
 
     VESTING_INFO.save(deps.storage, &acc, &vesting_info)?;
 
+    let mut response = Response::new();
+    if let Some(generator) = &config.generator {
+        response = response.add_message(unstake(deps.storage, &config, generator, amount)?);
+    }
+
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender);
     let transfer_msg = config
         .vesting_token
         .with_balance(amount)
         .into_msg(receiver.clone())?;
 
-    Ok(Response::new().add_message(transfer_msg).add_attributes([
+    Ok(response.add_message(transfer_msg).add_attributes([
         attr("action", "withdraw_from_active_schedule"),
         attr("account", account),
         attr("amount", amount),
@@ -430,6 +650,18 @@ fn withdraw_from_active_schedule(
 ///         }** Returns a list of vesting schedules together with their vesting recipients.
 ///
 /// * **QueryMsg::AvailableAmount { address }** Returns the available amount of tokens that can be claimed by a specific vesting recipient.
+///
+/// * **QueryMsg::ClaimHistory {
+///             address,
+///             start_after,
+///             limit,
+///         }** Returns the claims made by a specific vesting recipient.
+///
+/// * **QueryMsg::PendingRewards { address }** Returns the generator rewards which can be claimed
+/// by a specific vesting recipient.
+///
+/// * **QueryMsg::ClaimRedirect { address }** Returns the address which receives the tokens claimed
+/// by a specific vesting recipient.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -451,6 +683,25 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             deps, env, address,
         )?)?),
         QueryMsg::Timestamp {} => Ok(to_binary(&query_timestamp(env)?)?),
+        QueryMsg::ClaimHistory {
+            address,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_claim_history(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::PendingRewards { address } => {
+            Ok(to_binary(&query_pending_rewards(deps, env, address)?)?)
+        }
+        QueryMsg::ClaimRedirect { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_binary(
+                &CLAIM_REDIRECTS.may_load(deps.storage, &address)?,
+            )?)
+        }
     }
 }
 
@@ -461,6 +712,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(ConfigResponse {
         owner: config.owner,
         vesting_token: config.vesting_token,
+        generator: config.generator,
     })
 }
 
@@ -516,6 +768,50 @@ pub fn query_vesting_available_amount(deps: Deps, env: Env, address: String) ->
     Ok(available_amount)
 }
 
+/// Returns the claims made by a specific vesting recipient in ascending order.
+///
+/// * **address** vesting recipient for which to return the claims.
+///
+/// * **start_after** claim timestamp from which to start reading the claims.
+///
+/// * **limit** amount of claims to return.
+pub fn query_claim_history(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ClaimHistoryItem>> {
+    let address = deps.api.addr_validate(&address)?;
+
+    read_claim_history(deps, &address, start_after, limit)
+}
+
+/// Returns the generator rewards which can be claimed by a specific vesting recipient.
+///
+/// * **address** vesting recipient for which to return the rewards.
+pub fn query_pending_rewards(deps: Deps, env: Env, address: String) -> StdResult<Vec<Asset>> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let staked_amount = match VESTING_INFO.may_load(deps.storage, &address)? {
+        Some(vesting_info) => unclaimed_amount(&vesting_info)?,
+        None => Uint128::zero(),
+    };
+    let states = compute_reward_states(deps.storage, &deps.querier, &env, &config)?;
+    let user_rewards = USER_REWARDS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    Ok(accrue_user_rewards(&states, &user_rewards, staked_amount)?
+        .into_iter()
+        .filter(|reward| !reward.pending.is_zero())
+        .map(|reward| Asset {
+            info: reward.info,
+            amount: reward.pending,
+        })
+        .collect())
+}
+
 /// Manages contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
@@ -523,7 +819,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     match contract_version.contract.as_ref() {
         "gridiron-vesting" => match contract_version.version.as_ref() {
-            "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" => {}
+            "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" | "1.3.2" | "1.4.0" | "1.5.0" | "1.6.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
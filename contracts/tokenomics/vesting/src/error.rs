@@ -20,6 +20,9 @@ pub enum ContractError {
     #[error("Amount is not available!")]
     AmountIsNotAvailable {},
 
+    #[error("Claimed amount must not be zero")]
+    ZeroClaimAmount {},
+
     #[error("Vesting schedule error on addr: {0}. Should satisfy: (start < end, end > current_time and start_amount < end_amount)")]
     VestingScheduleError(String),
 
@@ -42,6 +45,9 @@ pub enum ContractError {
 
     #[error("Failed to withdraw from active schedule: amount left {0}")]
     NotEnoughTokens(Uint128),
+
+    #[error("Generator staking is only supported for CW20 vesting tokens")]
+    StakingNotSupported {},
 }
 
 impl From<OverflowError> for ContractError {
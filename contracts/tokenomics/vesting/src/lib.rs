@@ -1,5 +1,6 @@
 pub mod contract;
 pub mod error;
+pub mod staking;
 pub mod state;
 
 #[cfg(test)]
@@ -0,0 +1,261 @@
+use cosmwasm_std::{
+    to_binary, wasm_execute, Addr, CosmosMsg, Decimal256, Env, QuerierWrapper, StdResult, Storage,
+    Uint128, Uint256,
+};
+use cw20::Cw20ExecuteMsg;
+
+use gridiron::asset::{Asset, AssetInfo};
+use gridiron::generator::{
+    Cw20HookMsg as GeneratorHookMsg, ExecuteMsg as GeneratorExecuteMsg, PendingTokenResponse,
+    QueryMsg as GeneratorQueryMsg, RewardInfoResponse,
+};
+
+use crate::state::{Config, RewardState, UserReward, REWARD_STATES, TOTAL_STAKED, USER_REWARDS};
+
+/// Returns the reward states with the generator rewards received since the last update distributed
+/// among the staked vesting tokens. Rewards which are still pending in the generator are included
+/// as the generator pays them out on any deposit, withdrawal or claim the contract sends in the
+/// same transaction.
+pub fn compute_reward_states(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+) -> StdResult<Vec<RewardState>> {
+    let mut states = REWARD_STATES.may_load(storage)?.unwrap_or_default();
+
+    let generator = match &config.generator {
+        Some(generator) => generator,
+        None => return Ok(states),
+    };
+
+    // Nothing accrues while there is no position in the generator
+    let total_staked = TOTAL_STAKED.may_load(storage)?.unwrap_or_default();
+    if total_staked.is_zero() {
+        return Ok(states);
+    }
+
+    let lp_token = config.vesting_token.to_string();
+    let reward_info: RewardInfoResponse = querier.query_wasm_smart(
+        generator,
+        &GeneratorQueryMsg::RewardInfo {
+            lp_token: lp_token.clone(),
+        },
+    )?;
+    let pending: PendingTokenResponse = querier.query_wasm_smart(
+        generator,
+        &GeneratorQueryMsg::PendingToken {
+            lp_token,
+            user: env.contract.address.to_string(),
+        },
+    )?;
+
+    let rewards = std::iter::once(Asset {
+        info: reward_info.base_reward_token,
+        amount: pending.pending,
+    })
+    .chain(pending.pending_on_proxy.unwrap_or_default())
+    .chain(
+        reward_info
+            .proxy_reward_tokens
+            .into_iter()
+            .map(|token| Asset {
+                info: AssetInfo::cw20(token),
+                amount: Uint128::zero(),
+            }),
+    );
+
+    let mut pending_rewards: Vec<Asset> = vec![];
+    for reward in rewards {
+        // Staked vesting tokens must never be distributed as rewards
+        if reward.info == config.vesting_token {
+            continue;
+        }
+
+        if !states.iter().any(|state| state.info == reward.info) {
+            states.push(RewardState {
+                info: reward.info.clone(),
+                index: Decimal256::zero(),
+                balance: Uint128::zero(),
+            });
+        }
+
+        match pending_rewards
+            .iter_mut()
+            .find(|asset| asset.info == reward.info)
+        {
+            Some(asset) => asset.amount = asset.amount.checked_add(reward.amount)?,
+            None => pending_rewards.push(reward),
+        }
+    }
+
+    for state in states.iter_mut() {
+        let pending_amount = pending_rewards
+            .iter()
+            .find(|asset| asset.info == state.info)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        let received = state
+            .info
+            .query_pool(querier, &env.contract.address)?
+            .saturating_sub(state.balance)
+            .checked_add(pending_amount)?;
+
+        state.index = state
+            .index
+            .checked_add(Decimal256::from_ratio(received, total_staked))?;
+        state.balance = state.balance.checked_add(received)?;
+    }
+
+    Ok(states)
+}
+
+/// Distributes the generator rewards received since the last update and saves the new reward states.
+/// Must be called before the amount of staked vesting tokens changes.
+pub fn update_reward_states(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+) -> StdResult<()> {
+    if config.generator.is_none() {
+        return Ok(());
+    }
+
+    let states = compute_reward_states(storage, querier, env, config)?;
+    REWARD_STATES.save(storage, &states)
+}
+
+/// Returns the rewards of a vesting recipient accrued up to the given reward states.
+///
+/// * **staked_amount** amount of vesting tokens the recipient had staked since the last update.
+pub fn accrue_user_rewards(
+    states: &[RewardState],
+    user_rewards: &[UserReward],
+    staked_amount: Uint128,
+) -> StdResult<Vec<UserReward>> {
+    states
+        .iter()
+        .map(|state| {
+            // Reward tokens added after the last update are accrued from the start
+            let (index, pending) = user_rewards
+                .iter()
+                .find(|reward| reward.info == state.info)
+                .map(|reward| (reward.index, reward.pending))
+                .unwrap_or_default();
+            let accrued: Uint128 =
+                (Uint256::from(staked_amount) * (state.index - index)).try_into()?;
+
+            Ok(UserReward {
+                info: state.info.clone(),
+                index: state.index,
+                pending: pending.checked_add(accrued)?,
+            })
+        })
+        .collect()
+}
+
+/// Accrues the rewards of a vesting recipient up to the current reward states.
+///
+/// * **staked_amount** amount of vesting tokens the recipient had staked since the last update.
+pub fn checkpoint_user(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    staked_amount: Uint128,
+) -> StdResult<()> {
+    let states = REWARD_STATES.may_load(storage)?.unwrap_or_default();
+    let user_rewards = USER_REWARDS.may_load(storage, user)?.unwrap_or_default();
+
+    USER_REWARDS.save(
+        storage,
+        user,
+        &accrue_user_rewards(&states, &user_rewards, staked_amount)?,
+    )
+}
+
+/// Takes the accrued rewards of a vesting recipient out of the distributed reward balances.
+/// The recipient must be checkpointed beforehand.
+pub fn take_user_rewards(storage: &mut dyn Storage, user: &Addr) -> StdResult<Vec<Asset>> {
+    let mut states = REWARD_STATES.may_load(storage)?.unwrap_or_default();
+    let mut user_rewards = USER_REWARDS.may_load(storage, user)?.unwrap_or_default();
+
+    let mut rewards = vec![];
+    for user_reward in user_rewards
+        .iter_mut()
+        .filter(|reward| !reward.pending.is_zero())
+    {
+        if let Some(state) = states
+            .iter_mut()
+            .find(|state| state.info == user_reward.info)
+        {
+            state.balance = state.balance.checked_sub(user_reward.pending)?;
+        }
+
+        rewards.push(Asset {
+            info: user_reward.info.clone(),
+            amount: user_reward.pending,
+        });
+        user_reward.pending = Uint128::zero();
+    }
+
+    REWARD_STATES.save(storage, &states)?;
+    USER_REWARDS.save(storage, user, &user_rewards)?;
+
+    Ok(rewards)
+}
+
+/// Increases the amount of staked vesting tokens and returns a message staking them in the generator.
+pub fn stake(
+    storage: &mut dyn Storage,
+    config: &Config,
+    generator: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let total_staked = TOTAL_STAKED.may_load(storage)?.unwrap_or_default();
+    TOTAL_STAKED.save(storage, &total_staked.checked_add(amount)?)?;
+
+    Ok(wasm_execute(
+        config.vesting_token.to_string(),
+        &Cw20ExecuteMsg::Send {
+            contract: generator.to_string(),
+            amount,
+            msg: to_binary(&GeneratorHookMsg::Deposit {})?,
+        },
+        vec![],
+    )?
+    .into())
+}
+
+/// Decreases the amount of staked vesting tokens and returns a message unstaking them from the
+/// generator.
+pub fn unstake(
+    storage: &mut dyn Storage,
+    config: &Config,
+    generator: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let total_staked = TOTAL_STAKED.load(storage)?;
+    TOTAL_STAKED.save(storage, &total_staked.checked_sub(amount)?)?;
+
+    Ok(wasm_execute(
+        generator,
+        &GeneratorExecuteMsg::Withdraw {
+            lp_token: config.vesting_token.to_string(),
+            amount,
+        },
+        vec![],
+    )?
+    .into())
+}
+
+/// Returns a message claiming the generator rewards of the contract position.
+pub fn claim_rewards_msg(config: &Config, generator: &Addr) -> StdResult<CosmosMsg> {
+    Ok(wasm_execute(
+        generator,
+        &GeneratorExecuteMsg::ClaimRewards {
+            lp_tokens: vec![config.vesting_token.to_string()],
+        },
+        vec![],
+    )?
+    .into())
+}
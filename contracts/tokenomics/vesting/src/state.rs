@@ -2,8 +2,8 @@ use cosmwasm_schema::cw_serde;
 
 use gridiron::asset::AssetInfo;
 use gridiron::common::OwnershipProposal;
-use gridiron::vesting::{OrderBy, VestingInfo};
-use cosmwasm_std::{Addr, Deps, StdResult};
+use gridiron::vesting::{ClaimHistoryItem, OrderBy, VestingInfo};
+use cosmwasm_std::{Addr, Decimal256, Deps, Order, StdResult, Uint128};
 use cw_storage_plus::{Bound, Item, Map};
 
 /// This structure stores the main parameters for the generator vesting contract.
@@ -13,6 +13,30 @@ pub struct Config {
     pub owner: Addr,
     /// [`AssetInfo`] of the GRID token
     pub vesting_token: AssetInfo,
+    /// The generator contract in which vested LP tokens are staked
+    pub generator: Option<Addr>,
+}
+
+/// This structure stores the distribution state of a generator reward token.
+#[cw_serde]
+pub struct RewardState {
+    /// The reward token
+    pub info: AssetInfo,
+    /// The amount of rewards distributed per staked vesting token
+    pub index: Decimal256,
+    /// The distributed rewards held (or about to be received) by the contract
+    pub balance: Uint128,
+}
+
+/// This structure stores the generator rewards of a vesting recipient for a specific reward token.
+#[cw_serde]
+pub struct UserReward {
+    /// The reward token
+    pub info: AssetInfo,
+    /// The reward index at the last update of the recipient rewards
+    pub index: Decimal256,
+    /// The rewards accrued and not yet claimed
+    pub pending: Uint128,
 }
 
 /// Stores the contract config at the given key.
@@ -21,6 +45,21 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// The first key is the address of an account that's vesting, the second key is an object of type [`VestingInfo`].
 pub const VESTING_INFO: Map<&Addr, VestingInfo> = Map::new("vesting_info");
 
+/// Stores the address which receives the tokens claimed by a vesting recipient unless a claim specifies one.
+pub const CLAIM_REDIRECTS: Map<&Addr, Addr> = Map::new("claim_redirects");
+
+/// Stores the claims of each vesting recipient. The key is (recipient address, claim timestamp in seconds).
+pub const CLAIM_HISTORY: Map<(&Addr, u64), ClaimHistoryItem> = Map::new("claim_history");
+
+/// The amount of vesting tokens staked in the generator.
+pub const TOTAL_STAKED: Item<Uint128> = Item::new("total_staked");
+
+/// Stores the distribution state of every generator reward token.
+pub const REWARD_STATES: Item<Vec<RewardState>> = Item::new("reward_states");
+
+/// Stores the generator rewards of each vesting recipient.
+pub const USER_REWARDS: Map<&Addr, Vec<UserReward>> = Map::new("user_rewards");
+
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
@@ -63,6 +102,35 @@ pub fn read_vesting_infos(
     Ok(info)
 }
 
+/// Returns the claims made by a vesting recipient in ascending order.
+/// ## Params
+///
+/// * **address** vesting recipient for which to read the claims.
+///
+/// * **start_after** claim timestamp from which to start reading the claims.
+///
+/// * **limit** amount of claims to read.
+pub fn read_claim_history(
+    deps: Deps,
+    address: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ClaimHistoryItem>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    CLAIM_HISTORY
+        .prefix(address)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, claim)| claim))
+        .collect()
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
@@ -12,6 +12,7 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         owner: "owner".to_string(),
         vesting_token: token_asset_info(Addr::unchecked("grid_token")),
+        generator: None,
     };
 
     let env = mock_env();
@@ -24,6 +25,7 @@ fn proper_initialization() {
         ConfigResponse {
             owner: Addr::unchecked("owner"),
             vesting_token: token_asset_info(Addr::unchecked("grid_token")),
+            generator: None,
         }
     );
 }
@@ -38,6 +40,7 @@ fn update_owner() {
         vesting_token: AssetInfo::NativeToken {
             denom: "ucosmos".to_owned(),
         },
+        generator: None,
     };
 
     let env = mock_env();
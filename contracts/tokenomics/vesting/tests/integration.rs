@@ -1,20 +1,22 @@
 #![cfg(not(tarpaulin_include))]
 
-use gridiron::asset::{native_asset_info, token_asset_info};
+use gridiron::asset::{native_asset_info, token_asset, token_asset_info, Asset};
 use gridiron::querier::query_balance;
-use gridiron::vesting::{QueryMsg, VestingAccountResponse, VestingAccountsResponse, VestingInfo};
+use gridiron::vesting::{
+    ClaimHistoryItem, QueryMsg, VestingAccountResponse, VestingAccountsResponse, VestingInfo,
+};
 use gridiron::{
     token::InstantiateMsg as TokenInstantiateMsg,
     vesting::{
         Cw20HookMsg, ExecuteMsg, InstantiateMsg, VestingAccount, VestingSchedule,
-        VestingSchedulePoint,
+        VestingSchedulePoint, VestingStream,
     },
 };
 use gridiron_vesting::error::ContractError;
 use gridiron_vesting::state::Config;
 use cosmwasm_std::{coin, coins, to_binary, Addr, StdResult, Timestamp, Uint128};
 use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
 use cw_utils::PaymentError;
 
 const OWNER1: &str = "owner1";
@@ -1322,6 +1324,486 @@ fn withdraw_overlapping_schedules2() {
     );
 }
 
+#[test]
+fn claim_history() {
+    let user1 = Addr::unchecked(USER1);
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let grid_token = instantiate_token(&mut app, token_code_id, "GRID", None);
+    let vesting_instance = instantiate_vesting(&mut app, &grid_token);
+
+    let current_time = app.block_info().time.seconds();
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: user1.to_string(),
+                schedules: vec![
+                    VestingSchedule {
+                        start_point: VestingSchedulePoint {
+                            time: current_time + 100,
+                            amount: Uint128::zero(),
+                        },
+                        end_point: Some(VestingSchedulePoint {
+                            time: current_time + 200,
+                            amount: Uint128::new(100),
+                        }),
+                    },
+                    VestingSchedule {
+                        start_point: VestingSchedulePoint {
+                            time: current_time + 50,
+                            amount: Uint128::new(50),
+                        },
+                        end_point: None,
+                    },
+                ],
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(150),
+    };
+    app.execute_contract(owner.clone(), grid_token.clone(), &msg, &[])
+        .unwrap();
+
+    let claim_attr = |res: &AppResponse, key: &str| {
+        res.events
+            .iter()
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap()
+    };
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(current_time + 60));
+
+    // Only the schedule which started first is unlocked
+    let res = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::Claim {
+                recipient: None,
+                amount: Some(Uint128::new(30)),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(claim_attr(&res, "schedule_index"), "1");
+    assert_eq!(claim_attr(&res, "remaining_amount"), "120");
+    assert_eq!(claim_attr(&res, "remaining_available_amount"), "20");
+
+    // Partial claims can't be zero or exceed what is left
+    for (amount, expected_err) in [
+        (0, ContractError::ZeroClaimAmount {}),
+        (21, ContractError::AmountIsNotAvailable {}),
+    ] {
+        let err = app
+            .execute_contract(
+                user1.clone(),
+                vesting_instance.clone(),
+                &ExecuteMsg::Claim {
+                    recipient: None,
+                    amount: Some(Uint128::new(amount)),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(expected_err, err.downcast().unwrap());
+    }
+
+    // Claims in the same block are merged
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::Claim {
+            recipient: None,
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|b| b.time = Timestamp::from_seconds(current_time + 150));
+
+    let res = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::Claim {
+                recipient: None,
+                amount: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(claim_attr(&res, "claimed_amount"), "50");
+    assert_eq!(claim_attr(&res, "schedule_index"), "0");
+    assert_eq!(claim_attr(&res, "remaining_amount"), "50");
+
+    let history: Vec<ClaimHistoryItem> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::ClaimHistory {
+                address: user1.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            ClaimHistoryItem {
+                timestamp: current_time + 60,
+                claimed_amount: Uint128::new(50),
+                total_claimed: Uint128::new(50),
+            },
+            ClaimHistoryItem {
+                timestamp: current_time + 150,
+                claimed_amount: Uint128::new(50),
+                total_claimed: Uint128::new(100),
+            },
+        ]
+    );
+
+    let history: Vec<ClaimHistoryItem> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::ClaimHistory {
+                address: user1.to_string(),
+                start_after: Some(current_time + 60),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].total_claimed.u128(), 100);
+
+    check_token_balance(&mut app, &grid_token, &user1, 100);
+}
+
+#[test]
+fn generator_staked_vesting() {
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let lp_token = instantiate_token(&mut app, token_code_id, "LP", None);
+    let reward_token = instantiate_token(&mut app, token_code_id, "GRID", None);
+
+    let generator_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        mock_generator::execute,
+        mock_generator::instantiate,
+        mock_generator::query,
+    )));
+    let generator = app
+        .instantiate_contract(
+            generator_code_id,
+            owner.clone(),
+            &mock_generator::InstantiateMsg {
+                reward_token: reward_token.clone(),
+            },
+            &[],
+            "Generator",
+            None,
+        )
+        .unwrap();
+    mint_tokens(&mut app, &reward_token, &generator, 1000);
+
+    let vesting_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        gridiron_vesting::contract::execute,
+        gridiron_vesting::contract::instantiate,
+        gridiron_vesting::contract::query,
+    )));
+
+    // Native tokens can't be staked in the generator
+    let err = app
+        .instantiate_contract(
+            vesting_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: OWNER1.to_string(),
+                vesting_token: native_asset_info(IBC_GRID.to_string()),
+                generator: Some(generator.to_string()),
+            },
+            &[],
+            "Vesting",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StakingNotSupported {},
+        err.downcast().unwrap()
+    );
+
+    let vesting_instance = app
+        .instantiate_contract(
+            vesting_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: OWNER1.to_string(),
+                vesting_token: token_asset_info(lp_token.clone()),
+                generator: Some(generator.to_string()),
+            },
+            &[],
+            "Vesting",
+            None,
+        )
+        .unwrap();
+    mint_tokens(&mut app, &lp_token, &owner, 400);
+
+    let current_time = app.block_info().time.seconds();
+    let schedule = |amount: u128| VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: current_time,
+            amount: Uint128::zero(),
+        },
+        end_point: Some(VestingSchedulePoint {
+            time: current_time + 100,
+            amount: Uint128::new(amount),
+        }),
+    };
+    let msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![
+                VestingAccount {
+                    address: user1.to_string(),
+                    schedules: vec![schedule(100)],
+                },
+                VestingAccount {
+                    address: user2.to_string(),
+                    schedules: vec![schedule(300)],
+                },
+            ],
+        })
+        .unwrap(),
+        amount: Uint128::new(400),
+    };
+    app.execute_contract(owner.clone(), lp_token.clone(), &msg, &[])
+        .unwrap();
+
+    // The vested LP tokens are staked in the generator
+    check_token_balance(&mut app, &lp_token, &vesting_instance, 0);
+    check_token_balance(&mut app, &lp_token, &generator, 400);
+
+    let pending_rewards = |app: &App, user: &Addr| -> Vec<Asset> {
+        app.wrap()
+            .query_wasm_smart(
+                &vesting_instance,
+                &QueryMsg::PendingRewards {
+                    address: user.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    let add_rewards = |app: &mut App, amount: u128| {
+        app.execute_contract(
+            owner.clone(),
+            generator.clone(),
+            &mock_generator::ExecuteMsg::AddPending {
+                user: vesting_instance.to_string(),
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+    };
+
+    // Rewards are split by the staked amounts
+    add_rewards(&mut app, 40);
+    assert_eq!(
+        pending_rewards(&app, &user1),
+        vec![token_asset(reward_token.clone(), Uint128::new(10))]
+    );
+    assert_eq!(
+        pending_rewards(&app, &user2),
+        vec![token_asset(reward_token.clone(), Uint128::new(30))]
+    );
+
+    // Claimed principal is unstaked and stops earning rewards
+    app.update_block(|b| b.time = Timestamp::from_seconds(current_time + 50));
+    claim_and_check(&mut app, &user1, &vesting_instance, &lp_token, 50);
+    check_token_balance(&mut app, &lp_token, &generator, 350);
+    check_token_balance(&mut app, &reward_token, &vesting_instance, 40);
+
+    add_rewards(&mut app, 35);
+    assert_eq!(
+        pending_rewards(&app, &user1),
+        vec![token_asset(reward_token.clone(), Uint128::new(15))]
+    );
+    assert_eq!(
+        pending_rewards(&app, &user2),
+        vec![token_asset(reward_token.clone(), Uint128::new(60))]
+    );
+
+    for user in [&user1, &user2] {
+        app.execute_contract(
+            user.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::ClaimRewards { recipient: None },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(pending_rewards(&app, user), vec![]);
+    }
+    check_token_balance(&mut app, &reward_token, &user1, 15);
+    check_token_balance(&mut app, &reward_token, &user2, 60);
+    check_token_balance(&mut app, &reward_token, &vesting_instance, 0);
+}
+
+#[test]
+fn vesting_streams() {
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let owner = Addr::unchecked(OWNER1);
+
+    let mut app = mock_app(&owner);
+    let vesting_instance = instantiate_vesting_remote_chain(&mut app);
+
+    let current_time = app.block_info().time.seconds();
+
+    let msg = ExecuteMsg::RegisterVestingStreams {
+        streams: vec![VestingStream {
+            address: user1.to_string(),
+            start: current_time + 100,
+            end: current_time + 1100,
+            total: Uint128::new(1_000_000),
+        }],
+    };
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &msg,
+        &coins(1_000_000, IBC_GRID),
+    )
+    .unwrap();
+
+    let res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            vesting_instance.clone(),
+            &QueryMsg::VestingAccount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.info.schedules,
+        vec![VestingSchedule {
+            start_point: VestingSchedulePoint {
+                time: current_time + 100,
+                amount: Uint128::zero(),
+            },
+            end_point: Some(VestingSchedulePoint {
+                time: current_time + 1100,
+                amount: Uint128::new(1_000_000),
+            }),
+        }]
+    );
+
+    // Tokens accrue every second
+    let available_amount = |app: &App| -> Uint128 {
+        app.wrap()
+            .query_wasm_smart(
+                vesting_instance.clone(),
+                &QueryMsg::AvailableAmount {
+                    address: user1.to_string(),
+                },
+            )
+            .unwrap()
+    };
+    app.update_block(|b| b.time = b.time.plus_seconds(101));
+    assert_eq!(available_amount(&app), Uint128::new(1_000));
+    app.update_block(|b| b.time = b.time.plus_seconds(1));
+    assert_eq!(available_amount(&app), Uint128::new(2_000));
+
+    // Only vesting recipients can redirect claims
+    let redirect = |recipient: Option<&Addr>| ExecuteMsg::RedirectClaims {
+        recipient: recipient.map(Addr::to_string),
+    };
+    let err = app
+        .execute_contract(
+            user2.clone(),
+            vesting_instance.clone(),
+            &redirect(Some(&user1)),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &redirect(Some(&user2)),
+        &[],
+    )
+    .unwrap();
+    let res: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            vesting_instance.clone(),
+            &QueryMsg::ClaimRedirect {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res, Some(user2.clone()));
+
+    let claim = ExecuteMsg::Claim {
+        recipient: None,
+        amount: None,
+    };
+    app.execute_contract(user1.clone(), vesting_instance.clone(), &claim, &[])
+        .unwrap();
+    assert_eq!(
+        query_balance(&app.wrap(), &user2, IBC_GRID).unwrap(),
+        Uint128::new(2_000)
+    );
+
+    // An explicit recipient takes precedence over the redirect
+    app.update_block(|b| b.time = b.time.plus_seconds(10));
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::Claim {
+            recipient: Some(user1.to_string()),
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_balance(&app.wrap(), &user1, IBC_GRID).unwrap(),
+        Uint128::new(10_000)
+    );
+
+    // Removing the redirect sends claims to the vesting recipient again
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &redirect(None),
+        &[],
+    )
+    .unwrap();
+    app.update_block(|b| b.time = b.time.plus_seconds(10_000));
+    app.execute_contract(user1.clone(), vesting_instance.clone(), &claim, &[])
+        .unwrap();
+    assert_eq!(
+        query_balance(&app.wrap(), &user1, IBC_GRID).unwrap(),
+        Uint128::new(998_000)
+    );
+    assert_eq!(
+        query_balance(&app.wrap(), &user2, IBC_GRID).unwrap(),
+        Uint128::new(2_000)
+    );
+}
+
 fn mock_app(owner: &Addr) -> App {
     App::new(|app, _, storage| {
         app.bank
@@ -1385,6 +1867,7 @@ fn instantiate_vesting(mut app: &mut App, grid_token_instance: &Addr) -> Addr {
     let init_msg = InstantiateMsg {
         owner: OWNER1.to_string(),
         vesting_token: token_asset_info(grid_token_instance.clone()),
+        generator: None,
     };
 
     let vesting_instance = app
@@ -1436,6 +1919,7 @@ fn instantiate_vesting_remote_chain(app: &mut App) -> Addr {
     let init_msg = InstantiateMsg {
         owner: OWNER1.to_string(),
         vesting_token: native_asset_info(IBC_GRID.to_string()),
+        generator: None,
     };
 
     app.instantiate_contract(
@@ -1496,3 +1980,134 @@ fn claim_and_check(
     let grid_amount = query_token_balance(app, &grid_token, &who);
     assert_eq!(grid_amount.u128(), expected_amount);
 }
+
+/// A minimal generator which pays out rewards set by the test on every deposit, withdrawal or claim
+mod mock_generator {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+        StdResult, Uint128, WasmMsg,
+    };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::{Item, Map};
+    use gridiron::asset::token_asset_info;
+    use gridiron::generator::{Cw20HookMsg, PendingTokenResponse, RewardInfoResponse};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub reward_token: Addr,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Receive(Cw20ReceiveMsg),
+        Withdraw {
+            lp_token: String,
+            amount: Uint128,
+        },
+        ClaimRewards {
+            lp_tokens: Vec<String>,
+        },
+        /// Adds rewards which can be claimed by a specific user
+        AddPending {
+            user: String,
+            amount: Uint128,
+        },
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        PendingToken { lp_token: String, user: String },
+        RewardInfo { lp_token: String },
+    }
+
+    const REWARD_TOKEN: Item<Addr> = Item::new("reward_token");
+    const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+    const PENDING: Map<&Addr, Uint128> = Map::new("pending");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        REWARD_TOKEN.save(deps.storage, &msg.reward_token)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Receive(cw20_msg) => {
+                let user = deps.api.addr_validate(&cw20_msg.sender)?;
+                match from_binary(&cw20_msg.msg)? {
+                    Cw20HookMsg::Deposit {} => {
+                        let deposit = DEPOSITS.may_load(deps.storage, &user)?.unwrap_or_default();
+                        DEPOSITS.save(deps.storage, &user, &(deposit + cw20_msg.amount))?;
+                    }
+                    Cw20HookMsg::DepositFor(_) => {
+                        return Err(StdError::generic_err("Not supported"))
+                    }
+                }
+                send_pending(deps, &user, Response::new())
+            }
+            ExecuteMsg::Withdraw { lp_token, amount } => {
+                let deposit = DEPOSITS.load(deps.storage, &info.sender)?;
+                DEPOSITS.save(deps.storage, &info.sender, &deposit.checked_sub(amount)?)?;
+                let response = send_pending(deps, &info.sender, Response::new())?;
+                Ok(response.add_message(WasmMsg::Execute {
+                    contract_addr: lp_token,
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: info.sender.to_string(),
+                        amount,
+                    })?,
+                    funds: vec![],
+                }))
+            }
+            ExecuteMsg::ClaimRewards { .. } => send_pending(deps, &info.sender, Response::new()),
+            ExecuteMsg::AddPending { user, amount } => {
+                let user = deps.api.addr_validate(&user)?;
+                let pending = PENDING.may_load(deps.storage, &user)?.unwrap_or_default();
+                PENDING.save(deps.storage, &user, &(pending + amount))?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    fn send_pending(deps: DepsMut, user: &Addr, response: Response) -> StdResult<Response> {
+        let pending = PENDING.may_load(deps.storage, user)?.unwrap_or_default();
+        if pending.is_zero() {
+            return Ok(response);
+        }
+
+        PENDING.remove(deps.storage, user);
+        Ok(response.add_message(WasmMsg::Execute {
+            contract_addr: REWARD_TOKEN.load(deps.storage)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: user.to_string(),
+                amount: pending,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::PendingToken { user, .. } => {
+                let user = deps.api.addr_validate(&user)?;
+                to_binary(&PendingTokenResponse {
+                    pending: PENDING.may_load(deps.storage, &user)?.unwrap_or_default(),
+                    pending_on_proxy: None,
+                })
+            }
+            QueryMsg::RewardInfo { .. } => to_binary(&RewardInfoResponse {
+                base_reward_token: token_asset_info(REWARD_TOKEN.load(deps.storage)?),
+                proxy_reward_tokens: vec![],
+            }),
+        }
+    }
+}
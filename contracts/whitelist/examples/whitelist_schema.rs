@@ -1,10 +1,13 @@
 use cosmwasm_schema::write_api;
-use cw1_whitelist::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cosmwasm_std::Empty;
+use cw1_subkeys::msg::{ExecuteMsg, QueryMsg};
+use cw1_whitelist::msg::InstantiateMsg;
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         query: QueryMsg,
-        execute: ExecuteMsg
+        execute: ExecuteMsg,
+        migrate: Empty
     }
 }
@@ -1,13 +1,14 @@
 use cosmwasm_std::{
-    entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
 };
 
 use gridiron::common::validate_addresses;
-use cw1_whitelist::contract::{execute as cw1_execute, query as cw1_query};
-use cw1_whitelist::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw1_subkeys::contract::{execute as cw1_execute, query as cw1_query};
+use cw1_subkeys::msg::{ExecuteMsg, QueryMsg};
+use cw1_subkeys::ContractError;
+use cw1_whitelist::msg::InstantiateMsg;
 use cw1_whitelist::state::{AdminList, ADMIN_LIST};
-use cw1_whitelist::ContractError;
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 
 // Version info for contract migration.
 const CONTRACT_NAME: &str = "gridiron-cw1-whitelist";
@@ -30,6 +31,9 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
+/// Admins keep executing arbitrary messages and managing the admin list. On top of that, admins
+/// can grant other addresses (subkeys) native token allowances and staking permissions, both
+/// following the cw1-subkeys spec.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -44,3 +48,26 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     cw1_query(deps, env, msg)
 }
+
+/// Manages contract migration. The admin list is stored in the same place by cw1-subkeys, so no
+/// state changes are needed.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        "gridiron-cw1-whitelist" => match contract_version.version.as_ref() {
+            "1.0.0" | "1.0.1" => {}
+            _ => return Err(StdError::generic_err("Contract can't be migrated!")),
+        },
+        _ => return Err(StdError::generic_err("Contract can't be migrated!")),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
+}
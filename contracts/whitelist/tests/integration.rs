@@ -0,0 +1,179 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{coin, coins, Addr, BankMsg, CosmosMsg, Empty};
+use cw1::CanExecuteResponse;
+use cw1_subkeys::msg::{AllowanceInfo, ExecuteMsg, QueryMsg};
+use cw1_whitelist::msg::{AdminListResponse, InstantiateMsg};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_utils::Expiration;
+
+const ADMIN: &str = "admin";
+const SUBKEY: &str = "subkey";
+const RECIPIENT: &str = "recipient";
+
+fn instantiate_whitelist(app: &mut App) -> Addr {
+    let code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            gridiron_whitelist::contract::execute,
+            gridiron_whitelist::contract::instantiate,
+            gridiron_whitelist::contract::query,
+        )
+        .with_migrate(gridiron_whitelist::contract::migrate),
+    ));
+
+    let whitelist = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(ADMIN),
+            &InstantiateMsg {
+                admins: vec![ADMIN.to_string()],
+                mutable: true,
+            },
+            &[],
+            "Whitelist",
+            None,
+        )
+        .unwrap();
+
+    app.send_tokens(
+        Addr::unchecked(ADMIN),
+        whitelist.clone(),
+        &coins(1_000, "uluna"),
+    )
+    .unwrap();
+
+    whitelist
+}
+
+fn send_msg(amount: u128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: RECIPIENT.to_string(),
+        amount: coins(amount, "uluna"),
+    }
+    .into()
+}
+
+fn can_execute(app: &App, whitelist: &Addr, sender: &str, msg: CosmosMsg) -> bool {
+    let res: CanExecuteResponse = app
+        .wrap()
+        .query_wasm_smart(
+            whitelist,
+            &QueryMsg::<Empty>::CanExecute {
+                sender: sender.to_string(),
+                msg,
+            },
+        )
+        .unwrap();
+    res.can_execute
+}
+
+#[test]
+fn admin_semantics_are_kept() {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(ADMIN), coins(1_000, "uluna"))
+            .unwrap()
+    });
+    let whitelist = instantiate_whitelist(&mut app);
+
+    let admins: AdminListResponse = app
+        .wrap()
+        .query_wasm_smart(&whitelist, &QueryMsg::<Empty>::AdminList {})
+        .unwrap();
+    assert_eq!(admins.admins, vec![ADMIN.to_string()]);
+    assert!(admins.mutable);
+
+    assert!(can_execute(&app, &whitelist, ADMIN, send_msg(100)));
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        whitelist.clone(),
+        &ExecuteMsg::<Empty>::Execute {
+            msgs: vec![send_msg(100)],
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance(RECIPIENT, "uluna").unwrap(),
+        coin(100, "uluna")
+    );
+
+    // Non-admins can't change the admin list
+    app.execute_contract(
+        Addr::unchecked(SUBKEY),
+        whitelist.clone(),
+        &ExecuteMsg::<Empty>::UpdateAdmins {
+            admins: vec![SUBKEY.to_string()],
+        },
+        &[],
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn subkey_allowance_with_expiration() {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(ADMIN), coins(1_000, "uluna"))
+            .unwrap()
+    });
+    let whitelist = instantiate_whitelist(&mut app);
+
+    assert!(!can_execute(&app, &whitelist, SUBKEY, send_msg(100)));
+
+    let expires = Expiration::AtHeight(app.block_info().height + 10);
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        whitelist.clone(),
+        &ExecuteMsg::<Empty>::IncreaseAllowance {
+            spender: SUBKEY.to_string(),
+            amount: coin(300, "uluna"),
+            expires: Some(expires),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let allowance: AllowanceInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &whitelist,
+            &QueryMsg::<Empty>::Allowance {
+                spender: SUBKEY.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(allowance.expires, expires);
+
+    assert!(can_execute(&app, &whitelist, SUBKEY, send_msg(300)));
+    assert!(!can_execute(&app, &whitelist, SUBKEY, send_msg(301)));
+
+    app.execute_contract(
+        Addr::unchecked(SUBKEY),
+        whitelist.clone(),
+        &ExecuteMsg::<Empty>::Execute {
+            msgs: vec![send_msg(200)],
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance(RECIPIENT, "uluna").unwrap(),
+        coin(200, "uluna")
+    );
+
+    // The remaining allowance can't be used after expiration
+    app.update_block(|block| block.height += 10);
+    assert!(!can_execute(&app, &whitelist, SUBKEY, send_msg(100)));
+    app.execute_contract(
+        Addr::unchecked(SUBKEY),
+        whitelist.clone(),
+        &ExecuteMsg::<Empty>::Execute {
+            msgs: vec![send_msg(100)],
+        },
+        &[],
+    )
+    .unwrap_err();
+}
@@ -0,0 +1,33 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, CustomQuery, QuerierWrapper, StdResult};
+
+/// This structure describes the query messages which every allowlist contract used by
+/// permissioned pairs must support.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns whether the address is allowed to provide liquidity and swap in permissioned pairs
+    #[returns(bool)]
+    IsAllowed { address: String },
+}
+
+/// Returns whether the allowlist contract approves the specified address.
+///
+/// * **allowlist** is the allowlist contract address.
+///
+/// * **address** is the address to check.
+pub fn query_is_allowed<C>(
+    querier: &QuerierWrapper<C>,
+    allowlist: &Addr,
+    address: &Addr,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    querier.query_wasm_smart(
+        allowlist,
+        &QueryMsg::IsAllowed {
+            address: address.to_string(),
+        },
+    )
+}
@@ -2,15 +2,17 @@ use std::fmt;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, coins, ensure, to_binary, wasm_execute, Addr, Api, BankMsg, Coin,
+    coin, coins, ensure, to_binary, wasm_execute, Addr, Api, BankMsg, BlockInfo, Coin,
     ConversionOverflowError, CosmosMsg, CustomMsg, CustomQuery, Decimal256, Fraction, MessageInfo,
-    QuerierWrapper, ReplyOn, StdError, StdResult, SubMsg, Uint128, Uint256, WasmMsg,
+    QuerierWrapper, ReplyOn, StdError, StdResult, SubMsg, Timestamp, Uint128, Uint256, WasmMsg,
 };
 use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Denom, MinterResponse};
 use cw_storage_plus::{Key, KeyDeserialize, Prefixer, PrimaryKey};
 use cw_utils::must_pay;
 use itertools::Itertools;
+use thiserror::Error;
 
+use crate::allowlist::query_is_allowed;
 use crate::cosmwasm_ext::DecimalToInteger;
 use crate::factory::PairType;
 use crate::pair::QueryMsg as PairQueryMsg;
@@ -26,6 +28,38 @@ pub const ULUNA_DENOM: &str = "uluna";
 pub const MINIMUM_LIQUIDITY_AMOUNT: Uint128 = Uint128::new(1_000);
 /// Maximum denom length
 pub const DENOM_MAX_LENGTH: usize = 128;
+/// Length of the hex encoded hash in IBC denoms (ibc/{hash})
+pub const IBC_HASH_LENGTH: usize = 64;
+/// Length of the hex encoded Ethereum address in Injective peggy denoms (peggy0x{address})
+pub const PEGGY_ADDRESS_LENGTH: usize = 40;
+
+/// This enum describes why an asset info failed strict validation
+#[derive(Error, Debug, PartialEq)]
+pub enum AssetInfoError {
+    #[error("Invalid denom length [3,{max}]: {denom}", max = DENOM_MAX_LENGTH)]
+    InvalidDenomLength { denom: String },
+
+    #[error("Denom must start with an ASCII letter followed by ASCII alphanumerics or one of  /  :  .  _  -: {denom}")]
+    InvalidDenomCharacters { denom: String },
+
+    #[error("Denom must be lowercase: {denom}")]
+    DenomNotLowercase { denom: String },
+
+    #[error("Invalid IBC denom, expected ibc/ followed by {len} uppercase hex characters: {denom}", len = IBC_HASH_LENGTH)]
+    InvalidIbcDenom { denom: String },
+
+    #[error("Invalid token factory denom, expected factory/{{creator}}/{{subdenom}}: {denom}")]
+    InvalidFactoryDenom { denom: String },
+
+    #[error("Invalid cw20 token address {addr}: {reason}")]
+    InvalidTokenAddress { addr: String, reason: String },
+}
+
+impl From<AssetInfoError> for StdError {
+    fn from(err: AssetInfoError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
 
 /// This enum describes a Terra asset (native or CW20).
 #[cw_serde]
@@ -524,6 +558,26 @@ impl AssetInfo {
 
         Ok(())
     }
+
+    /// Strict version of [`AssetInfo::check`] used when a pool is created.
+    /// Token addresses must be valid and normalized while native denoms must pass [`validate_native_denom_strict`].
+    pub fn check_strict(&self, api: &dyn Api) -> Result<(), AssetInfoError> {
+        match self {
+            AssetInfo::Token { contract_addr } => {
+                api.addr_validate(contract_addr.as_str()).map_err(|err| {
+                    AssetInfoError::InvalidTokenAddress {
+                        addr: contract_addr.to_string(),
+                        reason: err.to_string(),
+                    }
+                })?;
+            }
+            AssetInfo::NativeToken { denom } => {
+                validate_native_denom_strict(api, denom)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Taken from https://github.com/mars-protocol/red-bank/blob/5bb0fe145588352b281803f7b870103bc6832621/packages/utils/src/helpers.rs#L68
@@ -557,6 +611,57 @@ pub fn validate_native_denom(denom: &str) -> StdResult<()> {
     Ok(())
 }
 
+/// On top of [`validate_native_denom`] checks that:
+/// * IBC denoms are `ibc/` followed by a 64 characters uppercase hex hash;
+/// * token factory denoms are `factory/{creator}/{subdenom}` where creator is a valid address;
+/// * all other denoms are lowercase. Injective peggy denoms (`peggy0x{address}`) keep their checksummed case.
+pub fn validate_native_denom_strict(api: &dyn Api, denom: &str) -> Result<(), AssetInfoError> {
+    if denom.len() < 3 || denom.len() > DENOM_MAX_LENGTH {
+        return Err(AssetInfoError::InvalidDenomLength {
+            denom: denom.to_string(),
+        });
+    }
+
+    if validate_native_denom(denom).is_err() {
+        return Err(AssetInfoError::InvalidDenomCharacters {
+            denom: denom.to_string(),
+        });
+    }
+
+    if let Some(hash) = denom.strip_prefix("ibc/") {
+        let is_valid_hash = hash.len() == IBC_HASH_LENGTH
+            && hash
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c));
+        if !is_valid_hash {
+            return Err(AssetInfoError::InvalidIbcDenom {
+                denom: denom.to_string(),
+            });
+        }
+    } else if let Some(creator_and_subdenom) = denom.strip_prefix("factory/") {
+        let is_valid_factory_denom = match creator_and_subdenom.split_once('/') {
+            Some((creator, subdenom)) => !subdenom.is_empty() && api.addr_validate(creator).is_ok(),
+            None => false,
+        };
+        if !is_valid_factory_denom {
+            return Err(AssetInfoError::InvalidFactoryDenom {
+                denom: denom.to_string(),
+            });
+        }
+    } else {
+        let is_peggy_denom = denom.strip_prefix("peggy0x").map_or(false, |address| {
+            address.len() == PEGGY_ADDRESS_LENGTH && address.chars().all(|c| c.is_ascii_hexdigit())
+        });
+        if !is_peggy_denom && denom.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(AssetInfoError::DenomNotLowercase {
+                denom: denom.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 /// This structure stores the main parameters for an Gridiron pair
 #[cw_serde]
 pub struct PairInfo {
@@ -568,6 +673,33 @@ pub struct PairInfo {
     pub liquidity_token: Addr,
     /// The pool type (xyk, stableswap etc) available in [`PairType`]
     pub pair_type: PairType,
+    /// The address which requested the pair creation.
+    /// None for pairs created before the creation metadata was recorded
+    pub creator: Option<Addr>,
+    /// The block at which the pair was created.
+    /// None for pairs created before the creation metadata was recorded
+    pub created_at: Option<PairCreatedAt>,
+    /// The allowlist contract which approves the addresses that can provide liquidity and swap.
+    /// None for permissionless pairs
+    pub allowlist: Option<Addr>,
+}
+
+/// This structure describes the block at which a pair was created
+#[cw_serde]
+pub struct PairCreatedAt {
+    /// Block height
+    pub height: u64,
+    /// Block time
+    pub time: Timestamp,
+}
+
+impl From<&BlockInfo> for PairCreatedAt {
+    fn from(block: &BlockInfo) -> Self {
+        Self {
+            height: block.height,
+            time: block.time,
+        }
+    }
 }
 
 impl PairInfo {
@@ -618,6 +750,20 @@ impl PairInfo {
             })
             .collect()
     }
+
+    /// Returns whether the address can provide liquidity and swap in the pair.
+    /// Every address is allowed in permissionless pairs.
+    ///
+    /// * **address** is the address to check.
+    pub fn is_allowed<C>(&self, querier: &QuerierWrapper<C>, address: &Addr) -> StdResult<bool>
+    where
+        C: CustomQuery,
+    {
+        match &self.allowlist {
+            Some(allowlist) => query_is_allowed(querier, allowlist, address),
+            None => Ok(true),
+        }
+    }
 }
 
 /// Returns a lowercased, validated address upon success if present.
@@ -837,7 +983,7 @@ impl Decimal256Ext for Decimal256 {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::mock_info;
+    use cosmwasm_std::testing::{mock_info, MockApi};
     use cosmwasm_std::{coin, coins};
     use test_case::test_case;
 
@@ -1041,6 +1187,90 @@ mod tests {
         validate_native_denom("factory/wasm1jdppe6fnj2q7hjsepty5crxtrryzhuqsjrj95y/uusd").unwrap();
     }
 
+    #[test]
+    fn native_denom_strict_validation() {
+        let api = MockApi::default();
+
+        let err = validate_native_denom_strict(&api, "ab").unwrap_err();
+        assert_eq!(
+            err,
+            AssetInfoError::InvalidDenomLength {
+                denom: "ab".to_string()
+            }
+        );
+        let err = validate_native_denom_strict(&api, "wow@usd").unwrap_err();
+        assert_eq!(
+            err,
+            AssetInfoError::InvalidDenomCharacters {
+                denom: "wow@usd".to_string()
+            }
+        );
+        let err = validate_native_denom_strict(&api, "uUSD").unwrap_err();
+        assert_eq!(
+            err,
+            AssetInfoError::DenomNotLowercase {
+                denom: "uUSD".to_string()
+            }
+        );
+        for denom in [
+            "ibc/EBD5A24C554198EBA",
+            "ibc/ebd5a24c554198ebaf44979c5b4d2c2d312e6ebab71962c92f735499c7575839",
+            "ibc/EBD5A24C554198EBAF44979C5B4D2C2D312E6EBAB71962C92F735499C757583G",
+        ] {
+            let err = validate_native_denom_strict(&api, denom).unwrap_err();
+            assert_eq!(
+                err,
+                AssetInfoError::InvalidIbcDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+        for denom in [
+            "factory/creator",
+            "factory/creator/",
+            "factory/CREATOR/uusd",
+        ] {
+            let err = validate_native_denom_strict(&api, denom).unwrap_err();
+            assert_eq!(
+                err,
+                AssetInfoError::InvalidFactoryDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+
+        validate_native_denom_strict(&api, "uusd").unwrap();
+        validate_native_denom_strict(
+            &api,
+            "ibc/EBD5A24C554198EBAF44979C5B4D2C2D312E6EBAB71962C92F735499C7575839",
+        )
+        .unwrap();
+        validate_native_denom_strict(&api, "factory/creator/uUSD").unwrap();
+        validate_native_denom_strict(&api, "peggy0xdAC17F958D2ee523a2206206994597C13D831ec7")
+            .unwrap();
+    }
+
+    #[test]
+    fn asset_info_strict_check() {
+        let api = MockApi::default();
+
+        AssetInfo::cw20_unchecked("mock_token")
+            .check_strict(&api)
+            .unwrap();
+        AssetInfo::native("uusd").check_strict(&api).unwrap();
+
+        let err = AssetInfo::cw20_unchecked("Mock_Token")
+            .check_strict(&api)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AssetInfoError::InvalidTokenAddress { addr, .. } if addr == "Mock_Token"
+        ));
+
+        let err = AssetInfo::native("USDC").check_strict(&api).unwrap_err();
+        assert_eq!(err.to_string(), "Denom must be lowercase: USDC".to_string());
+    }
+
     #[test]
     fn test_native_asset_info() {
         let info = AssetInfo::native("uusd");
@@ -0,0 +1,131 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::AssetInfo;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address that's allowed to change contract parameters
+    pub owner: String,
+    /// The GRID token asset info
+    pub grid_token: AssetInfo,
+    /// The xGRID staking contract address. xGRID held by the contract is unstaked through it
+    pub staking_contract: Option<String>,
+    /// The length of a burn epoch in seconds
+    pub epoch_length: u64,
+    /// The maximum amount of GRID burned per epoch. Unlimited if not set
+    pub epoch_burn_cap: Option<Uint128>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Burns GRID held by the contract up to the amount still allowed in the current epoch.
+    /// xGRID held by the contract is unstaked and the received GRID is burned by the next call.
+    /// ## Executor
+    /// Anyone can execute this
+    Burn {},
+    /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
+    Receive(Cw20ReceiveMsg),
+    /// Updates contract parameters
+    /// ## Executor
+    /// Only the owner can execute this
+    UpdateConfig {
+        /// The new xGRID staking contract address
+        staking_contract: Option<String>,
+        /// The new maximum amount of GRID burned per epoch. Zero removes the cap
+        epoch_burn_cap: Option<Uint128>,
+    },
+    /// Creates a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    /// ## Executor
+    /// Only the newly proposed owner can execute this
+    ClaimOwnership {},
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Donates GRID or xGRID to be burned
+    Donate {},
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns cumulative burn statistics and the state of the current epoch
+    #[returns(BurnStatsResponse)]
+    BurnStats {},
+}
+
+/// This structure stores the main parameters for the burn manager contract.
+#[cw_serde]
+pub struct Config {
+    /// Address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The GRID token asset info
+    pub grid_token: AssetInfo,
+    /// The xGRID staking contract address
+    pub staking_contract: Option<Addr>,
+    /// The xGRID token address of the staking contract
+    pub xgrid_token: Option<Addr>,
+    /// The length of a burn epoch in seconds
+    pub epoch_length: u64,
+    /// The maximum amount of GRID burned per epoch
+    pub epoch_burn_cap: Option<Uint128>,
+    /// The timestamp at which the first epoch starts
+    pub start_time: u64,
+}
+
+/// This structure stores cumulative burn statistics.
+#[cw_serde]
+#[derive(Default)]
+pub struct BurnStats {
+    /// The total amount of GRID burned
+    pub total_burned: Uint128,
+    /// The total amount of xGRID unstaked to be burned
+    pub total_xgrid_unstaked: Uint128,
+    /// The total amount of GRID donated through [`Cw20HookMsg::Donate`]
+    pub total_grid_donated: Uint128,
+    /// The total amount of xGRID donated through [`Cw20HookMsg::Donate`]
+    pub total_xgrid_donated: Uint128,
+    /// The number of burns executed
+    pub burns_count: u64,
+    /// The timestamp of the last burn
+    pub last_burn_time: Option<u64>,
+    /// The epoch of the last burn
+    pub epoch: u64,
+    /// The amount of GRID burned in `epoch`
+    pub epoch_burned: Uint128,
+}
+
+/// This structure describes the response of [`QueryMsg::BurnStats`].
+#[cw_serde]
+pub struct BurnStatsResponse {
+    /// Cumulative burn statistics
+    pub stats: BurnStats,
+    /// The current epoch
+    pub current_epoch: u64,
+    /// The amount of GRID that can still be burned in the current epoch. Not set if there's no cap
+    pub epoch_remaining: Option<Uint128>,
+    /// The amount of GRID held by the contract and waiting to be burned
+    pub pending_burn: Uint128,
+}
@@ -0,0 +1,114 @@
+use cosmwasm_std::{attr, Addr, Attribute, Uint128};
+use itertools::Itertools;
+
+use crate::asset::{Asset, AssetInfo};
+
+/// This structure describes the attributes emitted by a pair on a swap.
+/// Attribute names and their order are the same for all pair types.
+pub struct SwapEvent {
+    /// The address that initiated the swap
+    pub sender: Addr,
+    /// The address that receives the ask asset
+    pub receiver: Addr,
+    /// The asset sent to the pool
+    pub offer_asset: AssetInfo,
+    /// The asset returned by the pool
+    pub ask_asset: AssetInfo,
+    /// The amount of offer asset sent to the pool
+    pub offer_amount: Uint128,
+    /// The amount of ask asset returned to the receiver
+    pub return_amount: Uint128,
+    /// The spread charged on the swap
+    pub spread_amount: Uint128,
+    /// The total commission charged on the swap
+    pub commission_amount: Uint128,
+    /// The part of the commission sent to the Maker
+    pub maker_fee_amount: Uint128,
+    /// The part of the commission sent to the fee share address
+    pub fee_share_amount: Uint128,
+}
+
+impl SwapEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "swap"),
+            attr("sender", self.sender),
+            attr("receiver", self.receiver),
+            attr("offer_asset", self.offer_asset.to_string()),
+            attr("ask_asset", self.ask_asset.to_string()),
+            attr("offer_amount", self.offer_amount),
+            attr("return_amount", self.return_amount),
+            attr("spread_amount", self.spread_amount),
+            attr("commission_amount", self.commission_amount),
+            attr("maker_fee_amount", self.maker_fee_amount),
+            attr("fee_share_amount", self.fee_share_amount),
+        ]
+    }
+}
+
+/// This structure describes the attributes emitted by a pair when liquidity is provided.
+pub struct ProvideEvent {
+    /// The address that provided liquidity
+    pub sender: Addr,
+    /// The address that receives LP tokens
+    pub receiver: Addr,
+    /// The assets deposited into the pool
+    pub assets: Vec<Asset>,
+    /// The amount of LP tokens minted
+    pub share: Uint128,
+}
+
+impl ProvideEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "provide_liquidity"),
+            attr("sender", self.sender),
+            attr("receiver", self.receiver),
+            attr("assets", self.assets.iter().join(", ")),
+            attr("share", self.share),
+        ]
+    }
+}
+
+/// This structure describes the attributes emitted by the Generator when a user claims rewards.
+pub struct ClaimEvent {
+    /// The address that receives the rewards
+    pub user: Addr,
+    /// The LP tokens for which rewards are claimed
+    pub lp_tokens: Vec<Addr>,
+}
+
+impl ClaimEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "claim_rewards"),
+            attr("user", self.user),
+            attr("lp_tokens", self.lp_tokens.iter().join(",")),
+        ]
+    }
+}
+
+/// This structure describes the attributes emitted by the Maker when GRID is distributed.
+pub struct DistributeEvent {
+    /// The amount of GRID distributed
+    pub grid_distribution: Uint128,
+    /// The amount of GRID distributed from the pre-upgrade balance
+    pub preupgrade_grid_distribution: Uint128,
+}
+
+impl DistributeEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        let mut attributes = vec![
+            attr("action", "distribute_grid"),
+            attr("grid_distribution", self.grid_distribution),
+        ];
+        if !self.preupgrade_grid_distribution.is_zero() {
+            attributes.push(attr(
+                "preupgrade_grid_distribution",
+                self.preupgrade_grid_distribution,
+            ));
+        }
+
+        attributes
+    }
+}
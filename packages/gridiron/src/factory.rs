@@ -1,12 +1,19 @@
 use crate::asset::{AssetInfo, PairInfo};
+use crate::pair::FeeShareConfig;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 use std::fmt::{Display, Formatter, Result};
 
 const MAX_TOTAL_FEE_BPS: u16 = 10_000;
 const MAX_MAKER_FEE_BPS: u16 = 10_000;
 
+/// The delay (in seconds) after which a proposed generator address can be applied
+pub const GENERATOR_ROTATION_DELAY: u64 = 86_400;
+
+/// The highest cap (in bps) the factory can set on referral fees
+pub const MAX_REFERRAL_BPS: u16 = 1000;
+
 /// This structure holds the main contract parameters.
 #[cw_serde]
 pub struct Config {
@@ -22,6 +29,20 @@ pub struct Config {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins with their precision
     pub coin_registry_address: Addr,
+    /// The protocol-wide cap (in bps) on the fees a pair combines on a swap
+    #[serde(default)]
+    pub max_combined_fee_bps: Option<u16>,
+    /// The points contract which pairs and the generator report activity to
+    #[serde(default)]
+    pub points_contract: Option<Addr>,
+    /// The cap (in bps) on the referral fee frontends take from swap returns. `None` disables
+    /// referral fees
+    #[serde(default)]
+    pub max_referral_bps: Option<u16>,
+    /// The cap (in bps) on the buy and sell taxes of sale tax pairs. `None` applies
+    /// [`crate::pair_xyk_sale_tax::MAX_SALE_TAX_BPS`]
+    #[serde(default)]
+    pub max_sale_tax_bps: Option<u16>,
 }
 
 /// This enum describes available pair types.
@@ -114,6 +135,8 @@ pub enum ExecuteMsg {
         whitelist_code_id: Option<u64>,
         /// The address of the contract that contains the coins and their accuracy
         coin_registry_address: Option<String>,
+        /// The protocol-wide cap (in bps) on the fees a pair combines on a swap
+        max_combined_fee_bps: Option<u16>,
     },
     /// UpdatePairConfig updates the config for a pair type.
     UpdatePairConfig {
@@ -129,11 +152,133 @@ pub enum ExecuteMsg {
         /// Optional binary serialised parameters for custom pool types
         init_params: Option<Binary>,
     },
+    /// CreateDuplicatePair instantiates a new pair contract even if the assets already have a pool
+    /// of the same pair type.
+    /// ## Executor
+    /// Only the owner can execute this.
+    CreateDuplicatePair {
+        /// The pair type (exposed in [`PairType`])
+        pair_type: PairType,
+        /// The assets to create the pool for
+        asset_infos: Vec<AssetInfo>,
+        /// Optional binary serialised parameters for custom pool types
+        init_params: Option<Binary>,
+    },
     /// Deregister removes a previously created pair.
     Deregister {
         /// The assets for which we deregister a pool
         asset_infos: Vec<AssetInfo>,
     },
+    /// DisablePairFeeShare turns off swap fee sharing in a registered pair.
+    /// ## Executor
+    /// Only the owner or the fee address (the Maker) can execute this.
+    DisablePairFeeShare {
+        /// The assets of the pair in which fee sharing is disabled
+        asset_infos: Vec<AssetInfo>,
+    },
+    /// UpdatePairAllowlist restricts liquidity provision and swaps in a registered pair to the
+    /// addresses approved by an allowlist contract.
+    /// ## Executor
+    /// Only the owner can execute this.
+    UpdatePairAllowlist {
+        /// The assets of the pair which becomes permissioned
+        asset_infos: Vec<AssetInfo>,
+        /// The allowlist contract. `None` makes the pair permissionless again
+        allowlist: Option<String>,
+    },
+    /// ProposeNewGenerator creates a proposal to change the generator address. The proposal can be
+    /// applied after [`GENERATOR_ROTATION_DELAY`] seconds.
+    /// ## Executor
+    /// Only the owner can execute this.
+    ProposeNewGenerator {
+        /// The proposed generator address
+        generator: String,
+    },
+    /// DropGeneratorProposal removes the existing proposal to change the generator address.
+    DropGeneratorProposal {},
+    /// ApplyNewGenerator sets the proposed generator address once the delay has passed.
+    /// The new generator must respond to a version query and have every pool which is active
+    /// in the current generator registered.
+    /// ## Executor
+    /// Only the owner can execute this.
+    ApplyNewGenerator {},
+    /// SetPointsContract sets the points contract which pairs and the generator report swaps and
+    /// LP positions to.
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetPointsContract {
+        /// The points contract. `None` stops the reports
+        points_contract: Option<String>,
+    },
+    /// SetMaxReferralBps sets the cap on the referral fee frontends can take from swap returns.
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetMaxReferralBps {
+        /// The cap (in bps). `None` disables referral fees
+        max_referral_bps: Option<u16>,
+    },
+    /// SetMaxSaleTaxBps sets the cap on the buy and sell taxes sale tax pairs can charge.
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetMaxSaleTaxBps {
+        /// The cap (in bps). `None` applies [`crate::pair_xyk_sale_tax::MAX_SALE_TAX_BPS`]
+        max_sale_tax_bps: Option<u16>,
+    },
+    /// SetFeeDiscounts sets the swap fee discounts pairs of a specific type give to traders
+    /// holding xGRID.
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetFeeDiscounts {
+        /// The pair type whose fee discounts are set
+        pair_type: PairType,
+        /// The staking contract which issues xGRID
+        staking_contract: String,
+        /// Discount tiers ordered by the xGRID threshold. An empty list removes the discounts
+        tiers: Vec<FeeDiscountTier>,
+    },
+    /// UpdatePairFees overrides the fees of a specific pair registered in the factory. Fees which
+    /// are not set fall back to the config of the pair type.
+    /// ## Executor
+    /// Only the owner can execute this.
+    UpdatePairFees {
+        /// The pair contract address
+        pair_addr: String,
+        /// Total amount of fees (in bps) charged on a swap
+        total_fee_bps: Option<u16>,
+        /// Amount of fees (in bps) sent to the Maker contract
+        maker_fee_bps: Option<u16>,
+    },
+    /// ProposePairType submits a custom pair type for the owner's approval. Once approved, anyone
+    /// can create pairs of this type.
+    /// ## Executor
+    /// Anyone can execute this.
+    ProposePairType {
+        /// The name of the custom pair type
+        name: String,
+        /// ID of the pair contract code
+        code_id: u64,
+        /// The hex encoded SHA-256 hash of the JSON schema of the `init_params` the pair accepts
+        params_schema_hash: String,
+        /// Total amount of fees (in bps) charged on a swap
+        total_fee_bps: u16,
+        /// Amount of fees (in bps) sent to the Maker contract
+        maker_fee_bps: u16,
+    },
+    /// ApprovePairType adds the config of a proposed pair type. Generator rewards stay disabled
+    /// for the pair type until the owner enables them with `UpdatePairConfig`.
+    /// ## Executor
+    /// Only the owner can execute this.
+    ApprovePairType {
+        /// The proposed pair type
+        pair_type: PairType,
+    },
+    /// RejectPairType removes a pending pair type proposal.
+    /// ## Executor
+    /// Only the owner or the proposer can execute this.
+    RejectPairType {
+        /// The proposed pair type
+        pair_type: PairType,
+    },
     /// ProposeNewOwner creates a proposal to change contract ownership.
     /// The validity period for the proposal is set in the `expires_in` variable.
     ProposeNewOwner {
@@ -169,15 +314,84 @@ pub enum QueryMsg {
         /// The number of pairs to read and return. It is an [`Option`] type.
         limit: Option<u32>,
     },
+    /// PairsByAsset returns an array of pairs which contain the specified asset, ordered the same way as in `Pairs`
+    #[returns(PairsResponse)]
+    PairsByAsset {
+        /// The asset traded in the returned pairs
+        asset_info: AssetInfo,
+        /// The pair item to start reading from. It is an [`Option`] type that accepts [`AssetInfo`] elements.
+        start_after: Option<Vec<AssetInfo>>,
+        /// The number of pairs to read and return. It is an [`Option`] type.
+        limit: Option<u32>,
+    },
     /// FeeInfo returns fee parameters for a specific pair. The response is returned using a [`FeeInfoResponse`] structure
     #[returns(FeeInfoResponse)]
     FeeInfo {
         /// The pair type for which we return fee information. Pair type is a [`PairType`] struct
         pair_type: PairType,
     },
+    /// PairFeeInfo returns fee parameters for a specific pair taking its fee override into account
+    #[returns(FeeInfoResponse)]
+    PairFeeInfo {
+        /// The pair contract address
+        pair_addr: String,
+    },
     /// Returns a vector that contains blacklisted pair types
     #[returns(Vec<PairType>)]
     BlacklistedPairTypes {},
+    /// PairStatus returns the pair's LP token, pair config flags and contract version
+    #[returns(PairStatusResponse)]
+    PairStatus {
+        /// The pair contract address
+        pair: String,
+    },
+    /// PairCodeIdHistory returns the code ids used to create pairs of a specific pair type,
+    /// starting from the oldest one
+    #[returns(Vec<PairCodeIdEntry>)]
+    PairCodeIdHistory {
+        /// The pair type for which we return the history
+        pair_type: PairType,
+        /// The activation height to start reading from
+        start_after: Option<u64>,
+        /// The number of entries to read and return
+        limit: Option<u32>,
+    },
+    /// FeeBreakdown returns how swap fees of a specific pair are split between their destinations
+    #[returns(FeeBreakdownResponse)]
+    FeeBreakdown {
+        /// The pair contract address
+        pair: String,
+    },
+    /// GeneratorProposal returns the pending proposal to change the generator address
+    #[returns(Option<GeneratorProposal>)]
+    GeneratorProposal {},
+    /// PairTypeProposal returns a pending or approved proposal of a custom pair type
+    #[returns(Option<PairTypeProposal>)]
+    PairTypeProposal {
+        /// The proposed pair type
+        pair_type: PairType,
+    },
+    /// PairTypeProposals returns pending and approved proposals of custom pair types ordered by
+    /// pair type
+    #[returns(Vec<PairTypeProposal>)]
+    PairTypeProposals {
+        /// The pair type to start reading from
+        start_after: Option<PairType>,
+        /// The number of proposals to read and return
+        limit: Option<u32>,
+    },
+    /// Stats returns aggregate protocol statistics: pairs per type, disabled pairs, fee addresses and code ids
+    #[returns(StatsResponse)]
+    Stats {},
+}
+
+/// A code id used to create pairs of a specific pair type.
+#[cw_serde]
+pub struct PairCodeIdEntry {
+    /// The pair contract code id
+    pub code_id: u64,
+    /// The block height from which new pairs are created with this code id
+    pub activated_at: u64,
 }
 
 /// A custom struct for each query response that returns general contract settings/configs.
@@ -197,6 +411,141 @@ pub struct ConfigResponse {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins and their accuracy
     pub coin_registry_address: Addr,
+    /// The protocol-wide cap (in bps) on the fees a pair combines on a swap
+    pub max_combined_fee_bps: Option<u16>,
+    /// The points contract which pairs and the generator report activity to
+    pub points_contract: Option<Addr>,
+    /// The cap (in bps) on the referral fee frontends take from swap returns
+    pub max_referral_bps: Option<u16>,
+    /// The cap (in bps) on the buy and sell taxes of sale tax pairs
+    pub max_sale_tax_bps: Option<u16>,
+}
+
+/// A custom struct that aggregates the status of a pair registered in the factory.
+#[cw_serde]
+pub struct PairStatusResponse {
+    /// The pair contract address
+    pub contract_addr: Addr,
+    /// The pair type
+    pub pair_type: PairType,
+    /// The LP token address
+    pub liquidity_token: Addr,
+    /// Whether the pair's config is disabled. Also set if the pair type no longer has a config
+    pub is_disabled: bool,
+    /// Whether the pair's type is excluded from GRID emissions. Also set if the pair type no longer has a config
+    pub is_generator_disabled: bool,
+    /// The contract name stored by the pair according to cw2
+    pub contract_name: String,
+    /// The contract version stored by the pair according to cw2
+    pub contract_version: String,
+}
+
+/// The number of pairs of a specific pair type registered in the factory.
+#[cw_serde]
+pub struct PairTypeStats {
+    /// The pair type
+    pub pair_type: PairType,
+    /// The code id used to create new pairs of this type. `None` if the pair type no longer has a config
+    pub code_id: Option<u64>,
+    /// Whether the pair type's config is disabled. Also set if the pair type no longer has a config
+    pub is_disabled: bool,
+    /// The number of registered pairs of this type
+    pub pairs: u64,
+}
+
+/// A custom struct that aggregates protocol statistics in a single response.
+#[cw_serde]
+pub struct StatsResponse {
+    /// The total number of registered pairs
+    pub total_pairs: u64,
+    /// Pair counts and code ids per pair type, ordered by pair type name
+    pub pair_types: Vec<PairTypeStats>,
+    /// The number of registered pairs whose pair type config is disabled or missing
+    pub disabled_pairs: u64,
+    /// Address of contract to send governance fees to (the Maker)
+    pub fee_address: Option<Addr>,
+    /// Address of contract used to auto_stake LP tokens for Gridiron pairs that are incentivized
+    pub generator_address: Option<Addr>,
+    /// The points contract which pairs and the generator report activity to
+    pub points_contract: Option<Addr>,
+    /// CW20 token contract code identifier
+    pub token_code_id: u64,
+    /// CW1 whitelist contract code id
+    pub whitelist_code_id: u64,
+}
+
+/// This enum describes the destinations swap fees are routed to.
+#[cw_serde]
+pub enum FeeDestination {
+    /// The fee share recipient configured in the pair
+    FeeShare,
+    /// The Maker contract
+    Maker,
+    /// xGRID stakers
+    Stakers,
+    /// Liquidity providers of the pair
+    LiquidityProviders,
+}
+
+/// This structure describes the part of swap fees routed to a single destination.
+#[cw_serde]
+pub struct FeeDestinationShare {
+    /// The fee destination
+    pub destination: FeeDestination,
+    /// The address receiving the fees. None for liquidity providers as fees stay in the pool
+    pub recipient: Option<Addr>,
+    /// The part of the total fee routed to the destination
+    pub share: Decimal,
+    /// The fee routed to the destination in bps of the swap amount
+    pub fee_bps: Decimal,
+}
+
+/// This structure describes a proposal to change the generator address.
+#[cw_serde]
+pub struct GeneratorProposal {
+    /// The proposed generator address
+    pub generator: Addr,
+    /// The timestamp (in seconds) after which the proposal can be applied
+    pub apply_after: u64,
+}
+
+/// This structure describes a custom pair type proposed by a third party.
+#[cw_serde]
+pub struct PairTypeProposal {
+    /// The proposed pair type
+    pub pair_type: PairType,
+    /// ID of the pair contract code
+    pub code_id: u64,
+    /// The hex encoded SHA-256 hash of the JSON schema of the `init_params` the pair accepts
+    pub params_schema_hash: String,
+    /// Total amount of fees (in bps) charged on a swap
+    pub total_fee_bps: u16,
+    /// Amount of fees (in bps) sent to the Maker contract
+    pub maker_fee_bps: u16,
+    /// The address which proposed the pair type
+    pub proposer: Addr,
+    /// The block height at which the owner approved the pair type. `None` while pending
+    pub approved_at: Option<u64>,
+}
+
+/// A custom struct that breaks down the swap fees of a pair by destination.
+#[cw_serde]
+pub struct FeeBreakdownResponse {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The pair type
+    pub pair_type: PairType,
+    /// Total amount of fees (in bps) charged on a swap. Pair types with dynamic fees may charge a
+    /// different total, the split between destinations stays the same
+    pub total_fee_bps: u16,
+    /// The part of the fees (in bps) left after fee sharing which is sent to the Maker
+    pub maker_fee_bps: u16,
+    /// The fee share config of the pair
+    pub fee_share: Option<FeeShareConfig>,
+    /// The part of the LP fees (in bps) routed to xGRID stakers
+    pub staker_fee_share_bps: Option<u16>,
+    /// The fee destinations. Shares sum to one and fees sum to `total_fee_bps`
+    pub destinations: Vec<FeeDestinationShare>,
 }
 
 /// This structure stores the parameters used in a migration message.
@@ -221,6 +570,45 @@ pub struct FeeInfoResponse {
     pub total_fee_bps: u16,
     /// Amount of fees (in bps) sent to the Maker contract
     pub maker_fee_bps: u16,
+    /// The points contract which pairs report swaps to
+    #[serde(default)]
+    pub points_contract: Option<Addr>,
+    /// The swap fee discounts given to traders holding xGRID
+    #[serde(default)]
+    pub fee_discounts: Option<FeeDiscounts>,
+    /// The cap (in bps) on the referral fee taken from swap returns. `None` if referral fees
+    /// are disabled
+    #[serde(default)]
+    pub max_referral_bps: Option<u16>,
+}
+
+/// A swap fee discount tier.
+#[cw_serde]
+pub struct FeeDiscountTier {
+    /// The minimum xGRID balance a trader needs to hold
+    pub min_xgrid: Uint128,
+    /// The share of the swap fee (in bps) the trader doesn't pay
+    pub discount_bps: u16,
+}
+
+/// This structure describes the swap fee discounts given to traders holding xGRID.
+#[cw_serde]
+pub struct FeeDiscounts {
+    /// The staking contract which issues xGRID
+    pub staking_contract: Addr,
+    /// Discount tiers ordered by increasing `min_xgrid` and `discount_bps`
+    pub tiers: Vec<FeeDiscountTier>,
+}
+
+impl FeeDiscounts {
+    /// Returns the discount (in bps) of the highest tier reached by the xGRID balance.
+    pub fn discount_bps(&self, xgrid_balance: Uint128) -> u16 {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| xgrid_balance >= tier.min_xgrid)
+            .map_or(0, |tier| tier.discount_bps)
+    }
 }
 
 /// This is an enum used for setting and removing a contract address.
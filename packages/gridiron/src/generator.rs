@@ -1,4 +1,5 @@
 use crate::asset::{Asset, AssetInfo};
+use crate::common::OwnershipProposal;
 use crate::factory::PairType;
 use crate::restricted_vector::RestrictedVector;
 use cosmwasm_schema::{cw_serde, QueryResponses};
@@ -50,6 +51,19 @@ pub enum ExecuteMsg {
         voting_escrow: Option<String>,
         /// The amount of generators
         checkpoint_generator_limit: Option<u32>,
+        /// The minimum number of blocks between proxy reward claims triggered by deposits and
+        /// withdrawals. Zero disables batching
+        proxy_rewards_claim_interval: Option<u64>,
+        /// The staking contract used to auto-stake claimed GRID for users who opted in
+        staking_contract: Option<String>,
+        /// The maximum share of emissions a single pool can receive. Zero removes the cap.
+        /// Applied on the next pools setup
+        max_pool_share: Option<Decimal>,
+        /// The multiplier applied to allocation points of pools paired with GRID or xGRID.
+        /// One removes the boost. Applied on the next pools setup
+        grid_pool_boost: Option<Decimal>,
+        /// The router contract used to swap claimed GRID with `ClaimRewardsAs`
+        router: Option<String>,
     },
     /// Setup generators with their respective allocation points.
     /// ## Executor
@@ -63,6 +77,16 @@ pub enum ExecuteMsg {
         /// the LP token contract address
         lp_tokens: Vec<String>,
     },
+    /// Update rewards and return them to user with GRID swapped into another asset via the router.
+    /// GRID is sent as is if the swap fails
+    ClaimRewardsAs {
+        /// The LP token contract address
+        lp_token: String,
+        /// The asset GRID rewards are swapped into
+        target_asset: AssetInfo,
+        /// The minimum amount of the target asset to receive
+        min_received: Option<Uint128>,
+    },
     /// Withdraw LP tokens from the Generator
     Withdraw {
         /// The address of the LP token to withdraw
@@ -70,6 +94,32 @@ pub enum ExecuteMsg {
         /// The amount to withdraw
         amount: Uint128,
     },
+    /// Withdraw LP tokens from the Generator, withdraw the liquidity they represent from the pair
+    /// and send the pool assets to the sender
+    WithdrawAndUnwrap {
+        /// The address of the LP token to withdraw
+        lp_token: String,
+        /// The amount to withdraw
+        amount: Uint128,
+        /// The minimum amounts of the pool assets to receive
+        min_assets: Option<Vec<Asset>>,
+    },
+    /// Stake LP tokens on behalf of another address. The LP tokens are pulled from the sender
+    /// using a CW20 allowance, while the staked position and its rewards belong to the beneficiary.
+    DepositFor {
+        /// The address of the LP token to stake
+        lp_token: String,
+        /// The address that will own the staked position
+        beneficiary: String,
+        /// The amount of LP tokens to stake
+        amount: Uint128,
+    },
+    /// Stake native LP tokens sent along with the message. Only available when the Generator is
+    /// built in bank mode, otherwise LP tokens are staked with [`Cw20HookMsg::Deposit`]
+    Deposit {
+        /// The address that will own the staked position. Defaults to the sender
+        beneficiary: Option<String>,
+    },
     /// Withdraw LP tokens from the Generator without withdrawing outstanding rewards
     EmergencyWithdraw {
         /// The address of the LP token to withdraw
@@ -91,6 +141,33 @@ pub enum ExecuteMsg {
         /// The new amount of GRID to distro per block
         amount: Uint128,
     },
+    /// Queues a new amount of GRID to distribute per block which takes effect at the activation
+    /// height. Rewards accrued before the activation height use the previous amount
+    /// ## Executor
+    /// Only the owner can execute this.
+    ScheduleTokensPerBlock {
+        /// The new amount of GRID to distro per block
+        amount: Uint128,
+        /// The block height at which the new amount takes effect
+        activation_height: u64,
+    },
+    /// Cancels a queued change of the amount of GRID distributed per block before its activation
+    /// ## Executor
+    /// Only the owner can execute this.
+    CancelScheduledTokensPerBlock {
+        /// The activation height of the queued change
+        activation_height: u64,
+    },
+    /// Pauses or resumes accrual of new GRID emissions. Deposits, withdrawals and claims of
+    /// already accrued rewards keep working while emissions are paused
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetEmissionsPaused {
+        /// The LP token whose generator is paused. Pauses all generators if not specified
+        lp_token: Option<String>,
+        /// Whether emissions are paused
+        paused: bool,
+    },
     /// Creates a request to change contract ownership
     /// ## Executor
     /// Only the current owner can execute this.
@@ -109,17 +186,23 @@ pub enum ExecuteMsg {
     /// Only the newly proposed owner can execute this
     ClaimOwnership {},
     /// Sets a new proxy contract for a specific generator
-    /// Sets a proxy for the pool
+    /// Sets a proxy for the pool. The proxy holds the pool's staked LP tokens
     /// ## Executor
     /// Only the current owner or generator controller can execute this
-    MoveToProxy {
-        lp_token: String,
-        proxy: String,
-    },
-    MigrateProxy {
-        lp_token: String,
-        new_proxy: String,
-    },
+    MoveToProxy { lp_token: String, proxy: String },
+    /// Migrates LP tokens and collected rewards from the proxy which holds the pool's LP tokens
+    /// to a new proxy
+    MigrateProxy { lp_token: String, new_proxy: String },
+    /// Attaches an additional reward proxy to the pool. The proxy doesn't hold LP tokens and its
+    /// rewards are distributed pro rata to the LP tokens staked in the generator
+    /// ## Executor
+    /// Only the current owner can execute this
+    AddRewardProxy { lp_token: String, proxy: String },
+    /// Detaches a reward proxy from the pool. Rewards accrued so far stay claimable from the
+    /// proxy rewards holder. If the proxy holds the pool's LP tokens they are moved back to the generator
+    /// ## Executor
+    /// Only the current owner can execute this
+    DeactivateRewardProxy { lp_token: String, proxy: String },
     /// Add or remove token to the block list
     UpdateBlockedTokenslist {
         /// Tokens to add
@@ -127,23 +210,103 @@ pub enum ExecuteMsg {
         /// Tokens to remove
         remove: Option<Vec<AssetInfo>>,
     },
-    /// Sets the allocation point to zero for the specified pool
-    DeactivatePool {
-        lp_token: String,
+    /// Add or remove pools to and from the blocked list. Blocked pools receive no emissions and
+    /// are skipped on pools setup.
+    /// ## Executor
+    /// Only the owner can execute this.
+    UpdateBlockedPools {
+        /// LP tokens of the pools to add
+        add: Option<Vec<String>>,
+        /// LP tokens of the pools to remove
+        remove: Option<Vec<String>>,
     },
+    /// Sets the allocation point to zero for the specified pool
+    DeactivatePool { lp_token: String },
     /// Sets the allocation point to zero for each pool by the pair type
-    DeactivateBlacklistedPools {
-        pair_types: Vec<PairType>,
-    },
+    DeactivateBlacklistedPools { pair_types: Vec<PairType> },
     /// Updates the boost emissions for specified user and generators
     CheckpointUserBoost {
         generators: Vec<String>,
         user: Option<String>,
     },
-    /// Process action after the callback
-    Callback {
-        action: ExecuteOnReply,
+    /// Enables or disables auto-staking of the sender's claimed GRID rewards into xGRID.
+    /// Can only be enabled when a staking contract is set in the config
+    SetAutoStakeRewards {
+        /// Whether claimed GRID rewards are auto-staked
+        enabled: bool,
+    },
+    /// Instantiates a transferable receipt token for positions staked in a specific generator
+    /// ## Executor
+    /// Only the owner can execute this.
+    SetupReceiptToken {
+        /// The LP token whose staked positions are represented by the receipt token
+        lp_token: String,
+        /// The code ID of the receipt token contract
+        token_code_id: u64,
+    },
+    /// Mints receipt tokens backed 1:1 by the sender's staked LP tokens. The staked position and
+    /// its rewards follow the receipt tokens when they are transferred
+    MintReceipt {
+        /// The LP token whose receipt tokens are minted
+        lp_token: String,
+        /// The amount of staked LP tokens to back receipt tokens with
+        amount: Uint128,
+    },
+    /// Burns the sender's receipt tokens. The backing LP tokens stay staked
+    BurnReceipt {
+        /// The LP token whose receipt tokens are burned
+        lp_token: String,
+        /// The amount of receipt tokens to burn
+        amount: Uint128,
+    },
+    /// Moves the staked position backing transferred receipt tokens to the new holder
+    /// ## Executor
+    /// Only a receipt token contract can execute this.
+    ReceiptTransferHook {
+        /// The previous holder of the receipt tokens
+        from: String,
+        /// The new holder of the receipt tokens
+        to: String,
+        /// The amount of receipt tokens transferred
+        amount: Uint128,
+    },
+    /// Posts a merkle root of retroactive GRID rewards. The drop is funded with native GRID attached
+    /// to the message or with cw20 GRID pulled from the owner's allowance
+    /// ## Executor
+    /// Only the owner can execute this.
+    RegisterMerkleDrop {
+        /// The hex encoded merkle root of the (address, amount) leaves
+        merkle_root: String,
+        /// The total amount of GRID distributed by the drop
+        total_amount: Uint128,
+        /// The timestamp (in seconds) after which rewards can't be claimed anymore
+        expires_at: u64,
     },
+    /// Claims the sender's retroactive GRID rewards from a merkle drop
+    ClaimMerkleDrop {
+        /// The merkle drop identifier
+        drop_id: u64,
+        /// The amount of GRID allotted to the sender
+        amount: Uint128,
+        /// The hex encoded merkle proof of the sender's leaf
+        proof: Vec<String>,
+    },
+    /// Returns the unclaimed GRID of an expired merkle drop to the owner
+    ReclaimMerkleDrop {
+        /// The merkle drop identifier
+        drop_id: u64,
+    },
+    /// Updates the accrued GRID rewards of active generators ordered by LP token address. A pass
+    /// over all generators may span several transactions: the position is stored and the next call
+    /// without `start_after` continues from it
+    UpdatePools {
+        /// The LP token after which generators are updated. Continues the stored pass if not set
+        start_after: Option<String>,
+        /// The maximum amount of generators to update
+        limit: Option<u32>,
+    },
+    /// Process action after the callback
+    Callback { action: ExecuteOnReply },
 }
 
 #[cw_serde]
@@ -155,6 +318,17 @@ pub enum ExecuteOnReply {
         /// The rewards recipient
         account: Addr,
     },
+    /// Updates reward and returns it to user with GRID swapped into the target asset.
+    ClaimRewardsAs {
+        /// The LP token contract
+        lp_token: Addr,
+        /// The rewards recipient
+        account: Addr,
+        /// The asset GRID rewards are swapped into
+        target_asset: AssetInfo,
+        /// The minimum amount of the target asset to receive
+        min_received: Option<Uint128>,
+    },
     /// Stake LP tokens in the Generator to receive token emissions
     Deposit {
         /// The LP token to stake
@@ -173,6 +347,39 @@ pub enum ExecuteOnReply {
         /// The amount of tokens to withdraw
         amount: Uint128,
     },
+    /// Withdraw LP tokens from the Generator to the Generator itself and unwrap them afterwards
+    WithdrawAndUnwrap {
+        /// The LP tokens to withdraw
+        lp_token: Addr,
+        /// The account that receives the pool assets
+        account: Addr,
+        /// The amount of tokens to withdraw
+        amount: Uint128,
+        /// The minimum amounts of the pool assets to receive
+        min_assets: Vec<Asset>,
+    },
+    /// Withdraw liquidity from the pair with LP tokens held by the Generator
+    UnwrapLp {
+        /// The LP tokens to unwrap
+        lp_token: Addr,
+        /// The account that receives the pool assets
+        account: Addr,
+        /// The amount of tokens to unwrap
+        amount: Uint128,
+        /// The minimum amounts of the pool assets to receive
+        min_assets: Vec<Asset>,
+    },
+    /// Moves a staked position backing transferred receipt tokens
+    MoveReceiptPosition {
+        /// The LP token of the generator
+        lp_token: Addr,
+        /// The previous holder of the receipt tokens
+        from: Addr,
+        /// The new holder of the receipt tokens
+        to: Addr,
+        /// The amount of staked LP tokens to move
+        amount: Uint128,
+    },
     /// Sets a new amount of GRID to distribute per block between all active generators
     SetTokensPerBlock {
         /// The new amount of GRID to distribute per block
@@ -186,6 +393,19 @@ pub enum ExecuteOnReply {
         prev_proxy_addr: Addr,
         amount: Uint128,
     },
+    /// Detach a reward proxy from the pool
+    DeactivateRewardProxy { lp_addr: Addr, proxy_addr: Addr },
+    /// Stake GRID rewards claimed by the generator and send the received xGRID to the account.
+    /// The GRID is sent as is if staking fails
+    AutoStakeRewards { account: Addr, amount: Uint128 },
+    /// Swap GRID rewards claimed by the generator via the router and send the target asset to the
+    /// account. The GRID is sent as is if the swap fails
+    SwapRewards {
+        account: Addr,
+        amount: Uint128,
+        target_asset: AssetInfo,
+        min_received: Option<Uint128>,
+    },
 }
 
 impl ExecuteOnReply {
@@ -237,7 +457,7 @@ pub enum QueryMsg {
     /// SimulateFutureReward returns the amount of GRID that will be distributed until a future block and for a specific generator
     #[returns(Uint128)]
     SimulateFutureReward { lp_token: String, future_block: u64 },
-    /// Returns a list of stakers for a specific generator
+    /// Returns a list of stakers for a specific generator ordered by address
     #[returns(Vec<StakerResponse>)]
     PoolStakers {
         lp_token: String,
@@ -247,9 +467,122 @@ pub enum QueryMsg {
     /// Returns the blocked list of tokens
     #[returns(Vec<AssetInfo>)]
     BlockedTokensList {},
+    /// Returns the blocked list of pools (LP tokens)
+    #[returns(Vec<Addr>)]
+    BlockedPools {},
     /// Returns a list of reward proxy contracts which have been ever used
     #[returns(Vec<Addr>)]
     RewardProxiesList {},
+    /// Returns whether the user's claimed GRID rewards are auto-staked into xGRID
+    #[returns(bool)]
+    AutoStakeRewards { user: String },
+    /// Returns the receipt token of a specific generator if it was set up
+    #[returns(Option<Addr>)]
+    ReceiptToken { lp_token: String },
+    /// Returns the amount of the user's staked LP tokens that back receipt tokens
+    #[returns(Uint128)]
+    ReceiptBacked { lp_token: String, user: String },
+    /// Returns a merkle drop of retroactive GRID rewards
+    #[returns(MerkleDrop)]
+    MerkleDrop { drop_id: u64 },
+    /// Returns the amount of GRID the user claimed from a merkle drop
+    #[returns(Uint128)]
+    MerkleDropClaimed { drop_id: u64, user: String },
+    /// Returns the underlying assets of the LP tokens staked in a specific generator together with
+    /// the share of the total LP supply which is staked
+    #[returns(PoolTvlResponse)]
+    PoolTvl { lp_token: String },
+    /// Returns the rewards a user currently receives per block in a specific generator based on
+    /// their boosted share and the current alloc points
+    #[returns(UserEmissionRateResponse)]
+    UserEmissionRate { lp_token: String, user: String },
+    /// Returns the LP token of the last generator updated by an unfinished `UpdatePools` pass
+    #[returns(Option<Addr>)]
+    UpdatePoolsCursor {},
+    /// Returns a snapshot of the contract state in a deterministic order so it can be diffed across
+    /// upgrades. Global items are returned on every page while generators are paginated by LP token
+    #[returns(StateSnapshotResponse)]
+    StateSnapshot {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the queued changes of the amount of GRID distributed per block ordered by
+    /// activation height
+    #[returns(Vec<ScheduledEmission>)]
+    EmissionSchedule {},
+}
+
+/// The maximum number of queued changes of the amount of GRID distributed per block
+pub const MAX_SCHEDULED_EMISSIONS: usize = 10;
+
+/// This structure describes a queued change of the amount of GRID distributed per block
+#[cw_serde]
+pub struct ScheduledEmission {
+    /// The block height at which the new amount takes effect
+    pub activation_height: u64,
+    /// The new amount of GRID to distro per block
+    pub tokens_per_block: Uint128,
+}
+
+/// The layout version of [`StateSnapshotResponse`]. Bumped whenever the snapshot layout changes
+pub const STATE_SNAPSHOT_SCHEMA_VERSION: u32 = 2;
+
+/// This structure holds the response returned when querying for a snapshot of the contract state
+#[cw_serde]
+pub struct StateSnapshotResponse {
+    /// The layout version of the snapshot
+    pub schema_version: u32,
+    /// The contract config. Active pools and blocked pools are sorted by address, blocked tokens
+    /// are sorted by their string representation
+    pub config: Config,
+    /// The pending request to change contract ownership
+    pub ownership_proposal: Option<OwnershipProposal>,
+    /// The previous proxy rewards holder
+    pub proxy_rewards_holder: Option<Addr>,
+    /// The reward assets of all reward proxies which have ever been used, ordered by proxy
+    pub proxy_reward_assets: Vec<(Addr, AssetInfo)>,
+    /// All merkle drops ordered by drop id
+    pub merkle_drops: Vec<(u64, MerkleDrop)>,
+    /// The number of registered merkle drops
+    pub merkle_drop_count: u64,
+    /// The LP token of the last generator updated by an unfinished `UpdatePools` pass
+    pub update_pools_cursor: Option<Addr>,
+    /// Whether reward claims dispatched to proxies are in flight
+    pub reentrancy_lock: bool,
+    /// The queued changes of the amount of GRID distributed per block ordered by activation height
+    pub emission_schedule: Vec<ScheduledEmission>,
+    /// A page of generators ordered by LP token
+    pub pools: Vec<PoolSnapshot>,
+}
+
+/// This structure describes the state of a single generator in a [`StateSnapshotResponse`]
+#[cw_serde]
+pub struct PoolSnapshot {
+    /// The LP token staked in the generator
+    pub lp_token: Addr,
+    /// The allocation points of the generator, zero if it is not active
+    pub alloc_point: Uint128,
+    /// The generator's stored reward indexes and totals
+    pub pool_info: PoolInfo,
+    /// The receipt token of the generator if it was set up
+    pub receipt_token: Option<Addr>,
+    /// Total amount of LP tokens staked in the generator
+    pub lp_supply: Uint128,
+}
+
+/// This structure describes a merkle drop of retroactive GRID rewards.
+#[cw_serde]
+pub struct MerkleDrop {
+    /// The hex encoded merkle root of the (address, amount) leaves
+    pub merkle_root: String,
+    /// The total amount of GRID distributed by the drop
+    pub total_amount: Uint128,
+    /// The amount of GRID claimed so far
+    pub claimed_amount: Uint128,
+    /// The timestamp (in seconds) after which rewards can't be claimed anymore
+    pub expires_at: u64,
+    /// Whether the unclaimed GRID was returned to the owner
+    pub reclaimed: bool,
 }
 
 /// This structure holds the response returned when querying the amount of pending rewards that can be withdrawn from a 3rd party
@@ -262,24 +595,62 @@ pub struct PendingTokenResponse {
     pub pending_on_proxy: Option<Vec<Asset>>,
 }
 
+/// This structure describes a reward proxy attached to a pool
+#[cw_serde]
+pub struct RewardProxyInfo {
+    /// The reward proxy contract
+    pub proxy: Addr,
+    /// Whether the proxy holds the pool's staked LP tokens. At most one proxy per pool holds them
+    pub holds_lp_tokens: bool,
+    /// Proxy reward balance at the last update. Used for calculation of new proxy rewards
+    pub reward_balance_before_update: Uint128,
+    /// Rewards pending on the proxy which are already accounted in the proxy reward index but
+    /// were not claimed yet
+    #[serde(default)]
+    pub accounted_pending_rewards: Uint128,
+}
+
 /// This structure describes the main information of pool
 #[cw_serde]
 pub struct PoolInfo {
     /// Accumulated amount of reward per share unit. Used for reward calculations
     pub last_reward_block: Uint64,
     pub reward_global_index: Decimal,
-    /// the reward proxy contract
-    pub reward_proxy: Option<Addr>,
+    /// The active reward proxies of the pool
+    pub reward_proxies: Vec<RewardProxyInfo>,
     /// Accumulated reward indexes per reward proxy. Vector of pairs (reward_proxy, index).
     pub accumulated_proxy_rewards_per_share: RestrictedVector<Addr, Decimal>,
-    /// for calculation of new proxy rewards
-    pub proxy_reward_balance_before_update: Uint128,
     /// the orphan proxy rewards which are left by emergency withdrawals. Vector of pairs (reward_proxy, index).
     pub orphan_proxy_rewards: RestrictedVector<Addr, Uint128>,
     /// This field is not used anymore and can opt out on next migration of the structure
     pub has_asset_rewards: bool,
     /// Total virtual amount
     pub total_virtual_supply: Uint128,
+    /// The block at which proxy rewards were last claimed from the reward proxies
+    pub last_proxy_rewards_claim_block: Option<Uint64>,
+    /// Whether accrual of new GRID emissions is paused for this pool
+    #[serde(default)]
+    pub emissions_paused: bool,
+}
+
+impl PoolInfo {
+    /// Returns the reward proxy which holds the pool's staked LP tokens
+    pub fn lp_proxy(&self) -> Option<&Addr> {
+        self.reward_proxies
+            .iter()
+            .find(|info| info.holds_lp_tokens)
+            .map(|info| &info.proxy)
+    }
+
+    /// Returns true if the proxy is currently attached to the pool
+    pub fn is_active_proxy(&self, proxy: &Addr) -> bool {
+        self.reward_proxies.iter().any(|info| &info.proxy == proxy)
+    }
+
+    /// Returns true if no new GRID emissions accrue to the pool
+    pub fn is_emissions_paused(&self, cfg: &Config) -> bool {
+        cfg.emissions_paused || self.emissions_paused
+    }
 }
 
 /// This structure stores the outstanding amount of token rewards that a user accrued.
@@ -316,8 +687,8 @@ pub struct UserInfoV2 {
 pub struct RewardInfoResponse {
     /// [`AssetInfo`] of the base reward token
     pub base_reward_token: AssetInfo,
-    /// The address of the 3rd party reward token
-    pub proxy_reward_token: Option<Addr>,
+    /// The addresses of the 3rd party reward tokens of the active reward proxies
+    pub proxy_reward_tokens: Vec<Addr>,
 }
 
 /// This structure holds the response returned when querying for a pool's information
@@ -335,18 +706,49 @@ pub struct PoolInfoResponse {
     pub global_reward_index: Decimal,
     /// Pending amount of total GRID rewards which are claimable by stakers right now
     pub pending_grid_rewards: Uint128,
-    /// The address of the 3rd party reward proxy contract
-    pub reward_proxy: Option<Addr>,
-    /// Pending amount of total proxy rewards which are claimable by stakers right now
-    pub pending_proxy_rewards: Option<Uint128>,
+    /// The active 3rd party reward proxy contracts
+    pub reward_proxies: Vec<RewardProxyInfo>,
+    /// Pending amount of total proxy rewards which are claimable by stakers right now per active proxy
+    pub pending_proxy_rewards: Vec<(Addr, Uint128)>,
     /// Total amount of 3rd party token rewards already accumulated per LP token staked per proxy
     pub accumulated_proxy_rewards_per_share: Vec<(Addr, Decimal)>,
-    /// Reward balance for the dual rewards proxy before updating accrued rewards
-    pub proxy_reward_balance_before_update: Uint128,
     /// The amount of orphan proxy rewards which are left behind by emergency withdrawals and not yet transferred out
     pub orphan_proxy_rewards: Vec<(Addr, Uint128)>,
     /// Total amount of lp tokens staked in the pool's generator
     pub lp_supply: Uint128,
+    /// The block at which proxy rewards were last claimed from the reward proxy
+    pub last_proxy_rewards_claim_block: Option<u64>,
+    /// Whether accrual of new GRID emissions is paused for this pool, either directly or globally
+    pub emissions_paused: bool,
+}
+
+/// This structure holds the response returned when querying for the TVL of a pool's generator
+#[cw_serde]
+pub struct PoolTvlResponse {
+    /// The pair which issues the LP token
+    pub pair: Addr,
+    /// Total amount of LP tokens staked in the pool's generator
+    pub staked_lp: Uint128,
+    /// The total amount of LP tokens currently issued by the pair
+    pub total_lp: Uint128,
+    /// The share of the total LP supply which is staked in the generator
+    pub staked_share: Decimal,
+    /// The pool assets backing the staked LP tokens
+    pub staked_assets: Vec<Asset>,
+    /// The assets in the pool together with asset amounts
+    pub total_assets: Vec<Asset>,
+}
+
+/// This structure holds the response returned when querying for the rewards a user receives per block
+#[cw_serde]
+pub struct UserEmissionRateResponse {
+    /// The user's share of the generator's GRID emissions, i.e. the share of their virtual amount
+    pub share: Decimal,
+    /// The amount of GRID the user currently receives per block
+    pub grid_per_block: Uint128,
+    /// The 3rd party rewards the user currently receives per block. They are estimated from the
+    /// rewards which accrued on each proxy since they were last claimed
+    pub proxy_rewards_per_block: Vec<Asset>,
 }
 
 /// This structure stores the core parameters for the Generator contract.
@@ -380,6 +782,28 @@ pub struct Config {
     pub guardian: Option<Addr>,
     /// The amount of generators
     pub checkpoint_generator_limit: Option<u32>,
+    /// The minimum number of blocks between proxy reward claims triggered by deposits and withdrawals
+    pub proxy_rewards_claim_interval: Option<u64>,
+    /// Whether accrual of new GRID emissions is paused for all pools
+    #[serde(default)]
+    pub emissions_paused: bool,
+    /// The staking contract used to auto-stake claimed GRID for users who opted in
+    #[serde(default)]
+    pub staking_contract: Option<Addr>,
+    /// The maximum share of emissions a single pool can receive. Allocation points above the cap
+    /// are redistributed pro-rata among the other pools
+    #[serde(default)]
+    pub max_pool_share: Option<Decimal>,
+    /// The list of pools (LP tokens) blocked from receiving emissions
+    #[serde(default)]
+    pub blocked_pools: Vec<Addr>,
+    /// The multiplier applied to allocation points of pools where one of the assets is GRID or
+    /// xGRID (taken from the staking contract)
+    #[serde(default)]
+    pub grid_pool_boost: Option<Decimal>,
+    /// The router contract used to swap claimed GRID for users who claim rewards as another asset
+    #[serde(default)]
+    pub router: Option<Addr>,
 }
 
 /// This structure describes a migration message.
@@ -420,4 +844,6 @@ pub struct StakerResponse {
     pub account: String,
     // The amount that the staker currently has in the generator
     pub amount: Uint128,
+    // The staker's boosted amount used to split GRID emissions
+    pub virtual_amount: Uint128,
 }
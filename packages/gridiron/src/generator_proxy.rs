@@ -80,6 +80,12 @@ pub enum QueryMsg {
     /// Returns the 3rd party reward token contract address
     #[returns(Addr)]
     RewardInfo {},
+    /// Returns whether the proxy is in emergency mode (e.g. its reward token was compromised).
+    /// While it is, the generator doesn't claim or distribute its rewards and withdraws LP tokens
+    /// from it with [`ExecuteMsg::EmergencyWithdraw`]. Proxies which don't implement this query
+    /// are considered to operate normally.
+    #[returns(bool)]
+    EmergencyMode {},
 }
 
 pub type ConfigResponse = InstantiateMsg;
@@ -0,0 +1,202 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, Uint128};
+use cw20::{
+    AllAccountsResponse, AllAllowancesResponse, AllowanceResponse, BalanceResponse, Cw20ReceiveMsg,
+    Expiration, TokenInfoResponse,
+};
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address that's allowed to change contract parameters
+    pub owner: String,
+    /// The generator contract address
+    pub generator: String,
+    /// The LP token staked by the vault
+    pub lp_token: String,
+    /// The address that receives generator rewards earned by the vault position
+    pub reward_receiver: String,
+    /// Vault share token name
+    pub name: String,
+    /// Vault share token symbol
+    pub symbol: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
+    Receive(Cw20ReceiveMsg),
+    /// Burns shares from `owner` and sends exactly `assets` LP tokens to `receiver`
+    Withdraw {
+        /// The amount of LP tokens to withdraw
+        assets: Uint128,
+        /// The address that receives the LP tokens. Defaults to the sender
+        receiver: Option<String>,
+        /// The address whose shares are burned. Defaults to the sender. The sender must have
+        /// a share allowance from the owner otherwise
+        owner: Option<String>,
+    },
+    /// Burns exactly `shares` from `owner` and sends the corresponding LP tokens to `receiver`
+    Redeem {
+        /// The amount of shares to burn
+        shares: Uint128,
+        /// The address that receives the LP tokens. Defaults to the sender
+        receiver: Option<String>,
+        /// The address whose shares are burned. Defaults to the sender. The sender must have
+        /// a share allowance from the owner otherwise
+        owner: Option<String>,
+    },
+    /// Claims generator rewards for the vault position and sends them to the reward receiver
+    ClaimRewards {},
+    /// Updates the reward receiver
+    /// ## Executor
+    /// Only the owner can execute this
+    UpdateConfig {
+        /// The new reward receiver
+        reward_receiver: Option<String>,
+    },
+    /// Transfers shares to another account
+    Transfer { recipient: String, amount: Uint128 },
+    /// Sends shares to a contract and triggers a receive hook
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Allows a spender to transfer or redeem shares on behalf of the sender
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Lowers a spender's share allowance
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Transfers shares on behalf of the owner using an allowance
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Sends shares to a contract on behalf of the owner using an allowance
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Creates a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    /// ## Executor
+    /// Only the newly proposed owner can execute this
+    ClaimOwnership {},
+    /// Sends all reward tokens held by the vault to the reward receiver
+    /// ## Executor
+    /// Only the contract itself can execute this
+    ForwardRewards {},
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Stakes the received LP tokens in the generator and mints shares to `receiver`
+    Deposit {
+        /// The address that receives the shares. Defaults to the sender
+        receiver: Option<String>,
+    },
+    /// Mints exactly `shares` to `receiver`, staking only the LP tokens required for them.
+    /// The excess LP tokens are returned to the sender
+    Mint {
+        /// The amount of shares to mint
+        shares: Uint128,
+        /// The address that receives the shares. Defaults to the sender
+        receiver: Option<String>,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns the LP token managed by the vault
+    #[returns(Addr)]
+    Asset {},
+    /// Returns the amount of LP tokens backing all shares
+    #[returns(Uint128)]
+    TotalAssets {},
+    /// Returns the amount of shares the vault would exchange for the given LP amount
+    #[returns(Uint128)]
+    ConvertToShares { assets: Uint128 },
+    /// Returns the amount of LP tokens the vault would exchange for the given shares
+    #[returns(Uint128)]
+    ConvertToAssets { shares: Uint128 },
+    /// Returns the amount of shares minted by depositing `assets`
+    #[returns(Uint128)]
+    PreviewDeposit { assets: Uint128 },
+    /// Returns the amount of LP tokens required to mint `shares`
+    #[returns(Uint128)]
+    PreviewMint { shares: Uint128 },
+    /// Returns the amount of shares burned by withdrawing `assets`
+    #[returns(Uint128)]
+    PreviewWithdraw { assets: Uint128 },
+    /// Returns the amount of LP tokens received by redeeming `shares`
+    #[returns(Uint128)]
+    PreviewRedeem { shares: Uint128 },
+    /// Returns the maximum amount of LP tokens `owner` can withdraw
+    #[returns(Uint128)]
+    MaxWithdraw { owner: String },
+    /// Returns the share balance of the given address, 0 if unset
+    #[returns(BalanceResponse)]
+    Balance { address: String },
+    /// Returns the share token metadata and total supply
+    #[returns(TokenInfoResponse)]
+    TokenInfo {},
+    /// Returns the amount of shares the spender can spend from the owner account, 0 if unset
+    #[returns(AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+    /// Returns all the allowances this share holder has approved. Supports pagination
+    #[returns(AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all the accounts that hold shares. Supports pagination
+    #[returns(AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure stores the main parameters for the vault contract.
+#[cw_serde]
+pub struct Config {
+    /// Address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The generator contract address
+    pub generator: Addr,
+    /// The LP token staked by the vault
+    pub lp_token: Addr,
+    /// The address that receives generator rewards earned by the vault position
+    pub reward_receiver: Addr,
+}
@@ -0,0 +1,113 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+/// This enum describes how the converter provides the native GRID denom.
+#[cw_serde]
+pub enum NativeMode {
+    /// The converter is the tokenfactory admin of the denom. Native GRID is minted on conversion
+    /// from CW20 and burned on conversion back to CW20
+    MintBurn {},
+    /// The converter holds a pre-funded balance of native GRID and sends it from escrow
+    Escrow {},
+}
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address that's allowed to change contract parameters
+    pub owner: String,
+    /// The legacy CW20 GRID token address
+    pub cw20_grid: String,
+    /// The native tokenfactory GRID denom
+    pub native_grid: String,
+    /// How the native GRID denom is provided
+    pub native_mode: NativeMode,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
+    Receive(Cw20ReceiveMsg),
+    /// Converts the native GRID sent along with the message to CW20 GRID 1:1
+    Convert {
+        /// The address that receives CW20 GRID. Defaults to the sender
+        receiver: Option<String>,
+    },
+    /// Pauses or resumes conversion directions
+    /// ## Executor
+    /// Only the owner can execute this
+    UpdateConfig {
+        /// Whether CW20 GRID can be converted to native GRID
+        cw20_to_native_enabled: Option<bool>,
+        /// Whether native GRID can be converted to CW20 GRID
+        native_to_cw20_enabled: Option<bool>,
+    },
+    /// Creates a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    /// ## Executor
+    /// Only the current owner can execute this
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    /// ## Executor
+    /// Only the newly proposed owner can execute this
+    ClaimOwnership {},
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Converts the received CW20 GRID to native GRID 1:1
+    Convert {
+        /// The address that receives native GRID. Defaults to the sender
+        receiver: Option<String>,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns the total amounts converted in each direction
+    #[returns(ConversionStats)]
+    Stats {},
+}
+
+/// This structure stores the main parameters for the converter contract.
+#[cw_serde]
+pub struct Config {
+    /// Address that's allowed to change contract parameters
+    pub owner: Addr,
+    /// The legacy CW20 GRID token address
+    pub cw20_grid: Addr,
+    /// The native tokenfactory GRID denom
+    pub native_grid: String,
+    /// How the native GRID denom is provided
+    pub native_mode: NativeMode,
+    /// Whether CW20 GRID can be converted to native GRID
+    pub cw20_to_native_enabled: bool,
+    /// Whether native GRID can be converted to CW20 GRID
+    pub native_to_cw20_enabled: bool,
+}
+
+/// This structure stores the total amounts converted in each direction.
+#[cw_serde]
+#[derive(Default)]
+pub struct ConversionStats {
+    /// The total amount of CW20 GRID converted to native GRID
+    pub total_to_native: Uint128,
+    /// The total amount of native GRID converted to CW20 GRID
+    pub total_to_cw20: Uint128,
+}
@@ -1,11 +1,16 @@
+pub mod allowlist;
 pub mod asset;
+pub mod burn_manager;
 pub mod common;
 pub mod cosmwasm_ext;
 pub mod cw20_ics20;
+pub mod events;
 pub mod factory;
 pub mod fee_granter;
 pub mod generator;
 pub mod generator_proxy;
+pub mod generator_vault;
+pub mod grid_converter;
 #[cfg(feature = "injective")]
 pub mod injective_ext;
 pub mod maker;
@@ -18,12 +23,15 @@ pub mod pair;
 pub mod pair_bonded;
 pub mod pair_concentrated;
 pub mod pair_concentrated_inj;
+pub mod pair_xyk_sale_tax;
+pub mod points;
 pub mod querier;
 pub mod restricted_vector;
 pub mod router;
 pub mod shared_multisig;
 pub mod staking;
 pub mod token;
+pub mod versioned;
 pub mod vesting;
 pub mod xgrid_outpost_token;
 pub mod xgrid_token;
@@ -1,5 +1,7 @@
 use crate::asset::{Asset, AssetInfo};
 use crate::factory::UpdateAddr;
+use crate::pair::FeeShareConfig;
+use crate::router::SwapOperation;
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Decimal, Uint128, Uint64};
 
@@ -34,6 +36,12 @@ pub struct Config {
     pub pre_upgrade_grid_amount: Uint128,
     /// Parameters that describe the second receiver of fees
     pub second_receiver_cfg: Option<SecondReceiverConfig>,
+    /// The length of a distribution epoch in seconds. If set, GRID is distributed once per epoch
+    pub epoch_length: Option<u64>,
+    /// The oracle contract used to value pending fees in USD
+    pub oracle_contract: Option<Addr>,
+    /// The maximum amount of GRID sent to xGRID stakers per epoch. The excess is carried over
+    pub max_staking_per_epoch: Option<Uint128>,
 }
 
 /// This structure stores general parameters for the contract.
@@ -57,6 +65,8 @@ pub struct InstantiateMsg {
     pub max_spread: Option<Decimal>,
     /// The second receiver parameters of fees
     pub second_receiver_params: Option<SecondReceiverParams>,
+    /// The length of a distribution epoch in seconds. If None, GRID is distributed on every collect
+    pub epoch_length: Option<u64>,
 }
 
 /// This structure describes the functions that can be executed in this contract.
@@ -83,6 +93,12 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         /// The second receiver parameters of fees
         second_receiver_params: Option<SecondReceiverParams>,
+        /// The length of a distribution epoch in seconds. Zero disables epochs
+        epoch_length: Option<u64>,
+        /// The oracle contract used to value pending fees in USD
+        oracle_contract: Option<UpdateAddr>,
+        /// The maximum amount of GRID sent to xGRID stakers per epoch. Zero removes the cap
+        max_staking_per_epoch: Option<Uint128>,
     },
     /// Add bridge tokens used to swap specific fee tokens to GRID (effectively declaring a swap route)
     UpdateBridges {
@@ -106,6 +122,33 @@ pub enum ExecuteMsg {
     ClaimOwnership {},
     /// Enables the distribution of current fees accrued in the contract over "blocks" number of blocks
     EnableRewards { blocks: u64 },
+    /// Reads the fee share configs of the specified pairs and stores them in the fee share registry
+    SyncFeeShares {
+        /// The pair contract addresses
+        pairs: Vec<String>,
+    },
+    /// Adds or removes fee share recipients approved by governance
+    /// ## Executor
+    /// Only the owner can execute this.
+    UpdateFeeShareWhitelist {
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    /// Disables fee sharing in a pair (via the factory) if its fee share recipient is not whitelisted
+    /// ## Executor
+    /// Only the owner can execute this.
+    DisableFeeShare {
+        /// The pair contract address
+        pair: String,
+    },
+    /// Adds or removes the swap venues used to convert specific fee tokens. A configured venue
+    /// takes precedence over bridges and direct pairs
+    /// ## Executor
+    /// Only the owner can execute this.
+    UpdateSwapVenues {
+        add: Option<Vec<SwapVenueInfo>>,
+        remove: Option<Vec<AssetInfo>>,
+    },
 }
 
 /// This structure describes the query functions available in the contract.
@@ -120,6 +163,43 @@ pub enum QueryMsg {
     Balances { assets: Vec<AssetInfo> },
     #[returns(Vec<(String, String)>)]
     Bridges {},
+    /// Returns the fee share configs stored in the registry
+    #[returns(Vec<PairFeeShareResponse>)]
+    FeeShares {
+        /// The pair address to start reading from
+        start_after: Option<String>,
+        /// The number of items to read
+        limit: Option<u32>,
+    },
+    /// Returns the list of whitelisted fee share recipients
+    #[returns(Vec<Addr>)]
+    FeeShareWhitelist {},
+    /// Returns the accounting of a distribution epoch. Returns the current epoch if none is specified
+    #[returns(EpochStats)]
+    Epoch { epoch: Option<u64> },
+    /// Returns the accounting of distribution epochs
+    #[returns(Vec<EpochStats>)]
+    Epochs {
+        /// The epoch to start reading from
+        start_after: Option<u64>,
+        /// The number of items to read
+        limit: Option<u32>,
+    },
+    /// Returns the GRID value of the fees collected from each pair
+    #[returns(Vec<PairCollectedResponse>)]
+    CollectedByPair {
+        /// The pair address to start reading from
+        start_after: Option<String>,
+        /// The number of items to read
+        limit: Option<u32>,
+    },
+    /// Returns the swap venues configured for fee tokens
+    #[returns(Vec<SwapVenueInfo>)]
+    SwapVenues {},
+    /// Returns the fees awaiting distribution in the Maker together with their routes to GRID,
+    /// the estimated GRID output and the USD value if the oracle contract is configured
+    #[returns(PendingFeesResponse)]
+    PendingFees {},
 }
 
 /// A custom struct that holds contract parameters and is used to retrieve them.
@@ -147,6 +227,14 @@ pub struct ConfigResponse {
     pub pre_upgrade_grid_amount: Uint128,
     /// Parameters that describe the second receiver of fees
     pub second_receiver_cfg: Option<SecondReceiverConfig>,
+    /// The length of a distribution epoch in seconds
+    pub epoch_length: Option<u64>,
+    /// The oracle contract used to value pending fees in USD
+    pub oracle_contract: Option<Addr>,
+    /// The maximum amount of GRID sent to xGRID stakers per epoch
+    pub max_staking_per_epoch: Option<Uint128>,
+    /// The GRID withheld from xGRID stakers by the per-epoch cap and due in the next epochs
+    pub staking_carryover: Uint128,
 }
 
 /// A custom struct used to return multiple asset balances.
@@ -155,6 +243,87 @@ pub struct BalancesResponse {
     pub balances: Vec<Asset>,
 }
 
+/// A custom struct used to return a fee token awaiting distribution in the Maker.
+#[cw_serde]
+pub struct PendingFee {
+    /// The fee token and its balance in the Maker
+    pub asset: Asset,
+    /// The route the fee token takes to GRID. Empty if the token can't be swapped
+    pub route: Vec<AssetInfo>,
+    /// The estimated amount of GRID received for the fee token
+    pub grid_amount: Option<Uint128>,
+    /// The USD value of the estimated GRID amount according to the oracle
+    pub usd_value: Option<Uint128>,
+}
+
+/// A custom struct used to return the fees awaiting distribution in the Maker.
+#[cw_serde]
+pub struct PendingFeesResponse {
+    /// The fee tokens awaiting distribution
+    pub fees: Vec<PendingFee>,
+    /// The estimated amount of GRID received for all fee tokens
+    pub total_grid_amount: Uint128,
+    /// The USD value of the total estimated GRID amount according to the oracle
+    pub total_usd_value: Option<Uint128>,
+}
+
+/// This structure stores the fee share config of a pair synced to the Maker.
+#[cw_serde]
+pub struct PairFeeShare {
+    /// The assets traded in the pair
+    pub asset_infos: Vec<AssetInfo>,
+    /// The fee share config of the pair
+    pub fee_share: FeeShareConfig,
+    /// The timestamp when the config was last synced
+    pub synced_at: u64,
+}
+
+/// A custom struct used to return a fee share config of a pair.
+#[cw_serde]
+pub struct PairFeeShareResponse {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The fee share config of the pair
+    pub info: PairFeeShare,
+    /// Whether the fee share recipient is whitelisted
+    pub whitelisted: bool,
+}
+
+/// A custom struct used to return the GRID value of the fees collected from a pair.
+#[cw_serde]
+pub struct PairCollectedResponse {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The total GRID value of the collected fees, valued at conversion time
+    pub grid_amount: Uint128,
+}
+
+/// This structure stores the accounting of a distribution epoch.
+#[cw_serde]
+pub struct EpochStats {
+    /// The epoch number
+    pub epoch: u64,
+    /// The timestamp when the epoch started
+    pub start_time: u64,
+    /// The timestamp when the epoch was rolled over. None for the current epoch
+    pub end_time: Option<u64>,
+    /// The fee tokens swapped to GRID during the epoch
+    pub collected: Vec<Asset>,
+    /// The total amount of GRID distributed at rollover
+    pub distributed: Uint128,
+    /// The amount of GRID sent to xGRID stakers
+    pub to_staking: Uint128,
+    /// The amount of GRID sent to the vxGRID fee distributor
+    pub to_governance: Uint128,
+    /// The amount of GRID sent to the second fee receiver
+    pub to_second_receiver: Uint128,
+    /// The amount of GRID left in the Maker and carried over to the next epoch
+    pub carryover: Uint128,
+    /// The part of `carryover` withheld from xGRID stakers by the per-epoch cap
+    #[serde(default)]
+    pub staking_carryover: Uint128,
+}
+
 /// This structure describes a migration message.
 #[cw_serde]
 pub struct MigrateMsg {
@@ -169,6 +338,35 @@ pub struct AssetWithLimit {
     pub info: AssetInfo,
     /// The amount of tokens to swap
     pub limit: Option<Uint128>,
+    /// The pair the fee tokens were collected from. The GRID value of the swapped tokens
+    /// is added to the collected fees of this pair
+    pub pair: Option<String>,
+}
+
+/// This enum describes the venues a fee token can be converted through.
+#[cw_serde]
+pub enum SwapVenue {
+    /// Swaps in a specific pair registered in the factory
+    Pair { contract_addr: String },
+    /// Swaps through the Gridiron router following the given operations
+    Router {
+        contract_addr: String,
+        operations: Vec<SwapOperation>,
+    },
+    /// Swaps through an external venue adapter (e.g. an orderbook adapter). The adapter must
+    /// implement the pair `Swap` message, the cw20 `Swap` hook and the `Simulation` query
+    External { contract_addr: String },
+}
+
+/// This structure describes the swap venue used to convert a fee token.
+#[cw_serde]
+pub struct SwapVenueInfo {
+    /// The fee token converted through the venue
+    pub asset_info: AssetInfo,
+    /// The asset the venue converts to. Either GRID or an asset which is converted to GRID further on
+    pub ask_asset_info: AssetInfo,
+    /// The venue itself
+    pub venue: SwapVenue,
 }
 
 /// This structure describes the parameters for updating the second receiver of fees.
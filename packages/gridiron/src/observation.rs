@@ -11,6 +11,12 @@ use crate::cosmwasm_ext::AbsDiff;
 
 /// Circular buffer size which stores observations
 pub const OBSERVATIONS_SIZE: u32 = 3000;
+/// Circular buffer size which stores fee growth snapshots (one year of hourly snapshots)
+pub const FEE_GROWTH_OBSERVATIONS_SIZE: u32 = 8760;
+/// Minimum interval in seconds between two consecutive fee growth snapshots
+pub const FEE_GROWTH_SNAPSHOT_PERIOD: u64 = 3600;
+/// Number of seconds in a year. Used to annualize fee growth
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
 
 /// Stores trade size observations. We use it in orderbook integration
 /// and derive prices for external contracts/users.
@@ -31,6 +37,32 @@ pub struct OracleObservation {
     pub price: Decimal,
 }
 
+/// This structure holds the realized volatility of the pool price over a period of time.
+#[cw_serde]
+pub struct VolatilityResponse {
+    /// Timestamp of the oldest observation within the window
+    pub from_ts: u64,
+    /// Timestamp of the newest observation within the window
+    pub to_ts: u64,
+    /// Number of observed price changes the volatility is derived from
+    pub samples: u32,
+    /// Annualized realized variance of the pool price
+    pub variance: Decimal256,
+    /// Annualized realized volatility (square root of the variance)
+    pub volatility: Decimal256,
+}
+
+/// Stores a snapshot of the pool's accumulated fee profit (xcp_profit).
+/// Used to derive realized LP fee APR over arbitrary periods.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub struct FeeGrowthObservation {
+    /// Timestamp of the snapshot
+    pub ts: u64,
+    /// Virtual price growth due to fees accrued since pool creation
+    pub xcp_profit: Decimal256,
+}
+
 /// Returns price observation at point that was 'seconds_ago' seconds ago.
 pub fn query_observation<C>(
     deps: Deps<C>,
@@ -116,6 +148,65 @@ where
     })
 }
 
+/// Returns the annualized realized volatility of the pool price using the observations stored
+/// within the last `window` seconds. Realized variance is the sum of squared relative price
+/// changes between consecutive observations scaled to a year.
+pub fn query_volatility<C>(
+    deps: Deps<C>,
+    env: Env,
+    observations: CircularBuffer<Observation>,
+    window: u64,
+) -> StdResult<VolatilityResponse>
+where
+    C: CustomQuery,
+{
+    let buffer = BufferManager::new(deps.storage, observations)?;
+    let target = env.block.time.seconds().saturating_sub(window);
+
+    // Read observations starting from the newest one until the window start is reached
+    let mut window_obs = vec![];
+    for i in 1..=buffer.capacity() {
+        match buffer.read_single(deps.storage, buffer.head() + buffer.capacity() - i)? {
+            Some(obs) if obs.ts >= target => window_obs.push(obs),
+            _ => break,
+        }
+    }
+
+    let (newest, oldest) = match (window_obs.first(), window_obs.last()) {
+        (Some(newest), Some(oldest)) if newest.ts > oldest.ts => (*newest, *oldest),
+        _ => {
+            return Err(StdError::generic_err(
+                "Not enough observations to calculate volatility",
+            ))
+        }
+    };
+
+    let mut sum_squared_returns = Decimal256::zero();
+    for pair in window_obs.windows(2) {
+        let next = Decimal256::from(pair[0].price);
+        let prev = Decimal256::from(pair[1].price);
+        let relative_change = next
+            .diff(prev)
+            .checked_div(prev)
+            .map_err(|_| StdError::generic_err(format!("Observed zero price at {}", pair[1].ts)))?;
+        sum_squared_returns =
+            sum_squared_returns.checked_add(relative_change.checked_mul(relative_change)?)?;
+    }
+
+    let variance = sum_squared_returns
+        .checked_mul(Decimal256::from_ratio(SECONDS_PER_YEAR, 1u8))?
+        .checked_div(Decimal256::from_ratio(newest.ts - oldest.ts, 1u8))
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(VolatilityResponse {
+        from_ts: oldest.ts,
+        to_ts: newest.ts,
+        samples: window_obs.len() as u32 - 1,
+        variance,
+        volatility: variance.sqrt(),
+    })
+}
+
 /// Performs binary search in circular buffer. Returns left and right bounds of target value.
 /// Either left or right bound may hit in target value.
 fn binary_search(
@@ -1,10 +1,21 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use crate::observation::OracleObservation;
-use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_schema::serde::Deserialize;
+use cosmwasm_schema::cw_serde;
 
-use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::asset::{Asset, AssetInfo, PairCreatedAt, PairInfo};
+use crate::generator::Cw20HookMsg as GeneratorHookMsg;
+use crate::pair_concentrated::StakerFeeShareConfig;
+use crate::querier::query_factory_config;
+use crate::router::SwapOperation;
 
-use cosmwasm_std::{Addr, Binary, Decimal, Decimal256, Uint128, Uint64};
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_std::{
+    attr, to_binary, wasm_execute, Addr, Api, Attribute, Binary, CosmosMsg, CustomMsg, Decimal,
+    Decimal256, QuerierWrapper, StdError, StdResult, Uint128, Uint256, Uint64,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use thiserror::Error;
 
 /// The default swap slippage
 pub const DEFAULT_SLIPPAGE: &str = "0.005";
@@ -16,9 +27,41 @@ pub const MAX_FEE_SHARE_BPS: u16 = 1000;
 /// Decimal precision for TWAP results
 pub const TWAP_PRECISION: u8 = 6;
 
+/// Storage key under which every Gridiron pair keeps its config.
+/// This key is a stable part of the pair API and can be used in raw queries.
+pub const PAIR_CONFIG_KEY: &str = "config";
+/// Storage namespace of the precisions map (AssetInfo as String -> precision) used by
+/// stableswap and concentrated pairs. This key is a stable part of the pair API.
+pub const PAIR_PRECISIONS_KEY: &str = "precisions";
+
+/// Interface of pairs which reject swaps and liquidity changes after a `deadline`
+pub const INTERFACE_DEADLINE: &str = "deadline";
+/// Interface of pairs which answer the versioned queries, e.g. [`QueryMsg::VersionedPair`]
+pub const INTERFACE_VERSIONED_QUERIES: &str = "versioned_queries";
+/// Interface of pairs which answer [`QueryMsg::Observe`] queries with oracle observations
+pub const INTERFACE_OBSERVATIONS: &str = "observations";
+/// Interface of pairs which check `min_lp_to_receive` when liquidity is provided
+pub const INTERFACE_MIN_LP_TO_RECEIVE: &str = "min_lp_to_receive";
+/// Interface of pairs whose price scale can be re-anchored once the pool is dormant
+pub const INTERFACE_REANCHOR: &str = "reanchor";
+/// Interface of pairs which give swap fee discounts to traders holding xGRID
+pub const INTERFACE_FEE_DISCOUNTS: &str = "fee_discounts";
+/// Interface of pairs which chain several swaps with [`ExecuteMsg::BatchSwap`]
+pub const INTERFACE_BATCH_SWAP: &str = "batch_swap";
+/// Interface of pairs which take a [`Referral`] fee from swap returns
+pub const INTERFACE_REFERRAL: &str = "referral";
+
+/// The maximum number of swaps which can be chained in a single batch swap
+pub const MAX_BATCH_SWAP_OPERATIONS: usize = 5;
+
 /// Min safe trading size (0.00001) to calculate a price. This value considers
 /// amount in decimal form with respective token precision.
+/// Use [`min_trade_size`] to get the threshold for an asset with a known precision.
 pub const MIN_TRADE_SIZE: Decimal256 = Decimal256::raw(10000000000000);
+/// The maximum swap floor an owner can set (1 whole token)
+pub const MAX_MIN_TRADE_FLOOR: Decimal256 = Decimal256::one();
+/// The maximum price move per block an owner can set (100%)
+pub const MAX_PRICE_MOVE_PER_BLOCK: Decimal = Decimal::one();
 
 /// This structure describes the parameters used for creating a contract.
 #[cw_serde]
@@ -31,6 +74,20 @@ pub struct InstantiateMsg {
     pub factory_addr: String,
     /// Optional binary serialised parameters for custom pool types
     pub init_params: Option<Binary>,
+    /// The address which requested the pair creation. Defaults to the message sender
+    pub creator: Option<String>,
+}
+
+/// Fields which are shared by the configs of all Gridiron pairs.
+/// Unknown fields are ignored thus it can be deserialized from any pair config fetched with
+/// a raw query by [`PAIR_CONFIG_KEY`].
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct PairConfigBase {
+    /// General pair information
+    pub pair_info: PairInfo,
+    /// The factory contract address
+    pub factory_addr: Addr,
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -48,15 +105,43 @@ pub enum ExecuteMsg {
         auto_stake: Option<bool>,
         /// The receiver of LP tokens
         receiver: Option<String>,
+        /// The timestamp (in seconds) after which the provision is rejected
+        deadline: Option<u64>,
+        /// The minimum amount of LP tokens the receiver must get from the provision
+        min_lp_to_receive: Option<Uint128>,
     },
-    /// Swap performs a swap in the pool
+    /// Swap performs a swap in the pool. The sender is the trader, relayed swaps on behalf of
+    /// another trader go through the router's
+    /// [`ExecuteSwapOperations`](crate::router::ExecuteMsg::ExecuteSwapOperations)
     Swap {
         offer_asset: Asset,
         ask_asset_info: Option<AssetInfo>,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        /// The minimum amount of ask tokens to receive. When set, it is checked instead of
+        /// `belief_price` and `max_spread`
+        min_receive: Option<Uint128>,
         to: Option<String>,
+        /// The timestamp (in seconds) after which the swap is rejected
+        deadline: Option<u64>,
+        /// The frontend which takes a fee from the return amount
+        referral: Option<Referral>,
     },
+    /// BatchSwap chains several swaps atomically. Every operation must be a
+    /// [`SwapOperation::GridSwap`] offering the asset returned by the previous one, and the first
+    /// one must offer the sent native asset. Hops this pair can't serve go through factory pairs
+    BatchSwap {
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of the last ask asset to receive
+        minimum_receive: Option<Uint128>,
+        /// The receiver of the last ask asset. Defaults to the sender
+        to: Option<String>,
+        /// The timestamp (in seconds) after which the batch swap is rejected
+        deadline: Option<u64>,
+    },
+    /// Adds assets to the pool reserves without minting LP tokens, so their value accrues to the
+    /// existing LPs. Only available in pairs with enabled donations
+    Donate { assets: Vec<Asset> },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
     /// ProposeNewOwner creates a proposal to change contract ownership.
@@ -81,58 +166,135 @@ pub enum Cw20HookMsg {
         ask_asset_info: Option<AssetInfo>,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        /// The minimum amount of ask tokens to receive. When set, it is checked instead of
+        /// `belief_price` and `max_spread`
+        min_receive: Option<Uint128>,
+        to: Option<String>,
+        /// The timestamp (in seconds) after which the swap is rejected
+        deadline: Option<u64>,
+        /// The frontend which takes a fee from the return amount
+        referral: Option<Referral>,
+    },
+    /// Chain several swaps starting with the sent token. See [`ExecuteMsg::BatchSwap`]
+    BatchSwap {
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of the last ask asset to receive
+        minimum_receive: Option<Uint128>,
+        /// The receiver of the last ask asset. Defaults to the sender
         to: Option<String>,
+        /// The timestamp (in seconds) after which the batch swap is rejected
+        deadline: Option<u64>,
     },
     /// Withdraw liquidity from the pool
     WithdrawLiquidity {
         #[serde(default)]
         assets: Vec<Asset>,
+        /// The timestamp (in seconds) after which the withdrawal is rejected
+        deadline: Option<u64>,
     },
 }
 
-/// This structure describes the query messages available in the contract.
-#[cw_serde]
-#[derive(QueryResponses)]
-pub enum QueryMsg {
-    /// Returns information about a pair in an object of type [`super::asset::PairInfo`].
-    #[returns(PairInfo)]
-    Pair {},
-    /// Returns information about a pool in an object of type [`PoolResponse`].
-    #[returns(PoolResponse)]
-    Pool {},
-    /// Returns contract configuration settings in a custom [`ConfigResponse`] structure.
-    #[returns(ConfigResponse)]
-    Config {},
-    /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
-    #[returns(Vec<Asset>)]
-    Share { amount: Uint128 },
-    /// Returns information about a swap simulation in a [`SimulationResponse`] object.
-    #[returns(SimulationResponse)]
-    Simulation {
-        offer_asset: Asset,
-        ask_asset_info: Option<AssetInfo>,
-    },
-    /// Returns information about cumulative prices in a [`ReverseSimulationResponse`] object.
-    #[returns(ReverseSimulationResponse)]
-    ReverseSimulation {
-        offer_asset_info: Option<AssetInfo>,
-        ask_asset: Asset,
-    },
-    /// Returns information about the cumulative prices in a [`CumulativePricesResponse`] object
-    #[returns(CumulativePricesResponse)]
-    CumulativePrices {},
-    /// Returns current D invariant in as a [`u128`] value
-    #[returns(Uint128)]
-    QueryComputeD {},
-    /// Returns the balance of the specified asset that was in the pool just preceeding the moment of the specified block height creation.
-    #[returns(Option<Uint128>)]
-    AssetBalanceAt {
-        asset_info: AssetInfo,
-        block_height: Uint64,
-    },
-    /// Query price from observations
-    #[returns(OracleObservation)]
-    Observe { seconds_ago: u64 },
+crate::versioned_queries! {
+    /// This structure describes the query messages available in the contract.
+    pub enum QueryMsg {
+        /// Returns information about a pair in an object of type [`super::asset::PairInfo`].
+        #[returns(PairInfo)]
+        Pair {},
+        /// Returns information about a pool in an object of type [`PoolResponse`].
+        #[returns(PoolResponse)]
+        Pool {},
+        /// Returns contract configuration settings in a custom [`ConfigResponse`] structure.
+        #[returns(ConfigResponse)]
+        Config {},
+        /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
+        #[returns(Vec<Asset>)]
+        Share { amount: Uint128 },
+        /// Returns the value of the specified amount of LP tokens denominated in one of the pool assets
+        #[returns(Asset)]
+        ShareValueIn {
+            /// The amount of LP tokens
+            amount: Uint128,
+            /// The pool asset in which the value is denominated
+            asset_info: AssetInfo,
+        },
+        /// Returns information about a swap simulation in a [`SimulationResponse`] object.
+        #[returns(SimulationResponse)]
+        Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        },
+        /// Returns information about cumulative prices in a [`ReverseSimulationResponse`] object.
+        #[returns(ReverseSimulationResponse)]
+        ReverseSimulation {
+            offer_asset_info: Option<AssetInfo>,
+            ask_asset: Asset,
+        },
+        /// Returns information about the cumulative prices in a [`CumulativePricesResponse`] object
+        #[returns(CumulativePricesResponse)]
+        CumulativePrices {},
+        /// Returns current D invariant in as a [`u128`] value
+        #[returns(Uint128)]
+        QueryComputeD {},
+        /// Returns the balance of the specified asset that was in the pool just preceeding the moment of the specified block height creation.
+        #[returns(Option<Uint128>)]
+        AssetBalanceAt {
+            asset_info: AssetInfo,
+            block_height: Uint64,
+        },
+        /// Returns the pool assets that an amount of LP tokens would have redeemed just preceding the moment of the
+        /// specified block height creation. Returns None if asset balances were not tracked at that height.
+        #[returns(Option<Vec<Asset>>)]
+        ShareAt {
+            /// The amount of LP tokens
+            amount: Uint128,
+            block_height: Uint64,
+        },
+        /// Query price from observations
+        #[returns(OracleObservation)]
+        Observe { seconds_ago: u64 },
+        /// Returns the LP token virtual price and its moving average. Only supported by stableswap pools.
+        #[returns(VirtualPriceResponse)]
+        VirtualPrice {},
+        /// Returns the rounding dust each pool asset has accumulated. Only supported by constant product pools.
+        #[returns(Vec<RoundingDust>)]
+        RoundingDust {},
+        /// Returns whether the pair implements an interface, e.g. [`INTERFACE_DEADLINE`]. Unknown
+        /// interfaces are not supported.
+        #[returns(bool)]
+        SupportsInterface { interface: String },
+    }
+
+    versioned {
+        /// Returns information about a pair wrapped into a [`Versioned`](crate::versioned::Versioned)
+        /// envelope. New clients should prefer the versioned queries to the bare variants, which
+        /// are kept for compatibility.
+        VersionedPair => Pair {} => PairInfo,
+        /// Returns information about a pool wrapped into a versioned envelope
+        VersionedPool => Pool {} => PoolResponse,
+        /// Returns contract configuration settings wrapped into a versioned envelope
+        VersionedConfig => Config {} => ConfigResponse,
+        /// Returns information about a swap simulation wrapped into a versioned envelope
+        VersionedSimulation => Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        } => SimulationResponse,
+    }
+}
+
+/// Defines the `supports_interface` function which answers the `SupportsInterface` query of a
+/// pair with the listed interfaces:
+///
+/// ```ignore
+/// gridiron::supported_interfaces!(INTERFACE_DEADLINE, INTERFACE_VERSIONED_QUERIES);
+/// ```
+#[macro_export]
+macro_rules! supported_interfaces {
+    ($($interface:expr),* $(,)?) => {
+        /// Returns whether the pair implements the interface with the given name.
+        pub fn supports_interface(interface: &str) -> bool {
+            [$($interface),*].iter().any(|supported: &&str| *supported == interface)
+        }
+    };
 }
 
 /// This struct is used to return a query result with the total amount of LP tokens and assets in a specific pool.
@@ -144,6 +306,27 @@ pub struct PoolResponse {
     pub total_share: Uint128,
 }
 
+/// This struct is used to return the LP token virtual price of a stableswap pool.
+#[cw_serde]
+pub struct VirtualPriceResponse {
+    /// The current virtual price (D invariant per LP token). Can be moved within a single block
+    pub virtual_price: Decimal256,
+    /// The exponential moving average of the virtual price. Only includes prices from previous blocks
+    pub virtual_price_ma: Decimal256,
+    /// The half-time of the moving average in seconds
+    pub ma_half_time: u64,
+}
+
+/// This struct is used to return the rounding dust accumulated by a pool asset.
+#[cw_serde]
+pub struct RoundingDust {
+    /// The pool asset
+    pub info: AssetInfo,
+    /// The fractional amount the pool kept by rounding swap returns, fees and withdrawals down.
+    /// The kept units are part of the pool reserves and therefore belong to the LPs
+    pub amount: Decimal256,
+}
+
 /// This struct is used to return a query result with the general contract configuration.
 #[cw_serde]
 pub struct ConfigResponse {
@@ -155,6 +338,311 @@ pub struct ConfigResponse {
     pub owner: Addr,
     /// The factory contract address
     pub factory_addr: Addr,
+    /// The address which requested the pair creation
+    pub creator: Option<Addr>,
+    /// The block at which the pair was created
+    pub created_at: Option<PairCreatedAt>,
+    /// The min trade sizes computed for every pool asset. Empty for pair types which don't
+    /// enforce them
+    #[serde(default)]
+    pub min_trade_sizes: Vec<MinTradeSize>,
+    /// The scheduled maintenance window, if any. None for pair types which don't support them
+    #[serde(default)]
+    pub maintenance_window: Option<MaintenanceWindow>,
+}
+
+/// The operations halted during a maintenance window.
+#[cw_serde]
+pub enum MaintenanceMode {
+    /// Swaps are rejected while liquidity can still be provided and withdrawn
+    PostOnly,
+    /// Liquidity provision and withdrawals are rejected while swaps are still enabled
+    SwapOnly,
+}
+
+impl Display for MaintenanceMode {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        match self {
+            MaintenanceMode::PostOnly => fmt.write_str("post_only"),
+            MaintenanceMode::SwapOnly => fmt.write_str("swap_only"),
+        }
+    }
+}
+
+/// This structure describes a maintenance window scheduled by the pair owner.
+#[cw_serde]
+pub struct MaintenanceWindow {
+    /// The operations halted during the window
+    pub mode: MaintenanceMode,
+    /// The timestamp (in seconds) at which the window starts
+    pub start: u64,
+    /// The timestamp (in seconds) at which the window ends. The pair operates normally from then on
+    pub end: u64,
+}
+
+impl MaintenanceWindow {
+    /// Returns whether the window can be scheduled at `now`: it must not be empty and must
+    /// end in the future.
+    pub fn is_valid(&self, now: u64) -> bool {
+        self.start < self.end && self.end > now
+    }
+
+    /// Returns whether the window is in progress at `now`.
+    pub fn is_active(&self, now: u64) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Returns whether swaps are halted at `now`.
+    pub fn halts_swaps(&self, now: u64) -> bool {
+        self.mode == MaintenanceMode::PostOnly && self.is_active(now)
+    }
+
+    /// Returns whether liquidity provision and withdrawals are halted at `now`.
+    pub fn halts_liquidity(&self, now: u64) -> bool {
+        self.mode == MaintenanceMode::SwapOnly && self.is_active(now)
+    }
+}
+
+/// This structure describes the min trade sizes of a pool asset in the asset's own units.
+#[cw_serde]
+pub struct MinTradeSize {
+    /// The pool asset
+    pub asset_info: AssetInfo,
+    /// Trades smaller than this amount don't update price observations
+    pub observation: Uint128,
+    /// Swaps offering less than this amount are rejected. Zero if no floor is set
+    pub swap: Uint128,
+}
+
+impl MinTradeSize {
+    /// Computes the min trade sizes of an asset with the given precision.
+    ///
+    /// * **floor** the swap floor in decimal form set for the pair, if any.
+    pub fn new(asset_info: AssetInfo, precision: u8, floor: Option<Decimal256>) -> StdResult<Self> {
+        let swap = match floor {
+            Some(floor) => to_raw_amount_ceil(floor.max(smallest_unit(precision)), precision)?,
+            None => Uint128::zero(),
+        };
+
+        Ok(Self {
+            asset_info,
+            observation: to_raw_amount_ceil(min_trade_size(precision), precision)?,
+            swap,
+        })
+    }
+}
+
+/// Returns the min trade size in decimal form for an asset with the given precision.
+/// [`MIN_TRADE_SIZE`] is raised to the smallest unit of the asset, so low precision assets are
+/// never compared against amounts they can't represent.
+pub fn min_trade_size(precision: u8) -> Decimal256 {
+    MIN_TRADE_SIZE.max(smallest_unit(precision))
+}
+
+/// Returns the smallest representable amount of an asset with the given precision.
+fn smallest_unit(precision: u8) -> Decimal256 {
+    Decimal256::from_ratio(1u8, Uint256::from(10u8).pow(precision.into()))
+}
+
+/// Converts an amount in decimal form to the asset's own units rounding up.
+fn to_raw_amount_ceil(amount: Decimal256, precision: u8) -> StdResult<Uint128> {
+    let denominator = Uint256::from(10u8).pow(amount.decimal_places());
+    let numerator = amount.atomics() * Uint256::from(10u8).pow(precision.into());
+    let mut raw = numerator / denominator;
+    if !(numerator % denominator).is_zero() {
+        raw += Uint256::one();
+    }
+
+    Ok(raw.try_into()?)
+}
+
+/// This enum describes the errors of the liquidity provision steps shared by all pair types
+#[derive(Error, Debug, PartialEq)]
+pub enum ProvideLiquidityError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Generator address is not set in factory. Cannot auto-stake")]
+    AutoStakeError {},
+
+    #[error("Provision mints {share} LP tokens, less than the minimum of {min_lp_to_receive}")]
+    MinLpToReceiveAssertion {
+        share: Uint128,
+        min_lp_to_receive: Uint128,
+    },
+}
+
+/// Mint LP tokens for a beneficiary and auto stake the tokens in the Generator contract (if auto staking is specified).
+///
+/// * **lp_token** LP token of the pair.
+///
+/// * **factory_addr** factory which holds the Generator address.
+///
+/// * **contract_address** address of the pair which receives the LP tokens before staking them.
+///
+/// * **recipient** LP token recipient.
+///
+/// * **amount** amount of LP tokens that will be minted for the recipient.
+///
+/// * **auto_stake** whether the newly minted LP tokens will be automatically staked in the Generator on behalf of the recipient.
+pub fn mint_liquidity_token_message(
+    querier: QuerierWrapper,
+    lp_token: &Addr,
+    factory_addr: &Addr,
+    contract_address: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+    auto_stake: bool,
+) -> Result<Vec<CosmosMsg>, ProvideLiquidityError> {
+    // If no auto-stake - just mint to recipient
+    if !auto_stake {
+        return Ok(vec![wasm_execute(
+            lp_token,
+            &Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount,
+            },
+            vec![],
+        )?
+        .into()]);
+    }
+
+    // Mint for the pair contract and stake into the Generator contract
+    let generator = query_factory_config(&querier, factory_addr)?
+        .generator_address
+        .ok_or(ProvideLiquidityError::AutoStakeError {})?;
+
+    Ok(vec![
+        wasm_execute(
+            lp_token,
+            &Cw20ExecuteMsg::Mint {
+                recipient: contract_address.to_string(),
+                amount,
+            },
+            vec![],
+        )?
+        .into(),
+        wasm_execute(
+            lp_token,
+            &Cw20ExecuteMsg::Send {
+                contract: generator.to_string(),
+                amount,
+                msg: to_binary(&GeneratorHookMsg::DepositFor(recipient.to_string()))?,
+            },
+            vec![],
+        )?
+        .into(),
+    ])
+}
+
+/// Checks that a provision mints at least **min_lp_to_receive** LP tokens (if specified).
+///
+/// * **share** amount of LP tokens minted for the receiver.
+pub fn check_min_lp_to_receive(
+    share: Uint128,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<(), ProvideLiquidityError> {
+    match min_lp_to_receive {
+        Some(min_lp_to_receive) if share < min_lp_to_receive => {
+            Err(ProvideLiquidityError::MinLpToReceiveAssertion {
+                share,
+                min_lp_to_receive,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The frontend which referred a swap. It takes a fee from the return amount in the ask asset.
+#[cw_serde]
+pub struct Referral {
+    /// The address which receives the referral fee
+    pub address: String,
+    /// The share (in bps) of the return amount taken as the referral fee. Capped by the
+    /// `max_referral_bps` set in the factory
+    pub bps: u16,
+}
+
+/// This enum describes the referral fee errors shared by all pair types
+#[derive(Error, Debug, PartialEq)]
+pub enum ReferralError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Referral fees are disabled in the factory")]
+    ReferralDisabled {},
+
+    #[error("Referral fee of {bps} bps exceeds the max of {max_referral_bps} bps")]
+    ReferralFeeTooHigh { bps: u16, max_referral_bps: u16 },
+}
+
+/// The referral fee taken from the return amount of a swap.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReferralFee {
+    /// The address which receives the fee. `None` if the swap has no referral
+    pub receiver: Option<Addr>,
+    /// The fee amount in the ask asset
+    pub amount: Uint128,
+}
+
+impl ReferralFee {
+    /// Validates the referral against the factory cap and calculates its fee.
+    ///
+    /// * **referral** the referral attached to the swap.
+    ///
+    /// * **max_referral_bps** the cap set in the factory. `None` if referral fees are disabled.
+    ///
+    /// * **return_amount** the amount of ask tokens returned by the pool after the commission.
+    pub fn new(
+        api: &dyn Api,
+        referral: Option<Referral>,
+        max_referral_bps: Option<u16>,
+        return_amount: Uint128,
+    ) -> Result<Self, ReferralError> {
+        let referral = match referral {
+            Some(referral) => referral,
+            None => return Ok(Self::default()),
+        };
+        let max_referral_bps = max_referral_bps.ok_or(ReferralError::ReferralDisabled {})?;
+        if referral.bps > max_referral_bps {
+            return Err(ReferralError::ReferralFeeTooHigh {
+                bps: referral.bps,
+                max_referral_bps,
+            });
+        }
+
+        Ok(Self {
+            receiver: Some(api.addr_validate(&referral.address)?),
+            amount: return_amount * Decimal::from_ratio(referral.bps, 10000u16),
+        })
+    }
+
+    /// Returns the message which sends the fee to the referrer, if there is any fee to send.
+    pub fn into_msg<T>(&self, ask_asset_info: &AssetInfo) -> StdResult<Option<CosmosMsg<T>>>
+    where
+        T: CustomMsg,
+    {
+        match &self.receiver {
+            Some(receiver) if !self.amount.is_zero() => Asset {
+                info: ask_asset_info.clone(),
+                amount: self.amount,
+            }
+            .into_msg(receiver)
+            .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the swap event attributes describing the fee.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        match &self.receiver {
+            Some(receiver) => vec![
+                attr("referral_address", receiver),
+                attr("referral_amount", self.amount),
+            ],
+            None => vec![],
+        }
+    }
 }
 
 /// Holds the configuration for fee sharing
@@ -166,6 +654,54 @@ pub struct FeeShareConfig {
     pub recipient: Addr,
 }
 
+/// The fee share settings which are a part of the `params` returned in [`ConfigResponse`] by every
+/// Gridiron pair type. Unknown fields are ignored.
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct PairFeeShareParams {
+    /// The config for swap fee sharing
+    pub fee_share: Option<FeeShareConfig>,
+    /// The config for routing swap fees to xGRID stakers
+    pub staker_fee_share: Option<StakerFeeShareConfig>,
+}
+
+/// The fees (in bps) a pair combines on a swap. Their sum is capped by the protocol-wide
+/// `max_combined_fee_bps` set in the factory.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CombinedFees {
+    /// The total fee charged on a swap
+    pub total_fee_bps: u16,
+    /// The fee shared with the fee share recipient
+    pub fee_share_bps: u16,
+    /// The highest of the buy and sell taxes
+    pub sale_tax_bps: u16,
+    /// The share of swap fees routed to xGRID stakers
+    pub staker_fee_share_bps: u16,
+}
+
+impl CombinedFees {
+    /// Returns the sum of the combined fees (in bps).
+    pub fn combined_fee_bps(&self) -> u32 {
+        [
+            self.total_fee_bps,
+            self.fee_share_bps,
+            self.sale_tax_bps,
+            self.staker_fee_share_bps,
+        ]
+        .iter()
+        .map(|&bps| u32::from(bps))
+        .sum()
+    }
+
+    /// Returns whether the fees exceed the cap after an update from `previous`. Updates which don't
+    /// raise the combined fee are allowed, so a pair above a lowered cap can still reduce its fees.
+    pub fn exceed_cap(&self, previous: &CombinedFees, max_combined_fee_bps: u16) -> bool {
+        let combined_fee_bps = self.combined_fee_bps();
+        combined_fee_bps > u32::from(max_combined_fee_bps)
+            && combined_fee_bps > previous.combined_fee_bps()
+    }
+}
+
 /// This structure holds the parameters that are returned from a swap simulation response
 #[cw_serde]
 pub struct SimulationResponse {
@@ -220,6 +756,12 @@ pub struct XYKPoolConfig {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The max relative move of the pool price within a single block, if limited
+    #[serde(default)]
+    pub max_price_move_per_block: Option<Decimal>,
+    /// Whether anyone can donate assets to the pool reserves
+    #[serde(default)]
+    pub donations_enabled: bool,
 }
 
 /// This enum stores the option available to enable asset balances tracking over blocks.
@@ -235,6 +777,30 @@ pub enum XYKPoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Sets the floor (in decimal form) below which swaps are rejected. `None` removes the floor.
+    UpdateMinTradeFloor {
+        min_trade_floor: Option<Decimal256>,
+    },
+    /// Restricts liquidity provision and swaps to the addresses approved by the allowlist contract.
+    /// `None` makes the pair permissionless.
+    UpdateAllowlist {
+        allowlist: Option<String>,
+    },
+    /// Schedules a maintenance window which halts swaps or liquidity operations between its start
+    /// and end. `None` cancels the scheduled window.
+    ScheduleMaintenance {
+        window: Option<MaintenanceWindow>,
+    },
+    /// Limits how far swaps can move the pool price within a single block, cumulative across
+    /// swaps (e.g. `0.05` for 5%). Swaps moving the price further are rejected. `None` removes
+    /// the limit.
+    UpdatePriceMoveLimit {
+        max_price_move_per_block: Option<Decimal>,
+    },
+    /// Enables or disables donations to the pool reserves.
+    UpdateDonations {
+        enabled: bool,
+    },
 }
 
 /// This structure holds stableswap pool parameters.
@@ -271,6 +837,27 @@ pub enum StablePoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Re-reads asset precisions from the coin registry and refreshes the values cached
+    /// at instantiation. The greatest precision (LP token decimals) must not change.
+    RefreshPrecisions {},
+    /// Updates the half-time (in seconds) of the virtual price moving average.
+    UpdateVirtualPriceMaHalfTime {
+        ma_half_time: u64,
+    },
+    /// Sets the floor (in decimal form) below which swaps are rejected. `None` removes the floor.
+    UpdateMinTradeFloor {
+        min_trade_floor: Option<Decimal256>,
+    },
+    /// Restricts liquidity provision and swaps to the addresses approved by the allowlist contract.
+    /// `None` makes the pair permissionless.
+    UpdateAllowlist {
+        allowlist: Option<String>,
+    },
+    /// Schedules a maintenance window which halts swaps or liquidity operations between its start
+    /// and end. `None` cancels the scheduled window.
+    ScheduleMaintenance {
+        window: Option<MaintenanceWindow>,
+    },
 }
 
 #[cfg(test)]
@@ -334,6 +921,70 @@ mod tests {
     #[test]
     fn check_empty_vec_deserialization() {
         let variant: Cw20HookMsg = from_slice(br#"{"withdraw_liquidity": {} }"#).unwrap();
-        assert_eq!(variant, Cw20HookMsg::WithdrawLiquidity { assets: vec![] });
+        assert_eq!(
+            variant,
+            Cw20HookMsg::WithdrawLiquidity {
+                assets: vec![],
+                deadline: None
+            }
+        );
+    }
+
+    #[test]
+    fn min_trade_size_respects_precision() {
+        let info = native_asset_info("uusd".to_string());
+
+        let size = MinTradeSize::new(info.clone(), 6, None).unwrap();
+        assert_eq!(size.observation, Uint128::new(10));
+        assert_eq!(size.swap, Uint128::zero());
+
+        // The threshold can't be smaller than one unit of the asset
+        let size = MinTradeSize::new(info.clone(), 2, None).unwrap();
+        assert_eq!(size.observation, Uint128::new(1));
+        assert_eq!(min_trade_size(2), Decimal256::percent(1));
+
+        let floor = Decimal256::from_ratio(1u8, 1000u16);
+        let size = MinTradeSize::new(info.clone(), 18, Some(floor)).unwrap();
+        assert_eq!(size.observation, Uint128::new(10_000_000_000_000));
+        assert_eq!(size.swap, Uint128::new(1_000_000_000_000_000));
+
+        let size = MinTradeSize::new(info, 0, Some(floor)).unwrap();
+        assert_eq!(size.swap, Uint128::new(1));
+    }
+
+    #[test]
+    fn combined_fees_cap() {
+        let previous = CombinedFees {
+            total_fee_bps: 30,
+            fee_share_bps: 100,
+            ..Default::default()
+        };
+        let fees = CombinedFees {
+            sale_tax_bps: 400,
+            ..previous.clone()
+        };
+        assert_eq!(fees.combined_fee_bps(), 530);
+
+        assert!(!fees.exceed_cap(&previous, 530));
+        assert!(fees.exceed_cap(&previous, 500));
+
+        // Reducing fees above the cap is allowed
+        assert!(!previous.exceed_cap(&fees, 100));
+        assert!(!fees.exceed_cap(&fees, 100));
+    }
+
+    #[test]
+    fn min_lp_to_receive() {
+        let share = Uint128::new(1000);
+
+        check_min_lp_to_receive(share, None).unwrap();
+        check_min_lp_to_receive(share, Some(share)).unwrap();
+        assert_eq!(
+            check_min_lp_to_receive(share, Some(Uint128::new(1001))).unwrap_err(),
+            ProvideLiquidityError::MinLpToReceiveAssertion {
+                share,
+                min_lp_to_receive: Uint128::new(1001),
+            }
+        );
     }
 }
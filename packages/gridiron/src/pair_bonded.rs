@@ -1,4 +1,4 @@
-use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_schema::cw_serde;
 
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::pair::{
@@ -38,13 +38,20 @@ pub enum ExecuteMsg {
         auto_stake: Option<bool>,
         /// The receiver of LP tokens
         receiver: Option<String>,
+        /// The timestamp (in seconds) after which the provision is rejected
+        deadline: Option<u64>,
     },
     /// Swap performs a swap in the pool
     Swap {
         offer_asset: Asset,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        /// The minimum amount of ask tokens to receive. When set, it is checked instead of
+        /// `belief_price` and `max_spread`
+        min_receive: Option<Uint128>,
         to: Option<String>,
+        /// The timestamp (in seconds) after which the swap is rejected
+        deadline: Option<u64>,
     },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
@@ -57,32 +64,51 @@ pub enum ExecuteMsg {
         receiver: Addr,
         /// Sender who initiated the transaction
         sender: Addr,
+        /// The minimum amount of ask tokens the receiver must get
+        min_receive: Option<Uint128>,
     },
 }
 
-/// This structure describes the query messages available in the contract.
-#[cw_serde]
-#[derive(QueryResponses)]
-pub enum QueryMsg {
-    /// Returns information about a pair in an object of type [`super::asset::PairInfo`].
-    #[returns(PairInfo)]
-    Pair {},
-    /// Returns information about a pool in an object of type [`PoolResponse`].
-    #[returns(PoolResponse)]
-    Pool {},
-    /// Returns contract configuration settings in a custom [`ConfigResponse`] structure.
-    #[returns(ConfigResponse)]
-    Config {},
-    /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
-    #[returns(Vec<Asset>)]
-    Share { amount: Uint128 },
-    /// Returns information about a swap simulation in a [`SimulationResponse`] object.
-    #[returns(SimulationResponse)]
-    Simulation { offer_asset: Asset },
-    /// Returns information about cumulative prices in a [`ReverseSimulationResponse`] object.
-    #[returns(ReverseSimulationResponse)]
-    ReverseSimulation { ask_asset: Asset },
-    /// Returns information about the cumulative prices in a [`CumulativePricesResponse`] object
-    #[returns(CumulativePricesResponse)]
-    CumulativePrices {},
+crate::versioned_queries! {
+    /// This structure describes the query messages available in the contract.
+    pub enum QueryMsg {
+        /// Returns information about a pair in an object of type [`super::asset::PairInfo`].
+        #[returns(PairInfo)]
+        Pair {},
+        /// Returns information about a pool in an object of type [`PoolResponse`].
+        #[returns(PoolResponse)]
+        Pool {},
+        /// Returns contract configuration settings in a custom [`ConfigResponse`] structure.
+        #[returns(ConfigResponse)]
+        Config {},
+        /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
+        #[returns(Vec<Asset>)]
+        Share { amount: Uint128 },
+        /// Returns information about a swap simulation in a [`SimulationResponse`] object.
+        #[returns(SimulationResponse)]
+        Simulation { offer_asset: Asset },
+        /// Returns information about cumulative prices in a [`ReverseSimulationResponse`] object.
+        #[returns(ReverseSimulationResponse)]
+        ReverseSimulation { ask_asset: Asset },
+        /// Returns information about the cumulative prices in a [`CumulativePricesResponse`] object
+        #[returns(CumulativePricesResponse)]
+        CumulativePrices {},
+        /// Returns whether the pair implements an interface, e.g.
+        /// [`INTERFACE_DEADLINE`](crate::pair::INTERFACE_DEADLINE)
+        #[returns(bool)]
+        SupportsInterface { interface: String },
+    }
+
+    versioned {
+        /// Returns information about a pair wrapped into a [`Versioned`](crate::versioned::Versioned)
+        /// envelope. New clients should prefer the versioned queries to the bare variants, which
+        /// are kept for compatibility.
+        VersionedPair => Pair {} => PairInfo,
+        /// Returns information about a pool wrapped into a versioned envelope
+        VersionedPool => Pool {} => PoolResponse,
+        /// Returns contract configuration settings wrapped into a versioned envelope
+        VersionedConfig => Config {} => ConfigResponse,
+        /// Returns information about a swap simulation wrapped into a versioned envelope
+        VersionedSimulation => Simulation { offer_asset: Asset } => SimulationResponse,
+    }
 }
@@ -1,14 +1,21 @@
-use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint64};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Decimal, Decimal256, Uint128, Uint64};
+use cw20::Cw20ReceiveMsg;
 
 use crate::asset::PairInfo;
 use crate::asset::{Asset, AssetInfo};
-use crate::observation::OracleObservation;
+use crate::observation::{OracleObservation, VolatilityResponse};
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, FeeShareConfig, PoolResponse,
-    ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, CumulativePricesResponse, FeeShareConfig, MaintenanceWindow, PoolResponse,
+    Referral, ReverseSimulationResponse, SimulationResponse,
 };
 
+/// The maximum share of the LP part of swap fees which can be routed to xGRID stakers (50%)
+pub const MAX_STAKER_FEE_SHARE_BPS: u16 = 5000;
+
+/// The maximum allowed minimum time between two stored price observations (1 hour)
+pub const MAX_OBSERVATION_GRANULARITY: u64 = 3600;
+
 /// This structure holds concentrated pool parameters.
 #[cw_serde]
 pub struct ConcentratedPoolParams {
@@ -40,6 +47,63 @@ pub struct ConcentratedPoolParams {
     pub fee_share: Option<FeeShareConfig>,
 }
 
+/// The minimum number of seconds without trades before a pool can be re-anchored (1 day)
+pub const MIN_REANCHOR_DORMANCY_PERIOD: u64 = 86400;
+
+/// This structure is extended version of [`crate::pair::ExecuteMsg`].
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// ProvideLiquidity allows someone to provide liquidity in the pool
+    ProvideLiquidity {
+        /// The assets available in the pool
+        assets: Vec<Asset>,
+        /// The slippage tolerance that allows liquidity provision only if the price in the pool doesn't move too much
+        slippage_tolerance: Option<Decimal>,
+        /// Determines whether the LP tokens minted for the user is auto_staked in the Generator contract
+        auto_stake: Option<bool>,
+        /// The receiver of LP tokens
+        receiver: Option<String>,
+        /// The timestamp (in seconds) after which the provision is rejected
+        deadline: Option<u64>,
+        /// The minimum amount of LP tokens the receiver must get from the provision
+        min_lp_to_receive: Option<Uint128>,
+    },
+    /// Swap performs a swap in the pool
+    Swap {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        /// The minimum amount of ask tokens to receive. When set, it is checked instead of
+        /// `belief_price` and `max_spread`
+        min_receive: Option<Uint128>,
+        to: Option<String>,
+        /// The timestamp (in seconds) after which the swap is rejected
+        deadline: Option<u64>,
+        /// The frontend which takes a fee from the return amount
+        referral: Option<Referral>,
+    },
+    /// Update the pair configuration
+    UpdateConfig { params: Binary },
+    /// ProposeNewOwner creates a proposal to change contract ownership.
+    /// The validity period for the proposal is set in the `expires_in` variable.
+    ProposeNewOwner {
+        /// Newly proposed contract owner
+        owner: String,
+        /// The date after which this proposal expires
+        expires_in: u64,
+    },
+    /// DropOwnershipProposal removes the existing offer to change contract ownership.
+    DropOwnershipProposal {},
+    /// Used to claim contract ownership.
+    ClaimOwnership {},
+    /// Permissionless endpoint which moves price_scale towards the price of the re-anchor oracle
+    /// once the pool had no trades for the configured dormancy period.
+    Reanchor {},
+}
+
 /// This structure holds concentrated pool parameters which can be changed immediately.
 #[cw_serde]
 pub struct UpdatePoolParams {
@@ -78,6 +142,79 @@ pub enum ConcentratedPoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Routes a share of the LP part of swap fees to xGRID stakers.
+    EnableStakerFeeShare {
+        /// The share of the LP part of swap fees in bps
+        staker_fee_share_bps: u16,
+    },
+    /// Stops routing swap fees to xGRID stakers
+    DisableStakerFeeShare {},
+    /// Sets the floor (in decimal form) below which swaps are rejected. `None` removes the floor.
+    UpdateMinTradeFloor {
+        min_trade_floor: Option<Decimal256>,
+    },
+    /// Restricts liquidity provision and swaps to the addresses approved by the allowlist contract.
+    /// `None` makes the pair permissionless.
+    UpdateAllowlist {
+        allowlist: Option<String>,
+    },
+    /// Schedules a maintenance window which halts swaps or liquidity operations between its start
+    /// and end. `None` cancels the scheduled window.
+    ScheduleMaintenance {
+        window: Option<MaintenanceWindow>,
+    },
+    /// Sets the minimum number of seconds between two stored price observations.
+    /// Zero stores an observation for every block with trades.
+    UpdateObservationGranularity {
+        granularity: u64,
+    },
+    /// Sets the parameters of price scale re-anchoring. `None` disables re-anchoring.
+    UpdateReanchorConfig {
+        reanchor_config: Option<ReanchorParams>,
+    },
+}
+
+/// This structure holds the parameters of price scale re-anchoring.
+#[cw_serde]
+pub struct ReanchorParams {
+    /// The contract answering [`crate::oracle::QueryMsg::Consult`] with the price of the 2nd asset
+    /// in the 1st one
+    pub oracle: String,
+    /// The minimum number of seconds without swaps or provisions before a re-anchor
+    pub dormancy_period: u64,
+    /// The minimum relative deviation of price_scale from the oracle price which allows a
+    /// re-anchor
+    pub deviation_threshold: Decimal,
+    /// The maximum relative change of price_scale per re-anchor
+    pub max_step: Decimal,
+}
+
+/// This structure stores the validated parameters of price scale re-anchoring.
+#[cw_serde]
+pub struct ReanchorConfig {
+    /// The contract answering [`crate::oracle::QueryMsg::Consult`] with the price of the 2nd asset
+    /// in the 1st one
+    pub oracle: Addr,
+    /// The minimum number of seconds without swaps or provisions before a re-anchor
+    pub dormancy_period: u64,
+    /// The minimum relative deviation of price_scale from the oracle price which allows a
+    /// re-anchor
+    pub deviation_threshold: Decimal,
+    /// The maximum relative change of price_scale per re-anchor
+    pub max_step: Decimal,
+}
+
+/// This structure describes the share of swap fees which is routed to xGRID stakers.
+#[cw_serde]
+pub struct StakerFeeShareConfig {
+    /// The share of the LP part of swap fees in bps
+    pub bps: u16,
+    /// The xGRID staking contract which receives fees paid in GRID
+    pub staking_contract: Addr,
+    /// The GRID token
+    pub grid_token: AssetInfo,
+    /// The Maker contract which receives fees paid in other assets and converts them to GRID
+    pub maker: Addr,
 }
 
 /// This structure stores a CL pool's configuration.
@@ -107,55 +244,127 @@ pub struct ConcentratedPoolConfig {
     pub track_asset_balances: bool,
     /// The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The config for routing swap fees to xGRID stakers
+    pub staker_fee_share: Option<StakerFeeShareConfig>,
+}
+
+crate::versioned_queries! {
+    /// This structure describes the query messages available in the contract.
+    pub enum QueryMsg {
+        /// Returns information about a pair
+        #[returns(PairInfo)]
+        Pair {},
+        /// Returns information about a pool
+        #[returns(PoolResponse)]
+        Pool {},
+        /// Returns contract configuration
+        #[returns(ConfigResponse)]
+        Config {},
+        /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
+        #[returns(Vec<Asset>)]
+        Share { amount: Uint128 },
+        /// Returns the value of the specified amount of LP tokens denominated in one of the pool assets
+        #[returns(Asset)]
+        ShareValueIn {
+            /// The amount of LP tokens
+            amount: Uint128,
+            /// The pool asset in which the value is denominated
+            asset_info: AssetInfo,
+        },
+        /// Returns information about a swap simulation
+        #[returns(SimulationResponse)]
+        Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        },
+        /// Returns information about a reverse swap simulation
+        #[returns(ReverseSimulationResponse)]
+        ReverseSimulation {
+            offer_asset_info: Option<AssetInfo>,
+            ask_asset: Asset,
+        },
+        /// Returns information about the cumulative prices
+        #[returns(CumulativePricesResponse)]
+        CumulativePrices {},
+        /// Returns current D invariant
+        #[returns(Decimal256)]
+        ComputeD {},
+        /// Query LP token virtual price
+        #[returns(Decimal256)]
+        LpPrice {},
+        /// Returns the balance of the specified asset that was in the pool just preceding the moment
+        /// of the specified block height creation.
+        #[returns(Option<Uint128>)]
+        AssetBalanceAt {
+            asset_info: AssetInfo,
+            block_height: Uint64,
+        },
+        /// Query price from observations
+        #[returns(OracleObservation)]
+        Observe { seconds_ago: u64 },
+        /// Returns realized LP fee growth between now and the moment 'seconds_ago' seconds ago
+        #[returns(FeeGrowthResponse)]
+        FeeGrowth { seconds_ago: u64 },
+        /// Returns the observation granularity and the time range covered by stored observations
+        #[returns(ObservationsInfoResponse)]
+        ObservationsInfo {},
+        /// Returns the annualized realized volatility of the pool price over the last `window` seconds
+        #[returns(VolatilityResponse)]
+        Volatility { window: u64 },
+        /// Returns the parameters of price scale re-anchoring if it is enabled
+        #[returns(Option<ReanchorConfig>)]
+        ReanchorConfig {},
+        /// Returns whether the pair implements an interface, e.g.
+        /// [`INTERFACE_REANCHOR`](crate::pair::INTERFACE_REANCHOR)
+        #[returns(bool)]
+        SupportsInterface { interface: String },
+    }
+
+    versioned {
+        /// Returns information about a pair wrapped into a [`Versioned`](crate::versioned::Versioned)
+        /// envelope. New clients should prefer the versioned queries to the bare variants, which
+        /// are kept for compatibility.
+        VersionedPair => Pair {} => PairInfo,
+        /// Returns information about a pool wrapped into a versioned envelope
+        VersionedPool => Pool {} => PoolResponse,
+        /// Returns contract configuration settings wrapped into a versioned envelope
+        VersionedConfig => Config {} => ConfigResponse,
+        /// Returns information about a swap simulation wrapped into a versioned envelope
+        VersionedSimulation => Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        } => SimulationResponse,
+    }
 }
 
-/// This structure describes the query messages available in the contract.
+/// This structure describes the state of the price observations buffer.
 #[cw_serde]
-#[derive(QueryResponses)]
-pub enum QueryMsg {
-    /// Returns information about a pair
-    #[returns(PairInfo)]
-    Pair {},
-    /// Returns information about a pool
-    #[returns(PoolResponse)]
-    Pool {},
-    /// Returns contract configuration
-    #[returns(ConfigResponse)]
-    Config {},
-    /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
-    #[returns(Vec<Asset>)]
-    Share { amount: Uint128 },
-    /// Returns information about a swap simulation
-    #[returns(SimulationResponse)]
-    Simulation {
-        offer_asset: Asset,
-        ask_asset_info: Option<AssetInfo>,
-    },
-    /// Returns information about a reverse swap simulation
-    #[returns(ReverseSimulationResponse)]
-    ReverseSimulation {
-        offer_asset_info: Option<AssetInfo>,
-        ask_asset: Asset,
-    },
-    /// Returns information about the cumulative prices
-    #[returns(CumulativePricesResponse)]
-    CumulativePrices {},
-    /// Returns current D invariant
-    #[returns(Decimal256)]
-    ComputeD {},
-    /// Query LP token virtual price
-    #[returns(Decimal256)]
-    LpPrice {},
-    /// Returns the balance of the specified asset that was in the pool just preceding the moment
-    /// of the specified block height creation.
-    #[returns(Option<Uint128>)]
-    AssetBalanceAt {
-        asset_info: AssetInfo,
-        block_height: Uint64,
-    },
-    /// Query price from observations
-    #[returns(OracleObservation)]
-    Observe { seconds_ago: u64 },
+pub struct ObservationsInfoResponse {
+    /// Minimum number of seconds between two stored observations
+    pub granularity: u64,
+    /// The maximum number of observations kept in the buffer
+    pub capacity: u32,
+    /// Timestamp of the oldest stored observation
+    pub oldest_ts: Option<u64>,
+    /// Timestamp of the newest stored observation
+    pub newest_ts: Option<u64>,
+    /// Time range currently covered by stored observations
+    pub covered_duration: u64,
+    /// The minimum time range covered by a full buffer (capacity * granularity)
+    pub full_buffer_duration: u64,
+}
+
+/// This structure holds realized LP fee growth over a period of time.
+#[cw_serde]
+pub struct FeeGrowthResponse {
+    /// Timestamp of the fee growth snapshot used as the starting point
+    pub from_ts: u64,
+    /// Current block timestamp
+    pub to_ts: u64,
+    /// Relative growth of xcp_profit over the period
+    pub growth: Decimal256,
+    /// Annualized fee growth (APR)
+    pub apr: Decimal256,
 }
 
 #[cw_serde]
@@ -1,5 +1,5 @@
-use crate::observation::OracleObservation;
-use cosmwasm_schema::{cw_serde, QueryResponses};
+use crate::observation::{OracleObservation, VolatilityResponse};
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Binary, Decimal, Decimal256, Uint128};
 use cw20::Cw20ReceiveMsg;
 
@@ -7,8 +7,8 @@ use crate::asset::PairInfo;
 use crate::asset::{Asset, AssetInfo};
 
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    ConfigResponse, CumulativePricesResponse, MaintenanceWindow, PoolResponse, Referral,
+    ReverseSimulationResponse, SimulationResponse,
 };
 use crate::pair_concentrated::{ConcentratedPoolParams, PromoteParams, UpdatePoolParams};
 
@@ -17,6 +17,34 @@ pub struct OrderbookConfig {
     pub market_id: String,
     pub orders_number: u8,
     pub min_trades_to_avg: u32,
+    /// Sides of the orderbook to deploy liquidity to. Default: both
+    pub sides: Option<OrderbookSides>,
+}
+
+/// This enum describes which sides of the orderbook the pool deploys liquidity to.
+/// Liquidity of a disabled side stays in the AMM.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum OrderbookSides {
+    /// Place both bids and asks
+    #[default]
+    Both,
+    /// Place only buy orders
+    BidsOnly,
+    /// Place only sell orders
+    AsksOnly,
+}
+
+impl OrderbookSides {
+    /// Returns true if buy orders are placed in the orderbook
+    pub fn bids_enabled(&self) -> bool {
+        matches!(self, OrderbookSides::Both | OrderbookSides::BidsOnly)
+    }
+
+    /// Returns true if sell orders are placed in the orderbook
+    pub fn asks_enabled(&self) -> bool {
+        matches!(self, OrderbookSides::Both | OrderbookSides::AsksOnly)
+    }
 }
 
 /// This structure holds concentrated pool parameters along with orderbook params specific for Injective.
@@ -41,6 +69,8 @@ pub enum ExecuteMsg {
         auto_stake: Option<bool>,
         /// The receiver of LP tokens
         receiver: Option<String>,
+        /// The timestamp (in seconds) after which the provision is rejected
+        deadline: Option<u64>,
     },
     /// Swap performs a swap in the pool
     Swap {
@@ -48,7 +78,14 @@ pub enum ExecuteMsg {
         ask_asset_info: Option<AssetInfo>,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        /// The minimum amount of ask tokens to receive. When set, it is checked instead of
+        /// `belief_price` and `max_spread`
+        min_receive: Option<Uint128>,
         to: Option<String>,
+        /// The timestamp (in seconds) after which the swap is rejected
+        deadline: Option<u64>,
+        /// The frontend which takes a fee from the return amount
+        referral: Option<Referral>,
     },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
@@ -72,48 +109,85 @@ pub enum ExecuteMsg {
     UpdateMarketTicks {},
 }
 
-/// This structure describes the query messages available in the contract.
-#[cw_serde]
-#[derive(QueryResponses)]
-pub enum QueryMsg {
-    /// Returns information about a pair
-    #[returns(PairInfo)]
-    Pair {},
-    /// Returns information about a pool
-    #[returns(PoolResponse)]
-    Pool {},
-    /// Returns contract configuration
-    #[returns(ConfigResponse)]
-    Config {},
-    /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
-    #[returns(Vec<Asset>)]
-    Share { amount: Uint128 },
-    /// Returns information about a swap simulation
-    #[returns(SimulationResponse)]
-    Simulation {
-        offer_asset: Asset,
-        ask_asset_info: Option<AssetInfo>,
-    },
-    /// Returns information about a reverse swap simulation
-    #[returns(ReverseSimulationResponse)]
-    ReverseSimulation {
-        offer_asset_info: Option<AssetInfo>,
-        ask_asset: Asset,
-    },
-    /// Returns information about the cumulative prices
-    #[returns(CumulativePricesResponse)]
-    CumulativePrices {},
-    /// Returns current D invariant
-    #[returns(Decimal256)]
-    ComputeD {},
-    /// Query LP token virtual price
-    #[returns(Decimal256)]
-    LpPrice {},
-    /// Query price from observations
-    #[returns(OracleObservation)]
-    Observe { seconds_ago: u64 },
-    #[returns(OrderbookStateResponse)]
-    OrderbookState {},
+crate::versioned_queries! {
+    /// This structure describes the query messages available in the contract.
+    pub enum QueryMsg {
+        /// Returns information about a pair
+        #[returns(PairInfo)]
+        Pair {},
+        /// Returns information about a pool
+        #[returns(PoolResponse)]
+        Pool {},
+        /// Returns contract configuration
+        #[returns(ConfigResponse)]
+        Config {},
+        /// Returns information about the share of the pool in a vector that contains objects of type [`Asset`].
+        #[returns(Vec<Asset>)]
+        Share { amount: Uint128 },
+        /// Returns the value of the specified amount of LP tokens denominated in one of the pool assets
+        #[returns(Asset)]
+        ShareValueIn {
+            /// The amount of LP tokens
+            amount: Uint128,
+            /// The pool asset in which the value is denominated
+            asset_info: AssetInfo,
+        },
+        /// Returns information about a swap simulation
+        #[returns(SimulationResponse)]
+        Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        },
+        /// Returns information about a reverse swap simulation
+        #[returns(ReverseSimulationResponse)]
+        ReverseSimulation {
+            offer_asset_info: Option<AssetInfo>,
+            ask_asset: Asset,
+        },
+        /// Returns information about the cumulative prices
+        #[returns(CumulativePricesResponse)]
+        CumulativePrices {},
+        /// Returns current D invariant
+        #[returns(Decimal256)]
+        ComputeD {},
+        /// Query LP token virtual price
+        #[returns(Decimal256)]
+        LpPrice {},
+        /// Query price from observations
+        #[returns(OracleObservation)]
+        Observe { seconds_ago: u64 },
+        /// Returns the annualized realized volatility of the pool price over the last `window` seconds
+        #[returns(VolatilityResponse)]
+        Volatility { window: u64 },
+        #[returns(OrderbookStateResponse)]
+        OrderbookState {},
+        /// Returns the cumulative realized PnL of the liquidity deployed to the orderbook
+        #[returns(OrderbookPnlResponse)]
+        OrderbookPnl {},
+        /// Returns whether the pair runs in the degraded AMM-only mode because exchange queries fail
+        #[returns(OrderbookStatusResponse)]
+        OrderbookStatus {},
+        /// Returns whether the pair implements an interface, e.g.
+        /// [`INTERFACE_DEADLINE`](crate::pair::INTERFACE_DEADLINE)
+        #[returns(bool)]
+        SupportsInterface { interface: String },
+    }
+
+    versioned {
+        /// Returns information about a pair wrapped into a [`Versioned`](crate::versioned::Versioned)
+        /// envelope. New clients should prefer the versioned queries to the bare variants, which
+        /// are kept for compatibility.
+        VersionedPair => Pair {} => PairInfo,
+        /// Returns information about a pool wrapped into a versioned envelope
+        VersionedPool => Pool {} => PoolResponse,
+        /// Returns contract configuration settings wrapped into a versioned envelope
+        VersionedConfig => Config {} => ConfigResponse,
+        /// Returns information about a swap simulation wrapped into a versioned envelope
+        VersionedSimulation => Simulation {
+            offer_asset: Asset,
+            ask_asset_info: Option<AssetInfo>,
+        } => SimulationResponse,
+    }
 }
 
 #[cw_serde]
@@ -132,6 +206,8 @@ pub struct OrderbookStateResponse {
     pub last_balances: Vec<Asset>,
     /// Order number on each side of the orderbook
     pub orders_number: u8,
+    /// Sides of the orderbook the pool deploys liquidity to
+    pub sides: OrderbookSides,
     /// Minimum number of trades to accumulate average trade size.
     /// Orderbook integration will not be enabled until this number is reached.
     pub min_trades_to_avg: u32,
@@ -141,6 +217,39 @@ pub struct OrderbookStateResponse {
     pub enabled: bool,
 }
 
+/// Cumulative realized PnL of the liquidity deployed to the orderbook. It is tracked separately
+/// from the AMM fees. All values are denominated in the base asset (the first pool asset).
+#[cw_serde]
+pub struct OrderbookPnlResponse {
+    /// Number of processed orderbook fills
+    pub fills: u64,
+    /// Total base asset amount traded through the orderbook
+    pub base_volume: Uint128,
+    /// Total quote asset amount traded through the orderbook
+    pub quote_volume: Uint128,
+    /// Spread captured by fills against the pool price right before them, net of maker fees
+    pub fees_earned: Decimal256,
+    /// Spread lost by fills which happened at a worse price than the pool price
+    pub fees_lost: Decimal256,
+    /// Value gained because the pool price moved in favor of a fill until the next one
+    pub adverse_selection_gain: Decimal256,
+    /// Value lost because the pool price moved against a fill until the next one
+    pub adverse_selection_loss: Decimal256,
+}
+
+/// While Injective exchange queries fail the pair runs in a degraded AMM-only mode. Swaps and
+/// provides use the subaccount balances of the last reconciliation and the orderbook isn't
+/// reconciled until the queries work again.
+#[cw_serde]
+pub struct OrderbookStatusResponse {
+    /// Whether the pair runs in the degraded AMM-only mode
+    pub degraded: bool,
+    /// The timestamp (in seconds) when the pair entered the degraded mode
+    pub degraded_since: Option<u64>,
+    /// The error of the last failed exchange query. It is kept after the pair recovers
+    pub last_error: Option<String>,
+}
+
 #[cw_serde]
 pub enum MigrateMsg {
     MigrateToOrderbook { params: OrderbookConfig },
@@ -157,5 +266,16 @@ pub enum ConcentratedObPoolUpdateParams {
     /// Stops Amp and Gamma update and stores current values.
     StopChangingAmpGamma {},
     /// Update orderbook params.
-    UpdateOrderbookParams { orders_number: u8 },
+    UpdateOrderbookParams {
+        orders_number: Option<u8>,
+        sides: Option<OrderbookSides>,
+    },
+    /// Sets the floor (in decimal form) below which swaps are rejected. `None` removes the floor.
+    UpdateMinTradeFloor { min_trade_floor: Option<Decimal256> },
+    /// Restricts liquidity provision and swaps to the addresses approved by the allowlist contract.
+    /// `None` makes the pair permissionless.
+    UpdateAllowlist { allowlist: Option<String> },
+    /// Schedules a maintenance window which halts swaps or liquidity operations between its start
+    /// and end. `None` cancels the scheduled window.
+    ScheduleMaintenance { window: Option<MaintenanceWindow> },
 }
@@ -0,0 +1,101 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+
+use crate::asset::AssetInfo;
+use crate::pair::FeeShareConfig;
+
+/// The maximum buy or sell tax allowed, 10%
+pub const MAX_SALE_TAX_BPS: u16 = 1000;
+
+/// The name of the sale tax pair type registered in the factory as [`crate::factory::PairType::Custom`]
+pub const SALE_TAX_PAIR_TYPE: &str = "xyk_sale_tax";
+
+/// This structure holds the sale tax pool parameters.
+#[cw_serde]
+pub struct SaleTaxInitParams {
+    /// Whether asset balances are tracked over blocks or not.
+    pub track_asset_balances: Option<bool>,
+    /// The taxed asset. It must be one of the pair assets
+    pub tax_asset: AssetInfo,
+    /// The tax (in bps) charged when the taxed asset is bought from the pool
+    pub buy_tax_bps: u16,
+    /// The tax (in bps) charged when the taxed asset is sold to the pool
+    pub sell_tax_bps: u16,
+    /// The address which receives the collected taxes
+    pub tax_recipient: String,
+    /// The address which can update the tax config
+    pub tax_config_admin: String,
+}
+
+/// This structure stores the sale tax configuration.
+#[cw_serde]
+pub struct SaleTaxConfig {
+    /// The taxed asset
+    pub tax_asset: AssetInfo,
+    /// The tax (in bps) charged when the taxed asset is bought from the pool
+    pub buy_tax_bps: u16,
+    /// The tax (in bps) charged when the taxed asset is sold to the pool
+    pub sell_tax_bps: u16,
+    /// The address which receives the collected taxes
+    pub tax_recipient: Addr,
+    /// The address which can update the tax config
+    pub tax_config_admin: Addr,
+}
+
+/// This structure stores a sale tax pool's configuration.
+#[cw_serde]
+pub struct SaleTaxPoolConfig {
+    /// Whether asset balances are tracked over blocks or not.
+    pub track_asset_balances: bool,
+    /// The config for swap fee sharing
+    pub fee_share: Option<FeeShareConfig>,
+    /// The sale tax config
+    pub tax_config: SaleTaxConfig,
+}
+
+/// This enum stores the options available to update the sale tax config.
+/// Other updates are done with [`crate::pair::XYKPoolUpdateParams`].
+#[cw_serde]
+pub enum SaleTaxUpdateParams {
+    /// Updates the tax config.
+    /// ## Executor
+    /// Only the tax config admin can execute this.
+    UpdateTaxConfig {
+        buy_tax_bps: Option<u16>,
+        sell_tax_bps: Option<u16>,
+        tax_recipient: Option<String>,
+        tax_config_admin: Option<String>,
+    },
+}
+
+/// The side of a swap the sale tax was charged on.
+#[cw_serde]
+pub enum SaleTaxSide {
+    /// The taxed asset was bought, the tax is deducted from the return amount
+    Buy,
+    /// The taxed asset was sold, the tax is deducted from the offer amount
+    Sell,
+}
+
+impl SaleTaxConfig {
+    /// Returns the side and the tax rate (in bps) for a swap of `offer_asset` into `ask_asset`.
+    pub fn tax_for_swap(
+        &self,
+        offer_asset: &AssetInfo,
+        ask_asset: &AssetInfo,
+    ) -> Option<(SaleTaxSide, u16)> {
+        if self.tax_asset.equal(offer_asset) && self.sell_tax_bps > 0 {
+            Some((SaleTaxSide::Sell, self.sell_tax_bps))
+        } else if self.tax_asset.equal(ask_asset) && self.buy_tax_bps > 0 {
+            Some((SaleTaxSide::Buy, self.buy_tax_bps))
+        } else {
+            None
+        }
+    }
+}
+
+/// Splits `amount` into the amount left after tax and the tax itself.
+pub fn split_sale_tax(amount: Uint128, tax_bps: u16) -> (Uint128, Uint128) {
+    let tax = amount.multiply_ratio(tax_bps, 10000u16);
+    (amount - tax, tax)
+}
@@ -0,0 +1,143 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{wasm_execute, Addr, CosmosMsg, Decimal, StdResult, Uint128};
+
+use crate::asset::{Asset, AssetInfo};
+
+/// The default pagination limit for the leaderboard and LP rates
+pub const DEFAULT_LIMIT: u32 = 10;
+/// The maximum pagination limit for the leaderboard and LP rates
+pub const MAX_LIMIT: u32 = 50;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address allowed to change the point rates
+    pub owner: String,
+    /// The factory whose pairs and generator report activity to the contract
+    pub factory: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Sets the points accrued per unit of an offer asset swapped in a pair. A zero rate removes
+    /// the asset.
+    UpdateSwapRates { rates: Vec<(AssetInfo, Decimal)> },
+    /// Sets the points accrued per second for every LP token staked in the generator. A zero rate
+    /// stops the accrual for the LP token.
+    UpdateLpRates { rates: Vec<(String, Decimal)> },
+    /// Reports a swap. Only pairs registered in the factory can send this message.
+    SwapHook {
+        /// The address which receives the ask asset of the swap
+        receiver: String,
+        /// The asset sent to the pool
+        offer_asset: Asset,
+    },
+    /// Reports the new LP position of a user. Only the generator set in the factory can send this
+    /// message.
+    LpHook {
+        user: String,
+        lp_token: String,
+        /// The amount of LP tokens the user has staked after the change
+        amount: Uint128,
+    },
+    /// ProposeNewOwner creates a proposal to change contract ownership.
+    /// The validity period for the proposal is set in the `expires_in` variable.
+    ProposeNewOwner {
+        /// Newly proposed contract owner
+        owner: String,
+        /// The date after which this proposal expires
+        expires_in: u64,
+    },
+    /// DropOwnershipProposal removes the existing offer to change contract ownership.
+    DropOwnershipProposal {},
+    /// Used to claim contract ownership.
+    ClaimOwnership {},
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns the LP tokens which accrue points together with their rates
+    #[returns(Vec<(Addr, Decimal)>)]
+    LpRates {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the points of an address including the ones accrued by its LP positions since
+    /// their last update
+    #[returns(PointsResponse)]
+    Points { address: String },
+    /// Returns the addresses with the most points, highest first. Points accrued by LP positions
+    /// count once the generator reports a change of the position.
+    #[returns(Vec<PointsResponse>)]
+    Leaderboard {
+        /// The last address of the previous page
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure stores the main parameters of the contract.
+#[cw_serde]
+pub struct Config {
+    /// Address allowed to change the point rates
+    pub owner: Addr,
+    /// The factory whose pairs and generator report activity to the contract
+    pub factory: Addr,
+    /// The points accrued per unit of an offer asset swapped
+    pub swap_rates: Vec<(AssetInfo, Decimal)>,
+}
+
+/// This structure holds the points of an address.
+#[cw_serde]
+pub struct PointsResponse {
+    pub address: Addr,
+    pub points: Uint128,
+}
+
+/// Returns the message which reports a swap to the points contract.
+///
+/// * **receiver** address which receives the ask asset.
+///
+/// * **offer_asset** asset sent to the pool.
+pub fn swap_hook_msg(
+    points_contract: &Addr,
+    receiver: &Addr,
+    offer_asset: &Asset,
+) -> StdResult<CosmosMsg> {
+    Ok(wasm_execute(
+        points_contract,
+        &ExecuteMsg::SwapHook {
+            receiver: receiver.to_string(),
+            offer_asset: offer_asset.clone(),
+        },
+        vec![],
+    )?
+    .into())
+}
+
+/// Returns the message which reports the new LP position of a user to the points contract.
+///
+/// * **amount** amount of LP tokens the user has staked after the change.
+pub fn lp_hook_msg(
+    points_contract: &Addr,
+    user: &Addr,
+    lp_token: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(wasm_execute(
+        points_contract,
+        &ExecuteMsg::LpHook {
+            user: user.to_string(),
+            lp_token: lp_token.to_string(),
+            amount,
+        },
+        vec![],
+    )?
+    .into())
+}
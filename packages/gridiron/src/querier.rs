@@ -1,13 +1,19 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::factory::{
-    Config as FactoryConfig, FeeInfoResponse, PairType, PairsResponse, QueryMsg as FactoryQueryMsg,
+    Config as FactoryConfig, FeeDiscounts, FeeInfoResponse, PairType, PairsResponse,
+    QueryMsg as FactoryQueryMsg,
+};
+use crate::pair::{
+    PairConfigBase, QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse,
+    PAIR_CONFIG_KEY, PAIR_PRECISIONS_KEY,
 };
-use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
 
+use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_std::{
     from_slice, Addr, AllBalanceResponse, BankQuery, Coin, CustomQuery, Decimal, QuerierWrapper,
     QueryRequest, StdError, StdResult, Uint128,
 };
+use cw_storage_plus::Map;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
@@ -159,6 +165,25 @@ pub struct FeeInfo {
     pub total_fee_rate: Decimal,
     /// The amount of fees sent to the Maker contract
     pub maker_fee_rate: Decimal,
+    /// The points contract which swaps are reported to
+    pub points_contract: Option<Addr>,
+    /// The swap fee discounts given to traders holding xGRID
+    pub fee_discounts: Option<FeeDiscounts>,
+    /// The cap (in bps) on the referral fee taken from swap returns
+    pub max_referral_bps: Option<u16>,
+}
+
+impl From<FeeInfoResponse> for FeeInfo {
+    fn from(res: FeeInfoResponse) -> Self {
+        Self {
+            fee_address: res.fee_address,
+            total_fee_rate: Decimal::from_ratio(res.total_fee_bps, 10000u16),
+            maker_fee_rate: Decimal::from_ratio(res.maker_fee_bps, 10000u16),
+            points_contract: res.points_contract,
+            fee_discounts: res.fee_discounts,
+            max_referral_bps: res.max_referral_bps,
+        }
+    }
 }
 
 /// Returns the fee information for a specific pair type.
@@ -175,11 +200,28 @@ where
     let res: FeeInfoResponse =
         querier.query_wasm_smart(factory_contract, &FactoryQueryMsg::FeeInfo { pair_type })?;
 
-    Ok(FeeInfo {
-        fee_address: res.fee_address,
-        total_fee_rate: Decimal::from_ratio(res.total_fee_bps, 10000u16),
-        maker_fee_rate: Decimal::from_ratio(res.maker_fee_bps, 10000u16),
-    })
+    Ok(res.into())
+}
+
+/// Returns the fee information for a specific pair including its fee override in the factory.
+///
+/// * **pair_contract** pair we query information for.
+pub fn query_pair_fee_info<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: impl Into<String>,
+) -> StdResult<FeeInfo>
+where
+    C: CustomQuery,
+{
+    let res: FeeInfoResponse = querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::PairFeeInfo {
+            pair_addr: pair_contract.into(),
+        },
+    )?;
+
+    Ok(res.into())
 }
 
 /// Accepts two tokens as input and returns a pair's information.
@@ -247,3 +289,82 @@ pub fn reverse_simulate(
         },
     )
 }
+
+/// Storage namespace of cw20 balances in cw20-base compatible token contracts.
+const CW20_BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+
+/// Returns the full pair config using a raw query by [`PAIR_CONFIG_KEY`].
+/// The caller is responsible for choosing the config type that matches the pair type.
+pub fn query_pair_config_raw<C, T>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+) -> StdResult<T>
+where
+    C: CustomQuery,
+    T: DeserializeOwned,
+{
+    if let Some(res) = querier.query_wasm_raw(pair_contract, PAIR_CONFIG_KEY.as_bytes())? {
+        from_slice(&res)
+    } else {
+        Err(StdError::generic_err("The pair config not found!"))
+    }
+}
+
+/// Returns the pair information using a raw query. Works with any Gridiron pair type.
+pub fn query_pair_info_raw<C>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+) -> StdResult<PairInfo>
+where
+    C: CustomQuery,
+{
+    let config: PairConfigBase = query_pair_config_raw(querier, pair_contract)?;
+
+    Ok(config.pair_info)
+}
+
+/// Returns the precision of the given asset stored in a stableswap or concentrated pair
+/// using a raw query. Returns None if the pair does not store precisions.
+pub fn query_pair_precision_raw<C>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+    asset_info: &AssetInfo,
+) -> StdResult<Option<u8>>
+where
+    C: CustomQuery,
+{
+    Map::<String, u8>::new(PAIR_PRECISIONS_KEY).query(
+        querier,
+        Addr::unchecked(pair_contract.into()),
+        asset_info.to_string(),
+    )
+}
+
+/// Returns the pool reserves without calling smart queries on the pair.
+/// Native balances are fetched via bank queries, cw20 balances are read with raw queries
+/// from cw20-base compatible token contracts.
+pub fn query_pair_reserves_raw<C>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+) -> StdResult<Vec<Asset>>
+where
+    C: CustomQuery,
+{
+    let pair_contract = Addr::unchecked(pair_contract.into());
+    let pair_info = query_pair_info_raw(querier, &pair_contract)?;
+
+    pair_info
+        .asset_infos
+        .into_iter()
+        .map(|info| {
+            let amount = match &info {
+                AssetInfo::NativeToken { denom } => query_balance(querier, &pair_contract, denom)?,
+                AssetInfo::Token { contract_addr } => CW20_BALANCES
+                    .query(querier, contract_addr.clone(), &pair_contract)?
+                    .unwrap_or_default(),
+            };
+
+            Ok(Asset { info, amount })
+        })
+        .collect()
+}
@@ -1,11 +1,22 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo};
 
 pub const MAX_SWAP_OPERATIONS: usize = 50;
+/// The maximum number of pools which can be passed to [`QueryMsg::OptimizeSplit`]
+pub const MAX_SPLIT_POOLS: usize = 10;
+/// The maximum number of parts an offer can be divided into by [`QueryMsg::OptimizeSplit`]
+pub const MAX_SPLIT_PARTS: u8 = 20;
+/// The maximum number of orders which can be executed by [`ExecuteMsg::ExecuteOrders`]
+pub const MAX_BATCH_ORDERS: usize = 20;
+/// The maximum length of the fee payer memo of [`ExecuteMsg::ExecuteSwapOperations`]
+pub const MAX_FEE_PAYER_MEMO_LENGTH: usize = 256;
+/// Intermediate route balances below this amount are not refunded to the trader and can be
+/// swept with [`ExecuteMsg::SweepDust`]
+pub const DUST_THRESHOLD: Uint128 = Uint128::new(1_000);
 
 /// This structure holds the parameters used for creating a contract.
 #[cw_serde]
@@ -42,6 +53,17 @@ impl SwapOperation {
             SwapOperation::GridSwap { ask_asset_info, .. } => ask_asset_info.clone(),
         }
     }
+
+    pub fn get_offer_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::NativeSwap { offer_denom, .. } => AssetInfo::NativeToken {
+                denom: offer_denom.clone(),
+            },
+            SwapOperation::GridSwap {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+        }
+    }
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -55,6 +77,17 @@ pub enum ExecuteMsg {
         minimum_receive: Option<Uint128>,
         to: Option<String>,
         max_spread: Option<Decimal>,
+        /// The max spread (in bps) allowed on every intermediate hop of a multi-hop route
+        max_intermediate_spread_bps: Option<u16>,
+        /// The timestamp (in seconds) after which the swap operations are rejected
+        deadline: Option<u64>,
+        /// The trader on whose behalf a relayer executes the swap. The trader must approve the
+        /// relayer with [`ExecuteMsg::ApproveRelayer`]. The recipient defaults to the trader.
+        /// Pairs don't support relayed swaps, so relayers go through the router even for a
+        /// single hop
+        trader: Option<String>,
+        /// A memo of the fee payer (e.g. a fee grant relayer) emitted in the swap event for attribution
+        fee_payer_memo: Option<String>,
     },
 
     /// Internal use
@@ -65,6 +98,53 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         single: bool,
     },
+    /// Adds the attached native tokens to the sender's escrow balance
+    DepositEscrow {},
+    /// Withdraws tokens from the sender's escrow balance
+    WithdrawEscrow { asset: Asset },
+    /// Authorizes keepers to swap the offer from the sender's escrow balance.
+    /// The order is executed at most once
+    AuthorizeOrder {
+        /// The asset and amount to swap from the escrow balance
+        offer: Asset,
+        /// The swap route. It must start with the offer asset
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of tokens to receive. This is the limit price of the order
+        minimum_receive: Uint128,
+        /// The recipient. Defaults to the sender
+        to: Option<String>,
+        /// Max spread
+        max_spread: Option<Decimal>,
+        /// The timestamp (in seconds) after which the order can't be executed anymore
+        deadline: Option<u64>,
+    },
+    /// Cancels an order of the sender. The escrow balance is not affected
+    CancelOrder { order_id: u64 },
+    /// Executes the specified orders one by one. A failing order doesn't affect the others and
+    /// stays available for execution. Expired orders are removed. Can be called by anyone
+    ExecuteOrders { order_ids: Vec<u64> },
+
+    /// Internal use
+    /// ExecuteOrder executes a single order from the escrow balance of its owner
+    ExecuteOrder { order_id: u64 },
+    /// Allows a relayer to execute swap operations on behalf of the sender. Approvals are kept by
+    /// the router and only apply to swaps executed through it
+    ApproveRelayer {
+        /// The relayer address
+        relayer: String,
+        /// The timestamp (in seconds) after which the approval expires. Never expires if not set
+        expires: Option<u64>,
+    },
+    /// Removes the approval of a relayer
+    RevokeRelayer { relayer: String },
+    /// Sends the router balances of the specified assets which are below [`DUST_THRESHOLD`] to the
+    /// Maker or to the sender. Can be called by anyone
+    SweepDust {
+        /// The assets to sweep
+        assets: Vec<AssetInfo>,
+        /// Whether the dust is sent to the Maker (the factory fee address) instead of the sender
+        to_maker: bool,
+    },
 }
 
 #[cw_serde]
@@ -83,7 +163,18 @@ pub enum Cw20HookMsg {
         to: Option<String>,
         /// Max spread
         max_spread: Option<Decimal>,
+        /// The max spread (in bps) allowed on every intermediate hop of a multi-hop route
+        max_intermediate_spread_bps: Option<u16>,
+        /// The timestamp (in seconds) after which the swap operations are rejected
+        deadline: Option<u64>,
+        /// The trader on whose behalf a relayer executes the swap. The trader must approve the
+        /// relayer with [`ExecuteMsg::ApproveRelayer`]. The recipient defaults to the trader
+        trader: Option<String>,
+        /// A memo of the fee payer (e.g. a fee grant relayer) emitted in the swap event for attribution
+        fee_payer_memo: Option<String>,
     },
+    /// Adds the received tokens to the sender's escrow balance
+    DepositEscrow {},
 }
 
 /// This structure describes the query messages available in the contract.
@@ -101,6 +192,27 @@ pub enum QueryMsg {
         /// The swap operations to perform, each swap involving a specific pool
         operations: Vec<SwapOperation>,
     },
+    /// OptimizeSplit splits an offer across several pools trading the same assets so that the
+    /// total return is maximized. The offer is cut into `parts` equal chunks and every chunk
+    /// goes to the pool with the best marginal return.
+    #[returns(OptimizeSplitResponse)]
+    OptimizeSplit {
+        /// The asset and amount to swap
+        offer: Asset,
+        /// Candidate pair contract addresses. All of them must hold the same assets
+        pools: Vec<String>,
+        /// The number of chunks the offer is divided into
+        parts: u8,
+    },
+    /// Returns the escrow balance of a user for the specified asset as a [`Uint128`] value
+    #[returns(Uint128)]
+    Escrow { user: String, asset_info: AssetInfo },
+    /// Returns an order which keepers can execute using an [`Order`] object
+    #[returns(Order)]
+    Order { order_id: u64 },
+    /// Returns the approval of a relayer by a trader, if any
+    #[returns(Option<RelayerApproval>)]
+    RelayerApproval { trader: String, relayer: String },
 }
 
 /// This structure describes a custom struct to return a query response containing the base contract configuration.
@@ -117,6 +229,52 @@ pub struct SimulateSwapOperationsResponse {
     pub amount: Uint128,
 }
 
+/// This structure describes the amount routed through a single pool by [`QueryMsg::OptimizeSplit`]
+#[cw_serde]
+pub struct SplitAllocation {
+    /// The pair contract address
+    pub pool: String,
+    /// The amount of offer tokens to swap in this pool
+    pub offer_amount: Uint128,
+    /// The simulated amount of ask tokens returned by this pool
+    pub return_amount: Uint128,
+}
+
+/// This structure describes a custom struct to return the result of a [`QueryMsg::OptimizeSplit`] query
+#[cw_serde]
+pub struct OptimizeSplitResponse {
+    /// Allocations in the same order as the requested pools. Unused pools have zero amounts
+    pub splits: Vec<SplitAllocation>,
+    /// The total simulated return across all pools
+    pub total_return: Uint128,
+}
+
+/// This structure describes an order which keepers can execute on behalf of its owner
+#[cw_serde]
+pub struct Order {
+    /// The user whose escrow balance funds the order
+    pub owner: Addr,
+    /// The asset and amount to swap
+    pub offer: Asset,
+    /// The swap route
+    pub operations: Vec<SwapOperation>,
+    /// The minimum amount of tokens to receive
+    pub minimum_receive: Uint128,
+    /// The recipient of the ask tokens
+    pub to: Addr,
+    /// Max spread
+    pub max_spread: Option<Decimal>,
+    /// The timestamp (in seconds) after which the order can't be executed anymore
+    pub deadline: Option<u64>,
+}
+
+/// This structure describes the approval of a relayer to execute swap operations on behalf of a trader
+#[cw_serde]
+pub struct RelayerApproval {
+    /// The timestamp (in seconds) after which the approval expires. Never expires if not set
+    pub expires: Option<u64>,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations.
 #[cw_serde]
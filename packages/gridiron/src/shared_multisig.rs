@@ -1,6 +1,6 @@
 use crate::asset::Asset;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{from_slice, Addr, CosmosMsg, Decimal, Empty, StdResult, Uint128};
+use cosmwasm_std::{from_slice, Addr, Binary, CosmosMsg, Decimal, Empty, StdResult, Uint128};
 use cw3::Vote;
 use cw_storage_plus::{Key, KeyDeserialize, Prefixer, PrimaryKey};
 use cw_utils::{Duration, Expiration, Threshold, ThresholdResponse};
@@ -267,6 +267,71 @@ pub enum QueryMsg {
     Vote { proposal_id: u64, voter: String },
     #[returns(cw3::VoteListResponse)]
     ListVotes { proposal_id: u64 },
+    /// Dry-runs proposal messages against the current chain state where possible and
+    /// reports which of them are expected to fail
+    #[returns(SimulateProposalResponse)]
+    SimulateProposal { proposal_id: u64 },
+    /// Returns the canonical hash of a proposal so signers can verify its content on air-gapped devices
+    #[returns(ProposalHashResponse)]
+    ProposalHash { proposal_id: u64 },
+    /// Returns open and passed proposals which expire within the specified duration from now
+    #[returns(cw3::ProposalListResponse)]
+    ExpiringProposals {
+        within: Duration,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// The canonical proposal content. Its JSON serialization (fields in declaration order, no
+/// whitespace) is hashed with SHA-256 to get the proposal hash.
+#[cw_serde]
+pub struct ProposalHashPayload {
+    /// The chain the multisig is deployed on
+    pub chain_id: String,
+    /// The multisig contract address
+    pub contract: String,
+    pub proposal_id: u64,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg<Empty>>,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct ProposalHashResponse {
+    pub proposal_id: u64,
+    /// Hex encoded SHA-256 hash of the payload
+    pub hash: String,
+    /// The canonical JSON payload the hash is computed from
+    pub payload: Binary,
+}
+
+/// Expected outcome of a single proposal message.
+#[cw_serde]
+pub enum MsgSimulationStatus {
+    /// All performed checks passed
+    Ok,
+    /// The message is expected to fail
+    WouldFail { reason: String },
+    /// The message can not be checked via the querier
+    Unknown,
+}
+
+#[cw_serde]
+pub struct MsgSimulationResult {
+    /// Index of the message in the proposal
+    pub index: u32,
+    pub status: MsgSimulationStatus,
+}
+
+#[cw_serde]
+pub struct SimulateProposalResponse {
+    pub proposal_id: u64,
+    /// False if at least one message is expected to fail
+    pub success: bool,
+    /// Per message simulation results
+    pub results: Vec<MsgSimulationResult>,
 }
 
 /// This structure describes a migration message.
@@ -1,6 +1,7 @@
+use crate::asset::{Asset, AssetInfo};
 use crate::xgrid_token::InstantiateMarketingInfo;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128, Uint64};
 use cw20::Cw20ReceiveMsg;
 
 /// This structure describes the parameters used for creating a contract.
@@ -14,6 +15,21 @@ pub struct InstantiateMsg {
     pub deposit_token_addr: String,
     /// the marketing info of type [`InstantiateMarketingInfo`]
     pub marketing: Option<InstantiateMarketingInfo>,
+    /// The asset distributed to xGRID holders in addition to GRID (e.g. USDC from protocol fees)
+    pub revenue_asset: Option<AssetInfo>,
+    /// Enables the redemption queue for leaving xGRID
+    #[serde(default)]
+    pub redemption: Option<RedemptionParams>,
+}
+
+/// This structure describes the parameters of the xGRID redemption queue.
+#[cw_serde]
+pub struct RedemptionParams {
+    /// The time (in seconds) after which a queued redemption can be claimed without a penalty
+    pub unbonding_period: u64,
+    /// The penalty for claiming a redemption right after it was queued. It decays linearly to
+    /// zero over the unbonding period
+    pub max_penalty: Decimal,
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -21,6 +37,29 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Receive receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
     Receive(Cw20ReceiveMsg),
+    /// Distributes the attached native revenue asset to xGRID holders pro-rata to their balances
+    /// at the start of the current block
+    DistributeRevenue {},
+    /// Claims the revenue accrued by the sender's xGRID. At most `limit` distributions are
+    /// processed per call, the rest can be claimed with subsequent calls.
+    ClaimRevenue {
+        limit: Option<u32>,
+        receiver: Option<String>,
+    },
+    /// Claims the GRID of a queued redemption. Before the end of the unbonding period the
+    /// redemption can only be claimed with `instant` set, which pays the current penalty. The
+    /// penalty stays in the staking pool for the remaining xGRID holders.
+    ClaimRedemption { id: u64, instant: bool },
+    /// Stakes GRID on behalf of several users in one call. The total amount is pulled from the
+    /// sender, which must have approved it, and xGRID is minted to each recipient at the same
+    /// exchange rate. Used by vaults to restake the GRID accrued by their users.
+    Compound {
+        /// The recipients of xGRID along with the amount of GRID staked for each of them
+        recipients: Vec<(String, Uint128)>,
+    },
+    /// Pauses or resumes new deposits (`Enter`, `EnterFor` and `Compound`) during incident
+    /// response. Only the owner can execute it. Leaving and claiming are never paused.
+    SetEnterPaused { paused: bool },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -34,6 +73,27 @@ pub enum QueryMsg {
     TotalShares {},
     #[returns(Uint128)]
     TotalDeposit {},
+    /// Returns the GRID/xGRID exchange rate that was in the staking pool just preceeding the moment of the specified block height creation.
+    /// The totals are recorded on every deposit and withdrawal, so GRID sent to the contract directly
+    /// is only reflected after the next deposit or withdrawal.
+    #[returns(Option<ExchangeRateResponse>)]
+    ExchangeRateAt { block_height: Uint64 },
+    /// Returns the revenue distribution totals and the cumulative revenue per xGRID
+    #[returns(RevenueInfoResponse)]
+    RevenueInfo {},
+    /// Returns the revenue an address can claim from at most `limit` distributions
+    #[returns(PendingRevenueResponse)]
+    PendingRevenue { address: String, limit: Option<u32> },
+    /// Returns the queued redemptions of an address along with their current penalties
+    #[returns(Vec<RedemptionResponse>)]
+    Redemptions {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns whether new deposits are paused
+    #[returns(bool)]
+    EnterPaused {},
 }
 
 #[cw_serde]
@@ -42,17 +102,97 @@ pub struct ConfigResponse {
     pub deposit_token_addr: Addr,
     /// The xGRID token address
     pub share_token_addr: Addr,
+    /// The asset distributed to xGRID holders in addition to GRID
+    #[serde(default)]
+    pub revenue_asset: Option<AssetInfo>,
+    /// The redemption queue parameters. `None` if xGRID is redeemed instantly
+    #[serde(default)]
+    pub redemption: Option<RedemptionParams>,
+}
+
+/// This structure describes the staking pool totals at a specific block height.
+#[cw_serde]
+pub struct StakingTotals {
+    /// The amount of GRID in the staking pool
+    pub total_deposit: Uint128,
+    /// The xGRID supply
+    pub total_shares: Uint128,
+}
+
+/// This structure describes the GRID/xGRID exchange rate at a specific block height.
+#[cw_serde]
+pub struct ExchangeRateResponse {
+    /// The amount of GRID in the staking pool
+    pub total_deposit: Uint128,
+    /// The xGRID supply
+    pub total_shares: Uint128,
+    /// The amount of GRID one xGRID can be exchanged for
+    pub exchange_rate: Decimal,
+}
+
+/// This structure describes the revenue distributed to xGRID holders.
+#[cw_serde]
+pub struct RevenueInfoResponse {
+    /// The revenue asset. `None` if revenue distribution is disabled
+    pub revenue_asset: Option<AssetInfo>,
+    /// The total amount of revenue distributed
+    pub total_distributed: Uint128,
+    /// The total amount of revenue claimed
+    pub total_claimed: Uint128,
+    /// The cumulative amount of revenue distributed per xGRID
+    pub revenue_per_share: Decimal256,
+    /// The number of distributions made
+    pub distributions: u64,
+}
+
+/// This structure describes the revenue an address can claim.
+#[cw_serde]
+pub struct PendingRevenueResponse {
+    /// The claimable revenue
+    pub pending: Asset,
+    /// The number of distributions which were not included in `pending` due to the limit
+    pub distributions_left: u64,
+}
+
+/// This structure describes a queued redemption.
+#[cw_serde]
+pub struct RedemptionResponse {
+    /// The redemption id
+    pub id: u64,
+    /// The amount of GRID locked at the exchange rate of the moment the redemption was queued
+    pub amount: Uint128,
+    /// The timestamp (in seconds) after which the redemption can be claimed without a penalty
+    pub end: u64,
+    /// The penalty for claiming the redemption instantly at the current block time
+    pub penalty: Decimal,
+    /// The amount of GRID received when claiming the redemption instantly
+    pub instant_amount: Uint128,
 }
 
 /// This structure describes a migration message.
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// Enables revenue distribution with the given asset. Can only be set once
+    pub revenue_asset: Option<AssetInfo>,
+    /// Enables the redemption queue or updates its parameters for new redemptions
+    #[serde(default)]
+    pub redemption: Option<RedemptionParams>,
+    /// Sets the address which can pause new deposits. Required when migrating from versions
+    /// which didn't store the owner
+    #[serde(default)]
+    pub owner: Option<String>,
+}
 
 /// This structure describes a CW20 hook message.
 #[cw_serde]
 pub enum Cw20HookMsg {
     /// Deposits GRID in exchange for xGRID
     Enter {},
-    /// Burns xGRID in exchange for GRID
+    /// Deposits GRID in exchange for xGRID which is minted to the recipient
+    EnterFor { recipient: String },
+    /// Burns xGRID in exchange for GRID. If the redemption queue is enabled, GRID is queued
+    /// and can be claimed after the unbonding period
     Leave {},
+    /// Distributes the sent CW20 revenue asset to xGRID holders
+    DistributeRevenue {},
 }
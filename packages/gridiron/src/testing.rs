@@ -233,6 +233,9 @@ fn query_gridiron_pair_contract() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         },
     )]);
 
@@ -271,6 +274,9 @@ fn test_format_lp_token_name() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            creator: None,
+            created_at: None,
+            allowlist: None,
         },
     )]);
 
@@ -0,0 +1,118 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// The version of the data in [`Versioned`] query responses. It is increased whenever the layout
+/// of a versioned response changes.
+pub const QUERY_RESPONSE_VERSION: u32 = 1;
+
+/// This structure wraps a query response together with the version of its layout.
+#[cw_serde]
+pub struct Versioned<T> {
+    /// The version of the layout of `data`
+    pub version: u32,
+    /// The query response
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    /// Wraps the query response with the current [`QUERY_RESPONSE_VERSION`].
+    pub fn new(data: T) -> Self {
+        Self {
+            version: QUERY_RESPONSE_VERSION,
+            data,
+        }
+    }
+
+    /// Returns the query response if it has the layout of the current [`QUERY_RESPONSE_VERSION`].
+    pub fn into_data(self) -> StdResult<T> {
+        if self.version != QUERY_RESPONSE_VERSION {
+            return Err(StdError::generic_err(format!(
+                "Unsupported query response version {}, expected {}",
+                self.version, QUERY_RESPONSE_VERSION
+            )));
+        }
+
+        Ok(self.data)
+    }
+}
+
+/// Defines a query enum together with versioned variants of some of its queries. Every versioned
+/// variant has the same fields as the legacy variant it wraps and returns its response wrapped
+/// into [`Versioned`], so both variants are answered from one definition.
+///
+/// ```ignore
+/// gridiron::versioned_queries! {
+///     pub enum QueryMsg {
+///         #[returns(PairInfo)]
+///         Pair {},
+///     }
+///
+///     versioned {
+///         /// Versioned variant of [`QueryMsg::Pair`]
+///         VersionedPair => Pair {} => PairInfo,
+///     }
+/// }
+/// ```
+///
+/// The generated `query_versioned` method answers a versioned query by running its legacy variant
+/// with the passed handler:
+///
+/// ```ignore
+/// msg @ QueryMsg::VersionedPair {} => msg.query_versioned(|msg| query(deps, env, msg)),
+/// ```
+#[macro_export]
+macro_rules! versioned_queries {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($legacy:tt)*
+        }
+
+        versioned {
+            $(
+                $(#[doc = $doc:literal])*
+                $versioned:ident => $variant:ident {
+                    $($field:ident: $ty:ty),* $(,)?
+                } => $response:ty
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[cosmwasm_schema::cw_serde]
+        #[derive(cosmwasm_schema::QueryResponses)]
+        $vis enum $name {
+            $($legacy)*
+            $(
+                $(#[doc = $doc])*
+                #[returns($crate::versioned::Versioned<$response>)]
+                $versioned { $($field: $ty),* },
+            )+
+        }
+
+        impl $name {
+            /// Answers a versioned query with the response of its legacy variant wrapped into a
+            /// versioned envelope. Other queries are answered by the handler as is.
+            ///
+            /// * **legacy_query** handler of the legacy queries.
+            pub fn query_versioned<F>(
+                self,
+                legacy_query: F,
+            ) -> cosmwasm_std::StdResult<cosmwasm_std::Binary>
+            where
+                F: FnOnce($name) -> cosmwasm_std::StdResult<cosmwasm_std::Binary>,
+            {
+                match self {
+                    $(
+                        $name::$versioned { $($field),* } => {
+                            let data: $response = cosmwasm_std::from_binary(&legacy_query(
+                                $name::$variant { $($field),* },
+                            )?)?;
+                            cosmwasm_std::to_binary(&$crate::versioned::Versioned::new(data))
+                        }
+                    )+
+                    msg => legacy_query(msg),
+                }
+            }
+        }
+    };
+}
@@ -1,6 +1,6 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo};
 use cosmwasm_std::{Addr, Order, Uint128};
 use cw20::Cw20ReceiveMsg;
 
@@ -11,6 +11,9 @@ pub struct InstantiateMsg {
     pub owner: String,
     /// [`AssetInfo`] of the token that's being vested
     pub vesting_token: AssetInfo,
+    /// The generator contract in which vested LP tokens are staked until they are claimed.
+    /// Can only be set if the vesting token is a CW20 LP token
+    pub generator: Option<String>,
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -23,12 +26,22 @@ pub enum ExecuteMsg {
         /// The amount of tokens to claim
         amount: Option<Uint128>,
     },
+    /// Sends the generator rewards accrued by the sender's staked vesting tokens to a recipient
+    ClaimRewards {
+        /// The address that receives the rewards
+        recipient: Option<String>,
+    },
     /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template
     Receive(Cw20ReceiveMsg),
     /// RegisterVestingAccounts registers vesting targets/accounts
     RegisterVestingAccounts {
         vesting_accounts: Vec<VestingAccount>,
     },
+    /// RegisterVestingStreams registers continuous vesting streams
+    RegisterVestingStreams { streams: Vec<VestingStream> },
+    /// Sends the tokens claimed by the sender to another address unless a claim specifies a recipient.
+    /// `None` removes the redirect
+    RedirectClaims { recipient: Option<String> },
     /// Withdraws from current active schedule. Setups a new schedule with the remaining amount.
     WithdrawFromActiveSchedule {
         /// The account from which tokens will be withdrawn
@@ -66,6 +79,37 @@ pub struct VestingAccount {
     pub schedules: Vec<VestingSchedule>,
 }
 
+/// This structure describes a continuous vesting stream. Tokens unlock every second between `start` and `end`.
+#[cw_serde]
+pub struct VestingStream {
+    /// The address that is getting tokens
+    pub address: String,
+    /// The start time of the stream
+    pub start: u64,
+    /// The end time of the stream
+    pub end: u64,
+    /// The total amount of tokens streamed
+    pub total: Uint128,
+}
+
+impl From<VestingStream> for VestingAccount {
+    fn from(stream: VestingStream) -> Self {
+        VestingAccount {
+            address: stream.address,
+            schedules: vec![VestingSchedule {
+                start_point: VestingSchedulePoint {
+                    time: stream.start,
+                    amount: Uint128::zero(),
+                },
+                end_point: Some(VestingSchedulePoint {
+                    time: stream.end,
+                    amount: stream.total,
+                }),
+            }],
+        }
+    }
+}
+
 /// This structure stores parameters for a batch of vesting schedules.
 #[cw_serde]
 pub struct VestingInfo {
@@ -117,6 +161,20 @@ pub enum QueryMsg {
     /// Timestamp returns the current timestamp
     #[returns(u64)]
     Timestamp {},
+    /// Returns the claims made by a specific address in ascending order using a vector of [`ClaimHistoryItem`] objects.
+    #[returns(Vec<ClaimHistoryItem>)]
+    ClaimHistory {
+        address: String,
+        /// The claim timestamp (in seconds) from which to start reading the history
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the generator rewards which can be claimed by a specific address
+    #[returns(Vec<Asset>)]
+    PendingRewards { address: String },
+    /// Returns the address which receives the tokens claimed by a specific address, if redirected
+    #[returns(Option<Addr>)]
+    ClaimRedirect { address: String },
 }
 
 /// This structure describes a custom struct used to return the contract configuration.
@@ -126,6 +184,8 @@ pub struct ConfigResponse {
     pub owner: Addr,
     /// [`AssetInfo`] of the token being vested
     pub vesting_token: AssetInfo,
+    /// The generator contract in which vested LP tokens are staked
+    pub generator: Option<Addr>,
 }
 
 /// This structure describes a custom struct used to return vesting data about a specific vesting target.
@@ -144,6 +204,17 @@ pub struct VestingAccountsResponse {
     pub vesting_accounts: Vec<VestingAccountResponse>,
 }
 
+/// This structure describes the tokens claimed by a vesting recipient at a specific timestamp.
+#[cw_serde]
+pub struct ClaimHistoryItem {
+    /// The claim timestamp (in seconds)
+    pub timestamp: u64,
+    /// The amount of tokens claimed at this timestamp
+    pub claimed_amount: Uint128,
+    /// The total amount of tokens claimed by the recipient up to and including this timestamp
+    pub total_claimed: Uint128,
+}
+
 /// This enum describes the types of sorting that can be applied to some piece of data
 #[cw_serde]
 pub enum OrderBy {
@@ -176,4 +247,6 @@ pub enum Cw20HookMsg {
     RegisterVestingAccounts {
         vesting_accounts: Vec<VestingAccount>,
     },
+    /// RegisterVestingStreams registers continuous vesting streams
+    RegisterVestingStreams { streams: Vec<VestingStream> },
 }
@@ -0,0 +1,79 @@
+use std::fmt::Debug;
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{Addr, Api, Coin, CustomQuery, Storage, Timestamp};
+use cw_multi_test::{
+    App, AppResponse, Bank, BankSudo, Distribution, Gov, Ibc, Module, Staking, SudoMsg, WasmKeeper,
+};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+/// The number of seconds a block takes, the same as in [`cw_multi_test::next_block`]
+pub const BLOCK_TIME_SECONDS: u64 = 5;
+
+/// Returns a deterministic address for the given name. The same name always resolves to the same
+/// address, so tests and mock builders can refer to accounts by name.
+pub fn named_address(name: &str) -> Addr {
+    Addr::unchecked(name.to_lowercase())
+}
+
+/// Returns a deterministic address for the `index`-th account with the given name prefix,
+/// e.g. `user0`, `user1`.
+pub fn indexed_address(prefix: &str, index: usize) -> Addr {
+    named_address(&format!("{prefix}{index}"))
+}
+
+/// Block and balance helpers for integration tests.
+pub trait MockAppExt {
+    /// Moves the chain `n` blocks forward, [`BLOCK_TIME_SECONDS`] per block.
+    fn next_blocks(&mut self, n: u64);
+
+    /// Moves the chain one block forward and `seconds` in time.
+    fn next_block_after(&mut self, seconds: u64);
+
+    /// Sets the block time to `seconds` since epoch without changing the height.
+    fn set_time(&mut self, seconds: u64);
+
+    /// Mints native coins to the given address.
+    fn fund(&mut self, addr: &Addr, coins: &[Coin]) -> AnyResult<AppResponse>;
+}
+
+impl<B, A, S, C, X, D, I, G> MockAppExt
+    for App<B, A, S, C, WasmKeeper<C::ExecT, C::QueryT>, X, D, I, G>
+where
+    B: Bank,
+    A: Api,
+    S: Storage,
+    C: Module,
+    X: Staking,
+    D: Distribution,
+    I: Ibc,
+    G: Gov,
+    C::ExecT: Clone + Debug + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    C::QueryT: CustomQuery + DeserializeOwned + 'static,
+{
+    fn next_blocks(&mut self, n: u64) {
+        self.update_block(|block| {
+            block.height += n;
+            block.time = block.time.plus_seconds(n * BLOCK_TIME_SECONDS);
+        });
+    }
+
+    fn next_block_after(&mut self, seconds: u64) {
+        self.update_block(|block| {
+            block.height += 1;
+            block.time = block.time.plus_seconds(seconds);
+        });
+    }
+
+    fn set_time(&mut self, seconds: u64) {
+        self.update_block(|block| block.time = Timestamp::from_seconds(seconds));
+    }
+
+    fn fund(&mut self, addr: &Addr, coins: &[Coin]) -> AnyResult<AppResponse> {
+        self.sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: addr.to_string(),
+            amount: coins.to_vec(),
+        }))
+    }
+}
@@ -115,6 +115,7 @@ where
                     generator_address: Some(address.to_string()),
                     whitelist_code_id: None,
                     coin_registry_address: None,
+                    max_combined_fee_bps: None,
                 },
                 &[],
             )
@@ -161,6 +162,8 @@ where
                     voting_escrow_delegation: None,
                     voting_escrow: None,
                     checkpoint_generator_limit: None,
+                    proxy_rewards_claim_interval: None,
+                    staking_contract: None,
                 },
                 &[],
             )
@@ -259,6 +262,39 @@ where
             .unwrap();
     }
 
+    /// Sets the proxy which holds the pool's staked LP tokens and pays 3rd party rewards
+    pub fn move_to_proxy(&mut self, lp_token: &Addr, proxy: &Addr) {
+        self.app
+            .borrow_mut()
+            .execute_contract(
+                gridiron_address(),
+                self.address.clone(),
+                &ExecuteMsg::MoveToProxy {
+                    lp_token: lp_token.to_string(),
+                    proxy: proxy.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    /// Attaches an additional reward proxy to the pool, e.g. for dual rewards on a concentrated pair
+    pub fn add_reward_proxy(&mut self, lp_token: &Addr, proxy: &Addr) {
+        self.app
+            .borrow_mut()
+            .execute_contract(
+                gridiron_address(),
+                self.address.clone(),
+                &ExecuteMsg::AddRewardProxy {
+                    lp_token: lp_token.to_string(),
+                    proxy: proxy.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    /// Returns the pending GRID and the pending rewards of every proxy attached to the pool
     pub fn pending_token(&self, lp_token: &Addr, user: &Addr) -> PendingTokenResponse {
         let res: PendingTokenResponse = self
             .app
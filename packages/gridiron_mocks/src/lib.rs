@@ -7,6 +7,7 @@ pub use cw_multi_test;
 use cw_multi_test::{App, Module, WasmKeeper};
 
 pub use {
+    app::{indexed_address, named_address, MockAppExt, BLOCK_TIME_SECONDS},
     coin_registry::{MockCoinRegistry, MockCoinRegistryBuilder},
     factory::{MockFactory, MockFactoryBuilder},
     generator::{MockGenerator, MockGeneratorBuilder},
@@ -19,6 +20,7 @@ pub use {
     xgrid::{MockXgrid, MockXgridBuilder},
 };
 
+pub mod app;
 pub mod coin_registry;
 pub mod factory;
 pub mod generator;
@@ -35,7 +37,7 @@ pub mod xgrid;
 pub const GRIDIRON: &str = "gridiron";
 
 pub fn gridiron_address() -> Addr {
-    Addr::unchecked(GRIDIRON)
+    named_address(GRIDIRON)
 }
 
 pub type WKApp<B, A, S, C, X, D, I, G> = Rc<
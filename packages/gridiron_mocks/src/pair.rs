@@ -149,6 +149,8 @@ where
                     slippage_tolerance,
                     auto_stake: Some(auto_stake),
                     receiver: receiver.into(),
+                    deadline: None,
+                    min_lp_to_receive: None,
                 },
                 &coins,
             )
@@ -6,12 +6,13 @@ use crate::{gridiron_address, WKApp, GRIDIRON};
 use gridiron::asset::{Asset, AssetInfo};
 use gridiron::pair::ExecuteMsg as PairExecuteMsg;
 use gridiron::shared_multisig::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, PoolType, ProvideParams, QueryMsg,
+    ConfigResponse, ExecuteMsg, InstantiateMsg, PoolType, ProposalHashResponse, ProvideParams,
+    QueryMsg, SimulateProposalResponse,
 };
 
 use cosmwasm_std::{Addr, Api, Coin, CosmosMsg, CustomQuery, Decimal, StdResult, Storage, Uint128};
 use cw20::{BalanceResponse, Cw20QueryMsg};
-use cw3::{ProposalResponse, Vote, VoteListResponse, VoteResponse};
+use cw3::{ProposalListResponse, ProposalResponse, Vote, VoteListResponse, VoteResponse};
 use cw_multi_test::{
     AppResponse, Bank, ContractWrapper, Distribution, Executor, Gov, Ibc, Module, Staking,
 };
@@ -250,6 +251,7 @@ where
                 slippage_tolerance,
                 auto_stake,
                 receiver,
+                deadline: None,
             },
             &[],
         )
@@ -346,7 +348,10 @@ where
             ask_asset_info,
             belief_price,
             max_spread,
+            min_receive: None,
             to,
+            deadline: None,
+            referral: None,
         };
 
         let send_funds = vec![Coin {
@@ -390,6 +395,31 @@ where
             .query_wasm_smart(self.address.clone(), &QueryMsg::Proposal { proposal_id })
     }
 
+    pub fn query_simulate_proposal(&self, proposal_id: u64) -> StdResult<SimulateProposalResponse> {
+        self.app.borrow().wrap().query_wasm_smart(
+            self.address.clone(),
+            &QueryMsg::SimulateProposal { proposal_id },
+        )
+    }
+
+    pub fn query_proposal_hash(&self, proposal_id: u64) -> StdResult<ProposalHashResponse> {
+        self.app.borrow().wrap().query_wasm_smart(
+            self.address.clone(),
+            &QueryMsg::ProposalHash { proposal_id },
+        )
+    }
+
+    pub fn query_expiring_proposals(&self, within: Duration) -> StdResult<ProposalListResponse> {
+        self.app.borrow().wrap().query_wasm_smart(
+            self.address.clone(),
+            &QueryMsg::ExpiringProposals {
+                within,
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
     pub fn query_native_balance(&self, account: Option<&str>, denom: &str) -> StdResult<Coin> {
         self.app
             .borrow()
@@ -93,6 +93,8 @@ where
                     marketing: None,
                     token_code_id,
                     deposit_token_addr: grid_token.address.to_string(),
+                    revenue_asset: None,
+                    redemption: None,
                 },
                 &[],
                 "Gridiron Staking",
@@ -83,6 +83,7 @@ where
                 &InstantiateMsg {
                     owner: GRIDIRON.to_owned(),
                     vesting_token: grid_token,
+                    generator: None,
                 },
                 &[],
                 "Gridiron Vesting",
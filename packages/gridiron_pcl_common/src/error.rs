@@ -1,6 +1,8 @@
-use cosmwasm_std::{Decimal, StdError};
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use thiserror::Error;
 
+use gridiron::asset::AssetInfoError;
+
 use crate::consts::MIN_AMP_CHANGING_TIME;
 
 /// This enum describes pair contract errors
@@ -9,6 +11,9 @@ pub enum PclError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    AssetInfo(#[from] AssetInfoError),
+
     #[error("{0} parameter must be greater than {1} and less than or equal to {2}")]
     IncorrectPoolParam(String, String, String),
 
@@ -35,6 +40,12 @@ pub enum PclError {
     #[error("Operation exceeds max spread limit")]
     MaxSpreadAssertion {},
 
+    #[error("Swap return {return_amount} is less than min_receive {min_receive}")]
+    MinReceiveAssertion {
+        min_receive: Uint128,
+        return_amount: Uint128,
+    },
+
     #[error("Provided spread amount exceeds allowed limit")]
     AllowedSpreadAssertion {},
 
@@ -4,4 +4,6 @@ pub mod consts;
 pub mod error;
 mod math;
 pub mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod utils;
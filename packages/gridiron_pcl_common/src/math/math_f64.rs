@@ -1,8 +1,14 @@
+//! Float reference model of the PCL invariant. It mirrors the fixed-point implementation in
+//! [`super::math_decimal`] and is only meant to be used as an oracle in tests.
+
 use crate::consts::MAX_ITER;
 
 const N: f64 = 2.0;
 const TOL: f64 = 1e-5;
+/// Defines fee tolerance. If k coefficient is small enough then k = 0.
+const FEE_TOL: f64 = 1e-3;
 
+/// The invariant function. Equals zero for a valid (D, x) combination.
 pub fn f(d: f64, x: &[f64], a: f64, gamma: f64) -> f64 {
     let k0 = (x[0] * x[1] * N * N) / d.powi(2);
     let k = a * gamma.powi(2) * k0 / (gamma + 1.0f64 - k0).powi(2);
@@ -31,6 +37,7 @@ pub fn df_dx(d: f64, x: &[f64], a: f64, gamma: f64, i: usize) -> f64 {
     (k_x * (x[0] + x[1]) + k) * d + x_r - k_x * d.powi(2)
 }
 
+/// Calculates x[j] for the given D using Newton's method.
 pub fn newton_y(xs: &[f64], a: f64, gamma: f64, d: f64, j: usize) -> f64 {
     let mut x = xs.to_vec();
     let x_r = x[1 - j];
@@ -38,8 +45,6 @@ pub fn newton_y(xs: &[f64], a: f64, gamma: f64, d: f64, j: usize) -> f64 {
     let mut xi_1 = x0;
     x[j] = x0;
 
-    println!("Computing x[{j}]. First approximation {x0}");
-
     let mut i = 0;
     let mut diff = 1.0;
     let mut xi = 0.0;
@@ -49,7 +54,6 @@ pub fn newton_y(xs: &[f64], a: f64, gamma: f64, d: f64, j: usize) -> f64 {
         x[j] = xi;
 
         diff = (xi - xi_1).abs();
-        println!("{i}, {xi}, {xi_1}");
         xi_1 = xi;
         i += 1;
     }
@@ -57,9 +61,9 @@ pub fn newton_y(xs: &[f64], a: f64, gamma: f64, d: f64, j: usize) -> f64 {
     xi
 }
 
+/// Calculates D for the given pool volumes using Newton's method.
 pub fn newton_d(x: &[f64], a: f64, gamma: f64) -> f64 {
     let d0 = N * (x[0] * x[1]).sqrt();
-    println!("Computing D. First approximation {d0}");
     let mut di_1 = d0;
     let mut i = 0;
     let mut diff = 1.0;
@@ -68,10 +72,36 @@ pub fn newton_d(x: &[f64], a: f64, gamma: f64) -> f64 {
     while diff > TOL && i < MAX_ITER {
         di = di_1 - f(di_1, x, a, gamma) / df_dd(di_1, x, a, gamma);
         diff = (di - di_1).abs();
-        println!("{i}, {di}, {}", f(di, x, a, gamma));
         di_1 = di;
         i += 1;
     }
 
     di
 }
+
+/// Dynamic fee rate for the given internal pool volumes. Mirrors `PoolParams::fee`.
+pub fn fee(xp: &[f64], mid_fee: f64, out_fee: f64, fee_gamma: f64) -> f64 {
+    let sum = xp[0] + xp[1];
+    let mut k = xp[0] * xp[1] * N * N / sum.powi(2);
+    k = fee_gamma / (fee_gamma + 1.0 - k);
+
+    if k <= FEE_TOL {
+        k = 0.0
+    }
+
+    k * mid_fee + (1.0 - k) * out_fee
+}
+
+/// Fee rate applied on LP tokens minted for an imbalanced provide. Mirrors `calc_provide_fee`.
+pub fn provide_fee(
+    deposits: &[f64],
+    xp: &[f64],
+    mid_fee: f64,
+    out_fee: f64,
+    fee_gamma: f64,
+) -> f64 {
+    let sum = deposits[0] + deposits[1];
+    let avg = sum / N;
+
+    (deposits[0] - avg).abs() * fee(xp, mid_fee, out_fee, fee_gamma) / sum
+}
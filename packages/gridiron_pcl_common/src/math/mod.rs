@@ -5,8 +5,8 @@ use crate::math::math_decimal::{geometric_mean, newton_d, newton_y};
 use crate::state::AmpGamma;
 
 mod math_decimal;
-#[cfg(test)]
-mod math_f64;
+#[cfg(any(test, feature = "testing"))]
+pub(crate) mod math_f64;
 mod signed_decimal;
 
 pub use math_decimal::half_float_pow;
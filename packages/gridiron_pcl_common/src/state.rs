@@ -3,14 +3,14 @@ use std::fmt::Display;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
     attr, Addr, Attribute, CustomQuery, Decimal, Decimal256, DepsMut, Env, Order, StdError,
-    StdResult, Storage,
+    StdResult, Storage, Uint128,
 };
 use cw_storage_plus::Map;
 
 use gridiron::asset::{AssetInfo, PairInfo};
 use gridiron::cosmwasm_ext::{AbsDiff, IntegerToDecimal};
-use gridiron::pair::FeeShareConfig;
-use gridiron::pair_concentrated::{PromoteParams, UpdatePoolParams};
+use gridiron::pair::{CombinedFees, FeeShareConfig, PAIR_PRECISIONS_KEY};
+use gridiron::pair_concentrated::{PromoteParams, StakerFeeShareConfig, UpdatePoolParams};
 
 use crate::consts::{
     AMP_MAX, AMP_MIN, FEE_GAMMA_MAX, FEE_GAMMA_MIN, FEE_TOL, GAMMA_MAX, GAMMA_MIN, MAX_CHANGE,
@@ -37,6 +37,20 @@ pub struct Config {
     pub track_asset_balances: bool,
     /// The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The config for routing swap fees to xGRID stakers
+    pub staker_fee_share: Option<StakerFeeShareConfig>,
+}
+
+impl Config {
+    /// Returns the fees the pair combines on a swap. The maximum (out) fee is taken as the total fee.
+    pub fn combined_fees(&self) -> CombinedFees {
+        CombinedFees {
+            total_fee_bps: (Uint128::new(10_000) * self.pool_params.out_fee).u128() as u16,
+            fee_share_bps: self.fee_share.as_ref().map_or(0, |share| share.bps),
+            staker_fee_share_bps: self.staker_fee_share.as_ref().map_or(0, |share| share.bps),
+            ..Default::default()
+        }
+    }
 }
 
 /// This structure stores the pool parameters which may be adjusted via the `update_pool_params`.
@@ -376,7 +390,7 @@ pub struct Precisions(Vec<(String, u8)>);
 
 impl<'a> Precisions {
     /// Stores map of AssetInfo (as String) -> precision
-    const PRECISIONS: Map<'a, String, u8> = Map::new("precisions");
+    const PRECISIONS: Map<'a, String, u8> = Map::new(PAIR_PRECISIONS_KEY);
     pub fn new(storage: &dyn Storage) -> StdResult<Self> {
         let items = Self::PRECISIONS
             .range(storage, None, None, Order::Ascending)
@@ -0,0 +1,121 @@
+//! Differential testing harness for the PCL math. Enabled with the `testing` feature.
+//!
+//! Exposes the float reference model of D, y and fee functions alongside proptest strategies
+//! which generate inputs within the parameter limits enforced by the contracts.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use proptest::prelude::*;
+
+pub use crate::math::math_f64::{df_dd, df_dx, f, fee, newton_d, newton_y, provide_fee};
+
+/// Converts a float to a decimal type. The value is rounded to 18 decimal places.
+pub fn f64_to_dec<T>(val: f64) -> T
+where
+    T: FromStr,
+    T::Err: Error,
+{
+    T::from_str(&format!("{val:.18}")).unwrap()
+}
+
+/// Converts a decimal type to a float.
+pub fn dec_to_f64(val: impl Display) -> f64 {
+    f64::from_str(&val.to_string()).unwrap()
+}
+
+/// Asserts that a fixed-point value is within the relative tolerance of the reference value.
+pub fn assert_approx(dec: impl Display, reference: f64, tolerance: f64) {
+    let value = dec_to_f64(dec);
+    let diff = (value - reference).abs() / reference.abs().max(1.0);
+    assert!(
+        diff <= tolerance,
+        "{value} differs from the reference value {reference} by {diff}"
+    );
+}
+
+/// Generates Amp values within the range used by deployed pools.
+pub fn amp() -> impl Strategy<Value = f64> {
+    (1u64..=10_000u64).prop_map(|amp| amp as f64)
+}
+
+/// Generates Gamma values within the bounds allowed by `AmpGamma::new`.
+pub fn gamma() -> impl Strategy<Value = f64> {
+    (1u64..=2_000_000u64).prop_map(|gamma| gamma as f64 * 1e-8)
+}
+
+/// Generates internal pool volumes with the price scale applied. The imbalance between the two
+/// sides is limited to 100x so Newton's method converges for both implementations.
+pub fn pools() -> impl Strategy<Value = [f64; 2]> {
+    (1_000u64..=1_000_000_000_000u64, 1u64..=10_000u64)
+        .prop_map(|(x0, ratio)| [x0 as f64, x0 as f64 * ratio as f64 / 100.0])
+}
+
+/// Generates (mid_fee, out_fee, fee_gamma) within the bounds allowed by `PoolParams::update_params`.
+pub fn fee_params() -> impl Strategy<Value = (f64, f64, f64)> {
+    (5u64..=1_000u64, 5u64..=1_000u64, 1u64..=1_000_000u64).prop_map(|(a, b, fee_gamma)| {
+        let (mid_fee, out_fee) = if a <= b { (a, b) } else { (b, a) };
+        (
+            mid_fee as f64 * 1e-5,
+            out_fee as f64 * 1e-5,
+            fee_gamma as f64 * 1e-6,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{Decimal, Decimal256};
+
+    use crate::state::{AmpGamma, PoolParams};
+    use crate::utils::calc_provide_fee;
+    use crate::{calc_d, calc_y};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn d_and_y_match_reference(xs in pools(), amp in amp(), gamma in gamma()) {
+            let amp_gamma = AmpGamma {
+                amp: f64_to_dec(amp),
+                gamma: f64_to_dec(gamma),
+            };
+            let xs_dec: [Decimal256; 2] = [f64_to_dec(xs[0]), f64_to_dec(xs[1])];
+
+            let d_ref = newton_d(&xs, amp, gamma);
+            let d = calc_d(&xs_dec, &amp_gamma).unwrap();
+            assert_approx(d, d_ref, 1e-6);
+
+            let y_ref = newton_y(&[xs[0] * 1.01, 0.0], amp, gamma, d_ref, 1);
+            let offer: Decimal256 = f64_to_dec(xs[0] * 1.01);
+            let y = calc_y(&[offer, Decimal256::zero()], d, &amp_gamma, 1).unwrap();
+            assert_approx(y, y_ref, 1e-6);
+        }
+
+        #[test]
+        fn fees_match_reference(
+            xs in pools(),
+            deposit in 1u64..=1_000_000u64,
+            (mid_fee, out_fee, fee_gamma) in fee_params(),
+        ) {
+            let params = PoolParams {
+                mid_fee: f64_to_dec::<Decimal>(mid_fee),
+                out_fee: f64_to_dec::<Decimal>(out_fee),
+                fee_gamma: f64_to_dec::<Decimal>(fee_gamma),
+                ..PoolParams::default()
+            };
+            let xs_dec: [Decimal256; 2] = [f64_to_dec(xs[0]), f64_to_dec(xs[1])];
+
+            assert_approx(params.fee(&xs_dec), fee(&xs, mid_fee, out_fee, fee_gamma), 1e-9);
+
+            let deposits = [deposit as f64, 1.0];
+            let deposits_dec: [Decimal256; 2] = [f64_to_dec(deposits[0]), Decimal256::one()];
+            assert_approx(
+                calc_provide_fee(&deposits_dec, &xs_dec, &params),
+                provide_fee(&deposits, &xs, mid_fee, out_fee, fee_gamma),
+                1e-9,
+            );
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use cosmwasm_std::{
     to_binary, wasm_execute, Addr, Api, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256,
-    Env, Fraction, QuerierWrapper, StdError, StdResult, Uint128,
+    Env, Fraction, QuerierWrapper, StdError, StdResult, Uint128, Uint256,
 };
 use cw20::Cw20ExecuteMsg;
 use itertools::Itertools;
 
-use gridiron::asset::{Asset, AssetInfo, DecimalAsset};
+use gridiron::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset};
 use gridiron::cosmwasm_ext::AbsDiff;
 use gridiron::querier::query_factory_config;
 use gridiron_factory::state::pair_key;
@@ -15,7 +15,7 @@ use crate::error::PclError;
 use crate::state::{Config, PoolParams, PriceState};
 use crate::{calc_d, calc_y};
 
-/// Helper function to check the given asset infos are valid.
+/// Helper function to check the given asset infos of a new pool are valid.
 pub fn check_asset_infos(api: &dyn Api, asset_infos: &[AssetInfo]) -> Result<(), PclError> {
     if !asset_infos.iter().all_unique() {
         return Err(PclError::DoublingAssets {});
@@ -23,14 +23,21 @@ pub fn check_asset_infos(api: &dyn Api, asset_infos: &[AssetInfo]) -> Result<(),
 
     asset_infos
         .iter()
-        .try_for_each(|asset_info| asset_info.check(api))
+        .try_for_each(|asset_info| asset_info.check_strict(api))
         .map_err(Into::into)
 }
 
 /// Helper function to check that the assets in a given array are valid.
 pub fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), PclError> {
-    let asset_infos = assets.iter().map(|asset| asset.info.clone()).collect_vec();
-    check_asset_infos(api, &asset_infos)
+    if !assets.iter().map(|asset| &asset.info).all_unique() {
+        return Err(PclError::DoublingAssets {});
+    }
+
+    // Pools created before strict validation must keep accepting liquidity
+    assets
+        .iter()
+        .try_for_each(|asset| asset.info.check(api))
+        .map_err(Into::into)
 }
 
 /// Checks that cw20 token is part of the pool.
@@ -143,8 +150,10 @@ pub fn get_share_in_assets(
 
 /// If `belief_price` and `max_spread` are both specified, we compute a new spread,
 /// otherwise we just use the swap spread to check `max_spread`.
+/// The swap result is not rounded to integer amounts, so the check stays precise for tiny trades
+/// in high precision pools and for very large trades.
 ///
-/// * **belief_price** belief price used in the swap.
+/// * **belief_price** belief price used in the swap. It is the ratio of raw token amounts.
 ///
 /// * **max_spread** max spread allowed so that the swap can be executed successfuly.
 ///
@@ -153,12 +162,15 @@ pub fn get_share_in_assets(
 /// * **return_amount** amount of assets  a user wants to receive from the swap.
 ///
 /// * **spread_amount** spread used in the swap.
+///
+/// * **ask_precision** precision of the ask asset.
 pub fn assert_max_spread(
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     offer_amount: Uint128,
-    return_amount: Uint128,
-    spread_amount: Uint128,
+    return_amount: Decimal256,
+    spread_amount: Decimal256,
+    ask_precision: u8,
 ) -> Result<(), PclError> {
     let max_spread = max_spread.map(Decimal256::from).unwrap_or(DEFAULT_SLIPPAGE);
     if max_spread > MAX_ALLOWED_SLIPPAGE {
@@ -166,20 +178,39 @@ pub fn assert_max_spread(
     }
 
     if let Some(belief_price) = belief_price {
-        let expected_return = offer_amount
-            * belief_price.inv().ok_or_else(|| {
-                StdError::generic_err("Invalid belief_price. Check the input values.")
-            })?;
-
-        let spread_amount = expected_return.saturating_sub(return_amount);
+        // Belief price is set in raw token amounts
+        let ask_units = Decimal256::from_integer(Uint256::from(10u8).pow(ask_precision.into()));
+        let expected_return = Decimal256::from_integer(offer_amount)
+            .checked_div(belief_price.into())
+            .map_err(|_| StdError::generic_err("Invalid belief_price. Check the input values."))?;
+        let return_amount = return_amount * ask_units;
 
         if return_amount < expected_return
-            && Decimal256::from_ratio(spread_amount, expected_return) > max_spread
+            && (expected_return - return_amount) / expected_return > max_spread
         {
             return Err(PclError::MaxSpreadAssertion {});
         }
-    } else if Decimal256::from_ratio(spread_amount, return_amount + spread_amount) > max_spread {
-        return Err(PclError::MaxSpreadAssertion {});
+    } else {
+        let total_amount = return_amount + spread_amount;
+        if !total_amount.is_zero() && spread_amount / total_amount > max_spread {
+            return Err(PclError::MaxSpreadAssertion {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Ensures the amount received from a swap is not less than `min_receive`.
+///
+/// * **min_receive** minimum amount of ask tokens the trader accepts.
+///
+/// * **return_amount** amount of ask tokens the trader receives from the swap.
+pub fn assert_min_receive(min_receive: Uint128, return_amount: Uint128) -> Result<(), PclError> {
+    if return_amount < min_receive {
+        return Err(PclError::MinReceiveAssertion {
+            min_receive,
+            return_amount,
+        });
     }
 
     Ok(())
@@ -440,4 +471,72 @@ mod tests {
         );
         assert_eq!(dec_to_f64(fee_rate), 0.002205);
     }
+
+    #[test]
+    fn test_max_spread_extreme_magnitudes() {
+        // 1 wei trade in an 18 decimals pool which returns less than 1 wei
+        assert_max_spread(
+            None,
+            Some(f64_to_dec(0.02)),
+            Uint128::one(),
+            Decimal256::raw(0),
+            Decimal256::raw(0),
+            18,
+        )
+        .unwrap();
+        let err = assert_max_spread(
+            None,
+            Some(f64_to_dec(0.02)),
+            Uint128::new(3),
+            Decimal256::raw(2),
+            Decimal256::raw(1),
+            18,
+        )
+        .unwrap_err();
+        assert_eq!(err, PclError::MaxSpreadAssertion {});
+
+        // The fraction of the last raw unit is taken into account
+        assert_max_spread(
+            Some(Decimal::one()),
+            Some(f64_to_dec(0.015)),
+            Uint128::new(100),
+            f64_to_dec(0.0000985),
+            Decimal256::zero(),
+            6,
+        )
+        .unwrap();
+        let err = assert_max_spread(
+            Some(Decimal::one()),
+            Some(f64_to_dec(0.015)),
+            Uint128::new(100),
+            f64_to_dec(0.0000984),
+            Decimal256::zero(),
+            6,
+        )
+        .unwrap_err();
+        assert_eq!(err, PclError::MaxSpreadAssertion {});
+
+        // Huge trade with a belief price which can't be inverted precisely
+        let offer_amount = Uint128::new(300_000_000_000_000_000_000_000_000_000_000_000);
+        let return_amount = Decimal256::from_ratio(100_000_000_000_000_000u128, 1u8);
+        assert_max_spread(
+            Some(Decimal::from_ratio(3u8, 1u8)),
+            Some(Decimal::zero()),
+            offer_amount,
+            return_amount,
+            Decimal256::zero(),
+            18,
+        )
+        .unwrap();
+        let err = assert_max_spread(
+            Some(Decimal::from_ratio(3u8, 1u8)),
+            Some(Decimal::zero()),
+            offer_amount,
+            return_amount - Decimal256::raw(1),
+            Decimal256::zero(),
+            18,
+        )
+        .unwrap_err();
+        assert_eq!(err, PclError::MaxSpreadAssertion {});
+    }
 }
@@ -1,10 +1,10 @@
 use crate::error::ContractError;
 use crate::state::CONFIG;
-use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
+use gridiron::asset::{addr_opt_validate, Asset, AssetInfo, PairCreatedAt, PairInfo};
 use gridiron::factory::PairType;
 use gridiron::pair::{
     ConfigResponse, CumulativePricesResponse, Cw20HookMsg, InstantiateMsg, PoolResponse,
-    ReverseSimulationResponse, SimulationResponse,
+    ReverseSimulationResponse, SimulationResponse, INTERFACE_DEADLINE, INTERFACE_VERSIONED_QUERIES,
 };
 use gridiron::pair_bonded::{Config, ExecuteMsg, QueryMsg};
 use gridiron::querier::query_factory_config;
@@ -15,6 +15,8 @@ use cosmwasm_std::{
 use cw2::set_contract_version;
 use cw20::Cw20ReceiveMsg;
 
+gridiron::supported_interfaces!(INTERFACE_DEADLINE, INTERFACE_VERSIONED_QUERIES);
+
 pub trait PairBonded<'a> {
     /// Contract name that is used for migration.
     const CONTRACT_NAME: &'a str;
@@ -26,7 +28,7 @@ pub trait PairBonded<'a> {
         &self,
         deps: DepsMut,
         env: Env,
-        _info: MessageInfo,
+        info: MessageInfo,
         msg: InstantiateMsg,
     ) -> Result<Response, ContractError> {
         msg.asset_infos[0].check(deps.api)?;
@@ -38,12 +40,17 @@ pub trait PairBonded<'a> {
 
         set_contract_version(deps.storage, Self::CONTRACT_NAME, Self::CONTRACT_VERSION)?;
 
+        let creator = addr_opt_validate(deps.api, &msg.creator)?.unwrap_or(info.sender);
+
         let config = Config {
             pair_info: PairInfo {
                 contract_addr: env.contract.address,
                 liquidity_token: Addr::unchecked(""),
                 asset_infos: msg.asset_infos.clone(),
                 pair_type: PairType::Custom(String::from("Bonded")),
+                creator: Some(creator),
+                created_at: Some(PairCreatedAt::from(&env.block)),
+                allowlist: None,
             },
             factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
         };
@@ -66,13 +73,16 @@ pub trait PairBonded<'a> {
     ///             slippage_tolerance,
     ///             auto_stake,
     ///             receiver,
+    ///             deadline,
     ///         }**  Not supported.
     ///
     /// * **ExecuteMsg::Swap {
     ///             offer_asset,
     ///             belief_price,
     ///             max_spread,
+    ///             min_receive,
     ///             to,
+    ///             deadline,
     ///         }** Performs an swap using the specified parameters. (It needs to be implemented)
     ///
     /// * **ExecuteMsg::AssertAndSend {
@@ -82,6 +92,7 @@ pub trait PairBonded<'a> {
     ///             ask_asset_info,
     ///             receiver,
     ///             sender,
+    ///             min_receive,
     ///         }** (internal) Is used as a sub-execution to send received tokens to the receiver and check the spread/price.
     fn execute(
         &self,
@@ -98,13 +109,29 @@ pub trait PairBonded<'a> {
                 offer_asset,
                 belief_price,
                 max_spread,
+                min_receive,
                 to,
-            } => self.execute_swap(deps, env, info, offer_asset, belief_price, max_spread, to),
+                deadline,
+            } => {
+                self.assert_deadline(&env, deadline)?;
+
+                self.execute_swap(
+                    deps,
+                    env,
+                    info,
+                    offer_asset,
+                    belief_price,
+                    max_spread,
+                    min_receive,
+                    to,
+                )
+            }
             ExecuteMsg::AssertAndSend {
                 offer_asset,
                 ask_asset_info,
                 receiver,
                 sender,
+                min_receive,
             } => self.assert_receive_and_send(
                 deps,
                 env,
@@ -113,6 +140,7 @@ pub trait PairBonded<'a> {
                 offer_asset,
                 ask_asset_info,
                 receiver,
+                min_receive,
             ),
         }
     }
@@ -137,6 +165,13 @@ pub trait PairBonded<'a> {
     /// pool using a [`CumulativePricesResponse`] object.
     ///
     /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+    ///
+    /// * **QueryMsg::VersionedPair {}**, **QueryMsg::VersionedPool {}**,
+    /// **QueryMsg::VersionedConfig {}** and **QueryMsg::VersionedSimulation { offer_asset }** Answer
+    /// the `pair`, `pool`, `config` and `simulation` queries with the response wrapped into a
+    /// [`gridiron::versioned::Versioned`] envelope.
+    ///
+    /// * **QueryMsg::SupportsInterface { interface }** Returns whether the pair implements the interface.
     fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::Pair {} => to_binary(&self.query_pair_info(deps)?),
@@ -150,6 +185,13 @@ pub trait PairBonded<'a> {
             }
             QueryMsg::CumulativePrices {} => to_binary(&self.query_cumulative_prices(deps, env)?),
             QueryMsg::Config {} => to_binary(&self.query_config(deps)?),
+            msg @ (QueryMsg::VersionedPair {}
+            | QueryMsg::VersionedPool {}
+            | QueryMsg::VersionedConfig {}
+            | QueryMsg::VersionedSimulation { .. }) => {
+                msg.query_versioned(|msg| self.query(deps, env, msg))
+            }
+            QueryMsg::SupportsInterface { interface } => to_binary(&supports_interface(&interface)),
         }
     }
 
@@ -167,9 +209,13 @@ pub trait PairBonded<'a> {
             Cw20HookMsg::Swap {
                 belief_price,
                 max_spread,
+                min_receive,
                 to,
+                deadline,
                 ..
             } => {
+                self.assert_deadline(&env, deadline)?;
+
                 // Only asset contract can execute this message
                 let mut authorized = false;
                 let config = CONFIG.load(deps.storage)?;
@@ -200,9 +246,11 @@ pub trait PairBonded<'a> {
                     },
                     belief_price,
                     max_spread,
+                    min_receive,
                     to_addr,
                 )
             }
+            Cw20HookMsg::BatchSwap { .. } => Err(ContractError::NotSupported {}),
             Cw20HookMsg::WithdrawLiquidity { .. } => Err(ContractError::NotSupported {}),
         }
     }
@@ -218,6 +266,8 @@ pub trait PairBonded<'a> {
     ///
     /// * **max_spread** sets the maximum spread of the swap operation.
     ///
+    /// * **min_receive** sets the minimum amount of ask tokens to receive.
+    ///
     /// * **to** sets the recipient of the swap operation.
     ///
     /// NOTE - the address that wants to swap should approve the pair contract to pull the offer token.
@@ -230,6 +280,7 @@ pub trait PairBonded<'a> {
         offer_asset: Asset,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        min_receive: Option<Uint128>,
         to: Option<String>,
     ) -> Result<Response, ContractError> {
         offer_asset.info.check(deps.api)?;
@@ -247,6 +298,7 @@ pub trait PairBonded<'a> {
             offer_asset,
             belief_price,
             max_spread,
+            min_receive,
             to_addr,
         )
     }
@@ -263,6 +315,7 @@ pub trait PairBonded<'a> {
         offer_asset: Asset,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        min_receive: Option<Uint128>,
         to: Option<Addr>,
     ) -> Result<Response, ContractError>;
 
@@ -331,6 +384,10 @@ pub trait PairBonded<'a> {
             params: None,
             owner: factory_config.owner,
             factory_addr: config.factory_addr,
+            creator: config.pair_info.creator,
+            created_at: config.pair_info.created_at,
+            min_trade_sizes: vec![],
+            maintenance_window: None,
         })
     }
 
@@ -364,6 +421,8 @@ pub trait PairBonded<'a> {
     /// * **belief_price** used to calculate the maximum swap spread.
     ///
     /// * **max_spread** sets the maximum spread of the swap operation.
+    ///
+    /// * **min_receive** minimum amount of ask tokens the receiver must get.
     #[allow(clippy::too_many_arguments)]
     fn assert_receive_and_send(
         &self,
@@ -374,6 +433,7 @@ pub trait PairBonded<'a> {
         offer_asset: Asset,
         ask_asset_info: AssetInfo,
         receiver: Addr,
+        min_receive: Option<Uint128>,
     ) -> Result<Response, ContractError> {
         if env.contract.address != info.sender {
             // Only allowed to be sent by the contract itself
@@ -383,6 +443,15 @@ pub trait PairBonded<'a> {
         let offer_amount = offer_asset.amount;
         let return_amount = ask_asset_info.query_pool(&deps.querier, env.contract.address)?;
 
+        if let Some(min_receive) = min_receive {
+            if return_amount < min_receive {
+                return Err(ContractError::MinReceiveAssertion {
+                    min_receive,
+                    return_amount,
+                });
+            }
+        }
+
         let return_asset = Asset {
             info: ask_asset_info.clone(),
             amount: return_amount,
@@ -401,4 +470,16 @@ pub trait PairBonded<'a> {
             .add_attribute("commission_amount", "0")
             .add_attribute("maker_fee_amount", "0"))
     }
+
+    /// Ensures the transaction is not included after `deadline`.
+    ///
+    /// * **deadline** timestamp (in seconds) after which the transaction is rejected.
+    fn assert_deadline(&self, env: &Env, deadline: Option<u64>) -> Result<(), ContractError> {
+        match deadline {
+            Some(deadline) if env.block.time.seconds() > deadline => {
+                Err(ContractError::TransactionExpired {})
+            }
+            _ => Ok(()),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// This enum describes stableswap pair contract errors
@@ -22,6 +22,15 @@ pub enum ContractError {
     #[error("Operation exceeds max spread limit")]
     MaxSpreadAssertion {},
 
+    #[error("Swap return {return_amount} is less than min_receive {min_receive}")]
+    MinReceiveAssertion {
+        min_receive: Uint128,
+        return_amount: Uint128,
+    },
+
+    #[error("Transaction deadline has passed")]
+    TransactionExpired {},
+
     #[error("Native token balance mismatch between the argument and the transferred")]
     AssetMismatch {},
 
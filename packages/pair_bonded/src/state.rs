@@ -1,5 +1,6 @@
+use gridiron::pair::PAIR_CONFIG_KEY;
 use gridiron::pair_bonded::Config;
 use cw_storage_plus::Item;
 
 /// Stores the config struct at the given key
-pub const CONFIG: Item<Config> = Item::new("config");
+pub const CONFIG: Item<Config> = Item::new(PAIR_CONFIG_KEY);